@@ -0,0 +1,26 @@
+// Cost of one ivc step: folding the step's instance into the running one, for mimc chains of growing length
+use ark_algorithms::folding::{ivc::IVC, step_circuits::MiMCChainCircuit};
+use ark_bn254::{Fr, G1Projective};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn bench_ivc_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ivc_step");
+    group.sample_size(10);
+    // ~330 constraints per hash over bn254
+    for n_hashes in [1, 4, 16] {
+        let circuit = MiMCChainCircuit::new(n_hashes);
+        let mut ivc = IVC::<G1Projective, _>::new(circuit, vec![Fr::from(3u64)]).unwrap();
+        // the first two steps have no running instance to fold into
+        let mut z = vec![Fr::from(3u64)];
+        for _ in 0..2 {
+            z = ivc.prove_step(&z).unwrap();
+        }
+        group.bench_function(BenchmarkId::new("prove_step", n_hashes), |b| {
+            b.iter(|| z = ivc.prove_step(&z).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ivc_step);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+// KZG commit, open and verify over bn254, at degrees 2^8 to 2^12
+use ark_algorithms::cs::pcs::kzg::KZG;
+use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+use ark_ec::Group;
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn bench_kzg(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("kzg");
+    for log_degree in [8, 10, 12] {
+        let degree = (1 << log_degree) - 1;
+        let mut kzg =
+            KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+        kzg.setup_from_rng(&mut rng);
+        let polynomial = DensePolynomial::<Fr>::rand(degree, &mut rng);
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("commit", degree), &polynomial, |b, p| {
+            b.iter(|| kzg.commit(p))
+        });
+        group.bench_with_input(BenchmarkId::new("open", degree), &polynomial, |b, p| {
+            b.iter(|| kzg.open(p, z, y))
+        });
+        group.bench_function(BenchmarkId::new("verify", degree), |b| {
+            b.iter(|| kzg.verify(y, z, commitment, pi))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_kzg);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+// Matrix-vector products, dense against sparse, for r1cs-like matrices with a few entries per row
+use ark_algorithms::utils::linear_algebra::{Matrix, SparseMatrix, Vector};
+use ark_bn254::Fr;
+use ark_ff::UniformRand;
+use ark_std::{rand::Rng, test_rng};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// 3 random entries per row
+fn sparse_matrix<R: Rng>(size: usize, rng: &mut R) -> SparseMatrix<Fr> {
+    let entries = (0..size)
+        .flat_map(|row| (0..3).map(move |_| row))
+        .map(|row| (row, rng.gen_range(0..size), Fr::rand(rng)))
+        .collect();
+    SparseMatrix::new(size, size, entries)
+}
+
+fn bench_dot_vector(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("dot_vector");
+    for size in [1 << 6, 1 << 8, 1 << 10] {
+        let sparse = sparse_matrix(size, &mut rng);
+        let dense: Matrix<Fr> = sparse.to_dense();
+        let v = Vector::new(&(0..size).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>());
+
+        group.bench_function(BenchmarkId::new("dense", size), |b| {
+            b.iter(|| dense.dot_vector(&v))
+        });
+        group.bench_function(BenchmarkId::new("sparse", size), |b| {
+            b.iter(|| sparse.dot_vector(&v))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dot_vector);
+criterion_main!(benches);
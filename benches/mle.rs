@@ -0,0 +1,35 @@
+// The mle evaluation methods: naive (sum of chi_w), memoized (with an eq table) and by fixing variables
+use ark_algorithms::ip::sumcheck::{
+    eq_table, evaluate_mle, get_hypercube_points, memoized_mle_evaluation, naive_mle_evaluation,
+    sample_random_vector,
+};
+use ark_bn254::Fr;
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn bench_mle_evaluation(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("mle_evaluation");
+    for num_vars in [8, 12, 16] {
+        let evals = sample_random_vector::<Fr, _>(1 << num_vars, &mut rng);
+        let point = sample_random_vector::<Fr, _>(num_vars, &mut rng);
+
+        // the naive method is quadratic in the hypercube's size, it's only run on the smaller ones
+        if num_vars <= 12 {
+            let hypercube = get_hypercube_points::<Fr>(num_vars);
+            group.bench_function(BenchmarkId::new("naive", num_vars), |b| {
+                b.iter(|| naive_mle_evaluation(&evals, &hypercube, point.clone()))
+            });
+        }
+        group.bench_function(BenchmarkId::new("memoized", num_vars), |b| {
+            b.iter(|| memoized_mle_evaluation(&evals, &eq_table(&point)))
+        });
+        group.bench_function(BenchmarkId::new("fix_variables", num_vars), |b| {
+            b.iter(|| evaluate_mle(&evals, &point))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_mle_evaluation);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+// Sumcheck proving and verification for a product of 3 mles, each round binding one variable
+use ark_algorithms::ip::sumcheck::{
+    protocol::{prove, verify, VirtualPolynomial},
+    sample_random_vector, Mle,
+};
+use ark_algorithms::transcript::Transcript;
+use ark_bn254::Fr;
+use ark_ff::One;
+use ark_std::test_rng;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn bench_sumcheck(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("sumcheck");
+    for num_vars in [10, 14, 16] {
+        // g = f_0 * f_1 * f_2 + f_0
+        let mut g = VirtualPolynomial::new(num_vars);
+        for _ in 0..3 {
+            g.add_mle(Mle::new(sample_random_vector::<Fr, _>(
+                1 << num_vars,
+                &mut rng,
+            )));
+        }
+        g.add_product(Fr::one(), &[0, 1, 2]);
+        g.add_product(Fr::one(), &[0]);
+        let sum = g.sum_over_hypercube();
+        let (proof, _) = prove(&g, &mut Transcript::new(b"bench"));
+
+        group.bench_function(BenchmarkId::new("prove", num_vars), |b| {
+            b.iter(|| prove(&g, &mut Transcript::new(b"bench")))
+        });
+        group.bench_function(BenchmarkId::new("verify", num_vars), |b| {
+            b.iter(|| verify(num_vars, 3, sum, &proof, &mut Transcript::new(b"bench")))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sumcheck);
+criterion_main!(benches);
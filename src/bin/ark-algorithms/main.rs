@@ -0,0 +1,407 @@
+// Command line interface to the crate's workflows, over bn254, e.g. for tutorials and shell scripts.
+// Artifacts (srs, openings, proofs, folding instances) are files in the versioned binary format of
+// `utils::serialization`. Polynomials, evaluations and scalars are decimal field elements, whitespace separated.
+// Circuits are circom .r1cs files, with their snarkjs .wtns witnesses.
+// Randomness comes from the os: `kzg setup` samples tau and forgets it, it isn't a trusted setup ceremony.
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+use std::str::FromStr;
+
+use ark_algorithms::circuits::r1cs::circom::{read_r1cs, read_r1cs_with_header, read_wtns};
+use ark_algorithms::cs::pcs::kzg::{KZGOpening, KZG};
+use ark_algorithms::folding::nifs::{CommittedRelaxedInstance, RelaxedWitness, NIFS};
+use ark_algorithms::ip::sumcheck::{
+    protocol::{prove, verify, SumcheckProof, VirtualPolynomial},
+    Mle,
+};
+use ark_algorithms::transcript::Transcript;
+use ark_algorithms::utils::{linear_algebra::Matrix, serialization::Artifact};
+use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+use ark_ec::Group;
+use ark_ff::{One, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::rngs::OsRng;
+use serde::Serialize;
+
+const USAGE: &str = "usage:
+  ark-algorithms kzg setup <degree> <srs>
+  ark-algorithms kzg commit <srs> <polynomial>
+  ark-algorithms kzg open <srs> <polynomial> <z> <opening>
+  ark-algorithms kzg verify <srs> <opening>
+  ark-algorithms r1cs export <circuit.r1cs> <circuit.json>
+  ark-algorithms fold step <circuit.r1cs> <witness.wtns> <instance> <witness>
+  ark-algorithms sumcheck prove <proof> <evaluations>...
+  ark-algorithms sumcheck verify <proof> <sum> <evaluations>...";
+
+/// Label of the sumcheck transcripts, shared by `sumcheck prove` and `sumcheck verify`
+const SUMCHECK_LABEL: &[u8] = b"ark-algorithms sumcheck";
+
+fn read_artifact<T: Artifact>(path: &str) -> Result<T, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    T::from_bytes(&bytes).map_err(|e| format!("{path}: {e}"))
+}
+
+fn write_artifact<T: Artifact>(path: &str, artifact: &T) -> Result<(), String> {
+    fs::write(path, artifact.to_bytes()).map_err(|e| format!("{path}: {e}"))
+}
+
+fn parse_field(value: &str) -> Result<Fr, String> {
+    Fr::from_str(value).map_err(|_| format!("invalid field element: {value}"))
+}
+
+fn parse_usize(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid integer: {value}"))
+}
+
+/// Whitespace separated decimal field elements
+fn read_field_elements(path: &str) -> Result<Vec<Fr>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    text.split_whitespace().map(parse_field).collect()
+}
+
+fn to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = vec![];
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn kzg(args: &[String]) -> Result<String, String> {
+    match args {
+        [command, degree, srs] if command == "setup" => {
+            let degree = parse_usize(degree)?;
+            let mut kzg =
+                KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+            kzg.setup_from_rng(&mut OsRng);
+            write_artifact(srs, &kzg)?;
+            Ok(format!("srs of degree {degree} written to {srs}"))
+        }
+        [command, srs, polynomial] if command == "commit" => {
+            let kzg: KZG<Bn254> = read_artifact(srs)?;
+            let polynomial =
+                DensePolynomial::from_coefficients_vec(read_field_elements(polynomial)?);
+            let commitment = kzg.commit(&polynomial).map_err(|e| e.to_string())?;
+            Ok(to_hex(&commitment))
+        }
+        [command, srs, polynomial, z, opening] if command == "open" => {
+            let kzg: KZG<Bn254> = read_artifact(srs)?;
+            let polynomial =
+                DensePolynomial::from_coefficients_vec(read_field_elements(polynomial)?);
+            let commitment = kzg.commit(&polynomial).map_err(|e| e.to_string())?;
+            let z = parse_field(z)?;
+            let y = polynomial.evaluate(&z);
+            let pi = kzg.open(&polynomial, z, y).map_err(|e| e.to_string())?;
+            write_artifact(
+                opening,
+                &KZGOpening::<Bn254> {
+                    commitment,
+                    z,
+                    y,
+                    pi,
+                },
+            )?;
+            Ok(format!("p({z}) = {y}, opening written to {opening}"))
+        }
+        [command, srs, opening] if command == "verify" => {
+            let kzg: KZG<Bn254> = read_artifact(srs)?;
+            let opening: KZGOpening<Bn254> = read_artifact(opening)?;
+            if !kzg.verify_opening(&opening) {
+                return Err("invalid opening".to_string());
+            }
+            Ok(format!("valid opening: p({}) = {}", opening.z, opening.y))
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// `snarkjs r1cs export json`'s layout: the header's counts, then each constraint as its A, B and C rows, maps
+/// from wire to decimal coefficient
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct R1CSJson {
+    n8: usize,
+    prime: String,
+    n_vars: usize,
+    n_outputs: usize,
+    n_pub_inputs: usize,
+    n_prv_inputs: usize,
+    n_labels: u64,
+    n_constraints: usize,
+    constraints: Vec<[BTreeMap<usize, String>; 3]>,
+}
+
+fn r1cs(args: &[String]) -> Result<String, String> {
+    let [command, r1cs_path, json_path] = args else {
+        return Err(USAGE.to_string());
+    };
+    if command != "export" {
+        return Err(USAGE.to_string());
+    }
+    let file = File::open(r1cs_path).map_err(|e| format!("{r1cs_path}: {e}"))?;
+    let (header, r1cs) =
+        read_r1cs_with_header::<Fr, _>(file).map_err(|e| format!("{r1cs_path}: {e}"))?;
+    let rows = |matrix: &Matrix<Fr>| {
+        matrix
+            .to_ark_matrix()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(value, wire)| (wire, value.to_string()))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let (a, b, c) = (rows(r1cs.a()), rows(r1cs.b()), rows(r1cs.c()));
+    let constraints = a
+        .into_iter()
+        .zip(b)
+        .zip(c)
+        .map(|((a, b), c)| [a, b, c])
+        .collect();
+    let json = R1CSJson {
+        n8: header.n8,
+        prime: Fr::MODULUS.to_string(),
+        n_vars: header.n_wires,
+        n_outputs: header.n_public_outputs,
+        n_pub_inputs: header.n_public_inputs,
+        n_prv_inputs: header.n_private_inputs,
+        n_labels: header.n_labels,
+        n_constraints: header.n_constraints,
+        constraints,
+    };
+    let json = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+    fs::write(json_path, json).map_err(|e| format!("{json_path}: {e}"))?;
+    Ok(format!(
+        "{} constraints written to {json_path}",
+        header.n_constraints
+    ))
+}
+
+/// Folds the circuit's witness into the running instance, which the first step creates
+fn fold(args: &[String]) -> Result<String, String> {
+    let [command, r1cs_path, wtns_path, instance_path, witness_path] = args else {
+        return Err(USAGE.to_string());
+    };
+    if command != "step" {
+        return Err(USAGE.to_string());
+    }
+    let file = File::open(r1cs_path).map_err(|e| format!("{r1cs_path}: {e}"))?;
+    let r1cs = read_r1cs::<Fr, _>(file).map_err(|e| format!("{r1cs_path}: {e}"))?;
+    let file = File::open(wtns_path).map_err(|e| format!("{wtns_path}: {e}"))?;
+    let z = read_wtns::<Fr, _>(file).map_err(|e| format!("{wtns_path}: {e}"))?;
+    if !r1cs.is_satisfied(&z) {
+        return Err(format!("{wtns_path} doesn't satisfy {r1cs_path}"));
+    }
+    let nifs = NIFS::<G1Projective>::new(&r1cs);
+    let (step_instance, step_witness) = nifs.commit_instance(&r1cs, &z, &mut OsRng);
+    let (instance, witness) = if Path::new(instance_path).exists() {
+        let running_instance: CommittedRelaxedInstance<G1Projective> =
+            read_artifact(instance_path)?;
+        let running_witness: RelaxedWitness<Fr> = read_artifact(witness_path)?;
+        let (instance, witness, _) = nifs
+            .prove(
+                &r1cs,
+                &running_instance,
+                &running_witness,
+                &step_instance,
+                &step_witness,
+                &mut Transcript::new(b"ark-algorithms fold"),
+                &mut OsRng,
+            )
+            .map_err(|e| e.to_string())?;
+        (instance, witness)
+    } else {
+        (step_instance, step_witness)
+    };
+    if !nifs.is_satisfied(&r1cs, &instance, &witness) {
+        return Err("the folded instance isn't satisfied".to_string());
+    }
+    write_artifact(instance_path, &instance)?;
+    write_artifact(witness_path, &witness)?;
+    Ok(format!(
+        "folded instance written to {instance_path}, u = {}",
+        instance.u
+    ))
+}
+
+/// Sum over the hypercube of the product of the mles given by their evaluations
+fn sumcheck(args: &[String]) -> Result<String, String> {
+    let product = |paths: &[String]| -> Result<VirtualPolynomial<Fr>, String> {
+        if paths.is_empty() {
+            return Err(USAGE.to_string());
+        }
+        let mles = paths
+            .iter()
+            .map(|path| read_field_elements(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let size = mles[0].len();
+        if !size.is_power_of_two() || mles.iter().any(|mle| mle.len() != size) {
+            return Err("evaluations should have the same power of two length".to_string());
+        }
+        let mut g = VirtualPolynomial::new(size.trailing_zeros() as usize);
+        let indices = mles
+            .into_iter()
+            .map(|evaluations| g.add_mle(Mle::new(evaluations)))
+            .collect::<Vec<_>>();
+        g.add_product(Fr::one(), &indices);
+        Ok(g)
+    };
+    match args {
+        [command, proof_path, evaluations @ ..] if command == "prove" => {
+            let g = product(evaluations)?;
+            let (proof, _) = prove(&g, &mut Transcript::new(SUMCHECK_LABEL));
+            write_artifact(proof_path, &proof)?;
+            Ok(format!(
+                "sum = {}, proof written to {proof_path}",
+                g.sum_over_hypercube()
+            ))
+        }
+        [command, proof_path, sum, evaluations @ ..] if command == "verify" => {
+            let g = product(evaluations)?;
+            let proof: SumcheckProof<Fr> = read_artifact(proof_path)?;
+            let subclaim = verify(
+                g.num_vars(),
+                g.degree(),
+                parse_field(sum)?,
+                &proof,
+                &mut Transcript::new(SUMCHECK_LABEL),
+            )
+            .ok_or("inconsistent sumcheck rounds")?;
+            if g.evaluate(&subclaim.point) != subclaim.expected_evaluation {
+                return Err("the final evaluation doesn't match".to_string());
+            }
+            Ok("valid sumcheck proof".to_string())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    match args {
+        [group, rest @ ..] if group == "kzg" => kzg(rest),
+        [group, rest @ ..] if group == "r1cs" => r1cs(rest),
+        [group, rest @ ..] if group == "fold" => fold(rest),
+        [group, rest @ ..] if group == "sumcheck" => sumcheck(rest),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(output) => println!("{output}"),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    pub fn test_cli() {
+        let dir = std::env::temp_dir().join(format!("ark-algorithms-cli-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        // p(X) = 1 + 2X + 3X^2, p(2) = 17
+        fs::write(path("p.txt"), "1 2 3").unwrap();
+        run(&args(&["kzg", "setup", "4", &path("srs.bin")])).unwrap();
+        assert_eq!(
+            run(&args(&["kzg", "commit", &path("srs.bin"), &path("p.txt")]))
+                .unwrap()
+                .len(),
+            64
+        );
+        let output = run(&args(&[
+            "kzg",
+            "open",
+            &path("srs.bin"),
+            &path("p.txt"),
+            "2",
+            &path("opening.bin"),
+        ]))
+        .unwrap();
+        assert!(output.starts_with("p(2) = 17"));
+        assert!(run(&args(&[
+            "kzg",
+            "verify",
+            &path("srs.bin"),
+            &path("opening.bin")
+        ]))
+        .is_ok());
+        // an opening is not an srs
+        assert!(run(&args(&[
+            "kzg",
+            "verify",
+            &path("opening.bin"),
+            &path("opening.bin")
+        ]))
+        .is_err());
+
+        // sum of f * g over the 2-dimensional hypercube: 1 * 5 + 2 * 6 + 3 * 7 + 4 * 8 = 70
+        fs::write(path("f.txt"), "1 2 3 4").unwrap();
+        fs::write(path("g.txt"), "5 6 7 8").unwrap();
+        let output = run(&args(&[
+            "sumcheck",
+            "prove",
+            &path("proof.bin"),
+            &path("f.txt"),
+            &path("g.txt"),
+        ]))
+        .unwrap();
+        assert!(output.starts_with("sum = 70"));
+        let verify = |sum: &str| {
+            run(&args(&[
+                "sumcheck",
+                "verify",
+                &path("proof.bin"),
+                sum,
+                &path("f.txt"),
+                &path("g.txt"),
+            ]))
+        };
+        assert!(verify("70").is_ok());
+        assert!(verify("71").is_err());
+
+        // circom's c <== a * b, wires (1, c, a, b), in snarkjs' export layout: mycircuit.r1cs comes from
+        // ark-circom's test vectors (MIT/Apache-2.0)
+        let r1cs = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/bin/ark-algorithms/mycircuit.r1cs"
+        );
+        run(&args(&["r1cs", "export", r1cs, &path("mycircuit.json")])).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path("mycircuit.json")).unwrap()).unwrap();
+        let minus_one =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "n8": 32,
+                "prime": "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+                "nVars": 4,
+                "nOutputs": 1,
+                "nPubInputs": 0,
+                "nPrvInputs": 2,
+                "nLabels": 4,
+                "nConstraints": 1,
+                "constraints": [[{ "2": minus_one }, { "3": "1" }, { "1": minus_one }]],
+            })
+        );
+
+        assert!(run(&args(&["kzg"])).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
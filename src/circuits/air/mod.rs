@@ -0,0 +1,152 @@
+// Algebraic intermediate representation (AIR): https://eprint.iacr.org/2018/046 (section 4)
+// A computation is recorded in an execution trace of n rows (n a power of two) and w columns, row i being the
+// state of the machine at step i. The AIR constrains the trace with:
+// - transition constraints, polynomials P_j(current, next) which vanish over every pair of consecutive rows
+//   (i, i + 1), for i in [0, n - 1),
+// - boundary constraints, which fix the value of a column at a given row, e.g. the inputs and outputs.
+// Seen as polynomials T_k over the domain H = <g> of size n, row i sits at g^i and the next row at g^{i+1},
+// so that the transition constraints become P_j(T(X), T(gX)) = 0 over H \ {g^{n-1}}.
+use ark_ff::PrimeField;
+
+/// Fixes trace[column][row] to `value`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundaryConstraint<F: PrimeField> {
+    pub column: usize,
+    pub row: usize,
+    pub value: F,
+}
+
+pub trait Air<F: PrimeField> {
+    /// Number of columns of the trace
+    fn width(&self) -> usize;
+
+    /// Number of rows of the trace, a power of two
+    fn trace_length(&self) -> usize;
+
+    fn num_transition_constraints(&self) -> usize;
+
+    /// Maximum total degree of the transition constraints in the current and next rows
+    fn transition_degree(&self) -> usize;
+
+    /// Evaluates the transition constraints over two consecutive rows, all zero if the step is valid
+    fn evaluate_transition(&self, current: &[F], next: &[F]) -> Vec<F>;
+
+    fn boundary_constraints(&self) -> Vec<BoundaryConstraint<F>>;
+
+    /// Checks a trace, given as one vector per column
+    fn is_satisfied(&self, trace: &[Vec<F>]) -> bool {
+        let n = self.trace_length();
+        if trace.len() != self.width() || trace.iter().any(|column| column.len() != n) {
+            return false;
+        }
+        let row = |i: usize| trace.iter().map(|column| column[i]).collect::<Vec<_>>();
+        let transitions = (0..n - 1).all(|i| {
+            self.evaluate_transition(&row(i), &row(i + 1))
+                .iter()
+                .all(|c| c.is_zero())
+        });
+        let boundaries = self
+            .boundary_constraints()
+            .iter()
+            .all(|b| b.column < self.width() && b.row < n && trace[b.column][b.row] == b.value);
+        transitions && boundaries
+    }
+}
+
+/// Two columns (a, b) starting at (1, 1), each step computing (a, b) -> (b, a + b):
+/// b ends up at the (n + 1)-th fibonacci number, claimed to be `result`
+#[derive(Clone, Debug)]
+pub struct FibonacciAir<F: PrimeField> {
+    trace_length: usize,
+    result: F,
+}
+
+impl<F: PrimeField> FibonacciAir<F> {
+    pub fn new(trace_length: usize, result: F) -> Self {
+        assert!(
+            trace_length.is_power_of_two() && trace_length >= 2,
+            "the trace length must be a power of two, at least 2"
+        );
+        Self {
+            trace_length,
+            result,
+        }
+    }
+
+    /// Runs the computation, returns the trace columns
+    pub fn trace(trace_length: usize) -> Vec<Vec<F>> {
+        let (mut a, mut b) = (vec![F::one()], vec![F::one()]);
+        for i in 1..trace_length {
+            a.push(b[i - 1]);
+            b.push(a[i - 1] + b[i - 1]);
+        }
+        vec![a, b]
+    }
+}
+
+impl<F: PrimeField> Air<F> for FibonacciAir<F> {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    fn num_transition_constraints(&self) -> usize {
+        2
+    }
+
+    fn transition_degree(&self) -> usize {
+        1
+    }
+
+    fn evaluate_transition(&self, current: &[F], next: &[F]) -> Vec<F> {
+        vec![next[0] - current[1], next[1] - current[0] - current[1]]
+    }
+
+    fn boundary_constraints(&self) -> Vec<BoundaryConstraint<F>> {
+        vec![
+            BoundaryConstraint {
+                column: 0,
+                row: 0,
+                value: F::one(),
+            },
+            BoundaryConstraint {
+                column: 1,
+                row: 0,
+                value: F::one(),
+            },
+            BoundaryConstraint {
+                column: 1,
+                row: self.trace_length - 1,
+                value: self.result,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Air, FibonacciAir};
+    use ark_bn254::Fr;
+    use ark_ff::One;
+
+    #[test]
+    pub fn test_fibonacci_air() {
+        let trace = FibonacciAir::<Fr>::trace(8);
+        assert_eq!(
+            trace[1],
+            [1u64, 2, 3, 5, 8, 13, 21, 34].map(Fr::from).to_vec()
+        );
+        assert!(FibonacciAir::new(8, Fr::from(34u64)).is_satisfied(&trace));
+        assert!(!FibonacciAir::new(8, Fr::from(35u64)).is_satisfied(&trace));
+        // wrong shape
+        assert!(!FibonacciAir::new(16, Fr::from(34u64)).is_satisfied(&trace));
+
+        // a broken step, the result being consistent
+        let mut wrong = trace.clone();
+        wrong[0][3] += Fr::one();
+        assert!(!FibonacciAir::new(8, Fr::from(34u64)).is_satisfied(&wrong));
+    }
+}
@@ -0,0 +1,226 @@
+// Customizable constraint systems: https://eprint.iacr.org/2023/552
+// A CCS is given by t matrices M_j (m x n), q multisets S_i of matrix indices and q constants c_i.
+// z = (1, x, w) satisfies it when sum_i c_i * (o_{j in S_i} M_j z) = 0, o being the hadamard product.
+// R1CS is the CCS with M = (A, B, C), S = ({0, 1}, {2}) and c = (1, -1).
+// A plonk gate q_L * a + q_R * b + q_O * c + q_M * a * b + q_C = 0 over the wires (a, b, c) of z is encoded
+// with the selectors folded in the matrices: M_L z = q_L o a, .., M_M z o M_B z = q_M o a o b, M_C z = q_C.
+use ark_ff::PrimeField;
+
+use super::r1cs::R1CS;
+use crate::utils::linear_algebra::{Matrix, Vector};
+
+#[derive(Clone, Debug)]
+pub struct CCS<F: PrimeField> {
+    /// Number of constraints
+    m: usize,
+    /// Length of z
+    n: usize,
+    /// Number of public inputs, without the constant 1
+    l: usize,
+    /// Maximum size of a multiset, the degree of the constraints
+    d: usize,
+    matrices: Vec<Matrix<F>>,
+    multisets: Vec<Vec<usize>>,
+    constants: Vec<F>,
+}
+
+/// A plonk gate: q_L * z[a] + q_R * z[b] + q_O * z[c] + q_M * z[a] * z[b] + q_C = 0
+/// Wiring the same index of z in several gates expresses the copy constraints.
+#[derive(Clone, Debug)]
+pub struct PlonkGate<F: PrimeField> {
+    pub q_l: F,
+    pub q_r: F,
+    pub q_o: F,
+    pub q_m: F,
+    pub q_c: F,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
+
+impl<F: PrimeField> CCS<F> {
+    pub fn new(
+        l: usize,
+        matrices: Vec<Matrix<F>>,
+        multisets: Vec<Vec<usize>>,
+        constants: Vec<F>,
+    ) -> Self {
+        assert!(!matrices.is_empty(), "a ccs needs at least one matrix");
+        assert_eq!(multisets.len(), constants.len());
+        assert!(
+            multisets.iter().flatten().all(|j| *j < matrices.len()),
+            "unknown matrix in a multiset"
+        );
+        let (m, n) = (matrices[0].num_rows(), matrices[0].num_cols());
+        assert!(
+            matrices
+                .iter()
+                .all(|matrix| matrix.num_rows() == m && matrix.num_cols() == n),
+            "matrices should have the same dimensions"
+        );
+        Self {
+            m,
+            n,
+            l,
+            d: multisets.iter().map(|s| s.len()).max().unwrap_or(0),
+            matrices,
+            multisets,
+            constants,
+        }
+    }
+
+    /// Encodes plonk gates over z = (1, x, w) of length n, with l public inputs
+    pub fn from_plonk_gates(gates: &[PlonkGate<F>], n: usize, l: usize) -> Self {
+        let mut rows = vec![vec![vec![F::zero(); n]; gates.len()]; 6];
+        for (i, gate) in gates.iter().enumerate() {
+            assert!(gate.a < n && gate.b < n && gate.c < n, "wire out of z");
+            rows[0][i][gate.a] += gate.q_l;
+            rows[1][i][gate.b] += gate.q_r;
+            rows[2][i][gate.c] += gate.q_o;
+            rows[3][i][gate.a] += gate.q_m;
+            rows[4][i][gate.b] += F::one();
+            rows[5][i][0] += gate.q_c;
+        }
+        let matrices = rows
+            .iter()
+            .map(|matrix_rows| Matrix::new_from_vecs(matrix_rows))
+            .collect();
+        let multisets = vec![vec![0], vec![1], vec![2], vec![3, 4], vec![5]];
+        Self::new(l, matrices, multisets, vec![F::one(); 5])
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn l(&self) -> usize {
+        self.l
+    }
+
+    pub fn t(&self) -> usize {
+        self.matrices.len()
+    }
+
+    pub fn q(&self) -> usize {
+        self.multisets.len()
+    }
+
+    pub fn d(&self) -> usize {
+        self.d
+    }
+
+    pub fn matrices(&self) -> &[Matrix<F>] {
+        &self.matrices
+    }
+
+    pub fn multisets(&self) -> &[Vec<usize>] {
+        &self.multisets
+    }
+
+    pub fn constants(&self) -> &[F] {
+        &self.constants
+    }
+
+    /// Checks sum_i c_i * (o_{j in S_i} M_j z) = 0
+    pub fn is_satisfied(&self, z: &Vector<F>) -> bool {
+        if z.size() != self.n {
+            return false;
+        }
+        let Ok(products) = self
+            .matrices
+            .iter()
+            .map(|m| m.dot_vector(z))
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return false;
+        };
+        let mut sum = Vector::new_zero_vector(self.m);
+        for (multiset, c) in self.multisets.iter().zip(&self.constants) {
+            let mut hadamard = Vector::new(&vec![F::one(); self.m]);
+            for j in multiset {
+                let Ok(product) = hadamard * products[*j].clone() else {
+                    return false;
+                };
+                hadamard = product;
+            }
+            let Ok(next) = sum + hadamard.scalar_mul(c) else {
+                return false;
+            };
+            sum = next;
+        }
+        sum.is_zero_vector()
+    }
+}
+
+impl<F: PrimeField> From<R1CS<F>> for CCS<F> {
+    fn from(r1cs: R1CS<F>) -> Self {
+        let l = r1cs.n_instance() - 1;
+        let (a, b, c) = r1cs.into_matrices();
+        Self::new(
+            l,
+            vec![a, b, c],
+            vec![vec![0, 1], vec![2]],
+            vec![F::one(), -F::one()],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlonkGate, CCS};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::utils::linear_algebra::Vector;
+    use ark_ff::{One, Zero};
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_ccs_from_r1cs() {
+        let circuit = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(25));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let ccs = CCS::from(r1cs.clone());
+        assert_eq!((ccs.t(), ccs.q(), ccs.d(), ccs.l()), (3, 2, 2, 1));
+        assert_eq!((ccs.m(), ccs.n()), (r1cs.n_constraints(), z.size()));
+        assert!(ccs.is_satisfied(&z));
+
+        let circuit = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(26));
+        assert!(!ccs.is_satisfied(&get_z_from_cs(circuit).unwrap()));
+    }
+
+    #[test]
+    pub fn test_ccs_from_plonk_gates() {
+        // a^2 + b^2 = c, z = (1, c, a, b, a^2, b^2)
+        let gate = |q_l: i64, q_r: i64, q_o: i64, q_m: i64, q_c: i64, a, b, c| PlonkGate {
+            q_l: Fr::from(q_l),
+            q_r: Fr::from(q_r),
+            q_o: Fr::from(q_o),
+            q_m: Fr::from(q_m),
+            q_c: Fr::from(q_c),
+            a,
+            b,
+            c,
+        };
+        let gates = [
+            // a * a - a^2 = 0
+            gate(0, 0, -1, 1, 0, 2, 2, 4),
+            // b * b - b^2 = 0
+            gate(0, 0, -1, 1, 0, 3, 3, 5),
+            // a^2 + b^2 - c = 0
+            gate(1, 1, -1, 0, 0, 4, 5, 1),
+            // a - 3 = 0, with a constant selector
+            gate(1, 0, 0, 0, -3, 2, 2, 2),
+        ];
+        let ccs = CCS::from_plonk_gates(&gates, 6, 1);
+        assert_eq!((ccs.m(), ccs.t(), ccs.q(), ccs.d()), (4, 6, 5, 2));
+        let z = [1, 25, 3, 4, 9, 16].map(Fr::from);
+        assert!(ccs.is_satisfied(&Vector::new(&z)));
+        let mut wrong_z = z;
+        wrong_z[1] += Fr::one();
+        assert!(!ccs.is_satisfied(&Vector::new(&wrong_z)));
+        assert!(!ccs.is_satisfied(&Vector::new(&[Fr::zero(); 5])));
+    }
+}
@@ -0,0 +1,109 @@
+// Membership of a leaf in a poseidon merkle tree, as `cs::merkle::verify_path` with a `PoseidonHasher`:
+// the leaf, its index and its authentication path are the witness, the root is public.
+// The index is decomposed into one bit per level, each bit selecting the order of the node and its sibling.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar, bits::boolean::Boolean, eq::EqGadget, fields::fp::FpVar,
+    select::CondSelectGadget,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use crate::crypto::poseidon::{gadgets::hash_gadget, PoseidonParameters};
+
+/// The root of the tree with the leaf at `index`, recomputed from its path
+pub fn merkle_root<F: PrimeField>(
+    parameters: &PoseidonParameters<F>,
+    leaf: F,
+    index: usize,
+    path: &[F],
+) -> F {
+    path.iter()
+        .enumerate()
+        .fold(parameters.hash(&[leaf]), |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                parameters.hash(&[node, *sibling])
+            } else {
+                parameters.hash(&[*sibling, node])
+            }
+        })
+}
+
+/// The leaf at `index` hashes up to root along `path`, with root public
+#[derive(Clone, Debug)]
+pub struct MerklePathCircuit<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+    leaf: F,
+    index: usize,
+    path: Vec<F>,
+    root: F,
+}
+
+impl<F: PrimeField> MerklePathCircuit<F> {
+    /// `path` holds the siblings from the bottom, as returned by `MerkleTree::open`
+    pub fn new(parameters: PoseidonParameters<F>, leaf: F, index: usize, path: Vec<F>) -> Self {
+        assert!(index >> path.len() == 0);
+        let root = merkle_root(&parameters, leaf, index, &path);
+        Self {
+            parameters,
+            leaf,
+            index,
+            path,
+            root,
+        }
+    }
+
+    pub fn root(&self) -> F {
+        self.root
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for MerklePathCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let root = FpVar::new_input(cs.clone(), || Ok(self.root))?;
+        let leaf = FpVar::new_witness(cs.clone(), || Ok(self.leaf))?;
+        let mut node = hash_gadget(&self.parameters, &[leaf])?;
+        for (level, sibling) in self.path.iter().enumerate() {
+            let sibling = FpVar::new_witness(cs.clone(), || Ok(*sibling))?;
+            let is_right = Boolean::new_witness(cs.clone(), || Ok((self.index >> level) & 1 == 1))?;
+            let left = FpVar::conditionally_select(&is_right, &sibling, &node)?;
+            let right = FpVar::conditionally_select(&is_right, &node, &sibling)?;
+            node = hash_gadget(&self.parameters, &[left, right])?;
+        }
+        node.enforce_equal(&root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MerklePathCircuit;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs};
+    use crate::crypto::poseidon::PoseidonParameters;
+    use crate::cs::merkle::{MerkleTree, PoseidonHasher};
+    use ark_bn254::Fr;
+    use ark_ff::One;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_merkle_path_circuit() {
+        let mut rng = test_rng();
+        let parameters = PoseidonParameters::<Fr>::new(3, 8, 57).unwrap();
+        let leaves = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let tree = MerkleTree::new(PoseidonHasher::new(parameters.clone()), &leaves);
+        for index in [0, 5] {
+            let circuit =
+                MerklePathCircuit::new(parameters.clone(), leaves[index], index, tree.open(index));
+            assert_eq!(circuit.root(), tree.root());
+            let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+            let z = get_z_from_cs(circuit).unwrap();
+            assert!(r1cs.is_satisfied(&z));
+        }
+
+        // a root that isn't the tree's
+        let mut wrong = MerklePathCircuit::new(parameters, leaves[3], 3, tree.open(3));
+        wrong.root += Fr::one();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        wrong.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,11 @@
+// Example circuits, larger than `TestPythagoreCircuit`, to run the folding schemes and the snarks on
+// realistically sized constraint systems:
+// - sha256: a SHA-256 compression, ~40k constraints of boolean arithmetic,
+// - poseidon: a poseidon permutation, a few hundred constraints,
+// - merkle: a poseidon merkle path check, as produced by `cs::merkle::MerkleTree`,
+// - range: bit decompositions of field elements, a constraint per bit.
+// Each circuit computes its public inputs natively when built, so that the witness is always satisfying.
+pub mod merkle;
+pub mod poseidon;
+pub mod range;
+pub mod sha256;
@@ -0,0 +1,63 @@
+// A poseidon permutation: the input state is the witness, the permuted state is public.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use crate::crypto::poseidon::{gadgets::permute_gadget, PoseidonParameters};
+
+/// permute(input) = output, with output public
+#[derive(Clone, Debug)]
+pub struct PoseidonPermutationCircuit<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+    input: Vec<F>,
+    output: Vec<F>,
+}
+
+impl<F: PrimeField> PoseidonPermutationCircuit<F> {
+    pub fn new(parameters: PoseidonParameters<F>, input: Vec<F>) -> Self {
+        let mut output = input.clone();
+        parameters.permute(&mut output);
+        Self {
+            parameters,
+            input,
+            output,
+        }
+    }
+
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.output.clone()
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for PoseidonPermutationCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut state = Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(self.input))?;
+        permute_gadget(&self.parameters, &mut state)?;
+        let output = Vec::<FpVar<F>>::new_input(cs, || Ok(self.output))?;
+        state.enforce_equal(&output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PoseidonPermutationCircuit;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs};
+    use crate::crypto::poseidon::PoseidonParameters;
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_poseidon_permutation_circuit() {
+        let mut rng = test_rng();
+        let parameters = PoseidonParameters::<Fr>::new(3, 8, 57).unwrap();
+        let input = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let circuit = PoseidonPermutationCircuit::new(parameters.clone(), input.clone());
+        let mut expected = input;
+        parameters.permute(&mut expected);
+        assert_eq!(circuit.public_inputs(), expected);
+
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(r1cs.is_satisfied(&z));
+    }
+}
@@ -0,0 +1,61 @@
+// Range checks: each value is shown to fit in `bits` bits by decomposing it into as many boolean witnesses,
+// recomposed and enforced equal to the value. A value costs bits + 1 constraints.
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, bits::boolean::Boolean, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+/// 0 <= values[i] < 2^bits for all i, with the values public
+#[derive(Clone, Debug)]
+pub struct RangeCheckCircuit<F: PrimeField> {
+    values: Vec<F>,
+    bits: usize,
+}
+
+impl<F: PrimeField> RangeCheckCircuit<F> {
+    /// `bits` must be below the modulus size, for the decomposition to be unique
+    pub fn new(values: Vec<F>, bits: usize) -> Self {
+        assert!(bits < F::MODULUS_BIT_SIZE as usize);
+        Self { values, bits }
+    }
+
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.values.clone()
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for RangeCheckCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        for value in self.values {
+            let value_var = FpVar::new_input(cs.clone(), || Ok(value))?;
+            let decomposition = value.into_bigint().to_bits_le();
+            let bits = (0..self.bits)
+                .map(|i| Boolean::new_witness(cs.clone(), || Ok(decomposition[i])))
+                .collect::<Result<Vec<_>, _>>()?;
+            Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(&value_var)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeCheckCircuit;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs};
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    #[test]
+    pub fn test_range_check_circuit() {
+        let values = [0u64, 1, 200, 255].map(Fr::from).to_vec();
+        let circuit = RangeCheckCircuit::new(values, 8);
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(r1cs.is_satisfied(&z));
+
+        // 256 doesn't fit in 8 bits
+        let circuit = RangeCheckCircuit::new(vec![Fr::from(256u64)], 8);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
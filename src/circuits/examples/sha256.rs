@@ -0,0 +1,227 @@
+// The SHA-256 compression function (FIPS 180-4, section 6.2.2), as a circuit over 32 bits words.
+// Words are 32 booleans: rotations are free, xors and ands cost a constraint per bit, and the modular additions
+// are batched with `addmany`. A compression is ~40k constraints: the 48 message schedule words and the
+// 64 rounds. The state before and after the compression are public, the message block is the witness.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    bits::{boolean::Boolean, uint32::UInt32},
+    eq::EqGadget,
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+/// Initial hash value H^(0)
+pub const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Native compression of a 512 bits block, `block` being its big endian words
+pub fn sha256_compress(state: &[u32; 8], block: &[u32; 16]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    w[..16].copy_from_slice(block);
+    for i in 16..64 {
+        let s_0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s_1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s_0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s_1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s_1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t_1 = h
+            .wrapping_add(s_1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s_0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t_2 = s_0.wrapping_add(maj);
+        (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t_1), c, b, a, t_1.wrapping_add(t_2));
+    }
+    let mut output = *state;
+    for (o, v) in output.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *o = o.wrapping_add(v);
+    }
+    output
+}
+
+/// x >> by, the vacated bits being constant zeros
+fn shr<F: PrimeField>(x: &UInt32<F>, by: usize) -> UInt32<F> {
+    let bits = x.to_bits_le();
+    let shifted = (0..32)
+        .map(|i| bits.get(i + by).cloned().unwrap_or(Boolean::FALSE))
+        .collect::<Vec<_>>();
+    UInt32::from_bits_le(&shifted)
+}
+
+/// Applies `op` bitwise
+fn bitwise<F: PrimeField>(
+    x: &UInt32<F>,
+    y: &UInt32<F>,
+    op: impl Fn(&Boolean<F>, &Boolean<F>) -> Result<Boolean<F>, SynthesisError>,
+) -> Result<UInt32<F>, SynthesisError> {
+    let bits = x
+        .to_bits_le()
+        .iter()
+        .zip(y.to_bits_le().iter())
+        .map(|(a, b)| op(a, b))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// The compression function over word variables
+pub fn sha256_compress_gadget<F: PrimeField>(
+    state: &[UInt32<F>],
+    block: &[UInt32<F>],
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    assert_eq!(state.len(), 8);
+    assert_eq!(block.len(), 16);
+    let and = |x: &Boolean<F>, y: &Boolean<F>| x.and(y);
+    let mut w = block.to_vec();
+    for i in 16..64 {
+        let s_0 = w[i - 15]
+            .rotr(7)
+            .xor(&w[i - 15].rotr(18))?
+            .xor(&shr(&w[i - 15], 3))?;
+        let s_1 = w[i - 2]
+            .rotr(17)
+            .xor(&w[i - 2].rotr(19))?
+            .xor(&shr(&w[i - 2], 10))?;
+        w.push(UInt32::addmany(&[
+            w[i - 16].clone(),
+            s_0,
+            w[i - 7].clone(),
+            s_1,
+        ])?);
+    }
+    let mut v = state.to_vec();
+    for i in 0..64 {
+        let (a, b, c, e, f, g) = (&v[0], &v[1], &v[2], &v[4], &v[5], &v[6]);
+        let s_1 = e.rotr(6).xor(&e.rotr(11))?.xor(&e.rotr(25))?;
+        // ch = (e & f) ^ (!e & g) = g ^ (e & (f ^ g))
+        let ch = g.xor(&bitwise(e, &f.xor(g)?, and)?)?;
+        let t_1 = UInt32::addmany(&[v[7].clone(), s_1, ch, UInt32::constant(K[i]), w[i].clone()])?;
+        let s_0 = a.rotr(2).xor(&a.rotr(13))?.xor(&a.rotr(22))?;
+        // maj = (a & b) ^ (a & c) ^ (b & c) = (a & b) ^ (c & (a ^ b))
+        let maj = bitwise(a, b, and)?.xor(&bitwise(c, &a.xor(b)?, and)?)?;
+        let t_2 = UInt32::addmany(&[s_0, maj])?;
+        let e = UInt32::addmany(&[v[3].clone(), t_1.clone()])?;
+        let a = UInt32::addmany(&[t_1, t_2])?;
+        v = vec![
+            a,
+            v[0].clone(),
+            v[1].clone(),
+            v[2].clone(),
+            e,
+            v[4].clone(),
+            v[5].clone(),
+            v[6].clone(),
+        ];
+    }
+    state
+        .iter()
+        .zip(v)
+        .map(|(s, v)| UInt32::addmany(&[s.clone(), v]))
+        .collect()
+}
+
+/// Exposes a word as a public field element
+fn enforce_public_word<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    word: &UInt32<F>,
+    value: u32,
+) -> Result<(), SynthesisError> {
+    let public = FpVar::new_input(cs, || Ok(F::from(value)))?;
+    Boolean::le_bits_to_fp_var(&word.to_bits_le())?.enforce_equal(&public)
+}
+
+/// sha256_compress(state, block) = output, with state and output public
+#[derive(Clone, Debug)]
+pub struct Sha256CompressionCircuit {
+    state: [u32; 8],
+    block: [u32; 16],
+    output: [u32; 8],
+}
+
+impl Sha256CompressionCircuit {
+    pub fn new(state: [u32; 8], block: [u32; 16]) -> Self {
+        Self {
+            output: sha256_compress(&state, &block),
+            state,
+            block,
+        }
+    }
+
+    /// The public inputs, the state then the output words
+    pub fn public_inputs<F: PrimeField>(&self) -> Vec<F> {
+        self.state
+            .iter()
+            .chain(&self.output)
+            .map(|w| F::from(*w))
+            .collect()
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for Sha256CompressionCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let state = Vec::<UInt32<F>>::new_witness(cs.clone(), || Ok(self.state.to_vec()))?;
+        for (word, value) in state.iter().zip(self.state) {
+            enforce_public_word(cs.clone(), word, value)?;
+        }
+        let block = Vec::<UInt32<F>>::new_witness(cs.clone(), || Ok(self.block.to_vec()))?;
+        let output = sha256_compress_gadget(&state, &block)?;
+        for (word, value) in output.iter().zip(self.output) {
+            enforce_public_word(cs.clone(), word, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha256_compress, Sha256CompressionCircuit, SHA256_IV};
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    #[test]
+    pub fn test_sha256_compression() {
+        // sha256("abc"), a single padded block
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 24;
+        let digest = [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ];
+        assert_eq!(sha256_compress(&SHA256_IV, &block), digest);
+
+        let circuit = Sha256CompressionCircuit::new(SHA256_IV, block);
+        assert_eq!(circuit.public_inputs::<Fr>()[8..], digest.map(Fr::from));
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.num_instance_variables(), 17);
+
+        // a wrong output
+        let mut wrong = circuit;
+        wrong.output[0] ^= 1;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        wrong.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
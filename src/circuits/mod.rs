@@ -1,3 +1,7 @@
+pub mod air;
+pub mod ccs;
+pub mod examples;
+pub mod plonkish;
 pub mod qap;
 pub mod r1cs;
 pub mod relaxed_r1cs;
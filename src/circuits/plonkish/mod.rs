@@ -0,0 +1,329 @@
+// Plonkish arithmetization: https://eprint.iacr.org/2019/953
+// The circuit is a table of n rows (n a power of two), one gate per row over the wires (a_i, b_i, c_i):
+//   q_L,i * a_i + q_R,i * b_i + q_O,i * c_i + q_M,i * a_i * b_i + q_C,i + PI_i = 0,
+// where PI_i = -x_i for the first l rows, which hold the public inputs x in their a wire, and 0 otherwise.
+// Copy constraints tie wires that carry the same variable: the 3n wire positions, a's first then b's and c's,
+// are permuted by sigma, which cycles through the positions of each variable. The witness satisfies them
+// when w[sigma(i)] = w[i] for every position i.
+use ark_ff::PrimeField;
+
+use super::ccs::PlonkGate;
+
+/// A variable of a circuit under construction, an index in the builder's values
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Variable(usize);
+
+/// Selectors and copy constraints of a plonkish circuit
+#[derive(Clone, Debug)]
+pub struct PlonkishCircuit<F: PrimeField> {
+    n: usize,
+    n_public: usize,
+    q_l: Vec<F>,
+    q_r: Vec<F>,
+    q_o: Vec<F>,
+    q_m: Vec<F>,
+    q_c: Vec<F>,
+    sigma: Vec<usize>,
+}
+
+/// The wire values of a plonkish circuit, one column per wire
+#[derive(Clone, Debug)]
+pub struct PlonkishWitness<F: PrimeField> {
+    pub a: Vec<F>,
+    pub b: Vec<F>,
+    pub c: Vec<F>,
+}
+
+impl<F: PrimeField> PlonkishWitness<F> {
+    /// The wire values, a's first then b's and c's, as indexed by sigma
+    pub fn wires(&self) -> Vec<F> {
+        [self.a.clone(), self.b.clone(), self.c.clone()].concat()
+    }
+}
+
+impl<F: PrimeField> PlonkishCircuit<F> {
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn n_public(&self) -> usize {
+        self.n_public
+    }
+
+    pub fn q_l(&self) -> &[F] {
+        &self.q_l
+    }
+
+    pub fn q_r(&self) -> &[F] {
+        &self.q_r
+    }
+
+    pub fn q_o(&self) -> &[F] {
+        &self.q_o
+    }
+
+    pub fn q_m(&self) -> &[F] {
+        &self.q_m
+    }
+
+    pub fn q_c(&self) -> &[F] {
+        &self.q_c
+    }
+
+    pub fn sigma(&self) -> &[usize] {
+        &self.sigma
+    }
+
+    /// The public inputs carried by the witness
+    pub fn public_inputs(&self, witness: &PlonkishWitness<F>) -> Vec<F> {
+        witness.a[..self.n_public].to_vec()
+    }
+
+    /// Checks the gate of each row and the copy constraints
+    pub fn is_satisfied(&self, witness: &PlonkishWitness<F>, public_inputs: &[F]) -> bool {
+        if public_inputs.len() != self.n_public
+            || witness.a.len() != self.n
+            || witness.b.len() != self.n
+            || witness.c.len() != self.n
+        {
+            return false;
+        }
+        let gates = (0..self.n).all(|i| {
+            let (a, b, c) = (witness.a[i], witness.b[i], witness.c[i]);
+            let pi = public_inputs.get(i).map_or(F::zero(), |x| -*x);
+            self.q_l[i] * a
+                + self.q_r[i] * b
+                + self.q_o[i] * c
+                + self.q_m[i] * a * b
+                + self.q_c[i]
+                + pi
+                == F::zero()
+        });
+        let wires = witness.wires();
+        gates && (0..3 * self.n).all(|i| wires[self.sigma[i]] == wires[i])
+    }
+}
+
+/// Builds a plonkish circuit and its witness together, gate by gate
+#[derive(Clone, Debug, Default)]
+pub struct PlonkishBuilder<F: PrimeField> {
+    values: Vec<F>,
+    public: Vec<Variable>,
+    /// gates whose wires a, b, c are variables
+    gates: Vec<PlonkGate<F>>,
+    equalities: Vec<(Variable, Variable)>,
+}
+
+impl<F: PrimeField> PlonkishBuilder<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self, variable: Variable) -> F {
+        self.values[variable.0]
+    }
+
+    fn alloc(&mut self, value: F) -> Variable {
+        self.values.push(value);
+        Variable(self.values.len() - 1)
+    }
+
+    /// A public input, placed in the a wire of one of the first rows
+    pub fn public_input(&mut self, value: F) -> Variable {
+        let variable = self.alloc(value);
+        self.public.push(variable);
+        variable
+    }
+
+    /// A witness value, constrained only by the gates using it
+    pub fn private_input(&mut self, value: F) -> Variable {
+        self.alloc(value)
+    }
+
+    /// Adds the gate q_L * a + q_R * b + q_O * c + q_M * a * b + q_C = 0 over wires = (a, b, c)
+    pub fn custom_gate(&mut self, selectors: [F; 5], wires: [Variable; 3]) {
+        let [q_l, q_r, q_o, q_m, q_c] = selectors;
+        self.gates.push(PlonkGate {
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            a: wires[0].0,
+            b: wires[1].0,
+            c: wires[2].0,
+        });
+    }
+
+    /// c = a + b
+    pub fn add(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc(self.value(a) + self.value(b));
+        self.custom_gate([F::ONE, F::ONE, -F::ONE, F::zero(), F::zero()], [a, b, c]);
+        c
+    }
+
+    /// c = a * b
+    pub fn mul(&mut self, a: Variable, b: Variable) -> Variable {
+        let c = self.alloc(self.value(a) * self.value(b));
+        self.custom_gate(
+            [F::zero(), F::zero(), -F::ONE, F::ONE, F::zero()],
+            [a, b, c],
+        );
+        c
+    }
+
+    /// c = a + constant
+    pub fn add_constant(&mut self, a: Variable, constant: F) -> Variable {
+        let c = self.alloc(self.value(a) + constant);
+        let unused = self.alloc(F::zero());
+        self.custom_gate(
+            [F::ONE, F::zero(), -F::ONE, F::zero(), constant],
+            [a, unused, c],
+        );
+        c
+    }
+
+    /// A variable fixed to `value`
+    pub fn constant(&mut self, value: F) -> Variable {
+        let a = self.alloc(value);
+        let (b, c) = (self.alloc(F::zero()), self.alloc(F::zero()));
+        self.custom_gate([F::ONE, F::zero(), F::zero(), F::zero(), -value], [a, b, c]);
+        a
+    }
+
+    /// Copy constraint between a and b
+    pub fn assert_equal(&mut self, a: Variable, b: Variable) {
+        self.equalities.push((a, b));
+    }
+
+    /// Lays out the public input rows then the gates, padded to a power of two with empty gates,
+    /// and computes sigma from the positions of each variable
+    pub fn build(&self) -> (PlonkishCircuit<F>, PlonkishWitness<F>) {
+        let n_public = self.public.len();
+        let n_rows = n_public + self.gates.len();
+        let n = n_rows.next_power_of_two().max(2);
+
+        // variables merged by copy constraints share a representative
+        let mut parent = (0..self.values.len()).collect::<Vec<_>>();
+        fn find(parent: &mut [usize], v: usize) -> usize {
+            if parent[v] != v {
+                parent[v] = find(parent, parent[v]);
+            }
+            parent[v]
+        }
+        for (a, b) in &self.equalities {
+            let (root_a, root_b) = (find(&mut parent, a.0), find(&mut parent, b.0));
+            parent[root_a] = root_b;
+        }
+
+        // wires[k * n + i] is the variable of wire k in row i, None for the unused wires
+        let mut wires = vec![None; 3 * n];
+        let mut selectors = vec![vec![F::zero(); n]; 5];
+        for (i, x) in self.public.iter().enumerate() {
+            wires[i] = Some(x.0);
+            selectors[0][i] = F::ONE;
+        }
+        for (i, gate) in self.gates.iter().enumerate() {
+            let row = n_public + i;
+            for (k, v) in [gate.a, gate.b, gate.c].into_iter().enumerate() {
+                wires[k * n + row] = Some(v);
+            }
+            for (selector, q) in selectors
+                .iter_mut()
+                .zip([gate.q_l, gate.q_r, gate.q_o, gate.q_m, gate.q_c])
+            {
+                selector[row] = q;
+            }
+        }
+
+        // sigma maps each position to the next one carrying the same variable, cycling back to the first
+        let mut sigma = (0..3 * n).collect::<Vec<_>>();
+        let mut last_position = vec![None; self.values.len()];
+        let mut first_position = vec![None; self.values.len()];
+        for (position, wire) in wires.iter().enumerate() {
+            if let Some(v) = wire {
+                let root = find(&mut parent, *v);
+                match last_position[root] {
+                    Some(last) => sigma[last] = position,
+                    None => first_position[root] = Some(position),
+                }
+                last_position[root] = Some(position);
+            }
+        }
+        for (first, last) in first_position.iter().zip(&last_position) {
+            if let (Some(first), Some(last)) = (first, last) {
+                sigma[*last] = *first;
+            }
+        }
+
+        let values = wires
+            .iter()
+            .map(|wire| wire.map_or(F::zero(), |v| self.values[v]))
+            .collect::<Vec<_>>();
+        let [q_l, q_r, q_o, q_m, q_c]: [Vec<F>; 5] = selectors.try_into().unwrap();
+        let circuit = PlonkishCircuit {
+            n,
+            n_public,
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            sigma,
+        };
+        let witness = PlonkishWitness {
+            a: values[..n].to_vec(),
+            b: values[n..2 * n].to_vec(),
+            c: values[2 * n..].to_vec(),
+        };
+        (circuit, witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlonkishBuilder;
+    use ark_bn254::Fr;
+
+    #[test]
+    pub fn test_plonkish_circuit() {
+        // x^3 + x + 5 = y, y public
+        let mut builder = PlonkishBuilder::new();
+        let y = builder.public_input(Fr::from(35u64));
+        let x = builder.private_input(Fr::from(3u64));
+        let x_2 = builder.mul(x, x);
+        let x_3 = builder.mul(x_2, x);
+        let sum = builder.add(x_3, x);
+        let result = builder.add_constant(sum, Fr::from(5u64));
+        builder.assert_equal(result, y);
+        assert_eq!(builder.value(result), Fr::from(35u64));
+
+        let (circuit, witness) = builder.build();
+        assert_eq!((circuit.n(), circuit.n_public()), (8, 1));
+        assert_eq!(circuit.public_inputs(&witness), vec![Fr::from(35u64)]);
+        assert!(circuit.is_satisfied(&witness, &[Fr::from(35u64)]));
+        assert!(!circuit.is_satisfied(&witness, &[Fr::from(36u64)]));
+
+        // breaking a gate
+        let mut wrong = witness.clone();
+        wrong.c[1] += Fr::from(1u64);
+        assert!(!circuit.is_satisfied(&wrong, &[Fr::from(35u64)]));
+        // breaking a copy constraint, x is used with a different value in the second multiplication
+        let mut wrong = witness.clone();
+        wrong.b[2] = Fr::from(4u64);
+        wrong.c[2] = wrong.a[2] * wrong.b[2];
+        assert!(!circuit.is_satisfied(&wrong, &[Fr::from(35u64)]));
+
+        // the same circuit with a wrong witness
+        let mut builder = PlonkishBuilder::new();
+        let y = builder.public_input(Fr::from(35u64));
+        let x = builder.private_input(Fr::from(4u64));
+        let x_2 = builder.mul(x, x);
+        let x_3 = builder.mul(x_2, x);
+        let sum = builder.add(x_3, x);
+        let result = builder.add_constant(sum, Fr::from(5u64));
+        builder.assert_equal(result, y);
+        let (circuit, witness) = builder.build();
+        assert!(!circuit.is_satisfied(&witness, &[Fr::from(35u64)]));
+    }
+}
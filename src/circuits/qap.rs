@@ -1,40 +1,247 @@
 // How to turn an R1CS into a QAP and verify its satisfiability.
 use ark_ff::PrimeField;
-use ark_poly::univariate::DensePolynomial;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    GeneralEvaluationDomain,
+};
+use ark_std::{cfg_chunks, cfg_into_iter, Zero};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
+use super::r1cs::R1CS;
+use crate::error::AlgorithmsError;
 use crate::utils::lagrange::compute_lagrange_interpolation_on_roots_of_unity;
-use crate::utils::linear_algebra::Matrix;
+use crate::utils::linear_algebra::{Matrix, Vector};
+use crate::utils::polynomial::{sparse::SparsePolynomial, vanishing_poly};
 
+/// The qap of an r1cs: the column polynomials A_i(X), B_i(X), C_i(X), taking the values of the i-th column
+/// of A, B, C over the roots of unity of H, and Z_H(X), vanishing on H
+/// z satisfies the r1cs iff Z_H divides A_z(X) * B_z(X) - C_z(X), with A_z(X) = sum_i z_i * A_i(X), etc.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug)]
+pub struct QAP<F: PrimeField> {
+    domain: GeneralEvaluationDomain<F>,
+    a: Vec<DensePolynomial<F>>,
+    b: Vec<DensePolynomial<F>>,
+    c: Vec<DensePolynomial<F>>,
+    z_h: SparsePolynomial<F>,
+}
+
+impl<F: PrimeField> QAP<F> {
+    /// H has the size of the number of constraints, rounded to the next power of two
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Result<Self, AlgorithmsError> {
+        Self::from_matrices(r1cs.a(), r1cs.b(), r1cs.c())
+    }
+
+    /// The qap of `r1cs` with one more constraint z_i * 0 = 0 per public index i (the 1 included), i.e. an
+    /// extra row per input, with a 1 at column i in A, as snarkjs, ark-groth16 and bellman do
+    /// The A_i of the public indices are then linearly independent: in groth16, an input no constraint uses
+    /// would otherwise have L_i = 0, and the proof would verify for any value of it.
+    /// H has the size of n_constraints + n_instance, rounded to the next power of two
+    pub fn from_r1cs_with_input_constraints(r1cs: &R1CS<F>) -> Result<Self, AlgorithmsError> {
+        let n_cols = r1cs.a().num_cols();
+        let with_input_rows = |m: &Matrix<F>, one_at_input: bool| {
+            let mut rows = m.rows().to_vec();
+            rows.extend((0..r1cs.n_instance()).map(|i| {
+                Vector::new(
+                    &(0..n_cols)
+                        .map(|j| F::from(one_at_input && i == j))
+                        .collect::<Vec<_>>(),
+                )
+            }));
+            Matrix::new(&rows)
+        };
+        Self::from_matrices(
+            &with_input_rows(r1cs.a(), true),
+            &with_input_rows(r1cs.b(), false),
+            &with_input_rows(r1cs.c(), false),
+        )
+    }
+
+    /// A, B and C should have the same dimensions
+    pub fn from_matrices(
+        a: &Matrix<F>,
+        b: &Matrix<F>,
+        c: &Matrix<F>,
+    ) -> Result<Self, AlgorithmsError> {
+        for m in [b, c] {
+            if m.num_rows() != a.num_rows() {
+                return Err(AlgorithmsError::DimensionMismatch {
+                    expected: a.num_rows(),
+                    got: m.num_rows(),
+                });
+            }
+            if m.num_cols() != a.num_cols() {
+                return Err(AlgorithmsError::DimensionMismatch {
+                    expected: a.num_cols(),
+                    got: m.num_cols(),
+                });
+            }
+        }
+        let domain = GeneralEvaluationDomain::<F>::new(a.num_rows())
+            .ok_or(AlgorithmsError::DomainNotFound(a.num_rows()))?;
+        Ok(Self {
+            domain,
+            a: compute_lagrange_polynomial_from_matrix(a)?,
+            b: compute_lagrange_polynomial_from_matrix(b)?,
+            c: compute_lagrange_polynomial_from_matrix(c)?,
+            z_h: vanishing_poly(domain),
+        })
+    }
+
+    pub fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.domain
+    }
+
+    pub fn a(&self) -> &[DensePolynomial<F>] {
+        &self.a
+    }
+
+    pub fn b(&self) -> &[DensePolynomial<F>] {
+        &self.b
+    }
+
+    pub fn c(&self) -> &[DensePolynomial<F>] {
+        &self.c
+    }
+
+    /// X^n - 1, kept sparse: evaluating it takes O(log n)
+    pub fn z_h(&self) -> &SparsePolynomial<F> {
+        &self.z_h
+    }
+
+    /// (A_z(X), B_z(X), C_z(X))
+    #[allow(clippy::type_complexity)]
+    pub fn combine_with_witness(
+        &self,
+        z: &Vector<F>,
+    ) -> Result<(DensePolynomial<F>, DensePolynomial<F>, DensePolynomial<F>), AlgorithmsError> {
+        Ok((
+            compute_witness_polynomial(&self.a, z)?,
+            compute_witness_polynomial(&self.b, z)?,
+            compute_witness_polynomial(&self.c, z)?,
+        ))
+    }
+
+    /// H(X) = (A_z(X) * B_z(X) - C_z(X)) / Z_H(X), an error if Z_H leaves a remainder
+    /// Computed in O(n log n) with ffts: Z_H divides the numerator iff it vanishes on H, and on the coset gH,
+    /// Z_H is the constant g^n - 1, so H(X) is interpolated from the numerator's evaluations over gH.
+    pub fn compute_h(&self, z: &Vector<F>) -> Result<DensePolynomial<F>, AlgorithmsError> {
+        let (a_z, b_z, c_z) = self.combine_with_witness(z)?;
+        let numerator_evals = |domain: &GeneralEvaluationDomain<F>| {
+            let (a_evals, b_evals, c_evals) = (
+                domain.fft(&a_z.coeffs),
+                domain.fft(&b_z.coeffs),
+                domain.fft(&c_z.coeffs),
+            );
+            a_evals
+                .iter()
+                .zip(b_evals)
+                .zip(c_evals)
+                .map(|((a, b), c)| *a * b - c)
+                .collect::<Vec<_>>()
+        };
+        if !numerator_evals(&self.domain).iter().all(|e| e.is_zero()) {
+            return Err(AlgorithmsError::UnsatisfiedConstraints);
+        }
+        let coset = self
+            .domain
+            .get_coset(F::GENERATOR)
+            .ok_or(AlgorithmsError::DomainNotFound(self.domain.size()))?;
+        // F::GENERATOR isn't in H, Z_H doesn't vanish on it
+        let z_h_inverse = self.z_h.evaluate(&F::GENERATOR).inverse().unwrap();
+        let h_evals = numerator_evals(&coset)
+            .into_iter()
+            .map(|e| e * z_h_inverse)
+            .collect::<Vec<_>>();
+        Ok(DensePolynomial::from_coefficients_vec(coset.ifft(&h_evals)))
+    }
+
+    pub fn is_satisfied(&self, z: &Vector<F>) -> bool {
+        self.compute_h(z).is_ok()
+    }
+}
+
+/// Number of column polynomials summed sequentially by each rayon task
+const CHUNK_SIZE: usize = 64;
+
+/// Interpolates each column over the roots of unity with an ifft, in O(n log n) for n rows
+/// Columns are interpolated in parallel with the `parallel` feature
 pub fn compute_lagrange_polynomial_from_matrix<F: PrimeField>(
     mat: &Matrix<F>,
-) -> Vec<DensePolynomial<F>> {
-    let mut lagrange_polys: Vec<DensePolynomial<F>> = Vec::with_capacity(mat.num_cols);
-    let n_cols = mat.num_cols;
-    for i in 0..n_cols {
-        let mut evals: Vec<F> = Vec::with_capacity(mat.num_rows);
-        for j in 0..mat.num_rows {
-            evals.push(mat.rows[j].elements[i]);
-        }
-        // lagrange polynomial for the i-th column
-        let lagrange_poly = compute_lagrange_interpolation_on_roots_of_unity(&evals);
-        lagrange_polys.push(lagrange_poly);
+) -> Result<Vec<DensePolynomial<F>>, AlgorithmsError> {
+    let domain = GeneralEvaluationDomain::<F>::new(mat.num_rows())
+        .ok_or(AlgorithmsError::DomainNotFound(mat.num_rows()))?;
+    Ok(cfg_into_iter!(0..mat.num_cols())
+        .map(|i| {
+            let evals = Evaluations::from_vec_and_domain(
+                mat.rows().iter().map(|row| row.elements()[i]).collect(),
+                domain,
+            );
+            // lagrange polynomial for the i-th column
+            evals.interpolate()
+        })
+        .collect())
+}
+
+/// Computes sum_i witness[i] * polys[i], i.e. the a*s (resp. b*s, c*s) polynomial of the qap
+/// Columns are split in chunks, each chunk is summed (in parallel with the `parallel` feature)
+/// and partial sums are combined at the end
+pub fn compute_witness_polynomial<F: PrimeField>(
+    polys: &[DensePolynomial<F>],
+    witness: &Vector<F>,
+) -> Result<DensePolynomial<F>, AlgorithmsError> {
+    if polys.len() != witness.size() {
+        return Err(AlgorithmsError::DimensionMismatch {
+            expected: polys.len(),
+            got: witness.size(),
+        });
     }
-    lagrange_polys
+    let partial_sums = cfg_chunks!(polys, CHUNK_SIZE)
+        .zip(cfg_chunks!(witness.elements(), CHUNK_SIZE))
+        .map(|(polys_chunk, witness_chunk)| {
+            let mut sum = DensePolynomial::zero();
+            for (poly, w_i) in polys_chunk.iter().zip(witness_chunk) {
+                sum = &sum + &(poly * *w_i);
+            }
+            sum
+        })
+        .collect::<Vec<_>>();
+    Ok(partial_sums
+        .iter()
+        .fold(DensePolynomial::zero(), |acc, sum| &acc + sum))
+}
+
+/// Same as `compute_witness_polynomial`, but working in evaluation form
+/// Evaluated at \omega^{j}, sum_i witness[i] * polys[i] is the j-th row of mat * witness
+/// So we can skip interpolating each column and interpolate once, over mat * witness
+pub fn compute_witness_polynomial_from_matrix<F: PrimeField>(
+    mat: &Matrix<F>,
+    witness: &Vector<F>,
+) -> Result<DensePolynomial<F>, AlgorithmsError> {
+    let evals = mat.dot_vector(witness)?;
+    compute_lagrange_interpolation_on_roots_of_unity(evals.elements())
 }
 
 #[cfg(test)]
 pub mod tests {
 
-    use crate::circuits::r1cs::utils::{get_test_r1cs, get_test_satisfying_witness};
-    use crate::utils::get_omega_domain;
+    use crate::circuits::r1cs::utils::{
+        get_r1cs_from_cs, get_test_r1cs, get_test_satisfying_witness, get_z_from_cs,
+        TestPythagoreCircuit,
+    };
     use crate::utils::linear_algebra::{Matrix, Vector};
     use ark_ff::One;
     use ark_ff::Zero;
-    use ark_poly::univariate::DensePolynomial;
-    use ark_poly::Polynomial;
+    use ark_poly::{EvaluationDomain, Polynomial};
     use ark_test_curves::bls12_381::Fr;
 
-    use super::compute_lagrange_polynomial_from_matrix;
+    use crate::error::AlgorithmsError;
+
+    use super::{
+        compute_lagrange_polynomial_from_matrix, compute_witness_polynomial,
+        compute_witness_polynomial_from_matrix, QAP,
+    };
 
     #[test]
     pub fn test_qap_is_satisfied() {
@@ -43,50 +250,83 @@ pub mod tests {
 
         // we lagrange-interpolate polynomials over an n-roots of unity domain
         // i.e.: f(\omega^{i}) == vec[i]
-        let a_polys = compute_lagrange_polynomial_from_matrix(&a);
-        let b_polys = compute_lagrange_polynomial_from_matrix(&b);
-        let c_polys = compute_lagrange_polynomial_from_matrix(&c);
-
-        // retrieving the domain over which the polynomials have been interpolated (the roots of unity)
-        let (domain, omegas) = get_omega_domain::<Fr>(a_polys[0].coeffs.len());
+        let qap = QAP::from_matrices(&a, &b, &c).unwrap();
+        let omegas = qap.domain().elements().collect::<Vec<_>>();
 
         // to illustrate, we can retrieve the last row of A:
-        assert_eq!(a_polys[0].evaluate(&omegas[3]), a.rows[3].elements[0]);
-        assert_eq!(a_polys[1].evaluate(&omegas[3]), a.rows[3].elements[1]);
-        assert_eq!(a_polys[2].evaluate(&omegas[3]), a.rows[3].elements[2]);
-        assert_eq!(a_polys[3].evaluate(&omegas[3]), a.rows[3].elements[3]);
-        assert_eq!(a_polys[4].evaluate(&omegas[3]), a.rows[3].elements[4]);
-        assert_eq!(a_polys[5].evaluate(&omegas[3]), a.rows[3].elements[5]);
-
-        // compute a*s, b*s, c*s
-        let mut a_final_poly: DensePolynomial<Fr> = DensePolynomial::zero();
-        let mut b_final_poly: DensePolynomial<Fr> = DensePolynomial::zero();
-        let mut c_final_poly: DensePolynomial<Fr> = DensePolynomial::zero();
-        for i in 0..a_polys.len() {
-            a_final_poly = &a_final_poly + &(&a_polys[i] * witness.elements[i]);
-            b_final_poly = &b_final_poly + &(&b_polys[i] * witness.elements[i]);
-            c_final_poly = &c_final_poly + &(&c_polys[i] * witness.elements[i]);
+        for (i, a_poly) in qap.a().iter().enumerate() {
+            assert_eq!(a_poly.evaluate(&omegas[3]), a.rows()[3].elements()[i]);
         }
+        // and Z_H vanishes on the domain
+        assert!(omegas
+            .iter()
+            .all(|omega| qap.z_h().evaluate(omega).is_zero()));
 
-        // compute (a*s) * (b*s) - (c*s)
-        let final_poly: DensePolynomial<Fr> = &(&a_final_poly * &b_final_poly) - &c_final_poly;
+        // (a*s) * (b*s) - (c*s) is divisible by the vanishing polynomial
+        assert!(qap.is_satisfied(&witness));
+        let (a_final_poly, b_final_poly, c_final_poly) =
+            qap.combine_with_witness(&witness).unwrap();
+        let h = qap.compute_h(&witness).unwrap();
+        assert_eq!(
+            &(&a_final_poly * &b_final_poly) - &c_final_poly,
+            qap.z_h().mul_dense(&h)
+        );
 
-        // check that the division by the vanishing polynomial leaves no remainder
-        let (_, remainder) = final_poly.divide_by_vanishing_poly(domain).unwrap();
-        assert!(remainder.is_zero());
+        // tamper with the witness: the division by the vanishing polynomial leaves a remainder
+        let tampered = Vector::new(
+            &witness
+                .elements()
+                .iter()
+                .map(|w_i| *w_i + Fr::one())
+                .collect::<Vec<_>>(),
+        );
+        assert!(!qap.is_satisfied(&tampered));
+        assert_eq!(
+            qap.compute_h(&tampered),
+            Err(AlgorithmsError::UnsatisfiedConstraints)
+        );
+        // a witness of the wrong size
+        let short = Vector::new(&witness.elements()[1..]);
+        assert!(!qap.is_satisfied(&short));
+        assert_eq!(
+            qap.compute_h(&short),
+            Err(AlgorithmsError::DimensionMismatch {
+                expected: witness.size(),
+                got: witness.size() - 1
+            })
+        );
+        // matrices of different shapes
+        let b_short = Matrix::new(&b.rows()[1..]);
+        assert_eq!(
+            QAP::from_matrices(&a, &b_short, &c).unwrap_err(),
+            AlgorithmsError::DimensionMismatch {
+                expected: a.num_rows(),
+                got: a.num_rows() - 1
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_qap_from_r1cs() {
+        let circuit = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(25));
+        let qap = QAP::from_r1cs(&get_r1cs_from_cs(circuit.clone()).unwrap()).unwrap();
+        assert!(qap.is_satisfied(&get_z_from_cs(circuit).unwrap()));
+        let circuit = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(26));
+        assert!(!qap.is_satisfied(&get_z_from_cs(circuit).unwrap()));
+    }
+
+    #[test]
+    pub fn test_witness_polynomial_from_evaluations() {
+        let (a, b, c): (Matrix<Fr>, Matrix<Fr>, Matrix<Fr>) = get_test_r1cs();
+        let witness: Vector<Fr> = get_test_satisfying_witness(3);
 
-        // tamper with the witness
-        // and check that the division by the vanishing polynomial leaves a remainder
-        let mut a_final_tampered_poly: DensePolynomial<Fr> = DensePolynomial::zero();
-        for i in 0..a_polys.len() {
-            a_final_tampered_poly =
-                &a_final_tampered_poly + &(&a_polys[i] * (witness.elements[i] + Fr::one()));
+        // interpolating once in evaluation form gives the same polynomials as summing columns
+        for mat in [a, b, c] {
+            let polys = compute_lagrange_polynomial_from_matrix(&mat).unwrap();
+            assert_eq!(
+                compute_witness_polynomial(&polys, &witness).unwrap(),
+                compute_witness_polynomial_from_matrix(&mat, &witness).unwrap()
+            );
         }
-        let final_tampered_poly: DensePolynomial<Fr> =
-            &(&a_final_tampered_poly * &b_final_poly) - &c_final_poly;
-        let (_, remainder) = final_tampered_poly
-            .divide_by_vanishing_poly(domain)
-            .unwrap();
-        assert!(remainder.is_zero() == false);
     }
 }
@@ -0,0 +1,252 @@
+// Structural analysis of an r1cs, e.g. to inspect a third-party circuit before folding it, and two passes
+// shrinking it without changing its satisfying assignments:
+// - witness variables appearing in no constraint are dropped, the caller projects z on the kept columns,
+// - constraints repeated (up to swapping A and B) or with empty A, B and C rows are dropped.
+// A constraint's degree is 2 when both A z and B z involve a variable, 1 when it is linear in z, 0 when it
+// only involves the constant 1.
+use ark_ff::PrimeField;
+use ark_relations::r1cs::Matrix as ArkMatrix;
+use std::collections::{BTreeMap, HashSet};
+
+use super::R1CS;
+use crate::utils::linear_algebra::MatrixOps;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatrixStats {
+    pub non_zero: usize,
+    /// non_zero / (rows * columns)
+    pub density: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct R1CSStats {
+    pub n_constraints: usize,
+    /// Length of z = (1, x, w)
+    pub n_variables: usize,
+    pub n_instance: usize,
+    pub n_witness: usize,
+    pub a: MatrixStats,
+    pub b: MatrixStats,
+    pub c: MatrixStats,
+    /// Number of constraints of each degree
+    pub degrees: BTreeMap<usize, usize>,
+    /// Witness variables appearing in no constraint
+    pub unused_variables: usize,
+}
+
+impl std::fmt::Display for R1CSStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} constraints, {} variables ({} instance, {} witness, {} unused)",
+            self.n_constraints,
+            self.n_variables,
+            self.n_instance,
+            self.n_witness,
+            self.unused_variables
+        )?;
+        for (name, stats) in [("A", &self.a), ("B", &self.b), ("C", &self.c)] {
+            writeln!(
+                f,
+                "{name}: {} non-zero entries, density {:.4}",
+                stats.non_zero, stats.density
+            )?;
+        }
+        for (degree, count) in &self.degrees {
+            writeln!(f, "degree {degree}: {count} constraints")?;
+        }
+        Ok(())
+    }
+}
+
+/// Degree of a row of A z or B z: 1 if it involves a variable, 0 if it is constant
+fn row_degree<F: PrimeField>(row: &[(F, usize)]) -> usize {
+    usize::from(row.iter().any(|(_, col)| *col != 0))
+}
+
+impl<F: PrimeField, M: MatrixOps<F>> R1CS<F, M> {
+    fn n_cols(&self) -> usize {
+        self.n_instance + self.n_witness
+    }
+
+    fn ark_matrices(&self) -> [ArkMatrix<F>; 3] {
+        [
+            self.a.to_ark_matrix(),
+            self.b.to_ark_matrix(),
+            self.c.to_ark_matrix(),
+        ]
+    }
+
+    fn with_ark_matrices(&self, [a, b, c]: [ArkMatrix<F>; 3], n_witness: usize) -> Self {
+        let (n_rows, n_cols) = (a.len(), self.n_instance + n_witness);
+        R1CS::new(
+            n_witness,
+            self.n_instance,
+            M::from_ark_matrix(&a, n_rows, n_cols),
+            M::from_ark_matrix(&b, n_rows, n_cols),
+            M::from_ark_matrix(&c, n_rows, n_cols),
+        )
+    }
+
+    /// Marks the columns of z appearing in at least one constraint
+    fn used_columns(&self, matrices: &[ArkMatrix<F>; 3]) -> Vec<bool> {
+        let mut used = vec![false; self.n_cols()];
+        for (_, col) in matrices.iter().flatten().flatten() {
+            used[*col] = true;
+        }
+        used
+    }
+
+    pub fn stats(&self) -> R1CSStats {
+        let matrices = self.ark_matrices();
+        let size = (self.n_constraints * self.n_cols()).max(1) as f64;
+        let matrix_stats = |m: &ArkMatrix<F>| {
+            let non_zero = m.iter().map(Vec::len).sum();
+            MatrixStats {
+                non_zero,
+                density: non_zero as f64 / size,
+            }
+        };
+        let mut degrees = BTreeMap::new();
+        for i in 0..self.n_constraints {
+            let [a, b, c] = &matrices;
+            let degree = match (row_degree(&a[i]), row_degree(&b[i])) {
+                (1, 1) => 2,
+                _ => (row_degree(&a[i]) + row_degree(&b[i]) + row_degree(&c[i])).min(1),
+            };
+            *degrees.entry(degree).or_insert(0) += 1;
+        }
+        let used = self.used_columns(&matrices);
+        R1CSStats {
+            n_constraints: self.n_constraints,
+            n_variables: self.n_cols(),
+            n_instance: self.n_instance,
+            n_witness: self.n_witness,
+            a: matrix_stats(&matrices[0]),
+            b: matrix_stats(&matrices[1]),
+            c: matrix_stats(&matrices[2]),
+            degrees,
+            unused_variables: used[self.n_instance..].iter().filter(|u| !**u).count(),
+        }
+    }
+
+    /// Drops the witness variables appearing in no constraint, along with the indices of the kept columns
+    /// of z: the assignment of the new r1cs is (z[i] for i in kept)
+    pub fn remove_unused_variables(&self) -> (Self, Vec<usize>) {
+        let matrices = self.ark_matrices();
+        let used = self.used_columns(&matrices);
+        // (1, x) stays, so that the instance keeps its layout
+        let kept = (0..self.n_cols())
+            .filter(|i| *i < self.n_instance || used[*i])
+            .collect::<Vec<_>>();
+        let mut new_index = vec![0; self.n_cols()];
+        for (new, old) in kept.iter().enumerate() {
+            new_index[*old] = new;
+        }
+        let matrices = matrices.map(|m| {
+            m.into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|(value, col)| (value, new_index[col]))
+                        .collect()
+                })
+                .collect()
+        });
+        let n_witness = kept.len() - self.n_instance;
+        (self.with_ark_matrices(matrices, n_witness), kept)
+    }
+
+    /// Drops the constraints with empty A, B and C rows, and the repeated ones, keeping the first occurrence
+    pub fn deduplicate_constraints(&self) -> Self {
+        let [a, b, c] = self.ark_matrices();
+        let mut seen = HashSet::new();
+        let mut kept: [ArkMatrix<F>; 3] = [vec![], vec![], vec![]];
+        for ((a, b), c) in a.into_iter().zip(b).zip(c) {
+            if a.is_empty() && b.is_empty() && c.is_empty() {
+                continue;
+            }
+            // A z * B z = B z * A z
+            let key = if a <= b {
+                (a.clone(), b.clone(), c.clone())
+            } else {
+                (b.clone(), a.clone(), c.clone())
+            };
+            if seen.insert(key) {
+                kept[0].push(a);
+                kept[1].push(b);
+                kept[2].push(c);
+            }
+        }
+        self.with_ark_matrices(kept, self.n_witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::r1cs::utils::{get_test_r1cs, get_test_satisfying_witness};
+    use crate::circuits::r1cs::R1CS;
+    use crate::utils::linear_algebra::{Matrix, SparseMatrix, Vector};
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_r1cs_stats_and_optimizations() {
+        let (a, b, c): (Matrix<Fr>, Matrix<Fr>, Matrix<Fr>) = get_test_r1cs();
+        let r1cs = R1CS::new(4, 2, a, b, c);
+        let stats = r1cs.stats();
+        assert_eq!((stats.n_constraints, stats.n_variables), (4, 6));
+        assert_eq!(
+            (stats.a.non_zero, stats.b.non_zero, stats.c.non_zero),
+            (6, 4, 4)
+        );
+        assert_eq!(stats.c.density, 4.0 / 24.0);
+        // x * x, x^2 * x and x^3 + x = out - 5 (B z = 1 for the last two)
+        assert_eq!(stats.degrees.get(&2), Some(&2));
+        assert_eq!(stats.degrees.get(&1), Some(&2));
+        assert_eq!(stats.unused_variables, 0);
+        assert!(stats.to_string().contains("4 constraints"));
+
+        // pad with an unused witness column, a repeated (swapped) constraint and an empty one
+        let padded = |m: &Matrix<Fr>, extra: Vec<Vec<Fr>>| {
+            let mut rows = m
+                .rows()
+                .iter()
+                .map(|row| [row.elements(), &[Fr::from(0)]].concat())
+                .collect::<Vec<_>>();
+            rows.extend(extra);
+            SparseMatrix::from_dense(&Matrix::new_from_vecs(&rows))
+        };
+        let row = |m: &Matrix<Fr>, i: usize| [m.rows()[i].elements(), &[Fr::from(0)]].concat();
+        let zero = vec![Fr::from(0); 7];
+        let (a, b, c) = (r1cs.a(), r1cs.b(), r1cs.c());
+        let bloated = R1CS::new(
+            5,
+            2,
+            padded(a, vec![row(b, 1), zero.clone()]),
+            padded(b, vec![row(a, 1), zero.clone()]),
+            padded(c, vec![row(c, 1), zero]),
+        );
+        let z = get_test_satisfying_witness::<Fr>(3);
+        let bloated_z = Vector::new(&[z.elements(), &[Fr::from(42)]].concat());
+        assert!(bloated.is_satisfied(&bloated_z));
+        let stats = bloated.stats();
+        assert_eq!(stats.unused_variables, 1);
+        assert_eq!(stats.degrees.get(&0), Some(&1));
+
+        let deduplicated = bloated.deduplicate_constraints();
+        assert_eq!(deduplicated.n_constraints(), 4);
+        let (optimized, kept) = deduplicated.remove_unused_variables();
+        assert_eq!(kept, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(optimized.n_witness(), 4);
+        let projected = Vector::new(
+            &kept
+                .iter()
+                .map(|i| bloated_z.elements()[*i])
+                .collect::<Vec<_>>(),
+        );
+        assert!(optimized.is_satisfied(&projected));
+        assert_eq!(
+            optimized.to_constraint_matrices(),
+            r1cs.to_constraint_matrices()
+        );
+    }
+}
@@ -0,0 +1,343 @@
+// Readers for circom's binary files: https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md
+// and snarkjs' witness files. Both start with a 4 bytes magic, a u32 version and a u32 number of sections, each
+// section being a u32 type, a u64 size and its content. Integers are little endian, field elements are n8 bytes
+// little endian integers, in standard (not montgomery) form.
+// - .r1cs: the header (type 1) holds n8, the prime, the number of wires, of public outputs, public inputs,
+//   private inputs, of labels and of constraints. The constraints (type 2) are three linear combinations
+//   A, B, C each, as a u32 number of terms followed by (u32 wire, n8 bytes coefficient) pairs. The wire to label
+//   map (type 3) holds a u64 label per wire.
+// - .wtns: the header (type 1) holds n8, the prime and the number of values, listed in the section of type 2.
+// Circom orders the wires as 1, the public outputs, the public inputs, then the private ones, which is this
+// crate's z = (1, x, w) layout: the outputs come first in x.
+use ark_ff::{BigInteger, PrimeField};
+use std::io::Read;
+
+use super::R1CS;
+use crate::error::AlgorithmsError;
+use crate::utils::linear_algebra::{Matrix, Vector};
+
+fn invalid<E: ToString>(error: E) -> AlgorithmsError {
+    AlgorithmsError::Serialization(error.to_string())
+}
+
+/// Reads a header and the sections of a circom binary file, as (type, content)
+fn read_sections<R: Read>(
+    reader: &mut R,
+    magic: &[u8; 4],
+) -> Result<Vec<(u32, Vec<u8>)>, AlgorithmsError> {
+    let mut file_magic = [0u8; 4];
+    reader.read_exact(&mut file_magic).map_err(invalid)?;
+    if &file_magic != magic {
+        return Err(invalid(format!(
+            "invalid magic, expected {}",
+            String::from_utf8_lossy(magic)
+        )));
+    }
+    let _version = read_u32(reader)?;
+    let n_sections = read_u32(reader)?;
+    let mut sections = vec![];
+    for _ in 0..n_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let mut content = vec![];
+        reader
+            .take(size)
+            .read_to_end(&mut content)
+            .map_err(invalid)?;
+        if content.len() as u64 != size {
+            return Err(invalid(format!("section {section_type} is truncated")));
+        }
+        sections.push((section_type, content));
+    }
+    Ok(sections)
+}
+
+fn section(sections: &[(u32, Vec<u8>)], section_type: u32) -> Result<&[u8], AlgorithmsError> {
+    sections
+        .iter()
+        .find(|(t, _)| *t == section_type)
+        .map(|(_, content)| content.as_slice())
+        .ok_or_else(|| invalid(format!("missing section {section_type}")))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, AlgorithmsError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes).map_err(invalid)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, AlgorithmsError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes).map_err(invalid)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads n8 and the prime, checks that the prime is F's modulus
+fn read_field_header<F: PrimeField, R: Read>(reader: &mut R) -> Result<usize, AlgorithmsError> {
+    let n8 = read_u32(reader)? as usize;
+    if n8 != F::zero().compressed_size() {
+        return Err(invalid(format!("field elements of {n8} bytes don't fit F")));
+    }
+    let mut prime = vec![0u8; n8];
+    reader.read_exact(&mut prime).map_err(invalid)?;
+    let mut modulus = F::MODULUS.to_bytes_le();
+    modulus.resize(n8, 0);
+    if prime != modulus {
+        return Err(invalid("the file's prime isn't F's modulus"));
+    }
+    Ok(n8)
+}
+
+fn read_field<F: PrimeField, R: Read>(reader: &mut R, n8: usize) -> Result<F, AlgorithmsError> {
+    let mut bytes = vec![0u8; n8];
+    reader.read_exact(&mut bytes).map_err(invalid)?;
+    // validates that the value is below the modulus
+    F::deserialize_compressed(bytes.as_slice()).map_err(invalid)
+}
+
+/// The counts of a .r1cs file's header, its prime being F's modulus
+#[derive(Clone, Debug, PartialEq)]
+pub struct R1CSHeader {
+    pub n8: usize,
+    pub n_wires: usize,
+    pub n_public_outputs: usize,
+    pub n_public_inputs: usize,
+    pub n_private_inputs: usize,
+    pub n_labels: u64,
+    pub n_constraints: usize,
+}
+
+/// Parses a circom .r1cs file
+pub fn read_r1cs<F: PrimeField, R: Read>(reader: R) -> Result<R1CS<F>, AlgorithmsError> {
+    Ok(read_r1cs_with_header(reader)?.1)
+}
+
+/// Parses a circom .r1cs file, along with its header
+pub fn read_r1cs_with_header<F: PrimeField, R: Read>(
+    mut reader: R,
+) -> Result<(R1CSHeader, R1CS<F>), AlgorithmsError> {
+    let sections = read_sections(&mut reader, b"r1cs")?;
+    let mut header = section(&sections, 1)?;
+    let n8 = read_field_header::<F, _>(&mut header)?;
+    let n_wires = read_u32(&mut header)? as usize;
+    let n_public_outputs = read_u32(&mut header)? as usize;
+    let n_public_inputs = read_u32(&mut header)? as usize;
+    let n_private_inputs = read_u32(&mut header)? as usize;
+    let n_labels = read_u64(&mut header)?;
+    let n_constraints = read_u32(&mut header)? as usize;
+    let n_instance = 1 + n_public_outputs + n_public_inputs;
+    if n_instance > n_wires {
+        return Err(invalid("more public wires than wires"));
+    }
+    // the matrices are dense: bound their size by the file's before allocating them, the wire to label map
+    // holding a u64 per wire and each constraint at least its three u32 term counts
+    if section(&sections, 3)?.len() as u64 != 8 * n_wires as u64 {
+        return Err(invalid(
+            "the wire to label map doesn't hold a label per wire",
+        ));
+    }
+    let mut constraints = section(&sections, 2)?;
+    if (constraints.len() / 12) < n_constraints {
+        return Err(invalid("fewer constraints than the header's"));
+    }
+
+    // arkworks' sparse format: one vector of (coefficient, wire) per row
+    let (mut a, mut b, mut c) = (vec![], vec![], vec![]);
+    for _ in 0..n_constraints {
+        for matrix in [&mut a, &mut b, &mut c] {
+            let n_terms = read_u32(&mut constraints)?;
+            let mut row = vec![];
+            for _ in 0..n_terms {
+                let wire = read_u32(&mut constraints)? as usize;
+                if wire >= n_wires {
+                    return Err(invalid(format!("wire {wire} out of bounds")));
+                }
+                row.push((read_field::<F, _>(&mut constraints, n8)?, wire));
+            }
+            matrix.push(row);
+        }
+    }
+    let to_matrix = |m| Matrix::new_from_ark_matrix(&m, n_constraints, n_wires);
+    let r1cs = R1CS::new(
+        n_wires - n_instance,
+        n_instance,
+        to_matrix(a),
+        to_matrix(b),
+        to_matrix(c),
+    );
+    let header = R1CSHeader {
+        n8,
+        n_wires,
+        n_public_outputs,
+        n_public_inputs,
+        n_private_inputs,
+        n_labels,
+        n_constraints,
+    };
+    Ok((header, r1cs))
+}
+
+/// Parses a snarkjs .wtns file into z = (1, x, w)
+pub fn read_wtns<F: PrimeField, R: Read>(mut reader: R) -> Result<Vector<F>, AlgorithmsError> {
+    let sections = read_sections(&mut reader, b"wtns")?;
+    let mut header = section(&sections, 1)?;
+    let n8 = read_field_header::<F, _>(&mut header)?;
+    let n_values = read_u32(&mut header)? as usize;
+    let mut values = section(&sections, 2)?;
+    if values.len() as u64 != (n_values * n8) as u64 {
+        return Err(invalid(
+            "the witness section doesn't hold the header's number of values",
+        ));
+    }
+    let z = (0..n_values)
+        .map(|_| read_field::<F, _>(&mut values, n8))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Vector::new(&z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_r1cs, read_r1cs_with_header, read_wtns, R1CSHeader};
+    use crate::circuits::qap::QAP;
+    use crate::error::AlgorithmsError;
+    use ark_bn254::Fr;
+    use ark_ff::{BigInteger, PrimeField};
+
+    fn section(section_type: u32, content: &[u8]) -> Vec<u8> {
+        [
+            &section_type.to_le_bytes()[..],
+            &(content.len() as u64).to_le_bytes(),
+            content,
+        ]
+        .concat()
+    }
+
+    fn field_header() -> Vec<u8> {
+        [&32u32.to_le_bytes()[..], &Fr::MODULUS.to_bytes_le()].concat()
+    }
+
+    fn element(value: u64) -> Vec<u8> {
+        Fr::from(value).into_bigint().to_bytes_le()
+    }
+
+    fn file(magic: &[u8], sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = [
+            magic,
+            &1u32.to_le_bytes(),
+            &(sections.len() as u32).to_le_bytes(),
+        ]
+        .concat();
+        for s in sections {
+            bytes.extend(s);
+        }
+        bytes
+    }
+
+    #[test]
+    pub fn test_read_circom() {
+        // out = a * b + 2, with wires (1, out, a, b, t):
+        // a * b = t, (t + 2) * 1 = out
+        let mut header = field_header();
+        for value in [5u32, 1, 0, 2] {
+            header.extend(value.to_le_bytes());
+        }
+        header.extend(5u64.to_le_bytes());
+        header.extend(2u32.to_le_bytes());
+        let lc = |terms: &[(u32, u64)]| {
+            let mut bytes = (terms.len() as u32).to_le_bytes().to_vec();
+            for (wire, value) in terms {
+                bytes.extend(wire.to_le_bytes());
+                bytes.extend(element(*value));
+            }
+            bytes
+        };
+        let constraints = [
+            lc(&[(2, 1)]),
+            lc(&[(3, 1)]),
+            lc(&[(4, 1)]),
+            lc(&[(4, 1), (0, 2)]),
+            lc(&[(0, 1)]),
+            lc(&[(1, 1)]),
+        ]
+        .concat();
+        // sections in any order, with an ignored wire to label map
+        let r1cs_file = file(
+            b"r1cs",
+            &[
+                section(2, &constraints),
+                section(1, &header),
+                section(3, &[0u8; 40]),
+            ],
+        );
+        let (counts, r1cs) = read_r1cs_with_header::<Fr, _>(r1cs_file.as_slice()).unwrap();
+        assert_eq!(
+            counts,
+            R1CSHeader {
+                n8: 32,
+                n_wires: 5,
+                n_public_outputs: 1,
+                n_public_inputs: 0,
+                n_private_inputs: 2,
+                n_labels: 5,
+                n_constraints: 2,
+            }
+        );
+        assert_eq!(
+            (r1cs.n_constraints(), r1cs.n_instance(), r1cs.n_witness()),
+            (2, 2, 3)
+        );
+
+        let values = [1u64, 14, 3, 4, 12].map(element).concat();
+        let wtns_header = [field_header(), 5u32.to_le_bytes().to_vec()].concat();
+        let wtns_file = file(b"wtns", &[section(1, &wtns_header), section(2, &values)]);
+        let z = read_wtns::<Fr, _>(wtns_file.as_slice()).unwrap();
+        assert!(r1cs.is_satisfied(&z));
+        assert!(QAP::from_r1cs(&r1cs).unwrap().is_satisfied(&z));
+
+        // a wrong witness, magic or prime, a truncated file
+        let values = [1u64, 15, 3, 4, 12].map(element).concat();
+        let wrong_wtns = file(b"wtns", &[section(1, &wtns_header), section(2, &values)]);
+        assert!(!r1cs.is_satisfied(&read_wtns::<Fr, _>(wrong_wtns.as_slice()).unwrap()));
+        assert!(read_wtns::<Fr, _>(r1cs_file.as_slice()).is_err());
+        assert!(read_r1cs::<ark_test_curves::bls12_381::Fr, _>(r1cs_file.as_slice()).is_err());
+        assert!(read_r1cs::<Fr, _>(&r1cs_file[..r1cs_file.len() - 1]).is_err());
+
+        // headers claiming more wires, constraints or values than the file holds
+        let mut huge_header = header.clone();
+        huge_header[36..40].copy_from_slice(&u32::MAX.to_le_bytes());
+        let huge_wires = file(
+            b"r1cs",
+            &[
+                section(1, &huge_header),
+                section(2, &constraints),
+                section(3, &[0u8; 40]),
+            ],
+        );
+        let error = AlgorithmsError::Serialization(
+            "the wire to label map doesn't hold a label per wire".to_string(),
+        );
+        assert_eq!(
+            read_r1cs::<Fr, _>(huge_wires.as_slice()).unwrap_err(),
+            error
+        );
+        let no_labels = file(b"r1cs", &[section(1, &header), section(2, &constraints)]);
+        assert!(read_r1cs::<Fr, _>(no_labels.as_slice()).is_err());
+        let mut huge_header = header.clone();
+        huge_header[60..64].copy_from_slice(&u32::MAX.to_le_bytes());
+        let huge_constraints = file(
+            b"r1cs",
+            &[
+                section(1, &huge_header),
+                section(2, &constraints),
+                section(3, &[0u8; 40]),
+            ],
+        );
+        assert!(read_r1cs::<Fr, _>(huge_constraints.as_slice()).is_err());
+        let huge_wtns_header = [field_header(), u32::MAX.to_le_bytes().to_vec()].concat();
+        let huge_wtns = file(
+            b"wtns",
+            &[section(1, &huge_wtns_header), section(2, &values)],
+        );
+        assert!(read_wtns::<Fr, _>(huge_wtns.as_slice()).is_err());
+    }
+}
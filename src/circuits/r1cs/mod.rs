@@ -1,30 +1,129 @@
+pub mod analysis;
+pub mod circom;
+pub mod solver;
 pub mod utils;
-use crate::utils::linear_algebra::{Matrix, Vector};
+use crate::utils::linear_algebra::{Matrix, MatrixOps, SparseMatrix};
 /// A lot of code has been forked from https://github.com/privacy-scaling-explorations/folding-schemes
 /// It includes things such as how r1cs matrices or the z vector are extracted
 /// It has been adapted here and there, in minor ways.
 /// Thanks Arnau! :)
 use ark_ff::PrimeField;
+use ark_relations::r1cs::ConstraintMatrices;
+use std::marker::PhantomData;
 
 use self::utils::R1CSInstanceWitness;
 
-/// A "regular" R1CS equation
+/// A "regular" R1CS equation, backed by dense matrices unless M is e.g. a `SparseMatrix<F>`
 #[derive(Clone, Debug)]
-pub struct R1CS<F: PrimeField> {
-    pub n_constraints: usize,
-    pub n_witness: usize,
-    pub n_instance: usize,
-    pub a: Matrix<F>,
-    pub b: Matrix<F>,
-    pub c: Matrix<F>,
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "M: serde::Serialize",
+        deserialize = "M: serde::Deserialize<'de>"
+    ))
+)]
+pub struct R1CS<F: PrimeField, M: MatrixOps<F> = Matrix<F>> {
+    n_constraints: usize,
+    n_witness: usize,
+    n_instance: usize,
+    a: M,
+    b: M,
+    c: M,
+    _field: PhantomData<F>,
 }
 
-impl<F: PrimeField> R1CS<F> {
+impl<F: PrimeField, M: MatrixOps<F>> R1CS<F, M> {
+    /// `n_instance` counts the constant 1, the number of constraints is the number of rows of the matrices
+    pub fn new(n_witness: usize, n_instance: usize, a: M, b: M, c: M) -> Self {
+        Self {
+            n_constraints: a.num_rows(),
+            n_witness,
+            n_instance,
+            a,
+            b,
+            c,
+            _field: PhantomData,
+        }
+    }
+
+    pub fn n_constraints(&self) -> usize {
+        self.n_constraints
+    }
+
+    pub fn n_witness(&self) -> usize {
+        self.n_witness
+    }
+
+    pub fn n_instance(&self) -> usize {
+        self.n_instance
+    }
+
+    pub fn a(&self) -> &M {
+        &self.a
+    }
+
+    pub fn b(&self) -> &M {
+        &self.b
+    }
+
+    pub fn c(&self) -> &M {
+        &self.c
+    }
+
+    /// Consumes the r1cs, returning its (A, B, C) matrices
+    pub fn into_matrices(self) -> (M, M, M) {
+        (self.a, self.b, self.c)
+    }
+
+    /// Back to arkworks' matrices, e.g. to reuse arkworks tooling or compare with `ConstraintSystem::to_matrices`
+    pub fn to_constraint_matrices(&self) -> ConstraintMatrices<F> {
+        let (a, b, c) = (
+            self.a.to_ark_matrix(),
+            self.b.to_ark_matrix(),
+            self.c.to_ark_matrix(),
+        );
+        let num_non_zero = |m: &ark_relations::r1cs::Matrix<F>| m.iter().map(Vec::len).sum();
+        ConstraintMatrices {
+            num_instance_variables: self.n_instance,
+            num_witness_variables: self.n_witness,
+            num_constraints: self.n_constraints,
+            a_num_non_zero: num_non_zero(&a),
+            b_num_non_zero: num_non_zero(&b),
+            c_num_non_zero: num_non_zero(&c),
+            a,
+            b,
+            c,
+        }
+    }
+
+    /// False if z doesn't have as many elements as the matrices have columns
     pub fn is_satisfied(&self, z: &R1CSInstanceWitness<F>) -> bool {
-        let az = self.a.dot_vector(z);
-        let bz = self.b.dot_vector(z);
-        let cz = self.c.dot_vector(z);
-        ((az * bz) - cz).is_zero_vector()
+        let (Ok(az), Ok(bz), Ok(cz)) = (
+            self.a.dot_vector(z),
+            self.b.dot_vector(z),
+            self.c.dot_vector(z),
+        ) else {
+            return false;
+        };
+        (az * bz)
+            .and_then(|azbz| azbz - cz)
+            .is_ok_and(|v| v.is_zero_vector())
+    }
+}
+
+impl<F: PrimeField> R1CS<F, SparseMatrix<F>> {
+    /// Wraps arkworks' matrices, whose columns are (1, x, w)
+    pub fn from_constraint_matrices(matrices: &ConstraintMatrices<F>) -> Self {
+        let n_cols = matrices.num_instance_variables + matrices.num_witness_variables;
+        let matrix = |m| SparseMatrix::new_from_ark_matrix(m, matrices.num_constraints, n_cols);
+        R1CS::new(
+            matrices.num_witness_variables,
+            matrices.num_instance_variables,
+            matrix(&matrices.a),
+            matrix(&matrices.b),
+            matrix(&matrices.c),
+        )
     }
 }
 
@@ -36,23 +135,24 @@ mod test {
     use crate::{
         circuits::r1cs::{
             utils::{
-                get_r1cs_from_cs, get_test_r1cs, get_test_satisfying_witness, get_z_from_cs,
-                TestPythagoreCircuit,
+                extract_r1cs, extract_z, generate_constraint_system, get_r1cs_from_cs,
+                get_sparse_r1cs_from_cs, get_test_r1cs, get_test_satisfying_witness, get_z_from_cs,
+                MatricesCircuit, TestPythagoreCircuit,
             },
             R1CS,
         },
-        utils::linear_algebra::{Matrix, Vector},
+        utils::linear_algebra::{Matrix, SparseMatrix, Vector},
     };
 
     #[test]
     pub fn test_raw_r1cs_is_satisfied() {
         let (a, b, c): (Matrix<Fr>, Matrix<Fr>, Matrix<Fr>) = get_test_r1cs();
         let witness: Vector<Fr> = get_test_satisfying_witness(5);
-        let a_dot_w = a.dot_vector(&witness);
-        let b_dot_w = b.dot_vector(&witness);
-        let c_dot_w = c.dot_vector(&witness);
-        let a_times_b = a_dot_w * b_dot_w;
-        let a_times_b_minus_c = a_times_b - c_dot_w;
+        let a_dot_w = a.dot_vector(&witness).unwrap();
+        let b_dot_w = b.dot_vector(&witness).unwrap();
+        let c_dot_w = c.dot_vector(&witness).unwrap();
+        let a_times_b = (a_dot_w * b_dot_w).unwrap();
+        let a_times_b_minus_c = (a_times_b - c_dot_w).unwrap();
         assert!(a_times_b_minus_c.is_zero_vector());
     }
 
@@ -83,4 +183,71 @@ mod test {
         let z = get_z_from_cs(circuit.clone()).unwrap();
         assert!(!r1cs.is_satisfied(&z));
     }
+
+    #[test]
+    pub fn test_sparse_r1cs() {
+        let circuit = TestPythagoreCircuit::new(Fr::from(5), Fr::from(10), Fr::from(125));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let sparse_r1cs = get_sparse_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(sparse_r1cs.is_satisfied(&z));
+        assert_eq!(sparse_r1cs.n_constraints(), r1cs.n_constraints());
+        // only the non-zero entries are stored, and they give back the dense matrices
+        for (sparse, dense) in [
+            (sparse_r1cs.a(), r1cs.a()),
+            (sparse_r1cs.b(), r1cs.b()),
+            (sparse_r1cs.c(), r1cs.c()),
+        ] {
+            assert!(sparse.entries().len() < dense.num_rows() * dense.num_cols());
+            assert_eq!(
+                sparse.dot_vector(&z).unwrap().elements(),
+                dense.dot_vector(&z).unwrap().elements()
+            );
+            let roundtrip = SparseMatrix::from_dense(&sparse.to_dense());
+            assert_eq!(roundtrip.entries(), sparse.entries());
+        }
+
+        let circuit = TestPythagoreCircuit::new(Fr::from(1), Fr::from(1), Fr::from(100));
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(!sparse_r1cs.is_satisfied(&z));
+    }
+
+    #[test]
+    pub fn test_constraint_matrices_roundtrip() {
+        let circuit = TestPythagoreCircuit::new(Fr::from(5), Fr::from(10), Fr::from(125));
+        let cs = generate_constraint_system(circuit.clone()).unwrap();
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let matrices = cs.to_matrices().unwrap();
+        assert_eq!(r1cs.to_constraint_matrices(), matrices);
+        let sparse = R1CS::from_constraint_matrices(&matrices);
+        assert_eq!(sparse.to_constraint_matrices(), matrices);
+        assert!(sparse.is_satisfied(&z));
+
+        // synthesizing the matrices gives back the same system and assignment
+        let cs =
+            generate_constraint_system(MatricesCircuit::new(matrices.clone(), Some(z.clone())))
+                .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.to_matrices().unwrap(), matrices);
+        assert_eq!(extract_r1cs(&cs).to_constraint_matrices(), matrices);
+        assert_eq!(extract_z(&cs).elements(), z.elements());
+
+        // the raw test r1cs, and an assignment that doesn't satisfy it
+        let (a, b, c): (Matrix<Fr>, Matrix<Fr>, Matrix<Fr>) = get_test_r1cs();
+        let raw = R1CS::new(4, 2, a, b, c).to_constraint_matrices();
+        let cs = generate_constraint_system(MatricesCircuit::new(
+            raw.clone(),
+            Some(get_test_satisfying_witness(3)),
+        ))
+        .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        let mut wrong = get_test_satisfying_witness::<Fr>(3);
+        wrong.set(5, Fr::from(0)).unwrap();
+        let cs =
+            generate_constraint_system(MatricesCircuit::new(raw.clone(), Some(wrong))).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+        let short = Vector::new(&z.elements()[..3]);
+        assert!(generate_constraint_system(MatricesCircuit::new(raw, Some(short))).is_err());
+    }
 }
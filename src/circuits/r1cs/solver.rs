@@ -0,0 +1,142 @@
+// Witness calculator for r1cs whose constraints each introduce at most one new variable, as circuits
+// compiled gate by gate do: starting from z = (1, x), a constraint A z * B z = C z with a single unknown
+// variable v, appearing linearly in one of A, B or C only, gives v by forward substitution:
+// - in C: v = (A z * B z - rest of C z) / coeff,
+// - in A: v = (C z / B z - rest of A z) / coeff, B z being non-zero (same for B).
+// Constraints are swept in order until no new variable is found, so that a variable can be used before
+// the constraint defining it. The witness is then checked against all the constraints.
+use ark_ff::PrimeField;
+
+use super::{utils::R1CSInstanceWitness, R1CS};
+use crate::error::AlgorithmsError;
+use crate::utils::linear_algebra::{MatrixOps, Vector};
+
+/// The unknown variables of a row, and the sum over the known ones
+fn split_row<F: PrimeField>(row: &[(F, usize)], z: &[Option<F>]) -> (Vec<(F, usize)>, F) {
+    let mut unknown = vec![];
+    let mut sum = F::zero();
+    for &(coeff, col) in row {
+        match z[col] {
+            Some(value) => sum += coeff * value,
+            None => unknown.push((coeff, col)),
+        }
+    }
+    (unknown, sum)
+}
+
+impl<F: PrimeField, M: MatrixOps<F>> R1CS<F, M> {
+    /// Derives z = (1, x, w) from the public inputs x
+    pub fn solve_witness(
+        &self,
+        public_inputs: &[F],
+    ) -> Result<R1CSInstanceWitness<F>, AlgorithmsError> {
+        if public_inputs.len() + 1 != self.n_instance {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.n_instance - 1,
+                got: public_inputs.len(),
+            });
+        }
+        let (a, b, c) = (
+            self.a.to_ark_matrix(),
+            self.b.to_ark_matrix(),
+            self.c.to_ark_matrix(),
+        );
+        let mut z = vec![None; self.n_instance + self.n_witness];
+        z[0] = Some(F::one());
+        for (i, x) in public_inputs.iter().enumerate() {
+            z[i + 1] = Some(*x);
+        }
+        let mut progress = true;
+        while progress {
+            progress = false;
+            for i in 0..self.n_constraints {
+                let (a_unknown, a_sum) = split_row(&a[i], &z);
+                let (b_unknown, b_sum) = split_row(&b[i], &z);
+                let (c_unknown, c_sum) = split_row(&c[i], &z);
+                let solved = match (&a_unknown[..], &b_unknown[..], &c_unknown[..]) {
+                    ([], [], [(coeff, col)]) => coeff
+                        .inverse()
+                        .map(|inv| (*col, (a_sum * b_sum - c_sum) * inv)),
+                    ([(coeff, col)], [], []) => (b_sum * coeff)
+                        .inverse()
+                        .map(|inv| (*col, (c_sum - a_sum * b_sum) * inv)),
+                    ([], [(coeff, col)], []) => (a_sum * coeff)
+                        .inverse()
+                        .map(|inv| (*col, (c_sum - a_sum * b_sum) * inv)),
+                    _ => None,
+                };
+                if let Some((col, value)) = solved {
+                    z[col] = Some(value);
+                    progress = true;
+                }
+            }
+        }
+        let z = z
+            .iter()
+            .enumerate()
+            .map(|(i, value)| value.ok_or(AlgorithmsError::UnsolvedVariable(i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let z = Vector::new(&z);
+        if !self.is_satisfied(&z) {
+            return Err(AlgorithmsError::UnsatisfiedConstraints);
+        }
+        Ok(z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::r1cs::utils::{
+        get_r1cs_from_cs, get_test_r1cs, get_test_satisfying_witness, TestPythagoreCircuit,
+    };
+    use crate::circuits::r1cs::R1CS;
+    use crate::error::AlgorithmsError;
+    use crate::utils::linear_algebra::{Matrix, SparseMatrix};
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_solve_witness() {
+        let (a, b, c): (Matrix<Fr>, Matrix<Fr>, Matrix<Fr>) = get_test_r1cs();
+        let r1cs = R1CS::new(4, 2, a, b, c);
+        for x in [0, 3, 5] {
+            let z = r1cs.solve_witness(&[Fr::from(x as u64)]).unwrap();
+            assert_eq!(
+                z.elements(),
+                get_test_satisfying_witness::<Fr>(x).elements()
+            );
+        }
+        assert_eq!(
+            r1cs.solve_witness(&[]).err(),
+            Some(AlgorithmsError::DimensionMismatch {
+                expected: 1,
+                got: 0
+            })
+        );
+
+        // the constraints in reverse order need several sweeps
+        let reversed = |m: &Matrix<Fr>| {
+            let rows = m.rows().iter().rev().cloned().collect::<Vec<_>>();
+            SparseMatrix::from_dense(&Matrix::new(&rows))
+        };
+        let r1cs = R1CS::new(
+            4,
+            2,
+            reversed(r1cs.a()),
+            reversed(r1cs.b()),
+            reversed(r1cs.c()),
+        );
+        let z = r1cs.solve_witness(&[Fr::from(3u64)]).unwrap();
+        assert_eq!(
+            z.elements(),
+            get_test_satisfying_witness::<Fr>(3).elements()
+        );
+
+        // a and b are free in a^2 + b^2 = c: they can't be derived from c
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit).unwrap();
+        assert!(matches!(
+            r1cs.solve_witness(&[Fr::from(25u64)]),
+            Err(AlgorithmsError::UnsolvedVariable(_))
+        ));
+    }
+}
@@ -1,8 +1,11 @@
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
-use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef,
+    LinearCombination, SynthesisError, Variable,
+};
 
-use crate::utils::linear_algebra::{Matrix, Vector};
+use crate::utils::linear_algebra::{Matrix, SparseMatrix, Vector};
 
 use super::R1CS;
 
@@ -43,6 +46,14 @@ pub fn get_r1cs_from_cs<F: PrimeField>(
     Ok(r1cs)
 }
 
+/// Same as `get_r1cs_from_cs`, with the matrices kept sparse
+pub fn get_sparse_r1cs_from_cs<F: PrimeField>(
+    circuit: impl ConstraintSynthesizer<F>,
+) -> Result<R1CS<F, SparseMatrix<F>>, String> {
+    let cs = generate_constraint_system(circuit)?;
+    Ok(extract_sparse_r1cs::<F>(&cs))
+}
+
 /// Returns the instance-witness vector in (W, 1, x) format
 pub fn get_z_from_cs<F: PrimeField>(
     circuit: impl ConstraintSynthesizer<F>,
@@ -57,7 +68,6 @@ pub fn get_z_from_cs<F: PrimeField>(
 /// thanks Arnau! :)
 pub fn extract_r1cs<F: PrimeField>(cs: &ConstraintSystem<F>) -> R1CS<F> {
     let m = cs.to_matrices().unwrap();
-    let n_constraints = cs.num_constraints;
     let n_witness = cs.num_witness_variables;
     let n_instance = cs.num_instance_variables;
     let n_rows = cs.num_constraints;
@@ -65,23 +75,82 @@ pub fn extract_r1cs<F: PrimeField>(cs: &ConstraintSystem<F>) -> R1CS<F> {
     let a = Matrix::new_from_ark_matrix(&m.a, n_rows, n_cols);
     let b = Matrix::new_from_ark_matrix(&m.b, n_rows, n_cols);
     let c = Matrix::new_from_ark_matrix(&m.c, n_rows, n_cols);
-    R1CS {
-        n_constraints,
-        n_witness,
-        n_instance,
-        a,
-        b,
-        c,
-    }
+    R1CS::new(n_witness, n_instance, a, b, c)
+}
+
+/// Same as `extract_r1cs`, without densifying the arkworks matrices
+pub fn extract_sparse_r1cs<F: PrimeField>(cs: &ConstraintSystem<F>) -> R1CS<F, SparseMatrix<F>> {
+    let m = cs.to_matrices().unwrap();
+    let n_rows = cs.num_constraints;
+    let n_cols = cs.num_instance_variables + cs.num_witness_variables;
+    let a = SparseMatrix::new_from_ark_matrix(&m.a, n_rows, n_cols);
+    let b = SparseMatrix::new_from_ark_matrix(&m.b, n_rows, n_cols);
+    let c = SparseMatrix::new_from_ark_matrix(&m.c, n_rows, n_cols);
+    R1CS::new(cs.num_witness_variables, cs.num_instance_variables, a, b, c)
 }
 
 pub fn extract_z<F: PrimeField>(cs: &ConstraintSystem<F>) -> R1CSInstanceWitness<F> {
     let mut z = cs.instance_assignment.clone(); // starts with pub io
     let mut witness = cs.witness_assignment.clone();
-    _ = z.append(&mut witness);
+    z.append(&mut witness);
     Vector::new(&z)
 }
 
+/// A circuit enforcing raw arkworks matrices, to synthesize a `ConstraintSystem` from an r1cs
+/// Without an assignment, only the constraints can be generated, e.g. for a setup
+#[derive(Clone, Debug)]
+pub struct MatricesCircuit<F: PrimeField> {
+    matrices: ConstraintMatrices<F>,
+    z: Option<R1CSInstanceWitness<F>>,
+}
+
+impl<F: PrimeField> MatricesCircuit<F> {
+    /// `z` is (1, x, w), laid out as the columns of the matrices
+    pub fn new(matrices: ConstraintMatrices<F>, z: Option<R1CSInstanceWitness<F>>) -> Self {
+        Self { matrices, z }
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for MatricesCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let n_instance = self.matrices.num_instance_variables;
+        let n_cols = n_instance + self.matrices.num_witness_variables;
+        if self.z.as_ref().is_some_and(|z| z.size() != n_cols) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let value = |i: usize| {
+            self.z
+                .as_ref()
+                .map(|z| z.elements()[i])
+                .ok_or(SynthesisError::AssignmentMissing)
+        };
+        // column 0 is the constant 1, already allocated by the constraint system
+        let mut variables = vec![Variable::One];
+        for i in 1..n_instance {
+            variables.push(cs.new_input_variable(|| value(i))?);
+        }
+        for i in n_instance..n_cols {
+            variables.push(cs.new_witness_variable(|| value(i))?);
+        }
+        let lc = |row: &Vec<(F, usize)>| {
+            LinearCombination(
+                row.iter()
+                    .map(|&(coeff, col)| (coeff, variables[col]))
+                    .collect(),
+            )
+        };
+        for i in 0..self.matrices.num_constraints {
+            let row = |m: &ark_relations::r1cs::Matrix<F>| m.get(i).map_or(vec![], Clone::clone);
+            cs.enforce_constraint(
+                lc(&row(&self.matrices.a)),
+                lc(&row(&self.matrices.b)),
+                lc(&row(&self.matrices.c)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl<F: PrimeField> ConstraintSynthesizer<F> for TestPythagoreCircuit<F> {
     fn generate_constraints(
         self,
@@ -102,104 +171,104 @@ pub fn get_test_r1cs<F: PrimeField>() -> (Matrix<F>, Matrix<F>, Matrix<F>) {
     // Taken from vb: https://medium.com/@VitalikButerin/quadratic-arithmetic-programs-from-zero-to-hero-f6d558cea649
     let a: Vec<Vec<F>> = vec![
         vec![
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(5 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
+            F::from(5u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
         ],
     ];
     let b: Vec<Vec<F>> = vec![
         vec![
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
     ];
     let c: Vec<Vec<F>> = vec![
         vec![
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
         ],
         vec![
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(1 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
-            F::from(0 as u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(1u8),
+            F::from(0u8),
+            F::from(0u8),
+            F::from(0u8),
         ],
     ];
     (
@@ -213,12 +282,12 @@ pub fn get_test_r1cs<F: PrimeField>() -> (Matrix<F>, Matrix<F>, Matrix<F>) {
 pub fn get_test_satisfying_witness<F: PrimeField>(input: usize) -> Vector<F> {
     // z = (1, io, w)
     let input = F::from(input as u64);
-    Vector::new(&vec![
+    Vector::new(&[
         F::ONE,
-        input,                                            // io
-        input * input * input + input + F::from(5 as u8), // x^3 + x + 5
-        input * input,                                    // x^2
-        input * input * input,                            // x^2 * x
-        input * input * input + input,                    // x^3 + x
+        input,                                        // io
+        input * input * input + input + F::from(5u8), // x^3 + x + 5
+        input * input,                                // x^2
+        input * input * input,                        // x^2 * x
+        input * input * input + input,                // x^3 + x
     ])
 }
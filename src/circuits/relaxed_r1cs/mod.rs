@@ -1,170 +1,288 @@
-use std::ops::Add;
-
 use ark_ff::PrimeField;
+use std::marker::PhantomData;
 
-use crate::utils::linear_algebra::{Matrix, Vector};
+use crate::error::AlgorithmsError;
+use crate::utils::linear_algebra::{Matrix, MatrixOps, Vector};
 
-use super::r1cs::R1CS;
+use super::r1cs::{utils::R1CSInstanceWitness, R1CS};
 
 pub type R1CSRelaxedInstanceWitness<F> = Vector<F>;
 pub type R1CSRelaxedErrorTerm<F> = Vector<F>;
 
-/// A relaxed R1CS equation
-pub struct R1CSRelaxed<F: PrimeField> {
-    pub n_constraints: usize,
-    pub n_witness: usize,
-    pub n_instance: usize,
-    pub a: Matrix<F>,
-    pub b: Matrix<F>,
-    pub c: Matrix<F>,
-    pub e: R1CSRelaxedErrorTerm<F>,
-    pub u: F,
+/// The cross term of folding (u_1, z_1) with (u_2, z_2), z_i = (u_i, x_i, w_i) being full assignments:
+/// T = AZ_1 o BZ_2 + AZ_2 o BZ_1 - u_1CZ_2 - u_2CZ_1
+/// T is the cross term that pops up when taking linear combinations with naive r1cs, only the matrices are needed.
+/// An error if z_1 or z_2 doesn't have as many elements as the matrices have columns
+pub fn compute_t<F: PrimeField, M: MatrixOps<F>>(
+    a: &M,
+    b: &M,
+    c: &M,
+    u_1: F,
+    z_1: &Vector<F>,
+    u_2: F,
+    z_2: &Vector<F>,
+) -> Result<Vector<F>, AlgorithmsError> {
+    let cross = ((a.dot_vector(z_1)? * b.dot_vector(z_2)?)?
+        + (a.dot_vector(z_2)? * b.dot_vector(z_1)?)?)?;
+    (cross - c.dot_vector(z_2)?.scalar_mul(&u_1))? - c.dot_vector(z_1)?.scalar_mul(&u_2)
+}
+
+/// The structure of a relaxed R1CS equation, AZ o BZ = u * CZ + E, shared by all its instances
+/// Backed by dense matrices unless M is e.g. a `SparseMatrix<F>`
+#[derive(Clone, Debug)]
+pub struct R1CSRelaxed<F: PrimeField, M: MatrixOps<F> = Matrix<F>> {
+    n_constraints: usize,
+    n_witness: usize,
+    n_instance: usize,
+    a: M,
+    b: M,
+    c: M,
+    _field: PhantomData<F>,
 }
 
-/// An instance to a relaxed R1CS equation
+/// An instance to a relaxed R1CS equation, x excluding u
+#[derive(Clone, Debug)]
 pub struct R1CSRelaxedInstance<F: PrimeField> {
-    pub e: R1CSRelaxedErrorTerm<F>,
-    pub u: F,
-    pub x: Vector<F>,
+    e: R1CSRelaxedErrorTerm<F>,
+    u: F,
+    x: Vector<F>,
 }
 
-impl<F: PrimeField> From<R1CS<F>> for R1CSRelaxed<F> {
-    fn from(value: R1CS<F>) -> Self {
+/// A witness to a relaxed R1CS equation
+#[derive(Clone, Debug)]
+pub struct R1CSRelaxedWitness<F: PrimeField> {
+    w: Vector<F>,
+}
+
+impl<F: PrimeField> R1CSRelaxedInstance<F> {
+    pub fn new(e: R1CSRelaxedErrorTerm<F>, u: F, x: Vector<F>) -> Self {
+        Self { e, u, x }
+    }
+
+    pub fn e(&self) -> &R1CSRelaxedErrorTerm<F> {
+        &self.e
+    }
+
+    pub fn u(&self) -> F {
+        self.u
+    }
+
+    pub fn x(&self) -> &Vector<F> {
+        &self.x
+    }
+}
+
+impl<F: PrimeField> R1CSRelaxedWitness<F> {
+    pub fn new(w: Vector<F>) -> Self {
+        Self { w }
+    }
+
+    pub fn w(&self) -> &Vector<F> {
+        &self.w
+    }
+}
+
+impl<F: PrimeField, M: MatrixOps<F>> From<R1CS<F, M>> for R1CSRelaxed<F, M> {
+    fn from(value: R1CS<F, M>) -> Self {
+        let (n_constraints, n_witness, n_instance) =
+            (value.n_constraints(), value.n_witness(), value.n_instance());
+        let (a, b, c) = value.into_matrices();
         Self {
-            n_constraints: value.n_constraints,
-            n_witness: value.n_witness,
-            n_instance: value.n_instance,
-            a: value.a,
-            b: value.b,
-            c: value.c,
-            e: Vector::new_zero_vector(value.n_constraints),
-            u: F::ONE,
+            n_constraints,
+            n_witness,
+            n_instance,
+            a,
+            b,
+            c,
+            _field: PhantomData,
         }
     }
 }
 
-impl<F: PrimeField> R1CSRelaxed<F> {
-    /// Creates a relaxed r1cs by providing all necessary r1cs components with error term and u
-    pub fn from_relaxed_r1cs(
-        a: Matrix<F>,
-        b: Matrix<F>,
-        c: Matrix<F>,
-        u: F,
-        e: R1CSRelaxedErrorTerm<F>,
-    ) -> Self {
+impl<F: PrimeField, M: MatrixOps<F>> R1CSRelaxed<F, M> {
+    /// `n_instance` counts u, the number of constraints is the number of rows of the matrices
+    pub fn new(n_witness: usize, n_instance: usize, a: M, b: M, c: M) -> Self {
         Self {
-            n_constraints: a.num_rows,
-            n_witness: a.num_cols,
-            n_instance: b.num_cols,
+            n_constraints: a.num_rows(),
+            n_witness,
+            n_instance,
             a,
             b,
             c,
-            e,
-            u,
+            _field: PhantomData,
         }
     }
 
-    /// Checks if the relaxed r1cs is satisfied
-    pub fn is_satisfied(&self, z: &R1CSRelaxedInstanceWitness<F>) -> bool {
-        let az = self.a.dot_vector(z);
-        let bz = self.b.dot_vector(z);
-        let cz = self.c.dot_vector(z);
-        ((az * bz) - cz.scalar_mul(&self.u).add(self.e.clone())).is_zero_vector()
+    pub fn n_constraints(&self) -> usize {
+        self.n_constraints
+    }
+
+    pub fn n_witness(&self) -> usize {
+        self.n_witness
+    }
+
+    pub fn n_instance(&self) -> usize {
+        self.n_instance
+    }
+
+    pub fn a(&self) -> &M {
+        &self.a
+    }
+
+    pub fn b(&self) -> &M {
+        &self.b
+    }
+
+    pub fn c(&self) -> &M {
+        &self.c
+    }
+
+    /// Splits z = (1, x, w), satisfying the r1cs, in an instance with u = 1 and E = 0, and its witness
+    pub fn relax(
+        &self,
+        z: &R1CSInstanceWitness<F>,
+    ) -> (R1CSRelaxedInstance<F>, R1CSRelaxedWitness<F>) {
+        let (io, w) = z.elements().split_at(self.n_instance);
+        (
+            R1CSRelaxedInstance::new(
+                Vector::new_zero_vector(self.n_constraints),
+                F::ONE,
+                Vector::new(&io[1..]),
+            ),
+            R1CSRelaxedWitness::new(Vector::new(w)),
+        )
+    }
+
+    /// z = (u, x, w)
+    pub fn z(
+        instance: &R1CSRelaxedInstance<F>,
+        witness: &R1CSRelaxedWitness<F>,
+    ) -> R1CSRelaxedInstanceWitness<F> {
+        Vector::new(&[&[instance.u], instance.x.elements(), witness.w.elements()].concat())
+    }
+
+    /// Checks if the relaxed r1cs is satisfied by the instance and witness
+    pub fn is_satisfied(
+        &self,
+        instance: &R1CSRelaxedInstance<F>,
+        witness: &R1CSRelaxedWitness<F>,
+    ) -> bool {
+        if instance.x.size() + 1 != self.n_instance
+            || witness.w.size() != self.n_witness
+            || instance.e.size() != self.n_constraints
+        {
+            return false;
+        }
+        let z = Self::z(instance, witness);
+        let (Ok(az), Ok(bz), Ok(cz)) = (
+            self.a.dot_vector(&z),
+            self.b.dot_vector(&z),
+            self.c.dot_vector(&z),
+        ) else {
+            return false;
+        };
+        let (Ok(azbz), Ok(ucz_e)) = (az * bz, cz.scalar_mul(&instance.u) + instance.e.clone())
+        else {
+            return false;
+        };
+        (azbz - ucz_e).is_ok_and(|v| v.is_zero_vector())
     }
 
     /// Computes the T term, where:
     /// T = AZ_1 o BZ_2 + AZ_2 o BZ_1 - u_1CZ_2 - u_2CZ_1
-    /// It is required for computing the error vector
-    /// T is the cross term that pops up when taking linear combinations with naive r1cs
+    /// It is required for computing the error vector, see the free `compute_t` for assignments without a struct
     pub fn compute_t(
         &self,
-        rhs: &R1CSRelaxed<F>,
-        z1: &R1CSRelaxedInstanceWitness<F>,
-        z2: &R1CSRelaxedInstanceWitness<F>,
-    ) -> Vector<F> {
-        let (u1, u2) = (self.u, rhs.u);
-        (self.a.dot_vector(z1)) * (self.b.dot_vector(z2))
-            + (self.a.dot_vector(z2)) * (self.b.dot_vector(z1))
-            - (self.c.dot_vector(z2)).scalar_mul(&u1)
-            - (self.c.dot_vector(z1)).scalar_mul(&u2)
-    }
-
-    /// Computes the u term, where:
-    /// u = u_1 + r * u_2
-    /// This linear combination will be the "new" u for our updated relaxed r1cs
-    pub fn compute_u(&self, rhs: &R1CSRelaxed<F>, r: &F) -> F {
-        self.u + rhs.u * r
-    }
-
-    /// Computes the E term, where:
-    /// E = E_1 + r * T + r^2 * E_2
-    /// This linear combination will be the "new" E for our updated relaxed r1cs
-    pub fn compute_e(
-        &self,
-        rhs: &R1CSRelaxed<F>,
-        r: &F,
-        z1: &R1CSRelaxedInstanceWitness<F>,
-        z2: &R1CSRelaxedInstanceWitness<F>,
-    ) -> Vector<F> {
-        let e1 = &self.e;
-        let e2 = &rhs.e;
-        let r_square = r.square();
-        let t = &self.compute_t(rhs, z1, z2);
-        (t.scalar_mul(r) + e1.clone()) + e2.scalar_mul(&r_square)
-    }
-
-    /// Computes the Z term, where:
-    /// Z = Z_1 + r * Z_2
-    /// This linear combination will be the "new" Z for our updated relaxed r1cs
-    pub fn compute_z(
+        instance_1: &R1CSRelaxedInstance<F>,
+        witness_1: &R1CSRelaxedWitness<F>,
+        instance_2: &R1CSRelaxedInstance<F>,
+        witness_2: &R1CSRelaxedWitness<F>,
+    ) -> Result<Vector<F>, AlgorithmsError> {
+        compute_t(
+            &self.a,
+            &self.b,
+            &self.c,
+            instance_1.u,
+            &Self::z(instance_1, witness_1),
+            instance_2.u,
+            &Self::z(instance_2, witness_2),
+        )
+    }
+
+    /// Folds two instance-witness pairs with r, where:
+    /// E = E_1 + r * T + r^2 * E_2, u = u_1 + r * u_2, x = x_1 + r * x_2 and W = W_1 + r * W_2
+    /// The folded pair satisfies the relaxed r1cs if both pairs do, an error if a pair doesn't fit the structure
+    pub fn fold(
         &self,
+        instance_1: &R1CSRelaxedInstance<F>,
+        witness_1: &R1CSRelaxedWitness<F>,
+        instance_2: &R1CSRelaxedInstance<F>,
+        witness_2: &R1CSRelaxedWitness<F>,
         r: &F,
-        z1: &R1CSRelaxedInstanceWitness<F>,
-        z2: &R1CSRelaxedInstanceWitness<F>,
-    ) -> R1CSRelaxedInstanceWitness<F> {
-        z1.clone() + z2.scalar_mul(r)
+    ) -> Result<(R1CSRelaxedInstance<F>, R1CSRelaxedWitness<F>), AlgorithmsError> {
+        let t = self.compute_t(instance_1, witness_1, instance_2, witness_2)?;
+        let e = ((t.scalar_mul(r) + instance_1.e.clone())? + instance_2.e.scalar_mul(&r.square()))?;
+        let instance = R1CSRelaxedInstance::new(
+            e,
+            instance_1.u + instance_2.u * r,
+            (instance_1.x.clone() + instance_2.x.scalar_mul(r))?,
+        );
+        let witness = R1CSRelaxedWitness::new((witness_1.w.clone() + witness_2.w.scalar_mul(r))?);
+        Ok((instance, witness))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use ark_bn254::Fr;
+    use ark_ff::{One, UniformRand};
     use ark_std::test_rng;
 
-    use crate::circuits::{
-        r1cs::{
-            utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit},
-            R1CS,
-        },
-        relaxed_r1cs::R1CSRelaxedInstanceWitness,
+    use crate::circuits::r1cs::{
+        utils::{get_r1cs_from_cs, get_sparse_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit},
+        R1CS,
     };
 
-    use super::R1CSRelaxed;
-    use ark_ff::UniformRand;
+    use super::{R1CSRelaxed, R1CSRelaxedInstance};
 
     #[test]
     pub fn test_valid_rlc_of_two_relaxed_r1cs() {
         let circuit = TestPythagoreCircuit::new(Fr::from(2), Fr::from(3), Fr::from(13));
         let r1cs: R1CS<Fr> = get_r1cs_from_cs(circuit.clone()).unwrap();
-        let relaxed_r1cs_1 = R1CSRelaxed::from(r1cs.clone());
-        let z_1: R1CSRelaxedInstanceWitness<Fr> = get_z_from_cs(circuit.clone()).unwrap();
-        assert!(relaxed_r1cs_1.is_satisfied(&z_1));
+        // a single structure for all the instances
+        let relaxed_r1cs = R1CSRelaxed::from(r1cs);
+        let (instance_1, witness_1) = relaxed_r1cs.relax(&get_z_from_cs(circuit).unwrap());
+        assert!(relaxed_r1cs.is_satisfied(&instance_1, &witness_1));
 
         let circuit = TestPythagoreCircuit::new(Fr::from(5), Fr::from(10), Fr::from(125));
-        let relaxed_r1cs_2 = R1CSRelaxed::from(r1cs.clone()); // same relaxed r1cs as before
-        let z_2: R1CSRelaxedInstanceWitness<Fr> = get_z_from_cs(circuit.clone()).unwrap();
-        assert!(relaxed_r1cs_2.is_satisfied(&z_2));
+        let (instance_2, witness_2) = relaxed_r1cs.relax(&get_z_from_cs(circuit.clone()).unwrap());
+        assert!(relaxed_r1cs.is_satisfied(&instance_2, &witness_2));
 
         let mut rng = test_rng();
         let r = Fr::rand(&mut rng);
 
-        // Compute the new relaxed r1cs
-        let e_3 = relaxed_r1cs_1.compute_e(&relaxed_r1cs_2, &r, &z_1, &z_2);
-        let u_3 = relaxed_r1cs_1.compute_u(&relaxed_r1cs_2, &r);
-        let relaxed_r1cs_3 = R1CSRelaxed::from_relaxed_r1cs(r1cs.a, r1cs.b, r1cs.c, u_3, e_3);
+        // Compute the folded instance and its satisfying witness
+        let (instance_3, witness_3) = relaxed_r1cs
+            .fold(&instance_1, &witness_1, &instance_2, &witness_2, &r)
+            .unwrap();
+        assert!(relaxed_r1cs.is_satisfied(&instance_3, &witness_3));
+        // the same instances work with a sparse structure
+        let sparse_relaxed_r1cs = R1CSRelaxed::from(get_sparse_r1cs_from_cs(circuit).unwrap());
+        assert!(sparse_relaxed_r1cs.is_satisfied(&instance_3, &witness_3));
+
+        // folding again, with a non-trivial E and u on both sides
+        let (instance_4, witness_4) = relaxed_r1cs
+            .fold(&instance_3, &witness_3, &instance_3, &witness_3, &r)
+            .unwrap();
+        assert!(relaxed_r1cs.is_satisfied(&instance_4, &witness_4));
+        assert!(!relaxed_r1cs.is_satisfied(&instance_4, &witness_3));
 
-        // Compute satisfying instance-witness for the new relaxed r1cs
-        let z_3 = relaxed_r1cs_1.compute_z(&r, &z_1, &z_2);
-        assert!(relaxed_r1cs_3.is_satisfied(&z_3));
+        // an instance with a wrong u isn't satisfied by the witness
+        let wrong = R1CSRelaxedInstance::new(
+            instance_3.e().clone(),
+            instance_3.u() + Fr::one(),
+            instance_3.x().clone(),
+        );
+        assert!(!relaxed_r1cs.is_satisfied(&wrong, &witness_3));
     }
 }
@@ -0,0 +1,65 @@
+// BLAKE2s-256 and BLAKE2b, unkeyed: https://www.rfc-editor.org/rfc/rfc7693
+// Thin wrappers over the `blake2` crate, returning fixed size digests. BLAKE2b takes its digest length
+// as a parameter, which is hashed in: a 32 bytes digest isn't a truncated 64 bytes one.
+use blake2::digest::{Update, VariableOutput};
+use blake2::{Blake2bVar, Blake2s256, Digest};
+
+/// BLAKE2b with an N bytes digest, 1 <= N <= 64, and no key
+pub fn blake2b<const N: usize>(data: &[u8]) -> [u8; N] {
+    assert!((1..=64).contains(&N), "blake2b digests have 1 to 64 bytes");
+    let mut hasher = Blake2bVar::new(N).unwrap();
+    hasher.update(data);
+    let mut digest = [0u8; N];
+    hasher.finalize_variable(&mut digest).unwrap();
+    digest
+}
+
+/// BLAKE2s with a 32 bytes digest and no key
+pub fn blake2s(data: &[u8]) -> [u8; 32] {
+    Blake2s256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blake2b, blake2s};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    pub fn test_blake2s() {
+        // rfc 7693 appendix B, and the empty message
+        assert_eq!(
+            hex(&blake2s(b"abc")),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+        assert_eq!(
+            hex(&blake2s(b"")),
+            "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"
+        );
+        // several blocks, the last one full
+        let data = (0..128).map(|i| i as u8).collect::<Vec<_>>();
+        assert_ne!(blake2s(&data), blake2s(&data[..127]));
+    }
+
+    #[test]
+    pub fn test_blake2b() {
+        // rfc 7693 appendix A, and 32 bytes digests
+        assert_eq!(
+            hex(&blake2b::<64>(b"abc")),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            hex(&blake2b::<32>(b"")),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        // the digest length is hashed in, 32 bytes digests aren't truncated 64 bytes ones
+        let data = (0..256).map(|i| i as u8).collect::<Vec<_>>();
+        assert_eq!(
+            hex(&blake2b::<32>(&data)),
+            "39a7eb9fedc19aabc83425c6755dd90e6f9d0c804964a1f4aaeea3b9fb599835"
+        );
+        assert_ne!(blake2b::<32>(&data)[..], blake2b::<64>(&data)[..32]);
+    }
+}
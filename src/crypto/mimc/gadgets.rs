@@ -0,0 +1,69 @@
+// In-circuit MiMC, the parameters being constants: a round is an S-box, ~log2(alpha) multiplication constraints.
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+use ark_relations::r1cs::SynthesisError;
+
+use super::MiMCParameters;
+
+/// E_key(x), as `MiMCParameters::encrypt`
+pub fn encrypt_gadget<F: PrimeField>(
+    parameters: &MiMCParameters<F>,
+    x: &FpVar<F>,
+    key: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut x = x.clone();
+    for c in parameters.round_constants() {
+        x = (x + key + *c).pow_by_constant([parameters.alpha()])?;
+    }
+    Ok(x + key)
+}
+
+/// The keyless permutation E_0
+pub fn permute_gadget<F: PrimeField>(
+    parameters: &MiMCParameters<F>,
+    x: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    encrypt_gadget(parameters, x, &FpVar::zero())
+}
+
+/// Hashes `inputs` into a single field element, as `MiMCParameters::hash`
+pub fn hash_gadget<F: PrimeField>(
+    parameters: &MiMCParameters<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut h = FpVar::constant(F::from(inputs.len() as u64));
+    for x in inputs {
+        h = encrypt_gadget(parameters, x, &h)? + &h + x;
+    }
+    Ok(h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encrypt_gadget, hash_gadget, permute_gadget};
+    use crate::crypto::mimc::MiMCParameters;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_mimc_gadget() {
+        let mut rng = test_rng();
+        let parameters = MiMCParameters::<Fr>::new();
+        let inputs = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone())).unwrap();
+        let encrypted = encrypt_gadget(&parameters, &inputs_var[0], &inputs_var[1]).unwrap();
+        assert_eq!(
+            encrypted.value().unwrap(),
+            parameters.encrypt(inputs[0], inputs[1])
+        );
+        let permuted = permute_gadget(&parameters, &inputs_var[2]).unwrap();
+        assert_eq!(permuted.value().unwrap(), parameters.permute(inputs[2]));
+        let hash = hash_gadget(&parameters, &inputs_var).unwrap();
+        assert_eq!(hash.value().unwrap(), parameters.hash(&inputs));
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,113 @@
+// MiMC: https://eprint.iacr.org/2016/492
+// The block cipher E_k(x) iterates x -> (x + k + c_i)^alpha over n rounds and adds the key to the result,
+// the permutation being E_0. With the S-box the only non linear operation, a round costs ~log2(alpha)
+// multiplication constraints, and n = ceil(log_alpha(p)) rounds reach the maximal algebraic degree.
+// alpha is poseidon's, the smallest prime such that x^alpha is a permutation of F. The round constants are
+// derived with keccak, as poseidon's: the parameters don't match any reference implementation.
+// Hashing is Miyaguchi-Preneel: h_{i+1} = E_{h_i}(x_i) + h_i + x_i, h_0 being the number of inputs.
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+
+use super::poseidon::smallest_valid_alpha;
+
+pub mod gadgets;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MiMCParameters<F: PrimeField> {
+    alpha: u64,
+    round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> MiMCParameters<F> {
+    /// Generates the parameters with ceil(log_alpha(p)) rounds, e.g. 110 rounds over bn254 with alpha = 5
+    pub fn new() -> Self {
+        let alpha = smallest_valid_alpha::<F>();
+        let n_rounds = (F::MODULUS_BIT_SIZE as f64 / (alpha as f64).log2()).ceil() as usize;
+        let mut seed = b"mimc".to_vec();
+        (n_rounds as u64).serialize_compressed(&mut seed).unwrap();
+        seed.extend(F::MODULUS.to_bytes_le());
+        let round_constants = (0..n_rounds as u64)
+            .map(|round| {
+                let mut bytes = seed.clone();
+                round.serialize_compressed(&mut bytes).unwrap();
+                F::from_le_bytes_mod_order(&Keccak256::digest(&bytes))
+            })
+            .collect();
+        Self {
+            alpha,
+            round_constants,
+        }
+    }
+
+    pub fn alpha(&self) -> u64 {
+        self.alpha
+    }
+
+    pub fn round_constants(&self) -> &[F] {
+        &self.round_constants
+    }
+
+    /// E_key(x)
+    pub fn encrypt(&self, x: F, key: F) -> F {
+        self.round_constants
+            .iter()
+            .fold(x, |x, c| (x + key + c).pow([self.alpha]))
+            + key
+    }
+
+    /// The keyless permutation E_0
+    pub fn permute(&self, x: F) -> F {
+        self.encrypt(x, F::ZERO)
+    }
+
+    /// Hashes `inputs` into a single field element
+    pub fn hash(&self, inputs: &[F]) -> F {
+        inputs.iter().fold(F::from(inputs.len() as u64), |h, x| {
+            self.encrypt(*x, h) + h + x
+        })
+    }
+}
+
+impl<F: PrimeField> Default for MiMCParameters<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MiMCParameters;
+    use ark_bn254::Fr;
+    use std::str::FromStr;
+
+    #[test]
+    pub fn test_mimc() {
+        let parameters = MiMCParameters::<Fr>::new();
+        assert_eq!(parameters.alpha(), 5);
+        assert_eq!(parameters.round_constants().len(), 110);
+
+        // test vectors, from an independent implementation of the above
+        let vectors = [
+            (
+                parameters.permute(Fr::from(0u64)),
+                "16795619478597469323107343436537497059664067674198471750687236455577937145619",
+            ),
+            (
+                parameters.encrypt(Fr::from(1u64), Fr::from(2u64)),
+                "20883643532253902195786685393073339582755867189381795557117406691111387130714",
+            ),
+            (
+                parameters.hash(&[Fr::from(1u64), Fr::from(2u64)]),
+                "20959172313523921070356582858025379267484368426170354381525165417600376166934",
+            ),
+        ];
+        for (value, expected) in vectors {
+            assert_eq!(value, Fr::from_str(expected).unwrap());
+        }
+        assert_ne!(
+            parameters.hash(&[Fr::from(1u64)]),
+            parameters.hash(&[Fr::from(1u64), Fr::from(0u64)])
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod blake2;
+pub mod mimc;
+pub mod poseidon;
+pub mod rescue;
@@ -0,0 +1,135 @@
+// In-circuit poseidon: the permutation and the sponge of the parent module over `FpVar`s,
+// with the parameters as constants. A full round costs width S-boxes, a partial round a single one,
+// each S-box x^alpha costing ~log2(alpha) multiplication constraints. Additions and the MDS are free.
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+use ark_relations::r1cs::SynthesisError;
+
+use super::PoseidonParameters;
+
+/// Applies the poseidon permutation to `state`
+pub fn permute_gadget<F: PrimeField>(
+    parameters: &PoseidonParameters<F>,
+    state: &mut [FpVar<F>],
+) -> Result<(), SynthesisError> {
+    assert_eq!(state.len(), parameters.width());
+    for (round, constants) in parameters.round_constants().iter().enumerate() {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s += *c;
+        }
+        let n_sbox = if parameters.is_full_round(round) {
+            parameters.width()
+        } else {
+            1
+        };
+        for s in state[..n_sbox].iter_mut() {
+            *s = s.pow_by_constant([parameters.alpha()])?;
+        }
+        let mixed = parameters
+            .mds()
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(FpVar::zero(), |acc, (m, s)| acc + s * *m)
+            })
+            .collect::<Vec<_>>();
+        state.clone_from_slice(&mixed);
+    }
+    Ok(())
+}
+
+/// The sponge of `PoseidonSponge`, in a circuit
+#[derive(Clone, Debug)]
+pub struct PoseidonSpongeVar<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+    state: Vec<FpVar<F>>,
+    position: usize,
+    squeezing: bool,
+}
+
+impl<F: PrimeField> PoseidonSpongeVar<F> {
+    pub fn new(parameters: &PoseidonParameters<F>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            state: vec![FpVar::zero(); parameters.width()],
+            position: 0,
+            squeezing: false,
+        }
+    }
+
+    pub fn absorb(&mut self, elements: &[FpVar<F>]) -> Result<(), SynthesisError> {
+        if self.squeezing {
+            self.squeezing = false;
+            self.position = 0;
+        }
+        for element in elements {
+            if self.position == self.parameters.rate() {
+                permute_gadget(&self.parameters, &mut self.state)?;
+                self.position = 0;
+            }
+            self.state[1 + self.position] += element;
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    pub fn squeeze(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        if !self.squeezing {
+            permute_gadget(&self.parameters, &mut self.state)?;
+            self.squeezing = true;
+            self.position = 0;
+        }
+        let mut output = vec![];
+        for _ in 0..n {
+            if self.position == self.parameters.rate() {
+                permute_gadget(&self.parameters, &mut self.state)?;
+                self.position = 0;
+            }
+            output.push(self.state[1 + self.position].clone());
+            self.position += 1;
+        }
+        Ok(output)
+    }
+}
+
+/// Hashes `inputs` into a single field element, as `PoseidonParameters::hash`
+pub fn hash_gadget<F: PrimeField>(
+    parameters: &PoseidonParameters<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(parameters);
+    sponge.state[0] = FpVar::constant(F::from(inputs.len() as u64));
+    sponge.absorb(inputs)?;
+    Ok(sponge.squeeze(1)?.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_gadget, PoseidonSpongeVar};
+    use crate::crypto::poseidon::{PoseidonParameters, PoseidonSponge};
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_poseidon_gadget() {
+        let mut rng = test_rng();
+        let parameters = PoseidonParameters::<Fr>::new(3, 8, 57).unwrap();
+        let inputs = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone())).unwrap();
+        let hash = hash_gadget(&parameters, &inputs_var).unwrap();
+        assert_eq!(hash.value().unwrap(), parameters.hash(&inputs));
+
+        let mut sponge = PoseidonSponge::new(&parameters);
+        let mut sponge_var = PoseidonSpongeVar::new(&parameters);
+        sponge.absorb(&inputs);
+        sponge_var.absorb(&inputs_var).unwrap();
+        let squeezed = sponge_var.squeeze(3).unwrap();
+        assert_eq!(squeezed.value().unwrap(), sponge.squeeze(3));
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
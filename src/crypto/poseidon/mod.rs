@@ -0,0 +1,510 @@
+// Poseidon: https://eprint.iacr.org/2019/458
+// The permutation runs over a state of t field elements, R_F / 2 full rounds, R_P partial rounds then R_F / 2
+// full rounds. Each round adds round constants, applies the S-box x -> x^alpha (to the whole state in full rounds,
+// to its first element in partial rounds) and multiplies the state by an MDS matrix.
+// alpha is the smallest prime such that gcd(alpha, p - 1) = 1, which makes the S-box a permutation of F.
+// The parameters are generated as the reference implementation does (https://extgit.iaik.tugraz.at/krypto/hadeshash):
+// - R_F and R_P are the cheapest round numbers resisting the statistical, interpolation and groebner basis attacks
+//   of section 5.5 and https://eprint.iacr.org/2023/537 at the given security level, plus the paper's margin
+//   (R_F + 2, R_P * 1.075), as calc_round_numbers.py finds them,
+// - the round constants, then the MDS matrix come from the grain LFSR seeded with the field, the S-box, n = log2(p),
+//   t, R_F and R_P (generate_parameters_grain.sage). The MDS matrix is the cauchy matrix M_ij = 1 / (x_i + y_j),
+//   the x_i and y_j being 2t distinct sampled elements. The reference additionally rejects matrices with invariant
+//   subspaces (its algorithms 1 to 3), which this doesn't check.
+// Over bn254 with t = 3, R_F = 8 and R_P = 57 this is the reference's x5_254_3 instance, also circomlib's, whose
+// round numbers predate the script's current bounds: those give R_P = 56 at 128 bits.
+// The sponge keeps the capacity in the first element of the state and absorbs into the `rate` others.
+use ark_ff::{BigInteger, PrimeField};
+
+use crate::error::AlgorithmsError;
+
+pub mod gadgets;
+
+/// Cauchy matrices sampled before giving up, over fields too small to have one
+const MDS_ATTEMPTS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoseidonParameters<F: PrimeField> {
+    width: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    /// one vector of `width` constants per round
+    round_constants: Vec<Vec<F>>,
+    mds: Vec<Vec<F>>,
+}
+
+/// The smallest prime alpha >= 3 that doesn't divide p - 1
+pub(crate) fn smallest_valid_alpha<F: PrimeField>() -> u64 {
+    let p_minus_one = (-F::ONE).into_bigint();
+    let is_prime = |a: u64| {
+        (2..a)
+            .take_while(|d| d * d <= a)
+            .all(|d| !a.is_multiple_of(d))
+    };
+    (3..)
+        .filter(|a| is_prime(*a))
+        .find(|a| {
+            // (p - 1) mod a, from the most significant limb
+            let remainder = p_minus_one
+                .as_ref()
+                .iter()
+                .rev()
+                .fold(0u128, |r, limb| ((r << 64) + *limb as u128) % *a as u128);
+            remainder != 0
+        })
+        .unwrap()
+}
+
+/// Whether R_F full and R_P partial rounds resist the known attacks with `security_level` bits
+fn is_secure(log_p: f64, n: f64, t: f64, alpha: f64, m: f64, r_f: usize, r_p: usize) -> bool {
+    let (r_f, r_p) = (r_f as f64, r_p as f64);
+    let log_alpha = |x: f64| x.ln() / alpha.ln();
+    let statistical = if m <= (log_p - (alpha - 1.0) / 2.0).floor() * (t + 1.0) {
+        6.0
+    } else {
+        10.0
+    };
+    let interpolation = 1.0 + (log_alpha(2.0) * m.min(n)).ceil() + log_alpha(t).ceil() - r_p;
+    let groebner_1 = log_alpha(2.0) * m.min(log_p) - r_p;
+    let groebner_2 = t - 1.0 + log_alpha(2.0) * (m / (t + 1.0)).min(log_p / 2.0) - r_p;
+    let groebner_3 = (t - 2.0 + m / (2.0 * alpha.log2()) - r_p) / (t - 1.0);
+    let r_f_max = [
+        statistical,
+        interpolation,
+        groebner_1,
+        groebner_2,
+        groebner_3,
+    ]
+    .iter()
+    .map(|r| r.ceil())
+    .fold(f64::MIN, f64::max);
+    if r_f < r_f_max {
+        return false;
+    }
+    // https://eprint.iacr.org/2023/537: log2 of binomial(over, under) bounds the cost of a groebner basis attack
+    let r = (t / 3.0).floor();
+    let over = (r_f - 1.0) * t + r_p + r + r * (r_f / 2.0) + r_p + alpha;
+    let under = r * (r_f / 2.0) + r_p + alpha;
+    let log_binomial = (1..=under as u64)
+        .map(|i| ((over - under + i as f64) / i as f64).log2())
+        .sum::<f64>();
+    (2.0 * log_binomial).ceil() >= m
+}
+
+/// The round numbers (R_F, R_P) of the cheapest secure instance with margins, minimizing the number of S-boxes
+/// t * R_F + R_P, for a state of `width` elements over F with the S-box x -> x^alpha
+pub fn round_numbers<F: PrimeField>(
+    width: usize,
+    alpha: u64,
+    security_level: usize,
+) -> Result<(usize, usize), AlgorithmsError> {
+    let log_p = F::MODULUS
+        .as_ref()
+        .iter()
+        .rev()
+        .fold(0f64, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+        .log2();
+    let n = F::MODULUS_BIT_SIZE as f64;
+    let (t, alpha, m) = (width as f64, alpha as f64, security_level as f64);
+    let mut best: Option<(usize, usize)> = None;
+    let cost = |(r_f, r_p): (usize, usize)| width * r_f + r_p;
+    for r_p in 1..500 {
+        // as in the reference script, the margin applied to R_P carries over to the next R_F of the loop
+        let mut r_p = r_p;
+        for r_f in (4..100).step_by(2) {
+            if !is_secure(log_p, n, t, alpha, m, r_f, r_p) {
+                continue;
+            }
+            r_p = (r_p as f64 * 1.075).ceil() as usize;
+            let candidate = (r_f + 2, r_p);
+            let is_better = best.is_none_or(|best| {
+                cost(candidate) < cost(best)
+                    || (cost(candidate) == cost(best) && candidate.0 < best.0)
+            });
+            if is_better {
+                best = Some(candidate);
+            }
+        }
+    }
+    best.ok_or_else(|| {
+        AlgorithmsError::InvalidParameters(format!(
+            "no round numbers reach {security_level} bits of security"
+        ))
+    })
+}
+
+/// The grain LFSR of the reference implementation, over 80 bits
+struct Grain {
+    state: [bool; 80],
+    /// index of the oldest bit in `state`
+    head: usize,
+}
+
+impl Grain {
+    /// Seeds the LFSR with the field type (1 for prime fields), the S-box (0 for x^alpha), n, t, R_F and R_P,
+    /// on 2, 4, 12, 12, 10 and 10 bits, then 30 ones, and discards its first 160 bits
+    fn new(n: usize, width: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        let mut state = [true; 80];
+        let fields = [
+            (1, 2),
+            (0, 4),
+            (n, 12),
+            (width, 12),
+            (full_rounds, 10),
+            (partial_rounds, 10),
+        ];
+        let mut i = 0;
+        for (value, size) in fields {
+            for bit in (0..size).rev() {
+                state[i] = (value >> bit) & 1 == 1;
+                i += 1;
+            }
+        }
+        let mut grain = Self { state, head: 0 };
+        for _ in 0..160 {
+            grain.next_bit();
+        }
+        grain
+    }
+
+    /// b_80 = b_62 + b_51 + b_38 + b_23 + b_13 + b_0
+    fn next_bit(&mut self) -> bool {
+        let bit = |i: usize| self.state[(self.head + i) % 80];
+        let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+        self.state[self.head] = new_bit;
+        self.head = (self.head + 1) % 80;
+        new_bit
+    }
+
+    /// Outputs the second bit of each pair whose first bit is 1
+    fn filtered_bit(&mut self) -> bool {
+        while !self.next_bit() {
+            self.next_bit();
+        }
+        self.next_bit()
+    }
+
+    /// An n bits integer, most significant bit first
+    fn bigint<F: PrimeField>(&mut self) -> F::BigInt {
+        let bits = (0..F::MODULUS_BIT_SIZE)
+            .map(|_| self.filtered_bit())
+            .collect::<Vec<_>>();
+        F::BigInt::from_bits_be(&bits)
+    }
+
+    /// A uniform field element, sampled n bits at a time until one is below p
+    fn field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            if let Some(element) = F::from_bigint(self.bigint::<F>()) {
+                return element;
+            }
+        }
+    }
+
+    /// An n bits integer reduced modulo p
+    fn reduced_field_element<F: PrimeField>(&mut self) -> F {
+        F::from_le_bytes_mod_order(&self.bigint::<F>().to_bytes_le())
+    }
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+    /// Generates the parameters for a state of `width` elements, with the round numbers reaching
+    /// `security_level` bits, e.g. 8 full and 56 partial rounds for width 3 and 128 bits over bn254
+    pub fn with_security_level(
+        width: usize,
+        security_level: usize,
+    ) -> Result<Self, AlgorithmsError> {
+        let (full_rounds, partial_rounds) =
+            round_numbers::<F>(width, smallest_valid_alpha::<F>(), security_level)?;
+        Self::new(width, full_rounds, partial_rounds)
+    }
+
+    /// Generates the parameters for a state of `width` elements, `full_rounds` being even, with the round
+    /// constants and the MDS matrix of the reference implementation
+    pub fn new(
+        width: usize,
+        full_rounds: usize,
+        partial_rounds: usize,
+    ) -> Result<Self, AlgorithmsError> {
+        if width < 2 {
+            return Err(AlgorithmsError::InvalidParameters(
+                "the state holds at least one capacity and one rate element".to_string(),
+            ));
+        }
+        if !full_rounds.is_multiple_of(2) {
+            return Err(AlgorithmsError::InvalidParameters(
+                "the full rounds are split before and after the partial rounds".to_string(),
+            ));
+        }
+        let alpha = smallest_valid_alpha::<F>();
+
+        let mut grain = Grain::new(
+            F::MODULUS_BIT_SIZE as usize,
+            width,
+            full_rounds,
+            partial_rounds,
+        );
+        let round_constants = (0..full_rounds + partial_rounds)
+            .map(|_| (0..width).map(|_| grain.field_element()).collect())
+            .collect();
+        let mds = Self::cauchy_matrix(&mut grain, width)?;
+        Ok(Self {
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        })
+    }
+
+    /// M_ij = 1 / (x_i + y_j), resampling the x_i, y_j until they are distinct and no x_i + y_j is zero
+    fn cauchy_matrix(grain: &mut Grain, width: usize) -> Result<Vec<Vec<F>>, AlgorithmsError> {
+        for _ in 0..MDS_ATTEMPTS {
+            let elements = (0..2 * width)
+                .map(|_| grain.reduced_field_element::<F>())
+                .collect::<Vec<_>>();
+            let distinct = elements
+                .iter()
+                .enumerate()
+                .all(|(i, e)| !elements[..i].contains(e));
+            if !distinct {
+                continue;
+            }
+            let (xs, ys) = elements.split_at(width);
+            let mds = xs
+                .iter()
+                .map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+                .collect::<Option<Vec<Vec<F>>>>();
+            if let Some(mds) = mds {
+                return Ok(mds);
+            }
+        }
+        Err(AlgorithmsError::InvalidParameters(format!(
+            "no {width} x {width} cauchy matrix found over the field"
+        )))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn rate(&self) -> usize {
+        self.width - 1
+    }
+
+    pub fn full_rounds(&self) -> usize {
+        self.full_rounds
+    }
+
+    pub fn partial_rounds(&self) -> usize {
+        self.partial_rounds
+    }
+
+    pub fn alpha(&self) -> u64 {
+        self.alpha
+    }
+
+    pub fn round_constants(&self) -> &[Vec<F>] {
+        &self.round_constants
+    }
+
+    pub fn mds(&self) -> &[Vec<F>] {
+        &self.mds
+    }
+
+    /// Whether `round` applies the S-box to the whole state
+    pub fn is_full_round(&self, round: usize) -> bool {
+        round < self.full_rounds / 2 || round >= self.full_rounds / 2 + self.partial_rounds
+    }
+
+    /// Applies the poseidon permutation to `state`
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants) {
+                *s += c;
+            }
+            let n_sbox = if self.is_full_round(round) {
+                self.width
+            } else {
+                1
+            };
+            for s in state[..n_sbox].iter_mut() {
+                *s = s.pow([self.alpha]);
+            }
+            let mixed = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum())
+                .collect::<Vec<F>>();
+            state.copy_from_slice(&mixed);
+        }
+    }
+
+    /// Hashes `inputs` into a single field element
+    /// The capacity starts at the number of inputs, so that inputs padded with zeros hash differently
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut sponge = PoseidonSponge::new(self);
+        sponge.state[0] = F::from(inputs.len() as u64);
+        sponge.absorb(inputs);
+        sponge.squeeze(1)[0]
+    }
+}
+
+/// A duplex sponge over the poseidon permutation
+#[derive(Clone, Debug)]
+pub struct PoseidonSponge<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+    state: Vec<F>,
+    /// next rate element to absorb into or squeeze from
+    position: usize,
+    squeezing: bool,
+}
+
+impl<F: PrimeField> PoseidonSponge<F> {
+    pub fn new(parameters: &PoseidonParameters<F>) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            state: vec![F::zero(); parameters.width],
+            position: 0,
+            squeezing: false,
+        }
+    }
+
+    pub fn absorb(&mut self, elements: &[F]) {
+        if self.squeezing {
+            self.squeezing = false;
+            self.position = 0;
+        }
+        for element in elements {
+            if self.position == self.parameters.rate() {
+                self.parameters.permute(&mut self.state);
+                self.position = 0;
+            }
+            self.state[1 + self.position] += element;
+            self.position += 1;
+        }
+    }
+
+    pub fn squeeze(&mut self, n: usize) -> Vec<F> {
+        if !self.squeezing {
+            self.parameters.permute(&mut self.state);
+            self.squeezing = true;
+            self.position = 0;
+        }
+        let mut output = vec![];
+        for _ in 0..n {
+            if self.position == self.parameters.rate() {
+                self.parameters.permute(&mut self.state);
+                self.position = 0;
+            }
+            output.push(self.state[1 + self.position]);
+            self.position += 1;
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+// ark's `MontConfig` derive expands to impls inside a function
+#[allow(non_local_definitions)]
+mod tests {
+    use super::{round_numbers, PoseidonParameters, PoseidonSponge};
+    use crate::error::AlgorithmsError;
+    use crate::fields::goldilocks::Goldilocks;
+    use ark_bn254::Fr;
+    use ark_ff::{
+        fields::{Fp64, MontBackend, MontConfig},
+        MontFp,
+    };
+    use ark_std::{test_rng, UniformRand};
+
+    #[derive(MontConfig)]
+    #[modulus = "7"]
+    #[generator = "3"]
+    pub struct F7Config;
+    type F7 = Fp64<MontBackend<F7Config, 1>>;
+
+    #[test]
+    pub fn test_poseidon_reference_vectors() {
+        // the reference implementation's poseidonperm_x5_254_3, its first round constant being 0x0ee9a592...04cd8e6e
+        // and the permutation of [0, 1, 2] [0x115cc0f5...4417189a, 0x0fca49b7...b628ae29, 0x0e7ae82e...7045a30c]
+        let parameters = PoseidonParameters::<Fr>::new(3, 8, 57).unwrap();
+        assert_eq!(
+            parameters.round_constants()[0][0],
+            MontFp!("6745197990210204598374042828761989596302876299545964402857411729872131034734")
+        );
+        let mut state = [Fr::from(0u64), Fr::from(1u64), Fr::from(2u64)];
+        parameters.permute(&mut state);
+        assert_eq!(
+            state,
+            [
+                MontFp!(
+                    "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+                ),
+                MontFp!(
+                    "7142104613055408817911962100316808866448378443474503659992478482890339429929"
+                ),
+                MontFp!(
+                    "6549537674122432311777789598043107870002137484850126429160507761192163713804"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_poseidon_round_numbers() {
+        // 128 bits with alpha = 5 over bn254, alpha = 7 over goldilocks as in plonky2
+        assert_eq!(round_numbers::<Fr>(3, 5, 128), Ok((8, 56)));
+        assert_eq!(round_numbers::<Fr>(9, 5, 128), Ok((8, 57)));
+        assert_eq!(round_numbers::<Goldilocks>(12, 7, 128), Ok((8, 22)));
+        let parameters = PoseidonParameters::<Fr>::with_security_level(3, 128).unwrap();
+        assert_eq!(
+            (parameters.full_rounds(), parameters.partial_rounds()),
+            (8, 56)
+        );
+
+        // no cauchy matrix of size 4 over F_7, which lacks 8 distinct elements
+        assert!(matches!(
+            PoseidonParameters::<F7>::new(4, 8, 10),
+            Err(AlgorithmsError::InvalidParameters(_))
+        ));
+        assert!(PoseidonParameters::<Fr>::new(3, 7, 57).is_err());
+    }
+
+    #[test]
+    pub fn test_poseidon_sponge() {
+        let mut rng = test_rng();
+        let parameters = PoseidonParameters::<Fr>::new(3, 8, 57).unwrap();
+        // p - 1 is divisible by 3 for bn254
+        assert_eq!(parameters.alpha(), 5);
+        assert_eq!(parameters, PoseidonParameters::new(3, 8, 57).unwrap());
+        assert_ne!(parameters, PoseidonParameters::new(3, 8, 56).unwrap());
+
+        let inputs = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let hash = parameters.hash(&inputs);
+        assert_ne!(hash, parameters.hash(&inputs[..4]));
+        assert_ne!(hash, parameters.hash(&[inputs[1], inputs[0]]));
+        assert_ne!(
+            parameters.hash(&[inputs[0]]),
+            parameters.hash(&[inputs[0], Fr::from(0u64)])
+        );
+
+        // absorbing in several calls is the same as absorbing at once
+        let mut sponge = PoseidonSponge::new(&parameters);
+        sponge.absorb(&inputs[..3]);
+        sponge.absorb(&inputs[3..]);
+        let squeezed = sponge.squeeze(3);
+        // and so is squeezing
+        let mut other = PoseidonSponge::new(&parameters);
+        other.absorb(&inputs);
+        let mut other_squeezed = other.squeeze(1);
+        other_squeezed.extend(other.squeeze(2));
+        assert_eq!(squeezed, other_squeezed);
+        // absorbing after squeezing changes the next outputs
+        sponge.absorb(&[inputs[0]]);
+        assert_ne!(sponge.squeeze(1), other.squeeze(1));
+    }
+}
@@ -0,0 +1,106 @@
+// In-circuit rescue, the parameters being constants. The inverse S-box y = x^(1/alpha) is a witness, checked
+// with y^alpha = x: both S-boxes cost ~log2(alpha) multiplication constraints per state element.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+
+use super::RescueParameters;
+
+/// x^(1/alpha), constrained with (x^(1/alpha))^alpha = x
+fn inverse_sbox<F: PrimeField>(
+    parameters: &RescueParameters<F>,
+    x: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    if let FpVar::Constant(x) = x {
+        return Ok(FpVar::Constant(x.pow(parameters.alpha_inv())));
+    }
+    let y = FpVar::new_witness(x.cs(), || Ok(x.value()?.pow(parameters.alpha_inv())))?;
+    y.pow_by_constant([parameters.alpha()])?.enforce_equal(x)?;
+    Ok(y)
+}
+
+/// Applies the rescue permutation to `state`
+pub fn permute_gadget<F: PrimeField>(
+    parameters: &RescueParameters<F>,
+    state: &mut [FpVar<F>],
+) -> Result<(), SynthesisError> {
+    assert_eq!(state.len(), parameters.width());
+    for (half_round, constants) in parameters.round_constants().iter().enumerate() {
+        for s in state.iter_mut() {
+            *s = if half_round % 2 == 0 {
+                s.pow_by_constant([parameters.alpha()])?
+            } else {
+                inverse_sbox(parameters, s)?
+            };
+        }
+        let mixed = parameters
+            .mds()
+            .iter()
+            .zip(constants)
+            .map(|(row, c)| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(FpVar::constant(*c), |acc, (m, s)| acc + s * *m)
+            })
+            .collect::<Vec<_>>();
+        state.clone_from_slice(&mixed);
+    }
+    Ok(())
+}
+
+/// Hashes `inputs` into a single field element, as `RescueParameters::hash`
+pub fn hash_gadget<F: PrimeField>(
+    parameters: &RescueParameters<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut state = vec![FpVar::zero(); parameters.width()];
+    state[0] = FpVar::constant(F::from(inputs.len() as u64));
+    for block in inputs.chunks(parameters.rate()) {
+        for (s, x) in state[1..].iter_mut().zip(block) {
+            *s += x;
+        }
+        permute_gadget(parameters, &mut state)?;
+    }
+    if inputs.is_empty() {
+        permute_gadget(parameters, &mut state)?;
+    }
+    Ok(state.swap_remove(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_gadget, permute_gadget};
+    use crate::crypto::rescue::RescueParameters;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_rescue_gadget() {
+        let mut rng = test_rng();
+        let parameters = RescueParameters::<Fr>::new(3, 8);
+        let inputs = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone())).unwrap();
+        let mut state = inputs[..3].to_vec();
+        let mut state_var = inputs_var[..3].to_vec();
+        parameters.permute(&mut state);
+        permute_gadget(&parameters, &mut state_var).unwrap();
+        assert_eq!(state_var.value().unwrap(), state);
+        let hash = hash_gadget(&parameters, &inputs_var).unwrap();
+        assert_eq!(hash.value().unwrap(), parameters.hash(&inputs));
+        // the empty hash only involves constants
+        assert_eq!(
+            hash_gadget(&parameters, &[]).unwrap().value().unwrap(),
+            parameters.hash(&[])
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
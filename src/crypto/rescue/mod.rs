@@ -0,0 +1,217 @@
+// Rescue-Prime: https://eprint.iacr.org/2020/1143
+// The permutation runs over a state of t field elements for n rounds. Each round applies the S-box x -> x^alpha
+// to the whole state, the MDS matrix and round constants, then the inverse S-box x -> x^(1/alpha), the MDS
+// matrix and round constants again. 1/alpha is the inverse of alpha modulo p - 1: natively, the inverse S-box
+// is a ~log2(p) bits exponentiation, while in a circuit it's a witness y checked with y^alpha = x, as cheap as
+// the forward S-box, which makes rescue reach the security of poseidon with far fewer rounds.
+// alpha, the keccak derived round constants and the cauchy MDS matrix are as poseidon's: the parameters are
+// sound for any prime field, but don't match the reference implementation.
+// Hashing is poseidon's sponge: the capacity, in the first element, starts at the number of inputs.
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+
+use super::poseidon::smallest_valid_alpha;
+
+pub mod gadgets;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RescueParameters<F: PrimeField> {
+    width: usize,
+    rounds: usize,
+    alpha: u64,
+    /// 1 / alpha mod p - 1, as little endian limbs
+    alpha_inv: Vec<u64>,
+    /// one vector of `width` constants per half round
+    round_constants: Vec<Vec<F>>,
+    mds: Vec<Vec<F>>,
+}
+
+/// e such that alpha * e = 1 mod p - 1: e = (k (p - 1) + 1) / alpha for the k in 1..alpha making it an integer
+fn inverse_exponent<F: PrimeField>(alpha: u64) -> Vec<u64> {
+    let p_minus_one = (-F::ONE).into_bigint();
+    (1..alpha)
+        .find_map(|k| {
+            // k (p - 1) + 1, from the least significant limb
+            let mut carry = 1u128;
+            let mut n = p_minus_one
+                .as_ref()
+                .iter()
+                .map(|limb| {
+                    let v = *limb as u128 * k as u128 + carry;
+                    carry = v >> 64;
+                    v as u64
+                })
+                .collect::<Vec<_>>();
+            n.push(carry as u64);
+            // divided by alpha, from the most significant limb
+            let mut remainder = 0u128;
+            for limb in n.iter_mut().rev() {
+                let v = (remainder << 64) + *limb as u128;
+                *limb = (v / alpha as u128) as u64;
+                remainder = v % alpha as u128;
+            }
+            (remainder == 0).then_some(n)
+        })
+        .unwrap()
+}
+
+impl<F: PrimeField> RescueParameters<F> {
+    /// Generates the parameters for a state of `width` elements, e.g. width 3 and 8 rounds
+    pub fn new(width: usize, rounds: usize) -> Self {
+        assert!(
+            width >= 2,
+            "the state holds at least one capacity and one rate element"
+        );
+        let alpha = smallest_valid_alpha::<F>();
+
+        let mut seed = b"rescue".to_vec();
+        for value in [width, rounds] {
+            (value as u64).serialize_compressed(&mut seed).unwrap();
+        }
+        seed.extend(F::MODULUS.to_bytes_le());
+        let round_constants = (0..2 * rounds)
+            .map(|half_round| {
+                (0..width)
+                    .map(|i| {
+                        let mut bytes = seed.clone();
+                        ((half_round * width + i) as u64)
+                            .serialize_compressed(&mut bytes)
+                            .unwrap();
+                        F::from_le_bytes_mod_order(&Keccak256::digest(&bytes))
+                    })
+                    .collect()
+            })
+            .collect();
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| F::from((i + width + j) as u64).inverse().unwrap())
+                    .collect()
+            })
+            .collect();
+        Self {
+            width,
+            rounds,
+            alpha,
+            alpha_inv: inverse_exponent::<F>(alpha),
+            round_constants,
+            mds,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn rate(&self) -> usize {
+        self.width - 1
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn alpha(&self) -> u64 {
+        self.alpha
+    }
+
+    pub fn alpha_inv(&self) -> &[u64] {
+        &self.alpha_inv
+    }
+
+    pub fn round_constants(&self) -> &[Vec<F>] {
+        &self.round_constants
+    }
+
+    pub fn mds(&self) -> &[Vec<F>] {
+        &self.mds
+    }
+
+    /// Applies the rescue permutation to `state`
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width);
+        for (half_round, constants) in self.round_constants.iter().enumerate() {
+            let exponent = if half_round % 2 == 0 {
+                &[self.alpha][..]
+            } else {
+                &self.alpha_inv
+            };
+            for s in state.iter_mut() {
+                *s = s.pow(exponent);
+            }
+            let mixed = self
+                .mds
+                .iter()
+                .zip(constants)
+                .map(|(row, c)| row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum::<F>() + c)
+                .collect::<Vec<F>>();
+            state.copy_from_slice(&mixed);
+        }
+    }
+
+    /// Hashes `inputs` into a single field element
+    /// The inputs are absorbed `rate` at a time, with a permutation after each block, then squeezed once
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut state = vec![F::ZERO; self.width];
+        state[0] = F::from(inputs.len() as u64);
+        for block in inputs.chunks(self.rate()) {
+            for (s, x) in state[1..].iter_mut().zip(block) {
+                *s += x;
+            }
+            self.permute(&mut state);
+        }
+        if inputs.is_empty() {
+            self.permute(&mut state);
+        }
+        state[1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RescueParameters;
+    use ark_bn254::Fr;
+    use ark_ff::Field;
+    use ark_std::{test_rng, UniformRand};
+    use std::str::FromStr;
+
+    #[test]
+    pub fn test_rescue() {
+        let mut rng = test_rng();
+        let parameters = RescueParameters::<Fr>::new(3, 8);
+        assert_eq!(parameters.alpha(), 5);
+        let x = Fr::rand(&mut rng);
+        assert_eq!(x.pow([5]).pow(parameters.alpha_inv()), x);
+
+        // test vectors, from an independent implementation of the above
+        let mut state = [0u64, 1, 2].map(Fr::from);
+        parameters.permute(&mut state);
+        let expected = [
+            "14300180436198666158194746353439067882584414375188564450902438265050165117915",
+            "6011821841983312171380101041088552732467971985228333746127017758568835591866",
+            "8924263540490270148634035344490515920341304039315691025925770657813436093021",
+        ]
+        .map(|s| Fr::from_str(s).unwrap());
+        assert_eq!(state, expected);
+        let inputs = (1..=3).map(Fr::from).collect::<Vec<_>>();
+        assert_eq!(
+            parameters.hash(&inputs),
+            Fr::from_str(
+                "20162348586849887975026784589176581103694004802968575999805069701401120127583"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            parameters.hash(&[]),
+            Fr::from_str(
+                "1469319994426000189787264617671963641036965713779770767627755566601037390245"
+            )
+            .unwrap()
+        );
+        assert_ne!(
+            parameters.hash(&inputs[..2]),
+            parameters.hash(&[inputs[0], inputs[1], Fr::from(0u64)])
+        );
+    }
+}
@@ -0,0 +1,428 @@
+// Merkle tree vector commitment, with a pluggable hash:
+// - poseidon, over field elements, whose digests are field elements, cheap to verify in a circuit,
+// - blake2s and keccak256, over any serializable leaves, whose digests are 32 bytes, fast natively.
+//   Keccak trees commit to the fri codewords and the ligero columns.
+// Leaves and internal nodes are hashed differently, so that a leaf can't be passed off as a node.
+// A multi-proof opens several leaves at once: at each level, only the siblings that can't be recomputed
+// from the opened leaves are sent, ordered by level then by index.
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest as _, Keccak256};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use crate::crypto::{blake2::blake2s, poseidon::PoseidonParameters};
+
+/// Digest of the byte oriented hashers
+pub type Digest = [u8; 32];
+
+pub trait MerkleHasher<L> {
+    type Digest: Clone + Debug + PartialEq;
+
+    fn hash_leaf(&self, leaf: &L) -> Self::Digest;
+
+    fn hash_nodes(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+}
+
+/// Hashes a leaf as poseidon(leaf) and two nodes as poseidon(left, right), the sponge separating input lengths
+#[derive(Clone, Debug)]
+pub struct PoseidonHasher<F: PrimeField> {
+    parameters: PoseidonParameters<F>,
+}
+
+impl<F: PrimeField> PoseidonHasher<F> {
+    pub fn new(parameters: PoseidonParameters<F>) -> Self {
+        Self { parameters }
+    }
+}
+
+impl<F: PrimeField> MerkleHasher<F> for PoseidonHasher<F> {
+    type Digest = F;
+
+    fn hash_leaf(&self, leaf: &F) -> F {
+        self.parameters.hash(&[*leaf])
+    }
+
+    fn hash_nodes(&self, left: &F, right: &F) -> F {
+        self.parameters.hash(&[*left, *right])
+    }
+}
+
+/// Hashes a leaf as blake2s(0 || leaf) and two nodes as blake2s(1 || left || right)
+#[derive(Clone, Debug, Default)]
+pub struct Blake2Hasher;
+
+impl<L: CanonicalSerialize> MerkleHasher<L> for Blake2Hasher {
+    type Digest = Digest;
+
+    fn hash_leaf(&self, leaf: &L) -> Digest {
+        let mut bytes = vec![0u8];
+        leaf.serialize_compressed(&mut bytes).unwrap();
+        blake2s(&bytes)
+    }
+
+    fn hash_nodes(&self, left: &Digest, right: &Digest) -> Digest {
+        blake2s(&[&[1u8], &left[..], &right[..]].concat())
+    }
+}
+
+/// Hashes a leaf as keccak256(0 || leaf) and two nodes as keccak256(1 || left || right)
+#[derive(Clone, Debug, Default)]
+pub struct KeccakHasher;
+
+impl<L: CanonicalSerialize> MerkleHasher<L> for KeccakHasher {
+    type Digest = Digest;
+
+    fn hash_leaf(&self, leaf: &L) -> Digest {
+        let mut bytes = vec![0u8];
+        leaf.serialize_compressed(&mut bytes).unwrap();
+        Keccak256::digest(&bytes).into()
+    }
+
+    fn hash_nodes(&self, left: &Digest, right: &Digest) -> Digest {
+        Keccak256::digest([&[1u8], &left[..], &right[..]].concat()).into()
+    }
+}
+
+pub struct MerkleTree<L, H: MerkleHasher<L>> {
+    hasher: H,
+    leaves: Vec<L>,
+    /// layers[0] holds the leaf hashes, the last layer holds the root
+    layers: Vec<Vec<H::Digest>>,
+}
+
+/// The siblings of the leaves opened by a multi-proof, which the verifier can't recompute
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerkleMultiProof<D> {
+    pub siblings: Vec<D>,
+}
+
+impl<L: Clone, H: MerkleHasher<L>> MerkleTree<L, H> {
+    /// Builds the tree, the number of leaves must be a power of two
+    pub fn new(hasher: H, leaves: &[L]) -> Self {
+        assert!(leaves.len().is_power_of_two());
+        let mut layers = vec![leaves
+            .iter()
+            .map(|leaf| hasher.hash_leaf(leaf))
+            .collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hasher.hash_nodes(&pair[0], &pair[1]))
+                .collect();
+            layers.push(layer);
+        }
+        Self {
+            hasher,
+            leaves: leaves.to_vec(),
+            layers,
+        }
+    }
+
+    pub fn root(&self) -> H::Digest {
+        self.layers.last().unwrap()[0].clone()
+    }
+
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    pub fn leaves(&self) -> &[L] {
+        &self.leaves
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    /// Returns the authentication path of the leaf at `index`: its sibling at every level, from the bottom
+    pub fn open(&self, index: usize) -> Vec<H::Digest> {
+        let mut path = vec![];
+        let mut index = index;
+        for layer in self.layers[..self.depth()].iter() {
+            path.push(layer[index ^ 1].clone());
+            index >>= 1;
+        }
+        path
+    }
+
+    /// Opens the leaves at `indices` with the siblings that they don't determine
+    pub fn open_multi(&self, indices: &[usize]) -> MerkleMultiProof<H::Digest> {
+        let mut known = indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        let mut siblings = vec![];
+        for layer in self.layers[..self.depth()].iter() {
+            for index in &known {
+                if known.binary_search(&(index ^ 1)).is_err() {
+                    siblings.push(layer[index ^ 1].clone());
+                }
+            }
+            known = known.iter().map(|index| index >> 1).collect();
+            known.dedup();
+        }
+        MerkleMultiProof { siblings }
+    }
+}
+
+/// Checks that `leaf` is at `index` in the tree of depth `depth` with root `root`
+pub fn verify_path<L, H: MerkleHasher<L>>(
+    hasher: &H,
+    root: &H::Digest,
+    depth: usize,
+    index: usize,
+    leaf: &L,
+    path: &[H::Digest],
+) -> bool {
+    if path.len() != depth || depth >= usize::BITS as usize || index >> depth != 0 {
+        return false;
+    }
+    let mut node = hasher.hash_leaf(leaf);
+    let mut index = index;
+    for sibling in path {
+        node = if index & 1 == 0 {
+            hasher.hash_nodes(&node, sibling)
+        } else {
+            hasher.hash_nodes(sibling, &node)
+        };
+        index >>= 1;
+    }
+    node == *root
+}
+
+/// Checks that leaves[i] is at indices[i] in the tree of depth `depth` with root `root`
+pub fn verify_multi<L, H: MerkleHasher<L>>(
+    hasher: &H,
+    root: &H::Digest,
+    depth: usize,
+    indices: &[usize],
+    leaves: &[L],
+    proof: &MerkleMultiProof<H::Digest>,
+) -> bool {
+    if indices.len() != leaves.len()
+        || depth >= usize::BITS as usize
+        || indices.iter().any(|index| index >> depth != 0)
+    {
+        return false;
+    }
+    let mut known = BTreeMap::new();
+    for (index, leaf) in indices.iter().zip(leaves) {
+        let digest = hasher.hash_leaf(leaf);
+        // the same index opened twice should carry the same leaf
+        if known
+            .insert(*index, digest.clone())
+            .is_some_and(|d| d != digest)
+        {
+            return false;
+        }
+    }
+    let mut siblings = proof.siblings.iter();
+    for _ in 0..depth {
+        let mut parents = BTreeMap::new();
+        for (index, node) in &known {
+            if index & 1 == 1 && known.contains_key(&(index ^ 1)) {
+                // already hashed with its left sibling
+                continue;
+            }
+            let sibling = match known.get(&(index ^ 1)) {
+                Some(sibling) => sibling,
+                None => match siblings.next() {
+                    Some(sibling) => sibling,
+                    None => return false,
+                },
+            };
+            let parent = if index & 1 == 0 {
+                hasher.hash_nodes(node, sibling)
+            } else {
+                hasher.hash_nodes(sibling, node)
+            };
+            parents.insert(index >> 1, parent);
+        }
+        known = parents;
+    }
+    siblings.next().is_none() && known.len() == 1 && known.get(&0) == Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        verify_multi, verify_path, Blake2Hasher, KeccakHasher, MerkleTree, PoseidonHasher,
+    };
+    use crate::crypto::poseidon::PoseidonParameters;
+    use ark_bn254::Fr;
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_merkle_tree() {
+        let mut rng = test_rng();
+        let leaves = (0..16).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let poseidon_tree = MerkleTree::new(
+            PoseidonHasher::new(PoseidonParameters::new(3, 8, 57).unwrap()),
+            &leaves,
+        );
+        let blake2_tree = MerkleTree::new(Blake2Hasher, &leaves);
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = poseidon_tree.open(i);
+            assert_eq!(path.len(), 4);
+            let (hasher, root) = (poseidon_tree.hasher(), poseidon_tree.root());
+            assert!(verify_path(hasher, &root, 4, i, leaf, &path));
+            assert!(!verify_path(
+                hasher,
+                &root,
+                4,
+                i,
+                &(*leaf + Fr::one()),
+                &path
+            ));
+            assert!(!verify_path(hasher, &root, 4, (i + 1) % 16, leaf, &path));
+
+            let path = blake2_tree.open(i);
+            assert!(verify_path(
+                &Blake2Hasher,
+                &blake2_tree.root(),
+                4,
+                i,
+                leaf,
+                &path
+            ));
+            assert!(!verify_path(
+                &Blake2Hasher,
+                &blake2_tree.root(),
+                4,
+                i ^ 1,
+                leaf,
+                &path
+            ));
+        }
+        assert!(!verify_path(
+            &Blake2Hasher,
+            &blake2_tree.root(),
+            4,
+            16,
+            &leaves[0],
+            &blake2_tree.open(0)
+        ));
+        // a path longer than the tree, up to the width of an index
+        let mut path = blake2_tree.open(0);
+        path.resize(64, path[0]);
+        assert!(!verify_path(
+            &Blake2Hasher,
+            &blake2_tree.root(),
+            4,
+            0,
+            &leaves[0],
+            &path
+        ));
+        assert!(!verify_path(
+            &Blake2Hasher,
+            &blake2_tree.root(),
+            64,
+            0,
+            &leaves[0],
+            &path
+        ));
+    }
+
+    #[test]
+    pub fn test_keccak_merkle_tree() {
+        // leaves of any serializable type, e.g. the columns of a matrix
+        let mut rng = test_rng();
+        let leaves = (0..8)
+            .map(|_| (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(KeccakHasher, &leaves);
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = tree.open(i);
+            assert_eq!(path.len(), 3);
+            assert!(verify_path(&KeccakHasher, &tree.root(), 3, i, leaf, &path));
+            assert!(!verify_path(
+                &KeccakHasher,
+                &tree.root(),
+                3,
+                i,
+                &leaf[..2].to_vec(),
+                &path
+            ));
+            assert!(!verify_path(
+                &KeccakHasher,
+                &tree.root(),
+                3,
+                (i + 1) % 8,
+                leaf,
+                &path
+            ));
+        }
+        assert_ne!(tree.root(), MerkleTree::new(Blake2Hasher, &leaves).root());
+    }
+
+    #[test]
+    pub fn test_merkle_multi_proof() {
+        let mut rng = test_rng();
+        let leaves = (0..16).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let tree = MerkleTree::new(Blake2Hasher, &leaves);
+        let root = tree.root();
+
+        let indices = [3, 2, 9, 15, 2];
+        let opened = indices.iter().map(|i| leaves[*i]).collect::<Vec<_>>();
+        let proof = tree.open_multi(&indices);
+        // 2 and 3 are siblings, fewer digests than three separate paths
+        assert!(proof.siblings.len() < 3 * 4);
+        assert!(verify_multi(
+            &Blake2Hasher,
+            &root,
+            4,
+            &indices,
+            &opened,
+            &proof
+        ));
+
+        let mut wrong = opened.clone();
+        wrong[2] += Fr::one();
+        assert!(!verify_multi(
+            &Blake2Hasher,
+            &root,
+            4,
+            &indices,
+            &wrong,
+            &proof
+        ));
+        // the duplicated index opened to another leaf
+        wrong = opened.clone();
+        wrong[4] = leaves[3];
+        assert!(!verify_multi(
+            &Blake2Hasher,
+            &root,
+            4,
+            &indices,
+            &wrong,
+            &proof
+        ));
+        assert!(!verify_multi(
+            &Blake2Hasher,
+            &root,
+            4,
+            &[3, 2, 9, 14, 2],
+            &opened,
+            &proof
+        ));
+        let mut short = proof.clone();
+        short.siblings.pop();
+        assert!(!verify_multi(
+            &Blake2Hasher,
+            &root,
+            4,
+            &indices,
+            &opened,
+            &short
+        ));
+
+        // all the leaves need no sibling
+        let all = (0..16).collect::<Vec<_>>();
+        let proof = tree.open_multi(&all);
+        assert!(proof.siblings.is_empty());
+        assert!(verify_multi(&Blake2Hasher, &root, 4, &all, &leaves, &proof));
+    }
+}
@@ -1,2 +1,209 @@
-mod pcs;
-mod pedersen;
+pub mod merkle;
+pub mod obligations;
+pub mod pcs;
+pub mod pedersen;
+
+use ark_ec::{pairing::Pairing, CurveGroup, Group};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::rand::Rng;
+use std::fmt::Debug;
+
+use crate::utils::curve::SubgroupCheck;
+use pcs::kzg::KZG;
+use pedersen::Pedersen;
+
+/// A homomorphic commitment to vectors of field elements, e.g. the W and E of a folding scheme
+/// A vector v is also seen as the coefficients of p_v(X) = sum_i v_i X^i, which `open` evaluates.
+pub trait CommitmentScheme: Sized {
+    type Scalar: PrimeField;
+    type Commitment: Copy + Debug + PartialEq;
+    type Proof: Clone + Debug + PartialEq;
+
+    /// Parameters to commit to vectors of up to n elements
+    fn setup<R: Rng>(n: usize, rng: &mut R) -> Self;
+
+    /// Commits to v, hiding it with `blinding` when the scheme supports it
+    fn commit(&self, v: &[Self::Scalar], blinding: &Self::Scalar) -> Self::Commitment;
+
+    /// Returns y = p_v(z) and a proof that the commitment to (v, blinding) opens to y at z
+    fn open(
+        &self,
+        v: &[Self::Scalar],
+        blinding: &Self::Scalar,
+        z: Self::Scalar,
+    ) -> (Self::Scalar, Self::Proof);
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        z: Self::Scalar,
+        y: Self::Scalar,
+        proof: &Self::Proof,
+    ) -> bool;
+
+    /// Commitment to v_1 + v_2, with blinding b_1 + b_2
+    fn add(c_1: &Self::Commitment, c_2: &Self::Commitment) -> Self::Commitment;
+
+    /// Commitment to r * v, with blinding r * b
+    fn scale(c: &Self::Commitment, r: Self::Scalar) -> Self::Commitment;
+
+    /// c_1 + r * c_2, as folding schemes combine two commitments
+    fn fold(c_1: &Self::Commitment, c_2: &Self::Commitment, r: Self::Scalar) -> Self::Commitment {
+        Self::add(c_1, &Self::scale(c_2, r))
+    }
+}
+
+/// A pedersen opening reveals the vector and its blinding, the verifier recomputes the commitment
+#[derive(Clone, Debug, PartialEq)]
+pub struct PedersenOpening<F: PrimeField> {
+    pub v: Vec<F>,
+    pub blinding: F,
+}
+
+/// p_v(z), by horner's rule
+fn evaluate<F: Field>(v: &[F], z: F) -> F {
+    v.iter().rev().fold(F::zero(), |acc, v_i| acc * z + v_i)
+}
+
+impl<C: CurveGroup> CommitmentScheme for Pedersen<C> {
+    type Scalar = C::ScalarField;
+    type Commitment = C;
+    type Proof = PedersenOpening<C::ScalarField>;
+
+    /// Hashed generators: the rng is unused
+    fn setup<R: Rng>(n: usize, _rng: &mut R) -> Self {
+        Pedersen::setup(n)
+    }
+
+    fn commit(&self, v: &[C::ScalarField], blinding: &C::ScalarField) -> C {
+        Pedersen::commit(self, v, blinding)
+    }
+
+    fn open(
+        &self,
+        v: &[C::ScalarField],
+        blinding: &C::ScalarField,
+        z: C::ScalarField,
+    ) -> (C::ScalarField, PedersenOpening<C::ScalarField>) {
+        let opening = PedersenOpening {
+            v: v.to_vec(),
+            blinding: *blinding,
+        };
+        (evaluate(v, z), opening)
+    }
+
+    fn verify(
+        &self,
+        commitment: &C,
+        z: C::ScalarField,
+        y: C::ScalarField,
+        proof: &PedersenOpening<C::ScalarField>,
+    ) -> bool {
+        self.verify_opening(commitment, &proof.v, &proof.blinding) && evaluate(&proof.v, z) == y
+    }
+
+    fn add(c_1: &C, c_2: &C) -> C {
+        *c_1 + c_2
+    }
+
+    fn scale(c: &C, r: C::ScalarField) -> C {
+        *c * r
+    }
+}
+
+/// KZG isn't hiding: the blinding is ignored
+impl<E: Pairing> CommitmentScheme for KZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    type Scalar = E::ScalarField;
+    type Commitment = E::G1;
+    type Proof = E::G1;
+
+    /// Samples tau from `rng`, which should be cryptographically secure outside of tests
+    fn setup<R: Rng>(n: usize, rng: &mut R) -> Self {
+        let mut kzg = KZG::new(E::G1::generator(), E::G2::generator(), n.max(1) - 1);
+        kzg.setup_from_rng(rng);
+        kzg
+    }
+
+    /// Panics if v has more elements than the n of `setup`
+    fn commit(&self, v: &[E::ScalarField], _blinding: &E::ScalarField) -> E::G1 {
+        KZG::commit(self, &DensePolynomial::from_coefficients_slice(v))
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn open(
+        &self,
+        v: &[E::ScalarField],
+        _blinding: &E::ScalarField,
+        z: E::ScalarField,
+    ) -> (E::ScalarField, E::G1) {
+        let polynomial = DensePolynomial::from_coefficients_slice(v);
+        let y = polynomial.evaluate(&z);
+        // as `commit`, panics if the polynomial doesn't fit in the srs
+        let proof = KZG::open(self, &polynomial, z, y).unwrap_or_else(|e| panic!("{e}"));
+        (y, proof)
+    }
+
+    fn verify(
+        &self,
+        commitment: &E::G1,
+        z: E::ScalarField,
+        y: E::ScalarField,
+        proof: &E::G1,
+    ) -> bool {
+        KZG::verify(self, y, z, *commitment, *proof)
+    }
+
+    fn add(c_1: &E::G1, c_2: &E::G1) -> E::G1 {
+        *c_1 + c_2
+    }
+
+    fn scale(c: &E::G1, r: E::ScalarField) -> E::G1 {
+        *c * r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommitmentScheme;
+    use crate::cs::{pcs::kzg::KZG, pedersen::Pedersen};
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    /// Commits to two vectors, checks the openings and that folding the commitments commits to the folded vector
+    fn check_scheme<S: CommitmentScheme>() {
+        let mut rng = test_rng();
+        let scheme = S::setup(8, &mut rng);
+        let sample = |rng: &mut _| (0..8).map(|_| S::Scalar::rand(rng)).collect::<Vec<_>>();
+        let (v_1, v_2) = (sample(&mut rng), sample(&mut rng));
+        let (b_1, b_2) = (S::Scalar::rand(&mut rng), S::Scalar::rand(&mut rng));
+        let (c_1, c_2) = (scheme.commit(&v_1, &b_1), scheme.commit(&v_2, &b_2));
+
+        let z = S::Scalar::rand(&mut rng);
+        let (y, proof) = scheme.open(&v_1, &b_1, z);
+        assert!(scheme.verify(&c_1, z, y, &proof));
+        assert!(!scheme.verify(&c_1, z, y + S::Scalar::one(), &proof));
+        assert!(!scheme.verify(&c_2, z, y, &proof));
+
+        let r = S::Scalar::rand(&mut rng);
+        let folded = v_1
+            .iter()
+            .zip(&v_2)
+            .map(|(a, b)| *a + r * b)
+            .collect::<Vec<_>>();
+        let blinding = b_1 + r * b_2;
+        assert_eq!(S::fold(&c_1, &c_2, r), scheme.commit(&folded, &blinding));
+        let (y, proof) = scheme.open(&folded, &blinding, z);
+        assert!(scheme.verify(&S::fold(&c_1, &c_2, r), z, y, &proof));
+    }
+
+    #[test]
+    pub fn test_commitment_schemes() {
+        check_scheme::<Pedersen<ark_pallas::Projective>>();
+        check_scheme::<KZG<ark_bn254::Bn254>>();
+    }
+}
@@ -0,0 +1,205 @@
+// Deferred ("lazy") verification work.
+// A verifier first runs the cheap checks (lengths, subgroup membership, ...) and pushes the
+// expensive pairing/MSM work into an `ObligationSet`, which can be discharged later: one by one,
+// batched with a random linear combination, or only on a random sample (light-client style).
+// Lazy verifiers: `KZG::verify_lazy` and `KZG::verify_multi_open_lazy` defer their pairing checks, and
+// `NIFS::verify_lazy` checks a folding step's challenge and scalars, deferring the msms of the folded commitments.
+use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
+use ark_ff::{One, UniformRand};
+use ark_std::{rand::Rng, Zero};
+
+/// A multi-scalar multiplication sum_i scalars[i] * bases[i], kept unevaluated
+#[derive(Clone, Debug)]
+pub struct MsmTerm<E: Pairing> {
+    bases: Vec<E::G1>,
+    scalars: Vec<E::ScalarField>,
+}
+
+impl<E: Pairing> MsmTerm<E> {
+    pub fn new(bases: Vec<E::G1>, scalars: Vec<E::ScalarField>) -> Self {
+        assert_eq!(bases.len(), scalars.len());
+        Self { bases, scalars }
+    }
+
+    /// A term consisting of a single point, with scalar 1
+    pub fn from_point(point: E::G1) -> Self {
+        Self::new(vec![point], vec![E::ScalarField::one()])
+    }
+
+    pub fn bases(&self) -> &[E::G1] {
+        &self.bases
+    }
+
+    pub fn scalars(&self) -> &[E::ScalarField] {
+        &self.scalars
+    }
+
+    pub fn evaluate(&self) -> E::G1 {
+        let bases = E::G1::normalize_batch(&self.bases);
+        E::G1::msm(&bases, &self.scalars).unwrap()
+    }
+
+    /// Returns the same term, with all scalars multiplied by `r`
+    pub fn scale(&self, r: &E::ScalarField) -> Self {
+        let scalars = self.scalars.iter().map(|s| *s * r).collect();
+        Self::new(self.bases.clone(), scalars)
+    }
+}
+
+/// A deferred check
+#[derive(Clone, Debug)]
+pub enum Obligation<E: Pairing> {
+    /// prod_j e(g1_terms[j], g2_terms[j]) == 1
+    PairingProduct {
+        g1_terms: Vec<MsmTerm<E>>,
+        g2_terms: Vec<E::G2>,
+    },
+    /// sum_i scalars[i] * bases[i] == 0
+    MsmIsZero(MsmTerm<E>),
+}
+
+impl<E: Pairing> Obligation<E> {
+    pub fn pairing_product(g1_terms: Vec<MsmTerm<E>>, g2_terms: Vec<E::G2>) -> Self {
+        assert_eq!(g1_terms.len(), g2_terms.len());
+        Self::PairingProduct { g1_terms, g2_terms }
+    }
+
+    /// Runs the expensive work and checks that the obligation holds
+    pub fn discharge(&self) -> bool {
+        match self {
+            Self::PairingProduct { g1_terms, g2_terms } => {
+                let g1: Vec<E::G1> = g1_terms.iter().map(|t| t.evaluate()).collect();
+                E::multi_pairing(g1, g2_terms.clone()).0.is_one()
+            }
+            Self::MsmIsZero(term) => term.evaluate().is_zero(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ObligationSet<E: Pairing> {
+    obligations: Vec<Obligation<E>>,
+}
+
+impl<E: Pairing> Default for ObligationSet<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Pairing> ObligationSet<E> {
+    pub fn new() -> Self {
+        Self {
+            obligations: vec![],
+        }
+    }
+
+    pub fn push(&mut self, obligation: Obligation<E>) {
+        self.obligations.push(obligation);
+    }
+
+    /// Moves all obligations of `other` into this set
+    pub fn append(&mut self, other: &mut ObligationSet<E>) {
+        self.obligations.append(&mut other.obligations);
+    }
+
+    pub fn obligations(&self) -> &[Obligation<E>] {
+        &self.obligations
+    }
+
+    pub fn len(&self) -> usize {
+        self.obligations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obligations.is_empty()
+    }
+
+    /// Discharges every obligation independently
+    pub fn discharge(&self) -> bool {
+        self.obligations.iter().all(|o| o.discharge())
+    }
+
+    /// Discharges every obligation at once, using a random linear combination.
+    /// All pairing products are merged into a single multi-pairing and all msm checks into a single msm.
+    /// A false obligation makes this return true with probability at most 1/|F|.
+    pub fn discharge_batched<R: Rng>(&self, rng: &mut R) -> bool {
+        let mut g1 = vec![];
+        let mut g2 = vec![];
+        let mut msm_bases = vec![];
+        let mut msm_scalars = vec![];
+        for obligation in self.obligations.iter() {
+            let r = E::ScalarField::rand(rng);
+            match obligation {
+                Obligation::PairingProduct { g1_terms, g2_terms } => {
+                    for (g1_term, g2_term) in g1_terms.iter().zip(g2_terms) {
+                        g1.push(g1_term.scale(&r).evaluate());
+                        g2.push(*g2_term);
+                    }
+                }
+                Obligation::MsmIsZero(term) => {
+                    let scaled = term.scale(&r);
+                    msm_bases.extend(scaled.bases);
+                    msm_scalars.extend(scaled.scalars);
+                }
+            }
+        }
+        let pairings_hold = g1.is_empty() || E::multi_pairing(g1, g2).0.is_one();
+        let msms_hold = MsmTerm::<E>::new(msm_bases, msm_scalars)
+            .evaluate()
+            .is_zero();
+        pairings_hold && msms_hold
+    }
+
+    /// Discharges `n_samples` obligations picked at random (without replacement).
+    /// Useful when a verifier only wants probabilistic assurance over a large batch.
+    pub fn discharge_sampled<R: Rng>(&self, n_samples: usize, rng: &mut R) -> bool {
+        let n = self.obligations.len();
+        let mut indices: Vec<usize> = (0..n).collect();
+        for i in 0..n_samples.min(n) {
+            let j = rng.gen_range(i..n);
+            indices.swap(i, j);
+            if !self.obligations[indices[i]].discharge() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MsmTerm, Obligation, ObligationSet};
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ff::UniformRand;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_obligation_set_discharge() {
+        let mut rng = test_rng();
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let a = Fr::rand(&mut rng);
+
+        // e(a * g1, g2) * e(-g1, a * g2) == 1
+        let mut obligations = ObligationSet::<Bn254>::new();
+        obligations.push(Obligation::pairing_product(
+            vec![MsmTerm::new(vec![g1], vec![a]), MsmTerm::from_point(-g1)],
+            vec![g2, g2 * a],
+        ));
+        // a * g1 - (a * g1) == 0
+        obligations.push(Obligation::MsmIsZero(MsmTerm::new(
+            vec![g1, g1 * a],
+            vec![a, -Fr::from(1)],
+        )));
+        assert!(obligations.discharge());
+        assert!(obligations.discharge_batched(&mut rng));
+        assert!(obligations.discharge_sampled(1, &mut rng));
+
+        // a wrong obligation makes every discharge mode fail (sampling all of them)
+        obligations.push(Obligation::MsmIsZero(MsmTerm::from_point(g1)));
+        assert!(!obligations.discharge());
+        assert!(!obligations.discharge_batched(&mut rng));
+        assert!(!obligations.discharge_sampled(obligations.len(), &mut rng));
+    }
+}
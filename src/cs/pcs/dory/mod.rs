@@ -0,0 +1,366 @@
+// Dory multilinear commitment: https://eprint.iacr.org/2020/1274 (sections 3 and 4), without zero-knowledge
+// Transparent setup: the generators Gamma_1 in G1 and Gamma_2 in G2 are hashed to the curves.
+// The 2^n evaluations of an mle are laid out as a 2^k x 2^k matrix M, k = n - m, the m = n / 2 low variables
+// indexing the (zero padded) columns as in hyrax. Rows are committed to with pedersen, T_i = <M_i, Gamma_1>,
+// and the row commitments with an inner pairing product, the commitment being a single target group element
+// <T, Gamma_2> = sum_i e(T_i, Gamma_2,i).
+// Since f(r) = L^T M R, with L = eq(r_rows, .) and R = eq(r_cols, .), the prover sets u = L^T M,
+// E_1 = <u, Gamma_1> = <L, T> and proves knowledge of v_1 = T, v_2 = u * H_2 (H_2 = Gamma_2,0) such that:
+//   C = <v_1, v_2>, D_1 = <v_1, Gamma_2> (the commitment), D_2 = <Gamma_1, v_2> = e(E_1, H_2),
+//   E_1 = <v_1, L>, E_2 = <R, v_2> = y * H_2.
+// Each round of Dory-Reduce halves the vectors and keeps these 5 relations, with challenges beta and alpha:
+//   v_1 += beta * Gamma_1, v_2 += beta^-1 * Gamma_2, then v_1' = alpha * v_1L + v_1R, v_2' = alpha^-1 * v_2L + v_2R,
+// the prover sending the cross terms the verifier can't compute. The generators of round i + 1 being the first
+// half of those of round i, the pairings chi_i = <Gamma_1, Gamma_2> and Delta_i of the generators are computed
+// once at setup, and L, R are tensor products folding into a scalar: the verifier runs in O(log n).
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::{Field, One, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::ops::{Add, Mul};
+
+use crate::cs::pedersen::derive_generators;
+use crate::ip::sumcheck::eq_evaluations;
+use crate::transcript::Transcript;
+use crate::utils::curve::msm;
+
+pub struct Dory<E: Pairing> {
+    num_vars: usize,
+    /// Number of column variables, m = n / 2
+    num_col_vars: usize,
+    /// 2^k generators in each group, the generators of round i being their first 2^{k - i}
+    gamma_1: Vec<E::G1>,
+    gamma_2: Vec<E::G2>,
+    /// chi_i = <Gamma_1, Gamma_2> for the generators of rounds 0 to k
+    chi: Vec<PairingOutput<E>>,
+    /// <Gamma_1R, Gamma_2'> and <Gamma_1', Gamma_2R>, Gamma' being the generators of the next round;
+    /// <Gamma_1L, Gamma_2'> and <Gamma_1', Gamma_2L> are the next chi
+    delta_1r: Vec<PairingOutput<E>>,
+    delta_2r: Vec<PairingOutput<E>>,
+}
+
+/// The prover messages of a round: D values before beta, cross terms before alpha
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DoryRound<E: Pairing> {
+    pub d_1l: PairingOutput<E>,
+    pub d_1r: PairingOutput<E>,
+    pub d_2l: PairingOutput<E>,
+    pub d_2r: PairingOutput<E>,
+    pub e_1beta: E::G1,
+    pub e_2beta: E::G2,
+    pub c_plus: PairingOutput<E>,
+    pub c_minus: PairingOutput<E>,
+    pub e_1plus: E::G1,
+    pub e_1minus: E::G1,
+    pub e_2plus: E::G2,
+    pub e_2minus: E::G2,
+}
+
+/// An opening proof: E_1 = <L, T>, C = <v_1, v_2>, the rounds of Dory-Reduce and the final v_1, v_2
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DoryProof<E: Pairing> {
+    pub e_1: E::G1,
+    pub c: PairingOutput<E>,
+    pub rounds: Vec<DoryRound<E>>,
+    pub v_1: E::G1,
+    pub v_2: E::G2,
+}
+
+/// <a, b> = sum_i e(a_i, b_i)
+fn inner_pairing_product<E: Pairing>(a: &[E::G1], b: &[E::G2]) -> PairingOutput<E> {
+    E::multi_pairing(a.iter().copied(), b.iter().copied())
+}
+
+/// x * v_L + v_R
+fn fold<F: Field, T: Copy + Add<Output = T> + Mul<F, Output = T>>(v: &[T], x: F) -> Vec<T> {
+    let (left, right) = v.split_at(v.len() / 2);
+    left.iter().zip(right).map(|(l, r)| *l * x + *r).collect()
+}
+
+impl<E: Pairing> Dory<E> {
+    /// Hashes the generators and precomputes their pairings, in O(2^k) pairings
+    pub fn new(num_vars: usize) -> Self {
+        let num_col_vars = num_vars / 2;
+        let n = 1 << (num_vars - num_col_vars);
+        let gamma_1 = derive_generators::<E::G1>(b"dory gamma_1", n);
+        let gamma_2 = derive_generators::<E::G2>(b"dory gamma_2", n);
+        let mut chi = vec![];
+        let (mut delta_1r, mut delta_2r) = (vec![], vec![]);
+        let mut size = n;
+        while size > 1 {
+            let half = size / 2;
+            chi.push(inner_pairing_product(&gamma_1[..size], &gamma_2[..size]));
+            delta_1r.push(inner_pairing_product(
+                &gamma_1[half..size],
+                &gamma_2[..half],
+            ));
+            delta_2r.push(inner_pairing_product(
+                &gamma_1[..half],
+                &gamma_2[half..size],
+            ));
+            size = half;
+        }
+        chi.push(E::pairing(gamma_1[0], gamma_2[0]));
+        Self {
+            num_vars,
+            num_col_vars,
+            gamma_1,
+            gamma_2,
+            chi,
+            delta_1r,
+            delta_2r,
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// k, the number of row variables and of rounds
+    fn num_rounds(&self) -> usize {
+        self.num_vars - self.num_col_vars
+    }
+
+    fn num_cols(&self) -> usize {
+        1 << self.num_col_vars
+    }
+
+    /// L = eq(r_rows, .) and R = eq(r_cols, .), r_cols being zero padded to k variables
+    fn split_point(&self, r: &[E::ScalarField]) -> (Vec<E::ScalarField>, Vec<E::ScalarField>) {
+        let (r_cols, r_rows) = r.split_at(self.num_col_vars);
+        let mut r_cols = r_cols.to_vec();
+        r_cols.resize(self.num_rounds(), E::ScalarField::zero());
+        (r_rows.to_vec(), r_cols)
+    }
+
+    fn transcript(
+        commitment: &PairingOutput<E>,
+        r: &[E::ScalarField],
+        y: E::ScalarField,
+        e_1: &E::G1,
+        c: &PairingOutput<E>,
+    ) -> Transcript {
+        let mut transcript = Transcript::new(b"dory");
+        transcript.absorb(commitment);
+        transcript.absorb(&r.to_vec());
+        transcript.absorb(&y);
+        transcript.absorb(e_1);
+        transcript.absorb(c);
+        transcript
+    }
+
+    /// beta, after absorbing the first messages of a round
+    fn beta(transcript: &mut Transcript, round: &DoryRound<E>) -> E::ScalarField {
+        for d in [&round.d_1l, &round.d_1r, &round.d_2l, &round.d_2r] {
+            transcript.absorb(d);
+        }
+        transcript.absorb(&round.e_1beta);
+        transcript.absorb(&round.e_2beta);
+        transcript.challenge()
+    }
+
+    /// alpha, after absorbing the cross terms of a round
+    fn alpha(transcript: &mut Transcript, round: &DoryRound<E>) -> E::ScalarField {
+        transcript.absorb(&round.c_plus);
+        transcript.absorb(&round.c_minus);
+        for e in [&round.e_1plus, &round.e_1minus] {
+            transcript.absorb(e);
+        }
+        for e in [&round.e_2plus, &round.e_2minus] {
+            transcript.absorb(e);
+        }
+        transcript.challenge()
+    }
+
+    /// Commits to the rows of the evaluation matrix, returns the commitment and the row commitments
+    pub fn commit(
+        &self,
+        mle: &DenseMultilinearExtension<E::ScalarField>,
+    ) -> (PairingOutput<E>, Vec<E::G1>) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        let row_commitments = mle
+            .evaluations
+            .chunks(self.num_cols())
+            .map(|row| msm(&self.gamma_1[..row.len()], row).unwrap())
+            .collect::<Vec<_>>();
+        let commitment = inner_pairing_product(&row_commitments, &self.gamma_2);
+        (commitment, row_commitments)
+    }
+
+    /// Opens `mle` at r, returns the proof and y = f(r)
+    pub fn open(
+        &self,
+        mle: &DenseMultilinearExtension<E::ScalarField>,
+        row_commitments: &[E::G1],
+        r: &[E::ScalarField],
+    ) -> (DoryProof<E>, E::ScalarField) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        assert_eq!(r.len(), self.num_vars);
+        let (r_rows, r_cols) = self.split_point(r);
+        let (l, r_table) = (eq_evaluations(&r_rows), eq_evaluations(&r_cols));
+        let mut u = vec![E::ScalarField::zero(); l.len()];
+        for (l_i, row) in l.iter().zip(mle.evaluations.chunks(self.num_cols())) {
+            for (u_j, m_ij) in u.iter_mut().zip(row) {
+                *u_j += *l_i * m_ij;
+            }
+        }
+        let y = u.iter().zip(&r_table).map(|(u_j, r_j)| *u_j * r_j).sum();
+        let e_1 = msm(&self.gamma_1[..u.len()], &u).unwrap();
+
+        let h_2 = self.gamma_2[0];
+        let mut v_1 = row_commitments.to_vec();
+        let mut v_2 = u.iter().map(|u_j| h_2 * u_j).collect::<Vec<_>>();
+        let c = inner_pairing_product(&v_1, &v_2);
+        let (mut s_1, mut s_2) = (r_table, l);
+        let commitment = inner_pairing_product(row_commitments, &self.gamma_2);
+        let mut transcript = Self::transcript(&commitment, r, y, &e_1, &c);
+        let mut rounds = vec![];
+        while v_1.len() > 1 {
+            let half = v_1.len() / 2;
+            let (gamma_1, gamma_2) = (&self.gamma_1[..2 * half], &self.gamma_2[..2 * half]);
+            let (next_1, next_2) = (&gamma_1[..half], &gamma_2[..half]);
+            let mut round = DoryRound {
+                d_1l: inner_pairing_product(&v_1[..half], next_2),
+                d_1r: inner_pairing_product(&v_1[half..], next_2),
+                d_2l: inner_pairing_product(next_1, &v_2[..half]),
+                d_2r: inner_pairing_product(next_1, &v_2[half..]),
+                e_1beta: msm(gamma_1, &s_2).unwrap(),
+                e_2beta: msm(gamma_2, &s_1).unwrap(),
+                c_plus: PairingOutput::zero(),
+                c_minus: PairingOutput::zero(),
+                e_1plus: E::G1::zero(),
+                e_1minus: E::G1::zero(),
+                e_2plus: E::G2::zero(),
+                e_2minus: E::G2::zero(),
+            };
+            let beta = Self::beta(&mut transcript, &round);
+            let beta_inv = beta.inverse().unwrap();
+            for (v, g) in v_1.iter_mut().zip(gamma_1) {
+                *v += *g * beta;
+            }
+            for (v, g) in v_2.iter_mut().zip(gamma_2) {
+                *v += *g * beta_inv;
+            }
+            round.c_plus = inner_pairing_product(&v_1[..half], &v_2[half..]);
+            round.c_minus = inner_pairing_product(&v_1[half..], &v_2[..half]);
+            round.e_1plus = msm(&v_1[..half], &s_2[half..]).unwrap();
+            round.e_1minus = msm(&v_1[half..], &s_2[..half]).unwrap();
+            round.e_2plus = msm(&v_2[half..], &s_1[..half]).unwrap();
+            round.e_2minus = msm(&v_2[..half], &s_1[half..]).unwrap();
+            let alpha = Self::alpha(&mut transcript, &round);
+            let alpha_inv = alpha.inverse().unwrap();
+            v_1 = fold(&v_1, alpha);
+            v_2 = fold(&v_2, alpha_inv);
+            s_1 = fold(&s_1, alpha);
+            s_2 = fold(&s_2, alpha_inv);
+            rounds.push(round);
+        }
+        let proof = DoryProof {
+            e_1,
+            c,
+            rounds,
+            v_1: v_1[0],
+            v_2: v_2[0],
+        };
+        (proof, y)
+    }
+
+    /// Verifies that the mle committed in `commitment` evaluates to y at r, with O(log n) work
+    pub fn verify(
+        &self,
+        commitment: &PairingOutput<E>,
+        r: &[E::ScalarField],
+        y: E::ScalarField,
+        proof: &DoryProof<E>,
+    ) -> bool {
+        let k = self.num_rounds();
+        if r.len() != self.num_vars || proof.rounds.len() != k {
+            return false;
+        }
+        let (r_rows, r_cols) = self.split_point(r);
+        let (g_1, h_2) = (self.gamma_1[0], self.gamma_2[0]);
+        let mut c = proof.c;
+        let mut d_1 = *commitment;
+        let mut d_2 = E::pairing(proof.e_1, h_2);
+        let mut e_1 = proof.e_1;
+        let mut e_2 = h_2 * y;
+        // L and R folded so far, both being tensor products: alpha * s_L + s_R = (alpha * (1 - x) + x) * s'
+        let (mut s_1, mut s_2) = (E::ScalarField::one(), E::ScalarField::one());
+        let mut transcript = Self::transcript(commitment, r, y, &proof.e_1, &proof.c);
+        for (i, round) in proof.rounds.iter().enumerate() {
+            let beta = Self::beta(&mut transcript, round);
+            let alpha = Self::alpha(&mut transcript, round);
+            let (beta_inv, alpha_inv) = match (beta.inverse(), alpha.inverse()) {
+                (Some(beta_inv), Some(alpha_inv)) => (beta_inv, alpha_inv),
+                _ => return false,
+            };
+            c += self.chi[i]
+                + d_2 * beta
+                + d_1 * beta_inv
+                + round.c_plus * alpha
+                + round.c_minus * alpha_inv;
+            d_1 = round.d_1l * alpha
+                + round.d_1r
+                + self.chi[i + 1] * (alpha * beta)
+                + self.delta_1r[i] * beta;
+            d_2 = round.d_2l * alpha_inv
+                + round.d_2r
+                + self.chi[i + 1] * (alpha_inv * beta_inv)
+                + self.delta_2r[i] * beta_inv;
+            e_1 += round.e_1beta * beta + round.e_1plus * alpha + round.e_1minus * alpha_inv;
+            e_2 += round.e_2beta * beta_inv + round.e_2plus * alpha + round.e_2minus * alpha_inv;
+            // each round folds the top variable
+            let (x_1, x_2) = (r_cols[k - 1 - i], r_rows[k - 1 - i]);
+            s_1 *= alpha * (E::ScalarField::one() - x_1) + x_1;
+            s_2 *= alpha_inv * (E::ScalarField::one() - x_2) + x_2;
+        }
+        c == E::pairing(proof.v_1, proof.v_2)
+            && d_1 == E::pairing(proof.v_1, h_2)
+            && d_2 == E::pairing(g_1, proof.v_2)
+            && e_1 == proof.v_1 * s_2
+            && e_2 == proof.v_2 * s_1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dory;
+    use ark_bn254::{Bn254, Fr};
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_dory() {
+        let mut rng = test_rng();
+        // odd number of variables: 8 rows of 4 columns, padded to 8
+        for num_vars in [5, 4] {
+            let dory = Dory::<Bn254>::new(num_vars);
+            let mle = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+            let (commitment, row_commitments) = dory.commit(&mle);
+            let r = (0..num_vars)
+                .map(|_| Fr::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let (proof, y) = dory.open(&mle, &row_commitments, &r);
+            assert_eq!(y, mle.evaluate(&r).unwrap());
+            assert_eq!(proof.rounds.len(), num_vars - num_vars / 2);
+            assert!(dory.verify(&commitment, &r, y, &proof));
+
+            // wrong evaluation, point, or proof
+            assert!(!dory.verify(&commitment, &r, y + Fr::one(), &proof));
+            let mut wrong_r = r.clone();
+            wrong_r[0] += Fr::one();
+            assert!(!dory.verify(&commitment, &wrong_r, y, &proof));
+            let mut wrong_proof = proof.clone();
+            wrong_proof.rounds[0].e_1plus += wrong_proof.v_1;
+            assert!(!dory.verify(&commitment, &r, y, &wrong_proof));
+
+            // an honest opening of another mle against this commitment
+            let other = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+            let (other_commitment, other_rows) = dory.commit(&other);
+            let (other_proof, other_y) = dory.open(&other, &other_rows, &r);
+            assert!(dory.verify(&other_commitment, &r, other_y, &other_proof));
+            assert!(!dory.verify(&commitment, &r, other_y, &other_proof));
+        }
+    }
+}
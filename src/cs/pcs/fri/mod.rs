@@ -0,0 +1,430 @@
+// FRI low degree test and polynomial commitment: https://eccc.weizmann.ac.il/report/2017/134/
+// A polynomial of degree < k is committed to with the merkle root of its evaluations over a domain of size
+// n = k * blowup_factor (the codeword). Each folding round halves the degree bound and the domain:
+// f'(x^2) = (f(x) + f(-x)) / 2 + beta * (f(x) - f(-x)) / (2x)
+// After log(k) rounds the folded codeword is a constant, sent in the clear. The verifier then checks
+// the folding at `num_queries` random positions, each query catching a far-from-low-degree codeword with
+// probability roughly 1 - 1 / blowup_factor.
+// Openings at z prove that the quotient (f(X) - y) / (X - z) is low degree, its evaluations being tied to f's.
+use ark_ff::{Field, PrimeField};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::cs::merkle::{verify_path, Digest, KeccakHasher, MerkleTree};
+use crate::transcript::Transcript;
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct FRI<F: PrimeField> {
+    degree: usize,
+    blowup_factor: usize,
+    num_queries: usize,
+    /// Evaluation domain of the codewords, of size (degree + 1).next_power_of_two() * blowup_factor
+    domain: GeneralEvaluationDomain<F>,
+}
+
+/// Openings of a pair of symmetric positions (x, -x) of a committed codeword
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FRIQueryLayer<F: PrimeField> {
+    pub values: (F, F),
+    pub paths: (Vec<Digest>, Vec<Digest>),
+}
+
+/// A fri low degree proof: the roots of the folded codewords, the final constant, and for every query
+/// the openings of each layer
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FRIProof<F: PrimeField> {
+    pub layer_roots: Vec<Digest>,
+    pub final_value: F,
+    pub queries: Vec<Vec<FRIQueryLayer<F>>>,
+}
+
+/// An opening proof: a low degree proof for the quotient, and openings of the committed codeword
+/// at the positions queried in the quotient's first layer
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FRIOpeningProof<F: PrimeField> {
+    pub quotient_proof: FRIProof<F>,
+    pub queries: Vec<FRIQueryLayer<F>>,
+}
+
+/// Folds f and f(-x) at x: (f(x) + f(-x)) / 2 + beta * (f(x) - f(-x)) / (2x)
+fn fold_pair<F: Field>(f_x: F, f_minus_x: F, beta: F, x_inv: F) -> F {
+    let two_inv = F::from(2u64).inverse().unwrap();
+    two_inv * (f_x + f_minus_x + beta * (f_x - f_minus_x) * x_inv)
+}
+
+/// Folds a codeword over the domain generated by `generator`, since generator^{n/2} = -1, f(-x) sits n/2 positions away
+fn fold<F: Field>(codeword: &[F], beta: F, generator: F) -> Vec<F> {
+    let half = codeword.len() / 2;
+    let generator_inv = generator.inverse().unwrap();
+    let mut x_inv = F::one();
+    let mut folded = Vec::with_capacity(half);
+    for i in 0..half {
+        folded.push(fold_pair(codeword[i], codeword[i + half], beta, x_inv));
+        x_inv *= generator_inv;
+    }
+    folded
+}
+
+/// Opens positions p and p + n/2 of a committed codeword
+fn open_pair<F: PrimeField>(
+    codeword: &[F],
+    tree: &MerkleTree<F, KeccakHasher>,
+    p: usize,
+) -> FRIQueryLayer<F> {
+    let half = codeword.len() / 2;
+    FRIQueryLayer {
+        values: (codeword[p], codeword[p + half]),
+        paths: (tree.open(p), tree.open(p + half)),
+    }
+}
+
+fn verify_pair<F: PrimeField>(
+    root: &Digest,
+    layer: &FRIQueryLayer<F>,
+    p: usize,
+    half: usize,
+) -> bool {
+    // the layer's codeword has 2 * half values
+    let depth = (2 * half).trailing_zeros() as usize;
+    verify_path(
+        &KeccakHasher,
+        root,
+        depth,
+        p,
+        &layer.values.0,
+        &layer.paths.0,
+    ) && verify_path(
+        &KeccakHasher,
+        root,
+        depth,
+        p + half,
+        &layer.values.1,
+        &layer.paths.1,
+    )
+}
+
+impl<F: PrimeField> FRI<F> {
+    pub fn new(degree: usize, blowup_factor: usize, num_queries: usize) -> Self {
+        assert!(
+            blowup_factor.is_power_of_two() && blowup_factor >= 2,
+            "blowup factor must be a power of two, at least 2"
+        );
+        let domain_size = (degree + 1).next_power_of_two() * blowup_factor;
+        let domain = GeneralEvaluationDomain::<F>::new(domain_size).unwrap();
+        assert_eq!(domain.size(), domain_size);
+        Self {
+            degree,
+            blowup_factor,
+            num_queries,
+            domain,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    pub fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.domain
+    }
+
+    /// Number of folding rounds, after which the codeword is constant
+    fn num_rounds(&self) -> usize {
+        (self.domain.size() / self.blowup_factor).trailing_zeros() as usize
+    }
+
+    /// Evaluates `polynomial` over the domain
+    pub fn encode(&self, polynomial: &DensePolynomial<F>) -> Vec<F> {
+        assert!(
+            polynomial.degree() <= self.degree,
+            "polynomial degree exceeds the fri degree"
+        );
+        self.domain.fft(&polynomial.coeffs)
+    }
+
+    /// Commits to `polynomial` with the merkle root of its codeword
+    pub fn commit(&self, polynomial: &DensePolynomial<F>) -> Digest {
+        MerkleTree::new(KeccakHasher, &self.encode(polynomial)).root()
+    }
+
+    fn prove_with_transcript(
+        &self,
+        codeword: Vec<F>,
+        transcript: &mut Transcript,
+    ) -> (FRIProof<F>, Vec<usize>) {
+        let mut layers = vec![];
+        let mut trees = vec![];
+        let mut layer_roots = vec![];
+        let mut codeword = codeword;
+        let mut generator = self.domain.group_gen();
+        for _ in 0..self.num_rounds() {
+            let tree = MerkleTree::new(KeccakHasher, &codeword);
+            transcript.absorb(&tree.root()[..]);
+            let beta = transcript.challenge();
+            let folded = fold(&codeword, beta, generator);
+            layer_roots.push(tree.root());
+            layers.push(codeword);
+            trees.push(tree);
+            codeword = folded;
+            generator.square_in_place();
+        }
+        let final_value = codeword[0];
+        transcript.absorb(&final_value);
+
+        let indices = (0..self.num_queries)
+            .map(|_| transcript.challenge_index(self.domain.size()))
+            .collect::<Vec<_>>();
+        let queries = indices
+            .iter()
+            .map(|index| {
+                let mut index = *index;
+                layers
+                    .iter()
+                    .zip(trees.iter())
+                    .map(|(layer, tree)| {
+                        index %= layer.len() / 2;
+                        open_pair(layer, tree, index)
+                    })
+                    .collect()
+            })
+            .collect();
+        let proof = FRIProof {
+            layer_roots,
+            final_value,
+            queries,
+        };
+        (proof, indices)
+    }
+
+    /// Returns the queried positions if the proof is valid
+    fn verify_with_transcript(
+        &self,
+        proof: &FRIProof<F>,
+        transcript: &mut Transcript,
+    ) -> Option<Vec<usize>> {
+        let num_rounds = self.num_rounds();
+        if proof.layer_roots.len() != num_rounds
+            || proof.queries.len() != self.num_queries
+            || proof.queries.iter().any(|q| q.len() != num_rounds)
+        {
+            return None;
+        }
+        let mut betas = vec![];
+        for root in proof.layer_roots.iter() {
+            transcript.absorb(&root[..]);
+            betas.push(transcript.challenge::<F>());
+        }
+        transcript.absorb(&proof.final_value);
+        let indices = (0..self.num_queries)
+            .map(|_| transcript.challenge_index(self.domain.size()))
+            .collect::<Vec<_>>();
+
+        for (query, index) in proof.queries.iter().zip(indices.iter()) {
+            let mut index = *index;
+            let mut size = self.domain.size();
+            let mut generator = self.domain.group_gen();
+            for (i, layer) in query.iter().enumerate() {
+                let half = size / 2;
+                index %= half;
+                if !verify_pair(&proof.layer_roots[i], layer, index, half) {
+                    return None;
+                }
+                let x_inv = generator.pow([index as u64]).inverse().unwrap();
+                let folded = fold_pair(layer.values.0, layer.values.1, betas[i], x_inv);
+                // the folded value sits at `index` in the next layer, within its opened pair
+                let expected = match query.get(i + 1) {
+                    Some(next) if index < half / 2 => next.values.0,
+                    Some(next) => next.values.1,
+                    None => proof.final_value,
+                };
+                if folded != expected {
+                    return None;
+                }
+                size = half;
+                generator.square_in_place();
+            }
+        }
+        Some(indices)
+    }
+
+    /// Proves that `polynomial` is of degree at most (degree + 1).next_power_of_two() - 1
+    pub fn prove_low_degree(&self, polynomial: &DensePolynomial<F>) -> FRIProof<F> {
+        self.prove_with_transcript(self.encode(polynomial), &mut Transcript::new(b"fri"))
+            .0
+    }
+
+    /// Checks a low degree proof for the codeword committed in its first layer root
+    pub fn verify_low_degree(&self, proof: &FRIProof<F>) -> bool {
+        self.verify_with_transcript(proof, &mut Transcript::new(b"fri"))
+            .is_some()
+    }
+
+    /// Opens `polynomial` at z, returns the proof and y = p(z). z must lie outside of the domain.
+    pub fn open(&self, polynomial: &DensePolynomial<F>, z: F) -> (FRIOpeningProof<F>, F) {
+        assert!(
+            !self.domain.evaluate_vanishing_polynomial(z).is_zero(),
+            "z must lie outside of the evaluation domain"
+        );
+        let y = polynomial.evaluate(&z);
+        let codeword = self.encode(polynomial);
+        let tree = MerkleTree::new(KeccakHasher, &codeword);
+
+        // q(x) = (f(x) - y) / (x - z), over the domain
+        let mut denominators = self.domain.elements().map(|x| x - z).collect::<Vec<_>>();
+        ark_ff::batch_inversion(&mut denominators);
+        let quotient = codeword
+            .iter()
+            .zip(denominators)
+            .map(|(f_x, d)| (*f_x - y) * d)
+            .collect();
+
+        let mut transcript = Transcript::new(b"fri");
+        transcript.absorb(&tree.root()[..]);
+        transcript.absorb(&z);
+        transcript.absorb(&y);
+        let (quotient_proof, indices) = self.prove_with_transcript(quotient, &mut transcript);
+        let queries = indices
+            .iter()
+            .map(|index| open_pair(&codeword, &tree, index % (codeword.len() / 2)))
+            .collect();
+        let proof = FRIOpeningProof {
+            quotient_proof,
+            queries,
+        };
+        (proof, y)
+    }
+
+    /// Verifies that the polynomial committed in `commitment` evaluates to y at z
+    pub fn verify(&self, commitment: &Digest, z: F, y: F, proof: &FRIOpeningProof<F>) -> bool {
+        if self.domain.evaluate_vanishing_polynomial(z).is_zero() {
+            return false;
+        }
+        let mut transcript = Transcript::new(b"fri");
+        transcript.absorb(&commitment[..]);
+        transcript.absorb(&z);
+        transcript.absorb(&y);
+        let indices = match self.verify_with_transcript(&proof.quotient_proof, &mut transcript) {
+            Some(indices) => indices,
+            None => return false,
+        };
+        if proof.queries.len() != indices.len() {
+            return false;
+        }
+        let half = self.domain.size() / 2;
+        for ((f_layer, q_layers), index) in proof
+            .queries
+            .iter()
+            .zip(proof.quotient_proof.queries.iter())
+            .zip(indices)
+        {
+            let p = index % half;
+            if !verify_pair(commitment, f_layer, p, half) {
+                return false;
+            }
+            // the quotient's openings at x and -x must match (f(x) - y) / (x - z)
+            let x = self.domain.element(p);
+            // without folding rounds, the quotient's codeword is the constant final value
+            let (q_x, q_minus_x) = match q_layers.first() {
+                Some(q_layer) => q_layer.values,
+                None => {
+                    let q = proof.quotient_proof.final_value;
+                    (q, q)
+                }
+            };
+            if (f_layer.values.0 - y) != q_x * (x - z)
+                || (f_layer.values.1 - y) != q_minus_x * (-x - z)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FRI;
+    use crate::transcript::Transcript;
+    use ark_bn254::Fr;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_fri_low_degree() {
+        let mut rng = test_rng();
+        let degree = 31;
+        let fri = FRI::<Fr>::new(degree, 4, 16);
+        assert_eq!(fri.domain().size(), 128);
+
+        let polynomial = DensePolynomial::<Fr>::rand(degree, &mut rng);
+        let proof = fri.prove_low_degree(&polynomial);
+        assert_eq!(proof.layer_roots[0], fri.commit(&polynomial));
+        assert!(fri.verify_low_degree(&proof));
+
+        // a codeword of too high degree: the final layer isn't constant and queries catch it
+        let high_degree = DensePolynomial::<Fr>::rand(4 * degree, &mut rng);
+        let fri_high = FRI::<Fr>::new(4 * degree, 4, 16);
+        let codeword = fri_high.encode(&high_degree);
+        let cheating_fri = FRI::<Fr>::new(degree, 4, 16);
+        let mut transcript = Transcript::new(b"fri");
+        let (cheating_proof, _) =
+            cheating_fri.prove_with_transcript(codeword[..128].to_vec(), &mut transcript);
+        assert!(!cheating_fri.verify_low_degree(&cheating_proof));
+
+        // tampered final value
+        let mut wrong_proof = proof.clone();
+        wrong_proof.final_value += Fr::one();
+        assert!(!fri.verify_low_degree(&wrong_proof));
+    }
+
+    #[test]
+    pub fn test_fri_opening() {
+        let mut rng = test_rng();
+        let degree = 15;
+        let fri = FRI::<Fr>::new(degree, 8, 20);
+        let polynomial = DensePolynomial::<Fr>::rand(degree, &mut rng);
+        let commitment = fri.commit(&polynomial);
+        let z = Fr::rand(&mut rng);
+        let (proof, y) = fri.open(&polynomial, z);
+        assert_eq!(y, polynomial.evaluate(&z));
+        assert!(fri.verify(&commitment, z, y, &proof));
+
+        // wrong evaluation, point, or commitment
+        assert!(!fri.verify(&commitment, z, y + Fr::one(), &proof));
+        assert!(!fri.verify(&commitment, z + Fr::one(), y, &proof));
+        let other_commitment = fri.commit(&DensePolynomial::<Fr>::rand(degree, &mut rng));
+        assert!(!fri.verify(&other_commitment, z, y, &proof));
+        // z within the domain
+        assert!(!fri.verify(
+            &commitment,
+            Fr::one(),
+            polynomial.evaluate(&Fr::one()),
+            &proof
+        ));
+    }
+
+    #[test]
+    pub fn test_fri_constant_opening() {
+        // degree 0: no folding round, the quotient is checked against its final value
+        let mut rng = test_rng();
+        let fri = FRI::<Fr>::new(0, 4, 8);
+        let polynomial = DensePolynomial::<Fr>::rand(0, &mut rng);
+        let commitment = fri.commit(&polynomial);
+        let z = Fr::rand(&mut rng);
+        let (proof, y) = fri.open(&polynomial, z);
+        assert!(proof.quotient_proof.queries.iter().all(|q| q.is_empty()));
+        assert!(fri.verify(&commitment, z, y, &proof));
+        assert!(!fri.verify(&commitment, z, y + Fr::one(), &proof));
+    }
+}
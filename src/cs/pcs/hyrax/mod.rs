@@ -0,0 +1,167 @@
+// Hyrax multilinear commitment: https://eprint.iacr.org/2017/1132 (section 6)
+// The 2^n evaluations of an mle are laid out as a 2^{n - m} x 2^m matrix M, where the m low variables index
+// the columns and the n - m high variables index the rows. Each row is committed to with pedersen,
+// so commitments hold sqrt(2^n) points.
+// Since f(r) = L^T M R, with L = eq(r_rows, .) and R = eq(r_cols, .), the prover opens with u = L^T M
+// and the verifier checks sum_j L_j * C_j == <u, G> + b * H and y = <u, R>: both sides are sqrt-sized.
+use ark_ec::CurveGroup;
+use ark_ff::Zero;
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{rand::Rng, UniformRand};
+
+use crate::cs::pedersen::Pedersen;
+use crate::ip::sumcheck::eq_evaluations;
+use crate::utils::inner_product;
+
+pub struct Hyrax<C: CurveGroup> {
+    num_vars: usize,
+    /// Number of column variables, m = n / 2
+    num_col_vars: usize,
+    /// Pedersen parameters, one generator per column
+    pedersen: Pedersen<C>,
+}
+
+/// Row commitments, with the blinding factors kept by the prover
+#[derive(Clone, Debug, PartialEq)]
+pub struct HyraxCommitment<C: CurveGroup> {
+    pub row_commitments: Vec<C>,
+}
+
+/// An opening proof: u = L^T M and the combined blinding factor b = sum_j L_j * b_j
+#[derive(Clone, Debug, PartialEq)]
+pub struct HyraxProof<C: CurveGroup> {
+    pub u: Vec<C::ScalarField>,
+    pub blinding: C::ScalarField,
+}
+
+impl<C: CurveGroup> Hyrax<C> {
+    pub fn new(num_vars: usize) -> Self {
+        let num_col_vars = num_vars / 2;
+        Self {
+            num_vars,
+            num_col_vars,
+            pedersen: Pedersen::setup(1 << num_col_vars),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn pedersen(&self) -> &Pedersen<C> {
+        &self.pedersen
+    }
+
+    fn num_cols(&self) -> usize {
+        1 << self.num_col_vars
+    }
+
+    /// Commits to each row of the evaluation matrix, returns the commitment and the row blinding factors
+    pub fn commit<R: Rng>(
+        &self,
+        mle: &DenseMultilinearExtension<C::ScalarField>,
+        rng: &mut R,
+    ) -> (HyraxCommitment<C>, Vec<C::ScalarField>) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        let mut row_commitments = vec![];
+        let mut blindings = vec![];
+        for row in mle.evaluations.chunks(self.num_cols()) {
+            let blinding = C::ScalarField::rand(rng);
+            row_commitments.push(self.pedersen.commit(row, &blinding));
+            blindings.push(blinding);
+        }
+        (HyraxCommitment { row_commitments }, blindings)
+    }
+
+    /// Opens `mle` at r, returns the proof and y = f(r)
+    pub fn open(
+        &self,
+        mle: &DenseMultilinearExtension<C::ScalarField>,
+        blindings: &[C::ScalarField],
+        r: &[C::ScalarField],
+    ) -> (HyraxProof<C>, C::ScalarField) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        assert_eq!(r.len(), self.num_vars);
+        let (r_cols, r_rows) = r.split_at(self.num_col_vars);
+        let l = eq_evaluations(r_rows);
+        let mut u = vec![C::ScalarField::zero(); self.num_cols()];
+        for (l_j, row) in l.iter().zip(mle.evaluations.chunks(self.num_cols())) {
+            for (u_i, m_ji) in u.iter_mut().zip(row) {
+                *u_i += *l_j * m_ji;
+            }
+        }
+        let y = inner_product(&u, &eq_evaluations(r_cols));
+        let blinding = inner_product(&l, blindings);
+        (HyraxProof { u, blinding }, y)
+    }
+
+    /// Verifies that the mle committed in `commitment` evaluates to y at r
+    pub fn verify(
+        &self,
+        commitment: &HyraxCommitment<C>,
+        r: &[C::ScalarField],
+        y: C::ScalarField,
+        proof: &HyraxProof<C>,
+    ) -> bool {
+        let num_rows = 1 << (self.num_vars - self.num_col_vars);
+        if r.len() != self.num_vars
+            || commitment.row_commitments.len() != num_rows
+            || proof.u.len() != self.num_cols()
+        {
+            return false;
+        }
+        let (r_cols, r_rows) = r.split_at(self.num_col_vars);
+        let l = eq_evaluations(r_rows);
+        let bases = C::normalize_batch(&commitment.row_commitments);
+        let combined_commitment = C::msm(&bases, &l).unwrap();
+        let expected = self.pedersen.commit(&proof.u, &proof.blinding);
+        combined_commitment == expected && inner_product(&proof.u, &eq_evaluations(r_cols)) == y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hyrax;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::{Fr, Projective};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_hyrax() {
+        let mut rng = test_rng();
+        // odd number of variables: 8 rows of 4 columns
+        let num_vars = 5;
+        let hyrax = Hyrax::<Projective>::new(num_vars);
+        assert_eq!(hyrax.pedersen().size(), 4);
+
+        let mle = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+        let (commitment, blindings) = hyrax.commit(&mle, &mut rng);
+        assert_eq!(commitment.row_commitments.len(), 8);
+
+        let r = (0..num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (proof, y) = hyrax.open(&mle, &blindings, &r);
+        assert_eq!(y, mle.evaluate(&r).unwrap());
+        assert!(hyrax.verify(&commitment, &r, y, &proof));
+
+        // opening at a point of the hypercube gives back the evaluation
+        let point = [Fr::one(), Fr::from(0), Fr::one(), Fr::one(), Fr::from(0)];
+        let (proof_point, y_point) = hyrax.open(&mle, &blindings, &point);
+        assert_eq!(y_point, mle.evaluations[0b01101]);
+        assert!(hyrax.verify(&commitment, &point, y_point, &proof_point));
+
+        // wrong evaluation, point, proof or commitment
+        assert!(!hyrax.verify(&commitment, &r, y + Fr::one(), &proof));
+        let mut wrong_r = r.clone();
+        wrong_r[4] += Fr::one();
+        assert!(!hyrax.verify(&commitment, &wrong_r, y, &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.u[0] += Fr::one();
+        assert!(!hyrax.verify(&commitment, &r, y, &wrong_proof));
+        let mut wrong_commitment = commitment.clone();
+        wrong_commitment.row_commitments.swap(0, 1);
+        assert!(!hyrax.verify(&wrong_commitment, &r, y, &proof));
+    }
+}
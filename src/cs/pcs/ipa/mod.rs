@@ -0,0 +1,331 @@
+// Inner product argument polynomial commitment, as used in Halo: https://eprint.iacr.org/2019/1021 (section 3)
+// Transparent setup (generators are hashed to the curve), commitments are a single point and
+// opening proofs hold 2 * log(n) points. Intended for the pallas/vesta cycle.
+// Opening p at z amounts to proving <a, b> = y, where a are the coefficients of p and b = (1, z, z^2, ...).
+// Each round halves the vectors: a' = a_lo * u + a_hi * u^-1, b' = b_lo * u^-1 + b_hi * u, G' = G_lo * u^-1 + G_hi * u
+// After k = log(n) rounds, b' = h(z) and G' = <s, G> = [h(X)], with h(X) = prod_j (u_j^-1 + u_j * X^{2^{k-1-j}})
+// Computing G' is the only linear time work of the verifier: the amortized verifier defers it into an accumulator.
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ff::{Field, One, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+
+use crate::cs::pedersen::derive_generators;
+use crate::transcript::Transcript;
+use crate::utils::{
+    curve::{check_subgroup_all, msm, SubgroupCheck},
+    inner_product,
+};
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct IPA<P: SWCurveConfig> {
+    degree: usize,
+    /// Commitment key, of size n = (degree + 1).next_power_of_two()
+    generators: Vec<Projective<P>>,
+    /// Generator binding the claimed inner product
+    u: Projective<P>,
+}
+
+/// An ipa opening proof: the L_j, R_j points of every round, the final a and the final G
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IPAProof<P: SWCurveConfig> {
+    pub l_vec: Vec<Projective<P>>,
+    pub r_vec: Vec<Projective<P>>,
+    pub a: P::ScalarField,
+    pub g_final: Projective<P>,
+}
+
+/// The deferred part of an ipa verification: checks that g = [h(X)], h being defined by the round challenges
+#[derive(Clone, Debug, PartialEq)]
+pub struct IPAAccumulator<P: SWCurveConfig> {
+    pub challenges: Vec<P::ScalarField>,
+    pub g: Projective<P>,
+}
+
+impl<P: SWCurveConfig> IPAAccumulator<P> {
+    /// Evaluates h(X) = prod_j (u_j^-1 + u_j * X^{2^{k-1-j}}) at x, in O(log n)
+    pub fn evaluate_h(&self, x: P::ScalarField) -> P::ScalarField {
+        let k = self.challenges.len();
+        let mut result = P::ScalarField::one();
+        for (j, u_j) in self.challenges.iter().enumerate() {
+            let x_pow = x.pow([1u64 << (k - 1 - j)]);
+            result *= u_j.inverse().unwrap() + *u_j * x_pow;
+        }
+        result
+    }
+
+    /// The coefficients s of h(X): s_i = prod_j u_j^{b_j}, b_j = 1 if bit k-1-j of i is set, -1 otherwise
+    pub fn h_coefficients(&self) -> Vec<P::ScalarField> {
+        let mut s = vec![P::ScalarField::one()];
+        for u_j in self.challenges.iter().rev() {
+            let u_j_inv = u_j.inverse().unwrap();
+            s = s
+                .iter()
+                .map(|s_i| *s_i * u_j_inv)
+                .chain(s.iter().map(|s_i| *s_i * u_j))
+                .collect();
+        }
+        s
+    }
+
+    pub fn h_polynomial(&self) -> DensePolynomial<P::ScalarField> {
+        DensePolynomial::from_coefficients_vec(self.h_coefficients())
+    }
+}
+
+/// Absorbs the statement and derives xi, which binds the claimed inner product to U
+fn statement_challenge<P: SWCurveConfig>(
+    transcript: &mut Transcript,
+    commitment: Projective<P>,
+    z: P::ScalarField,
+    y: P::ScalarField,
+) -> P::ScalarField {
+    transcript.absorb(&commitment);
+    transcript.absorb(&[z, y]);
+    transcript.challenge()
+}
+
+impl<P: SWCurveConfig> IPA<P> {
+    /// Transparent setup: the generators are derived from `seed` and nobody knows their discrete logs
+    pub fn new(degree: usize, seed: &[u8]) -> Self {
+        let n = (degree + 1).next_power_of_two();
+        let mut generators = derive_generators(seed, n + 1);
+        let u = generators.pop().unwrap();
+        Self {
+            degree,
+            generators,
+            u,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn generators(&self) -> &[Projective<P>] {
+        &self.generators
+    }
+
+    pub fn u(&self) -> Projective<P> {
+        self.u
+    }
+
+    /// Pads the coefficients of `polynomial` with zeros, up to the size of the commitment key
+    fn padded_coefficients(
+        &self,
+        polynomial: &DensePolynomial<P::ScalarField>,
+    ) -> Vec<P::ScalarField> {
+        assert!(
+            polynomial.coeffs.len() <= self.generators.len(),
+            "polynomial degree exceeds the setup degree"
+        );
+        let mut coeffs = polynomial.coeffs.clone();
+        coeffs.resize(self.generators.len(), P::ScalarField::zero());
+        coeffs
+    }
+
+    pub fn commit(&self, polynomial: &DensePolynomial<P::ScalarField>) -> Projective<P> {
+        msm(&self.generators, &self.padded_coefficients(polynomial)).unwrap()
+    }
+
+    /// Opens `polynomial` (committed to in `commitment`) at z, returns the proof and y = p(z)
+    pub fn open(
+        &self,
+        polynomial: &DensePolynomial<P::ScalarField>,
+        commitment: Projective<P>,
+        z: P::ScalarField,
+    ) -> (IPAProof<P>, P::ScalarField) {
+        let y = polynomial.evaluate(&z);
+        let mut transcript = Transcript::new(b"ipa");
+        let xi = statement_challenge(&mut transcript, commitment, z, y);
+        let u = self.u * xi;
+
+        let mut a = self.padded_coefficients(polynomial);
+        let mut b = (0..a.len()).map(|i| z.pow([i as u64])).collect::<Vec<_>>();
+        let mut g = self.generators.clone();
+        let mut l_vec = vec![];
+        let mut r_vec = vec![];
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_lo, a_hi) = a.split_at(half);
+            let (b_lo, b_hi) = b.split_at(half);
+            let (g_lo, g_hi) = g.split_at(half);
+            let l = msm(g_hi, a_lo).unwrap() + u * inner_product(a_lo, b_hi);
+            let r = msm(g_lo, a_hi).unwrap() + u * inner_product(a_hi, b_lo);
+            transcript.absorb(&[l, r]);
+            let u_j: P::ScalarField = transcript.challenge();
+            let u_j_inv = u_j.inverse().unwrap();
+
+            a = (0..half)
+                .map(|i| a_lo[i] * u_j + a_hi[i] * u_j_inv)
+                .collect();
+            b = (0..half)
+                .map(|i| b_lo[i] * u_j_inv + b_hi[i] * u_j)
+                .collect();
+            g = (0..half)
+                .map(|i| g_lo[i] * u_j_inv + g_hi[i] * u_j)
+                .collect();
+            l_vec.push(l);
+            r_vec.push(r);
+        }
+        let proof = IPAProof {
+            l_vec,
+            r_vec,
+            a: a[0],
+            g_final: g[0],
+        };
+        (proof, y)
+    }
+
+    /// Runs all the logarithmic work of the verifier, and defers the check g_final = [h(X)] into an accumulator
+    /// Returns None if the proof is invalid.
+    pub fn verify_amortized(
+        &self,
+        commitment: Projective<P>,
+        z: P::ScalarField,
+        y: P::ScalarField,
+        proof: &IPAProof<P>,
+    ) -> Option<IPAAccumulator<P>> {
+        let k = self.generators.len().trailing_zeros() as usize;
+        if proof.l_vec.len() != k || proof.r_vec.len() != k {
+            return None;
+        }
+        if !commitment.check_subgroup()
+            || !proof.g_final.check_subgroup()
+            || !check_subgroup_all(&proof.l_vec)
+            || !check_subgroup_all(&proof.r_vec)
+        {
+            return None;
+        }
+        let mut transcript = Transcript::new(b"ipa");
+        let xi = statement_challenge(&mut transcript, commitment, z, y);
+        let u = self.u * xi;
+
+        // P = C + y * U, then P' = u_j^2 * L_j + P + u_j^-2 * R_j at every round
+        let mut p = commitment + u * y;
+        let mut challenges = vec![];
+        for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+            transcript.absorb(&[*l, *r]);
+            let u_j: P::ScalarField = transcript.challenge();
+            let u_j_square = u_j.square();
+            p += *l * u_j_square + *r * u_j_square.inverse().unwrap();
+            challenges.push(u_j);
+        }
+        let accumulator = IPAAccumulator {
+            challenges,
+            g: proof.g_final,
+        };
+        let b = accumulator.evaluate_h(z);
+        if p != proof.g_final * proof.a + u * (proof.a * b) {
+            return None;
+        }
+        Some(accumulator)
+    }
+
+    /// Checks that the accumulator's g commits to h(X), in O(n)
+    pub fn check_accumulator(&self, accumulator: &IPAAccumulator<P>) -> bool {
+        if accumulator.challenges.len() != self.generators.len().trailing_zeros() as usize {
+            return false;
+        }
+        msm(&self.generators, &accumulator.h_coefficients()).is_ok_and(|g| g == accumulator.g)
+    }
+
+    /// Checks many accumulators with a single msm, using a random linear combination
+    pub fn check_accumulators<R: Rng>(
+        &self,
+        accumulators: &[IPAAccumulator<P>],
+        rng: &mut R,
+    ) -> bool {
+        let k = self.generators.len().trailing_zeros() as usize;
+        if accumulators.iter().any(|acc| acc.challenges.len() != k) {
+            return false;
+        }
+        let mut scalars = vec![P::ScalarField::zero(); self.generators.len()];
+        let mut expected = Projective::<P>::zero();
+        for accumulator in accumulators {
+            let r = P::ScalarField::rand(rng);
+            for (scalar, s_i) in scalars.iter_mut().zip(accumulator.h_coefficients()) {
+                *scalar += r * s_i;
+            }
+            expected += accumulator.g * r;
+        }
+        msm(&self.generators, &scalars).unwrap() == expected
+    }
+
+    /// Full verification: amortized verification followed by the accumulator check
+    pub fn verify(
+        &self,
+        commitment: Projective<P>,
+        z: P::ScalarField,
+        y: P::ScalarField,
+        proof: &IPAProof<P>,
+    ) -> bool {
+        self.verify_amortized(commitment, z, y, proof)
+            .is_some_and(|accumulator| self.check_accumulator(&accumulator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IPA;
+    use ark_ec::short_weierstrass::SWCurveConfig;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    fn check_ipa<P: SWCurveConfig + Clone>() {
+        let mut rng = test_rng();
+        let degree = 13;
+        let ipa = IPA::<P>::new(degree, b"ark-algorithms ipa test");
+        assert_eq!(ipa.generators().len(), 16);
+
+        let polynomial = DensePolynomial::<P::ScalarField>::rand(degree, &mut rng);
+        let commitment = ipa.commit(&polynomial);
+        let z = P::ScalarField::rand(&mut rng);
+        let (proof, y) = ipa.open(&polynomial, commitment, z);
+        assert_eq!(y, polynomial.evaluate(&z));
+        assert_eq!(proof.l_vec.len(), 4);
+        assert!(ipa.verify(commitment, z, y, &proof));
+
+        // the accumulator holds the challenge polynomial h(X), and g = [h(X)]
+        let accumulator = ipa.verify_amortized(commitment, z, y, &proof).unwrap();
+        let h = accumulator.h_polynomial();
+        assert_eq!(h.evaluate(&z), accumulator.evaluate_h(z));
+        assert_eq!(ipa.commit(&h), accumulator.g);
+
+        // wrong evaluation, wrong point, wrong commitment
+        assert!(!ipa.verify(commitment, z, y + P::ScalarField::one(), &proof));
+        assert!(!ipa.verify(commitment, z + P::ScalarField::one(), y, &proof));
+        assert!(!ipa.verify(commitment + ipa.u(), z, y, &proof));
+
+        // wrong g_final
+        let mut wrong_proof = proof.clone();
+        wrong_proof.g_final += ipa.u();
+        assert!(ipa
+            .verify_amortized(commitment, z, y, &wrong_proof)
+            .is_none());
+
+        // batched accumulator checks
+        let other_polynomial = DensePolynomial::<P::ScalarField>::rand(degree - 5, &mut rng);
+        let other_commitment = ipa.commit(&other_polynomial);
+        let (other_proof, other_y) = ipa.open(&other_polynomial, other_commitment, z);
+        let other_accumulator = ipa
+            .verify_amortized(other_commitment, z, other_y, &other_proof)
+            .unwrap();
+        let mut accumulators = vec![accumulator, other_accumulator];
+        assert!(ipa.check_accumulators(&accumulators, &mut rng));
+        accumulators[1].g += ipa.u();
+        assert!(!ipa.check_accumulators(&accumulators, &mut rng));
+    }
+
+    #[test]
+    pub fn test_ipa_pallas() {
+        check_ipa::<ark_pallas::PallasConfig>();
+    }
+
+    #[test]
+    pub fn test_ipa_vesta() {
+        check_ipa::<ark_vesta::VestaConfig>();
+    }
+}
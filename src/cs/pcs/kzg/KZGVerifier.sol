@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// KZG opening verifier over bn254, generated by ark-algorithms
+contract KZGVerifier {
+    uint256 constant R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 constant Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 constant G1_X = 1;
+    uint256 constant G1_Y = 2;
+    uint256 constant G2_X_C1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant G2_X_C0 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant G2_Y_C1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant G2_Y_C0 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+    uint256 constant VK_X_C1 = 14416758382573655613759630047087335987589204875210754033209020324436453375498;
+    uint256 constant VK_X_C0 = 16722841927989344655898393374113767041105098334265492728460727986419084209266;
+    uint256 constant VK_Y_C1 = 19764659414454002362799547518042310180729170041004855465975807056855544040871;
+    uint256 constant VK_Y_C0 = 2132887282801709298065386885510414923697597532538115303392982522462563197400;
+    uint256 constant CRS_LENGTH = 4;
+
+    function crs() internal pure returns (uint256[8] memory) {
+        return [uint256(1), 2, 18020261745151769346640321353202908298529952164951698635913853300840718605928, 21575249119971933833869389406942447477550630433937132884940024025820176450977, 2157354801154083793622186088911438753505929996417269979339265202433763015395, 3388748112166239414132446700042788089107969618051802992440899902205754537859, 13828377941534270966908413633893932531657936414137358670778789158990379440281, 5507154645474550636280850604927515292597497133870127068930966936169406861824];
+    }
+
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }
+        require(success, "ecAdd failed");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }
+        require(success, "ecMul failed");
+    }
+
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        if (p[0] == 0 && p[1] == 0) {
+            return p;
+        }
+        return [p[0], Q - (p[1] % Q)];
+    }
+
+    /// Checks e(a1, b1) * e(a2, b2) == 1
+    function pairing(uint256[2] memory a1, uint256[4] memory b1, uint256[2] memory a2, uint256[4] memory b2)
+        internal
+        view
+        returns (bool)
+    {
+        uint256[12] memory input = [a1[0], a1[1], b1[0], b1[1], b1[2], b1[3], a2[0], a2[1], b2[0], b2[1], b2[2], b2[3]];
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x08, input, 0x180, out, 0x20)
+        }
+        require(success, "pairing failed");
+        return out[0] == 1;
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs` (lowest degree first) at x, mod R
+    function evaluate(uint256[] calldata coeffs, uint256 x) internal pure returns (uint256 result) {
+        for (uint256 i = coeffs.length; i > 0; i--) {
+            result = addmod(mulmod(result, x, R), coeffs[i - 1], R);
+        }
+    }
+
+    /// Computes sum_i coeffs[i] * [tau^i]_1
+    function commit(uint256[] calldata coeffs) internal view returns (uint256[2] memory acc) {
+        uint256[8] memory points = crs();
+        for (uint256 i = 0; i < coeffs.length; i++) {
+            acc = ecAdd(acc, ecMul([points[2 * i], points[2 * i + 1]], coeffs[i]));
+        }
+    }
+
+    /// Single point opening: e(pi, vk) * e(-z * pi - commitment + y * g1, g2) == 1
+    function verify(uint256[2] calldata commitment, uint256 z, uint256 y, uint256[2] calldata pi)
+        external
+        view
+        returns (bool)
+    {
+        if (z >= R || y >= R) {
+            return false;
+        }
+        uint256[2] memory p = ecAdd(ecMul(pi, R - z), negate(commitment));
+        p = ecAdd(p, ecMul([G1_X, G1_Y], y));
+        return pairing(pi, [VK_X_C1, VK_X_C0, VK_Y_C1, VK_Y_C0], p, [G2_X_C1, G2_X_C0, G2_Y_C1, G2_Y_C0]);
+    }
+
+    /// Multi point opening: e([Z(tau)]_1, pi) * e(-commitment + [I(tau)]_1, g2) == 1
+    /// where I interpolates (zValues, yValues) and Z vanishes over zValues
+    function verifyMultiOpen(
+        uint256[2] calldata commitment,
+        uint256[] calldata zValues,
+        uint256[] calldata yValues,
+        uint256[] calldata lagrangeCoeffs,
+        uint256[] calldata zeroCoeffs,
+        uint256[4] calldata pi
+    ) external view returns (bool) {
+        if (zValues.length != yValues.length || zeroCoeffs.length != zValues.length + 1) {
+            return false;
+        }
+        if (lagrangeCoeffs.length > CRS_LENGTH || zeroCoeffs.length > CRS_LENGTH) {
+            return false;
+        }
+        for (uint256 i = 0; i < zValues.length; i++) {
+            if (evaluate(lagrangeCoeffs, zValues[i]) != yValues[i] % R || evaluate(zeroCoeffs, zValues[i]) != 0) {
+                return false;
+            }
+        }
+        uint256[2] memory zTau = commit(zeroCoeffs);
+        uint256[2] memory iTau = ecAdd(negate(commitment), commit(lagrangeCoeffs));
+        return pairing(zTau, pi, iTau, [G2_X_C1, G2_X_C0, G2_Y_C1, G2_Y_C0]);
+    }
+}
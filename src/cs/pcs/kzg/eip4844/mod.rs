@@ -0,0 +1,330 @@
+// EIP-4844 blob commitments, mirroring the consensus specs:
+// https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/polynomial-commitments.md
+// A blob holds 4096 bls12-381 scalars, the evaluations of a polynomial over the 4096-th roots of unity,
+// taken in bit-reversed order. Commitments and proofs are computed in evaluation form,
+// against the lagrange-basis srs of the KZG ceremony.
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, Group};
+use ark_ff::{Field, One, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::evm::{decode_field, encode_field};
+use super::KZG;
+use crate::utils::bit_reversal_permutation;
+
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+pub const BYTES_PER_COMMITMENT: usize = 48;
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// The trusted setup produced by the Ethereum KZG ceremony, in the format used by c-kzg:
+/// number of G1 points, number of G2 points, then hex encoded compressed G1 (lagrange basis) and G2 (monomial basis) points
+pub const ETHEREUM_TRUSTED_SETUP: &str = include_str!("trusted_setup.txt");
+
+pub type KZGCommitmentBytes = [u8; BYTES_PER_COMMITMENT];
+pub type KZGProofBytes = [u8; BYTES_PER_PROOF];
+pub type FieldElementBytes = [u8; BYTES_PER_FIELD_ELEMENT];
+
+/// KZG settings for blobs: a `KZG<Bls12_381>` holding the lagrange srs over the blob domain
+pub struct Eip4844 {
+    kzg: KZG<Bls12_381>,
+    domain: GeneralEvaluationDomain<Fr>,
+    /// The roots of unity, in bit-reversed order: blob[i] is the evaluation at roots_of_unity_brp[i]
+    roots_of_unity_brp: Vec<Fr>,
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim().trim_start_matches("0x");
+    if !hex.len().is_multiple_of(2) {
+        return Err("odd number of hex characters".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+impl Eip4844 {
+    /// Parses a trusted setup, in the c-kzg text format. All points are checked to be in the right subgroup.
+    pub fn parse_trusted_setup(trusted_setup: &str) -> Result<Self, String> {
+        let mut lines = trusted_setup.lines().filter(|l| !l.trim().is_empty());
+        let mut next_line = || lines.next().ok_or("trusted setup is too short".to_string());
+        let n_g1: usize = next_line()?
+            .trim()
+            .parse()
+            .map_err(|_| "invalid G1 count")?;
+        let n_g2: usize = next_line()?
+            .trim()
+            .parse()
+            .map_err(|_| "invalid G2 count")?;
+        if n_g1 != FIELD_ELEMENTS_PER_BLOB || n_g2 < 2 {
+            return Err(format!("unexpected trusted setup sizes: {n_g1}, {n_g2}"));
+        }
+        let mut g1_lagrange = Vec::with_capacity(n_g1);
+        for _ in 0..n_g1 {
+            let bytes = decode_hex(next_line()?)?;
+            let point = G1Affine::deserialize_compressed(&bytes[..]).map_err(|e| e.to_string())?;
+            g1_lagrange.push(G1Projective::from(point));
+        }
+        let mut g2_monomial = Vec::with_capacity(n_g2);
+        for _ in 0..n_g2 {
+            let bytes = decode_hex(next_line()?)?;
+            let point = G2Affine::deserialize_compressed(&bytes[..]).map_err(|e| e.to_string())?;
+            g2_monomial.push(point.into_group());
+        }
+
+        let domain = GeneralEvaluationDomain::<Fr>::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+        let roots_of_unity = domain.elements().collect::<Vec<_>>();
+        let kzg = KZG {
+            g1: G1Projective::generator(),
+            g2: g2_monomial[0],
+            degree: FIELD_ELEMENTS_PER_BLOB - 1,
+            crs: vec![],
+            crs_2: g2_monomial.clone(),
+            crs_lagrange: g1_lagrange,
+            vk: g2_monomial[1],
+        };
+        Ok(Self {
+            kzg,
+            domain,
+            roots_of_unity_brp: bit_reversal_permutation(&roots_of_unity),
+        })
+    }
+
+    /// Loads the Ethereum mainnet trusted setup
+    pub fn ethereum_trusted_setup() -> Result<Self, String> {
+        Self::parse_trusted_setup(ETHEREUM_TRUSTED_SETUP)
+    }
+
+    pub fn kzg(&self) -> &KZG<Bls12_381> {
+        &self.kzg
+    }
+
+    /// Decodes a blob into its 4096 evaluations (in bit-reversed order), rejecting non canonical scalars
+    pub fn blob_to_polynomial(&self, blob: &[u8]) -> Result<Vec<Fr>, String> {
+        if blob.len() != BYTES_PER_BLOB {
+            return Err(format!(
+                "expected {} bytes, got {}",
+                BYTES_PER_BLOB,
+                blob.len()
+            ));
+        }
+        blob.chunks(BYTES_PER_FIELD_ELEMENT)
+            .map(|chunk| decode_field::<Fr>(chunk).map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    /// Commits to evaluations given in bit-reversed order
+    fn commit_brp(&self, evals_brp: &[Fr]) -> G1Projective {
+        // bit reversal is an involution: this puts evaluations back in the domain's natural order
+        let evals = bit_reversal_permutation(evals_brp);
+        self.kzg.commit_evals(&evals, self.domain)
+    }
+
+    pub fn blob_to_kzg_commitment(&self, blob: &[u8]) -> Result<KZGCommitmentBytes, String> {
+        let polynomial = self.blob_to_polynomial(blob)?;
+        Ok(serialize_g1(&self.commit_brp(&polynomial)))
+    }
+
+    /// Evaluates the polynomial at z, with the barycentric formula when z is outside of the domain:
+    /// p(z) = (z^n - 1) / n * sum_i p_i * omega_i / (z - omega_i)
+    pub fn evaluate_polynomial_in_evaluation_form(&self, polynomial: &[Fr], z: Fr) -> Fr {
+        if let Some(i) = self.roots_of_unity_brp.iter().position(|omega| *omega == z) {
+            return polynomial[i];
+        }
+        let n = Fr::from(FIELD_ELEMENTS_PER_BLOB as u64);
+        let mut result = Fr::zero();
+        for (p_i, omega_i) in polynomial.iter().zip(self.roots_of_unity_brp.iter()) {
+            result += *p_i * omega_i * (z - omega_i).inverse().unwrap();
+        }
+        result * (z.pow([FIELD_ELEMENTS_PER_BLOB as u64]) - Fr::one()) * n.inverse().unwrap()
+    }
+
+    /// Computes the kzg proof that p(z) = y, returning (proof, y)
+    /// The quotient q(X) = (p(X) - y) / (X - z) is computed in evaluation form.
+    /// When z = omega_m is in the domain, q(omega_m) = sum_{i != m} (p_i - y) * omega_i / (z * (z - omega_i))
+    pub fn compute_kzg_proof(
+        &self,
+        blob: &[u8],
+        z_bytes: &FieldElementBytes,
+    ) -> Result<(KZGProofBytes, FieldElementBytes), String> {
+        let polynomial = self.blob_to_polynomial(blob)?;
+        let z: Fr = decode_field(z_bytes).map_err(|e| e.to_string())?;
+        let y = self.evaluate_polynomial_in_evaluation_form(&polynomial, z);
+
+        let mut quotient = vec![Fr::zero(); FIELD_ELEMENTS_PER_BLOB];
+        let mut index_in_domain = None;
+        for (i, (p_i, omega_i)) in polynomial
+            .iter()
+            .zip(self.roots_of_unity_brp.iter())
+            .enumerate()
+        {
+            if *omega_i == z {
+                index_in_domain = Some(i);
+                continue;
+            }
+            quotient[i] = (*p_i - y) * (*omega_i - z).inverse().unwrap();
+        }
+        if let Some(m) = index_in_domain {
+            let mut q_m = Fr::zero();
+            for (i, (p_i, omega_i)) in polynomial
+                .iter()
+                .zip(self.roots_of_unity_brp.iter())
+                .enumerate()
+            {
+                if i != m {
+                    q_m += (*p_i - y) * omega_i * (z * (z - omega_i)).inverse().unwrap();
+                }
+            }
+            quotient[m] = q_m;
+        }
+        let proof = self.commit_brp(&quotient);
+        Ok((serialize_g1(&proof), encode_field(&y)))
+    }
+
+    /// Verifies that the polynomial committed in `commitment` evaluates to y at z
+    /// Commitment and proof are deserialized with on-curve and subgroup checks.
+    pub fn verify_kzg_proof(
+        &self,
+        commitment: &KZGCommitmentBytes,
+        z_bytes: &FieldElementBytes,
+        y_bytes: &FieldElementBytes,
+        proof: &KZGProofBytes,
+    ) -> Result<bool, String> {
+        let commitment = deserialize_g1(commitment)?;
+        let proof = deserialize_g1(proof)?;
+        let z: Fr = decode_field(z_bytes).map_err(|e| e.to_string())?;
+        let y: Fr = decode_field(y_bytes).map_err(|e| e.to_string())?;
+        Ok(self.kzg.verify(y, z, commitment, proof))
+    }
+}
+
+pub fn serialize_g1(point: &G1Projective) -> [u8; 48] {
+    let mut bytes = [0u8; 48];
+    G1Affine::from(*point)
+        .serialize_compressed(&mut bytes[..])
+        .unwrap();
+    bytes
+}
+
+/// Deserializes a compressed G1 point (zcash format), checking it is on the curve and in the subgroup
+pub fn deserialize_g1(bytes: &[u8; 48]) -> Result<G1Projective, String> {
+    let point = G1Affine::deserialize_compressed(&bytes[..]).map_err(|e| e.to_string())?;
+    let point = point.into_group();
+    // arkworks ignores the bits following the infinity flag, the spec requires them to be zero
+    if serialize_g1(&point) != *bytes {
+        return Err("non canonical G1 encoding".to_string());
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_hex, deserialize_g1, serialize_g1, Eip4844, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT,
+        ETHEREUM_TRUSTED_SETUP,
+    };
+    use ark_bls12_381::G1Projective;
+    use ark_ec::Group;
+    use ark_ff::Zero;
+
+    fn field_element_bytes(value: u64) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        bytes
+    }
+
+    /// blob[i] = i^2 + 7
+    fn test_blob() -> Vec<u8> {
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+        for (i, chunk) in blob.chunks_mut(BYTES_PER_FIELD_ELEMENT).enumerate() {
+            chunk.copy_from_slice(&field_element_bytes((i * i + 7) as u64));
+        }
+        blob
+    }
+
+    fn hex_48(hex: &str) -> [u8; 48] {
+        decode_hex(hex).unwrap().try_into().unwrap()
+    }
+
+    fn hex_32(hex: &str) -> [u8; 32] {
+        decode_hex(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    pub fn test_g1_serialization() {
+        // compressed encodings of the generator and of infinity, as in the zcash serialization format
+        assert_eq!(
+            serialize_g1(&G1Projective::generator()),
+            hex_48("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb")
+        );
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert_eq!(serialize_g1(&G1Projective::zero()), infinity);
+        assert_eq!(deserialize_g1(&infinity).unwrap(), G1Projective::zero());
+        assert!(deserialize_g1(&[0xff; 48]).is_err());
+    }
+
+    #[test]
+    pub fn test_eip4844_ceremony_compatibility() {
+        let settings = Eip4844::parse_trusted_setup(ETHEREUM_TRUSTED_SETUP).unwrap();
+        let blob = test_blob();
+
+        // reference values computed with c-kzg-4844 on the same blob and trusted setup
+        let commitment = settings.blob_to_kzg_commitment(&blob).unwrap();
+        assert_eq!(
+            commitment,
+            hex_48("9557ada58d1ea7f48806d66720bb05d148ce52dfab866d26b638e3c838db4d390a37ff3ea7fedf29e9c9181b3ea9e589")
+        );
+
+        // z outside of the domain
+        let z = field_element_bytes(123456789);
+        let (proof, y) = settings.compute_kzg_proof(&blob, &z).unwrap();
+        assert_eq!(
+            proof,
+            hex_48("8223d196e9763af85715f1f78d7084c8860316d70df5e0840b535a6f69003cdabfd7f59f307d06a97d1486551e0ced8f")
+        );
+        assert_eq!(
+            y,
+            hex_32("16ea6606c7536a08f3b90ee19580dd81884d539a6c7b13741e8031e2db3188ae")
+        );
+        assert!(settings
+            .verify_kzg_proof(&commitment, &z, &y, &proof)
+            .unwrap());
+
+        // z = 1 is the first root of unity: y is blob[0]
+        let z = field_element_bytes(1);
+        let (proof, y) = settings.compute_kzg_proof(&blob, &z).unwrap();
+        assert_eq!(
+            proof,
+            hex_48("803768473348be42d8c905f805f874024256ceea857d1270498e30d3269e277c40279b6185806d5b030b169fddf554d1")
+        );
+        assert_eq!(y, field_element_bytes(7));
+        assert!(settings
+            .verify_kzg_proof(&commitment, &z, &y, &proof)
+            .unwrap());
+
+        // wrong evaluation
+        let wrong_y = field_element_bytes(8);
+        assert!(!settings
+            .verify_kzg_proof(&commitment, &z, &wrong_y, &proof)
+            .unwrap());
+
+        // the blob with a single 1 at index 1 commits to the bit-reversed lagrange point
+        let mut unit_blob = vec![0u8; BYTES_PER_BLOB];
+        unit_blob[BYTES_PER_FIELD_ELEMENT..2 * BYTES_PER_FIELD_ELEMENT]
+            .copy_from_slice(&field_element_bytes(1));
+        assert_eq!(
+            settings.blob_to_kzg_commitment(&unit_blob).unwrap(),
+            hex_48("837567ad073e42266951a9a54750919280a2ac835a73c158407c3a2b1904cf0d17b7195a393c71a18ad029cbd9cf79ee")
+        );
+
+        // non canonical field elements are rejected
+        let mut invalid_blob = blob.clone();
+        invalid_blob[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&[0xff; BYTES_PER_FIELD_ELEMENT]);
+        assert!(settings.blob_to_kzg_commitment(&invalid_blob).is_err());
+        assert!(settings.blob_to_kzg_commitment(&blob[1..]).is_err());
+    }
+}
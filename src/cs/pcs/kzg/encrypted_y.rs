@@ -0,0 +1,252 @@
+// Openings at a hidden evaluation: the prover reveals py = [y]_1 instead of y = p(z).
+// - the opening verifies as e(pi, [tau - z]_2) == e(C - py, g2), once py, C and pi are checked to be
+//   on the curve and in the prime order subgroup: a py outside of it could cancel against a malformed proof,
+// - py alone doesn't show that the prover knows y (e.g. py could be derived from other points), so openings come
+//   with a schnorr proof of knowledge of y such that py = y * g1, bound to (C, z, py) with a transcript,
+// - many openings, each at its own point, are checked with a single 2-pairings check as `verify_batch`,
+//   py_i replacing [y_i]_1.
+use ark_ec::pairing::Pairing;
+use ark_ff::UniformRand;
+use ark_poly::{univariate::DensePolynomial, Polynomial};
+use ark_std::rand::Rng;
+
+use super::KZG;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::{
+    curve::{check_subgroup_all, msm, mul_secret, SubgroupCheck},
+    pairing::multi_pairing_check,
+};
+
+/// Separates the knowledge proofs of hidden evaluations from other transcripts
+const ENCRYPTED_Y_LABEL: &[u8] = b"ark-algorithms kzg encrypted y";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncryptedYError {
+    /// A point isn't on the curve or in the prime order subgroup
+    InvalidPoint,
+    /// The batch doesn't have as many challenges as openings
+    LengthMismatch { openings: usize, challenges: usize },
+    /// The schnorr proof doesn't show knowledge of y behind py
+    InvalidKnowledgeProof,
+    /// The opening doesn't verify
+    PairingCheckFailed,
+}
+
+impl std::fmt::Display for EncryptedYError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncryptedYError::InvalidPoint => write!(f, "invalid point"),
+            EncryptedYError::LengthMismatch {
+                openings,
+                challenges,
+            } => write!(f, "{openings} openings for {challenges} challenges"),
+            EncryptedYError::InvalidKnowledgeProof => {
+                write!(f, "invalid proof of knowledge of the evaluation")
+            }
+            EncryptedYError::PairingCheckFailed => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for EncryptedYError {}
+
+/// An opening of `commitment` at z, to the evaluation hidden in py = [y]_1
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedOpening<E: Pairing> {
+    pub commitment: E::G1,
+    pub z: E::ScalarField,
+    pub py: E::G1,
+    pub pi: E::G1,
+}
+
+/// Schnorr proof of knowledge of y such that py = y * g1: r = k * g1 and s = k + c * y
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvaluationKnowledgeProof<E: Pairing> {
+    pub r: E::G1,
+    pub s: E::ScalarField,
+}
+
+/// The schnorr challenge, bound to the opening the proof comes with
+fn knowledge_challenge<E: Pairing>(opening: &EncryptedOpening<E>, r: E::G1) -> E::ScalarField {
+    let mut transcript = Transcript::new(ENCRYPTED_Y_LABEL);
+    transcript.absorb(&opening.commitment);
+    transcript.absorb(&opening.z);
+    transcript.absorb(&opening.py);
+    transcript.absorb(&r);
+    transcript.challenge()
+}
+
+impl<E: Pairing> KZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Opens `polynomial` at z without revealing y = p(z), along with a proof of knowledge of y
+    pub fn open_encrypted<R: Rng>(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        z: E::ScalarField,
+        rng: &mut R,
+    ) -> Result<(EncryptedOpening<E>, EvaluationKnowledgeProof<E>), AlgorithmsError> {
+        let y = polynomial.evaluate(&z);
+        let opening = EncryptedOpening {
+            commitment: self.commit(polynomial)?,
+            z,
+            py: mul_secret(self.g1, &y),
+            pi: self.open(polynomial, z, y)?,
+        };
+        let k = E::ScalarField::rand(rng);
+        let r = mul_secret(self.g1, &k);
+        let c = knowledge_challenge(&opening, r);
+        Ok((opening, EvaluationKnowledgeProof { r, s: k + c * y }))
+    }
+
+    /// Single point kzg verification against py = [y]_1: e(pi, [tau - z]_2) == e(commitment - py, g2)
+    /// py, the commitment and pi must be valid subgroup points. This doesn't show that the prover knows y,
+    /// see `verify_encrypted_opening`.
+    pub fn verify_from_encrypted_y(
+        &self,
+        py: E::G1,
+        z: E::ScalarField,
+        commitment: E::G1,
+        pi: E::G1,
+    ) -> Result<(), EncryptedYError> {
+        if !check_subgroup_all(&[py, commitment, pi]) {
+            return Err(EncryptedYError::InvalidPoint);
+        }
+        let pz = self.g2 * z;
+        if !multi_pairing_check::<E>(&[(pi, self.vk - pz), (py - commitment, self.g2)]) {
+            return Err(EncryptedYError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+
+    /// Checks the proof of knowledge of y behind py, then the opening
+    pub fn verify_encrypted_opening(
+        &self,
+        opening: &EncryptedOpening<E>,
+        proof: &EvaluationKnowledgeProof<E>,
+    ) -> Result<(), EncryptedYError> {
+        if !proof.r.check_subgroup() {
+            return Err(EncryptedYError::InvalidPoint);
+        }
+        let c = knowledge_challenge(opening, proof.r);
+        if self.g1 * proof.s != proof.r + opening.py * c {
+            return Err(EncryptedYError::InvalidKnowledgeProof);
+        }
+        self.verify_from_encrypted_y(opening.py, opening.z, opening.commitment, opening.pi)
+    }
+
+    /// Batch verification of openings at hidden evaluations, each at its own point, as `verify_batch`:
+    /// e(sum_i r_i pi_i, [tau]_2) == e(sum_i r_i (C_i - py_i + z_i pi_i), g2)
+    /// The challenges must be sampled once the openings are fixed. The knowledge proofs are checked separately.
+    pub fn verify_from_encrypted_y_batch(
+        &self,
+        openings: &[EncryptedOpening<E>],
+        challenges: &[E::ScalarField],
+    ) -> Result<(), EncryptedYError> {
+        if openings.len() != challenges.len() {
+            return Err(EncryptedYError::LengthMismatch {
+                openings: openings.len(),
+                challenges: challenges.len(),
+            });
+        }
+        let points = openings
+            .iter()
+            .flat_map(|o| [o.commitment, o.py, o.pi])
+            .collect::<Vec<_>>();
+        if !check_subgroup_all(&points) {
+            return Err(EncryptedYError::InvalidPoint);
+        }
+        let proofs = openings.iter().map(|o| o.pi).collect::<Vec<_>>();
+        let aggregated_pi = msm(&proofs, challenges).unwrap();
+        let z_challenges = openings
+            .iter()
+            .zip(challenges)
+            .map(|(o, r)| o.z * r)
+            .collect::<Vec<_>>();
+        let c_minus_py = openings
+            .iter()
+            .map(|o| o.commitment - o.py)
+            .collect::<Vec<_>>();
+        let rhs = msm(&c_minus_py, challenges).unwrap() + msm(&proofs, &z_challenges).unwrap();
+        if !multi_pairing_check::<E>(&[(aggregated_pi, self.vk), (-rhs, self.g2)]) {
+            return Err(EncryptedYError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptedYError, EvaluationKnowledgeProof};
+    use crate::cs::pcs::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::Field;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_encrypted_y() {
+        let mut rng = test_rng();
+        let degree = 8;
+        let g1 = G1Projective::generator();
+        let mut kzg = KZG::<Bn254>::new(g1, G2Projective::generator(), degree);
+        kzg.setup_from_rng(&mut rng);
+
+        let polynomials = (0..3)
+            .map(|_| DensePolynomial::<Fr>::rand(degree, &mut rng))
+            .collect::<Vec<_>>();
+        let (openings, proofs): (Vec<_>, Vec<_>) = polynomials
+            .iter()
+            .map(|p| kzg.open_encrypted(p, Fr::rand(&mut rng), &mut rng).unwrap())
+            .unzip();
+        for (opening, proof) in openings.iter().zip(&proofs) {
+            assert_eq!(kzg.verify_encrypted_opening(opening, proof), Ok(()));
+        }
+        let (opening, proof) = (&openings[0], &proofs[0]);
+        let y = polynomials[0].evaluate(&opening.z);
+        assert_eq!(opening.py, g1 * y);
+
+        // a wrong evaluation, a knowledge proof of another opening, or a forged one
+        let wrong_py = opening.py + g1;
+        assert_eq!(
+            kzg.verify_from_encrypted_y(wrong_py, opening.z, opening.commitment, opening.pi),
+            Err(EncryptedYError::PairingCheckFailed)
+        );
+        assert_eq!(
+            kzg.verify_encrypted_opening(opening, &proofs[1]),
+            Err(EncryptedYError::InvalidKnowledgeProof)
+        );
+        let forged = EvaluationKnowledgeProof::<Bn254> {
+            r: proof.r,
+            s: proof.s + Fr::ONE,
+        };
+        assert_eq!(
+            kzg.verify_encrypted_opening(opening, &forged),
+            Err(EncryptedYError::InvalidKnowledgeProof)
+        );
+
+        // batch
+        let challenges = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        assert_eq!(
+            kzg.verify_from_encrypted_y_batch(&openings, &challenges),
+            Ok(())
+        );
+        assert_eq!(
+            kzg.verify_from_encrypted_y_batch(&openings, &challenges[1..]),
+            Err(EncryptedYError::LengthMismatch {
+                openings: 3,
+                challenges: 2
+            })
+        );
+        let mut tampered = openings.clone();
+        tampered[2].py = wrong_py;
+        assert_eq!(
+            kzg.verify_from_encrypted_y_batch(&tampered, &challenges),
+            Err(EncryptedYError::PairingCheckFailed)
+        );
+    }
+}
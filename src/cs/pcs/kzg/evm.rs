@@ -0,0 +1,417 @@
+// EVM encoding of bn254 KZG proofs.
+// Points and scalars are laid out the way the bn254 precompiles (0x06, 0x07, 0x08) expect them:
+// - a scalar is a 32 bytes big-endian uint256
+// - a G1 point is (x, y), two uint256, the point at infinity being (0, 0)
+// - a G2 point is (x_c1, x_c0, y_c1, y_c0), four uint256: imaginary parts come first
+// The calldata for a single point opening is the abi encoding of
+// `verify(uint256[2] commitment, uint256 z, uint256 y, uint256[2] pi)`, all arguments being static.
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use sha3::{Digest, Keccak256};
+
+use crate::error::AlgorithmsError;
+use crate::utils::curve::SubgroupCheck;
+
+pub const WORD_SIZE: usize = 32;
+
+/// Signature of the verifying function, whose selector prefixes the calldata
+pub const VERIFY_SIGNATURE: &str = "verify(uint256[2],uint256,uint256,uint256[2])";
+
+/// Size of the calldata for a single point opening: selector and 6 words
+pub const CALLDATA_SIZE: usize = 4 + 6 * WORD_SIZE;
+
+/// Signature of the multi point verifying function:
+/// (commitment, z values, y values, lagrange polynomial coeffs, zero polynomial coeffs, pi in G2)
+pub const VERIFY_MULTI_OPEN_SIGNATURE: &str =
+    "verifyMultiOpen(uint256[2],uint256[],uint256[],uint256[],uint256[],uint256[4])";
+
+/// Returns the 4 bytes selector of a solidity function signature
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+pub(crate) fn encode_field<F: PrimeField>(element: &F) -> [u8; WORD_SIZE] {
+    let bytes = element.into_bigint().to_bytes_be();
+    let mut word = [0u8; WORD_SIZE];
+    word[WORD_SIZE - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+/// Decodes a big-endian uint256, rejecting values that are not canonical field elements
+pub(crate) fn decode_field<F: PrimeField>(word: &[u8]) -> Result<F, AlgorithmsError> {
+    if word.len() != WORD_SIZE {
+        return Err(AlgorithmsError::Serialization(format!(
+            "expected {} bytes, got {}",
+            WORD_SIZE,
+            word.len()
+        )));
+    }
+    let element = F::from_be_bytes_mod_order(word);
+    if encode_field(&element) != word {
+        return Err(AlgorithmsError::Serialization(
+            "value is not a canonical field element".to_string(),
+        ));
+    }
+    Ok(element)
+}
+
+pub fn encode_fr(scalar: &Fr) -> [u8; WORD_SIZE] {
+    encode_field(scalar)
+}
+
+pub fn decode_fr(word: &[u8]) -> Result<Fr, AlgorithmsError> {
+    decode_field(word)
+}
+
+pub fn encode_g1(point: &G1Projective) -> [u8; 2 * WORD_SIZE] {
+    let mut bytes = [0u8; 2 * WORD_SIZE];
+    let affine = point.into_affine();
+    if let Some((x, y)) = affine.xy() {
+        bytes[..WORD_SIZE].copy_from_slice(&encode_field(x));
+        bytes[WORD_SIZE..].copy_from_slice(&encode_field(y));
+    }
+    bytes
+}
+
+/// Decodes a G1 point, checking that it lies on the curve
+pub fn decode_g1(bytes: &[u8]) -> Result<G1Projective, AlgorithmsError> {
+    if bytes.len() != 2 * WORD_SIZE {
+        return Err(AlgorithmsError::Serialization(format!(
+            "expected {} bytes, got {}",
+            2 * WORD_SIZE,
+            bytes.len()
+        )));
+    }
+    let x: Fq = decode_field(&bytes[..WORD_SIZE])?;
+    let y: Fq = decode_field(&bytes[WORD_SIZE..])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Projective::zero());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.check_subgroup() {
+        return Err(AlgorithmsError::Serialization(
+            "G1 point is not on the curve".to_string(),
+        ));
+    }
+    Ok(point.into())
+}
+
+pub fn encode_g2(point: &G2Projective) -> [u8; 4 * WORD_SIZE] {
+    let mut bytes = [0u8; 4 * WORD_SIZE];
+    let affine = point.into_affine();
+    if let Some((x, y)) = affine.xy() {
+        for (i, coordinate) in [x.c1, x.c0, y.c1, y.c0].iter().enumerate() {
+            bytes[i * WORD_SIZE..(i + 1) * WORD_SIZE].copy_from_slice(&encode_field(coordinate));
+        }
+    }
+    bytes
+}
+
+/// Decodes a G2 point, checking that it lies on the curve and in the prime order subgroup
+pub fn decode_g2(bytes: &[u8]) -> Result<G2Projective, AlgorithmsError> {
+    if bytes.len() != 4 * WORD_SIZE {
+        return Err(AlgorithmsError::Serialization(format!(
+            "expected {} bytes, got {}",
+            4 * WORD_SIZE,
+            bytes.len()
+        )));
+    }
+    let mut coordinates = [Fq::zero(); 4];
+    for (i, coordinate) in coordinates.iter_mut().enumerate() {
+        *coordinate = decode_field(&bytes[i * WORD_SIZE..(i + 1) * WORD_SIZE])?;
+    }
+    let x = Fq2::new(coordinates[1], coordinates[0]);
+    let y = Fq2::new(coordinates[3], coordinates[2]);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Projective::zero());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.check_subgroup() {
+        return Err(AlgorithmsError::Serialization(
+            "G2 point is not in the prime order subgroup".to_string(),
+        ));
+    }
+    Ok(point.into())
+}
+
+/// Encodes a single point opening proof as calldata for `verify(uint256[2],uint256,uint256,uint256[2])`
+pub fn encode_calldata(commitment: &G1Projective, z: &Fr, y: &Fr, pi: &G1Projective) -> Vec<u8> {
+    let mut calldata = Vec::with_capacity(CALLDATA_SIZE);
+    calldata.extend_from_slice(&function_selector(VERIFY_SIGNATURE));
+    calldata.extend_from_slice(&encode_g1(commitment));
+    calldata.extend_from_slice(&encode_fr(z));
+    calldata.extend_from_slice(&encode_fr(y));
+    calldata.extend_from_slice(&encode_g1(pi));
+    calldata
+}
+
+/// Decodes calldata produced by `encode_calldata` into (commitment, z, y, pi)
+pub fn decode_calldata(
+    calldata: &[u8],
+) -> Result<(G1Projective, Fr, Fr, G1Projective), AlgorithmsError> {
+    if calldata.len() != CALLDATA_SIZE {
+        return Err(AlgorithmsError::Serialization(format!(
+            "expected {} bytes of calldata, got {}",
+            CALLDATA_SIZE,
+            calldata.len()
+        )));
+    }
+    if calldata[..4] != function_selector(VERIFY_SIGNATURE) {
+        return Err(AlgorithmsError::Serialization(
+            "unexpected function selector".to_string(),
+        ));
+    }
+    let words = &calldata[4..];
+    let commitment = decode_g1(&words[..2 * WORD_SIZE])?;
+    let z = decode_fr(&words[2 * WORD_SIZE..3 * WORD_SIZE])?;
+    let y = decode_fr(&words[3 * WORD_SIZE..4 * WORD_SIZE])?;
+    let pi = decode_g1(&words[4 * WORD_SIZE..])?;
+    Ok((commitment, z, y, pi))
+}
+
+fn encode_usize(value: usize) -> [u8; WORD_SIZE] {
+    let mut word = [0u8; WORD_SIZE];
+    word[WORD_SIZE - 8..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+fn decode_usize(word: &[u8]) -> Result<usize, AlgorithmsError> {
+    if word[..WORD_SIZE - 8].iter().any(|b| *b != 0) {
+        return Err(AlgorithmsError::Serialization(
+            "value does not fit in a usize".to_string(),
+        ));
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&word[WORD_SIZE - 8..WORD_SIZE]);
+    Ok(u64::from_be_bytes(bytes) as usize)
+}
+
+/// Encodes a multi point opening proof as calldata for `verifyMultiOpen`
+/// Arrays are dynamic abi types: the head holds their offsets, the tail their length and elements.
+pub fn encode_multi_open_calldata(
+    commitment: &G1Projective,
+    z_values: &[Fr],
+    y_values: &[Fr],
+    lagrange_polynomial: &DensePolynomial<Fr>,
+    zero_polynomial: &DensePolynomial<Fr>,
+    pi: &G2Projective,
+) -> Vec<u8> {
+    let arrays = [
+        z_values,
+        y_values,
+        &lagrange_polynomial.coeffs,
+        &zero_polynomial.coeffs,
+    ];
+    // commitment (2 words), 4 offsets, pi (4 words)
+    let head_size = 10 * WORD_SIZE;
+    let mut head = vec![];
+    let mut tail = vec![];
+    head.extend_from_slice(&encode_g1(commitment));
+    for array in arrays {
+        head.extend_from_slice(&encode_usize(head_size + tail.len()));
+        tail.extend_from_slice(&encode_usize(array.len()));
+        for element in array.iter() {
+            tail.extend_from_slice(&encode_fr(element));
+        }
+    }
+    head.extend_from_slice(&encode_g2(pi));
+
+    let mut calldata = function_selector(VERIFY_MULTI_OPEN_SIGNATURE).to_vec();
+    calldata.append(&mut head);
+    calldata.append(&mut tail);
+    calldata
+}
+
+/// A decoded multi point opening: commitment, z values, y values, lagrange polynomial, zero polynomial, pi
+pub type MultiOpenCalldata = (
+    G1Projective,
+    Vec<Fr>,
+    Vec<Fr>,
+    DensePolynomial<Fr>,
+    DensePolynomial<Fr>,
+    G2Projective,
+);
+
+/// Decodes calldata produced by `encode_multi_open_calldata`
+pub fn decode_multi_open_calldata(calldata: &[u8]) -> Result<MultiOpenCalldata, AlgorithmsError> {
+    if calldata.len() < 4 + 10 * WORD_SIZE {
+        return Err(AlgorithmsError::Serialization(
+            "calldata is too short".to_string(),
+        ));
+    }
+    if calldata[..4] != function_selector(VERIFY_MULTI_OPEN_SIGNATURE) {
+        return Err(AlgorithmsError::Serialization(
+            "unexpected function selector".to_string(),
+        ));
+    }
+    let args = &calldata[4..];
+    let too_short = || AlgorithmsError::Serialization("calldata is too short".to_string());
+    // offsets and lengths are read from the calldata: their arithmetic is checked
+    let word = |i: usize| -> Result<&[u8], AlgorithmsError> {
+        let end = i.checked_add(WORD_SIZE).ok_or_else(too_short)?;
+        args.get(i..end).ok_or_else(too_short)
+    };
+    let commitment = decode_g1(&args[..2 * WORD_SIZE])?;
+    let mut arrays: Vec<Vec<Fr>> = vec![];
+    for i in 0..4 {
+        let offset = decode_usize(word((2 + i) * WORD_SIZE)?)?;
+        let len = decode_usize(word(offset)?)?;
+        let mut array = vec![];
+        for j in 0..len {
+            let position = (1 + j)
+                .checked_mul(WORD_SIZE)
+                .and_then(|p| p.checked_add(offset))
+                .ok_or_else(too_short)?;
+            array.push(decode_fr(word(position)?)?);
+        }
+        arrays.push(array);
+    }
+    let pi = decode_g2(&args[6 * WORD_SIZE..10 * WORD_SIZE])?;
+    let zero_coeffs = arrays.pop().unwrap();
+    let lagrange_coeffs = arrays.pop().unwrap();
+    let y_values = arrays.pop().unwrap();
+    let z_values = arrays.pop().unwrap();
+    Ok((
+        commitment,
+        z_values,
+        y_values,
+        DensePolynomial::from_coefficients_vec(lagrange_coeffs),
+        DensePolynomial::from_coefficients_vec(zero_coeffs),
+        pi,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_calldata, decode_fr, decode_g1, decode_g2, decode_multi_open_calldata, decode_usize,
+        encode_calldata, encode_g1, encode_g2, encode_multi_open_calldata, encode_usize,
+        function_selector, CALLDATA_SIZE, WORD_SIZE,
+    };
+    use crate::cs::pcs::kzg::KZG;
+    use crate::setup::secret::Secret;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_function_selector() {
+        // well known selector of the erc20 transfer function
+        assert_eq!(
+            function_selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+    }
+
+    #[test]
+    pub fn test_point_encoding() {
+        // bn254's G1 generator is (1, 2)
+        let encoded = encode_g1(&G1Projective::generator());
+        assert_eq!(encoded[WORD_SIZE - 1], 1);
+        assert_eq!(encoded[2 * WORD_SIZE - 1], 2);
+        assert!(encoded[..WORD_SIZE - 1].iter().all(|b| *b == 0));
+
+        // infinity is (0, 0)
+        assert_eq!(encode_g1(&G1Projective::zero()), [0u8; 64]);
+        assert_eq!(decode_g1(&[0u8; 64]).unwrap(), G1Projective::zero());
+
+        let mut rng = test_rng();
+        let g2 = G2Projective::rand(&mut rng);
+        assert_eq!(decode_g2(&encode_g2(&g2)).unwrap(), g2);
+
+        // (1, 3) is not on the curve
+        let mut not_on_curve = encoded;
+        not_on_curve[2 * WORD_SIZE - 1] = 3;
+        assert!(decode_g1(&not_on_curve).is_err());
+
+        // values above the scalar field modulus are not canonical scalars
+        assert!(decode_fr(&[0xff; WORD_SIZE]).is_err());
+    }
+
+    #[test]
+    pub fn test_calldata_round_trip() {
+        let mut rng = test_rng();
+        let degree = 5;
+        let tau = Fr::rand(&mut rng);
+        let mut kzg =
+            KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+        kzg.setup(Secret::new(tau));
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+
+        let calldata = encode_calldata(&commitment, &z, &y, &pi);
+        assert_eq!(calldata.len(), CALLDATA_SIZE);
+        let (commitment, z, y, pi) = decode_calldata(&calldata).unwrap();
+        assert!(kzg.verify_no_g2_ops_evm_opcode(y, z, commitment, pi));
+
+        let mut wrong_selector = calldata.clone();
+        wrong_selector[0] ^= 1;
+        assert!(decode_calldata(&wrong_selector).is_err());
+        assert!(decode_calldata(&calldata[1..]).is_err());
+    }
+
+    #[test]
+    pub fn test_multi_open_calldata_round_trip() {
+        let mut rng = test_rng();
+        let degree = 5;
+        let tau = Fr::rand(&mut rng);
+        let mut kzg =
+            KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+        kzg.setup(Secret::new(tau));
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z_values = vec![Fr::from(0), Fr::from(1), Fr::from(2)];
+        let y_values = z_values
+            .iter()
+            .map(|z| polynomial.evaluate(z))
+            .collect::<Vec<_>>();
+        let (pi, lagrange_polynomial, zero_polynomial) =
+            kzg.multi_open(&polynomial, &z_values).unwrap();
+
+        let calldata = encode_multi_open_calldata(
+            &commitment,
+            &z_values,
+            &y_values,
+            &lagrange_polynomial,
+            &zero_polynomial,
+            &pi,
+        );
+        // selector, head of 10 words, 4 arrays with their length
+        let n_words = 10
+            + 4
+            + z_values.len()
+            + y_values.len()
+            + lagrange_polynomial.coeffs.len()
+            + zero_polynomial.coeffs.len();
+        assert_eq!(calldata.len(), 4 + n_words * WORD_SIZE);
+
+        let decoded = decode_multi_open_calldata(&calldata).unwrap();
+        assert_eq!(decoded.0, commitment);
+        assert_eq!(decoded.1, z_values);
+        assert_eq!(decoded.2, y_values);
+        assert_eq!(decoded.3, lagrange_polynomial);
+        assert_eq!(decoded.4, zero_polynomial);
+        assert_eq!(decoded.5, pi);
+        assert!(kzg.verify_multi_open_no_g2_ops(
+            &decoded.0, &decoded.1, &decoded.2, &decoded.3, &decoded.4, &decoded.5
+        ));
+        assert!(decode_multi_open_calldata(&calldata[..calldata.len() - 1]).is_err());
+        // offsets or lengths that overflow
+        let mut huge_offset = calldata.clone();
+        huge_offset[4 + 2 * WORD_SIZE..4 + 3 * WORD_SIZE]
+            .copy_from_slice(&encode_usize(usize::MAX));
+        assert!(decode_multi_open_calldata(&huge_offset).is_err());
+        let offset = decode_usize(&calldata[4 + 2 * WORD_SIZE..4 + 3 * WORD_SIZE]).unwrap();
+        let mut huge_length = calldata.clone();
+        huge_length[4 + offset..4 + offset + WORD_SIZE].copy_from_slice(&encode_usize(usize::MAX));
+        assert!(decode_multi_open_calldata(&huge_length).is_err());
+    }
+}
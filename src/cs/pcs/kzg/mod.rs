@@ -1,20 +1,98 @@
+pub mod eip4844;
+pub mod encrypted_y;
+pub mod evm;
+pub mod shplonk;
+pub mod solidity;
+pub mod vector_commitment;
+pub mod zeromorph;
+
 use ark_ec::pairing::Pairing;
 use ark_ff::{Field, One};
-use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
-use ark_std::Zero;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::Rng, Zero};
+use zeroize::Zeroize;
+
+use crate::cs::obligations::{MsmTerm, Obligation, ObligationSet};
+use crate::cs::pedersen::hash_to_field;
+use crate::error::AlgorithmsError;
+use crate::setup::secret::Secret;
+use crate::utils::curve::{
+    batch_mul_secret, check_subgroup_all, msm, msm_secret, mul_secret, SubgroupCheck,
+};
+use crate::utils::{
+    build_zero_polynomial,
+    lagrange::compute_lagrange_interpolation_on_points,
+    pairing::multi_pairing_check,
+    polynomial::{
+        divide_with_remainder, linear_combination, ruffini_division, sparse::SparsePolynomial,
+        SubproductTree,
+    },
+};
 
-use crate::utils::{build_zero_polynomial, lagrange::compute_lagrange_interpolation};
+/// Separates the taus of `setup_from_seed` from other values hashed from the same seed
+const KZG_SEED_LABEL: &[u8] = b"ark-algorithms kzg tau";
 
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct KZG<E: Pairing> {
-    pub g1: E::G1,
-    pub g2: E::G2,
-    pub degree: usize,
-    pub crs: Vec<E::G1>,
-    pub crs_2: Vec<E::G2>,
-    pub vk: E::G2,
+    g1: E::G1,
+    g2: E::G2,
+    degree: usize,
+    crs: Vec<E::G1>,
+    crs_2: Vec<E::G2>,
+    /// Lagrange-basis srs: [L_i(tau)]_1, for the roots-of-unity domain it has been computed over
+    crs_lagrange: Vec<E::G1>,
+    vk: E::G2,
+}
+
+/// A single point opening: pi proves that the polynomial committed in `commitment` evaluates to y at z
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct KZGOpening<E: Pairing> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub commitment: E::G1,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub z: E::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub y: E::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub pi: E::G1,
+}
+
+/// A multi point opening: pi = [q(tau)]_2 proves that the committed polynomial p evaluates to y_values[i]
+/// at z_values[i], with q = (p - I) / Z, where I interpolates the (z_i, y_i) and Z vanishes on the z_i.
+/// The verifier recomputes I and Z from the (z_i, y_i), rather than taking them from the prover.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MultiOpenProof<E: Pairing> {
+    pub z_values: Vec<E::ScalarField>,
+    pub y_values: Vec<E::ScalarField>,
+    pub pi: E::G2,
 }
 
-impl<E: Pairing> KZG<E> {
+impl<E: Pairing> MultiOpenProof<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Checks the proof against `commitment`, as `KZG::verify_multi_open`
+    pub fn verify(&self, kzg: &KZG<E>, commitment: &E::G1) -> bool {
+        kzg.verify_multi_open(commitment, &self.z_values, &self.y_values, &self.pi)
+    }
+}
+
+impl<E: Pairing> KZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
     pub fn new(g1: E::G1, g2: E::G2, degree: usize) -> Self {
         Self {
             g1,
@@ -22,73 +100,466 @@ impl<E: Pairing> KZG<E> {
             degree,
             crs: vec![],
             crs_2: vec![],
+            crs_lagrange: vec![],
             vk: g2,
         }
     }
 
-    pub fn setup(&mut self, tau: E::ScalarField) {
-        let vk = self.g2 * tau;
-        for pow in 0..self.degree + 1 {
-            let tau_i: E::ScalarField = tau.pow([pow as u64]);
-            let crs_point_g1 = self.g1 * tau_i;
-            let crs_point_g2 = self.g2 * tau_i;
-            self.crs.push(crs_point_g1);
-            self.crs_2.push(crs_point_g2);
+    /// Builds the srs from the toxic waste tau, which is zeroized along with its powers once the srs is built
+    pub fn setup(&mut self, tau: Secret<E::ScalarField>) {
+        let tau = tau.expose();
+        let mut powers = Vec::with_capacity(self.degree + 1);
+        let mut tau_i = E::ScalarField::one();
+        for _ in 0..self.degree + 1 {
+            powers.push(tau_i);
+            tau_i *= tau;
         }
-        self.vk = vk;
+        // g1 and g2 are multiplied by all powers of tau, see `batch_mul_secret`
+        self.crs = batch_mul_secret(self.g1, &powers);
+        self.crs_2 = batch_mul_secret(self.g2, &powers);
+        self.vk = mul_secret(self.g2, tau);
+        powers.zeroize();
+        tau_i.zeroize();
     }
 
-    pub fn commit(&mut self, polynomial: &DensePolynomial<E::ScalarField>) -> E::G1 {
-        let mut commitment = E::G1::zero();
-        for i in 0..self.degree + 1 {
-            let value = self.crs[i as usize] * polynomial.coeffs[i as usize];
-            commitment += value;
+    /// Runs the setup with a tau sampled from `rng`, which should be cryptographically secure outside of tests
+    /// tau never leaves the scheme.
+    pub fn setup_from_rng<R: Rng>(&mut self, rng: &mut R) {
+        self.setup(Secret::random(rng));
+    }
+
+    /// Runs the setup with tau hashed from `seed`, for reproducible test vectors
+    /// Anyone knowing the seed knows tau: this srs is only sound for tests.
+    pub fn setup_from_seed(&mut self, seed: &[u8]) {
+        self.setup(Secret::new(hash_to_field(
+            &[KZG_SEED_LABEL, seed].concat(),
+            0,
+        )));
+    }
+
+    /// Builds the scheme from an existing srs [tau^i]_1, [tau^i]_2, e.g. the output of a ceremony
+    pub fn from_srs(crs: Vec<E::G1>, crs_2: Vec<E::G2>) -> Self {
+        assert!(
+            !crs.is_empty() && crs_2.len() >= 2,
+            "the srs should hold [1]_1, [1]_2 and [tau]_2"
+        );
+        Self {
+            g1: crs[0],
+            g2: crs_2[0],
+            degree: crs.len() - 1,
+            vk: crs_2[1],
+            crs,
+            crs_2,
+            crs_lagrange: vec![],
+        }
+    }
+
+    /// The srs restricted to polynomials of degree at most `new_degree`, e.g. to serve a smaller circuit
+    /// from a larger ceremony. The lagrange srs is kept when its domain still fits.
+    pub fn trim(&self, new_degree: usize) -> Result<Self, AlgorithmsError> {
+        if new_degree > self.degree {
+            return Err(AlgorithmsError::DegreeTooLarge {
+                degree: new_degree,
+                max_degree: self.degree,
+            });
         }
-        commitment
+        // [tau]_2 is always kept, it is the verifying key
+        let crs_2_len = self.crs_2.len().min((new_degree + 1).max(2));
+        let crs_lagrange = if self.crs_lagrange.len() <= new_degree + 1 {
+            self.crs_lagrange.clone()
+        } else {
+            vec![]
+        };
+        Ok(Self {
+            g1: self.g1,
+            g2: self.g2,
+            degree: new_degree,
+            crs: self.crs[..=new_degree].to_vec(),
+            crs_2: self.crs_2[..crs_2_len].to_vec(),
+            crs_lagrange,
+            vk: self.vk,
+        })
+    }
+
+    /// Appends the next powers [tau^i]_1 and [tau^i]_2 of the same ceremony to the srs
+    /// The extended srs is checked with `verify_srs`, and left unchanged if it isn't well formed.
+    pub fn extend(&mut self, crs: &[E::G1], crs_2: &[E::G2]) -> Result<(), AlgorithmsError> {
+        let mut extended = self.clone();
+        extended.crs.extend_from_slice(crs);
+        extended.crs_2.extend_from_slice(crs_2);
+        extended.degree = extended.crs.len() - 1;
+        if !extended.verify_srs() {
+            return Err(AlgorithmsError::InvalidSrs);
+        }
+        *self = extended;
+        Ok(())
+    }
+
+    pub fn g1(&self) -> E::G1 {
+        self.g1
     }
 
-    /// Single point kzg opening
+    pub fn g2(&self) -> E::G2 {
+        self.g2
+    }
+
+    pub fn vk(&self) -> E::G2 {
+        self.vk
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn crs(&self) -> &[E::G1] {
+        &self.crs
+    }
+
+    pub fn crs_2(&self) -> &[E::G2] {
+        &self.crs_2
+    }
+
+    pub fn crs_lagrange(&self) -> &[E::G1] {
+        &self.crs_lagrange
+    }
+
+    /// An error if the polynomial doesn't fit in the srs
+    pub fn commit(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+    ) -> Result<E::G1, AlgorithmsError> {
+        self.check_degree(polynomial)?;
+        msm_secret(&self.crs[..polynomial.coeffs.len()], &polynomial.coeffs)
+    }
+
+    /// Commits to a sparse polynomial, with an msm over the srs points at its non-zero terms only
+    pub fn commit_sparse(
+        &self,
+        polynomial: &SparsePolynomial<E::ScalarField>,
+    ) -> Result<E::G1, AlgorithmsError> {
+        if polynomial.degree() >= self.crs.len() {
+            return Err(AlgorithmsError::DegreeTooLarge {
+                degree: polynomial.degree(),
+                max_degree: self.crs.len().saturating_sub(1),
+            });
+        }
+        let (bases, scalars): (Vec<_>, Vec<_>) = polynomial
+            .terms()
+            .iter()
+            .map(|(degree, coeff)| (self.crs[*degree], *coeff))
+            .unzip();
+        msm_secret(&bases, &scalars)
+    }
+
+    fn check_degree(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+    ) -> Result<(), AlgorithmsError> {
+        if polynomial.coeffs.len() > self.crs.len() {
+            return Err(AlgorithmsError::DegreeTooLarge {
+                degree: polynomial.degree(),
+                max_degree: self.crs.len().saturating_sub(1),
+            });
+        }
+        Ok(())
+    }
+
+    /// Commits to X^{D - d} * p(X), where D is the srs degree and d the degree bound on p
+    /// Only possible when deg(p) <= d, since the srs holds no powers of tau above D.
+    pub fn commit_shifted(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        degree_bound: usize,
+    ) -> E::G1 {
+        assert!(
+            degree_bound <= self.degree,
+            "degree bound exceeds the srs degree"
+        );
+        assert!(
+            polynomial.degree() <= degree_bound,
+            "polynomial degree exceeds the degree bound"
+        );
+        let shift = self.degree - degree_bound;
+        let bases = &self.crs[shift..shift + polynomial.coeffs.len()];
+        msm_secret(bases, &polynomial.coeffs).unwrap()
+    }
+
+    /// Commits to p(X) alongside its shifted version X^{D - d} * p(X)
+    /// The pair lets a verifier check that deg(p) <= d, see `verify_degree_bound`
+    pub fn commit_with_degree_bound(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        degree_bound: usize,
+    ) -> (E::G1, E::G1) {
+        let shifted_commitment = self.commit_shifted(polynomial, degree_bound);
+        let commitment = self
+            .commit(polynomial)
+            .expect("deg(p) <= degree bound <= srs degree, checked by commit_shifted");
+        (commitment, shifted_commitment)
+    }
+
+    /// Checks that every point of the srs is on the curve and in the right subgroup
+    /// Verifiers only check the (attacker supplied) proofs and commitments they are given,
+    /// this should be called once whenever the srs itself comes from an untrusted source.
+    pub fn check_srs(&self) -> bool {
+        check_subgroup_all(&[self.g1])
+            && check_subgroup_all(&[self.g2, self.vk])
+            && check_subgroup_all(&self.crs)
+            && check_subgroup_all(&self.crs_2)
+            && check_subgroup_all(&self.crs_lagrange)
+    }
+
+    /// Checks that the srs is well formed: points in the right subgroups, and both crs and crs_2 are powers
+    /// of the tau in vk, i.e. e(crs[i], g2) == e(crs[i-1], vk) and e(g1, crs_2[i]) == e(crs[1], crs_2[i-1])
+    /// The relations are batched with powers of r, itself hashed from the srs, into a single multi-pairing.
+    /// Meant for imported or ceremony-produced srs, before any use.
+    pub fn verify_srs(&self) -> bool {
+        let (crs, crs_2) = (&self.crs, &self.crs_2);
+        if crs.len() != self.degree + 1 || crs_2.is_empty() || !self.check_srs() {
+            return false;
+        }
+        if crs[0] != self.g1 || crs_2[0] != self.g2 || crs_2.get(1).is_some_and(|vk| *vk != self.vk)
+        {
+            return false;
+        }
+        // tau = 0 or 1 would be known to anyone
+        if self.g1.is_zero() || self.g2.is_zero() || self.vk.is_zero() || self.vk == self.g2 {
+            return false;
+        }
+        // crs_2 powers above tau are checked against [tau]_1
+        if crs.len() < 2 && crs_2.len() > 2 {
+            return false;
+        }
+
+        let mut bytes = vec![];
+        self.serialize_compressed(&mut bytes).unwrap();
+        let r: E::ScalarField = hash_to_field(&bytes, 0);
+        let s: E::ScalarField = hash_to_field(&bytes, 1);
+        let powers = |n: usize| {
+            let mut r_i = r;
+            (0..n)
+                .map(|_| {
+                    let r_prev = r_i;
+                    r_i *= r;
+                    r_prev
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // sum_i r^i crs[i] == tau * sum_i r^i crs[i-1]
+        let r_1 = powers(crs.len() - 1);
+        let mut pairs = vec![
+            (msm(&crs[1..], &r_1).unwrap(), self.g2),
+            (-msm(&crs[..crs.len() - 1], &r_1).unwrap(), self.vk),
+        ];
+        // same on crs_2 above [tau]_2 = vk, the second equation is scaled by s
+        if crs_2.len() > 2 {
+            let r_2 = powers(crs_2.len() - 1);
+            pairs.push((self.g1 * s, msm(&crs_2[1..], &r_2).unwrap()));
+            pairs.push((-crs[1] * s, msm(&crs_2[..crs_2.len() - 1], &r_2).unwrap()));
+        }
+        multi_pairing_check::<E>(&pairs)
+    }
+
+    /// Transforms the monomial srs [tau^i]_1 into the lagrange-basis srs [L_i(tau)]_1 over `domain`
+    /// Since L_i(tau) = 1/n * sum_j omega^{-ij} * tau^j, this is an inverse fft over G1 elements.
+    pub fn compute_lagrange_crs(
+        &self,
+        domain: GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Vec<E::G1> {
+        let n = domain.size();
+        assert!(
+            n <= self.crs.len(),
+            "domain is larger than the srs: {} > {}",
+            n,
+            self.crs.len()
+        );
+        domain.ifft(&self.crs[..n])
+    }
+
+    /// Precomputes and stores the lagrange-basis srs over `domain`, to be used by `commit_evals`
+    pub fn setup_lagrange_crs(&mut self, domain: GeneralEvaluationDomain<E::ScalarField>) {
+        self.crs_lagrange = self.compute_lagrange_crs(domain);
+    }
+
+    /// Commits to the polynomial p such that p(omega^i) = evals[i], where omega generates `domain`
+    /// Uses the precomputed lagrange-basis srs: C = sum_i evals[i] * [L_i(tau)]_1, no ifft needed.
+    pub fn commit_evals(
+        &self,
+        evals: &[E::ScalarField],
+        domain: GeneralEvaluationDomain<E::ScalarField>,
+    ) -> E::G1 {
+        assert_eq!(
+            domain.size(),
+            self.crs_lagrange.len(),
+            "lagrange srs has not been computed over this domain"
+        );
+        assert_eq!(evals.len(), domain.size());
+        msm_secret(&self.crs_lagrange, evals).unwrap()
+    }
+
+    /// Single point kzg opening: an error if the polynomial doesn't fit in the srs, or if y isn't p(z),
+    /// in which case (p(X) - y) / (X - z) leaves a remainder and no valid proof exists
     pub fn open(
         &self,
         polynomial: &DensePolynomial<E::ScalarField>,
         z: E::ScalarField,
         y: E::ScalarField,
-    ) -> E::G1 {
+    ) -> Result<E::G1, AlgorithmsError> {
+        self.check_degree(polynomial)?;
         // Opening at y = p(z). Notation from here: https://hackmd.io/@gnark/kzg-bls24
+        let (q_x, remainder) = ruffini_division(polynomial, z);
+        if remainder != y {
+            return Err(AlgorithmsError::NonZeroRemainder);
+        }
+        self.commit(&q_x)
+    }
+
+    /// `open` without checking y: commits to the quotient of (p(X) - y) / (X - z), dropping the remainder,
+    /// so the proof doesn't verify when y isn't p(z). An error if the polynomial doesn't fit in the srs
+    pub fn open_unchecked(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        z: E::ScalarField,
+        y: E::ScalarField,
+    ) -> Result<E::G1, AlgorithmsError> {
+        self.check_degree(polynomial)?;
         let y_polynomial = DensePolynomial::from_coefficients_vec(vec![y]);
         let numerator = polynomial - &y_polynomial;
-        let denominator = DensePolynomial::from_coefficients_vec(vec![-z, E::ScalarField::ONE]);
-        let q_x = &numerator / &denominator;
-        let mut pi = E::G1::zero();
-        for (i, coeff) in q_x.coeffs.iter().enumerate() {
-            pi += self.crs[i] * coeff;
+        let (q_x, _) = ruffini_division(&numerator, z);
+        self.commit(&q_x)
+    }
+
+    /// Commitment to sum_i w_i * p_i from the commitments C_i to the p_i: sum_i w_i * C_i
+    /// As for the linearization polynomial of PLONK-like verifiers, no polynomial nor srs point is needed.
+    pub fn commit_linear_combination(terms: &[(E::ScalarField, E::G1)]) -> E::G1 {
+        let (weights, commitments): (Vec<_>, Vec<_>) = terms.iter().copied().unzip();
+        msm(&commitments, &weights).unwrap()
+    }
+
+    /// Opens sum_i w_i * p_i at z, returning its evaluation y and the proof, which verify against
+    /// `commit_linear_combination` of the commitments to the p_i, with the same weights
+    pub fn open_linear_combination(
+        &self,
+        terms: &[(E::ScalarField, &DensePolynomial<E::ScalarField>)],
+        z: E::ScalarField,
+    ) -> Result<(E::ScalarField, E::G1), AlgorithmsError> {
+        let combination = linear_combination(terms);
+        let y = combination.evaluate(&z);
+        Ok((y, self.open(&combination, z, y)?))
+    }
+
+    /// Computes the single point openings of `polynomial` at all the points of `domain`, in O(n log n)
+    /// Follows Feist-Khovratovich (https://eprint.iacr.org/2023/033), pi_j opens the polynomial at \omega^{j}.
+    /// With f(X) = sum_k f_k X^k of degree d, the opening at z is
+    /// [(f(tau) - f(z)) / (tau - z)]_1 = sum_{e=0}^{d-1} h_e * z^e, where h_e = sum_{k=e+1}^{d} f_k [tau^{k-1-e}]_1
+    /// So the openings are the dft of h over the domain, and h is a toeplitz product (a convolution), computed with ffts.
+    pub fn open_all(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        domain: GeneralEvaluationDomain<E::ScalarField>,
+    ) -> Vec<E::G1> {
+        let d = polynomial.degree();
+        assert!(d <= self.degree, "polynomial degree exceeds the srs degree");
+        if d == 0 {
+            return vec![E::G1::zero(); domain.size()];
         }
-        pi
+
+        // convolution of a_t = [tau^t]_1 (t < d) with b_u = f_{d-u} (u < d): h_e = (a * b)_{d-1-e}
+        let conv_domain = GeneralEvaluationDomain::<E::ScalarField>::new(2 * d).unwrap();
+        let a_evals = conv_domain.fft(&self.crs[..d]);
+        let b = (0..d).map(|u| polynomial.coeffs[d - u]).collect::<Vec<_>>();
+        let b_evals = conv_domain.fft(&b);
+        let ab_evals = a_evals
+            .iter()
+            .zip(b_evals.iter())
+            .map(|(a_i, b_i)| *a_i * b_i)
+            .collect::<Vec<_>>();
+        let ab = conv_domain.ifft(&ab_evals);
+
+        // since \omega^{n} = 1, h_e contributes to the same openings as h_{e mod n}
+        let n = domain.size();
+        let mut h = vec![E::G1::zero(); n];
+        for e in 0..d {
+            h[e % n] += ab[d - 1 - e];
+        }
+        domain.fft(&h)
     }
 
     /// Multi-point kzg opening, also referred as "batch opening"
+    /// An error if there are no points, a repeated point, or if the quotient doesn't fit in the G2 srs
+    #[allow(clippy::type_complexity)]
     pub fn multi_open(
         &self,
         polynomial: &DensePolynomial<E::ScalarField>,
-        z_values: &Vec<E::ScalarField>, // z_values = {0, 1, 2, 3, ...}
-    ) -> (
-        E::G2,
-        DensePolynomial<E::ScalarField>,
-        DensePolynomial<E::ScalarField>,
-    ) {
-        let mut y_values = Vec::new();
-        for z in z_values.iter() {
-            let y = polynomial.evaluate(z);
-            y_values.push(y);
-        }
-        let lagrange_polynomial = compute_lagrange_interpolation::<E::ScalarField>(&y_values);
-        let zero_polynomial = build_zero_polynomial::<E::ScalarField>(&z_values);
-        let q = &(polynomial - &lagrange_polynomial) / &zero_polynomial;
-        let mut pi = E::G2::zero();
-        for (i, coeff) in q.coeffs.iter().enumerate() {
-            pi += self.crs_2[i] * coeff;
-        }
-        (pi, lagrange_polynomial, zero_polynomial)
+        z_values: &[E::ScalarField],
+    ) -> Result<
+        (
+            E::G2,
+            DensePolynomial<E::ScalarField>,
+            DensePolynomial<E::ScalarField>,
+        ),
+        AlgorithmsError,
+    > {
+        let (proof, lagrange_polynomial, zero_polynomial) =
+            self.multi_open_with_polynomials(polynomial, z_values)?;
+        Ok((proof.pi, lagrange_polynomial, zero_polynomial))
+    }
+
+    /// `multi_open_proof`, along with I and Z
+    #[allow(clippy::type_complexity)]
+    fn multi_open_with_polynomials(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        z_values: &[E::ScalarField],
+    ) -> Result<
+        (
+            MultiOpenProof<E>,
+            DensePolynomial<E::ScalarField>,
+            DensePolynomial<E::ScalarField>,
+        ),
+        AlgorithmsError,
+    > {
+        let mut sorted = z_values.to_vec();
+        sorted.sort_unstable();
+        if sorted.is_empty() || sorted.windows(2).any(|w| w[0] == w[1]) {
+            return Err(AlgorithmsError::InvalidParameters(
+                "the opening points should be distinct, and at least one".to_string(),
+            ));
+        }
+        // the subproduct tree gives both the evaluations and the vanishing polynomial
+        let tree = SubproductTree::new(z_values);
+        let y_values = tree.evaluate(polynomial);
+        let lagrange_polynomial = compute_lagrange_interpolation_on_points(z_values, &y_values);
+        let zero_polynomial = tree.zero_polynomial().clone();
+        // I interpolates the polynomial over the points, so Z divides their difference
+        let (q, remainder) =
+            divide_with_remainder(&(polynomial - &lagrange_polynomial), &zero_polynomial)
+                .ok_or_else(|| {
+                    AlgorithmsError::InvalidParameters("the zero polynomial is zero".to_string())
+                })?;
+        debug_assert!(remainder.is_zero(), "Z doesn't divide p - I");
+        if q.coeffs.len() > self.crs_2.len() {
+            return Err(AlgorithmsError::DegreeTooLarge {
+                degree: q.degree(),
+                max_degree: self.crs_2.len().saturating_sub(1),
+            });
+        }
+        let proof = MultiOpenProof {
+            z_values: z_values.to_vec(),
+            y_values,
+            pi: msm(&self.crs_2[..q.coeffs.len()], &q.coeffs)?,
+        };
+        Ok((proof, lagrange_polynomial, zero_polynomial))
+    }
+
+    /// `multi_open` as a self-contained proof, carrying the points and evaluations instead of I and Z
+    pub fn multi_open_proof(
+        &self,
+        polynomial: &DensePolynomial<E::ScalarField>,
+        z_values: &[E::ScalarField],
+    ) -> Result<MultiOpenProof<E>, AlgorithmsError> {
+        Ok(self.multi_open_with_polynomials(polynomial, z_values)?.0)
     }
 
     /// Single point kzg verification
@@ -99,11 +570,108 @@ impl<E: Pairing> KZG<E> {
         commitment: E::G1,
         pi: E::G1,
     ) -> bool {
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
         let py = self.g1 * y;
         let pz = self.g2 * z;
-        let lhs = E::pairing(pi, self.vk - pz);
-        let rhs = E::pairing(commitment - py, self.g2);
-        lhs == rhs
+        // e(pi, [tau - z]_2) == e(commitment - [y]_1, g2)
+        multi_pairing_check::<E>(&[(pi, self.vk - pz), (py - commitment, self.g2)])
+    }
+
+    /// `verify`, for an opening bundled with its commitment and evaluation
+    pub fn verify_opening(&self, opening: &KZGOpening<E>) -> bool {
+        self.verify(opening.y, opening.z, opening.commitment, opening.pi)
+    }
+
+    /// Challenges r^0, ..., r^{n-1} for aggregating `openings`, r being hashed from all of them (proofs included)
+    pub fn aggregation_challenges(openings: &[KZGOpening<E>]) -> Vec<E::ScalarField> {
+        let mut bytes = vec![];
+        openings.serialize_compressed(&mut bytes).unwrap();
+        let r: E::ScalarField = hash_to_field(&bytes, 0);
+        let mut r_i = E::ScalarField::one();
+        (0..openings.len())
+            .map(|_| {
+                let r_prev = r_i;
+                r_i *= r;
+                r_prev
+            })
+            .collect()
+    }
+
+    /// sum_i challenges[i] * proofs[i]
+    pub fn aggregate_proofs(proofs: &[E::G1], challenges: &[E::ScalarField]) -> E::G1 {
+        assert_eq!(proofs.len(), challenges.len());
+        msm(proofs, challenges).unwrap()
+    }
+
+    /// Batch verification of openings of many polynomials at many points, as done for eip-4844 blob proofs:
+    /// e(sum_i r_i pi_i, [tau]_2) == e(sum_i r_i (C_i - [y_i]_1 + z_i pi_i), g2)
+    /// The n openings cost a single 2-pairings check, but the z_i pi_i terms need every proof: this saves
+    /// verifier work, not proof size. A single aggregated proof suffices at a single point, see
+    /// `verify_aggregated_at_point`, and at many points with `shplonk`.
+    /// The r_i are `aggregation_challenges`, hashed from the openings.
+    pub fn verify_batch(&self, openings: &[KZGOpening<E>]) -> bool {
+        let proofs = openings.iter().map(|o| o.pi).collect::<Vec<_>>();
+        let commitments = openings.iter().map(|o| o.commitment).collect::<Vec<_>>();
+        if !check_subgroup_all(&proofs) || !check_subgroup_all(&commitments) {
+            return false;
+        }
+        let challenges = Self::aggregation_challenges(openings);
+        let aggregated_pi = Self::aggregate_proofs(&proofs, &challenges);
+        let z_pi = msm(
+            &proofs,
+            &openings
+                .iter()
+                .zip(&challenges)
+                .map(|(o, r)| o.z * r)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let y: E::ScalarField = openings.iter().zip(&challenges).map(|(o, r)| o.y * r).sum();
+        let rhs = msm(&commitments, &challenges).unwrap() - self.g1 * y + z_pi;
+        multi_pairing_check::<E>(&[(aggregated_pi, self.vk), (-rhs, self.g2)])
+    }
+
+    /// Verifies openings of many polynomials at a single point z against one aggregated proof,
+    /// pi = sum_i r_i pi_i: e(pi, [tau - z]_2) == e(sum_i r_i (C_i - [y_i]_1), g2)
+    /// The challenges must be sampled after the commitments, z and the evaluations are fixed.
+    pub fn verify_aggregated_at_point(
+        &self,
+        commitments: &[E::G1],
+        z: E::ScalarField,
+        y_values: &[E::ScalarField],
+        challenges: &[E::ScalarField],
+        aggregated_pi: E::G1,
+    ) -> bool {
+        if commitments.len() != y_values.len() || commitments.len() != challenges.len() {
+            return false;
+        }
+        if !check_subgroup_all(commitments) || !aggregated_pi.check_subgroup() {
+            return false;
+        }
+        let y: E::ScalarField = y_values.iter().zip(challenges).map(|(y, r)| *y * r).sum();
+        let rhs = msm(commitments, challenges).unwrap() - self.g1 * y;
+        multi_pairing_check::<E>(&[(aggregated_pi, self.vk - self.g2 * z), (-rhs, self.g2)])
+    }
+
+    /// Degree bound verification: checks that `shifted_commitment` commits to X^{D - d} * p(X),
+    /// where `commitment` commits to p(X). This holds only if deg(p) <= d.
+    /// e(commitment, [tau^{D - d}]_2) == e(shifted_commitment, g2)
+    pub fn verify_degree_bound(
+        &self,
+        commitment: E::G1,
+        shifted_commitment: E::G1,
+        degree_bound: usize,
+    ) -> bool {
+        if degree_bound > self.degree {
+            return false;
+        }
+        if !commitment.check_subgroup() || !shifted_commitment.check_subgroup() {
+            return false;
+        }
+        let tau_shift = self.crs_2[self.degree - degree_bound];
+        multi_pairing_check::<E>(&[(commitment, tau_shift), (-shifted_commitment, self.g2)])
     }
 
     /// This is the same as `verify` but re-wrote as to avoid any operations in G2
@@ -115,13 +683,16 @@ impl<E: Pairing> KZG<E> {
         commitment: E::G1,
         pi: E::G1,
     ) -> bool {
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
         let py = self.g1 * y;
-        let g2_neg = -self.g2;
-        let lhs_1 = E::pairing(pi, self.vk);
-        let lhs_2 = E::pairing(pi * z, g2_neg);
-        let lhs = lhs_1.0 * lhs_2.0;
-        let rhs = E::pairing(commitment - py, self.g2);
-        lhs == rhs.0
+        // e(pi, [tau]_2) * e(z * pi, -g2) == e(commitment - [y]_1, g2)
+        multi_pairing_check::<E>(&[
+            (pi, self.vk),
+            (pi * z, -self.g2),
+            (py - commitment, self.g2),
+        ])
     }
 
     /// This is the same as `verify_no_g2_ops` but with the pairing written as an EVM opcode.
@@ -133,70 +704,187 @@ impl<E: Pairing> KZG<E> {
         commitment: E::G1,
         pi: E::G1,
     ) -> bool {
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
         let py = self.g1 * y;
-        let lhs = E::pairing(pi, self.vk);
-        let rhs = E::pairing(pi * -z - commitment + py, self.g2);
-        (lhs.0 * rhs.0).is_one()
+        multi_pairing_check::<E>(&[(pi, self.vk), (pi * -z - commitment + py, self.g2)])
     }
 
-    pub fn verify_from_encrypted_y(
+    /// Multi-point kzg verification: e([Z(tau)]_1, pi) == e(commitment - [I(tau)]_1, g2)
+    /// I, interpolating the (z_i, y_i), and Z, vanishing on the z_i, are computed here.
+    /// The z values must be distinct, and fewer than the srs degree.
+    pub fn verify_multi_open(
         &self,
-        py: E::G1,
-        z: E::ScalarField,
-        commitment: E::G1,
-        pi: E::G1,
+        commitment: &E::G1,
+        z_values: &[E::ScalarField],
+        y_values: &[E::ScalarField],
+        pi: &E::G2,
     ) -> bool {
-        let pz = self.g2 * z;
-        let lhs = E::pairing(pi, self.vk - pz);
-        let rhs = E::pairing(commitment - py, self.g2);
-        lhs == rhs
+        let n = z_values.len();
+        if n == 0 || n != y_values.len() || n >= self.crs.len() {
+            return false;
+        }
+        let mut sorted = z_values.to_vec();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|w| w[0] == w[1]) {
+            return false;
+        }
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
+        let lagrange_polynomial = compute_lagrange_interpolation_on_points(z_values, y_values);
+        let zero_polynomial = build_zero_polynomial(z_values);
+        let z_tau = msm(
+            &self.crs[..zero_polynomial.coeffs.len()],
+            &zero_polynomial.coeffs,
+        )
+        .unwrap();
+        let i_tau = msm(
+            &self.crs[..lagrange_polynomial.coeffs.len()],
+            &lagrange_polynomial.coeffs,
+        )
+        .unwrap();
+        multi_pairing_check::<E>(&[(z_tau, *pi), (i_tau - commitment, self.g2)])
     }
 
-    /// Verify a multi-open proof for a polynomial `p` at points `z_values`.
-    /// Verifier can compute the zero polynomial on its own.
-    /// The zero poly is provided here since we emulate what would be done within the EVM.
-    /// This is because computing the zero poly would be quite expensive on the EVM.
+    /// `verify_multi_open` as the EVM verifier runs it, to test the generated contract against:
+    /// computing I and Z would be expensive on the EVM, so they are provided, and checked against the (z_i, y_i).
+    /// Outside of the EVM, use `verify_multi_open`.
     pub fn verify_multi_open_no_g2_ops(
         &self,
         commitment: &E::G1,
-        z_values: &Vec<E::ScalarField>,
-        y_values: &Vec<E::ScalarField>, // evaluations of \phi(z)
+        z_values: &[E::ScalarField],
+        y_values: &[E::ScalarField], // evaluations of \phi(z)
         lagrange_polynomial: &DensePolynomial<E::ScalarField>,
         zero_polynomial: &DensePolynomial<E::ScalarField>,
         pi: &E::G2,
     ) -> bool {
-        // 1. check that lagrange interpolated poly is correct
-        let _ = z_values
-            .iter()
-            .zip(y_values)
-            .map(|(z, y)| assert_eq!(lagrange_polynomial.evaluate(&z), *y));
+        if z_values.len() != y_values.len()
+            || zero_polynomial.degree() != z_values.len()
+            || zero_polynomial.coeffs.len() > self.crs.len()
+            || lagrange_polynomial.coeffs.len() > self.crs.len()
+        {
+            return false;
+        }
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
 
+        // 1. check that the lagrange polynomial interpolates the (z_i, y_i)
         // 2. check that the zero polynomial is zero at all z_values
-        let _ = z_values
-            .iter()
-            .map(|z| assert_eq!(zero_polynomial.evaluate(&z), E::ScalarField::ZERO));
+        for (z, y) in z_values.iter().zip(y_values) {
+            if lagrange_polynomial.evaluate(z) != *y
+                || zero_polynomial.evaluate(z) != E::ScalarField::ZERO
+            {
+                return false;
+            }
+        }
 
         // 3. Compute input values to pairing
-        let z_tau = zero_polynomial
-            .coeffs
-            .iter()
-            .zip(&self.crs)
-            .fold(E::G1::zero(), |acc, (coeff, tau)| acc + *tau * coeff);
-        let i_tau = lagrange_polynomial
-            .coeffs
-            .iter()
-            .zip(&self.crs)
-            .fold(E::G1::zero(), |acc, (coeff, tau)| acc + *tau * coeff);
-        (E::pairing(z_tau, pi).0 * E::pairing(-*commitment + i_tau, self.g2).0).is_one()
+        let z_tau = msm(
+            &self.crs[..zero_polynomial.coeffs.len()],
+            &zero_polynomial.coeffs,
+        )
+        .unwrap();
+        let i_tau = msm(
+            &self.crs[..lagrange_polynomial.coeffs.len()],
+            &lagrange_polynomial.coeffs,
+        )
+        .unwrap();
+        multi_pairing_check::<E>(&[(z_tau, *pi), (i_tau - commitment, self.g2)])
+    }
+
+    /// Single point kzg verification, where the pairing check is deferred into `obligations`.
+    /// Only the cheap checks are run here: returns false if they already fail.
+    /// The deferred check is e(pi, vk) * e(-z * pi - commitment + y * g1, g2) == 1
+    pub fn verify_lazy(
+        &self,
+        y: E::ScalarField,
+        z: E::ScalarField,
+        commitment: E::G1,
+        pi: E::G1,
+        obligations: &mut ObligationSet<E>,
+    ) -> bool {
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
+        obligations.push(Obligation::pairing_product(
+            vec![
+                MsmTerm::from_point(pi),
+                MsmTerm::new(
+                    vec![pi, commitment, self.g1],
+                    vec![-z, -E::ScalarField::ONE, y],
+                ),
+            ],
+            vec![self.vk, self.g2],
+        ));
+        true
+    }
+
+    /// Multi-point kzg verification, where both msms (committing to the zero and lagrange polynomials)
+    /// and the pairing check are deferred into `obligations`.
+    /// Only the cheap checks are run here: returns false if they already fail.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_multi_open_lazy(
+        &self,
+        commitment: &E::G1,
+        z_values: &[E::ScalarField],
+        y_values: &[E::ScalarField],
+        lagrange_polynomial: &DensePolynomial<E::ScalarField>,
+        zero_polynomial: &DensePolynomial<E::ScalarField>,
+        pi: &E::G2,
+        obligations: &mut ObligationSet<E>,
+    ) -> bool {
+        if z_values.len() != y_values.len()
+            || zero_polynomial.degree() != z_values.len()
+            || zero_polynomial.coeffs.len() > self.crs.len()
+            || lagrange_polynomial.coeffs.len() > self.crs.len()
+        {
+            return false;
+        }
+        if !commitment.check_subgroup() || !pi.check_subgroup() {
+            return false;
+        }
+        for (z, y) in z_values.iter().zip(y_values) {
+            if lagrange_polynomial.evaluate(z) != *y
+                || zero_polynomial.evaluate(z) != E::ScalarField::ZERO
+            {
+                return false;
+            }
+        }
+        let n_zero = zero_polynomial.coeffs.len();
+        let n_lagrange = lagrange_polynomial.coeffs.len();
+        let mut i_bases = self.crs[..n_lagrange].to_vec();
+        let mut i_scalars = lagrange_polynomial.coeffs.clone();
+        i_bases.push(*commitment);
+        i_scalars.push(-E::ScalarField::ONE);
+        obligations.push(Obligation::pairing_product(
+            vec![
+                MsmTerm::new(self.crs[..n_zero].to_vec(), zero_polynomial.coeffs.clone()),
+                MsmTerm::new(i_bases, i_scalars),
+            ],
+            vec![*pi, self.g2],
+        ));
+        true
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::cs::pcs::kzg::KZG;
-    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
-    use ark_ff::{Field, UniformRand};
-    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use crate::cs::obligations::ObligationSet;
+    use crate::cs::pcs::kzg::{encrypted_y::EncryptedYError, KZGOpening, MultiOpenProof, KZG};
+    use crate::error::AlgorithmsError;
+    use crate::setup::secret::Secret;
+    use crate::utils::polynomial::{linear_combination, sparse::SparsePolynomial};
+    use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::{Field, UniformRand, Zero};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+        Polynomial,
+    };
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::test_rng;
 
     #[test]
@@ -208,14 +896,51 @@ mod tests {
         let g2 = G2Projective::rand(&mut rng);
         let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
         let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
-        let _ = kzg.setup(tau);
-        let commitment = kzg.commit(&polynomial);
+        kzg.setup(Secret::new(tau));
+        let commitment = kzg.commit(&polynomial).unwrap();
         let z = Fr::rand(&mut rng);
         let y = polynomial.evaluate(&z);
-        let pi = kzg.open(&polynomial, z, y);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
         assert!(kzg.verify(y, z, commitment, pi));
         assert!(kzg.verify_no_g2_ops(y, z, commitment, pi));
         assert!(kzg.verify_no_g2_ops_evm_opcode(y, z, commitment, pi));
+        assert_eq!(kzg.commit(&polynomial), Ok(commitment));
+        assert_eq!(kzg.open_unchecked(&polynomial, z, y).unwrap(), pi);
+
+        // y isn't an evaluation, or the polynomial doesn't fit in the srs
+        let wrong_y = y + Fr::from(1u64);
+        assert_eq!(
+            kzg.open(&polynomial, z, wrong_y),
+            Err(AlgorithmsError::NonZeroRemainder)
+        );
+        let forged = kzg.open_unchecked(&polynomial, z, wrong_y).unwrap();
+        assert!(!kzg.verify(wrong_y, z, commitment, forged));
+        let too_large: DensePolynomial<Fr> = DensePolynomial::rand(degree + 1, &mut rng);
+        let error = AlgorithmsError::DegreeTooLarge {
+            degree: degree + 1,
+            max_degree: degree,
+        };
+        assert_eq!(kzg.commit(&too_large), Err(error.clone()));
+        assert_eq!(kzg.open(&too_large, z, too_large.evaluate(&z)), Err(error));
+
+        // tau drawn by the scheme itself
+        let mut random_kzg = KZG::<Bn254>::new(g1, g2, degree);
+        random_kzg.setup_from_rng(&mut rng);
+        assert!(random_kzg.check_srs());
+        let pi = random_kzg.open(&polynomial, z, y).unwrap();
+        assert!(random_kzg.verify(y, z, random_kzg.commit(&polynomial).unwrap(), pi));
+
+        // tau hashed from a seed: the same srs on every run
+        let mut seeded_kzg = KZG::<Bn254>::new(g1, g2, degree);
+        seeded_kzg.setup_from_seed(b"test");
+        let mut same_seed = KZG::<Bn254>::new(g1, g2, degree);
+        same_seed.setup_from_seed(b"test");
+        assert_eq!(seeded_kzg, same_seed);
+        let mut other_seed = KZG::<Bn254>::new(g1, g2, degree);
+        other_seed.setup_from_seed(b"other");
+        assert_ne!(seeded_kzg.vk(), other_seed.vk());
+        let pi = seeded_kzg.open(&polynomial, z, y).unwrap();
+        assert!(seeded_kzg.verify(y, z, seeded_kzg.commit(&polynomial).unwrap(), pi));
     }
 
     #[test]
@@ -227,14 +952,15 @@ mod tests {
         let g2 = G2Projective::rand(&mut rng);
         let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
         let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
-        let _ = kzg.setup(tau);
-        let commitment = kzg.commit(&polynomial);
+        kzg.setup(Secret::new(tau));
+        let commitment = kzg.commit(&polynomial).unwrap();
         let z_values = vec![Fr::ZERO, Fr::ONE]; // evaluations proven at 0 and 1
         let y_values = z_values
             .iter()
             .map(|z| polynomial.evaluate(z))
             .collect::<Vec<_>>();
-        let (pi, lagrange_polynomial, zero_polynomial) = kzg.multi_open(&polynomial, &z_values);
+        let (pi, lagrange_polynomial, zero_polynomial) =
+            kzg.multi_open(&polynomial, &z_values).unwrap();
         let result = kzg.verify_multi_open_no_g2_ops(
             &commitment,
             &z_values,
@@ -256,5 +982,509 @@ mod tests {
             &pi,
         );
         assert!(!result);
+        assert!(kzg.verify_multi_open(&commitment, &z_values, &y_values, &pi));
+        assert!(!kzg.verify_multi_open(&wrong_commitment, &z_values, &y_values, &pi));
+
+        // evaluations that the lagrange polynomial doesn't interpolate
+        let wrong_y_values = vec![y_values[0], y_values[1] + Fr::ONE];
+        assert!(!kzg.verify_multi_open_no_g2_ops(
+            &commitment,
+            &z_values,
+            &wrong_y_values,
+            &lagrange_polynomial,
+            &zero_polynomial,
+            &pi,
+        ));
+        assert!(!kzg.verify_multi_open(&commitment, &z_values, &wrong_y_values, &pi));
+    }
+
+    #[test]
+    pub fn test_multi_open_proof() {
+        let mut rng = test_rng();
+        let degree = 7;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z_values = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let proof = kzg.multi_open_proof(&polynomial, &z_values).unwrap();
+        assert_eq!(proof.pi, kzg.multi_open(&polynomial, &z_values).unwrap().0);
+        for (z, y) in z_values.iter().zip(&proof.y_values) {
+            assert_eq!(polynomial.evaluate(z), *y);
+        }
+        assert!(proof.verify(&kzg, &commitment));
+
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = MultiOpenProof::<Bn254>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(decoded, proof);
+
+        // a wrong evaluation, a wrong commitment, repeated points, or too many points for the srs
+        let mut wrong = proof.clone();
+        wrong.y_values[1] += Fr::ONE;
+        assert!(!wrong.verify(&kzg, &commitment));
+        assert!(!proof.verify(&kzg, &(commitment + kzg.g1)));
+        let mut repeated = proof.clone();
+        repeated.z_values[1] = repeated.z_values[0];
+        repeated.y_values[1] = repeated.y_values[0];
+        assert!(!repeated.verify(&kzg, &commitment));
+        let mut truncated = proof.clone();
+        truncated.y_values.pop();
+        assert!(!truncated.verify(&kzg, &commitment));
+        let z_values = (0..=degree).map(|i| Fr::from(i as u64)).collect::<Vec<_>>();
+        let too_many = kzg.multi_open_proof(&polynomial, &z_values).unwrap();
+        assert!(!too_many.verify(&kzg, &commitment));
+
+        // a polynomial whose quotient doesn't fit in the G2 srs, repeated or no points
+        let too_large = DensePolynomial::<Fr>::rand(degree + 2, &mut rng);
+        assert!(matches!(
+            kzg.multi_open_proof(&too_large, &z_values[..1]),
+            Err(AlgorithmsError::DegreeTooLarge { .. })
+        ));
+        assert!(kzg
+            .multi_open_proof(&polynomial, &[z_values[0], z_values[0]])
+            .is_err());
+        assert!(kzg.multi_open_proof(&polynomial, &[]).is_err());
+    }
+
+    #[test]
+    pub fn test_lazy_kzg_verification() {
+        let mut rng = test_rng();
+        let degree = 7;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+
+        // batch a few single point openings and a multi-open
+        let mut obligations = ObligationSet::<Bn254>::new();
+        for _ in 0..3 {
+            let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+            let commitment = kzg.commit(&polynomial).unwrap();
+            let z = Fr::rand(&mut rng);
+            let y = polynomial.evaluate(&z);
+            let pi = kzg.open(&polynomial, z, y).unwrap();
+            assert!(kzg.verify_lazy(y, z, commitment, pi, &mut obligations));
+        }
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z_values = vec![Fr::ZERO, Fr::ONE, Fr::from(2)];
+        let y_values = z_values
+            .iter()
+            .map(|z| polynomial.evaluate(z))
+            .collect::<Vec<_>>();
+        let (pi, lagrange_polynomial, zero_polynomial) =
+            kzg.multi_open(&polynomial, &z_values).unwrap();
+        assert!(kzg.verify_multi_open_lazy(
+            &commitment,
+            &z_values,
+            &y_values,
+            &lagrange_polynomial,
+            &zero_polynomial,
+            &pi,
+            &mut obligations,
+        ));
+        assert_eq!(obligations.len(), 4);
+        assert!(obligations.discharge());
+        assert!(obligations.discharge_batched(&mut rng));
+        assert!(obligations.discharge_sampled(2, &mut rng));
+
+        // cheap checks catch wrong claimed evaluations right away
+        let wrong_y_values = vec![Fr::ZERO; 3];
+        assert!(!kzg.verify_multi_open_lazy(
+            &commitment,
+            &z_values,
+            &wrong_y_values,
+            &lagrange_polynomial,
+            &zero_polynomial,
+            &pi,
+            &mut obligations,
+        ));
+
+        // a wrong opening passes the cheap checks but is caught when discharging
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+        assert!(kzg.verify_lazy(y + Fr::ONE, z, commitment, pi, &mut obligations));
+        assert!(!obligations.discharge());
+        assert!(!obligations.discharge_batched(&mut rng));
+    }
+
+    #[test]
+    pub fn test_commit_evals() {
+        let mut rng = test_rng();
+        let degree = 7;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+
+        let domain = GeneralEvaluationDomain::<Fr>::new(degree + 1).unwrap();
+        kzg.setup_lagrange_crs(domain);
+
+        // [L_i(tau)]_1 matches the lagrange polynomials evaluated at tau
+        let lagrange_at_tau = domain.evaluate_all_lagrange_coefficients(tau);
+        for (l_i, l_i_tau) in kzg.crs_lagrange.iter().zip(lagrange_at_tau) {
+            assert_eq!(*l_i, g1 * l_i_tau);
+        }
+
+        // committing in evaluation form is the same as committing to the interpolated polynomial
+        let evals = (0..degree + 1)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let polynomial = DensePolynomial::from_coefficients_vec(domain.ifft(&evals));
+        assert_eq!(
+            kzg.commit_evals(&evals, domain),
+            kzg.commit(&polynomial).unwrap()
+        );
+
+        // and the commitment can be opened as usual
+        let commitment = kzg.commit_evals(&evals, domain);
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+        assert!(kzg.verify(y, z, commitment, pi));
+    }
+
+    #[test]
+    pub fn test_verifiers_reject_invalid_points() {
+        let mut rng = test_rng();
+        let degree = 5;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+        assert!(kzg.check_srs());
+
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+
+        // a proof which is not on the curve, as could come out of an unchecked deserialization
+        let pi_affine = pi.into_affine();
+        let invalid_pi: G1Projective =
+            G1Affine::new_unchecked(pi_affine.x, pi_affine.y + Fq::ONE).into();
+        assert!(!kzg.verify(y, z, commitment, invalid_pi));
+        assert!(!kzg.verify_no_g2_ops(y, z, commitment, invalid_pi));
+        assert!(!kzg.verify_no_g2_ops_evm_opcode(y, z, commitment, invalid_pi));
+        assert_eq!(
+            kzg.verify_from_encrypted_y(g1 * y, z, commitment, invalid_pi),
+            Err(EncryptedYError::InvalidPoint)
+        );
+        assert!(!kzg.verify(y, z, invalid_pi, pi));
+
+        // same goes for the srs
+        kzg.crs[1] = invalid_pi;
+        assert!(!kzg.check_srs());
+    }
+
+    #[test]
+    pub fn test_trim_and_extend_srs() {
+        let mut rng = test_rng();
+        let degree = 8;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+
+        let trimmed = kzg.trim(3).unwrap();
+        assert_eq!(trimmed.degree(), 3);
+        assert_eq!(trimmed.crs(), &kzg.crs()[..4]);
+        assert!(trimmed.verify_srs());
+        assert!(kzg.trim(0).unwrap().verify_srs());
+        assert_eq!(
+            kzg.trim(degree + 1),
+            Err(AlgorithmsError::DegreeTooLarge {
+                degree: degree + 1,
+                max_degree: degree
+            })
+        );
+
+        // the trimmed srs commits and opens as the full one, up to its degree
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(3, &mut rng);
+        let commitment = trimmed.commit(&polynomial).unwrap();
+        assert_eq!(commitment, kzg.commit(&polynomial).unwrap());
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = trimmed.open(&polynomial, z, y).unwrap();
+        assert!(kzg.verify(y, z, commitment, pi));
+        let too_large: DensePolynomial<Fr> = DensePolynomial::rand(4, &mut rng);
+        assert_eq!(
+            trimmed.commit(&too_large),
+            Err(AlgorithmsError::DegreeTooLarge {
+                degree: 4,
+                max_degree: 3
+            })
+        );
+
+        // extending back with the ceremony powers recovers the full srs
+        let mut extended = trimmed.clone();
+        extended.extend(&kzg.crs()[4..], &kzg.crs_2()[4..]).unwrap();
+        assert_eq!(extended, kzg);
+        // powers from another ceremony are rejected
+        let mut other = KZG::<Bn254>::new(kzg.g1(), kzg.g2(), degree);
+        other.setup_from_rng(&mut rng);
+        let mut extended = trimmed.clone();
+        assert_eq!(
+            extended.extend(&other.crs()[4..], &other.crs_2()[4..]),
+            Err(AlgorithmsError::InvalidSrs)
+        );
+        assert_eq!(extended, trimmed);
+    }
+
+    #[test]
+    pub fn test_commit_sparse() {
+        let mut rng = test_rng();
+        let degree = 64;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+
+        // a selector-like polynomial
+        let sparse = SparsePolynomial::from_terms(vec![
+            (0, Fr::rand(&mut rng)),
+            (17, Fr::rand(&mut rng)),
+            (degree, Fr::rand(&mut rng)),
+        ]);
+        let commitment = kzg.commit_sparse(&sparse).unwrap();
+        assert_eq!(commitment, kzg.commit(&sparse.to_dense()).unwrap());
+        let z = Fr::rand(&mut rng);
+        let y = sparse.evaluate(&z);
+        let pi = kzg.open(&sparse.to_dense(), z, y).unwrap();
+        assert!(kzg.verify(y, z, commitment, pi));
+
+        let too_large = SparsePolynomial::from_terms(vec![(degree + 1, Fr::ONE)]);
+        assert_eq!(
+            kzg.commit_sparse(&too_large),
+            Err(AlgorithmsError::DegreeTooLarge {
+                degree: degree + 1,
+                max_degree: degree
+            })
+        );
+        assert!(kzg
+            .commit_sparse(&SparsePolynomial::from_terms(vec![]))
+            .unwrap()
+            .is_zero());
+    }
+
+    #[test]
+    pub fn test_aggregated_openings() {
+        let mut rng = test_rng();
+        let degree = 8;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+
+        // many polynomials, each opened at its own point
+        let polynomials = (0..5)
+            .map(|_| DensePolynomial::<Fr>::rand(degree, &mut rng))
+            .collect::<Vec<_>>();
+        let mut openings = polynomials
+            .iter()
+            .map(|p| {
+                let z = Fr::rand(&mut rng);
+                let y = p.evaluate(&z);
+                KZGOpening {
+                    commitment: kzg.commit(p).unwrap(),
+                    z,
+                    y,
+                    pi: kzg.open(p, z, y).unwrap(),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert!(kzg.verify_batch(&openings));
+        let mut swapped = openings.clone();
+        swapped.swap(0, 1);
+        swapped[0].pi = openings[0].pi;
+        assert!(!kzg.verify_batch(&swapped));
+        openings[2].y += Fr::ONE;
+        assert!(!kzg.verify_batch(&openings));
+
+        // at a single point, the aggregated proof is all the verifier needs
+        let z = Fr::rand(&mut rng);
+        let commitments = polynomials
+            .iter()
+            .map(|p| kzg.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let mut y_values = polynomials
+            .iter()
+            .map(|p| p.evaluate(&z))
+            .collect::<Vec<_>>();
+        let proofs = polynomials
+            .iter()
+            .zip(&y_values)
+            .map(|(p, y)| kzg.open(p, z, *y).unwrap())
+            .collect::<Vec<_>>();
+        let challenges = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let aggregated_pi = KZG::<Bn254>::aggregate_proofs(&proofs, &challenges);
+        assert!(kzg.verify_aggregated_at_point(
+            &commitments,
+            z,
+            &y_values,
+            &challenges,
+            aggregated_pi
+        ));
+        y_values[0] += Fr::ONE;
+        assert!(!kzg.verify_aggregated_at_point(
+            &commitments,
+            z,
+            &y_values,
+            &challenges,
+            aggregated_pi
+        ));
+    }
+
+    #[test]
+    pub fn test_linear_combination() {
+        let mut rng = test_rng();
+        let degree = 8;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+        let polynomials = (0..4)
+            .map(|_| DensePolynomial::<Fr>::rand(degree, &mut rng))
+            .collect::<Vec<_>>();
+        let weights = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+
+        // the combined commitment is the commitment to the combined polynomial
+        let commitment = KZG::<Bn254>::commit_linear_combination(
+            &weights
+                .iter()
+                .zip(&polynomials)
+                .map(|(w, p)| (*w, kzg.commit(p).unwrap()))
+                .collect::<Vec<_>>(),
+        );
+        let terms = weights
+            .iter()
+            .copied()
+            .zip(&polynomials)
+            .collect::<Vec<_>>();
+        let combination = linear_combination(&terms);
+        assert_eq!(commitment, kzg.commit(&combination).unwrap());
+
+        let z = Fr::rand(&mut rng);
+        let (y, pi) = kzg.open_linear_combination(&terms, z).unwrap();
+        assert_eq!(y, combination.evaluate(&z));
+        assert!(kzg.verify(y, z, commitment, pi));
+        assert!(!kzg.verify(y, z, commitment - kzg.g1(), pi));
+    }
+
+    #[test]
+    pub fn test_verify_srs() {
+        let mut rng = test_rng();
+        let degree = 6;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            degree,
+        );
+        kzg.setup_from_rng(&mut rng);
+        assert!(kzg.verify_srs());
+        // an imported srs
+        assert!(KZG::<Bn254>::from_srs(kzg.crs.clone(), kzg.crs_2.clone()).verify_srs());
+        assert!(KZG::<Bn254>::from_srs(kzg.crs.clone(), kzg.crs_2[..2].to_vec()).verify_srs());
+
+        // a G1 power which isn't tau times the previous one
+        let mut tampered = kzg.clone();
+        tampered.crs[3] += tampered.g1;
+        assert!(!tampered.verify_srs());
+        // same in G2
+        let mut tampered = kzg.clone();
+        tampered.crs_2[degree] += tampered.g2;
+        assert!(!tampered.verify_srs());
+        // vk from another tau
+        let mut tampered = kzg.clone();
+        tampered.vk += tampered.g2;
+        assert!(!tampered.verify_srs());
+        // swapped powers
+        let mut tampered = kzg.clone();
+        tampered.crs.swap(2, 3);
+        assert!(!tampered.verify_srs());
+        // tau = 1 passes the pairing relations, but is known
+        let mut trivial = KZG::<Bn254>::new(kzg.g1, kzg.g2, degree);
+        trivial.setup(Secret::new(Fr::ONE));
+        assert!(!trivial.verify_srs());
+    }
+
+    #[test]
+    pub fn test_degree_bound() {
+        let mut rng = test_rng();
+        let degree = 10;
+        let degree_bound = 4;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree_bound, &mut rng);
+        let (commitment, shifted_commitment) =
+            kzg.commit_with_degree_bound(&polynomial, degree_bound);
+        assert!(kzg.verify_degree_bound(commitment, shifted_commitment, degree_bound));
+
+        // the commitment to p still opens as usual
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+        assert!(kzg.verify(y, z, commitment, pi));
+
+        // a tighter bound than the actual degree does not verify
+        assert!(!kzg.verify_degree_bound(commitment, shifted_commitment, degree_bound - 1));
+
+        // a prover with a higher degree polynomial can only shift it by less than D - d
+        let high_degree_polynomial: DensePolynomial<Fr> =
+            DensePolynomial::rand(degree_bound + 2, &mut rng);
+        let commitment = kzg.commit(&high_degree_polynomial).unwrap();
+        let under_shifted_commitment =
+            kzg.commit_shifted(&high_degree_polynomial, degree_bound + 2);
+        assert!(!kzg.verify_degree_bound(commitment, under_shifted_commitment, degree_bound));
+
+        // bounds above the srs degree are rejected
+        assert!(!kzg.verify_degree_bound(commitment, commitment, degree + 1));
+    }
+
+    #[test]
+    pub fn test_open_all() {
+        let mut rng = test_rng();
+        let degree = 15;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+        let polynomial: DensePolynomial<Fr> = DensePolynomial::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+
+        // domain as large as the polynomial, and smaller than the polynomial
+        for domain_size in [degree + 1, 4] {
+            let domain = GeneralEvaluationDomain::<Fr>::new(domain_size).unwrap();
+            let proofs = kzg.open_all(&polynomial, domain);
+            assert_eq!(proofs.len(), domain.size());
+            for (z, pi) in domain.elements().zip(proofs) {
+                let y = polynomial.evaluate(&z);
+                assert_eq!(pi, kzg.open(&polynomial, z, y).unwrap());
+                assert!(kzg.verify(y, z, commitment, pi));
+            }
+        }
     }
 }
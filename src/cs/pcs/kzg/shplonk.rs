@@ -0,0 +1,272 @@
+// Shplonk, from BDFG20: https://eprint.iacr.org/2020/081 (section 4)
+// Opens polynomials f_0, ..., f_{k-1} at (possibly different) point sets S_0, ..., S_{k-1}
+// with a constant size proof (two G1 points), verified with two pairings.
+// Notation: T is the union of the S_i, Z_S the vanishing polynomial of S, r_i interpolates f_i over S_i.
+// gamma and z are Fiat-Shamir challenges: z is only sampled once W is absorbed, as knowing z
+// beforehand lets a prover forge W for any claimed evaluations.
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+use ark_std::Zero;
+
+use super::KZG;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::{
+    build_zero_polynomial,
+    curve::SubgroupCheck,
+    lagrange::compute_lagrange_interpolation_on_points,
+    pairing::multi_pairing_check,
+    polynomial::{divide_with_remainder, ruffini_division},
+};
+
+/// A shplonk opening proof: W = [h(tau)]_1 and W' = [L(tau) / (tau - z)]_1
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShplonkProof<E: Pairing> {
+    pub w: E::G1,
+    pub w_prime: E::G1,
+}
+
+/// Returns T, the union of all the point sets, without duplicates
+fn union_of_point_sets<F: Field>(point_sets: &[Vec<F>]) -> Vec<F> {
+    let mut union = vec![];
+    for point in point_sets.iter().flatten() {
+        if !union.contains(point) {
+            union.push(*point);
+        }
+    }
+    union
+}
+
+/// Returns T \ S
+fn complement<F: Field>(t: &[F], s: &[F]) -> Vec<F> {
+    t.iter().filter(|p| !s.contains(p)).copied().collect()
+}
+
+/// Evaluates Z_S(z) = prod_{s in S} (z - s)
+fn evaluate_zero_polynomial<F: Field>(s: &[F], z: &F) -> F {
+    s.iter().fold(F::one(), |acc, s_i| acc * (*z - s_i))
+}
+
+/// Checks that no point appears twice in a set, which would leave r_i undefined
+fn check_distinct_points<F: Field>(point_sets: &[Vec<F>]) -> Result<(), AlgorithmsError> {
+    for s_i in point_sets {
+        if s_i.iter().enumerate().any(|(j, p)| s_i[..j].contains(p)) {
+            return Err(AlgorithmsError::InvalidParameters(
+                "a point set contains the same point twice".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Starts the transcript and samples gamma, from the commitments, point sets and evaluations
+fn gamma_challenge<E: Pairing>(
+    commitments: &[E::G1],
+    point_sets: &[Vec<E::ScalarField>],
+    evaluations: &[Vec<E::ScalarField>],
+) -> (Transcript, E::ScalarField) {
+    let mut transcript = Transcript::new(b"shplonk");
+    transcript.absorb(commitments);
+    transcript.absorb(point_sets);
+    transcript.absorb(evaluations);
+    let gamma = transcript.challenge();
+    (transcript, gamma)
+}
+
+/// Samples z, once W is known
+fn z_challenge<E: Pairing>(transcript: &mut Transcript, w: &E::G1) -> E::ScalarField {
+    transcript.absorb(w);
+    transcript.challenge()
+}
+
+impl<E: Pairing> KZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Opens `polynomials[i]` at all points of `point_sets[i]`
+    /// The challenges are derived with a transcript: gamma from the commitments, point sets and evaluations,
+    /// z from W.
+    /// An error if a polynomial doesn't fit in the srs or a point set has a duplicate point
+    pub fn open_shplonk(
+        &self,
+        polynomials: &[DensePolynomial<E::ScalarField>],
+        point_sets: &[Vec<E::ScalarField>],
+    ) -> Result<ShplonkProof<E>, AlgorithmsError> {
+        if polynomials.len() != point_sets.len() {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: point_sets.len(),
+                got: polynomials.len(),
+            });
+        }
+        check_distinct_points(point_sets)?;
+        let commitments = polynomials
+            .iter()
+            .map(|p| self.commit(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let evaluations = polynomials
+            .iter()
+            .zip(point_sets)
+            .map(|(p, s_i)| s_i.iter().map(|s| p.evaluate(s)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let (mut transcript, gamma) = gamma_challenge::<E>(&commitments, point_sets, &evaluations);
+        let t = union_of_point_sets(point_sets);
+        let z_t = build_zero_polynomial(&t);
+
+        // f = sum_i gamma^i * Z_{T \ S_i} * (f_i - r_i), h = f / Z_T
+        let mut f = DensePolynomial::zero();
+        let mut interpolations = Vec::with_capacity(polynomials.len());
+        let mut gamma_i = E::ScalarField::one();
+        for ((poly, s_i), y_i) in polynomials.iter().zip(point_sets).zip(&evaluations) {
+            let r_i = compute_lagrange_interpolation_on_points(s_i, y_i);
+            let z_complement = build_zero_polynomial(&complement(&t, s_i));
+            f = &f + &(&(&(poly - &r_i) * &z_complement) * gamma_i);
+            interpolations.push((r_i, z_complement));
+            gamma_i *= gamma;
+        }
+        // each f_i - r_i vanishes on S_i, so Z_T divides f
+        let (h, remainder) = divide_with_remainder(&f, &z_t).expect("Z_T is monic, hence non-zero");
+        debug_assert!(remainder.is_zero(), "Z_T doesn't divide f");
+        let w = self.commit(&h)?;
+        let z = z_challenge::<E>(&mut transcript, &w);
+
+        // L = sum_i gamma^i * Z_{T \ S_i}(z) * (f_i - r_i(z)) - Z_T(z) * h
+        let mut l = DensePolynomial::zero();
+        let mut gamma_i = E::ScalarField::one();
+        for (poly, (r_i, z_complement)) in polynomials.iter().zip(&interpolations) {
+            let r_i_z = DensePolynomial::from_coefficients_vec(vec![r_i.evaluate(&z)]);
+            l = &l + &(&(poly - &r_i_z) * (gamma_i * z_complement.evaluate(&z)));
+            gamma_i *= gamma;
+        }
+        l = &l - &(&h * z_t.evaluate(&z));
+
+        // L(z) = 0, so L is divisible by (X - z)
+        let (q, remainder) = ruffini_division(&l, z);
+        debug_assert!(remainder.is_zero(), "L doesn't vanish at z");
+        Ok(ShplonkProof {
+            w,
+            w_prime: self.commit(&q)?,
+        })
+    }
+
+    /// Verifies that `commitments[i]` opens to `evaluations[i][j]` at `point_sets[i][j]`
+    /// Computes F = sum_i gamma^i * Z_{T \ S_i}(z) * (C_i - [r_i(z)]_1) - Z_T(z) * W
+    /// and checks e(F + z * W', g2) == e(W', [tau]_2), with the challenges of `open_shplonk`
+    pub fn verify_shplonk(
+        &self,
+        commitments: &[E::G1],
+        point_sets: &[Vec<E::ScalarField>],
+        evaluations: &[Vec<E::ScalarField>],
+        proof: &ShplonkProof<E>,
+    ) -> bool {
+        if commitments.len() != point_sets.len() || evaluations.len() != point_sets.len() {
+            return false;
+        }
+        if point_sets
+            .iter()
+            .zip(evaluations)
+            .any(|(s_i, y_i)| s_i.len() != y_i.len())
+            || check_distinct_points(point_sets).is_err()
+        {
+            return false;
+        }
+        if !proof.w.check_subgroup()
+            || !proof.w_prime.check_subgroup()
+            || !commitments.iter().all(|c| c.check_subgroup())
+        {
+            return false;
+        }
+        let (mut transcript, gamma) = gamma_challenge::<E>(commitments, point_sets, evaluations);
+        let z = z_challenge::<E>(&mut transcript, &proof.w);
+        let t = union_of_point_sets(point_sets);
+        let mut f = E::G1::zero();
+        let mut r_z = E::ScalarField::zero();
+        let mut gamma_i = E::ScalarField::one();
+        for ((commitment, s_i), y_i) in commitments.iter().zip(point_sets).zip(evaluations) {
+            let coeff = gamma_i * evaluate_zero_polynomial(&complement(&t, s_i), &z);
+            let r_i = compute_lagrange_interpolation_on_points(s_i, y_i);
+            f += *commitment * coeff;
+            r_z += coeff * r_i.evaluate(&z);
+            gamma_i *= gamma;
+        }
+        f -= self.g1 * r_z;
+        f -= proof.w * evaluate_zero_polynomial(&t, &z);
+        multi_pairing_check::<E>(&[(f + proof.w_prime * z, self.g2), (-proof.w_prime, self.vk)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cs::pcs::kzg::KZG;
+    use crate::setup::secret::Secret;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_shplonk() {
+        let mut rng = test_rng();
+        let degree = 10;
+        let tau = Fr::rand(&mut rng);
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut kzg = KZG::<Bn254>::new(g1, g2, degree);
+        kzg.setup(Secret::new(tau));
+
+        // three polynomials of different degrees, opened at overlapping point sets
+        let polynomials: Vec<DensePolynomial<Fr>> = vec![
+            DensePolynomial::rand(degree - 3, &mut rng),
+            DensePolynomial::rand(degree - 4, &mut rng),
+            DensePolynomial::rand(degree - 4, &mut rng),
+        ];
+        let points = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let point_sets = vec![
+            vec![points[0], points[1]],
+            vec![points[1], points[2], points[3]],
+            vec![points[3]],
+        ];
+        let commitments = polynomials
+            .iter()
+            .map(|p| kzg.commit(p).unwrap())
+            .collect::<Vec<_>>();
+        let evaluations = polynomials
+            .iter()
+            .zip(point_sets.iter())
+            .map(|(p, s)| s.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let proof = kzg.open_shplonk(&polynomials, &point_sets).unwrap();
+        assert!(kzg.verify_shplonk(&commitments, &point_sets, &evaluations, &proof));
+
+        // wrong evaluation
+        let mut wrong_evaluations = evaluations.clone();
+        wrong_evaluations[1][2] += Fr::one();
+        assert!(!kzg.verify_shplonk(&commitments, &point_sets, &wrong_evaluations, &proof));
+
+        // wrong commitment
+        let mut wrong_commitments = commitments.clone();
+        wrong_commitments.swap(1, 2);
+        assert!(!kzg.verify_shplonk(&wrong_commitments, &point_sets, &evaluations, &proof));
+
+        // a proof for other point sets
+        let mut other_sets = point_sets.clone();
+        other_sets[2] = vec![points[0]];
+        let other_proof = kzg.open_shplonk(&polynomials, &other_sets).unwrap();
+        assert!(!kzg.verify_shplonk(&commitments, &point_sets, &evaluations, &other_proof));
+
+        // the same point twice in a set
+        let mut duplicate_sets = point_sets.clone();
+        duplicate_sets[0] = vec![points[0], points[0]];
+        assert!(kzg.open_shplonk(&polynomials, &duplicate_sets).is_err());
+        let mut duplicate_evaluations = evaluations.clone();
+        duplicate_evaluations[0][1] = duplicate_evaluations[0][0];
+        assert!(!kzg.verify_shplonk(
+            &commitments,
+            &duplicate_sets,
+            &duplicate_evaluations,
+            &proof
+        ));
+    }
+}
@@ -0,0 +1,248 @@
+// Solidity verifier generation for bn254 KZG openings.
+// The generated contract embeds the srs of a given `KZG<Bn254>` and exposes:
+// - `verify(uint256[2] commitment, uint256 z, uint256 y, uint256[2] pi)`, the on-chain version of
+//   `verify_no_g2_ops_evm_opcode`: e(pi, vk) * e(-z * pi - commitment + y * g1, g2) == 1
+// - `verifyMultiOpen(uint256[2] commitment, uint256[] zValues, uint256[] yValues,
+//   uint256[] lagrangeCoeffs, uint256[] zeroCoeffs, uint256[4] pi)`, the on-chain version of
+//   `verify_multi_open_no_g2_ops`: e([Z(tau)]_1, pi) * e(-commitment + [I(tau)]_1, g2) == 1
+// Calldata for both functions is produced by the `evm` module, which uses the same point encodings.
+// KZGVerifier.sol, next to this module, is the contract generated for the srs seeded with "solidity".
+use ark_bn254::{Bn254, Fq, G1Projective, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+
+use super::KZG;
+
+/// Decimal representation of a field element, as a solidity uint256 literal
+fn to_uint256<F: PrimeField>(element: &F) -> String {
+    element.into_bigint().to_string()
+}
+
+/// Returns the (x, y) coordinates of a G1 point, (0, 0) being the point at infinity
+fn g1_coordinates(point: &G1Projective) -> [String; 2] {
+    let affine = point.into_affine();
+    let (x, y) = affine
+        .xy()
+        .map_or((Fq::zero(), Fq::zero()), |(x, y)| (*x, *y));
+    [to_uint256(&x), to_uint256(&y)]
+}
+
+/// Returns the (x_c1, x_c0, y_c1, y_c0) coordinates of a G2 point, as expected by the pairing precompile
+fn g2_coordinates(point: &G2Projective) -> [String; 4] {
+    let affine = point.into_affine();
+    match affine.xy() {
+        Some((x, y)) => [
+            to_uint256(&x.c1),
+            to_uint256(&x.c0),
+            to_uint256(&y.c1),
+            to_uint256(&y.c0),
+        ],
+        None => ["0", "0", "0", "0"].map(String::from),
+    }
+}
+
+fn g2_constants(name: &str, point: &G2Projective) -> String {
+    let [x_c1, x_c0, y_c1, y_c0] = g2_coordinates(point);
+    format!(
+        "    uint256 constant {name}_X_C1 = {x_c1};\n    uint256 constant {name}_X_C0 = {x_c0};\n    uint256 constant {name}_Y_C1 = {y_c1};\n    uint256 constant {name}_Y_C0 = {y_c0};\n"
+    )
+}
+
+/// Generates a self-contained solidity contract verifying single and multi point openings for `kzg`
+pub fn generate_verifier(kzg: &KZG<Bn254>, contract_name: &str) -> String {
+    let [g1_x, g1_y] = g1_coordinates(&kzg.g1);
+    let crs = kzg
+        .crs
+        .iter()
+        .flat_map(g1_coordinates)
+        .enumerate()
+        .map(|(i, c)| if i == 0 { format!("uint256({c})") } else { c })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let crs_len = kzg.crs.len();
+    let mut constants = String::new();
+    constants.push_str(&format!("    uint256 constant G1_X = {g1_x};\n"));
+    constants.push_str(&format!("    uint256 constant G1_Y = {g1_y};\n"));
+    constants.push_str(&g2_constants("G2", &kzg.g2));
+    constants.push_str(&g2_constants("VK", &kzg.vk));
+    constants.push_str(&format!("    uint256 constant CRS_LENGTH = {crs_len};\n"));
+
+    format!(
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// KZG opening verifier over bn254, generated by ark-algorithms
+contract {contract_name} {{
+    uint256 constant R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 constant Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+{constants}
+    function crs() internal pure returns (uint256[{crs_words}] memory) {{
+        return [{crs}];
+    }}
+
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {{
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool success;
+        assembly {{
+            success := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }}
+        require(success, "ecAdd failed");
+    }}
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {{
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {{
+            success := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }}
+        require(success, "ecMul failed");
+    }}
+
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {{
+        if (p[0] == 0 && p[1] == 0) {{
+            return p;
+        }}
+        return [p[0], Q - (p[1] % Q)];
+    }}
+
+    /// Checks e(a1, b1) * e(a2, b2) == 1
+    function pairing(uint256[2] memory a1, uint256[4] memory b1, uint256[2] memory a2, uint256[4] memory b2)
+        internal
+        view
+        returns (bool)
+    {{
+        uint256[12] memory input = [a1[0], a1[1], b1[0], b1[1], b1[2], b1[3], a2[0], a2[1], b2[0], b2[1], b2[2], b2[3]];
+        uint256[1] memory out;
+        bool success;
+        assembly {{
+            success := staticcall(gas(), 0x08, input, 0x180, out, 0x20)
+        }}
+        require(success, "pairing failed");
+        return out[0] == 1;
+    }}
+
+    /// Evaluates the polynomial with coefficients `coeffs` (lowest degree first) at x, mod R
+    function evaluate(uint256[] calldata coeffs, uint256 x) internal pure returns (uint256 result) {{
+        for (uint256 i = coeffs.length; i > 0; i--) {{
+            result = addmod(mulmod(result, x, R), coeffs[i - 1], R);
+        }}
+    }}
+
+    /// Computes sum_i coeffs[i] * [tau^i]_1
+    function commit(uint256[] calldata coeffs) internal view returns (uint256[2] memory acc) {{
+        uint256[{crs_words}] memory points = crs();
+        for (uint256 i = 0; i < coeffs.length; i++) {{
+            acc = ecAdd(acc, ecMul([points[2 * i], points[2 * i + 1]], coeffs[i]));
+        }}
+    }}
+
+    /// Single point opening: e(pi, vk) * e(-z * pi - commitment + y * g1, g2) == 1
+    function verify(uint256[2] calldata commitment, uint256 z, uint256 y, uint256[2] calldata pi)
+        external
+        view
+        returns (bool)
+    {{
+        if (z >= R || y >= R) {{
+            return false;
+        }}
+        uint256[2] memory p = ecAdd(ecMul(pi, R - z), negate(commitment));
+        p = ecAdd(p, ecMul([G1_X, G1_Y], y));
+        return pairing(pi, [VK_X_C1, VK_X_C0, VK_Y_C1, VK_Y_C0], p, [G2_X_C1, G2_X_C0, G2_Y_C1, G2_Y_C0]);
+    }}
+
+    /// Multi point opening: e([Z(tau)]_1, pi) * e(-commitment + [I(tau)]_1, g2) == 1
+    /// where I interpolates (zValues, yValues) and Z vanishes over zValues
+    function verifyMultiOpen(
+        uint256[2] calldata commitment,
+        uint256[] calldata zValues,
+        uint256[] calldata yValues,
+        uint256[] calldata lagrangeCoeffs,
+        uint256[] calldata zeroCoeffs,
+        uint256[4] calldata pi
+    ) external view returns (bool) {{
+        if (zValues.length != yValues.length || zeroCoeffs.length != zValues.length + 1) {{
+            return false;
+        }}
+        if (lagrangeCoeffs.length > CRS_LENGTH || zeroCoeffs.length > CRS_LENGTH) {{
+            return false;
+        }}
+        for (uint256 i = 0; i < zValues.length; i++) {{
+            if (evaluate(lagrangeCoeffs, zValues[i]) != yValues[i] % R || evaluate(zeroCoeffs, zValues[i]) != 0) {{
+                return false;
+            }}
+        }}
+        uint256[2] memory zTau = commit(zeroCoeffs);
+        uint256[2] memory iTau = ecAdd(negate(commitment), commit(lagrangeCoeffs));
+        return pairing(zTau, pi, iTau, [G2_X_C1, G2_X_C0, G2_Y_C1, G2_Y_C0]);
+    }}
+}}
+"#,
+        crs_words = 2 * crs_len,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{g1_coordinates, g2_coordinates, generate_verifier};
+    use crate::cs::pcs::kzg::{
+        evm::{VERIFY_MULTI_OPEN_SIGNATURE, VERIFY_SIGNATURE},
+        KZG,
+    };
+    use ark_bn254::{Bn254, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::Zero;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_coordinates() {
+        assert_eq!(g1_coordinates(&G1Projective::generator()), ["1", "2"]);
+        assert_eq!(g1_coordinates(&G1Projective::zero()), ["0", "0"]);
+        assert_eq!(g2_coordinates(&G2Projective::zero()), ["0", "0", "0", "0"]);
+    }
+
+    #[test]
+    pub fn test_generate_verifier() {
+        let mut rng = test_rng();
+        let degree = 3;
+        let mut kzg =
+            KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+        kzg.setup_from_rng(&mut rng);
+        let contract = generate_verifier(&kzg, "KZGVerifier");
+
+        assert!(contract.contains("contract KZGVerifier {"));
+        assert!(contract.contains("uint256 constant CRS_LENGTH = 4;"));
+        assert!(contract.contains("uint256[8] memory points = crs();"));
+        let [x_c1, x_c0, y_c1, y_c0] = g2_coordinates(&kzg.vk);
+        assert!(contract.contains(&format!("uint256 constant VK_X_C1 = {x_c1};")));
+        assert!(contract.contains(&format!("uint256 constant VK_X_C0 = {x_c0};")));
+        assert!(contract.contains(&format!("uint256 constant VK_Y_C1 = {y_c1};")));
+        assert!(contract.contains(&format!("uint256 constant VK_Y_C0 = {y_c0};")));
+        for point in kzg.crs.iter() {
+            let [x, y] = g1_coordinates(point);
+            assert!(contract.contains(&x) && contract.contains(&y));
+        }
+
+        // verifying functions match the signatures used by the calldata encoders
+        assert!(contract.contains(
+            "function verify(uint256[2] calldata commitment, uint256 z, uint256 y, uint256[2] calldata pi)"
+        ));
+        assert_eq!(
+            VERIFY_SIGNATURE,
+            "verify(uint256[2],uint256,uint256,uint256[2])"
+        );
+        assert!(contract.contains("function verifyMultiOpen("));
+        assert_eq!(
+            VERIFY_MULTI_OPEN_SIGNATURE,
+            "verifyMultiOpen(uint256[2],uint256[],uint256[],uint256[],uint256[],uint256[4])"
+        );
+    }
+
+    #[test]
+    pub fn test_generate_verifier_golden() {
+        // a deterministic srs, compared to the contract checked in next to this module
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 3);
+        kzg.setup_from_seed(b"solidity");
+        let contract = generate_verifier(&kzg, "KZGVerifier");
+        assert_eq!(contract, include_str!("KZGVerifier.sol"));
+    }
+}
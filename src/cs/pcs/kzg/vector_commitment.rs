@@ -0,0 +1,186 @@
+// Vector commitments from KZG: a vector v is seen as the evaluations of a polynomial p over a roots-of-unity
+// domain, p(omega^i) = v_i. Proving a position is a kzg opening at omega^i, several positions at once use `multi_open`.
+// Updates: changing v_j by delta adds delta * L_j(X) to p, so the commitment moves by delta * [L_j(tau)]_1
+// and the proof at position i by delta * [(L_j(X) - L_j(omega^i)) / (X - omega^i)]_1 (the update key of (i, j)).
+use ark_ec::pairing::Pairing;
+use ark_ff::Zero;
+use ark_poly::{EvaluationDomain, Evaluations, GeneralEvaluationDomain};
+
+use super::KZG;
+use crate::error::AlgorithmsError;
+use crate::utils::curve::SubgroupCheck;
+
+pub struct VectorCommitment<E: Pairing> {
+    kzg: KZG<E>,
+    domain: GeneralEvaluationDomain<E::ScalarField>,
+}
+
+impl<E: Pairing> VectorCommitment<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Vectors of up to `size` elements, `kzg` must have been setup with a large enough degree
+    pub fn new(mut kzg: KZG<E>, size: usize) -> Self {
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(size).unwrap();
+        kzg.setup_lagrange_crs(domain);
+        Self { kzg, domain }
+    }
+
+    pub fn kzg(&self) -> &KZG<E> {
+        &self.kzg
+    }
+
+    pub fn domain(&self) -> GeneralEvaluationDomain<E::ScalarField> {
+        self.domain
+    }
+
+    /// Pads `values` with zeros up to the domain size
+    fn padded(&self, values: &[E::ScalarField]) -> Vec<E::ScalarField> {
+        assert!(values.len() <= self.domain.size(), "vector is too long");
+        let mut padded = values.to_vec();
+        padded.resize(self.domain.size(), E::ScalarField::zero());
+        padded
+    }
+
+    pub fn commit(&self, values: &[E::ScalarField]) -> E::G1 {
+        self.kzg.commit_evals(&self.padded(values), self.domain)
+    }
+
+    /// Proves that the committed vector holds values[i] at position i
+    pub fn prove_index(&self, values: &[E::ScalarField], i: usize) -> E::G1 {
+        let values = self.padded(values);
+        let polynomial =
+            Evaluations::from_vec_and_domain(values.clone(), self.domain).interpolate();
+        // the interpolation takes values[i] at omega^i, and the domain fits in the srs
+        self.kzg
+            .open(&polynomial, self.domain.element(i), values[i])
+            .expect("the polynomial interpolates values over the domain")
+    }
+
+    pub fn verify_index(
+        &self,
+        commitment: E::G1,
+        i: usize,
+        value: E::ScalarField,
+        proof: E::G1,
+    ) -> bool {
+        if i >= self.domain.size() {
+            return false;
+        }
+        self.kzg
+            .verify(value, self.domain.element(i), commitment, proof)
+    }
+
+    /// Proves several positions at once, with a single G2 element
+    /// An error if an index is repeated, or if there are too many for the G2 srs
+    pub fn prove_indices(
+        &self,
+        values: &[E::ScalarField],
+        indices: &[usize],
+    ) -> Result<E::G2, AlgorithmsError> {
+        let polynomial =
+            Evaluations::from_vec_and_domain(self.padded(values), self.domain).interpolate();
+        let z_values = indices
+            .iter()
+            .map(|i| self.domain.element(*i))
+            .collect::<Vec<_>>();
+        let (pi, _, _) = self.kzg.multi_open(&polynomial, &z_values)?;
+        Ok(pi)
+    }
+
+    /// Verifies a multi position proof, the interpolation and zero polynomials are computed here
+    pub fn verify_indices(
+        &self,
+        commitment: E::G1,
+        indices: &[usize],
+        values: &[E::ScalarField],
+        proof: E::G2,
+    ) -> bool {
+        if indices.len() != values.len() || indices.iter().any(|i| *i >= self.domain.size()) {
+            return false;
+        }
+        let z_values = indices
+            .iter()
+            .map(|i| self.domain.element(*i))
+            .collect::<Vec<_>>();
+        self.kzg
+            .verify_multi_open(&commitment, &z_values, values, &proof)
+    }
+
+    /// The update key of (i, j): [(L_j(X) - L_j(omega^i)) / (X - omega^i)]_1
+    pub fn update_key(&self, i: usize, j: usize) -> E::G1 {
+        let mut unit = vec![E::ScalarField::zero(); self.domain.size()];
+        unit[j] = E::ScalarField::from(1u64);
+        let l_j = Evaluations::from_vec_and_domain(unit.clone(), self.domain).interpolate();
+        self.kzg
+            .open(&l_j, self.domain.element(i), unit[i])
+            .expect("L_j interpolates the unit vector over the domain")
+    }
+
+    /// Commitment to the vector where `delta` has been added at position j
+    pub fn update_commitment(&self, commitment: E::G1, j: usize, delta: E::ScalarField) -> E::G1 {
+        commitment + self.kzg.crs_lagrange()[j] * delta
+    }
+
+    /// Proof for position i, once `delta` has been added at position j
+    pub fn update_proof(&self, proof: E::G1, i: usize, j: usize, delta: E::ScalarField) -> E::G1 {
+        proof + self.update_key(i, j) * delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VectorCommitment;
+    use crate::cs::pcs::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_vector_commitment() {
+        let mut rng = test_rng();
+        let size = 8;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            size,
+        );
+        kzg.setup_from_rng(&mut rng);
+        let vc = VectorCommitment::new(kzg, size);
+
+        // a vector shorter than the domain is padded with zeros
+        let mut values = (0..size - 1)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let commitment = vc.commit(&values);
+        let proof = vc.prove_index(&values, 3);
+        assert!(vc.verify_index(commitment, 3, values[3], proof));
+        assert!(!vc.verify_index(commitment, 3, values[3] + Fr::one(), proof));
+        assert!(!vc.verify_index(commitment, 4, values[3], proof));
+        let padding_proof = vc.prove_index(&values, size - 1);
+        assert!(vc.verify_index(commitment, size - 1, Fr::from(0), padding_proof));
+
+        // multi position proofs
+        let indices = [0, 2, 5];
+        let opened = indices.iter().map(|i| values[*i]).collect::<Vec<_>>();
+        let multi_proof = vc.prove_indices(&values, &indices).unwrap();
+        assert!(vc.verify_indices(commitment, &indices, &opened, multi_proof));
+        let mut wrong_opened = opened.clone();
+        wrong_opened[1] += Fr::one();
+        assert!(!vc.verify_indices(commitment, &indices, &wrong_opened, multi_proof));
+
+        // updating position 5 moves the commitment, and the proofs at positions 3 and 5
+        let delta = Fr::rand(&mut rng);
+        let proof_5 = vc.prove_index(&values, 5);
+        let updated_commitment = vc.update_commitment(commitment, 5, delta);
+        let updated_proof_3 = vc.update_proof(proof, 3, 5, delta);
+        let updated_proof_5 = vc.update_proof(proof_5, 5, 5, delta);
+        values[5] += delta;
+        assert_eq!(updated_commitment, vc.commit(&values));
+        assert_eq!(updated_proof_3, vc.prove_index(&values, 3));
+        assert!(vc.verify_index(updated_commitment, 3, values[3], updated_proof_3));
+        assert!(vc.verify_index(updated_commitment, 5, values[5], updated_proof_5));
+        assert!(!vc.verify_index(updated_commitment, 3, values[3], proof));
+    }
+}
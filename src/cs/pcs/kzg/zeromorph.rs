@@ -0,0 +1,264 @@
+// Zeromorph, from KT23: https://eprint.iacr.org/2023/917
+// Commits to a multilinear f of n variables with the univariate KZG commitment of U_n(f)(X) = sum_i f_i X^i,
+// f_i being f's evaluations over the hypercube (variable k is bit k of i, as in the sumcheck module).
+// Writing f(X) - v = sum_k (X_k - u_k) * q_k(X_0, ..., X_{k-1}), the evaluation claim f(u) = v becomes
+// U_n(f)(X) - v * Phi_n(X) = sum_k (X^{2^k} * Phi_{n-k-1}(X^{2^{k+1}}) - u_k * Phi_{n-k}(X^{2^k})) * U_k(q_k)(X)
+// where Phi_n(X) = sum_{i < 2^n} X^i. Both sides are checked at a random point x with a single kzg opening.
+// The challenges come from a transcript: y once the quotient commitments are absorbed, x and z after q_hat.
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use ark_std::Zero;
+
+use super::KZG;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::curve::{check_subgroup_all, SubgroupCheck};
+
+/// A zeromorph evaluation proof: commitments to the U_k(q_k), to the batched and degree shifted
+/// quotient q_hat (with its degree bound proof), and the final kzg opening
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeromorphProof<E: Pairing> {
+    pub quotient_commitments: Vec<E::G1>,
+    pub q_hat: E::G1,
+    pub q_hat_shifted: E::G1,
+    pub pi: E::G1,
+}
+
+/// Phi_n(x) = sum_{i < 2^n} x^i
+fn phi<F: Field>(n: usize, x: F) -> F {
+    let mut result = F::zero();
+    let mut x_i = F::one();
+    for _ in 0..1 << n {
+        result += x_i;
+        x_i *= x;
+    }
+    result
+}
+
+/// Computes the evaluations of q_0, ..., q_{n-1}: q_k = f_k(.., X_{k-1}, 1) - f_k(.., X_{k-1}, 0),
+/// f_k being f with its n - k - 1 last variables set to u
+fn compute_quotients<F: Field>(evaluations: &[F], u: &[F]) -> Vec<Vec<F>> {
+    let mut quotients = vec![vec![]; u.len()];
+    let mut current = evaluations.to_vec();
+    for k in (0..u.len()).rev() {
+        let half = current.len() / 2;
+        let (low, high) = current.split_at(half);
+        let q_k = low
+            .iter()
+            .zip(high)
+            .map(|(l, h)| *h - l)
+            .collect::<Vec<_>>();
+        current = low
+            .iter()
+            .zip(q_k.iter())
+            .map(|(l, q)| *l + u[k] * q)
+            .collect();
+        quotients[k] = q_k;
+    }
+    quotients
+}
+
+/// Starts the transcript with the evaluation claim: y is sampled after the quotient commitments, x and z
+/// after q_hat and its degree bound proof
+fn zeromorph_transcript<E: Pairing>(
+    commitment: E::G1,
+    u: &[E::ScalarField],
+    v: E::ScalarField,
+) -> Transcript {
+    let mut transcript = Transcript::new(b"zeromorph");
+    transcript.absorb(&commitment);
+    transcript.absorb(u);
+    transcript.absorb(&v);
+    transcript
+}
+
+impl<E: Pairing> KZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Commits to the multilinear polynomial given by its evaluations over the hypercube
+    /// An error if there are more evaluations than the srs has points
+    pub fn commit_mle(&self, evaluations: &[E::ScalarField]) -> Result<E::G1, AlgorithmsError> {
+        if !evaluations.len().is_power_of_two() {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: evaluations.len().next_power_of_two(),
+                got: evaluations.len(),
+            });
+        }
+        self.commit(&DensePolynomial::from_coefficients_slice(evaluations))
+    }
+
+    /// Coefficient of U_k(q_k) in the univariate identity, evaluated at x:
+    /// x^{2^k} * Phi_{n-k-1}(x^{2^{k+1}}) - u_k * Phi_{n-k}(x^{2^k})
+    fn zeromorph_coefficient(
+        n: usize,
+        k: usize,
+        u_k: E::ScalarField,
+        x: E::ScalarField,
+    ) -> E::ScalarField {
+        let x_2k = x.pow([1u64 << k]);
+        x_2k * phi(n - k - 1, x_2k.square()) - u_k * phi(n - k, x_2k)
+    }
+
+    /// Proves that the multilinear polynomial with `evaluations` over the hypercube evaluates to v at u,
+    /// returns the proof and v, an error if the polynomial doesn't fit in the srs or doesn't have n variables.
+    pub fn open_zeromorph(
+        &self,
+        evaluations: &[E::ScalarField],
+        u: &[E::ScalarField],
+    ) -> Result<(ZeromorphProof<E>, E::ScalarField), AlgorithmsError> {
+        let n = u.len();
+        let size = 1 << n;
+        if evaluations.len() != size {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: size,
+                got: evaluations.len(),
+            });
+        }
+        let mut v = evaluations.to_vec();
+        for u_k in u.iter().rev() {
+            let half = v.len() / 2;
+            v = (0..half)
+                .map(|i| v[i] + *u_k * (v[i + half] - v[i]))
+                .collect();
+        }
+        let v = v[0];
+        let mut transcript = zeromorph_transcript::<E>(self.commit_mle(evaluations)?, u, v);
+
+        let quotients = compute_quotients(evaluations, u);
+        let quotient_polynomials = quotients
+            .iter()
+            .map(|q_k| DensePolynomial::from_coefficients_slice(q_k))
+            .collect::<Vec<_>>();
+        let quotient_commitments = quotient_polynomials
+            .iter()
+            .map(|q_k| self.commit(q_k))
+            .collect::<Result<Vec<_>, _>>()?;
+        transcript.absorb(&quotient_commitments);
+        let y: E::ScalarField = transcript.challenge();
+
+        // q_hat = sum_k y^k * X^{2^n - 2^k} * U_k(q_k), of degree < 2^n only if each U_k(q_k) is of degree < 2^k
+        let mut q_hat_coeffs = vec![E::ScalarField::zero(); size];
+        let mut y_k = E::ScalarField::one();
+        for (k, q_k) in quotients.iter().enumerate() {
+            let shift = size - (1 << k);
+            for (i, coeff) in q_k.iter().enumerate() {
+                q_hat_coeffs[shift + i] += y_k * coeff;
+            }
+            y_k *= y;
+        }
+        let q_hat = DensePolynomial::from_coefficients_vec(q_hat_coeffs);
+        self.check_degree(&q_hat)?;
+        let (q_hat_commitment, q_hat_shifted) = self.commit_with_degree_bound(&q_hat, size - 1);
+        transcript.absorb(&[q_hat_commitment, q_hat_shifted]);
+        let [x, z]: [E::ScalarField; 2] = [(); 2].map(|_| transcript.challenge());
+
+        // zeta_x = q_hat - sum_k y^k * x^{2^n - 2^k} * U_k(q_k)
+        // Z_x = U_n(f) - v * Phi_n(x) - sum_k c_k(x) * U_k(q_k)
+        // both vanish at x, and are opened at once with zeta_x + z * Z_x
+        let f = DensePolynomial::from_coefficients_slice(evaluations);
+        let mut batched = &q_hat + &(&f * z);
+        batched = &batched - &DensePolynomial::from_coefficients_vec(vec![z * v * phi(n, x)]);
+        let mut y_k = E::ScalarField::one();
+        for (k, q_k) in quotient_polynomials.iter().enumerate() {
+            let scalar = y_k * x.pow([(size - (1 << k)) as u64])
+                + z * Self::zeromorph_coefficient(n, k, u[k], x);
+            batched = &batched - &(q_k * scalar);
+            y_k *= y;
+        }
+        let pi = self
+            .open(&batched, x, E::ScalarField::zero())
+            .expect("the batched polynomial vanishes at x");
+        let proof = ZeromorphProof {
+            quotient_commitments,
+            q_hat: q_hat_commitment,
+            q_hat_shifted,
+            pi,
+        };
+        Ok((proof, v))
+    }
+
+    /// Verifies that the multilinear polynomial committed in `commitment` evaluates to v at u
+    pub fn verify_zeromorph(
+        &self,
+        commitment: E::G1,
+        u: &[E::ScalarField],
+        v: E::ScalarField,
+        proof: &ZeromorphProof<E>,
+    ) -> bool {
+        let n = u.len();
+        let size = 1 << n;
+        if proof.quotient_commitments.len() != n || size - 1 > self.degree {
+            return false;
+        }
+        if !commitment.check_subgroup()
+            || !check_subgroup_all(&proof.quotient_commitments)
+            || !proof.q_hat.check_subgroup()
+        {
+            return false;
+        }
+        if !self.verify_degree_bound(proof.q_hat, proof.q_hat_shifted, size - 1) {
+            return false;
+        }
+        let mut transcript = zeromorph_transcript::<E>(commitment, u, v);
+        transcript.absorb(&proof.quotient_commitments);
+        let y: E::ScalarField = transcript.challenge();
+        transcript.absorb(&[proof.q_hat, proof.q_hat_shifted]);
+        let [x, z]: [E::ScalarField; 2] = [(); 2].map(|_| transcript.challenge());
+        // [zeta_x + z * Z_x]_1, which must open to 0 at x
+        let mut c = proof.q_hat + commitment * z - self.g1 * (z * v * phi(n, x));
+        let mut y_k = E::ScalarField::one();
+        for (k, q_k) in proof.quotient_commitments.iter().enumerate() {
+            let scalar = y_k * x.pow([(size - (1 << k)) as u64])
+                + z * Self::zeromorph_coefficient(n, k, u[k], x);
+            c -= *q_k * scalar;
+            y_k *= y;
+        }
+        self.verify(E::ScalarField::zero(), x, c, proof.pi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cs::pcs::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_zeromorph() {
+        let mut rng = test_rng();
+        let n = 4;
+        let mut kzg = KZG::<Bn254>::new(
+            G1Projective::rand(&mut rng),
+            G2Projective::rand(&mut rng),
+            (1 << n) - 1,
+        );
+        kzg.setup_from_rng(&mut rng);
+
+        let mle = DenseMultilinearExtension::<Fr>::rand(n, &mut rng);
+        let commitment = kzg.commit_mle(&mle.evaluations).unwrap();
+        let u = (0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (proof, v) = kzg.open_zeromorph(&mle.evaluations, &u).unwrap();
+        assert_eq!(v, mle.evaluate(&u).unwrap());
+        assert!(kzg.verify_zeromorph(commitment, &u, v, &proof));
+
+        // wrong evaluation or point
+        assert!(!kzg.verify_zeromorph(commitment, &u, v + Fr::one(), &proof));
+        let mut wrong_u = u.clone();
+        wrong_u[1] += Fr::one();
+        assert!(!kzg.verify_zeromorph(commitment, &wrong_u, v, &proof));
+
+        // tampered quotient commitments
+        let mut wrong_proof = proof.clone();
+        wrong_proof.quotient_commitments.swap(0, 1);
+        assert!(!kzg.verify_zeromorph(commitment, &u, v, &wrong_proof));
+
+        // evaluations over another number of variables, or not over a hypercube
+        assert!(kzg.open_zeromorph(&mle.evaluations, &u[1..]).is_err());
+        assert!(kzg.commit_mle(&mle.evaluations[..3]).is_err());
+    }
+}
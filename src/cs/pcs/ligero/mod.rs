@@ -0,0 +1,225 @@
+// Ligero-style linear code multilinear commitment: https://eprint.iacr.org/2022/1608 (section 4, Ligero
+// instantiated with Reed-Solomon, Brakedown swapping in a linear-time encodable code)
+// The 2^n evaluations of an mle are laid out as in hyrax, as a 2^{n - m} x 2^m matrix M whose m low variables
+// index the columns. Each row is encoded with a Reed-Solomon code of rate 1 / blowup_factor, and the columns of
+// the encoded matrix Enc(M) are the leaves of a merkle tree, whose root is the commitment.
+// Since f(r) = L^T M R, with L = eq(r_rows, .) and R = eq(r_cols, .), the prover opens with:
+// - a proximity combination u' = gamma^T M, gamma being random, showing that the committed rows are codewords,
+// - the evaluation combination u = L^T M, such that y = <u, R>,
+// then the verifier queries columns j of Enc(M) and checks, code linearity giving Enc(gamma^T M) = gamma^T Enc(M),
+// Enc(u')_j = <gamma, Enc(M)_j> and Enc(u)_j = <L, Enc(M)_j>.
+// Only hashes are involved: the commitment is transparent and plausibly post-quantum, with O(2^n) prover work
+// beyond the encoding, and sqrt-sized proofs. This version isn't zero-knowledge: rows aren't masked.
+use ark_ff::PrimeField;
+use ark_poly::DenseMultilinearExtension;
+
+use crate::cs::merkle::{verify_path, Digest, KeccakHasher, MerkleTree};
+use crate::ip::sumcheck::eq_evaluations;
+use crate::transcript::Transcript;
+use crate::utils::{inner_product, rs::ReedSolomon};
+
+pub struct Ligero<F: PrimeField> {
+    num_vars: usize,
+    /// Number of column variables, m = n / 2
+    num_col_vars: usize,
+    num_queries: usize,
+    code: ReedSolomon<F>,
+}
+
+/// What the prover keeps from the commitment: the encoded rows and the merkle tree over their columns
+pub struct LigeroProverData<F: PrimeField> {
+    encoded_rows: Vec<Vec<F>>,
+    tree: MerkleTree<Vec<F>, KeccakHasher>,
+}
+
+/// An opening proof: the proximity and evaluation combinations, and the queried columns of Enc(M)
+#[derive(Clone, Debug, PartialEq)]
+pub struct LigeroProof<F: PrimeField> {
+    pub proximity: Vec<F>,
+    pub evaluation: Vec<F>,
+    pub columns: Vec<Vec<F>>,
+    pub paths: Vec<Vec<Digest>>,
+}
+
+/// v^T M, M being given by its rows
+fn combine_rows<F: PrimeField>(v: &[F], rows: &[&[F]]) -> Vec<F> {
+    let mut combination = vec![F::zero(); rows.first().map_or(0, |row| row.len())];
+    for (v_j, row) in v.iter().zip(rows) {
+        for (c, m) in combination.iter_mut().zip(row.iter()) {
+            *c += *v_j * m;
+        }
+    }
+    combination
+}
+
+impl<F: PrimeField> Ligero<F> {
+    /// Each query catches a proximity error with probability about 1 - 1 / blowup_factor
+    pub fn new(num_vars: usize, blowup_factor: usize, num_queries: usize) -> Self {
+        let num_col_vars = num_vars / 2;
+        Self {
+            num_vars,
+            num_col_vars,
+            num_queries,
+            code: ReedSolomon::new(1 << num_col_vars, blowup_factor),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn code(&self) -> &ReedSolomon<F> {
+        &self.code
+    }
+
+    fn num_cols(&self) -> usize {
+        1 << self.num_col_vars
+    }
+
+    fn num_rows(&self) -> usize {
+        1 << (self.num_vars - self.num_col_vars)
+    }
+
+    /// gamma, after absorbing the commitment, the point and the evaluation
+    fn transcript(&self, commitment: &Digest, r: &[F], y: F) -> (Transcript, Vec<F>) {
+        let mut transcript = Transcript::new(b"ligero");
+        transcript.absorb(commitment);
+        transcript.absorb(&r.to_vec());
+        transcript.absorb(&y);
+        let gamma = transcript.challenges(self.num_rows());
+        (transcript, gamma)
+    }
+
+    /// Column indices, sampled after the combinations are absorbed
+    fn queries(
+        &self,
+        transcript: &mut Transcript,
+        proximity: &[F],
+        evaluation: &[F],
+    ) -> Vec<usize> {
+        transcript.absorb(&proximity.to_vec());
+        transcript.absorb(&evaluation.to_vec());
+        let n = self.code.codeword_length();
+        (0..self.num_queries)
+            .map(|_| transcript.challenge_index(n))
+            .collect()
+    }
+
+    /// Encodes the rows of the evaluation matrix, returns the merkle root of the encoded columns
+    pub fn commit(&self, mle: &DenseMultilinearExtension<F>) -> (Digest, LigeroProverData<F>) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        let encoded_rows = mle
+            .evaluations
+            .chunks(self.num_cols())
+            .map(|row| self.code.encode(row))
+            .collect::<Vec<_>>();
+        let columns = (0..self.code.codeword_length())
+            .map(|j| encoded_rows.iter().map(|row| row[j]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(KeccakHasher, &columns);
+        (tree.root(), LigeroProverData { encoded_rows, tree })
+    }
+
+    /// Opens `mle` at r, returns the proof and y = f(r)
+    pub fn open(
+        &self,
+        mle: &DenseMultilinearExtension<F>,
+        data: &LigeroProverData<F>,
+        r: &[F],
+    ) -> (LigeroProof<F>, F) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        assert_eq!(r.len(), self.num_vars);
+        let (r_cols, r_rows) = r.split_at(self.num_col_vars);
+        let rows = mle.evaluations.chunks(self.num_cols()).collect::<Vec<_>>();
+        let evaluation = combine_rows(&eq_evaluations(r_rows), &rows);
+        let y = inner_product(&evaluation, &eq_evaluations(r_cols));
+
+        let (mut transcript, gamma) = self.transcript(&data.tree.root(), r, y);
+        let proximity = combine_rows(&gamma, &rows);
+        let indices = self.queries(&mut transcript, &proximity, &evaluation);
+        let columns = indices
+            .iter()
+            .map(|j| data.encoded_rows.iter().map(|row| row[*j]).collect())
+            .collect();
+        let paths = indices.iter().map(|j| data.tree.open(*j)).collect();
+        let proof = LigeroProof {
+            proximity,
+            evaluation,
+            columns,
+            paths,
+        };
+        (proof, y)
+    }
+
+    /// Verifies that the mle committed in `commitment` evaluates to y at r
+    pub fn verify(&self, commitment: &Digest, r: &[F], y: F, proof: &LigeroProof<F>) -> bool {
+        if r.len() != self.num_vars
+            || proof.proximity.len() != self.num_cols()
+            || proof.evaluation.len() != self.num_cols()
+            || proof.columns.len() != self.num_queries
+            || proof.paths.len() != self.num_queries
+        {
+            return false;
+        }
+        let (r_cols, r_rows) = r.split_at(self.num_col_vars);
+        if inner_product(&proof.evaluation, &eq_evaluations(r_cols)) != y {
+            return false;
+        }
+        let (mut transcript, gamma) = self.transcript(commitment, r, y);
+        let indices = self.queries(&mut transcript, &proof.proximity, &proof.evaluation);
+        let encoded_proximity = self.code.encode(&proof.proximity);
+        let encoded_evaluation = self.code.encode(&proof.evaluation);
+        let l = eq_evaluations(r_rows);
+        let depth = self.code.codeword_length().trailing_zeros() as usize;
+        indices
+            .iter()
+            .zip(proof.columns.iter().zip(&proof.paths))
+            .all(|(j, (column, path))| {
+                column.len() == self.num_rows()
+                    && verify_path(&KeccakHasher, commitment, depth, *j, column, path)
+                    && inner_product(&gamma, column) == encoded_proximity[*j]
+                    && inner_product(&l, column) == encoded_evaluation[*j]
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ligero;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::Fr;
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_ligero() {
+        let mut rng = test_rng();
+        // 8 rows of 4 columns, encoded to 16
+        let num_vars = 5;
+        let ligero = Ligero::<Fr>::new(num_vars, 4, 10);
+        assert_eq!(ligero.code().codeword_length(), 16);
+
+        let mle = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+        let (commitment, data) = ligero.commit(&mle);
+        let r = (0..num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (proof, y) = ligero.open(&mle, &data, &r);
+        assert_eq!(y, mle.evaluate(&r).unwrap());
+        assert!(ligero.verify(&commitment, &r, y, &proof));
+
+        // wrong evaluation, point, or proof
+        assert!(!ligero.verify(&commitment, &r, y + Fr::one(), &proof));
+        let mut wrong_r = r.clone();
+        wrong_r[4] += Fr::one();
+        assert!(!ligero.verify(&commitment, &wrong_r, y, &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.columns[0][1] += Fr::one();
+        assert!(!ligero.verify(&commitment, &r, y, &wrong_proof));
+
+        // a consistent evaluation combination for another mle
+        let other = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+        let (other_proof, other_y) = ligero.open(&other, &data, &r);
+        assert!(!ligero.verify(&commitment, &r, other_y, &other_proof));
+    }
+}
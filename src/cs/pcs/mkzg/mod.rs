@@ -0,0 +1,216 @@
+// Multilinear KZG, from PST13: https://eprint.iacr.org/2011/587
+// Commits to a multilinear extension f of n variables, given by its evaluations over the hypercube
+// (variable i is bit i of the evaluation's index, as in the sumcheck module).
+// C = [f(t)]_1 for a secret t in F^n. Opening at r relies on f(X) - f(r) = sum_i (X_i - r_i) * q_i(X_{i+1}, ..., X_{n-1}),
+// the proof being pi_i = [q_i(t_{i+1}, ..., t_{n-1})]_1, checked with e(C - [y]_1, g2) == prod_i e(pi_i, [t_i - r_i]_2)
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_poly::DenseMultilinearExtension;
+use ark_std::{rand::Rng, UniformRand};
+
+use crate::cs::pedersen::hash_to_field;
+use crate::ip::sumcheck::eq_evaluations;
+use crate::utils::curve::{check_subgroup_all, FixedBaseTable, SubgroupCheck};
+
+/// Separates the points of `setup_from_seed` from other values hashed from the same seed
+const MKZG_SEED_LABEL: &[u8] = b"ark-algorithms multilinear kzg t";
+
+pub struct MultilinearKZG<E: Pairing> {
+    g1: E::G1,
+    g2: E::G2,
+    num_vars: usize,
+    /// srs[k] holds [eq((t_k, ..., t_{n-1}), b)]_1 for all b in {0, 1}^{n-k}, srs[n] = [g1]
+    srs: Vec<Vec<E::G1>>,
+    /// vk[i] = [t_i]_2
+    vk: Vec<E::G2>,
+}
+
+impl<E: Pairing> MultilinearKZG<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    pub fn new(g1: E::G1, g2: E::G2, num_vars: usize) -> Self {
+        Self {
+            g1,
+            g2,
+            num_vars,
+            srs: vec![],
+            vk: vec![],
+        }
+    }
+
+    pub fn setup(&mut self, t: &[E::ScalarField]) {
+        assert_eq!(t.len(), self.num_vars);
+        let table = FixedBaseTable::new(self.g1, 2 << self.num_vars);
+        self.srs = (0..=self.num_vars)
+            .map(|k| table.batch_mul(&eq_evaluations(&t[k..])))
+            .collect();
+        self.vk = t.iter().map(|t_i| self.g2 * t_i).collect();
+    }
+
+    /// Runs the setup with a point t sampled from `rng`
+    pub fn setup_random<R: Rng>(&mut self, rng: &mut R) {
+        let t = (0..self.num_vars)
+            .map(|_| E::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        self.setup(&t);
+    }
+
+    /// Runs the setup with t hashed from `seed`, for reproducible test vectors: only sound for tests
+    pub fn setup_from_seed(&mut self, seed: &[u8]) {
+        let seed = [MKZG_SEED_LABEL, seed].concat();
+        let t = (0..self.num_vars as u64)
+            .map(|i| hash_to_field(&seed, i))
+            .collect::<Vec<_>>();
+        self.setup(&t);
+    }
+
+    pub fn g1(&self) -> E::G1 {
+        self.g1
+    }
+
+    pub fn g2(&self) -> E::G2 {
+        self.g2
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn srs(&self) -> &[Vec<E::G1>] {
+        &self.srs
+    }
+
+    pub fn vk(&self) -> &[E::G2] {
+        &self.vk
+    }
+
+    /// Commits to evaluations over the hypercube of n - k variables, using srs[k]
+    fn commit_evaluations(&self, evaluations: &[E::ScalarField], k: usize) -> E::G1 {
+        assert_eq!(evaluations.len(), self.srs[k].len());
+        let mut commitment = E::G1::zero();
+        for (e, point) in evaluations.iter().zip(self.srs[k].iter()) {
+            commitment += *point * e;
+        }
+        commitment
+    }
+
+    pub fn commit(&self, mle: &DenseMultilinearExtension<E::ScalarField>) -> E::G1 {
+        assert_eq!(mle.num_vars, self.num_vars);
+        self.commit_evaluations(&mle.evaluations, 0)
+    }
+
+    /// Opens `mle` at r, returns the n proof elements and y = f(r)
+    pub fn open(
+        &self,
+        mle: &DenseMultilinearExtension<E::ScalarField>,
+        r: &[E::ScalarField],
+    ) -> (Vec<E::G1>, E::ScalarField) {
+        assert_eq!(mle.num_vars, self.num_vars);
+        assert_eq!(r.len(), self.num_vars);
+        let mut evaluations = mle.evaluations.clone();
+        let mut pis = Vec::with_capacity(self.num_vars);
+        for (i, r_i) in r.iter().enumerate() {
+            // f(X_i, ...) = f(0, ...) + X_i * (f(1, ...) - f(0, ...)): q_i = f(1, ...) - f(0, ...)
+            // and the remainder f(r_i, ...) is opened in the next rounds
+            let q_i = evaluations
+                .chunks(2)
+                .map(|pair| pair[1] - pair[0])
+                .collect::<Vec<_>>();
+            evaluations = evaluations
+                .chunks(2)
+                .zip(q_i.iter())
+                .map(|(pair, q)| pair[0] + *r_i * q)
+                .collect();
+            pis.push(self.commit_evaluations(&q_i, i + 1));
+        }
+        (pis, evaluations[0])
+    }
+
+    /// Verifies that the mle committed in `commitment` evaluates to y at r
+    pub fn verify(
+        &self,
+        commitment: E::G1,
+        r: &[E::ScalarField],
+        y: E::ScalarField,
+        pis: &[E::G1],
+    ) -> bool {
+        if r.len() != self.num_vars || pis.len() != self.num_vars {
+            return false;
+        }
+        if !commitment.check_subgroup() || !check_subgroup_all(pis) {
+            return false;
+        }
+        // e(C - [y]_1, g2) * prod_i e(-pi_i, [t_i - r_i]_2) == 1
+        let mut g1_terms = vec![commitment - self.g1 * y];
+        let mut g2_terms = vec![self.g2];
+        for ((pi, r_i), vk_i) in pis.iter().zip(r.iter()).zip(self.vk.iter()) {
+            g1_terms.push(-*pi);
+            g2_terms.push(*vk_i - self.g2 * r_i);
+        }
+        E::multi_pairing(g1_terms, g2_terms).0.is_one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultilinearKZG;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{DenseMultilinearExtension, MultilinearExtension};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_multilinear_kzg() {
+        let mut rng = test_rng();
+        let num_vars = 5;
+        let t = (0..num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut mkzg = MultilinearKZG::<Bn254>::new(g1, g2, num_vars);
+        mkzg.setup(&t);
+
+        let mle = DenseMultilinearExtension::<Fr>::rand(num_vars, &mut rng);
+        let commitment = mkzg.commit(&mle);
+        // the commitment is [f(t)]_1
+        assert_eq!(commitment, g1 * mle.evaluate(&t).unwrap());
+
+        let r = (0..num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let (pis, y) = mkzg.open(&mle, &r);
+        assert_eq!(pis.len(), num_vars);
+        assert_eq!(y, mle.evaluate(&r).unwrap());
+        assert!(mkzg.verify(commitment, &r, y, &pis));
+
+        // wrong evaluation, point, or proof
+        assert!(!mkzg.verify(commitment, &r, y + Fr::one(), &pis));
+        let mut wrong_r = r.clone();
+        wrong_r[2] += Fr::one();
+        assert!(!mkzg.verify(commitment, &wrong_r, y, &pis));
+
+        // a secret point drawn by the scheme itself
+        let mut random_mkzg = MultilinearKZG::<Bn254>::new(g1, g2, num_vars);
+        random_mkzg.setup_random(&mut rng);
+        let commitment = random_mkzg.commit(&mle);
+        let (pis, y) = random_mkzg.open(&mle, &r);
+        assert!(random_mkzg.verify(commitment, &r, y, &pis));
+        let mut wrong_pis = pis.clone();
+        wrong_pis.swap(0, 1);
+        assert!(!mkzg.verify(commitment, &r, y, &wrong_pis));
+        assert!(!mkzg.verify(commitment, &r[1..], y, &pis[1..]));
+
+        // a point hashed from a seed
+        let mut seeded_mkzg = MultilinearKZG::<Bn254>::new(g1, g2, num_vars);
+        seeded_mkzg.setup_from_seed(b"test");
+        let mut same_seed = MultilinearKZG::<Bn254>::new(g1, g2, num_vars);
+        same_seed.setup_from_seed(b"test");
+        let commitment = seeded_mkzg.commit(&mle);
+        assert_eq!(commitment, same_seed.commit(&mle));
+        let (pis, y) = seeded_mkzg.open(&mle, &r);
+        assert!(same_seed.verify(commitment, &r, y, &pis));
+    }
+}
@@ -1 +1,7 @@
+pub mod dory;
+pub mod fri;
+pub mod hyrax;
+pub mod ipa;
 pub mod kzg;
+pub mod ligero;
+pub mod mkzg;
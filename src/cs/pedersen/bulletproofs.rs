@@ -0,0 +1,380 @@
+// Bulletproofs range proofs: https://eprint.iacr.org/2017/1066 (sections 3 and 4)
+// Proves that m pedersen commitments V_j = v_j * G + gamma_j * H hide values v_j in [0, 2^n), with a proof of
+// 2 * log(n * m) + 4 points and 5 scalars.
+// The bits a_L of all the values are committed to along with a_R = a_L - 1, and the constraints
+// <a_L, 2^n> = v_j, a_L o a_R = 0 and a_L - a_R = 1 are batched with challenges y and z into a single
+// inner product <l(x), r(x)> = t(x), proven with the (log size) inner product argument.
+use ark_ec::CurveGroup;
+use ark_ff::{Field, UniformRand};
+use ark_std::{rand::Rng, One};
+
+use super::{derive_generators, hash_to_curve, Pedersen};
+use crate::transcript::Transcript;
+use crate::utils::{curve::msm, inner_product, powers};
+
+/// Parameters for proving that m values lie in [0, 2^n)
+pub struct RangeProofParameters<C: CurveGroup> {
+    n: usize,
+    m: usize,
+    /// Commits to the values: G is its single generator, H the blinding generator
+    pedersen: Pedersen<C>,
+    g_vec: Vec<C>,
+    h_vec: Vec<C>,
+    /// Generator binding the inner product
+    u: C,
+}
+
+/// Inner product argument for P = <a, G> + <b, H> + <a, b> * u
+#[derive(Clone, Debug, PartialEq)]
+pub struct InnerProductProof<C: CurveGroup> {
+    pub l_vec: Vec<C>,
+    pub r_vec: Vec<C>,
+    pub a: C::ScalarField,
+    pub b: C::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeProof<C: CurveGroup> {
+    pub a: C,
+    pub s: C,
+    pub t_1: C,
+    pub t_2: C,
+    pub t_hat: C::ScalarField,
+    pub tau_x: C::ScalarField,
+    pub mu: C::ScalarField,
+    pub ipa: InnerProductProof<C>,
+}
+
+/// Proves knowledge of a, b such that P = <a, G> + <b, H> + <a, b> * u
+fn prove_inner_product<C: CurveGroup>(
+    g: &[C],
+    h: &[C],
+    u: &C,
+    a: &[C::ScalarField],
+    b: &[C::ScalarField],
+    transcript: &mut Transcript,
+) -> InnerProductProof<C> {
+    let (mut g, mut h, mut a, mut b) = (g.to_vec(), h.to_vec(), a.to_vec(), b.to_vec());
+    let mut l_vec = vec![];
+    let mut r_vec = vec![];
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(half);
+        let (b_lo, b_hi) = b.split_at(half);
+        let (g_lo, g_hi) = g.split_at(half);
+        let (h_lo, h_hi) = h.split_at(half);
+        let l =
+            msm(g_hi, a_lo).unwrap() + msm(h_lo, b_hi).unwrap() + *u * inner_product(a_lo, b_hi);
+        let r =
+            msm(g_lo, a_hi).unwrap() + msm(h_hi, b_lo).unwrap() + *u * inner_product(a_hi, b_lo);
+        transcript.absorb(&[l, r]);
+        let x: C::ScalarField = transcript.challenge();
+        let x_inv = x.inverse().unwrap();
+        a = (0..half).map(|i| a_lo[i] * x + a_hi[i] * x_inv).collect();
+        b = (0..half).map(|i| b_lo[i] * x_inv + b_hi[i] * x).collect();
+        g = (0..half).map(|i| g_lo[i] * x_inv + g_hi[i] * x).collect();
+        h = (0..half).map(|i| h_lo[i] * x + h_hi[i] * x_inv).collect();
+        l_vec.push(l);
+        r_vec.push(r);
+    }
+    InnerProductProof {
+        l_vec,
+        r_vec,
+        a: a[0],
+        b: b[0],
+    }
+}
+
+fn verify_inner_product<C: CurveGroup>(
+    g: &[C],
+    h: &[C],
+    u: &C,
+    p: C,
+    proof: &InnerProductProof<C>,
+    transcript: &mut Transcript,
+) -> bool {
+    if proof.l_vec.len() != g.len().trailing_zeros() as usize
+        || proof.r_vec.len() != proof.l_vec.len()
+    {
+        return false;
+    }
+    let (mut g, mut h, mut p) = (g.to_vec(), h.to_vec(), p);
+    for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+        transcript.absorb(&[*l, *r]);
+        let x: C::ScalarField = transcript.challenge();
+        let x_inv = x.inverse().unwrap();
+        let half = g.len() / 2;
+        p += *l * x.square() + *r * x_inv.square();
+        g = (0..half).map(|i| g[i] * x_inv + g[half + i] * x).collect();
+        h = (0..half).map(|i| h[i] * x + h[half + i] * x_inv).collect();
+    }
+    p == g[0] * proof.a + h[0] * proof.b + *u * (proof.a * proof.b)
+}
+
+impl<C: CurveGroup> RangeProofParameters<C> {
+    /// Parameters for m values of n bits, both powers of two, n <= 64
+    pub fn new(n: usize, m: usize) -> Self {
+        assert!(
+            n.is_power_of_two() && n <= 64,
+            "n must be a power of two, at most 64"
+        );
+        assert!(m.is_power_of_two(), "m must be a power of two");
+        let seed = b"ark-algorithms bulletproofs";
+        let nm = n * m;
+        Self {
+            n,
+            m,
+            pedersen: Pedersen::setup_with_seed(1, seed),
+            g_vec: derive_generators(b"bulletproofs g", nm),
+            h_vec: derive_generators(b"bulletproofs h", nm),
+            u: hash_to_curve(b"bulletproofs u", 0),
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn pedersen(&self) -> &Pedersen<C> {
+        &self.pedersen
+    }
+
+    /// Pedersen commitment to a value: v * G + gamma * H
+    pub fn commit(&self, value: u64, blinding: &C::ScalarField) -> C {
+        self.pedersen
+            .commit(&[C::ScalarField::from(value)], blinding)
+    }
+
+    /// Coefficients of y^{nm} o z + z^{2+j} * 2^n, multiplying h' in P, and added to a_R in r(X)
+    fn h_exponents(&self, y_nm: &[C::ScalarField], z: C::ScalarField) -> Vec<C::ScalarField> {
+        let two_n = powers(C::ScalarField::from(2u64), self.n);
+        let mut exponents = Vec::with_capacity(self.n * self.m);
+        let mut z_j = z.square();
+        for j in 0..self.m {
+            for i in 0..self.n {
+                exponents.push(z * y_nm[j * self.n + i] + z_j * two_n[i]);
+            }
+            z_j *= z;
+        }
+        exponents
+    }
+
+    /// Proves that the commitments to `values` (with blindings `blindings`) hide values in [0, 2^n)
+    /// Returns the commitments and the aggregated proof.
+    pub fn prove<R: Rng>(
+        &self,
+        values: &[u64],
+        blindings: &[C::ScalarField],
+        rng: &mut R,
+    ) -> (Vec<C>, RangeProof<C>) {
+        assert_eq!(values.len(), self.m);
+        assert_eq!(blindings.len(), self.m);
+        assert!(
+            self.n == 64 || values.iter().all(|v| *v >> self.n == 0),
+            "value out of range"
+        );
+        let nm = self.n * self.m;
+        let g = self.pedersen.generators()[0];
+        let h = self.pedersen.h();
+        let commitments = values
+            .iter()
+            .zip(blindings)
+            .map(|(v, gamma)| self.commit(*v, gamma))
+            .collect::<Vec<_>>();
+
+        let mut a_l = Vec::with_capacity(nm);
+        for v in values {
+            for i in 0..self.n {
+                a_l.push(C::ScalarField::from((v >> i) & 1));
+            }
+        }
+        let a_r = a_l
+            .iter()
+            .map(|a| *a - C::ScalarField::one())
+            .collect::<Vec<_>>();
+        let alpha = C::ScalarField::rand(rng);
+        let a = msm(&self.g_vec, &a_l).unwrap() + msm(&self.h_vec, &a_r).unwrap() + h * alpha;
+        let s_l = (0..nm)
+            .map(|_| C::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let s_r = (0..nm)
+            .map(|_| C::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let rho = C::ScalarField::rand(rng);
+        let s = msm(&self.g_vec, &s_l).unwrap() + msm(&self.h_vec, &s_r).unwrap() + h * rho;
+
+        let mut transcript = Transcript::new(b"range proof");
+        transcript.absorb(&commitments);
+        transcript.absorb(&[a, s]);
+        let y: C::ScalarField = transcript.challenge();
+        let z: C::ScalarField = transcript.challenge();
+
+        // l(X) = a_L - z + s_L * X, r(X) = y^{nm} o (a_R + z + s_R * X) + z^{2+j} * 2^n
+        let y_nm = powers(y, nm);
+        let h_exponents = self.h_exponents(&y_nm, z);
+        let l_0 = a_l.iter().map(|a| *a - z).collect::<Vec<_>>();
+        let r_0 = (0..nm)
+            .map(|k| y_nm[k] * a_r[k] + h_exponents[k])
+            .collect::<Vec<_>>();
+        let r_1 = (0..nm).map(|k| y_nm[k] * s_r[k]).collect::<Vec<_>>();
+        let t_1 = inner_product(&l_0, &r_1) + inner_product(&s_l, &r_0);
+        let t_2 = inner_product(&s_l, &r_1);
+        let tau_1 = C::ScalarField::rand(rng);
+        let tau_2 = C::ScalarField::rand(rng);
+        let t_1_commitment = g * t_1 + h * tau_1;
+        let t_2_commitment = g * t_2 + h * tau_2;
+
+        transcript.absorb(&[t_1_commitment, t_2_commitment]);
+        let x: C::ScalarField = transcript.challenge();
+        let l = (0..nm).map(|k| l_0[k] + s_l[k] * x).collect::<Vec<_>>();
+        let r = (0..nm).map(|k| r_0[k] + r_1[k] * x).collect::<Vec<_>>();
+        let t_hat = inner_product(&l, &r);
+        let mut tau_x = tau_2 * x.square() + tau_1 * x;
+        let mut z_j = z.square();
+        for gamma in blindings {
+            tau_x += z_j * gamma;
+            z_j *= z;
+        }
+        let mu = alpha + rho * x;
+
+        transcript.absorb(&[t_hat, tau_x, mu]);
+        let w: C::ScalarField = transcript.challenge();
+        let y_inv_nm = powers(y.inverse().unwrap(), nm);
+        let h_prime = self
+            .h_vec
+            .iter()
+            .zip(y_inv_nm)
+            .map(|(h_k, y_k)| *h_k * y_k)
+            .collect::<Vec<_>>();
+        let ipa = prove_inner_product(
+            &self.g_vec,
+            &h_prime,
+            &(self.u * w),
+            &l,
+            &r,
+            &mut transcript,
+        );
+        let proof = RangeProof {
+            a,
+            s,
+            t_1: t_1_commitment,
+            t_2: t_2_commitment,
+            t_hat,
+            tau_x,
+            mu,
+            ipa,
+        };
+        (commitments, proof)
+    }
+
+    pub fn verify(&self, commitments: &[C], proof: &RangeProof<C>) -> bool {
+        if commitments.len() != self.m {
+            return false;
+        }
+        let nm = self.n * self.m;
+        let g = self.pedersen.generators()[0];
+        let h = self.pedersen.h();
+        let mut transcript = Transcript::new(b"range proof");
+        transcript.absorb(commitments);
+        transcript.absorb(&[proof.a, proof.s]);
+        let y: C::ScalarField = transcript.challenge();
+        let z: C::ScalarField = transcript.challenge();
+        transcript.absorb(&[proof.t_1, proof.t_2]);
+        let x: C::ScalarField = transcript.challenge();
+        transcript.absorb(&[proof.t_hat, proof.tau_x, proof.mu]);
+        let w: C::ScalarField = transcript.challenge();
+
+        // t_hat * G + tau_x * H == sum_j z^{2+j} * V_j + delta(y, z) * G + x * T_1 + x^2 * T_2
+        // delta(y, z) = (z - z^2) * <1, y^{nm}> - sum_j z^{3+j} * <1, 2^n>
+        let y_nm = powers(y, nm);
+        let sum_two_n = C::ScalarField::from(((1u128 << self.n) - 1) as u64);
+        let mut delta = (z - z.square()) * y_nm.iter().sum::<C::ScalarField>();
+        let mut v_sum = C::zero();
+        let mut z_j = z.square();
+        for v in commitments {
+            v_sum += *v * z_j;
+            delta -= z_j * z * sum_two_n;
+            z_j *= z;
+        }
+        let lhs = g * proof.t_hat + h * proof.tau_x;
+        let rhs = v_sum + g * delta + proof.t_1 * x + proof.t_2 * x.square();
+        if lhs != rhs {
+            return false;
+        }
+
+        // P = A + x * S - z * <1, G> + <y^{nm} o z + z^{2+j} * 2^n, H'>, the inner product argument is run on
+        // P - mu * H + t_hat * u'
+        let y_inv_nm = powers(y.inverse().unwrap(), nm);
+        let h_prime = self
+            .h_vec
+            .iter()
+            .zip(y_inv_nm)
+            .map(|(h_k, y_k)| *h_k * y_k)
+            .collect::<Vec<_>>();
+        let u = self.u * w;
+        let minus_z = vec![-z; nm];
+        let p = proof.a
+            + proof.s * x
+            + msm(&self.g_vec, &minus_z).unwrap()
+            + msm(&h_prime, &self.h_exponents(&y_nm, z)).unwrap()
+            - h * proof.mu
+            + u * proof.t_hat;
+        verify_inner_product(&self.g_vec, &h_prime, &u, p, &proof.ipa, &mut transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeProofParameters;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_range_proof() {
+        let mut rng = test_rng();
+        let params = RangeProofParameters::<Projective>::new(32, 1);
+        let blinding = Fr::rand(&mut rng);
+        let (commitments, proof) = params.prove(&[123456789], &[blinding], &mut rng);
+        assert_eq!(commitments[0], params.commit(123456789, &blinding));
+        assert_eq!(proof.ipa.l_vec.len(), 5);
+        assert!(params.verify(&commitments, &proof));
+
+        // edge values
+        let (commitments, proof) = params.prove(&[0], &[blinding], &mut rng);
+        assert!(params.verify(&commitments, &proof));
+        let (commitments, proof) = params.prove(&[u32::MAX as u64], &[blinding], &mut rng);
+        assert!(params.verify(&commitments, &proof));
+
+        // the proof doesn't hold for another commitment
+        let other = params.commit(5, &blinding);
+        assert!(!params.verify(&[other], &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.t_hat += Fr::one();
+        assert!(!params.verify(&commitments, &wrong_proof));
+    }
+
+    #[test]
+    pub fn test_aggregated_range_proof() {
+        let mut rng = test_rng();
+        let params = RangeProofParameters::<Projective>::new(8, 4);
+        let values = [0, 1, 200, 255];
+        let blindings = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (commitments, proof) = params.prove(&values, &blindings, &mut rng);
+        assert!(params.verify(&commitments, &proof));
+        let mut swapped = commitments.clone();
+        swapped.swap(0, 3);
+        assert!(!params.verify(&swapped, &proof));
+
+        // a value out of range: the commitment to 256 = 2^8 can't be proven with the 8 bits of 0,
+        // a proof built for 0 doesn't verify against it
+        let (_, proof_zero) = params.prove(&[0, 1, 200, 0], &blindings, &mut rng);
+        let mut commitments_256 = commitments.clone();
+        commitments_256[3] = params.commit(256, &blindings[3]);
+        assert!(!params.verify(&commitments_256, &proof_zero));
+    }
+}
@@ -1,9 +1,141 @@
 // Pedersen commitments
+// C = sum_i m_i * G_i + r * H commits to the vector m with blinding r. It is hiding (r is uniform) and binding
+// as long as nobody knows a discrete log relation between the generators: they are derived by hashing to the curve.
+pub mod bulletproofs;
+pub mod sigma;
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::Rng, UniformRand};
+use sha3::{Digest, Keccak256};
+
+use crate::utils::curve::{msm_secret, mul_secret, FixedBaseTable};
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Pedersen<C: CurveGroup> {
+    generators: Vec<C>,
+    h: C,
+}
+
+/// Default seed used by `Pedersen::setup`
+pub const PEDERSEN_SEED: &[u8] = b"ark-algorithms pedersen";
+
+/// 64 bytes, enough to fill the base field of any supported curve, hashed from (seed, index, counter)
+fn hash_bytes(seed: &[u8], index: u64, counter: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    for half in 0u8..2 {
+        let mut hasher = Keccak256::new();
+        hasher.update(seed);
+        hasher.update(index.to_be_bytes());
+        hasher.update(counter.to_be_bytes());
+        hasher.update([half]);
+        bytes.extend(hasher.finalize());
+    }
+    bytes
+}
+
+/// Hashes `seed` and `index` to a field element: 512 bits reduced modulo p, whose bias is negligible
+pub fn hash_to_field<F: PrimeField>(seed: &[u8], index: u64) -> F {
+    F::from_le_bytes_mod_order(&hash_bytes(seed, index, 0))
+}
+
+/// Hashes `seed` and `index` to a curve point, by try-and-increment: hashes are read as (x, sign) until
+/// they decode to a point, which is then multiplied by the cofactor
+pub fn hash_to_curve<C: CurveGroup>(seed: &[u8], index: u64) -> C {
+    let mut counter = 0u64;
+    loop {
+        let bytes = hash_bytes(seed, index, counter);
+        if let Some(point) = C::Affine::from_random_bytes(&bytes) {
+            let point = point.mul_by_cofactor_to_group();
+            if !point.is_zero() {
+                return point;
+            }
+        }
+        counter += 1;
+    }
+}
+
+/// n nothing-up-my-sleeve generators: hash_to_curve(label, i) for i < n
+/// Nobody knows discrete log relations between them, and distinct labels give independent generators.
+pub fn derive_generators<C: CurveGroup>(label: &[u8], n: usize) -> Vec<C> {
+    (0..n as u64).map(|i| hash_to_curve(label, i)).collect()
+}
+
+impl<C: CurveGroup> Pedersen<C> {
+    pub fn new(generators: Vec<C>, h: C) -> Self {
+        Self { generators, h }
+    }
+
+    /// Parameters to commit to vectors of up to n messages, with generators derived from the default seed
+    pub fn setup(n: usize) -> Self {
+        Self::setup_with_seed(n, PEDERSEN_SEED)
+    }
+
+    /// The generators are derived from `seed`, and h from `seed` under its own "/h" label, so that h
+    /// doesn't depend on n and isn't a generator of a larger setup
+    pub fn setup_with_seed(n: usize, seed: &[u8]) -> Self {
+        let generators = derive_generators(seed, n);
+        let h = hash_to_curve(&[seed, b"/h"].concat(), 0);
+        Self { generators, h }
+    }
+
+    /// Parameters with generators sampled from `rng`. Whoever runs it could keep their discrete logs, prefer
+    /// the hashed generators of `setup` unless the randomness is trusted.
+    pub fn setup_random<R: Rng>(n: usize, rng: &mut R) -> Self {
+        let scalars = (0..=n)
+            .map(|_| C::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let mut generators = FixedBaseTable::new(C::generator(), n + 1).batch_mul(&scalars);
+        let h = generators.pop().unwrap();
+        Self { generators, h }
+    }
+
+    pub fn generators(&self) -> &[C] {
+        &self.generators
+    }
+
+    pub fn h(&self) -> C {
+        self.h
+    }
+
+    /// Maximum number of messages in a commitment
+    pub fn size(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// Commits to `msgs` (at most `size()` of them) with blinding factor `blinding`
+    pub fn commit(&self, msgs: &[C::ScalarField], blinding: &C::ScalarField) -> C {
+        assert!(
+            msgs.len() <= self.generators.len(),
+            "too many messages for the pedersen parameters"
+        );
+        msm_secret(&self.generators[..msgs.len()], msgs).unwrap() + mul_secret(self.h, blinding)
+    }
+
+    /// Commitment to the sum of the messages of `c_1` and `c_2`, with the sum of their blindings
+    pub fn add(&self, c_1: &C, c_2: &C) -> C {
+        *c_1 + c_2
+    }
+
+    pub fn verify_opening(
+        &self,
+        commitment: &C,
+        msgs: &[C::ScalarField],
+        blinding: &C::ScalarField,
+    ) -> bool {
+        msgs.len() <= self.generators.len() && self.commit(msgs, blinding) == *commitment
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use ark_ff::UniformRand;
+    use super::{derive_generators, Pedersen};
+    use ark_ff::{One, UniformRand};
     use ark_pallas::Affine;
     use ark_pallas::Fr;
+    use ark_pallas::Projective;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::test_rng;
 
     #[test]
@@ -25,4 +157,69 @@ mod test {
         let homomorphic_sum = g * (m_1 + m_2) + h * (r_1 + r_2);
         assert!(c1_plus_c2.eq(&homomorphic_sum));
     }
+
+    #[test]
+    pub fn test_pedersen_vector_commitment() {
+        let mut rng = test_rng();
+        let pedersen = Pedersen::<Projective>::setup(4);
+        assert_eq!(pedersen.size(), 4);
+        // generators are deterministic, and depend on the seed
+        assert_eq!(pedersen, Pedersen::<Projective>::setup(4));
+        assert_ne!(
+            pedersen.generators()[0],
+            Pedersen::<Projective>::setup_with_seed(4, b"other").generators()[0]
+        );
+        let random = Pedersen::<Projective>::setup_random(4, &mut rng);
+        assert_eq!(random.size(), 4);
+        assert_ne!(random, pedersen);
+
+        let msgs_1 = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let msgs_2 = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (r_1, r_2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let c_1 = pedersen.commit(&msgs_1, &r_1);
+        let c_2 = pedersen.commit(&msgs_2, &r_2);
+        assert!(pedersen.verify_opening(&c_1, &msgs_1, &r_1));
+        assert!(!pedersen.verify_opening(&c_1, &msgs_1, &(r_1 + Fr::one())));
+        assert!(!pedersen.verify_opening(&c_1, &msgs_1[..3], &r_1));
+
+        // homomorphism, the shorter vector being padded with zeros
+        let mut msgs_sum = msgs_1.clone();
+        for (m, m_2) in msgs_sum.iter_mut().zip(msgs_2.iter()) {
+            *m += m_2;
+        }
+        assert!(pedersen.verify_opening(&pedersen.add(&c_1, &c_2), &msgs_sum, &(r_1 + r_2)));
+
+        // parameters serialization
+        let mut bytes = vec![];
+        pedersen.serialize_compressed(&mut bytes).unwrap();
+        let deserialized = Pedersen::<Projective>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(deserialized, pedersen);
+    }
+
+    #[test]
+    pub fn test_hash_to_curve_bls12_381() {
+        // a 48 bytes base field, and a cofactor to clear
+        let pedersen = Pedersen::<ark_bls12_381::G1Projective>::setup(2);
+        for g in pedersen.generators() {
+            assert!(crate::utils::curve::SubgroupCheck::check_subgroup(g));
+        }
+    }
+
+    #[test]
+    pub fn test_derive_generators() {
+        let gens = derive_generators::<Projective>(b"label", 4);
+        assert_eq!(gens.len(), 4);
+        // a prefix of a longer derivation, and independent of other labels
+        assert_eq!(gens[..], derive_generators::<Projective>(b"label", 8)[..4]);
+        let other = derive_generators::<Projective>(b"other label", 4);
+        assert!(gens.iter().all(|g| !other.contains(g)));
+        // the seeded setup is the labelled derivation, h being outside of it
+        let pedersen = Pedersen::<Projective>::setup_with_seed(4, b"label");
+        assert_eq!(pedersen.generators(), &gens[..]);
+        assert!(!derive_generators::<Projective>(b"label", 8).contains(&pedersen.h));
+        assert_eq!(
+            pedersen.h,
+            Pedersen::<Projective>::setup_with_seed(8, b"label").h
+        );
+    }
 }
@@ -0,0 +1,277 @@
+// Schnorr-style sigma protocols for pedersen commitments
+// - knowledge of an opening (m, r) of C = <m, G> + r * H:
+//   the prover sends A = <a, G> + s * H for random (a, s), receives e, and answers z = a + e * m, z_r = s + e * r.
+//   The verifier checks <z, G> + z_r * H == A + e * C.
+// - equality of the messages committed in C_1 and C_2 (possibly under different parameters):
+//   same protocol run on both commitments, with the same masks a (hence the same responses z) for the messages.
+// Interactive versions take the verifier's challenge, `_fs` versions derive it by hashing the statement
+// and the prover's first message (Fiat-Shamir).
+use ark_ec::CurveGroup;
+use ark_ff::UniformRand;
+use ark_std::rand::Rng;
+
+use super::Pedersen;
+use crate::transcript::Transcript;
+
+/// First message of the opening protocol, along with the prover's secret masks
+pub struct OpeningNonce<C: CurveGroup> {
+    pub a: C,
+    masks: Vec<C::ScalarField>,
+    mask_blinding: C::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningProof<C: CurveGroup> {
+    pub a: C,
+    pub z_msgs: Vec<C::ScalarField>,
+    pub z_blinding: C::ScalarField,
+}
+
+/// First message of the equality protocol, along with the prover's secret masks
+pub struct EqualityNonce<C: CurveGroup> {
+    pub a_1: C,
+    pub a_2: C,
+    masks: Vec<C::ScalarField>,
+    mask_blinding_1: C::ScalarField,
+    mask_blinding_2: C::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EqualityProof<C: CurveGroup> {
+    pub a_1: C,
+    pub a_2: C,
+    pub z_msgs: Vec<C::ScalarField>,
+    pub z_blinding_1: C::ScalarField,
+    pub z_blinding_2: C::ScalarField,
+}
+
+/// Hashes the parameters, the commitments and the prover's first message into a challenge
+fn fiat_shamir_challenge<C: CurveGroup>(
+    parameters: &[&Pedersen<C>],
+    points: &[C],
+) -> C::ScalarField {
+    let mut transcript = Transcript::new(b"pedersen sigma");
+    for p in parameters {
+        transcript.absorb(*p);
+    }
+    transcript.absorb(points);
+    transcript.challenge()
+}
+
+impl<C: CurveGroup> Pedersen<C> {
+    pub fn opening_nonce<R: Rng>(&self, n: usize, rng: &mut R) -> OpeningNonce<C> {
+        let masks = (0..n)
+            .map(|_| C::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let mask_blinding = C::ScalarField::rand(rng);
+        OpeningNonce {
+            a: self.commit(&masks, &mask_blinding),
+            masks,
+            mask_blinding,
+        }
+    }
+
+    pub fn opening_response(
+        &self,
+        nonce: OpeningNonce<C>,
+        msgs: &[C::ScalarField],
+        blinding: &C::ScalarField,
+        e: C::ScalarField,
+    ) -> OpeningProof<C> {
+        assert_eq!(nonce.masks.len(), msgs.len());
+        OpeningProof {
+            a: nonce.a,
+            z_msgs: nonce
+                .masks
+                .iter()
+                .zip(msgs)
+                .map(|(a_i, m_i)| *a_i + e * m_i)
+                .collect(),
+            z_blinding: nonce.mask_blinding + e * blinding,
+        }
+    }
+
+    /// Checks <z, G> + z_r * H == A + e * C
+    pub fn verify_opening_proof(
+        &self,
+        commitment: &C,
+        proof: &OpeningProof<C>,
+        e: C::ScalarField,
+    ) -> bool {
+        proof.z_msgs.len() <= self.size()
+            && self.commit(&proof.z_msgs, &proof.z_blinding) == proof.a + *commitment * e
+    }
+
+    /// Non-interactive proof of knowledge of an opening of `commitment`
+    pub fn prove_opening_fs<R: Rng>(
+        &self,
+        commitment: &C,
+        msgs: &[C::ScalarField],
+        blinding: &C::ScalarField,
+        rng: &mut R,
+    ) -> OpeningProof<C> {
+        let nonce = self.opening_nonce(msgs.len(), rng);
+        let e = fiat_shamir_challenge(&[self], &[*commitment, nonce.a]);
+        self.opening_response(nonce, msgs, blinding, e)
+    }
+
+    pub fn verify_opening_proof_fs(&self, commitment: &C, proof: &OpeningProof<C>) -> bool {
+        let e = fiat_shamir_challenge(&[self], &[*commitment, proof.a]);
+        self.verify_opening_proof(commitment, proof, e)
+    }
+
+    /// First message for proving that a commitment under `self` and one under `other` hide the same n messages
+    pub fn equality_nonce<R: Rng>(
+        &self,
+        other: &Pedersen<C>,
+        n: usize,
+        rng: &mut R,
+    ) -> EqualityNonce<C> {
+        let masks = (0..n)
+            .map(|_| C::ScalarField::rand(rng))
+            .collect::<Vec<_>>();
+        let mask_blinding_1 = C::ScalarField::rand(rng);
+        let mask_blinding_2 = C::ScalarField::rand(rng);
+        EqualityNonce {
+            a_1: self.commit(&masks, &mask_blinding_1),
+            a_2: other.commit(&masks, &mask_blinding_2),
+            masks,
+            mask_blinding_1,
+            mask_blinding_2,
+        }
+    }
+
+    pub fn equality_response(
+        &self,
+        nonce: EqualityNonce<C>,
+        msgs: &[C::ScalarField],
+        blinding_1: &C::ScalarField,
+        blinding_2: &C::ScalarField,
+        e: C::ScalarField,
+    ) -> EqualityProof<C> {
+        assert_eq!(nonce.masks.len(), msgs.len());
+        EqualityProof {
+            a_1: nonce.a_1,
+            a_2: nonce.a_2,
+            z_msgs: nonce
+                .masks
+                .iter()
+                .zip(msgs)
+                .map(|(a_i, m_i)| *a_i + e * m_i)
+                .collect(),
+            z_blinding_1: nonce.mask_blinding_1 + e * blinding_1,
+            z_blinding_2: nonce.mask_blinding_2 + e * blinding_2,
+        }
+    }
+
+    /// Checks both opening equations, with the same message responses
+    pub fn verify_equality_proof(
+        &self,
+        other: &Pedersen<C>,
+        commitment_1: &C,
+        commitment_2: &C,
+        proof: &EqualityProof<C>,
+        e: C::ScalarField,
+    ) -> bool {
+        proof.z_msgs.len() <= self.size().min(other.size())
+            && self.commit(&proof.z_msgs, &proof.z_blinding_1) == proof.a_1 + *commitment_1 * e
+            && other.commit(&proof.z_msgs, &proof.z_blinding_2) == proof.a_2 + *commitment_2 * e
+    }
+
+    /// Non-interactive proof that `commitment_1` (under `self`) and `commitment_2` (under `other`) hide `msgs`
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_equality_fs<R: Rng>(
+        &self,
+        other: &Pedersen<C>,
+        commitment_1: &C,
+        commitment_2: &C,
+        msgs: &[C::ScalarField],
+        blinding_1: &C::ScalarField,
+        blinding_2: &C::ScalarField,
+        rng: &mut R,
+    ) -> EqualityProof<C> {
+        let nonce = self.equality_nonce(other, msgs.len(), rng);
+        let e = fiat_shamir_challenge(
+            &[self, other],
+            &[*commitment_1, *commitment_2, nonce.a_1, nonce.a_2],
+        );
+        self.equality_response(nonce, msgs, blinding_1, blinding_2, e)
+    }
+
+    pub fn verify_equality_proof_fs(
+        &self,
+        other: &Pedersen<C>,
+        commitment_1: &C,
+        commitment_2: &C,
+        proof: &EqualityProof<C>,
+    ) -> bool {
+        let e = fiat_shamir_challenge(
+            &[self, other],
+            &[*commitment_1, *commitment_2, proof.a_1, proof.a_2],
+        );
+        self.verify_equality_proof(other, commitment_1, commitment_2, proof, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cs::pedersen::Pedersen;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_opening_proof() {
+        let mut rng = test_rng();
+        let pedersen = Pedersen::<Projective>::setup(3);
+        let msgs = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let blinding = Fr::rand(&mut rng);
+        let commitment = pedersen.commit(&msgs, &blinding);
+
+        // interactive
+        let nonce = pedersen.opening_nonce(msgs.len(), &mut rng);
+        let e = Fr::rand(&mut rng);
+        let proof = pedersen.opening_response(nonce, &msgs, &blinding, e);
+        assert!(pedersen.verify_opening_proof(&commitment, &proof, e));
+        assert!(!pedersen.verify_opening_proof(&commitment, &proof, e + Fr::one()));
+
+        // non-interactive
+        let proof = pedersen.prove_opening_fs(&commitment, &msgs, &blinding, &mut rng);
+        assert!(pedersen.verify_opening_proof_fs(&commitment, &proof));
+        let other_commitment = pedersen.commit(&msgs, &(blinding + Fr::one()));
+        assert!(!pedersen.verify_opening_proof_fs(&other_commitment, &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.z_msgs[0] += Fr::one();
+        assert!(!pedersen.verify_opening_proof_fs(&commitment, &wrong_proof));
+    }
+
+    #[test]
+    pub fn test_equality_proof() {
+        let mut rng = test_rng();
+        let pedersen_1 = Pedersen::<Projective>::setup(2);
+        let pedersen_2 = Pedersen::<Projective>::setup_with_seed(2, b"other parameters");
+        let msgs = (0..2).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (r_1, r_2) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let c_1 = pedersen_1.commit(&msgs, &r_1);
+        let c_2 = pedersen_2.commit(&msgs, &r_2);
+
+        // interactive
+        let nonce = pedersen_1.equality_nonce(&pedersen_2, msgs.len(), &mut rng);
+        let e = Fr::rand(&mut rng);
+        let proof = pedersen_1.equality_response(nonce, &msgs, &r_1, &r_2, e);
+        assert!(pedersen_1.verify_equality_proof(&pedersen_2, &c_1, &c_2, &proof, e));
+
+        // non-interactive
+        let proof =
+            pedersen_1.prove_equality_fs(&pedersen_2, &c_1, &c_2, &msgs, &r_1, &r_2, &mut rng);
+        assert!(pedersen_1.verify_equality_proof_fs(&pedersen_2, &c_1, &c_2, &proof));
+
+        // commitments to different messages
+        let mut other_msgs = msgs.clone();
+        other_msgs[1] += Fr::one();
+        let c_3 = pedersen_2.commit(&other_msgs, &r_2);
+        let proof =
+            pedersen_1.prove_equality_fs(&pedersen_2, &c_1, &c_3, &msgs, &r_1, &r_2, &mut rng);
+        assert!(!pedersen_1.verify_equality_proof_fs(&pedersen_2, &c_1, &c_3, &proof));
+    }
+}
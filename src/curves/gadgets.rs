@@ -0,0 +1,199 @@
+// Non-native plumbing for circuits over one curve of a cycle that handle values of the other side:
+// - emulated field elements, whose limbs match `curves::to_limbs`, so that a value hashed or passed as public
+//   input natively (as limbs) and in the circuit agree,
+// - points of a curve in a circuit over its scalar field, e.g. a kzg commitment verified in a circuit over
+//   bn254's Fr, or a C_1 commitment absorbed by the augmented circuit over C_1's scalar field.
+// Points are only checked to be on the curve: the cycle curves have prime order.
+use ark_ec::{
+    short_weierstrass::{Projective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{PrimeField, Zero};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, nonnative::NonNativeFieldVar, FieldVar},
+    R1CSVar, ToBitsGadget,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+
+use super::from_limbs;
+
+/// An element of T emulated in a circuit over F
+pub type EmulatedFpVar<T, F> = NonNativeFieldVar<T, F>;
+
+/// Conversions between emulated field elements and native limbs of `limb_bits` bits, little-endian
+pub trait LimbsGadget<F: PrimeField>: Sized {
+    /// The limbs of the canonical value, as `curves::to_limbs` computes them
+    fn to_limbs(&self, limb_bits: usize, num_limbs: usize)
+        -> Result<Vec<FpVar<F>>, SynthesisError>;
+
+    /// A witness whose canonical value has these limbs
+    fn from_limbs(limbs: &[FpVar<F>], limb_bits: usize) -> Result<Self, SynthesisError>;
+}
+
+impl<T: PrimeField, F: PrimeField> LimbsGadget<F> for EmulatedFpVar<T, F> {
+    fn to_limbs(
+        &self,
+        limb_bits: usize,
+        num_limbs: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        assert!(
+            limb_bits < F::MODULUS_BIT_SIZE as usize,
+            "limbs don't fit in the circuit field"
+        );
+        // to_bits_le enforces the canonical decomposition
+        let mut bits = self.to_bits_le()?;
+        let size = limb_bits * num_limbs;
+        if bits.len() > size {
+            for bit in bits.drain(size..) {
+                bit.enforce_equal(&Boolean::FALSE)?;
+            }
+        }
+        bits.resize(size, Boolean::FALSE);
+        bits.chunks(limb_bits)
+            .map(Boolean::le_bits_to_fp_var)
+            .collect()
+    }
+
+    fn from_limbs(limbs: &[FpVar<F>], limb_bits: usize) -> Result<Self, SynthesisError> {
+        let cs = limbs.cs();
+        let value = || {
+            let values = limbs.value()?;
+            from_limbs::<T, F>(&values, limb_bits).ok_or(SynthesisError::Unsatisfiable)
+        };
+        let var = Self::new_witness(cs, value)?;
+        var.to_limbs(limb_bits, limbs.len())?.enforce_equal(limbs)?;
+        Ok(var)
+    }
+}
+
+/// A point of the curve with config P, in a circuit over F, usually P's scalar field
+/// The point at infinity is (0, 0) with `is_infinity` set.
+#[derive(Clone, Debug)]
+pub struct NonNativePointVar<P: SWCurveConfig, F: PrimeField>
+where
+    P::BaseField: PrimeField,
+{
+    pub x: EmulatedFpVar<P::BaseField, F>,
+    pub y: EmulatedFpVar<P::BaseField, F>,
+    pub is_infinity: Boolean<F>,
+}
+
+impl<P: SWCurveConfig, F: PrimeField> NonNativePointVar<P, F>
+where
+    P::BaseField: PrimeField,
+{
+    /// y^2 = x^3 + a * x + b, unless the point is at infinity
+    pub fn enforce_on_curve(&self) -> Result<(), SynthesisError> {
+        let a = EmulatedFpVar::constant(P::COEFF_A);
+        let b = EmulatedFpVar::constant(P::COEFF_B);
+        let rhs = self.x.square()? * &self.x + a * &self.x + b;
+        self.y
+            .square()?
+            .is_eq(&rhs)?
+            .or(&self.is_infinity)?
+            .enforce_equal(&Boolean::TRUE)
+    }
+
+    /// (x limbs, y limbs, is_infinity), as `curves::point_to_limbs` lays them out
+    pub fn to_limbs(
+        &self,
+        limb_bits: usize,
+        num_limbs: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut limbs = self.x.to_limbs(limb_bits, num_limbs)?;
+        limbs.extend(self.y.to_limbs(limb_bits, num_limbs)?);
+        limbs.push(FpVar::from(self.is_infinity.clone()));
+        Ok(limbs)
+    }
+}
+
+impl<P: SWCurveConfig, F: PrimeField> AllocVar<Projective<P>, F> for NonNativePointVar<P, F>
+where
+    P::BaseField: PrimeField,
+{
+    fn new_variable<T: Borrow<Projective<P>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let point = f().map(|point| point.borrow().into_affine());
+        let coordinates = point.map(|point| match point.xy() {
+            Some((x, y)) => (*x, *y, false),
+            None => (P::BaseField::zero(), P::BaseField::zero(), true),
+        });
+        let var = Self {
+            x: EmulatedFpVar::new_variable(cs.clone(), || coordinates.map(|c| c.0), mode)?,
+            y: EmulatedFpVar::new_variable(cs.clone(), || coordinates.map(|c| c.1), mode)?,
+            is_infinity: Boolean::new_variable(cs, || coordinates.map(|c| c.2), mode)?,
+        };
+        if mode != AllocationMode::Constant {
+            var.enforce_on_curve()?;
+        }
+        Ok(var)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmulatedFpVar, LimbsGadget, NonNativePointVar};
+    use crate::curves::{point_to_limbs, to_limbs};
+    use ark_bn254::{Fq, Fr, G1Projective};
+    use ark_ec::{short_weierstrass::Projective, CurveGroup};
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_emulated_limbs() {
+        let mut rng = test_rng();
+        let x = Fq::rand(&mut rng);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x_var = EmulatedFpVar::<Fq, Fr>::new_witness(cs.clone(), || Ok(x)).unwrap();
+        let limbs = x_var.to_limbs(68, 4).unwrap();
+        assert_eq!(limbs.value().unwrap(), to_limbs::<Fq, Fr>(&x, 68, 4));
+
+        let limb_vars =
+            Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(to_limbs(&x, 64, 4))).unwrap();
+        let y_var = EmulatedFpVar::<Fq, Fr>::from_limbs(&limb_vars, 64).unwrap();
+        assert_eq!(y_var.value().unwrap(), x);
+        assert!(cs.is_satisfied().unwrap());
+
+        // a limb too large for its 64 bits
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut limbs = to_limbs::<Fq, Fr>(&x, 64, 4);
+        limbs[1] += Fr::from(u64::MAX) + Fr::one();
+        let limb_vars = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(limbs)).unwrap();
+        let _ = EmulatedFpVar::<Fq, Fr>::from_limbs(&limb_vars, 64).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    pub fn test_non_native_points() {
+        let mut rng = test_rng();
+        // bn254 points in a circuit over bn254's scalar field
+        for point in [G1Projective::rand(&mut rng), G1Projective::zero()] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let point_var = NonNativePointVar::new_witness(cs.clone(), || Ok(point)).unwrap();
+            let limbs = point_var.to_limbs(64, 4).unwrap();
+            assert_eq!(
+                limbs.value().unwrap(),
+                point_to_limbs::<_, Fr>(&point, 64, 4)
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+
+        // a point off the curve
+        let point = G1Projective::rand(&mut rng).into_affine();
+        let off_curve = Projective::new_unchecked(point.x, point.y + Fq::one(), Fq::one());
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        NonNativePointVar::<ark_bn254::g1::Config, Fr>::new_witness(cs.clone(), || Ok(off_curve))
+            .unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,39 @@
+// Grumpkin: y^2 = x^3 - 17 over bn254's scalar field, https://hackmd.io/@aztec-network/ByzgNxBfd#2-Grumpkin---A-curve-on-top-of-BN-254-for-SNARK-efficient-group-operations
+// Its group order is the base field modulus of bn254, so that the two curves form a cycle:
+// bn254 G1 operations are native in a circuit over grumpkin's scalar field, and conversely.
+use ark_bn254::{Fq, Fr};
+use ark_ec::{
+    models::CurveConfig,
+    short_weierstrass::{self, SWCurveConfig},
+};
+use ark_ff::{Field, MontFp};
+
+pub type Affine = short_weierstrass::Affine<GrumpkinConfig>;
+pub type Projective = short_weierstrass::Projective<GrumpkinConfig>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GrumpkinConfig;
+
+impl CurveConfig for GrumpkinConfig {
+    type BaseField = Fr;
+    type ScalarField = Fq;
+
+    /// The curve has prime order
+    const COFACTOR: &'static [u64] = &[1];
+    const COFACTOR_INV: Fq = Fq::ONE;
+}
+
+impl SWCurveConfig for GrumpkinConfig {
+    const COEFF_A: Fr = Fr::ZERO;
+    const COEFF_B: Fr = MontFp!("-17");
+
+    /// (1, sqrt(-16))
+    const GENERATOR: Affine = Affine::new_unchecked(
+        Fr::ONE,
+        MontFp!("17631683881184975370165255887551781615748388533673675138860"),
+    );
+
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Fr::ZERO
+    }
+}
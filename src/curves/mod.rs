@@ -0,0 +1,184 @@
+// Cycles of curves: pairs (C_1, C_2) where the scalar field of each curve is the base field of the other.
+// A circuit over C_1's scalar field can then do C_2's group operations natively, which recursion relies on:
+// folding or ivc over C_1 defers the verification of C_2 points to a circuit over C_2's scalar field.
+// Whatever crosses from one side to the other without being native, e.g. the coordinates of a C_1 point in a
+// circuit over C_1's scalar field, is represented with limbs: little-endian chunks of `limb_bits` bits.
+pub mod gadgets;
+pub mod grumpkin;
+
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField, Zero};
+
+/// A cycle of curves: C_1's base field is C_2's scalar field, and the other way around
+pub trait CurveCycle {
+    type C1: CurveGroup<BaseField = <Self::C2 as Group>::ScalarField>;
+    type C2: CurveGroup<BaseField = <Self::C1 as Group>::ScalarField>;
+}
+
+/// The pasta cycle: pallas and vesta
+pub struct PallasVesta;
+
+impl CurveCycle for PallasVesta {
+    type C1 = ark_pallas::Projective;
+    type C2 = ark_vesta::Projective;
+}
+
+/// bn254's G1 and grumpkin, which allows recursion over a curve with an ethereum precompile
+pub struct Bn254Grumpkin;
+
+impl CurveCycle for Bn254Grumpkin {
+    type C1 = ark_bn254::G1Projective;
+    type C2 = grumpkin::Projective;
+}
+
+/// Number of limbs of `limb_bits` bits needed to hold any element of F
+pub fn num_limbs<F: PrimeField>(limb_bits: usize) -> usize {
+    (F::MODULUS_BIT_SIZE as usize).div_ceil(limb_bits)
+}
+
+/// Decomposes x into `num_limbs` limbs of `limb_bits` bits, each one being an element of another field T
+/// Panics if the limbs don't fit in T or don't hold x
+pub fn to_limbs<F: PrimeField, T: PrimeField>(x: &F, limb_bits: usize, num_limbs: usize) -> Vec<T> {
+    assert!(
+        limb_bits < T::MODULUS_BIT_SIZE as usize,
+        "limbs don't fit in the target field"
+    );
+    let mut bits = x.into_bigint().to_bits_le();
+    assert!(
+        bits[(limb_bits * num_limbs).min(bits.len())..]
+            .iter()
+            .all(|bit| !bit),
+        "{num_limbs} limbs of {limb_bits} bits can't hold the element"
+    );
+    bits.resize(limb_bits * num_limbs, false);
+    bits.chunks(limb_bits)
+        .map(|chunk| T::from_bigint(T::BigInt::from_bits_le(chunk)).unwrap())
+        .collect()
+}
+
+/// Recomposes sum_i limbs[i] * 2^{i * limb_bits}, None if a limb has more than `limb_bits` bits
+/// or if the result isn't smaller than F's modulus
+pub fn from_limbs<F: PrimeField, T: PrimeField>(limbs: &[T], limb_bits: usize) -> Option<F> {
+    let mut bits = vec![];
+    for limb in limbs {
+        let mut limb_bits_le = limb.into_bigint().to_bits_le();
+        if limb_bits_le.iter().skip(limb_bits).any(|bit| *bit) {
+            return None;
+        }
+        limb_bits_le.resize(limb_bits, false);
+        bits.extend(limb_bits_le);
+    }
+    let capacity = F::BigInt::NUM_LIMBS * 64;
+    if bits.iter().skip(capacity).any(|bit| *bit) {
+        return None;
+    }
+    bits.truncate(capacity);
+    F::from_bigint(F::BigInt::from_bits_le(&bits))
+}
+
+/// The element of T with the same integer value as x, None if x isn't smaller than T's modulus
+/// Along a cycle, both moduli are close: most, but not all, elements cross over unchanged.
+pub fn cast<F: PrimeField, T: PrimeField>(x: &F) -> Option<T> {
+    from_limbs(&to_limbs::<F, F>(x, 64, num_limbs::<F>(64)), 64)
+}
+
+/// The limbs of a point's affine coordinates, (x, y, is_infinity), the point at infinity being (0, 0, 1)
+pub fn point_to_limbs<C: CurveGroup, T: PrimeField>(
+    point: &C,
+    limb_bits: usize,
+    num_limbs: usize,
+) -> Vec<T>
+where
+    C::BaseField: PrimeField,
+{
+    let affine = point.into_affine();
+    let (x, y, is_infinity) = match affine.xy() {
+        Some((x, y)) => (*x, *y, T::zero()),
+        None => (C::BaseField::zero(), C::BaseField::zero(), T::one()),
+    };
+    let mut limbs = to_limbs(&x, limb_bits, num_limbs);
+    limbs.extend(to_limbs::<_, T>(&y, limb_bits, num_limbs));
+    limbs.push(is_infinity);
+    limbs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cast, from_limbs, grumpkin, num_limbs, point_to_limbs, to_limbs, Bn254Grumpkin, CurveCycle,
+        PallasVesta,
+    };
+    use crate::utils::curve::SubgroupCheck;
+    use ark_ec::{AffineRepr, CurveGroup, Group};
+    use ark_ff::{PrimeField, UniformRand, Zero};
+    use ark_std::test_rng;
+
+    /// Both curves' generators have the other curve's base field modulus as order
+    fn check_cycle<Cycle: CurveCycle>() {
+        let g_1 = Cycle::C1::generator();
+        let g_2 = Cycle::C2::generator();
+        assert!(g_1
+            .mul_bigint(<Cycle::C2 as CurveGroup>::BaseField::MODULUS)
+            .is_zero());
+        assert!(g_2
+            .mul_bigint(<Cycle::C1 as CurveGroup>::BaseField::MODULUS)
+            .is_zero());
+    }
+
+    #[test]
+    pub fn test_curve_cycles() {
+        check_cycle::<PallasVesta>();
+        check_cycle::<Bn254Grumpkin>();
+        let mut rng = test_rng();
+        let point = grumpkin::Projective::rand(&mut rng);
+        assert!(point.check_subgroup());
+        assert!(grumpkin::Affine::generator().is_on_curve());
+    }
+
+    #[test]
+    pub fn test_limbs() {
+        let mut rng = test_rng();
+        // a bn254 base field element, as limbs in bn254's scalar field
+        assert_eq!(num_limbs::<ark_bn254::Fq>(64), 4);
+        assert_eq!(num_limbs::<ark_bn254::Fq>(68), 4);
+        for _ in 0..10 {
+            let x = ark_bn254::Fq::rand(&mut rng);
+            for limb_bits in [64, 68, 100] {
+                let n = num_limbs::<ark_bn254::Fq>(limb_bits);
+                let limbs = to_limbs::<_, ark_bn254::Fr>(&x, limb_bits, n);
+                assert_eq!(limbs.len(), n);
+                assert_eq!(from_limbs::<ark_bn254::Fq, _>(&limbs, limb_bits), Some(x));
+            }
+        }
+        // an oversized limb, or a value above the modulus
+        let mut limbs = to_limbs::<_, ark_bn254::Fr>(&ark_bn254::Fq::from(5u64), 64, 4);
+        limbs[0] = ark_bn254::Fr::from(u128::MAX);
+        assert_eq!(from_limbs::<ark_bn254::Fq, _>(&limbs, 64), None);
+        let mut above = to_limbs::<_, ark_bn254::Fr>(&ark_bn254::Fq::from(5u64), 64, 4);
+        above[3] = ark_bn254::Fr::from(u64::MAX);
+        assert_eq!(from_limbs::<ark_bn254::Fq, _>(&above, 64), None);
+
+        // r < q for bn254: scalars cross over, some base field elements don't
+        let r_minus_one = -ark_bn254::Fr::from(1u64);
+        let crossed = cast::<_, ark_bn254::Fq>(&r_minus_one).unwrap();
+        assert_eq!(crossed.into_bigint(), r_minus_one.into_bigint());
+        assert_eq!(cast::<_, ark_bn254::Fr>(&-ark_bn254::Fq::from(1u64)), None);
+
+        // a pallas point in a circuit over pallas' scalar field
+        let point = ark_pallas::Projective::rand(&mut rng);
+        let limbs = point_to_limbs::<_, ark_pallas::Fr>(&point, 64, 4);
+        assert_eq!(limbs.len(), 9);
+        let affine = point.into_affine();
+        assert_eq!(
+            from_limbs::<ark_pallas::Fq, _>(&limbs[..4], 64),
+            Some(affine.x)
+        );
+        assert_eq!(
+            from_limbs::<ark_pallas::Fq, _>(&limbs[4..8], 64),
+            Some(affine.y)
+        );
+        let zero = point_to_limbs::<_, ark_pallas::Fr>(&ark_pallas::Projective::zero(), 64, 4);
+        assert!(zero[..8].iter().all(|limb| limb.is_zero()));
+        assert_eq!(zero[8], ark_pallas::Fr::from(1u64));
+    }
+}
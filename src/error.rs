@@ -0,0 +1,63 @@
+// Errors shared by the crate's building blocks (matrices, domains, polynomial commitments, qaps)
+// Protocols with failure modes of their own, e.g. plonk, keep a dedicated error type.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlgorithmsError {
+    /// A vector or matrix doesn't have the expected length
+    DimensionMismatch { expected: usize, got: usize },
+    /// A polynomial doesn't fit in the srs
+    DegreeTooLarge { degree: usize, max_degree: usize },
+    /// The field has no multiplicative subgroup of the requested size
+    DomainNotFound(usize),
+    /// The witness doesn't satisfy the constraints
+    UnsatisfiedConstraints,
+    /// A division expected to be exact left a remainder
+    NonZeroRemainder,
+    /// The circuit failed to generate its constraints
+    Synthesis(String),
+    /// No constraint determines this variable of z from the known ones
+    UnsolvedVariable(usize),
+    /// Bytes that don't decode to the expected artifact
+    Serialization(String),
+    /// The srs points aren't successive powers of a single tau
+    InvalidSrs,
+    /// A point isn't on the curve or in the prime order subgroup
+    InvalidPoint,
+    /// Parameters that can't be instantiated, e.g. poseidon's over too small a field
+    InvalidParameters(String),
+}
+
+impl std::fmt::Display for AlgorithmsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlgorithmsError::DimensionMismatch { expected, got } => {
+                write!(f, "expected a dimension of {expected}, got {got}")
+            }
+            AlgorithmsError::DegreeTooLarge { degree, max_degree } => {
+                write!(f, "degree {degree} exceeds the maximum degree {max_degree}")
+            }
+            AlgorithmsError::DomainNotFound(size) => {
+                write!(f, "no evaluation domain of size {size}")
+            }
+            AlgorithmsError::UnsatisfiedConstraints => {
+                write!(f, "the witness doesn't satisfy the constraints")
+            }
+            AlgorithmsError::NonZeroRemainder => write!(f, "the division left a remainder"),
+            AlgorithmsError::Synthesis(error) => write!(f, "synthesis failed: {error}"),
+            AlgorithmsError::UnsolvedVariable(i) => {
+                write!(f, "variable {i} can't be derived from the constraints")
+            }
+            AlgorithmsError::Serialization(error) => write!(f, "serialization failed: {error}"),
+            AlgorithmsError::InvalidSrs => write!(f, "the srs is not well formed"),
+            AlgorithmsError::InvalidPoint => {
+                write!(
+                    f,
+                    "a point is not on the curve or not in the prime order subgroup"
+                )
+            }
+            AlgorithmsError::InvalidParameters(error) => write!(f, "invalid parameters: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for AlgorithmsError {}
@@ -0,0 +1,217 @@
+// Gas estimates for on-chain verification over bn254, from the precompile calls and the calldata of a verifier:
+// - ecAdd (0x06) 150, ecMul (0x07) 6000, and ecPairing (0x08) 45000 + 34000 per pair, as set by EIP-1108,
+// - calldata 4 gas per zero byte and 16 per non zero byte (EIP-2028), on top of the 21000 of a transaction.
+// The verifier's own opcodes (memory, field arithmetic, hashing the transcript) aren't counted: they add a few
+// thousand gas, against ~100k for the precompiles. There is no precompile for G2 scalar multiplications, so
+// verifiers which need them, as `KZG::verify`, can't run on-chain: their `_no_g2_ops` variants are estimated instead.
+// The verifier transcripts follow the repo's verifiers and `cs::pcs::kzg::solidity`, with static calldata as
+// snarkjs' verifiers for groth16 (`verifyProof(uint[2], uint[2][2], uint[2], uint[n])`) and plonk
+// (`verifyProof(uint256[24], uint256[n])`).
+use crate::cs::pcs::kzg::evm::{CALLDATA_SIZE, WORD_SIZE};
+
+pub const TX_BASE_GAS: u64 = 21_000;
+pub const EC_ADD_GAS: u64 = 150;
+pub const EC_MUL_GAS: u64 = 6_000;
+pub const PAIRING_BASE_GAS: u64 = 45_000;
+pub const PAIRING_PER_PAIR_GAS: u64 = 34_000;
+pub const CALLDATA_ZERO_BYTE_GAS: u64 = 4;
+pub const CALLDATA_NONZERO_BYTE_GAS: u64 = 16;
+
+/// What a verifier costs on-chain: its precompile calls and calldata
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerificationTranscript {
+    pub ec_add: usize,
+    pub ec_mul: usize,
+    /// calls to the pairing precompile, each checking a product of pairings
+    pub pairing_checks: usize,
+    /// pairs over all the pairing checks
+    pub pairs: usize,
+    pub calldata_zero_bytes: usize,
+    pub calldata_nonzero_bytes: usize,
+}
+
+/// A gas estimate, split between the precompiles and the calldata
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub precompiles: u64,
+    pub calldata: u64,
+}
+
+impl GasEstimate {
+    /// The gas of a transaction calling the verifier
+    pub fn total(&self) -> u64 {
+        TX_BASE_GAS + self.precompiles + self.calldata
+    }
+}
+
+impl VerificationTranscript {
+    /// Counts the zero and non zero bytes of actual calldata
+    pub fn with_calldata(mut self, calldata: &[u8]) -> Self {
+        let zero_bytes = calldata.iter().filter(|b| **b == 0).count();
+        self.calldata_zero_bytes = zero_bytes;
+        self.calldata_nonzero_bytes = calldata.len() - zero_bytes;
+        self
+    }
+
+    /// Calldata of `size` bytes, all counted as non zero: an upper bound
+    pub fn with_calldata_size(mut self, size: usize) -> Self {
+        self.calldata_zero_bytes = 0;
+        self.calldata_nonzero_bytes = size;
+        self
+    }
+
+    pub fn gas(&self) -> GasEstimate {
+        let precompiles = self.ec_add as u64 * EC_ADD_GAS
+            + self.ec_mul as u64 * EC_MUL_GAS
+            + self.pairing_checks as u64 * PAIRING_BASE_GAS
+            + self.pairs as u64 * PAIRING_PER_PAIR_GAS;
+        let calldata = self.calldata_zero_bytes as u64 * CALLDATA_ZERO_BYTE_GAS
+            + self.calldata_nonzero_bytes as u64 * CALLDATA_NONZERO_BYTE_GAS;
+        GasEstimate {
+            precompiles,
+            calldata,
+        }
+    }
+
+    /// `KZG::verify_no_g2_ops`, its two sides moved to a single check:
+    /// e(pi, vk) * e(z * pi, -g2) * e(y * g1 - commitment, g2) == 1
+    pub fn kzg_verify_no_g2_ops() -> Self {
+        Self {
+            ec_add: 1,
+            ec_mul: 2,
+            pairing_checks: 1,
+            pairs: 3,
+            ..Default::default()
+        }
+        .with_calldata_size(CALLDATA_SIZE)
+    }
+
+    /// `KZG::verify_no_g2_ops_evm_opcode`, the generated contract's `verify`:
+    /// e(pi, vk) * e(-z * pi - commitment + y * g1, g2) == 1
+    pub fn kzg_verify_no_g2_ops_evm_opcode() -> Self {
+        Self {
+            ec_add: 2,
+            ec_mul: 2,
+            pairing_checks: 1,
+            pairs: 2,
+            ..Default::default()
+        }
+        .with_calldata_size(CALLDATA_SIZE)
+    }
+
+    /// `KZG::verify_multi_open_no_g2_ops`, the generated contract's `verifyMultiOpen`, at `n_points` points
+    /// with a lagrange polynomial of `n_lagrange_coeffs` coefficients (n_points, unless its leading ones are zero):
+    /// [Z(tau)]_1 and [I(tau)]_1 are computed with a multiplication and an addition per coefficient.
+    pub fn kzg_verify_multi_open(n_points: usize, n_lagrange_coeffs: usize) -> Self {
+        let n_coeffs = n_points + 1 + n_lagrange_coeffs;
+        // selector, commitment, 4 array offsets, pi, then the arrays' lengths and elements
+        let n_words = 10 + 4 + 2 * n_points + n_lagrange_coeffs + n_points + 1;
+        Self {
+            ec_add: n_coeffs + 1,
+            ec_mul: n_coeffs,
+            pairing_checks: 1,
+            pairs: 2,
+            ..Default::default()
+        }
+        .with_calldata_size(4 + n_words * WORD_SIZE)
+    }
+
+    /// Groth16 with `n_public` public inputs:
+    /// e(A, B) == e(alpha, beta) * e(sum_i x_i [IC_i], gamma) * e(C, delta), a multiplication and an addition per input
+    pub fn groth16(n_public: usize) -> Self {
+        Self {
+            ec_add: n_public,
+            ec_mul: n_public,
+            pairing_checks: 1,
+            pairs: 4,
+            ..Default::default()
+        }
+        .with_calldata_size(4 + (8 + n_public) * WORD_SIZE)
+    }
+
+    /// `Plonk::verify` with `n_public` public inputs: [D] has 9 multiplications (q_c's scalar is 1),
+    /// [F] 5 more, W_zeta + u * W_zeta_omega 1 and the right hand side 3, each followed by an addition.
+    /// The proof is 9 points and 6 evaluations, 24 words.
+    pub fn plonk(n_public: usize) -> Self {
+        Self {
+            ec_add: 18,
+            ec_mul: 18,
+            pairing_checks: 1,
+            pairs: 2,
+            ..Default::default()
+        }
+        .with_calldata_size(4 + (24 + n_public) * WORD_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerificationTranscript;
+    use crate::cs::pcs::kzg::{
+        evm::{encode_calldata, encode_multi_open_calldata},
+        KZG,
+    };
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_gas_estimates() {
+        let single = VerificationTranscript::kzg_verify_no_g2_ops_evm_opcode().gas();
+        // 2 * 150 + 2 * 6000 + 45000 + 2 * 34000, and 196 bytes of calldata
+        assert_eq!(single.precompiles, 125_300);
+        assert_eq!(single.calldata, 196 * 16);
+        assert_eq!(single.total(), 21_000 + 125_300 + 3_136);
+        // the extra pair costs more than the saved addition
+        let three_pairs = VerificationTranscript::kzg_verify_no_g2_ops().gas();
+        assert_eq!(three_pairs.precompiles - single.precompiles, 34_000 - 150);
+        assert_eq!(
+            VerificationTranscript::groth16(2).gas().precompiles,
+            2 * 150 + 2 * 6000 + 45_000 + 4 * 34_000
+        );
+        assert!(
+            VerificationTranscript::plonk(2).gas().total()
+                > VerificationTranscript::groth16(2).gas().total()
+        );
+
+        // the calldata sizes match the encoders', whose zero bytes are cheaper
+        let mut rng = test_rng();
+        let degree = 7;
+        let mut kzg =
+            KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), degree);
+        kzg.setup_from_rng(&mut rng);
+        let polynomial = DensePolynomial::<Fr>::rand(degree, &mut rng);
+        let commitment = kzg.commit(&polynomial).unwrap();
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let pi = kzg.open(&polynomial, z, y).unwrap();
+        let calldata = encode_calldata(&commitment, &z, &y, &pi);
+        let estimate = VerificationTranscript::kzg_verify_no_g2_ops_evm_opcode();
+        assert_eq!(estimate.calldata_nonzero_bytes, calldata.len());
+        assert!(
+            estimate.clone().with_calldata(&calldata).gas().calldata <= estimate.gas().calldata
+        );
+
+        let z_values = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let y_values = z_values
+            .iter()
+            .map(|z| polynomial.evaluate(z))
+            .collect::<Vec<_>>();
+        let (pi, lagrange_polynomial, zero_polynomial) =
+            kzg.multi_open(&polynomial, &z_values).unwrap();
+        let calldata = encode_multi_open_calldata(
+            &commitment,
+            &z_values,
+            &y_values,
+            &lagrange_polynomial,
+            &zero_polynomial,
+            &pi,
+        );
+        let estimate =
+            VerificationTranscript::kzg_verify_multi_open(3, lagrange_polynomial.coeffs.len());
+        assert_eq!(estimate.calldata_nonzero_bytes, calldata.len());
+        // 4 + 3 coefficients
+        assert_eq!(estimate.ec_mul, 7);
+    }
+}
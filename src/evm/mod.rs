@@ -0,0 +1,3 @@
+// EVM tooling shared by the verifiers. The bn254 KZG encodings and the solidity generator are
+// `cs::pcs::kzg::{evm, solidity}`.
+pub mod gas;
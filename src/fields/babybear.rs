@@ -0,0 +1,58 @@
+// BabyBear: p = 15 * 2^27 + 1, as in risc0 and plonky3
+// Elements fit in 31 bits, and ffts run over domains of up to 2^27 elements.
+// ark's `MontConfig` derive expands to impls inside a function
+#![allow(non_local_definitions)]
+use ark_ff::fields::{Fp64, MontBackend, MontConfig};
+
+#[derive(MontConfig)]
+#[modulus = "2013265921"]
+#[generator = "31"]
+pub struct BabyBearConfig;
+pub type BabyBear = Fp64<MontBackend<BabyBearConfig, 1>>;
+
+#[cfg(test)]
+mod tests {
+    use super::BabyBear;
+    use crate::error::AlgorithmsError;
+    use crate::utils::{
+        get_omega_domain,
+        lagrange::{
+            compute_lagrange_interpolation, compute_lagrange_interpolation_on_roots_of_unity,
+        },
+    };
+    use ark_ff::{FftField, Field, One, UniformRand};
+    use ark_poly::Polynomial;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_babybear() {
+        let mut rng = test_rng();
+        assert_eq!(BabyBear::TWO_ADICITY, 27);
+        assert_eq!(-BabyBear::one(), BabyBear::from(15u64 << 27));
+        let x = BabyBear::rand(&mut rng);
+        assert_eq!(x * x.inverse().unwrap(), BabyBear::one());
+
+        let evals = (0..8).map(|_| BabyBear::rand(&mut rng)).collect::<Vec<_>>();
+        let (_, elements) = get_omega_domain::<BabyBear>(8).unwrap();
+        let p = compute_lagrange_interpolation_on_roots_of_unity(&evals).unwrap();
+        for (omega_i, y_i) in elements.iter().zip(&evals) {
+            assert_eq!(p.evaluate(omega_i), *y_i);
+        }
+        let p = compute_lagrange_interpolation(&evals);
+        for (i, y_i) in evals.iter().enumerate() {
+            assert_eq!(p.evaluate(&BabyBear::from(i as u64)), *y_i);
+        }
+
+        // no subgroup of order 2^28
+        let n = 1 << 28;
+        assert_eq!(
+            get_omega_domain::<BabyBear>(n).unwrap_err(),
+            AlgorithmsError::DomainNotFound(n)
+        );
+        assert_eq!(
+            compute_lagrange_interpolation_on_roots_of_unity(&vec![BabyBear::one(); n])
+                .unwrap_err(),
+            AlgorithmsError::DomainNotFound(n)
+        );
+    }
+}
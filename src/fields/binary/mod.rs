@@ -0,0 +1,360 @@
+// Binary tower fields, as used by Binius: https://eprint.iacr.org/2023/1784 (section 2.3)
+// T_0 = GF(2) and T_{k+1} = T_k[X_k] / (X_k^2 + X_{k-1} * X_k + 1), with X_{-1} = 1 (Wiedemann's tower).
+// An element lo + hi * X_k of T_{k+1} stores lo in the low half of its bits and hi in the high half, so that
+// T_k is exactly the set of elements below 2^(2^k) in T_7 = GF(2^128): small field values embed for free,
+// which is what makes committing to bits or bytes cheap in hash-based protocols.
+// Addition is xor; multiplication recurses down the tower with karatsuba, 3 products per level.
+// Elements implement `SumcheckField`, so that mles and the sumcheck protocol run over them. Inverses are
+// computed as a^(2^128 - 2): good enough for prototyping, not for performance.
+use ark_ff::{One, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::ip::sumcheck::SumcheckField;
+use crate::transcript::Transcript;
+
+/// Number of levels above GF(2): elements are 2^TOWER_HEIGHT = 128 bits
+const TOWER_HEIGHT: usize = 7;
+
+/// a * X_{k-1} in T_k, X_{k-1} being the generator of T_k over T_{k-1}
+/// (lo + hi * X) * X = hi + X * (lo + hi * X_{k-2}), since X^2 = X_{k-2} * X + 1
+fn mul_by_generator(a: u128, k: usize) -> u128 {
+    if k == 0 {
+        return a;
+    }
+    let half = 1 << (k - 1);
+    let mask = (1u128 << half) - 1;
+    let (lo, hi) = (a & mask, a >> half);
+    hi | ((lo ^ mul_by_generator(hi, k - 1)) << half)
+}
+
+/// a * b in T_k, both being below 2^(2^k)
+fn mul(a: u128, b: u128, k: usize) -> u128 {
+    if k == 0 {
+        return a & b;
+    }
+    let half = 1 << (k - 1);
+    let mask = (1u128 << half) - 1;
+    let (a_0, a_1) = (a & mask, a >> half);
+    let (b_0, b_1) = (b & mask, b >> half);
+    let z_0 = mul(a_0, b_0, k - 1);
+    let z_2 = mul(a_1, b_1, k - 1);
+    let z_1 = mul(a_0 ^ a_1, b_0 ^ b_1, k - 1) ^ z_0 ^ z_2;
+    // a_1 * b_1 * X^2 = a_1 * b_1 * (X_{k-2} * X + 1)
+    let lo = z_0 ^ z_2;
+    let hi = z_1 ^ mul_by_generator(z_2, k - 1);
+    lo | (hi << half)
+}
+
+/// An element of T_7 = GF(2^128)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BinaryField128b(u128);
+
+impl BinaryField128b {
+    pub fn new(value: u128) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+
+    /// The smallest level k such that the element lies in the subfield T_k
+    pub fn tower_level(&self) -> usize {
+        (0..TOWER_HEIGHT)
+            .find(|k| self.0 >> (1 << k) == 0)
+            .unwrap_or(TOWER_HEIGHT)
+    }
+
+    pub fn square(&self) -> Self {
+        *self * self
+    }
+
+    pub fn pow(&self, mut exponent: u128) -> Self {
+        let (mut result, mut base) = (Self::one(), *self);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base = base.square();
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// a^(2^128 - 2) = a^-1, the multiplicative group having order 2^128 - 1
+    pub fn inverse(&self) -> Option<Self> {
+        (!self.is_zero()).then(|| self.pow(u128::MAX - 1))
+    }
+}
+
+impl From<u128> for BinaryField128b {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+impl Zero for BinaryField128b {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for BinaryField128b {
+    fn one() -> Self {
+        Self(1)
+    }
+}
+
+impl Add for BinaryField128b {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl<'a> Add<&'a Self> for BinaryField128b {
+    type Output = Self;
+
+    fn add(self, rhs: &'a Self) -> Self {
+        self + *rhs
+    }
+}
+
+/// Characteristic 2: subtraction is addition
+impl Sub for BinaryField128b {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl<'a> Sub<&'a Self> for BinaryField128b {
+    type Output = Self;
+
+    fn sub(self, rhs: &'a Self) -> Self {
+        self - *rhs
+    }
+}
+
+impl Neg for BinaryField128b {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self
+    }
+}
+
+impl Mul for BinaryField128b {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mul(self.0, rhs.0, TOWER_HEIGHT))
+    }
+}
+
+impl<'a> Mul<&'a Self> for BinaryField128b {
+    type Output = Self;
+
+    fn mul(self, rhs: &'a Self) -> Self {
+        self * *rhs
+    }
+}
+
+impl AddAssign for BinaryField128b {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for BinaryField128b {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for BinaryField128b {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Sum for BinaryField128b {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for BinaryField128b {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+/// 16 little-endian bytes, compressed or not: ark_serialize has no u128 impl
+impl CanonicalSerialize for BinaryField128b {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        _compress: Compress,
+    ) -> Result<(), SerializationError> {
+        Ok(writer.write_all(&self.0.to_le_bytes())?)
+    }
+
+    fn serialized_size(&self, _compress: Compress) -> usize {
+        16
+    }
+}
+
+/// Every 128 bits string is an element
+impl Valid for BinaryField128b {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for BinaryField128b {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        _compress: Compress,
+        _validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let mut bytes = [0u8; 16];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self(u128::from_le_bytes(bytes)))
+    }
+}
+
+impl Distribution<BinaryField128b> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BinaryField128b {
+        BinaryField128b(rng.gen())
+    }
+}
+
+impl SumcheckField for BinaryField128b {
+    /// The element with the bits of i: distinct for distinct i, 0 and 1 being zero and one
+    fn from_index(i: u64) -> Self {
+        Self(i as u128)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    fn challenge(transcript: &mut Transcript) -> Self {
+        let bytes = transcript.challenge_bytes();
+        Self(u128::from_le_bytes(bytes[..16].try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryField128b;
+    use crate::ip::sumcheck::{
+        evaluate_mle,
+        protocol::{prove, verify, VirtualPolynomial},
+        Mle,
+    };
+    use crate::transcript::Transcript;
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{rand::Rng, test_rng};
+
+    #[test]
+    pub fn test_binary_tower_field() {
+        let mut rng = test_rng();
+        let (a, b, c) = (
+            BinaryField128b::rand(&mut rng),
+            BinaryField128b::rand(&mut rng),
+            BinaryField128b::rand(&mut rng),
+        );
+        assert_eq!(a * b, b * a);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a + a, BinaryField128b::zero());
+        assert_eq!(a * a.inverse().unwrap(), BinaryField128b::one());
+        assert_eq!(BinaryField128b::zero().inverse(), None);
+
+        // T_1 = GF(4): X_0^2 = X_0 + 1
+        let x_0 = BinaryField128b::new(2);
+        assert_eq!(x_0 * x_0, x_0 + BinaryField128b::one());
+        // subfields are closed under the field operations
+        for k in 0..7 {
+            let mask = (1u128 << (1 << k)) - 1;
+            let (a, b) = (
+                BinaryField128b::new(a.value() & mask),
+                BinaryField128b::new(b.value() & mask | 1),
+            );
+            assert!((a * b).tower_level() <= k);
+            assert!(b.inverse().unwrap().tower_level() <= k);
+        }
+        let mut bytes = vec![];
+        a.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(
+            BinaryField128b::deserialize_compressed(&bytes[..]).unwrap(),
+            a
+        );
+
+        assert_eq!(BinaryField128b::new(0xff).tower_level(), 3);
+        assert_eq!(BinaryField128b::new(0x100).tower_level(), 4);
+    }
+
+    #[test]
+    pub fn test_sumcheck_over_binary_field() {
+        let mut rng = test_rng();
+        let num_vars = 4;
+        // g = f_0 * f_1 + f_2, f_0 holding bits: small field values in the large field
+        let mut g = VirtualPolynomial::new(num_vars);
+        g.add_mle(Mle::new(
+            (0..1 << num_vars)
+                .map(|_| BinaryField128b::new(rng.gen_range(0..2)))
+                .collect(),
+        ));
+        for _ in 0..2 {
+            g.add_mle(Mle::new(
+                (0..1 << num_vars)
+                    .map(|_| BinaryField128b::rand(&mut rng))
+                    .collect(),
+            ));
+        }
+        g.add_product(BinaryField128b::one(), &[0, 1]);
+        g.add_product(BinaryField128b::one(), &[2]);
+        let sum = g.sum_over_hypercube();
+
+        let (proof, point) = prove(&g, &mut Transcript::new(b"binary"));
+        let subclaim = verify(num_vars, 2, sum, &proof, &mut Transcript::new(b"binary")).unwrap();
+        assert_eq!(subclaim.point, point);
+        assert_eq!(subclaim.expected_evaluation, g.evaluate(&point));
+        assert!(verify(
+            num_vars,
+            2,
+            sum + BinaryField128b::one(),
+            &proof,
+            &mut Transcript::new(b"binary")
+        )
+        .is_none());
+
+        // mles of bits evaluate to field elements at non boolean points
+        let bits = (0..1 << num_vars)
+            .map(|i| BinaryField128b::new(i & 1))
+            .collect::<Vec<_>>();
+        assert_eq!(evaluate_mle(&bits, &point), point[0]);
+    }
+}
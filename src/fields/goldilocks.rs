@@ -0,0 +1,128 @@
+// Goldilocks: p = 2^64 - 2^32 + 1, as in plonky2
+// Elements fit in a u64 and p - 1 = 2^32 * (2^32 - 1), so that ffts run over domains of up to 2^32 elements.
+// With 64 bits challenges only give ~64 bits of soundness: protocols draw them from the quadratic extension
+// F_p[X] / (X^2 - 7) instead, see `ip::sumcheck::protocol::prove_small_field`.
+// ark's `MontConfig` derive expands to impls inside a function
+#![allow(non_local_definitions)]
+use ark_ff::{
+    fields::{Fp2, Fp2Config, Fp64, MontBackend, MontConfig},
+    Field, MontFp,
+};
+
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+pub type Goldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+pub struct GoldilocksExt2Config;
+pub type GoldilocksExt2 = Fp2<GoldilocksExt2Config>;
+
+impl Fp2Config for GoldilocksExt2Config {
+    type Fp = Goldilocks;
+
+    /// 7 is not a square mod p
+    const NONRESIDUE: Goldilocks = MontFp!("7");
+
+    /// NONRESIDUE^((p^i - 1) / 2) for i = 0, 1
+    const FROBENIUS_COEFF_FP2_C1: &'static [Goldilocks] = &[Goldilocks::ONE, MontFp!("-1")];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Goldilocks, GoldilocksExt2};
+    use crate::ip::sumcheck::{
+        protocol::{prove, prove_small_field, verify, VirtualPolynomial},
+        ExtensionField, Mle,
+    };
+    use crate::transcript::Transcript;
+    use crate::utils::linear_algebra::{Matrix, Vector};
+    use crate::utils::{
+        get_omega_domain, lagrange::compute_lagrange_interpolation_on_roots_of_unity,
+    };
+    use ark_ff::{FftField, Field, One, PrimeField, UniformRand};
+    use ark_poly::{EvaluationDomain, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_goldilocks() {
+        let mut rng = test_rng();
+        assert_eq!(Goldilocks::MODULUS.0[0], 0xffff_ffff_0000_0001);
+        assert_eq!(Goldilocks::TWO_ADICITY, 32);
+        assert_eq!(
+            -Goldilocks::one(),
+            Goldilocks::from(u64::MAX - (1 << 32) + 1)
+        );
+        let x = Goldilocks::rand(&mut rng);
+        assert_eq!(x * x.inverse().unwrap(), Goldilocks::one());
+        // 7 has no square root, the extension is a field
+        assert!(Goldilocks::from(7u64).sqrt().is_none());
+        let y = GoldilocksExt2::rand(&mut rng);
+        assert_eq!(y * y.inverse().unwrap(), GoldilocksExt2::one());
+
+        // ffts and interpolation over the subgroups
+        let (domain, elements) = get_omega_domain::<Goldilocks>(16).unwrap();
+        let evals = (0..16)
+            .map(|_| Goldilocks::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let p = compute_lagrange_interpolation_on_roots_of_unity(&evals).unwrap();
+        for (omega_i, y_i) in elements.iter().zip(&evals) {
+            assert_eq!(p.evaluate(omega_i), *y_i);
+        }
+        assert!(get_omega_domain::<Goldilocks>(1 << 33).is_err());
+        assert_eq!(domain.size(), 16);
+
+        // linear algebra
+        let m = Matrix::from(vec![
+            vec![Goldilocks::from(2u64), Goldilocks::from(1u64)],
+            vec![Goldilocks::from(1u64), Goldilocks::from(3u64)],
+        ]);
+        let v = Vector::from(vec![Goldilocks::from(5u64), -Goldilocks::one()]);
+        assert_eq!(
+            m.dot_vector(&v).unwrap().elements(),
+            &[Goldilocks::from(9u64), Goldilocks::from(2u64)]
+        );
+    }
+
+    #[test]
+    pub fn test_goldilocks_sumcheck() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let mut g = VirtualPolynomial::new(num_vars);
+        for _ in 0..2 {
+            g.add_mle(Mle::new(
+                (0..1 << num_vars)
+                    .map(|_| Goldilocks::rand(&mut rng))
+                    .collect(),
+            ));
+        }
+        g.add_product(Goldilocks::one(), &[0, 1]);
+        let sum = g.sum_over_hypercube();
+
+        let (proof, point) = prove(&g, &mut Transcript::new(b"goldilocks"));
+        let subclaim = verify(
+            num_vars,
+            2,
+            sum,
+            &proof,
+            &mut Transcript::new(b"goldilocks"),
+        )
+        .unwrap();
+        assert_eq!(subclaim.expected_evaluation, g.evaluate(&point));
+
+        // challenges from the extension
+        let (proof, point) = prove_small_field::<Goldilocks, GoldilocksExt2>(
+            &g,
+            &mut Transcript::new(b"goldilocks"),
+        );
+        let subclaim = verify(
+            num_vars,
+            2,
+            GoldilocksExt2::lift(sum),
+            &proof,
+            &mut Transcript::new(b"goldilocks"),
+        )
+        .unwrap();
+        assert_eq!(subclaim.expected_evaluation, g.evaluate_extension(&point));
+    }
+}
@@ -0,0 +1,7 @@
+// Fields defined by the crate itself, for protocols over fields that neither ark nor its curves provide
+// Goldilocks and BabyBear are the small prime fields of hash-based proof systems, binary tower fields those
+// of Binius.
+pub mod babybear;
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod goldilocks;
@@ -0,0 +1,251 @@
+// In-circuit verification of the NIFS fold, the building block of a Nova augmented circuit
+// The fold has a field part, u = u_1 + r * u_2 and x = x_1 + r * x_2, over the scalar field of the curve C
+// the commitments live on, and a group part, comm_E = comm_E1 + r * comm_T + r^2 * comm_E2 and
+// comm_W = comm_W1 + r * comm_W2, whose points have coordinates in the base field of C.
+// Over a cycle of curves (e.g. pallas/vesta), the group part is checked in a circuit over the base field of C,
+// which is the scalar field of the other curve, with r as a non-native field element.
+// The challenge r is an input of the gadgets: the augmented circuit should derive it by hashing the instances.
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ec::CurveConfig;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, nonnative::NonNativeFieldVar, FieldVar},
+    groups::{curves::short_weierstrass::ProjectiveVar, CurveVar},
+    ToBitsGadget,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+
+use super::nifs::CommittedRelaxedInstance;
+
+/// A point of the curve with config `P`, in a circuit over its base field
+pub type PointVar<P> = ProjectiveVar<P, FpVar<<P as CurveConfig>::BaseField>>;
+
+/// The field elements (u, x) of a committed relaxed instance, in a circuit over its scalar field
+#[derive(Clone, Debug)]
+pub struct RelaxedInstanceFieldsVar<F: PrimeField> {
+    pub u: FpVar<F>,
+    pub x: Vec<FpVar<F>>,
+}
+
+/// The commitments (comm_E, comm_W) of a committed relaxed instance, in a circuit over the base field
+#[derive(Clone, Debug)]
+pub struct RelaxedInstanceCommitmentsVar<P: SWCurveConfig>
+where
+    P::BaseField: PrimeField,
+{
+    pub comm_e: PointVar<P>,
+    pub comm_w: PointVar<P>,
+}
+
+impl<C: ark_ec::CurveGroup> AllocVar<CommittedRelaxedInstance<C>, C::ScalarField>
+    for RelaxedInstanceFieldsVar<C::ScalarField>
+{
+    fn new_variable<T: Borrow<CommittedRelaxedInstance<C>>>(
+        cs: impl Into<Namespace<C::ScalarField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let binding = f()?;
+        let instance = binding.borrow();
+        Ok(Self {
+            u: FpVar::new_variable(cs.clone(), || Ok(instance.u), mode)?,
+            x: Vec::new_variable(cs, || Ok(instance.x.clone()), mode)?,
+        })
+    }
+}
+
+impl<P: SWCurveConfig> AllocVar<CommittedRelaxedInstance<Projective<P>>, P::BaseField>
+    for RelaxedInstanceCommitmentsVar<P>
+where
+    P::BaseField: PrimeField,
+{
+    fn new_variable<T: Borrow<CommittedRelaxedInstance<Projective<P>>>>(
+        cs: impl Into<Namespace<P::BaseField>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let binding = f()?;
+        let instance = binding.borrow();
+        Ok(Self {
+            comm_e: PointVar::new_variable(cs.clone(), || Ok(instance.comm_e), mode)?,
+            comm_w: PointVar::new_variable(cs, || Ok(instance.comm_w), mode)?,
+        })
+    }
+}
+
+impl<F: PrimeField> EqGadget<F> for RelaxedInstanceFieldsVar<F> {
+    fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
+        if self.x.len() != other.x.len() {
+            return Ok(Boolean::FALSE);
+        }
+        let mut equalities = vec![self.u.is_eq(&other.u)?];
+        for (a, b) in self.x.iter().zip(&other.x) {
+            equalities.push(a.is_eq(b)?);
+        }
+        Boolean::kary_and(&equalities)
+    }
+}
+
+impl<P: SWCurveConfig> EqGadget<P::BaseField> for RelaxedInstanceCommitmentsVar<P>
+where
+    P::BaseField: PrimeField,
+{
+    fn is_eq(&self, other: &Self) -> Result<Boolean<P::BaseField>, SynthesisError> {
+        self.comm_e
+            .is_eq(&other.comm_e)?
+            .and(&self.comm_w.is_eq(&other.comm_w)?)
+    }
+}
+
+pub struct NIFSGadget;
+
+impl NIFSGadget {
+    /// u = u_1 + r * u_2, x = x_1 + r * x_2
+    pub fn fold_fields<F: PrimeField>(
+        instance_1: &RelaxedInstanceFieldsVar<F>,
+        instance_2: &RelaxedInstanceFieldsVar<F>,
+        r: &FpVar<F>,
+    ) -> Result<RelaxedInstanceFieldsVar<F>, SynthesisError> {
+        if instance_1.x.len() != instance_2.x.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        Ok(RelaxedInstanceFieldsVar {
+            u: &instance_1.u + r * &instance_2.u,
+            x: instance_1
+                .x
+                .iter()
+                .zip(&instance_2.x)
+                .map(|(x_1, x_2)| x_1 + r * x_2)
+                .collect(),
+        })
+    }
+
+    /// comm_E = comm_E1 + r * comm_T + r^2 * comm_E2, comm_W = comm_W1 + r * comm_W2
+    pub fn fold_commitments<P: SWCurveConfig>(
+        instance_1: &RelaxedInstanceCommitmentsVar<P>,
+        instance_2: &RelaxedInstanceCommitmentsVar<P>,
+        comm_t: &PointVar<P>,
+        r: &NonNativeFieldVar<P::ScalarField, P::BaseField>,
+    ) -> Result<RelaxedInstanceCommitmentsVar<P>, SynthesisError>
+    where
+        P::BaseField: PrimeField,
+    {
+        let r_bits = r.to_bits_le()?;
+        let r_square_bits = r.square()?.to_bits_le()?;
+        Ok(RelaxedInstanceCommitmentsVar {
+            comm_e: instance_1.comm_e.clone()
+                + Self::scalar_mul(comm_t, &r_bits)?
+                + Self::scalar_mul(&instance_2.comm_e, &r_square_bits)?,
+            comm_w: instance_1.comm_w.clone() + Self::scalar_mul(&instance_2.comm_w, &r_bits)?,
+        })
+    }
+
+    /// `scalar_mul_le` doesn't support the point at infinity (comm_E of a fresh instance),
+    /// which is swapped for the generator before multiplying
+    fn scalar_mul<P: SWCurveConfig>(
+        point: &PointVar<P>,
+        bits: &[Boolean<P::BaseField>],
+    ) -> Result<PointVar<P>, SynthesisError>
+    where
+        P::BaseField: PrimeField,
+    {
+        let is_zero = point.is_zero()?;
+        let generator = PointVar::constant(P::GENERATOR.into());
+        let product = is_zero
+            .select(&generator, point)?
+            .scalar_mul_le(bits.iter())?;
+        is_zero.select(&PointVar::zero(), &product)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NIFSGadget, PointVar, RelaxedInstanceCommitmentsVar, RelaxedInstanceFieldsVar};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::folding::nifs::NIFS;
+    use crate::transcript::Transcript;
+    use ark_ff::One;
+    use ark_pallas::{Fq, Fr, Projective};
+    use ark_r1cs_std::{
+        alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, fields::nonnative::NonNativeFieldVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_nifs_gadgets() {
+        let mut rng = test_rng();
+        let circuit_1 = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(25));
+        let circuit_2 = TestPythagoreCircuit::new(Fr::from(5), Fr::from(12), Fr::from(169));
+        let r1cs = get_r1cs_from_cs(circuit_1.clone()).unwrap();
+        let nifs = NIFS::<Projective>::new(&r1cs);
+        let (u_1, w_1) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_1).unwrap(), &mut rng);
+        let (u_2, w_2) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_2).unwrap(), &mut rng);
+        let (u_3, w_3, _) = nifs
+            .prove(
+                &r1cs,
+                &u_1,
+                &w_1,
+                &u_2,
+                &w_2,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+        // fold a running instance, with comm_E != 0, and a fresh one
+        let (u_4, _, comm_t) = nifs
+            .prove(
+                &r1cs,
+                &u_3,
+                &w_3,
+                &u_1,
+                &w_1,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+        let r = NIFS::challenge(&u_3, &u_1, &comm_t, &mut Transcript::new(b"nifs"));
+
+        // field part, over the scalar field of pallas
+        for (r, satisfied) in [(r, true), (r + Fr::one(), false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let var_3 = RelaxedInstanceFieldsVar::new_witness(cs.clone(), || Ok(&u_3)).unwrap();
+            let var_1 = RelaxedInstanceFieldsVar::new_witness(cs.clone(), || Ok(&u_1)).unwrap();
+            let var_4 = RelaxedInstanceFieldsVar::new_input(cs.clone(), || Ok(&u_4)).unwrap();
+            let r_var = FpVar::new_witness(cs.clone(), || Ok(r)).unwrap();
+            NIFSGadget::fold_fields(&var_3, &var_1, &r_var)
+                .unwrap()
+                .enforce_equal(&var_4)
+                .unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+
+        // group part, over the base field of pallas
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let var_3 = RelaxedInstanceCommitmentsVar::new_witness(cs.clone(), || Ok(&u_3)).unwrap();
+        let var_1 = RelaxedInstanceCommitmentsVar::new_witness(cs.clone(), || Ok(&u_1)).unwrap();
+        let var_4 = RelaxedInstanceCommitmentsVar::new_input(cs.clone(), || Ok(&u_4)).unwrap();
+        let comm_t_var = PointVar::new_witness(cs.clone(), || Ok(comm_t)).unwrap();
+        let r_var = NonNativeFieldVar::new_witness(cs.clone(), || Ok(r)).unwrap();
+        let folded = NIFSGadget::fold_commitments(&var_3, &var_1, &comm_t_var, &r_var).unwrap();
+        folded.enforce_equal(&var_4).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // the fold of u_3 with u_2 is not u_4
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let var_3 = RelaxedInstanceCommitmentsVar::new_witness(cs.clone(), || Ok(&u_3)).unwrap();
+        let var_2 = RelaxedInstanceCommitmentsVar::new_witness(cs.clone(), || Ok(&u_2)).unwrap();
+        let var_4 = RelaxedInstanceCommitmentsVar::new_input(cs.clone(), || Ok(&u_4)).unwrap();
+        let comm_t_var = PointVar::new_witness(cs.clone(), || Ok(comm_t)).unwrap();
+        let r_var = NonNativeFieldVar::new_witness(cs.clone(), || Ok(r)).unwrap();
+        let folded = NIFSGadget::fold_commitments(&var_3, &var_2, &comm_t_var, &r_var).unwrap();
+        folded.enforce_equal(&var_4).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,349 @@
+// HyperNova multifolding of CCS instances: https://eprint.iacr.org/2023/573 (section 5)
+// With s = log2(m) (constraints padded to a power of two) and z = (u, x, w):
+// - a CCCS instance x holds when sum_i c_i * (o_{j in S_i} M_j z) = 0 with u = 1, i.e. on every point of {0, 1}^s,
+// - an LCCCS instance (u, x, r, v_1, .., v_t) holds when v_j = MLE(M_j z)(r) for each j.
+// Folding an LCCCS (z_1) and a CCCS (z_2): with gamma, beta from the transcript, sumcheck
+// g(X) = sum_j gamma^j * eq(r, X) * MLE(M_j z_1)(X) + gamma^{t+1} * eq(beta, X) * sum_i c_i * o_{j in S_i} MLE(M_j z_2)(X)
+// whose sum over the hypercube is sum_j gamma^j * v_j. At the sumcheck point r', the prover sends
+// sigma_j = MLE(M_j z_1)(r') and theta_j = MLE(M_j z_2)(r'), which determine g(r').
+// With rho from the transcript, the folded LCCCS is (u + rho, x_1 + rho * x_2, r', sigma + rho * theta), w = w_1 + rho * w_2.
+// This is the field level of the scheme: the witness commitments, folded with the same rho, are left out.
+use ark_ff::PrimeField;
+
+use super::nifs::fold_vectors;
+use crate::circuits::ccs::CCS;
+use crate::error::AlgorithmsError;
+use crate::ip::sumcheck::{
+    compute_chi_w, eq_evaluations,
+    protocol::{self, SumcheckProof, VirtualPolynomial},
+    Mle,
+};
+use crate::transcript::Transcript;
+use crate::utils::linear_algebra::Vector;
+
+/// A committed CCS instance, without its commitment
+#[derive(Clone, Debug, PartialEq)]
+pub struct CCCS<F: PrimeField> {
+    pub x: Vec<F>,
+}
+
+/// A linearized committed CCS instance, without its commitment
+#[derive(Clone, Debug, PartialEq)]
+pub struct LCCCS<F: PrimeField> {
+    pub u: F,
+    pub x: Vec<F>,
+    pub r_x: Vec<F>,
+    pub v: Vec<F>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultifoldingProof<F: PrimeField> {
+    pub sumcheck_proof: SumcheckProof<F>,
+    pub sigmas: Vec<F>,
+    pub thetas: Vec<F>,
+}
+
+/// The folded lcccs and witness, with the proof
+pub type MultifoldingFold<F> = (LCCCS<F>, Vec<F>, MultifoldingProof<F>);
+
+pub struct HyperNova<F: PrimeField> {
+    ccs: CCS<F>,
+    /// Number of variables of the MLEs over the constraints
+    s: usize,
+}
+
+impl<F: PrimeField> LCCCS<F> {
+    fn absorb_in(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.u);
+        transcript.absorb(&self.x);
+        transcript.absorb(&self.r_x);
+        transcript.absorb(&self.v);
+    }
+}
+
+impl<F: PrimeField> HyperNova<F> {
+    pub fn new(ccs: CCS<F>) -> Self {
+        let s = ccs.m().next_power_of_two().trailing_zeros() as usize;
+        Self { ccs, s }
+    }
+
+    pub fn ccs(&self) -> &CCS<F> {
+        &self.ccs
+    }
+
+    /// Splits z = (1, x, w) in a CCCS instance and its witness
+    pub fn cccs_from_z(&self, z: &Vector<F>) -> (CCCS<F>, Vec<F>) {
+        let (io, w) = z.elements().split_at(self.ccs.l() + 1);
+        (
+            CCCS {
+                x: io[1..].to_vec(),
+            },
+            w.to_vec(),
+        )
+    }
+
+    /// (MLE(M_j z))_j, z = (u, x, w), each M_j z padded to 2^s, an error if z doesn't fit the ccs
+    fn matrix_mles(&self, u: F, x: &[F], w: &[F]) -> Result<Vec<Mle<F>>, AlgorithmsError> {
+        let z = Vector::new(&[vec![u], x.to_vec(), w.to_vec()].concat());
+        self.ccs
+            .matrices()
+            .iter()
+            .map(|matrix| {
+                let mut evaluations = matrix.dot_vector(&z)?.elements().to_vec();
+                evaluations.resize(1 << self.s, F::zero());
+                Ok(Mle::new(evaluations))
+            })
+            .collect()
+    }
+
+    /// Linearizes a CCCS instance at a point from the transcript, the starting running instance
+    pub fn linearize(
+        &self,
+        cccs: &CCCS<F>,
+        w: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<LCCCS<F>, AlgorithmsError> {
+        transcript.absorb(&cccs.x);
+        let r_x: Vec<F> = transcript.challenges(self.s);
+        let v = self
+            .matrix_mles(F::one(), &cccs.x, w)?
+            .iter()
+            .map(|mle| mle.evaluate(&r_x))
+            .collect();
+        Ok(LCCCS {
+            u: F::one(),
+            x: cccs.x.clone(),
+            r_x,
+            v,
+        })
+    }
+
+    /// Folds (lcccs, w_1) with (cccs, w_2), returns the folded lcccs, its witness and the proof,
+    /// or an error if a witness doesn't fit the ccs
+    pub fn prove(
+        &self,
+        lcccs: &LCCCS<F>,
+        w_1: &[F],
+        cccs: &CCCS<F>,
+        w_2: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<MultifoldingFold<F>, AlgorithmsError> {
+        lcccs.absorb_in(transcript);
+        transcript.absorb(&cccs.x);
+        let gamma: F = transcript.challenge();
+        let beta: Vec<F> = transcript.challenges(self.s);
+
+        let mles_1 = self.matrix_mles(lcccs.u, &lcccs.x, w_1)?;
+        let mles_2 = self.matrix_mles(F::one(), &cccs.x, w_2)?;
+        let mut g = VirtualPolynomial::new(self.s);
+        let eq_r = g.add_mle(Mle::new(eq_evaluations(&lcccs.r_x)));
+        let eq_beta = g.add_mle(Mle::new(eq_evaluations(&beta)));
+        let indices_1 = mles_1
+            .iter()
+            .map(|mle| g.add_mle(mle.clone()))
+            .collect::<Vec<_>>();
+        let indices_2 = mles_2
+            .iter()
+            .map(|mle| g.add_mle(mle.clone()))
+            .collect::<Vec<_>>();
+        let mut gamma_j = gamma;
+        for index in &indices_1 {
+            g.add_product(gamma_j, &[eq_r, *index]);
+            gamma_j *= gamma;
+        }
+        for (multiset, c) in self.ccs.multisets().iter().zip(self.ccs.constants()) {
+            let mut product = vec![eq_beta];
+            product.extend(multiset.iter().map(|j| indices_2[*j]));
+            g.add_product(gamma_j * c, &product);
+        }
+        let (sumcheck_proof, r_x) = protocol::prove(&g, transcript);
+
+        let sigmas = mles_1.iter().map(|mle| mle.evaluate(&r_x)).collect();
+        let thetas = mles_2.iter().map(|mle| mle.evaluate(&r_x)).collect();
+        let proof = MultifoldingProof {
+            sumcheck_proof,
+            sigmas,
+            thetas,
+        };
+        let rho = Self::rho(&proof, transcript);
+        let folded = Self::fold(lcccs, cccs, &proof, r_x, rho);
+        Ok((folded, fold_vectors(w_1, w_2, rho), proof))
+    }
+
+    /// Returns the folded lcccs, or None if the sumcheck or the final evaluation check fails
+    pub fn verify(
+        &self,
+        lcccs: &LCCCS<F>,
+        cccs: &CCCS<F>,
+        proof: &MultifoldingProof<F>,
+        transcript: &mut Transcript,
+    ) -> Option<LCCCS<F>> {
+        let t = self.ccs.t();
+        if lcccs.v.len() != t
+            || lcccs.r_x.len() != self.s
+            || proof.sigmas.len() != t
+            || proof.thetas.len() != t
+            || lcccs.x.len() != cccs.x.len()
+        {
+            return None;
+        }
+        lcccs.absorb_in(transcript);
+        transcript.absorb(&cccs.x);
+        let gamma: F = transcript.challenge();
+        let beta: Vec<F> = transcript.challenges(self.s);
+
+        let mut claimed_sum = F::zero();
+        let mut gamma_j = gamma;
+        for v_j in &lcccs.v {
+            claimed_sum += gamma_j * v_j;
+            gamma_j *= gamma;
+        }
+        let subclaim = protocol::verify(
+            self.s,
+            self.ccs.d() + 1,
+            claimed_sum,
+            &proof.sumcheck_proof,
+            transcript,
+        )?;
+
+        // g(r') from sigma and theta
+        let r_x = subclaim.point;
+        let eq_r = compute_chi_w(&lcccs.r_x, &r_x);
+        let mut expected = F::zero();
+        let mut gamma_j = gamma;
+        for sigma in &proof.sigmas {
+            expected += gamma_j * eq_r * sigma;
+            gamma_j *= gamma;
+        }
+        let q: F = self
+            .ccs
+            .multisets()
+            .iter()
+            .zip(self.ccs.constants())
+            .map(|(multiset, c)| *c * multiset.iter().map(|j| proof.thetas[*j]).product::<F>())
+            .sum();
+        expected += gamma_j * compute_chi_w(&beta, &r_x) * q;
+        if expected != subclaim.expected_evaluation {
+            return None;
+        }
+        let rho = Self::rho(proof, transcript);
+        Some(Self::fold(lcccs, cccs, proof, r_x, rho))
+    }
+
+    fn rho(proof: &MultifoldingProof<F>, transcript: &mut Transcript) -> F {
+        transcript.absorb(&proof.sigmas);
+        transcript.absorb(&proof.thetas);
+        transcript.challenge()
+    }
+
+    fn fold(
+        lcccs: &LCCCS<F>,
+        cccs: &CCCS<F>,
+        proof: &MultifoldingProof<F>,
+        r_x: Vec<F>,
+        rho: F,
+    ) -> LCCCS<F> {
+        LCCCS {
+            u: lcccs.u + rho,
+            x: fold_vectors(&lcccs.x, &cccs.x, rho),
+            r_x,
+            v: fold_vectors(&proof.sigmas, &proof.thetas, rho),
+        }
+    }
+
+    /// Checks v_j = MLE(M_j z)(r) for each j
+    pub fn is_satisfied_lcccs(&self, lcccs: &LCCCS<F>, w: &[F]) -> bool {
+        lcccs.x.len() == self.ccs.l()
+            && lcccs.x.len() + w.len() + 1 == self.ccs.n()
+            && lcccs.r_x.len() == self.s
+            && self.matrix_mles(lcccs.u, &lcccs.x, w).is_ok_and(|mles| {
+                mles.iter()
+                    .zip(&lcccs.v)
+                    .all(|(mle, v_j)| mle.evaluate(&lcccs.r_x) == *v_j)
+            })
+    }
+
+    pub fn is_satisfied_cccs(&self, cccs: &CCCS<F>, w: &[F]) -> bool {
+        self.ccs.is_satisfied(&Vector::new(
+            &[vec![F::one()], cccs.x.clone(), w.to_vec()].concat(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HyperNova;
+    use crate::circuits::ccs::CCS;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::transcript::Transcript;
+    use ark_ff::One;
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_hypernova_multifolding() {
+        let circuit = |a: u64, b: u64| {
+            let (a, b) = (Fr::from(a), Fr::from(b));
+            TestPythagoreCircuit::new(a, b, a * a + b * b)
+        };
+        let r1cs = get_r1cs_from_cs(circuit(3, 4)).unwrap();
+        let hypernova = HyperNova::new(CCS::from(r1cs));
+        let (cccs, w) = hypernova.cccs_from_z(&get_z_from_cs(circuit(3, 4)).unwrap());
+        assert!(hypernova.is_satisfied_cccs(&cccs, &w));
+        let mut lcccs = hypernova
+            .linearize(&cccs, &w, &mut Transcript::new(b"hypernova"))
+            .unwrap();
+        let mut w_running = w;
+        assert!(hypernova.is_satisfied_lcccs(&lcccs, &w_running));
+
+        for (a, b) in [(5, 12), (8, 15), (7, 24)] {
+            let (cccs, w) = hypernova.cccs_from_z(&get_z_from_cs(circuit(a, b)).unwrap());
+            let (folded, w_folded, proof) = hypernova
+                .prove(
+                    &lcccs,
+                    &w_running,
+                    &cccs,
+                    &w,
+                    &mut Transcript::new(b"hypernova"),
+                )
+                .unwrap();
+            let verified = hypernova
+                .verify(&lcccs, &cccs, &proof, &mut Transcript::new(b"hypernova"))
+                .unwrap();
+            assert_eq!(verified, folded);
+            assert!(hypernova.is_satisfied_lcccs(&folded, &w_folded));
+            (lcccs, w_running) = (folded, w_folded);
+        }
+
+        let (cccs, mut w) = hypernova.cccs_from_z(&get_z_from_cs(circuit(9, 40)).unwrap());
+        let (_, _, mut proof) = hypernova
+            .prove(
+                &lcccs,
+                &w_running,
+                &cccs,
+                &w,
+                &mut Transcript::new(b"hypernova"),
+            )
+            .unwrap();
+        // wrong theta: g(r') doesn't match the sumcheck subclaim
+        proof.thetas[0] += Fr::one();
+        assert!(hypernova
+            .verify(&lcccs, &cccs, &proof, &mut Transcript::new(b"hypernova"))
+            .is_none());
+
+        // unsatisfied cccs: the sum of g isn't the one claimed by the lcccs
+        w[0] += Fr::one();
+        assert!(!hypernova.is_satisfied_cccs(&cccs, &w));
+        let (_, _, proof) = hypernova
+            .prove(
+                &lcccs,
+                &w_running,
+                &cccs,
+                &w,
+                &mut Transcript::new(b"hypernova"),
+            )
+            .unwrap();
+        assert!(hypernova
+            .verify(&lcccs, &cccs, &proof, &mut Transcript::new(b"hypernova"))
+            .is_none());
+    }
+}
@@ -0,0 +1,330 @@
+// Incrementally verifiable computation by folding: z_{i+1} = F(z_i), i = 0, 1, ..
+// Each step is synthesized as an r1cs with public io x = (z_i, z_{i+1}), turned into a committed instance and
+// folded with the NIFS. The running instance U_i accumulates steps 0..i-1, the last step instance u_i is kept
+// aside, and the decider checks that u_i outputs z_i and that the fold of U_i and u_i is satisfied.
+// There is no in-circuit verification of the previous fold here, so the step instances are not chained
+// together by the circuit: this checks the steps were computed, not how they link to each other.
+// Nor are the steps hidden: the blinding factors of the commitments come from an rng seeded with z_0.
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    One,
+};
+
+use super::nifs::{CommittedRelaxedInstance, RelaxedWitness, NIFS};
+use crate::circuits::r1cs::{
+    utils::{extract_r1cs, extract_z, generate_constraint_system},
+    R1CS,
+};
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+
+/// The function F iterated by the IVC, over a state of `state_len()` field elements
+pub trait StepCircuit<F: PrimeField>: Clone {
+    fn state_len(&self) -> usize;
+
+    /// Computes z_{i+1} = F(z_i) natively
+    fn output(&self, z_i: &[F]) -> Vec<F>;
+
+    /// Constrains z_{i+1} = F(z_i) and returns z_{i+1}
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        z_i: &[FpVar<F>],
+    ) -> Result<Vec<FpVar<F>>, SynthesisError>;
+}
+
+/// One step as a constraint synthesizer, with public io (z_i, z_{i+1})
+struct StepSynthesizer<F: PrimeField, SC: StepCircuit<F>> {
+    step_circuit: SC,
+    z_i: Vec<F>,
+}
+
+impl<F: PrimeField, SC: StepCircuit<F>> ConstraintSynthesizer<F> for StepSynthesizer<F, SC> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let z_i = self
+            .z_i
+            .iter()
+            .map(|z| FpVar::new_input(cs.clone(), || Ok(*z)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let z_i_plus_1 = self
+            .step_circuit
+            .generate_step_constraints(cs.clone(), &z_i)?;
+        for z in z_i_plus_1 {
+            FpVar::new_input(cs.clone(), || z.value())?.enforce_equal(&z)?;
+        }
+        Ok(())
+    }
+}
+
+type InstanceWitness<C> = (
+    CommittedRelaxedInstance<C>,
+    RelaxedWitness<<C as ark_ec::Group>::ScalarField>,
+);
+
+pub struct IVC<C: CurveGroup, SC: StepCircuit<C::ScalarField>> {
+    step_circuit: SC,
+    r1cs: R1CS<C::ScalarField>,
+    nifs: NIFS<C>,
+    i: usize,
+    z_0: Vec<C::ScalarField>,
+    z_i: Vec<C::ScalarField>,
+    /// U_i, folding the steps 0..i-1
+    running: Option<InstanceWitness<C>>,
+    /// u_i, the last step
+    last: Option<InstanceWitness<C>>,
+    rng: StdRng,
+}
+
+impl<C: CurveGroup, SC: StepCircuit<C::ScalarField>> IVC<C, SC> {
+    /// Extracts the step r1cs by synthesizing a first step from z_0
+    pub fn new(step_circuit: SC, z_0: Vec<C::ScalarField>) -> Result<Self, AlgorithmsError> {
+        if z_0.len() != step_circuit.state_len() {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: step_circuit.state_len(),
+                got: z_0.len(),
+            });
+        }
+        let cs = generate_constraint_system(StepSynthesizer {
+            step_circuit: step_circuit.clone(),
+            z_i: z_0.clone(),
+        })
+        .map_err(AlgorithmsError::Synthesis)?;
+        let r1cs = extract_r1cs(&cs);
+        let nifs = NIFS::new(&r1cs);
+        let mut transcript = Transcript::new(b"ivc rng");
+        transcript.absorb(&z_0);
+        let rng = StdRng::from_seed(transcript.challenge_bytes());
+        Ok(Self {
+            step_circuit,
+            r1cs,
+            nifs,
+            i: 0,
+            z_i: z_0.clone(),
+            z_0,
+            running: None,
+            last: None,
+            rng,
+        })
+    }
+
+    pub fn i(&self) -> usize {
+        self.i
+    }
+
+    pub fn z_0(&self) -> &[C::ScalarField] {
+        &self.z_0
+    }
+
+    pub fn z_i(&self) -> &[C::ScalarField] {
+        &self.z_i
+    }
+
+    pub fn r1cs(&self) -> &R1CS<C::ScalarField> {
+        &self.r1cs
+    }
+
+    /// Runs one step from z_i, folds u_i in U_i, keeps the new step as u_{i+1} and returns z_{i+1}
+    /// An error if z_i isn't the current state, or if the step circuit doesn't compute F(z_i)
+    pub fn prove_step(
+        &mut self,
+        z_i: &[C::ScalarField],
+    ) -> Result<Vec<C::ScalarField>, AlgorithmsError> {
+        if z_i != self.z_i {
+            return Err(AlgorithmsError::InvalidParameters(format!(
+                "z_i isn't the state after {} steps",
+                self.i
+            )));
+        }
+        let cs = generate_constraint_system(StepSynthesizer {
+            step_circuit: self.step_circuit.clone(),
+            z_i: z_i.to_vec(),
+        })
+        .map_err(AlgorithmsError::Synthesis)?;
+        if !cs
+            .is_satisfied()
+            .map_err(|e| AlgorithmsError::Synthesis(e.to_string()))?
+        {
+            return Err(AlgorithmsError::UnsatisfiedConstraints);
+        }
+        if cs.num_constraints != self.r1cs.n_constraints()
+            || cs.num_instance_variables != self.r1cs.n_instance()
+            || cs.num_witness_variables != self.r1cs.n_witness()
+        {
+            return Err(AlgorithmsError::Synthesis(
+                "the step circuit shape changed between steps".to_string(),
+            ));
+        }
+        let z = extract_z(&cs);
+        let step = self.nifs.commit_instance(&self.r1cs, &z, &mut self.rng);
+        if step.0.x[self.step_circuit.state_len()..] != self.step_circuit.output(z_i)[..] {
+            return Err(AlgorithmsError::Synthesis(
+                "the step constraints don't compute the step output".to_string(),
+            ));
+        }
+
+        if let Some(last) = self.last.take() {
+            self.running = Some(match self.running.take() {
+                None => last,
+                Some(running) => {
+                    let mut rng = self.rng.clone();
+                    let folded = self.fold(&running, &last, &mut rng)?;
+                    self.rng = rng;
+                    folded
+                }
+            });
+        }
+        self.z_i = step.0.x[self.step_circuit.state_len()..].to_vec();
+        self.last = Some(step);
+        self.i += 1;
+        Ok(self.z_i.clone())
+    }
+
+    fn fold(
+        &self,
+        running: &InstanceWitness<C>,
+        step: &InstanceWitness<C>,
+        rng: &mut StdRng,
+    ) -> Result<InstanceWitness<C>, AlgorithmsError> {
+        let mut transcript = Transcript::new(b"ivc");
+        transcript.absorb(&(self.i as u64));
+        let (instance, witness, _) = self.nifs.prove(
+            &self.r1cs,
+            &running.0,
+            &running.1,
+            &step.0,
+            &step.1,
+            &mut transcript,
+            rng,
+        )?;
+        Ok((instance, witness))
+    }
+
+    /// The decider: u_i is a fresh instance ending in z_i, and the fold of U_i and u_i is satisfied
+    pub fn decide(&self) -> bool {
+        let last = match &self.last {
+            None => return self.i == 0 && self.z_i == self.z_0,
+            Some(last) => last,
+        };
+        let state_len = self.step_circuit.state_len();
+        if last.0.u != C::ScalarField::one()
+            || !last.0.comm_e.is_zero()
+            || last.0.x[state_len..] != self.z_i[..]
+            || (self.i == 1 && last.0.x[..state_len] != self.z_0[..])
+        {
+            return false;
+        }
+        let (instance, witness) = match &self.running {
+            None => last.clone(),
+            Some(running) => match self.fold(running, last, &mut self.rng.clone()) {
+                Ok(folded) => folded,
+                Err(_) => return false,
+            },
+        };
+        self.nifs.is_satisfied(&self.r1cs, &instance, &witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StepCircuit, IVC};
+    use crate::error::AlgorithmsError;
+    use ark_ff::{Field, One};
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+    /// z_{i+1} = z_i^3 + z_i + 5
+    #[derive(Clone)]
+    struct CubicCircuit;
+
+    impl StepCircuit<Fr> for CubicCircuit {
+        fn state_len(&self) -> usize {
+            1
+        }
+
+        fn output(&self, z_i: &[Fr]) -> Vec<Fr> {
+            vec![z_i[0].pow([3]) + z_i[0] + Fr::from(5)]
+        }
+
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<Fr>,
+            z_i: &[FpVar<Fr>],
+        ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+            let z = &z_i[0];
+            Ok(vec![z * z * z + z + FpVar::Constant(Fr::from(5))])
+        }
+    }
+
+    /// Constrains z_{i+1} = z_i + 1 but claims z_{i+1} = z_i
+    #[derive(Clone)]
+    struct MismatchedCircuit;
+
+    impl StepCircuit<Fr> for MismatchedCircuit {
+        fn state_len(&self) -> usize {
+            1
+        }
+
+        fn output(&self, z_i: &[Fr]) -> Vec<Fr> {
+            z_i.to_vec()
+        }
+
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<Fr>,
+            z_i: &[FpVar<Fr>],
+        ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+            Ok(vec![&z_i[0] + Fr::one()])
+        }
+    }
+
+    #[test]
+    pub fn test_ivc() {
+        let mut ivc = IVC::<Projective, _>::new(CubicCircuit, vec![Fr::from(3)]).unwrap();
+        assert!(ivc.decide());
+
+        let mut z = vec![Fr::from(3)];
+        for _ in 0..5 {
+            let z_i_plus_1 = ivc.prove_step(&z).unwrap();
+            z = vec![z[0].pow([3]) + z[0] + Fr::from(5)];
+            assert_eq!(z_i_plus_1, z);
+            assert_eq!(ivc.z_i(), &z[..]);
+            assert!(ivc.decide());
+        }
+        assert_eq!(ivc.i(), 5);
+
+        // stepping from another state than z_i
+        assert_eq!(
+            ivc.prove_step(&[Fr::from(3)]),
+            Err(AlgorithmsError::InvalidParameters(
+                "z_i isn't the state after 5 steps".to_string()
+            ))
+        );
+        assert_eq!(ivc.i(), 5);
+
+        // a running witness that doesn't match its instance
+        let mut wrong = ivc;
+        wrong.running.as_mut().unwrap().1.w[0] += Fr::one();
+        assert!(!wrong.decide());
+        // a claimed z_i that the last step doesn't output
+        wrong.running.as_mut().unwrap().1.w[0] -= Fr::one();
+        assert!(wrong.decide());
+        wrong.z_i[0] += Fr::one();
+        assert!(!wrong.decide());
+
+        let mut mismatched =
+            IVC::<Projective, _>::new(MismatchedCircuit, vec![Fr::from(3)]).unwrap();
+        assert!(mismatched.prove_step(&[Fr::from(3)]).is_err());
+        assert_eq!(
+            IVC::<Projective, _>::new(CubicCircuit, vec![]).err(),
+            Some(AlgorithmsError::DimensionMismatch {
+                expected: 1,
+                got: 0
+            })
+        );
+    }
+}
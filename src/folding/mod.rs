@@ -1,3 +1,11 @@
+pub mod gadgets;
+pub mod hypernova;
+pub mod ivc;
+pub mod mova;
+pub mod nifs;
+pub mod protogalaxy;
+pub mod step_circuits;
+
 #[cfg(test)]
 mod tests {
     use crate::circuits::r1cs::utils::{get_test_r1cs, get_test_satisfying_witness};
@@ -19,7 +27,7 @@ mod tests {
         let w_1 = get_test_satisfying_witness::<Fr>(3);
         let w_2 = get_test_satisfying_witness::<Fr>(5);
         let (u_1, u_2) = (Fr::ONE, Fr::ONE);
-        let e_1 = Vector::<Fr>::new_zero_vector(a.num_rows); // a.dot(w) has dimensions a.num_rows. see below.
+        let e_1 = Vector::<Fr>::new_zero_vector(a.num_rows()); // a.dot(w) has dimensions a.num_rows. see below.
         let e_2 = e_1.clone();
 
         // u <-- u_1 + r * u_2
@@ -29,24 +37,33 @@ mod tests {
 
         // E <-- E_1 + r * (AZ_1 o BZ_2 + AZ_2 o BZ_1 - u_1CZ_2 - u_2CZ_1) + r^2 * E_2
         // E is the F^m error vector
-        let e = (a.dot_vector(&w_1) * b.dot_vector(&w_2) + a.dot_vector(&w_2) * b.dot_vector(&w_1)
-            - c.dot_vector(&w_2).scalar_mul(&u_1)
-            - c.dot_vector(&w_1).scalar_mul(&u_2))
-        .scalar_mul(&r)
-            + e_1
-            + e_2.scalar_mul(&(r.square()));
+        let (az_1, bz_1, cz_1) = (
+            a.dot_vector(&w_1).unwrap(),
+            b.dot_vector(&w_1).unwrap(),
+            c.dot_vector(&w_1).unwrap(),
+        );
+        let (az_2, bz_2, cz_2) = (
+            a.dot_vector(&w_2).unwrap(),
+            b.dot_vector(&w_2).unwrap(),
+            c.dot_vector(&w_2).unwrap(),
+        );
+        // the vectors all have a.num_rows elements, so that the operations below don't fail
+        let cross = ((az_1.clone() * bz_2.clone()).unwrap()
+            + (az_2.clone() * bz_1.clone()).unwrap())
+        .unwrap();
+        let t = ((cross.clone() - cz_2.scalar_mul(&u_1)).unwrap() - cz_1.scalar_mul(&u_2)).unwrap();
+        let e = ((t.scalar_mul(&r) + e_1).unwrap() + e_2.scalar_mul(&(r.square()))).unwrap();
 
         // AZ o BZ
-        let az_bz = a.dot_vector(&w_1) * b.dot_vector(&w_1)
-            + (a.dot_vector(&w_1) * b.dot_vector(&w_2) + a.dot_vector(&w_2) * b.dot_vector(&w_1))
-                .scalar_mul(&r)
-            + (a.dot_vector(&w_2) * b.dot_vector(&w_2)).scalar_mul(&(r.square()));
+        let az_bz = (((az_1 * bz_1).unwrap() + cross.scalar_mul(&r)).unwrap()
+            + (az_2 * bz_2).unwrap().scalar_mul(&(r.square())))
+        .unwrap();
 
         // uCZ + E
-        let cz = c.dot_vector(&w_1) + (c.dot_vector(&w_2)).scalar_mul(&r);
-        let u_cz_plus_e = cz.scalar_mul(&u) + e;
+        let cz = (cz_1 + cz_2.scalar_mul(&r)).unwrap();
+        let u_cz_plus_e = (cz.scalar_mul(&u) + e).unwrap();
 
         // checks that relaxed r1cs is satisfied
-        assert!((az_bz - u_cz_plus_e).is_zero_vector());
+        assert!((az_bz - u_cz_plus_e).unwrap().is_zero_vector());
     }
 }
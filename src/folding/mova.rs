@@ -0,0 +1,359 @@
+// Mova's folding scheme for relaxed r1cs, without committing to the error term: https://eprint.iacr.org/2024/1220
+// An instance is (comm_W, u, x, r_E, v), a witness (W, r_W). With z = (u, x, W) and E = AZ o BZ - u * CZ
+// (padded to 2^s), the instance holds when v = MLE(E)(r_E): E is fixed by z, so it is never committed to.
+// Folding two instances:
+// - both error terms are brought to the same point, by restricting them to the line l(X) = r_E1 + X * (r_E2 - r_E1):
+//   the prover sends h_i(X) = MLE(E_i)(l(X)), of degree s, the verifier checks h_1(0) = v_1, h_2(1) = v_2
+//   and moves both claims to r_E = l(beta), beta from the transcript,
+// - the prover sends MLE(T)(r_E) for the cross term T of nova, and with alpha from the transcript:
+//   comm_W = comm_W1 + alpha * comm_W2, u = u_1 + alpha * u_2, x = x_1 + alpha * x_2,
+//   v = h_1(beta) + alpha * MLE(T)(r_E) + alpha^2 * h_2(beta), which holds since MLE is linear.
+// Compared to `NIFS`, a fold costs a single commitment (to W) instead of two (to W and T), and no commitment to E.
+use ark_ec::CurveGroup;
+use ark_ff::{Field, PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::Rng, One, Zero};
+
+use super::nifs::{fold_vectors, NIFS};
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::cs::pedersen::Pedersen;
+use crate::error::AlgorithmsError;
+use crate::ip::sumcheck::{evaluate_mle, protocol::interpolate_at};
+use crate::transcript::Transcript;
+use crate::utils::linear_algebra::Vector;
+
+/// A relaxed r1cs instance whose error term is given by an evaluation of its mle, x excluding u
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MovaInstance<C: CurveGroup> {
+    pub comm_w: C,
+    pub u: C::ScalarField,
+    pub x: Vec<C::ScalarField>,
+    pub r_e: Vec<C::ScalarField>,
+    pub v: C::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovaWitness<F: PrimeField> {
+    pub w: Vec<F>,
+    pub r_w: F,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovaProof<F: PrimeField> {
+    /// Evaluations of h_1 and h_2 at 0, 1, .., s
+    pub h_1: Vec<F>,
+    pub h_2: Vec<F>,
+    /// MLE(T)(r_E)
+    pub mle_t: F,
+}
+
+/// The folded instance and witness, with the proof
+pub type MovaFold<C> = (
+    MovaInstance<C>,
+    MovaWitness<<C as ark_ec::Group>::ScalarField>,
+    MovaProof<<C as ark_ec::Group>::ScalarField>,
+);
+
+pub struct Mova<C: CurveGroup> {
+    pedersen: Pedersen<C>,
+    /// Number of variables of the mle of E
+    s: usize,
+}
+
+impl<C: CurveGroup> MovaInstance<C> {
+    fn absorb_in(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.comm_w);
+        transcript.absorb(&self.u);
+        transcript.absorb(&self.x);
+        transcript.absorb(&self.r_e);
+        transcript.absorb(&self.v);
+    }
+}
+
+/// l(t) = p_1 + t * (p_2 - p_1)
+fn line_at<F: PrimeField>(p_1: &[F], p_2: &[F], t: F) -> Vec<F> {
+    p_1.iter()
+        .zip(p_2)
+        .map(|(a, b)| *a + t * (*b - a))
+        .collect()
+}
+
+impl<C: CurveGroup> Mova<C> {
+    /// Commitment parameters for the witnesses of `r1cs`
+    pub fn new(r1cs: &R1CS<C::ScalarField>) -> Self {
+        Self {
+            pedersen: Pedersen::setup(r1cs.n_witness()),
+            s: r1cs.n_constraints().next_power_of_two().trailing_zeros() as usize,
+        }
+    }
+
+    pub fn pedersen(&self) -> &Pedersen<C> {
+        &self.pedersen
+    }
+
+    /// z = (u, x, W)
+    fn z(
+        instance: &MovaInstance<C>,
+        witness: &MovaWitness<C::ScalarField>,
+    ) -> R1CSInstanceWitness<C::ScalarField> {
+        Vector::new(&[vec![instance.u], instance.x.clone(), witness.w.clone()].concat())
+    }
+
+    /// E = AZ o BZ - u * CZ, padded to 2^s
+    fn error_term(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        u: C::ScalarField,
+        z: &R1CSInstanceWitness<C::ScalarField>,
+    ) -> Result<Vec<C::ScalarField>, AlgorithmsError> {
+        let e = ((r1cs.a().dot_vector(z)? * r1cs.b().dot_vector(z)?)?
+            - r1cs.c().dot_vector(z)?.scalar_mul(&u))?;
+        Ok(self.pad(e.elements().to_vec()))
+    }
+
+    fn pad(&self, mut evaluations: Vec<C::ScalarField>) -> Vec<C::ScalarField> {
+        evaluations.resize(1 << self.s, C::ScalarField::zero());
+        evaluations
+    }
+
+    /// Turns z = (1, x, w), satisfying `r1cs`, into an instance with u = 1 and v = 0
+    /// r_E is drawn from the transcript once W is committed to, so that MLE(E)(r_E) = 0 implies E = 0.
+    pub fn commit_instance<R: Rng>(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        z: &R1CSInstanceWitness<C::ScalarField>,
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> (MovaInstance<C>, MovaWitness<C::ScalarField>) {
+        let (io, w) = z.elements().split_at(r1cs.n_instance());
+        let r_w = C::ScalarField::rand(rng);
+        let comm_w = self.pedersen.commit(w, &r_w);
+        transcript.absorb(&comm_w);
+        transcript.absorb(io);
+        let instance = MovaInstance {
+            comm_w,
+            u: C::ScalarField::one(),
+            x: io[1..].to_vec(),
+            r_e: transcript.challenges(self.s),
+            v: C::ScalarField::zero(),
+        };
+        let witness = MovaWitness { w: w.to_vec(), r_w };
+        (instance, witness)
+    }
+
+    /// Folds (instance_1, witness_1) with (instance_2, witness_2)
+    /// Returns the folded instance and witness, and the proof the verifier needs, or an error if
+    /// an instance-witness pair doesn't fit the r1cs.
+    pub fn prove(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        instance_1: &MovaInstance<C>,
+        witness_1: &MovaWitness<C::ScalarField>,
+        instance_2: &MovaInstance<C>,
+        witness_2: &MovaWitness<C::ScalarField>,
+        transcript: &mut Transcript,
+    ) -> Result<MovaFold<C>, AlgorithmsError> {
+        let z_1 = Self::z(instance_1, witness_1);
+        let z_2 = Self::z(instance_2, witness_2);
+        let e_1 = self.error_term(r1cs, instance_1.u, &z_1)?;
+        let e_2 = self.error_term(r1cs, instance_2.u, &z_2)?;
+
+        instance_1.absorb_in(transcript);
+        instance_2.absorb_in(transcript);
+        // h_i(k) = MLE(E_i)(l(k)), k = 0..=s determine h_i, of degree s
+        let (mut h_1, mut h_2) = (vec![], vec![]);
+        for k in 0..=self.s.max(1) {
+            let point = line_at(
+                &instance_1.r_e,
+                &instance_2.r_e,
+                C::ScalarField::from(k as u64),
+            );
+            h_1.push(evaluate_mle(&e_1, &point));
+            h_2.push(evaluate_mle(&e_2, &point));
+        }
+        let (r_e, beta) = self.point(instance_1, instance_2, &h_1, &h_2, transcript);
+
+        let t = self.pad(NIFS::<C>::compute_t(
+            r1cs,
+            instance_1.u,
+            &z_1,
+            instance_2.u,
+            &z_2,
+        )?);
+        let proof = MovaProof {
+            mle_t: evaluate_mle(&t, &r_e),
+            h_1,
+            h_2,
+        };
+        let alpha = Self::alpha(&proof, transcript);
+        let instance = Self::fold_instances(instance_1, instance_2, &proof, r_e, beta, alpha);
+        let witness = MovaWitness {
+            w: fold_vectors(&witness_1.w, &witness_2.w, alpha),
+            r_w: witness_1.r_w + alpha * witness_2.r_w,
+        };
+        Ok((instance, witness, proof))
+    }
+
+    /// Returns the folded instance, or None if h_1 or h_2 don't match the claimed evaluations
+    pub fn verify(
+        &self,
+        instance_1: &MovaInstance<C>,
+        instance_2: &MovaInstance<C>,
+        proof: &MovaProof<C::ScalarField>,
+        transcript: &mut Transcript,
+    ) -> Option<MovaInstance<C>> {
+        let degree = self.s.max(1);
+        if instance_1.r_e.len() != self.s
+            || instance_2.r_e.len() != self.s
+            || instance_1.x.len() != instance_2.x.len()
+            || proof.h_1.len() != degree + 1
+            || proof.h_2.len() != degree + 1
+            || proof.h_1[0] != instance_1.v
+            || proof.h_2[1] != instance_2.v
+        {
+            return None;
+        }
+        instance_1.absorb_in(transcript);
+        instance_2.absorb_in(transcript);
+        let (r_e, beta) = self.point(instance_1, instance_2, &proof.h_1, &proof.h_2, transcript);
+        let alpha = Self::alpha(proof, transcript);
+        Some(Self::fold_instances(
+            instance_1, instance_2, proof, r_e, beta, alpha,
+        ))
+    }
+
+    /// beta, after absorbing h_1 and h_2, and the point r_E = l(beta)
+    fn point(
+        &self,
+        instance_1: &MovaInstance<C>,
+        instance_2: &MovaInstance<C>,
+        h_1: &[C::ScalarField],
+        h_2: &[C::ScalarField],
+        transcript: &mut Transcript,
+    ) -> (Vec<C::ScalarField>, C::ScalarField) {
+        transcript.absorb(h_1);
+        transcript.absorb(h_2);
+        let beta: C::ScalarField = transcript.challenge();
+        (line_at(&instance_1.r_e, &instance_2.r_e, beta), beta)
+    }
+
+    /// The folding challenge alpha, after absorbing MLE(T)(r_E)
+    fn alpha(proof: &MovaProof<C::ScalarField>, transcript: &mut Transcript) -> C::ScalarField {
+        transcript.absorb(&proof.mle_t);
+        transcript.challenge()
+    }
+
+    fn fold_instances(
+        instance_1: &MovaInstance<C>,
+        instance_2: &MovaInstance<C>,
+        proof: &MovaProof<C::ScalarField>,
+        r_e: Vec<C::ScalarField>,
+        beta: C::ScalarField,
+        alpha: C::ScalarField,
+    ) -> MovaInstance<C> {
+        let v_1 = interpolate_at(&proof.h_1, beta);
+        let v_2 = interpolate_at(&proof.h_2, beta);
+        MovaInstance {
+            comm_w: instance_1.comm_w + instance_2.comm_w * alpha,
+            u: instance_1.u + alpha * instance_2.u,
+            x: fold_vectors(&instance_1.x, &instance_2.x, alpha),
+            r_e,
+            v: v_1 + alpha * proof.mle_t + alpha.square() * v_2,
+        }
+    }
+
+    /// Checks MLE(AZ o BZ - u * CZ)(r_E) = v and the commitment to W
+    pub fn is_satisfied(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        instance: &MovaInstance<C>,
+        witness: &MovaWitness<C::ScalarField>,
+    ) -> bool {
+        if instance.x.len() + 1 != r1cs.n_instance()
+            || witness.w.len() != r1cs.n_witness()
+            || instance.r_e.len() != self.s
+        {
+            return false;
+        }
+        let Ok(e) = self.error_term(r1cs, instance.u, &Self::z(instance, witness)) else {
+            return false;
+        };
+        evaluate_mle(&e, &instance.r_e) == instance.v
+            && self
+                .pedersen
+                .verify_opening(&instance.comm_w, &witness.w, &witness.r_w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mova;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::transcript::Transcript;
+    use ark_ff::One;
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_mova() {
+        let mut rng = test_rng();
+        let circuit = |a: u64, b: u64, c: u64| {
+            TestPythagoreCircuit::new(Fr::from(a), Fr::from(b), Fr::from(c))
+        };
+        let r1cs = get_r1cs_from_cs(circuit(3, 4, 25)).unwrap();
+        let mova = Mova::<Projective>::new(&r1cs);
+        let mut transcript = Transcript::new(b"mova instances");
+        let mut commit = |c: TestPythagoreCircuit<Fr>| {
+            mova.commit_instance(&r1cs, &get_z_from_cs(c).unwrap(), &mut transcript, &mut rng)
+        };
+        let (u_1, w_1) = commit(circuit(3, 4, 25));
+        let (u_2, w_2) = commit(circuit(5, 12, 169));
+        let (u_5, w_5) = commit(circuit(5, 12, 170));
+        assert!(mova.is_satisfied(&r1cs, &u_1, &w_1));
+        assert!(mova.is_satisfied(&r1cs, &u_2, &w_2));
+
+        // fold twice, the prover's and the verifier's folded instances agree
+        let (u_3, w_3, proof) = mova
+            .prove(&r1cs, &u_1, &w_1, &u_2, &w_2, &mut Transcript::new(b"mova"))
+            .unwrap();
+        assert_eq!(
+            mova.verify(&u_1, &u_2, &proof, &mut Transcript::new(b"mova")),
+            Some(u_3.clone())
+        );
+        assert!(mova.is_satisfied(&r1cs, &u_3, &w_3));
+        let (u_4, w_4, proof) = mova
+            .prove(&r1cs, &u_3, &w_3, &u_1, &w_1, &mut Transcript::new(b"mova"))
+            .unwrap();
+        assert_eq!(
+            mova.verify(&u_3, &u_1, &proof, &mut Transcript::new(b"mova")),
+            Some(u_4.clone())
+        );
+        assert!(mova.is_satisfied(&r1cs, &u_4, &w_4));
+
+        // h_1 has to start at the claimed evaluation
+        let mut wrong_proof = proof.clone();
+        wrong_proof.h_1[0] += Fr::one();
+        assert!(mova
+            .verify(&u_3, &u_1, &wrong_proof, &mut Transcript::new(b"mova"))
+            .is_none());
+        // a wrong MLE(T)(r_E) gives an unsatisfied folded instance
+        let mut wrong_proof = proof;
+        wrong_proof.mle_t += Fr::one();
+        let wrong = mova
+            .verify(&u_3, &u_1, &wrong_proof, &mut Transcript::new(b"mova"))
+            .unwrap();
+        assert!(!mova.is_satisfied(&r1cs, &wrong, &w_4));
+
+        // folding an unsatisfied instance: h_2(1) is MLE(E_2)(r_E2), not the claimed v_2 = 0
+        assert!(!mova.is_satisfied(&r1cs, &u_5, &w_5));
+        let (_, _, proof) = mova
+            .prove(&r1cs, &u_1, &w_1, &u_5, &w_5, &mut Transcript::new(b"mova"))
+            .unwrap();
+        assert!(mova
+            .verify(&u_1, &u_5, &proof, &mut Transcript::new(b"mova"))
+            .is_none());
+        let mut w_wrong = w_4;
+        w_wrong.r_w += Fr::one();
+        assert!(!mova.is_satisfied(&r1cs, &u_4, &w_wrong));
+    }
+}
@@ -0,0 +1,458 @@
+// Nova's non-interactive folding scheme for committed relaxed r1cs: https://eprint.iacr.org/2021/370 (section 4)
+// An instance is (comm_E, u, comm_W, x), a witness (E, r_E, W, r_W), and z = (u, x, W) satisfies
+// AZ o BZ = u * CZ + E. Folding two of them with the cross term T = AZ_1 o BZ_2 + AZ_2 o BZ_1 - u_1 CZ_2 - u_2 CZ_1:
+// comm_E = comm_E1 + r * comm_T + r^2 * comm_E2, u = u_1 + r * u_2, comm_W = comm_W1 + r * comm_W2,
+// x = x_1 + r * x_2, and the witness the same way.
+// The challenge r is derived by absorbing both instances and comm_T in a transcript (Fiat-Shamir).
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::Rng, One, Zero};
+
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::circuits::relaxed_r1cs::compute_t;
+use crate::cs::obligations::{MsmTerm, Obligation, ObligationSet};
+use crate::cs::pedersen::Pedersen;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::{
+    curve::{check_subgroup_all, SubgroupCheck},
+    linear_algebra::Vector,
+};
+
+/// A committed relaxed r1cs instance, x excluding u
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct CommittedRelaxedInstance<C: CurveGroup> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub comm_e: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub u: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub comm_w: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde::vec"))]
+    pub x: Vec<C::ScalarField>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RelaxedWitness<F: PrimeField> {
+    pub e: Vec<F>,
+    pub r_e: F,
+    pub w: Vec<F>,
+    pub r_w: F,
+}
+
+/// The folded instance and witness, with the commitment to T
+pub type NIFSFold<C> = (
+    CommittedRelaxedInstance<C>,
+    RelaxedWitness<<C as ark_ec::Group>::ScalarField>,
+    C,
+);
+
+pub struct NIFS<C: CurveGroup> {
+    pedersen: Pedersen<C>,
+}
+
+impl<C: CurveGroup> CommittedRelaxedInstance<C> {
+    fn absorb_in(&self, transcript: &mut Transcript) {
+        transcript.absorb(&[self.comm_e, self.comm_w]);
+        transcript.absorb(&self.u);
+        transcript.absorb(&self.x);
+    }
+}
+
+pub(crate) fn fold_vectors<F: PrimeField>(v_1: &[F], v_2: &[F], r: F) -> Vec<F> {
+    v_1.iter().zip(v_2).map(|(a, b)| *a + r * b).collect()
+}
+
+impl<C: CurveGroup> NIFS<C> {
+    /// Commitment parameters for the witnesses and error terms of `r1cs`
+    pub fn new(r1cs: &R1CS<C::ScalarField>) -> Self {
+        Self {
+            pedersen: Pedersen::setup(r1cs.n_constraints().max(r1cs.n_witness())),
+        }
+    }
+
+    pub fn pedersen(&self) -> &Pedersen<C> {
+        &self.pedersen
+    }
+
+    /// z = (u, x, W)
+    fn z(
+        instance: &CommittedRelaxedInstance<C>,
+        witness: &RelaxedWitness<C::ScalarField>,
+    ) -> R1CSInstanceWitness<C::ScalarField> {
+        Vector::new(&[vec![instance.u], instance.x.clone(), witness.w.clone()].concat())
+    }
+
+    /// Turns z = (1, x, w), satisfying `r1cs`, into a committed relaxed instance with u = 1 and E = 0
+    pub fn commit_instance<R: Rng>(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        z: &R1CSInstanceWitness<C::ScalarField>,
+        rng: &mut R,
+    ) -> (CommittedRelaxedInstance<C>, RelaxedWitness<C::ScalarField>) {
+        let (io, w) = z.elements().split_at(r1cs.n_instance());
+        let r_w = C::ScalarField::rand(rng);
+        let witness = RelaxedWitness {
+            e: vec![C::ScalarField::zero(); r1cs.n_constraints()],
+            r_e: C::ScalarField::zero(),
+            w: w.to_vec(),
+            r_w,
+        };
+        let instance = CommittedRelaxedInstance {
+            comm_e: C::zero(),
+            u: C::ScalarField::one(),
+            comm_w: self.pedersen.commit(w, &r_w),
+            x: io[1..].to_vec(),
+        };
+        (instance, witness)
+    }
+
+    /// T = AZ_1 o BZ_2 + AZ_2 o BZ_1 - u_1 CZ_2 - u_2 CZ_1, see `relaxed_r1cs::compute_t`
+    /// An error if z_1 or z_2 doesn't fit the r1cs
+    pub fn compute_t(
+        r1cs: &R1CS<C::ScalarField>,
+        u_1: C::ScalarField,
+        z_1: &R1CSInstanceWitness<C::ScalarField>,
+        u_2: C::ScalarField,
+        z_2: &R1CSInstanceWitness<C::ScalarField>,
+    ) -> Result<Vec<C::ScalarField>, AlgorithmsError> {
+        Ok(compute_t(r1cs.a(), r1cs.b(), r1cs.c(), u_1, z_1, u_2, z_2)?
+            .elements()
+            .to_vec())
+    }
+
+    /// comm_T, the commitment to the cross term that the verifier folds the error commitments with,
+    /// along with its blinding factor r_T
+    pub fn commit_t<R: Rng>(&self, t: &[C::ScalarField], rng: &mut R) -> (C, C::ScalarField) {
+        let r_t = C::ScalarField::rand(rng);
+        (self.pedersen.commit(t, &r_t), r_t)
+    }
+
+    /// Folds (instance_1, witness_1) with (instance_2, witness_2)
+    /// Returns the folded instance and witness, and comm_T, which the verifier needs, or an error if
+    /// an instance-witness pair doesn't fit the r1cs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove<R: Rng>(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        instance_1: &CommittedRelaxedInstance<C>,
+        witness_1: &RelaxedWitness<C::ScalarField>,
+        instance_2: &CommittedRelaxedInstance<C>,
+        witness_2: &RelaxedWitness<C::ScalarField>,
+        transcript: &mut Transcript,
+        rng: &mut R,
+    ) -> Result<NIFSFold<C>, AlgorithmsError> {
+        let z_1 = Self::z(instance_1, witness_1);
+        let z_2 = Self::z(instance_2, witness_2);
+        let t = Self::compute_t(r1cs, instance_1.u, &z_1, instance_2.u, &z_2)?;
+        let (comm_t, r_t) = self.commit_t(&t, rng);
+
+        let r = Self::challenge(instance_1, instance_2, &comm_t, transcript);
+        let r_square = r.square();
+        let e = t
+            .iter()
+            .zip(witness_1.e.iter().zip(&witness_2.e))
+            .map(|(t_i, (e_1, e_2))| *e_1 + r * t_i + r_square * e_2)
+            .collect();
+        let witness = RelaxedWitness {
+            e,
+            r_e: witness_1.r_e + r * r_t + r_square * witness_2.r_e,
+            w: fold_vectors(&witness_1.w, &witness_2.w, r),
+            r_w: witness_1.r_w + r * witness_2.r_w,
+        };
+        let instance = Self::fold_instances(instance_1, instance_2, &comm_t, r);
+        Ok((instance, witness, comm_t))
+    }
+
+    /// Folds the instances, with r derived from the transcript
+    pub fn verify(
+        instance_1: &CommittedRelaxedInstance<C>,
+        instance_2: &CommittedRelaxedInstance<C>,
+        comm_t: &C,
+        transcript: &mut Transcript,
+    ) -> CommittedRelaxedInstance<C> {
+        let r = Self::challenge(instance_1, instance_2, comm_t, transcript);
+        Self::fold_instances(instance_1, instance_2, comm_t, r)
+    }
+
+    /// `verify`, for the folded instance claimed by the prover: r, u and x are checked here, while the msms of the
+    /// folded commitments are deferred into `obligations`, as
+    /// comm_E1 + r * comm_T + r^2 * comm_E2 - comm_E = 0 and comm_W1 + r * comm_W2 - comm_W = 0
+    /// Returns false if the cheap checks already fail.
+    pub fn verify_lazy<E: Pairing<G1 = C, ScalarField = C::ScalarField>>(
+        instance_1: &CommittedRelaxedInstance<C>,
+        instance_2: &CommittedRelaxedInstance<C>,
+        comm_t: &C,
+        folded: &CommittedRelaxedInstance<C>,
+        transcript: &mut Transcript,
+        obligations: &mut ObligationSet<E>,
+    ) -> bool
+    where
+        C: SubgroupCheck,
+    {
+        if instance_1.x.len() != instance_2.x.len() || folded.x.len() != instance_1.x.len() {
+            return false;
+        }
+        let points = [
+            instance_1.comm_e,
+            instance_1.comm_w,
+            instance_2.comm_e,
+            instance_2.comm_w,
+            *comm_t,
+            folded.comm_e,
+            folded.comm_w,
+        ];
+        if !check_subgroup_all(&points) {
+            return false;
+        }
+        let r = Self::challenge(instance_1, instance_2, comm_t, transcript);
+        if folded.u != instance_1.u + r * instance_2.u
+            || folded.x != fold_vectors(&instance_1.x, &instance_2.x, r)
+        {
+            return false;
+        }
+        let one = C::ScalarField::one();
+        obligations.push(Obligation::MsmIsZero(MsmTerm::new(
+            vec![instance_1.comm_e, *comm_t, instance_2.comm_e, folded.comm_e],
+            vec![one, r, r.square(), -one],
+        )));
+        obligations.push(Obligation::MsmIsZero(MsmTerm::new(
+            vec![instance_1.comm_w, instance_2.comm_w, folded.comm_w],
+            vec![one, r, -one],
+        )));
+        true
+    }
+
+    /// The folding challenge r, after absorbing both instances and comm_T
+    pub fn challenge(
+        instance_1: &CommittedRelaxedInstance<C>,
+        instance_2: &CommittedRelaxedInstance<C>,
+        comm_t: &C,
+        transcript: &mut Transcript,
+    ) -> C::ScalarField {
+        instance_1.absorb_in(transcript);
+        instance_2.absorb_in(transcript);
+        transcript.absorb(comm_t);
+        transcript.challenge()
+    }
+
+    fn fold_instances(
+        instance_1: &CommittedRelaxedInstance<C>,
+        instance_2: &CommittedRelaxedInstance<C>,
+        comm_t: &C,
+        r: C::ScalarField,
+    ) -> CommittedRelaxedInstance<C> {
+        CommittedRelaxedInstance {
+            comm_e: instance_1.comm_e + *comm_t * r + instance_2.comm_e * r.square(),
+            u: instance_1.u + r * instance_2.u,
+            comm_w: instance_1.comm_w + instance_2.comm_w * r,
+            x: fold_vectors(&instance_1.x, &instance_2.x, r),
+        }
+    }
+
+    /// Checks AZ o BZ = u * CZ + E and both commitments
+    pub fn is_satisfied(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        instance: &CommittedRelaxedInstance<C>,
+        witness: &RelaxedWitness<C::ScalarField>,
+    ) -> bool {
+        if instance.x.len() + 1 != r1cs.n_instance()
+            || witness.w.len() != r1cs.n_witness()
+            || witness.e.len() != r1cs.n_constraints()
+        {
+            return false;
+        }
+        let z = Self::z(instance, witness);
+        let (Ok(az), Ok(bz), Ok(cz)) = (
+            r1cs.a().dot_vector(&z),
+            r1cs.b().dot_vector(&z),
+            r1cs.c().dot_vector(&z),
+        ) else {
+            return false;
+        };
+        let (Ok(az_bz), Ok(u_cz_e)) = (
+            az * bz,
+            cz.scalar_mul(&instance.u) + Vector::new(&witness.e),
+        ) else {
+            return false;
+        };
+        (az_bz - u_cz_e).is_ok_and(|v| v.is_zero_vector())
+            && self
+                .pedersen
+                .verify_opening(&instance.comm_e, &witness.e, &witness.r_e)
+            && self
+                .pedersen
+                .verify_opening(&instance.comm_w, &witness.w, &witness.r_w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommittedRelaxedInstance, RelaxedWitness, NIFS};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::cs::obligations::ObligationSet;
+    use crate::transcript::Transcript;
+    use crate::utils::linear_algebra::Vector;
+    use ark_bn254::{Bn254, G1Projective};
+    use ark_ff::One;
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_nifs() {
+        let mut rng = test_rng();
+        let circuit_1 = TestPythagoreCircuit::new(Fr::from(3), Fr::from(4), Fr::from(25));
+        let circuit_2 = TestPythagoreCircuit::new(Fr::from(5), Fr::from(12), Fr::from(169));
+        let r1cs = get_r1cs_from_cs(circuit_1.clone()).unwrap();
+        let nifs = NIFS::<Projective>::new(&r1cs);
+        let (u_1, w_1) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_1).unwrap(), &mut rng);
+        let (u_2, w_2) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_2).unwrap(), &mut rng);
+        assert!(nifs.is_satisfied(&r1cs, &u_1, &w_1));
+        assert!(nifs.is_satisfied(&r1cs, &u_2, &w_2));
+
+        // fold twice, the prover's and the verifier's folded instances agree
+        let (u_3, w_3, comm_t) = nifs
+            .prove(
+                &r1cs,
+                &u_1,
+                &w_1,
+                &u_2,
+                &w_2,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(
+            NIFS::verify(&u_1, &u_2, &comm_t, &mut Transcript::new(b"nifs")),
+            u_3
+        );
+        assert!(nifs.is_satisfied(&r1cs, &u_3, &w_3));
+
+        // both errors are zero: the folded error is r * T, T being computed from the matrices and z vectors
+        let z = |u: &CommittedRelaxedInstance<Projective>, w: &RelaxedWitness<Fr>| {
+            Vector::new(&[vec![u.u], u.x.clone(), w.w.clone()].concat())
+        };
+        let t = NIFS::<Projective>::compute_t(&r1cs, u_1.u, &z(&u_1, &w_1), u_2.u, &z(&u_2, &w_2))
+            .unwrap();
+        let r = NIFS::challenge(&u_1, &u_2, &comm_t, &mut Transcript::new(b"nifs"));
+        assert_eq!(w_3.e, t.iter().map(|t_i| r * t_i).collect::<Vec<_>>());
+        let (comm, r_t) = nifs.commit_t(&t, &mut rng);
+        assert_eq!(comm, nifs.pedersen().commit(&t, &r_t));
+
+        let (u_4, w_4, comm_t) = nifs
+            .prove(
+                &r1cs,
+                &u_3,
+                &w_3,
+                &u_1,
+                &w_1,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+        assert_eq!(
+            NIFS::verify(&u_3, &u_1, &comm_t, &mut Transcript::new(b"nifs")),
+            u_4
+        );
+        assert!(nifs.is_satisfied(&r1cs, &u_4, &w_4));
+
+        // the challenge depends on comm_T: another cross term commitment gives another folded instance
+        let other = NIFS::verify(
+            &u_3,
+            &u_1,
+            &(comm_t + nifs.pedersen().h()),
+            &mut Transcript::new(b"nifs"),
+        );
+        assert_ne!(other.u, u_4.u);
+
+        // folding an unsatisfied instance gives an unsatisfied folded instance
+        let circuit_5 = TestPythagoreCircuit::new(Fr::from(5), Fr::from(12), Fr::from(170));
+        let (u_5, w_5) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_5).unwrap(), &mut rng);
+        assert!(!nifs.is_satisfied(&r1cs, &u_5, &w_5));
+        let (u_6, w_6, _) = nifs
+            .prove(
+                &r1cs,
+                &u_1,
+                &w_1,
+                &u_5,
+                &w_5,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+        assert!(!nifs.is_satisfied(&r1cs, &u_6, &w_6));
+        let mut w_wrong = w_4.clone();
+        w_wrong.r_w += Fr::one();
+        assert!(!nifs.is_satisfied(&r1cs, &u_4, &w_wrong));
+    }
+
+    #[test]
+    pub fn test_nifs_lazy() {
+        let mut rng = test_rng();
+        let circuit_1 = TestPythagoreCircuit::new(
+            ark_bn254::Fr::from(3),
+            ark_bn254::Fr::from(4),
+            ark_bn254::Fr::from(25),
+        );
+        let circuit_2 = TestPythagoreCircuit::new(
+            ark_bn254::Fr::from(5),
+            ark_bn254::Fr::from(12),
+            ark_bn254::Fr::from(169),
+        );
+        let r1cs = get_r1cs_from_cs(circuit_1.clone()).unwrap();
+        let nifs = NIFS::<G1Projective>::new(&r1cs);
+        let (u_1, w_1) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_1).unwrap(), &mut rng);
+        let (u_2, w_2) = nifs.commit_instance(&r1cs, &get_z_from_cs(circuit_2).unwrap(), &mut rng);
+        let (u_3, _, comm_t) = nifs
+            .prove(
+                &r1cs,
+                &u_1,
+                &w_1,
+                &u_2,
+                &w_2,
+                &mut Transcript::new(b"nifs"),
+                &mut rng,
+            )
+            .unwrap();
+
+        let mut obligations = ObligationSet::<Bn254>::new();
+        let mut verify = |folded: &CommittedRelaxedInstance<G1Projective>| {
+            NIFS::verify_lazy(
+                &u_1,
+                &u_2,
+                &comm_t,
+                folded,
+                &mut Transcript::new(b"nifs"),
+                &mut obligations,
+            )
+        };
+        assert!(verify(&u_3));
+        // a wrong u is caught right away, a wrong commitment once the obligations are discharged
+        let mut wrong_u = u_3.clone();
+        wrong_u.u += ark_bn254::Fr::one();
+        assert!(!verify(&wrong_u));
+        assert_eq!(obligations.len(), 2);
+        assert!(obligations.discharge());
+        assert!(obligations.discharge_batched(&mut rng));
+        let mut wrong_comm_w = u_3.clone();
+        wrong_comm_w.comm_w += nifs.pedersen().h();
+        let mut obligations = ObligationSet::<Bn254>::new();
+        assert!(NIFS::verify_lazy(
+            &u_1,
+            &u_2,
+            &comm_t,
+            &wrong_comm_w,
+            &mut Transcript::new(b"nifs"),
+            &mut obligations,
+        ));
+        assert!(!obligations.discharge());
+        assert!(!obligations.discharge_batched(&mut rng));
+    }
+}
@@ -0,0 +1,453 @@
+// ProtoGalaxy: folding k r1cs instances into a running one at once, https://eprint.iacr.org/2023/1106
+// With f_i(z) = (Az)_i * (Bz)_i - (Cz)_i over m = 2^t constraints (padded with zeros) and
+// pow_i(beta) = prod_{l: bit l of i is set} beta_l, the running instance (comm_W, x, beta, e)
+// holds when sum_i pow_i(beta) * f_i(z) = e, z = (1, x, W). A fresh instance has e = 0 for any beta.
+// 1. delta from the transcript, the prover sends F(X) = sum_i pow_i(beta + X * delta) * f_i(z_0),
+//    delta being (delta, delta^2, delta^4, ..); F(0) = e so only its non constant coefficients are sent.
+// 2. alpha from the transcript, beta* = beta + alpha * delta.
+// 3. With L_j the lagrange basis over the (k + 1)-th roots of unity and Z their vanishing polynomial,
+//    G(X) = sum_i pow_i(beta*) * f_i(sum_j L_j(X) * z_j), the prover sends K(X) = (G(X) - F(alpha) * L_0(X)) / Z(X)
+// 4. gamma from the transcript, the folded instance is (sum_j L_j(gamma) * comm_W_j, sum_j L_j(gamma) * x_j,
+//    beta*, F(alpha) * L_0(gamma) + Z(gamma) * K(gamma)), the folded witness sum_j L_j(gamma) * W_j.
+// The first coordinate of z stays 1 since sum_j L_j(X) = 1.
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::Rng, One, UniformRand, Zero};
+
+use super::nifs::fold_vectors;
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::cs::pedersen::Pedersen;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::lagrange::compute_lagrange_interpolation_on_roots_of_unity;
+use crate::utils::linear_algebra::Vector;
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProtoGalaxyInstance<C: CurveGroup> {
+    pub comm_w: C,
+    pub x: Vec<C::ScalarField>,
+    pub betas: Vec<C::ScalarField>,
+    pub e: C::ScalarField,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProtoGalaxyWitness<F: PrimeField> {
+    pub w: Vec<F>,
+    pub r_w: F,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProtoGalaxyProof<F: PrimeField> {
+    /// Coefficients of F(X), without the constant one
+    pub f_coefficients: Vec<F>,
+    /// Coefficients of K(X)
+    pub k_coefficients: Vec<F>,
+}
+
+/// The folded instance and witness, with the proof
+pub type ProtoGalaxyFold<C> = (
+    ProtoGalaxyInstance<C>,
+    ProtoGalaxyWitness<<C as ark_ec::Group>::ScalarField>,
+    ProtoGalaxyProof<<C as ark_ec::Group>::ScalarField>,
+);
+
+pub struct ProtoGalaxy<C: CurveGroup> {
+    pedersen: Pedersen<C>,
+}
+
+/// (pow_i(betas))_{i < 2^t}, bit l of i selecting betas[l]
+pub fn pow_evaluations<F: PrimeField>(betas: &[F]) -> Vec<F> {
+    let mut pows = vec![F::one()];
+    for beta in betas {
+        let high = pows.iter().map(|p| *p * beta).collect::<Vec<_>>();
+        pows.extend(high);
+    }
+    pows
+}
+
+/// (delta, delta^2, delta^4, .., delta^{2^{t-1}})
+fn deltas<F: PrimeField>(delta: F, t: usize) -> Vec<F> {
+    let mut deltas = vec![delta; t];
+    for l in 1..t {
+        deltas[l] = deltas[l - 1].square();
+    }
+    deltas
+}
+
+impl<C: CurveGroup> ProtoGalaxyInstance<C> {
+    fn absorb_in(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.comm_w);
+        transcript.absorb(&self.x);
+        transcript.absorb(&self.betas);
+        transcript.absorb(&self.e);
+    }
+}
+
+impl<C: CurveGroup> ProtoGalaxy<C> {
+    pub fn new(r1cs: &R1CS<C::ScalarField>) -> Self {
+        Self {
+            pedersen: Pedersen::setup(r1cs.n_witness()),
+        }
+    }
+
+    /// t, the number of betas: log2 of the number of constraints padded to a power of two
+    pub fn t(r1cs: &R1CS<C::ScalarField>) -> usize {
+        r1cs.n_constraints().next_power_of_two().trailing_zeros() as usize
+    }
+
+    /// Commits to z = (1, x, w), satisfying `r1cs`: e = 0 and beta = 0
+    pub fn commit_instance<R: Rng>(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        z: &R1CSInstanceWitness<C::ScalarField>,
+        rng: &mut R,
+    ) -> (ProtoGalaxyInstance<C>, ProtoGalaxyWitness<C::ScalarField>) {
+        let (io, w) = z.elements().split_at(r1cs.n_instance());
+        let r_w = C::ScalarField::rand(rng);
+        let instance = ProtoGalaxyInstance {
+            comm_w: self.pedersen.commit(w, &r_w),
+            x: io[1..].to_vec(),
+            betas: vec![C::ScalarField::zero(); Self::t(r1cs)],
+            e: C::ScalarField::zero(),
+        };
+        (instance, ProtoGalaxyWitness { w: w.to_vec(), r_w })
+    }
+
+    /// (f_i(z))_{i < 2^t}, f_i(z) = (Az)_i * (Bz)_i - (Cz)_i, an error if z doesn't fit the r1cs
+    fn constraint_evaluations(
+        r1cs: &R1CS<C::ScalarField>,
+        x: &[C::ScalarField],
+        w: &[C::ScalarField],
+    ) -> Result<Vec<C::ScalarField>, AlgorithmsError> {
+        let z = Vector::new(&[vec![C::ScalarField::one()], x.to_vec(), w.to_vec()].concat());
+        let f =
+            ((r1cs.a().dot_vector(&z)? * r1cs.b().dot_vector(&z)?)? - r1cs.c().dot_vector(&z)?)?;
+        let mut f = f.elements().to_vec();
+        f.resize(1 << Self::t(r1cs), C::ScalarField::zero());
+        Ok(f)
+    }
+
+    fn weighted_sum(pows: &[C::ScalarField], f: &[C::ScalarField]) -> C::ScalarField {
+        pows.iter().zip(f).map(|(p, f_i)| *p * f_i).sum()
+    }
+
+    /// Folds the `instances` in the running instance
+    pub fn prove(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        running: (&ProtoGalaxyInstance<C>, &ProtoGalaxyWitness<C::ScalarField>),
+        instances: &[(ProtoGalaxyInstance<C>, ProtoGalaxyWitness<C::ScalarField>)],
+        transcript: &mut Transcript,
+    ) -> Result<ProtoGalaxyFold<C>, String> {
+        let (running_instance, running_witness) = running;
+        let k = instances.len();
+        if !(k + 1).is_power_of_two() {
+            return Err("k + 1 should be a power of two".to_string());
+        }
+        let t = Self::t(r1cs);
+        if running_instance.betas.len() != t {
+            return Err("the running instance should have t betas".to_string());
+        }
+        Self::absorb_instances(
+            running_instance,
+            instances.iter().map(|(u, _)| u),
+            transcript,
+        );
+
+        // F(X), evaluated over a domain of size > t and interpolated
+        let delta: C::ScalarField = transcript.challenge();
+        let deltas = deltas(delta, t);
+        let f_0 = Self::constraint_evaluations(r1cs, &running_instance.x, &running_witness.w)
+            .map_err(|e| e.to_string())?;
+        let f_domain = GeneralEvaluationDomain::<C::ScalarField>::new(t + 1).unwrap();
+        let f_evals = f_domain
+            .elements()
+            .map(|point| {
+                let betas = fold_vectors(&running_instance.betas, &deltas, point);
+                Self::weighted_sum(&pow_evaluations(&betas), &f_0)
+            })
+            .collect::<Vec<_>>();
+        let f_poly = compute_lagrange_interpolation_on_roots_of_unity(&f_evals)
+            .map_err(|e| e.to_string())?;
+        let mut f_coefficients = f_poly.coeffs().to_vec();
+        f_coefficients.resize(t + 1, C::ScalarField::zero());
+        let f_coefficients = f_coefficients[1..].to_vec();
+        transcript.absorb(&f_coefficients);
+
+        let alpha: C::ScalarField = transcript.challenge();
+        let betas_star = fold_vectors(&running_instance.betas, &deltas, alpha);
+        let pows_star = pow_evaluations(&betas_star);
+        let f_alpha = f_poly.evaluate(&alpha);
+
+        // G(X), of degree 2k, evaluated over a domain of size > 2k and interpolated
+        let domain = GeneralEvaluationDomain::<C::ScalarField>::new(k + 1).unwrap();
+        let g_domain = GeneralEvaluationDomain::<C::ScalarField>::new(2 * k + 1).unwrap();
+        let g_evals = g_domain
+            .elements()
+            .map(|point| {
+                let lagranges = domain.evaluate_all_lagrange_coefficients(point);
+                let (x, w) = Self::combine(running, instances, &lagranges);
+                let f = Self::constraint_evaluations(r1cs, &x, &w.w)?;
+                Ok(Self::weighted_sum(&pows_star, &f))
+            })
+            .collect::<Result<Vec<_>, AlgorithmsError>>()
+            .map_err(|e| e.to_string())?;
+        let g_poly = compute_lagrange_interpolation_on_roots_of_unity(&g_evals)
+            .map_err(|e| e.to_string())?;
+        // G - F(alpha) * L_0, L_0(X) = (X^{k+1} - 1) / ((k + 1) * (X - 1))
+        let l_0 = &DensePolynomial::from_coefficients_vec(vec![C::ScalarField::one(); k + 1])
+            * domain.size_inv();
+        let (k_poly, remainder) = (&g_poly - &(&l_0 * f_alpha))
+            .divide_by_vanishing_poly(domain)
+            .ok_or("Error dividing by the vanishing polynomial")?;
+        if !remainder.is_zero() {
+            return Err("G(X) - F(alpha) * L_0(X) doesn't vanish over the domain".to_string());
+        }
+        let mut k_coefficients = k_poly.coeffs().to_vec();
+        k_coefficients.resize(k, C::ScalarField::zero());
+        transcript.absorb(&k_coefficients);
+
+        let gamma: C::ScalarField = transcript.challenge();
+        let lagranges = domain.evaluate_all_lagrange_coefficients(gamma);
+        let (x, witness) = Self::combine(running, instances, &lagranges);
+        let instance = ProtoGalaxyInstance {
+            comm_w: Self::combine_commitments(
+                running_instance,
+                instances.iter().map(|(u, _)| u),
+                &lagranges,
+            ),
+            x,
+            betas: betas_star,
+            e: f_alpha * lagranges[0]
+                + domain.evaluate_vanishing_polynomial(gamma) * k_poly.evaluate(&gamma),
+        };
+        let proof = ProtoGalaxyProof {
+            f_coefficients,
+            k_coefficients,
+        };
+        Ok((instance, witness, proof))
+    }
+
+    /// Computes the folded instance, or None if the proof is malformed
+    pub fn verify(
+        running_instance: &ProtoGalaxyInstance<C>,
+        instances: &[ProtoGalaxyInstance<C>],
+        proof: &ProtoGalaxyProof<C::ScalarField>,
+        transcript: &mut Transcript,
+    ) -> Option<ProtoGalaxyInstance<C>> {
+        let (k, t) = (instances.len(), running_instance.betas.len());
+        if !(k + 1).is_power_of_two()
+            || proof.f_coefficients.len() != t
+            || proof.k_coefficients.len() != k
+            || instances
+                .iter()
+                .any(|u| u.x.len() != running_instance.x.len())
+        {
+            return None;
+        }
+        Self::absorb_instances(running_instance, instances.iter(), transcript);
+
+        let delta: C::ScalarField = transcript.challenge();
+        let deltas = deltas(delta, t);
+        transcript.absorb(&proof.f_coefficients);
+        let alpha: C::ScalarField = transcript.challenge();
+        let f_poly = DensePolynomial::from_coefficients_vec(
+            [vec![running_instance.e], proof.f_coefficients.clone()].concat(),
+        );
+        let f_alpha = f_poly.evaluate(&alpha);
+        transcript.absorb(&proof.k_coefficients);
+
+        let gamma: C::ScalarField = transcript.challenge();
+        let domain = GeneralEvaluationDomain::<C::ScalarField>::new(k + 1).unwrap();
+        let lagranges = domain.evaluate_all_lagrange_coefficients(gamma);
+        let k_gamma =
+            DensePolynomial::from_coefficients_vec(proof.k_coefficients.clone()).evaluate(&gamma);
+        let mut x = running_instance
+            .x
+            .iter()
+            .map(|x_i| lagranges[0] * x_i)
+            .collect::<Vec<_>>();
+        for (instance, l_j) in instances.iter().zip(&lagranges[1..]) {
+            x = fold_vectors(&x, &instance.x, *l_j);
+        }
+        Some(ProtoGalaxyInstance {
+            comm_w: Self::combine_commitments(running_instance, instances.iter(), &lagranges),
+            x,
+            betas: fold_vectors(&running_instance.betas, &deltas, alpha),
+            e: f_alpha * lagranges[0] + domain.evaluate_vanishing_polynomial(gamma) * k_gamma,
+        })
+    }
+
+    fn absorb_instances<'a>(
+        running_instance: &ProtoGalaxyInstance<C>,
+        instances: impl Iterator<Item = &'a ProtoGalaxyInstance<C>>,
+        transcript: &mut Transcript,
+    ) {
+        running_instance.absorb_in(transcript);
+        for instance in instances {
+            transcript.absorb(&instance.comm_w);
+            transcript.absorb(&instance.x);
+        }
+    }
+
+    /// sum_j L_j * x_j and sum_j L_j * W_j, j = 0 being the running instance
+    fn combine(
+        running: (&ProtoGalaxyInstance<C>, &ProtoGalaxyWitness<C::ScalarField>),
+        instances: &[(ProtoGalaxyInstance<C>, ProtoGalaxyWitness<C::ScalarField>)],
+        lagranges: &[C::ScalarField],
+    ) -> (Vec<C::ScalarField>, ProtoGalaxyWitness<C::ScalarField>) {
+        let scale =
+            |v: &[C::ScalarField]| v.iter().map(|v_i| lagranges[0] * v_i).collect::<Vec<_>>();
+        let mut x = scale(&running.0.x);
+        let mut w = scale(&running.1.w);
+        let mut r_w = lagranges[0] * running.1.r_w;
+        for ((instance, witness), l_j) in instances.iter().zip(&lagranges[1..]) {
+            x = fold_vectors(&x, &instance.x, *l_j);
+            w = fold_vectors(&w, &witness.w, *l_j);
+            r_w += *l_j * witness.r_w;
+        }
+        (x, ProtoGalaxyWitness { w, r_w })
+    }
+
+    fn combine_commitments<'a>(
+        running_instance: &'a ProtoGalaxyInstance<C>,
+        instances: impl Iterator<Item = &'a ProtoGalaxyInstance<C>>,
+        lagranges: &[C::ScalarField],
+    ) -> C {
+        let commitments = ark_std::iter::once(running_instance)
+            .chain(instances)
+            .map(|u| u.comm_w)
+            .collect::<Vec<_>>();
+        C::msm(&C::normalize_batch(&commitments), lagranges).unwrap()
+    }
+
+    /// Checks sum_i pow_i(beta) * f_i(z) = e and the witness commitment
+    pub fn is_satisfied(
+        &self,
+        r1cs: &R1CS<C::ScalarField>,
+        instance: &ProtoGalaxyInstance<C>,
+        witness: &ProtoGalaxyWitness<C::ScalarField>,
+    ) -> bool {
+        if instance.x.len() + 1 != r1cs.n_instance()
+            || witness.w.len() != r1cs.n_witness()
+            || instance.betas.len() != Self::t(r1cs)
+        {
+            return false;
+        }
+        let Ok(f) = Self::constraint_evaluations(r1cs, &instance.x, &witness.w) else {
+            return false;
+        };
+        Self::weighted_sum(&pow_evaluations(&instance.betas), &f) == instance.e
+            && self
+                .pedersen
+                .verify_opening(&instance.comm_w, &witness.w, &witness.r_w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pow_evaluations, ProtoGalaxy};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::transcript::Transcript;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_pow_evaluations() {
+        let mut rng = test_rng();
+        let betas = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let pows = pow_evaluations(&betas);
+        assert_eq!(pows.len(), 8);
+        assert_eq!(pows[0], Fr::one());
+        assert_eq!(pows[5], betas[0] * betas[2]);
+        assert_eq!(pows[7], betas[0] * betas[1] * betas[2]);
+    }
+
+    fn fold_pythagore_instances(n_instances: usize) {
+        let mut rng = test_rng();
+        let circuits = (0..n_instances as u64)
+            .map(|i| {
+                let (a, b) = (Fr::from(i + 1), Fr::from(i + 2));
+                TestPythagoreCircuit::new(a, b, a * a + b * b)
+            })
+            .collect::<Vec<_>>();
+        let r1cs = get_r1cs_from_cs(circuits[0].clone()).unwrap();
+        let protogalaxy = ProtoGalaxy::<Projective>::new(&r1cs);
+        let mut instances = circuits
+            .into_iter()
+            .map(|c| protogalaxy.commit_instance(&r1cs, &get_z_from_cs(c).unwrap(), &mut rng))
+            .collect::<Vec<_>>();
+        let (running_instance, running_witness) = instances.remove(0);
+
+        // fold twice: fresh instances in a fresh running instance, then in the folded one
+        let mut running = (running_instance, running_witness);
+        for _ in 0..2 {
+            let (instance, witness, proof) = protogalaxy
+                .prove(
+                    &r1cs,
+                    (&running.0, &running.1),
+                    &instances,
+                    &mut Transcript::new(b"protogalaxy"),
+                )
+                .unwrap();
+            let incoming = instances.iter().map(|(u, _)| u.clone()).collect::<Vec<_>>();
+            let verified = ProtoGalaxy::verify(
+                &running.0,
+                &incoming,
+                &proof,
+                &mut Transcript::new(b"protogalaxy"),
+            )
+            .unwrap();
+            assert_eq!(verified, instance);
+            assert!(protogalaxy.is_satisfied(&r1cs, &instance, &witness));
+            running = (instance, witness);
+        }
+
+        // a wrong K(X) changes the folded error term
+        let (_, witness, mut proof) = protogalaxy
+            .prove(
+                &r1cs,
+                (&running.0, &running.1),
+                &instances,
+                &mut Transcript::new(b"protogalaxy"),
+            )
+            .unwrap();
+        proof.k_coefficients[0] += Fr::one();
+        let incoming = instances.iter().map(|(u, _)| u.clone()).collect::<Vec<_>>();
+        let verified = ProtoGalaxy::verify(
+            &running.0,
+            &incoming,
+            &proof,
+            &mut Transcript::new(b"protogalaxy"),
+        )
+        .unwrap();
+        assert!(!protogalaxy.is_satisfied(&r1cs, &verified, &witness));
+
+        // an incoming instance with a wrong witness: G(X) - F(alpha) * L_0(X) doesn't vanish over the domain
+        instances[0].1.w[0] += Fr::one();
+        assert!(protogalaxy
+            .prove(
+                &r1cs,
+                (&running.0, &running.1),
+                &instances,
+                &mut Transcript::new(b"protogalaxy"),
+            )
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_protogalaxy_fold_4_instances() {
+        fold_pythagore_instances(4);
+    }
+
+    #[test]
+    pub fn test_protogalaxy_fold_8_instances() {
+        fold_pythagore_instances(8);
+    }
+}
@@ -0,0 +1,131 @@
+// Standard step circuits for the ivc and the folding benchmarks:
+// - counter: z_{i+1} = z_i + 1,
+// - fibonacci: (a, b) -> (b, a + b),
+//   both linear, so their step r1cs only holds the constraints binding z_{i+1} to the public io,
+// - mimc chain: z_{i+1} = mimc^n(z_i), n applications of the keyless mimc permutation,
+//   ~3 n log_alpha(p) constraints.
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::ivc::StepCircuit;
+use crate::crypto::mimc::{gadgets::permute_gadget, MiMCParameters};
+
+/// z_{i+1} = z_i + 1
+#[derive(Clone, Debug, Default)]
+pub struct CounterCircuit;
+
+impl<F: PrimeField> StepCircuit<F> for CounterCircuit {
+    fn state_len(&self) -> usize {
+        1
+    }
+
+    fn output(&self, z_i: &[F]) -> Vec<F> {
+        vec![z_i[0] + F::ONE]
+    }
+
+    fn generate_step_constraints(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        z_i: &[FpVar<F>],
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![&z_i[0] + F::ONE])
+    }
+}
+
+/// (a, b) -> (b, a + b)
+#[derive(Clone, Debug, Default)]
+pub struct FibonacciCircuit;
+
+impl<F: PrimeField> StepCircuit<F> for FibonacciCircuit {
+    fn state_len(&self) -> usize {
+        2
+    }
+
+    fn output(&self, z_i: &[F]) -> Vec<F> {
+        vec![z_i[1], z_i[0] + z_i[1]]
+    }
+
+    fn generate_step_constraints(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        z_i: &[FpVar<F>],
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![z_i[1].clone(), &z_i[0] + &z_i[1]])
+    }
+}
+
+/// z_{i+1} = mimc^n_hashes(z_i)
+#[derive(Clone, Debug)]
+pub struct MiMCChainCircuit<F: PrimeField> {
+    n_hashes: usize,
+    parameters: MiMCParameters<F>,
+}
+
+impl<F: PrimeField> MiMCChainCircuit<F> {
+    pub fn new(n_hashes: usize) -> Self {
+        Self {
+            n_hashes,
+            parameters: MiMCParameters::new(),
+        }
+    }
+}
+
+impl<F: PrimeField> StepCircuit<F> for MiMCChainCircuit<F> {
+    fn state_len(&self) -> usize {
+        1
+    }
+
+    fn output(&self, z_i: &[F]) -> Vec<F> {
+        let z = (0..self.n_hashes).fold(z_i[0], |z, _| self.parameters.permute(z));
+        vec![z]
+    }
+
+    fn generate_step_constraints(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        z_i: &[FpVar<F>],
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut z = z_i[0].clone();
+        for _ in 0..self.n_hashes {
+            z = permute_gadget(&self.parameters, &z)?;
+        }
+        Ok(vec![z])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CounterCircuit, FibonacciCircuit, MiMCChainCircuit};
+    use crate::folding::ivc::{StepCircuit, IVC};
+    use ark_pallas::{Fr, Projective};
+
+    /// Runs `n_steps` steps from z_0, checking z_i against the native output at each step
+    fn run<SC: StepCircuit<Fr>>(step_circuit: SC, z_0: Vec<Fr>, n_steps: usize) -> Vec<Fr> {
+        let mut ivc = IVC::<Projective, _>::new(step_circuit.clone(), z_0.clone()).unwrap();
+        let mut z = z_0;
+        for _ in 0..n_steps {
+            let z_i_plus_1 = ivc.prove_step(&z).unwrap();
+            z = step_circuit.output(&z);
+            assert_eq!(z_i_plus_1, z);
+        }
+        assert!(ivc.decide());
+        z
+    }
+
+    #[test]
+    pub fn test_step_circuits() {
+        assert_eq!(run(CounterCircuit, vec![Fr::from(7)], 4), [Fr::from(11)]);
+        assert_eq!(
+            run(FibonacciCircuit, vec![Fr::from(0), Fr::from(1)], 10),
+            [Fr::from(55), Fr::from(89)]
+        );
+
+        let mimc = MiMCChainCircuit::<Fr>::new(2);
+        let z = run(mimc.clone(), vec![Fr::from(1)], 3);
+        let once = MiMCChainCircuit::<Fr>::new(1);
+        let twice = once.output(&once.output(&[Fr::from(1)]));
+        assert_eq!(mimc.output(&[Fr::from(1)]), twice);
+        assert_ne!(z, [Fr::from(1)]);
+    }
+}
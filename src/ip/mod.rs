@@ -1 +1,3 @@
-pub mod sumcheck;
\ No newline at end of file
+pub mod permcheck;
+pub mod sumcheck;
+pub mod zerocheck;
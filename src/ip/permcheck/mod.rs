@@ -0,0 +1,262 @@
+// Multiset and permutation checks over the hypercube
+// Multiset: {f(x)} == {g(x)} iff sum_x 1 / (gamma + f(x)) == sum_x 1 / (gamma + g(x)) for a random gamma
+// (logarithmic derivatives, https://eprint.iacr.org/2022/1530). The prover provides the inverses h_f, h_g,
+// a zerocheck shows h_f * (gamma + f) - 1 and h_g * (gamma + g) - 1 vanish (batched with alpha),
+// and a sumcheck shows sum_x h_f(x) - h_g(x) = 0.
+// Permutation: g(x) = f(sigma(x)) iff {(f(x), x)} == {(g(x), sigma(x))}, the pairs being compressed as
+// f(x) + beta * id(x) and g(x) + beta * sigma(x) with a random beta, id(x) = sum_k 2^k * x_k.
+// In an IOP, f, g, sigma, h_f and h_g are oracles (commitments): they should be absorbed in the transcript
+// before the corresponding challenges, and the subclaims are checked by opening them.
+use ark_ff::{batch_inversion, PrimeField};
+
+use crate::ip::sumcheck::{
+    protocol::{self, SumcheckProof, SumcheckSubClaim, VirtualPolynomial},
+    Mle,
+};
+use crate::ip::zerocheck::{self, ZeroCheckProof, ZeroCheckSubClaim};
+use crate::transcript::Transcript;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultisetCheckProof<F: PrimeField> {
+    pub zerocheck_proof: ZeroCheckProof<F>,
+    pub sumcheck_proof: SumcheckProof<F>,
+}
+
+/// Evaluation claims left to the verifier: one on f, g, h_f, h_g at the zerocheck point,
+/// one on h_f, h_g at the sumcheck point
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultisetCheckSubClaim<F: PrimeField> {
+    pub gamma: F,
+    pub alpha: F,
+    pub zerocheck_subclaim: ZeroCheckSubClaim<F>,
+    pub sumcheck_subclaim: SumcheckSubClaim<F>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PermutationCheckSubClaim<F: PrimeField> {
+    pub beta: F,
+    pub multiset_subclaim: MultisetCheckSubClaim<F>,
+}
+
+impl<F: PrimeField> MultisetCheckSubClaim<F> {
+    /// Checks the evaluations of f, g, h_f and h_g at the zerocheck point
+    pub fn check_zerocheck(&self, f: F, g: F, h_f: F, h_g: F) -> bool {
+        h_f * (self.gamma + f) - F::one() + self.alpha * (h_g * (self.gamma + g) - F::one())
+            == self.zerocheck_subclaim.expected_evaluation
+    }
+
+    /// Checks the evaluations of h_f and h_g at the sumcheck point
+    pub fn check_sumcheck(&self, h_f: F, h_g: F) -> bool {
+        h_f - h_g == self.sumcheck_subclaim.expected_evaluation
+    }
+}
+
+impl<F: PrimeField> PermutationCheckSubClaim<F> {
+    /// Checks the evaluations of f, g, sigma, h_f and h_g at the zerocheck point
+    pub fn check_zerocheck(&self, f: F, g: F, sigma: F, h_f: F, h_g: F) -> bool {
+        let point = &self.multiset_subclaim.zerocheck_subclaim.point;
+        let id = identity_evaluation(point);
+        self.multiset_subclaim
+            .check_zerocheck(f + self.beta * id, g + self.beta * sigma, h_f, h_g)
+    }
+
+    pub fn check_sumcheck(&self, h_f: F, h_g: F) -> bool {
+        self.multiset_subclaim.check_sumcheck(h_f, h_g)
+    }
+}
+
+/// id(point) = sum_k 2^k * point_k, the multilinear extension of x -> x over the hypercube
+fn identity_evaluation<F: PrimeField>(point: &[F]) -> F {
+    point
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, x_k| acc.double() + x_k)
+}
+
+fn inverses<F: PrimeField>(mle: &Mle<F>, gamma: F) -> Mle<F> {
+    let mut inverses = mle
+        .evaluations()
+        .iter()
+        .map(|v| gamma + v)
+        .collect::<Vec<_>>();
+    batch_inversion(&mut inverses);
+    Mle::new(inverses)
+}
+
+/// Proves that the evaluations of f and g over the hypercube are equal as multisets
+/// Returns the proof and the inverses h_f, h_g the subclaims refer to.
+pub fn prove_multiset<F: PrimeField>(
+    f: &Mle<F>,
+    g: &Mle<F>,
+    transcript: &mut Transcript,
+) -> (MultisetCheckProof<F>, Mle<F>, Mle<F>) {
+    assert_eq!(f.num_vars(), g.num_vars());
+    let num_vars = f.num_vars();
+    let gamma: F = transcript.challenge();
+    let h_f = inverses(f, gamma);
+    let h_g = inverses(g, gamma);
+    let alpha: F = transcript.challenge();
+
+    let mut q = VirtualPolynomial::new(num_vars);
+    let i_f = q.add_mle(f.clone());
+    let i_g = q.add_mle(g.clone());
+    let i_h_f = q.add_mle(h_f.clone());
+    let i_h_g = q.add_mle(h_g.clone());
+    q.add_product(F::one(), &[i_h_f, i_f]);
+    q.add_product(gamma, &[i_h_f]);
+    q.add_product(alpha, &[i_h_g, i_g]);
+    q.add_product(alpha * gamma, &[i_h_g]);
+    q.add_product(-F::one() - alpha, &[]);
+    let (zerocheck_proof, _) = zerocheck::prove(&q, transcript);
+
+    let mut sum = VirtualPolynomial::new(num_vars);
+    let i_h_f = sum.add_mle(h_f.clone());
+    let i_h_g = sum.add_mle(h_g.clone());
+    sum.add_product(F::one(), &[i_h_f]);
+    sum.add_product(-F::one(), &[i_h_g]);
+    let (sumcheck_proof, _) = protocol::prove(&sum, transcript);
+
+    let proof = MultisetCheckProof {
+        zerocheck_proof,
+        sumcheck_proof,
+    };
+    (proof, h_f, h_g)
+}
+
+pub fn verify_multiset<F: PrimeField>(
+    num_vars: usize,
+    proof: &MultisetCheckProof<F>,
+    transcript: &mut Transcript,
+) -> Option<MultisetCheckSubClaim<F>> {
+    let gamma: F = transcript.challenge();
+    let alpha: F = transcript.challenge();
+    let zerocheck_subclaim = zerocheck::verify(num_vars, 2, &proof.zerocheck_proof, transcript)?;
+    let sumcheck_subclaim =
+        protocol::verify(num_vars, 1, F::zero(), &proof.sumcheck_proof, transcript)?;
+    Some(MultisetCheckSubClaim {
+        gamma,
+        alpha,
+        zerocheck_subclaim,
+        sumcheck_subclaim,
+    })
+}
+
+/// Proves that g(x) = f(sigma(x)) over the hypercube, sigma being a permutation of the indices
+/// Returns the proof and the inverses h_f, h_g of the underlying multiset check.
+pub fn prove_permutation<F: PrimeField>(
+    f: &Mle<F>,
+    g: &Mle<F>,
+    sigma: &[usize],
+    transcript: &mut Transcript,
+) -> (MultisetCheckProof<F>, Mle<F>, Mle<F>) {
+    assert_eq!(sigma.len(), f.evaluations().len());
+    let beta: F = transcript.challenge();
+    let f_id = f
+        .evaluations()
+        .iter()
+        .enumerate()
+        .map(|(i, f_i)| *f_i + beta * F::from(i as u64))
+        .collect::<Vec<_>>();
+    let g_sigma = g
+        .evaluations()
+        .iter()
+        .zip(sigma)
+        .map(|(g_i, s_i)| *g_i + beta * F::from(*s_i as u64))
+        .collect::<Vec<_>>();
+    prove_multiset(&Mle::new(f_id), &Mle::new(g_sigma), transcript)
+}
+
+pub fn verify_permutation<F: PrimeField>(
+    num_vars: usize,
+    proof: &MultisetCheckProof<F>,
+    transcript: &mut Transcript,
+) -> Option<PermutationCheckSubClaim<F>> {
+    let beta: F = transcript.challenge();
+    let multiset_subclaim = verify_multiset(num_vars, proof, transcript)?;
+    Some(PermutationCheckSubClaim {
+        beta,
+        multiset_subclaim,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove_multiset, prove_permutation, verify_multiset, verify_permutation};
+    use crate::ip::sumcheck::Mle;
+    use crate::transcript::Transcript;
+    use ark_ff::UniformRand;
+    use ark_pallas::Fr;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_multiset_check() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let f = (0..1 << num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        // g holds the same values in another order, with a repeated value in both
+        let mut f_repeated = f.clone();
+        f_repeated[7] = f_repeated[0];
+        let g = Mle::new(f_repeated.iter().rev().cloned().collect());
+        let (f, f_repeated) = (Mle::new(f), Mle::new(f_repeated));
+
+        let (proof, h_f, h_g) = prove_multiset(&f_repeated, &g, &mut Transcript::new(b"test"));
+        let subclaim = verify_multiset(num_vars, &proof, &mut Transcript::new(b"test")).unwrap();
+        let z = &subclaim.zerocheck_subclaim.point;
+        let s = &subclaim.sumcheck_subclaim.point;
+        assert!(subclaim.check_zerocheck(
+            f_repeated.evaluate(z),
+            g.evaluate(z),
+            h_f.evaluate(z),
+            h_g.evaluate(z)
+        ));
+        assert!(subclaim.check_sumcheck(h_f.evaluate(s), h_g.evaluate(s)));
+
+        // different multisets: the inverses don't sum to the same value
+        let (proof, h_f, h_g) = prove_multiset(&f, &g, &mut Transcript::new(b"test"));
+        let subclaim = verify_multiset(num_vars, &proof, &mut Transcript::new(b"test"));
+        assert!(subclaim.is_none_or(|subclaim| {
+            let s = &subclaim.sumcheck_subclaim.point;
+            !subclaim.check_sumcheck(h_f.evaluate(s), h_g.evaluate(s))
+        }));
+    }
+
+    #[test]
+    pub fn test_permutation_check() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        let f = Mle::new((0..1 << num_vars).map(|_| Fr::rand(&mut rng)).collect());
+        let sigma = [3, 0, 6, 1, 7, 2, 5, 4];
+        let g = Mle::new(sigma.iter().map(|s| f.evaluations()[*s]).collect());
+        let sigma_evals = Mle::new(sigma.iter().map(|s| Fr::from(*s as u64)).collect());
+
+        let (proof, h_f, h_g) = prove_permutation(&f, &g, &sigma, &mut Transcript::new(b"test"));
+        let subclaim = verify_permutation(num_vars, &proof, &mut Transcript::new(b"test")).unwrap();
+        let z = &subclaim.multiset_subclaim.zerocheck_subclaim.point;
+        let s = &subclaim.multiset_subclaim.sumcheck_subclaim.point;
+        assert!(subclaim.check_zerocheck(
+            f.evaluate(z),
+            g.evaluate(z),
+            sigma_evals.evaluate(z),
+            h_f.evaluate(z),
+            h_g.evaluate(z)
+        ));
+        assert!(subclaim.check_sumcheck(h_f.evaluate(s), h_g.evaluate(s)));
+
+        // same multiset, but not the claimed permutation
+        let mut swapped = g.into_evaluations();
+        swapped.swap(0, 1);
+        let (proof, h_f, h_g) = prove_permutation(
+            &f,
+            &Mle::new(swapped),
+            &sigma,
+            &mut Transcript::new(b"test"),
+        );
+        let subclaim = verify_permutation(num_vars, &proof, &mut Transcript::new(b"test"));
+        assert!(subclaim.is_none_or(|subclaim| {
+            let s = &subclaim.multiset_subclaim.sumcheck_subclaim.point;
+            !subclaim.check_sumcheck(h_f.evaluate(s), h_g.evaluate(s))
+        }));
+    }
+}
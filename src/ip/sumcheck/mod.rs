@@ -1,8 +1,104 @@
-use ark_ff::PrimeField;
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::{
     multivariate::{SparsePolynomial, SparseTerm},
-    Polynomial,
+    DenseMultilinearExtension, Polynomial,
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::transcript::Transcript;
+use crate::utils::linear_algebra::Matrix;
+
+pub mod multivariate;
+pub mod protocol;
+
+/// What the mle utilities and the sumcheck protocol need from a field
+/// Implemented for all of ark's fields, and for the binary tower fields of `crate::fields::binary`. In
+/// characteristic 2 the integers 0, 1, 2, .. are not distinct field elements: round polynomials are
+/// evaluated at `from_index(0)`, `from_index(1)`, .. instead, which only have to be distinct.
+pub trait SumcheckField:
+    'static
+    + Copy
+    + Debug
+    + PartialEq
+    + Send
+    + Sync
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + for<'a> Add<&'a Self, Output = Self>
+    + for<'a> Sub<&'a Self, Output = Self>
+    + for<'a> Mul<&'a Self, Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + Sum
+    + for<'a> Sum<&'a Self>
+    + CanonicalSerialize
+    + CanonicalDeserialize
+{
+    /// The i-th evaluation point of round polynomials, 0 and 1 being the field's zero and one
+    fn from_index(i: u64) -> Self;
+
+    /// None for zero
+    fn invert(&self) -> Option<Self>;
+
+    /// A challenge drawn from the transcript
+    fn challenge(transcript: &mut Transcript) -> Self;
+}
+
+impl<F: Field> SumcheckField for F {
+    fn from_index(i: u64) -> Self {
+        F::from(i)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        self.inverse()
+    }
+
+    /// One challenge per coordinate over the base prime field, a single one for prime fields
+    fn challenge(transcript: &mut Transcript) -> Self {
+        let coordinates =
+            transcript.challenges::<F::BasePrimeField>(F::extension_degree() as usize);
+        F::from_base_prime_field_elems(&coordinates).unwrap()
+    }
+}
+
+/// A field E containing F, e.g. an extension of a small base field (Goldilocks, BabyBear, ..)
+/// Sumchecks over such fields keep the evaluations in F and draw challenges from E, which is large enough for
+/// soundness: values are only lifted to E once a challenge binds their variable.
+pub trait ExtensionField<F: SumcheckField>: SumcheckField {
+    fn lift(base: F) -> Self;
+
+    /// self * base, which can be cheaper than lifting base first
+    fn mul_base(&self, base: &F) -> Self {
+        *self * Self::lift(*base)
+    }
+}
+
+/// ark's fields over their base prime field, including the prime fields themselves
+impl<E: Field> ExtensionField<E::BasePrimeField> for E {
+    fn lift(base: E::BasePrimeField) -> Self {
+        E::from_base_prime_field(base)
+    }
+}
+
+/// n * 1 in F, by double and add: `F::from(n)` is only available for ark's fields
+pub fn from_count<F: SumcheckField>(n: u64) -> F {
+    let mut result = F::zero();
+    for bit in (0..64 - n.leading_zeros()).rev() {
+        result += result;
+        if n >> bit & 1 == 1 {
+            result += F::one();
+        }
+    }
+    result
+}
 
 /// Utility types
 pub type HyperCube<F> = Vec<Vec<F>>;
@@ -20,11 +116,11 @@ pub fn get_hypercube_points<F: PrimeField>(v: usize) -> HyperCube<F> {
     points
 }
 
-pub fn sample_random_vector<F: PrimeField>(v: usize) -> Vec<F> {
-    let mut rng = ark_std::test_rng();
+/// Samples v field elements, e.g. a random point of the hypercube's extension
+pub fn sample_random_vector<F: PrimeField, R: Rng>(v: usize, rng: &mut R) -> Vec<F> {
     let mut x = Vec::with_capacity(v);
     for _ in 0..v {
-        x.push(F::rand(&mut rng));
+        x.push(F::rand(rng));
     }
     x
 }
@@ -35,12 +131,12 @@ pub fn get_evaluations_f_over_hypercube<F: PrimeField>(
 ) -> Vec<F> {
     let mut evaluations = Vec::with_capacity(h.len());
     for point in h {
-        evaluations.push(f.evaluate(&point));
+        evaluations.push(f.evaluate(point));
     }
     evaluations
 }
 
-pub fn compute_chi_w<F: PrimeField>(w: &Vec<F>, x: &Vec<F>) -> F {
+pub fn compute_chi_w<F: PrimeField>(w: &[F], x: &[F]) -> F {
     let mut chi_w = F::one();
     for (w_i, x_i) in w.iter().zip(x.iter()) {
         chi_w *= x_i.mul(w_i) + (F::one() - x_i) * (F::one() - w_i);
@@ -50,51 +146,34 @@ pub fn compute_chi_w<F: PrimeField>(w: &Vec<F>, x: &Vec<F>) -> F {
 
 /// Naive M.L.E. evaluations
 /// Follows Thaler's notation in Proofs, Args and zk (lemma 3.6.) f, w, Chi, x
-pub fn naive_mle_evaluation<F: PrimeField>(poly_evals: &Vec<F>, h: &HyperCube<F>, x: Vec<F>) -> F {
+pub fn naive_mle_evaluation<F: PrimeField>(poly_evals: &[F], h: &HyperCube<F>, x: Vec<F>) -> F {
     let mut sum = F::zero();
     for (point, coeff) in h.iter().zip(poly_evals.iter()) {
-        let chi_w = compute_chi_w::<F>(&point, &x);
+        let chi_w = compute_chi_w::<F>(point, &x);
         sum += *coeff * chi_w;
     }
     sum
 }
 
-pub fn binary_vec_to_usize<F: PrimeField>(binary_vec: &Vec<F>) -> usize {
-    let mut result = 0;
-    for (i, bit) in binary_vec.iter().enumerate() {
-        if bit == &F::one() {
-            result += 1 << i;
+/// Table of chi_w(r) = eq(w, r) for all w in the hypercube, bit j of w's index being variable j
+/// Follows Thaler's notation in Proofs, Args and zk (lemma 3.8.), built iteratively in a single 2^n buffer:
+/// at stage j, the 2^j entries so far are each split into the entries for w_j = 0 and w_j = 1.
+pub fn eq_table<F: SumcheckField>(r: &[F]) -> Vec<F> {
+    let mut table = vec![F::zero(); 1 << r.len()];
+    table[0] = F::one();
+    for (j, r_j) in r.iter().enumerate() {
+        let half = 1 << j;
+        // from the top, so that table[i] is read before being overwritten
+        for i in (0..half).rev() {
+            let high = table[i] * r_j;
+            table[i + half] = high;
+            table[i] -= high;
         }
     }
-    result
+    table
 }
 
-/// Implementing lemma 3.8
-/// At stage j, build table A^{j} of size 2^{j}
-/// Follows Thaler's notation in Proofs, Args and zk (lemma 3.8.)
-pub fn build_memoized_chi_table<F: PrimeField>(
-    stage: usize,
-    prev_table: Vec<F>,
-    h: &HyperCube<F>,
-    r: &Vec<F>,
-) -> Vec<F> {
-    let mut table = Vec::<F>::with_capacity(h.len());
-    for w in h {
-        let table_idx = binary_vec_to_usize::<F>(&w);
-        let a_j = prev_table[table_idx]
-            * (w[stage] * r[stage] + (F::one() - w[stage]) * (F::one() - r[stage]));
-        table.push(a_j);
-    }
-    if stage == r.len() - 1 {
-        return table;
-    }
-    build_memoized_chi_table(stage + 1, table, h, r)
-}
-
-pub fn memoized_mle_evaluation<F: PrimeField>(
-    poly_evals: &Vec<F>,
-    memoized_chi_table: &Vec<F>,
-) -> F {
+pub fn memoized_mle_evaluation<F: PrimeField>(poly_evals: &[F], memoized_chi_table: &[F]) -> F {
     let mut sum = F::zero();
     for (coeff, a_j) in poly_evals.iter().zip(memoized_chi_table.iter()) {
         sum += *coeff * a_j;
@@ -102,6 +181,276 @@ pub fn memoized_mle_evaluation<F: PrimeField>(
     sum
 }
 
+/// Binds the first variable of the mle given by `evals` to r: f(r, x_1, ..., x_{n-1})
+/// Pairs (f(0, x), f(1, x)) sit at indices (2i, 2i + 1), and fold into f(0, x) + r * (f(1, x) - f(0, x)).
+pub fn fix_variable<F: SumcheckField>(evals: &[F], r: F) -> Vec<F> {
+    assert!(evals.len().is_power_of_two() && evals.len() > 1);
+    evals
+        .chunks(2)
+        .map(|pair| pair[0] + r * (pair[1] - pair[0]))
+        .collect()
+}
+
+/// `fix_variable`, overwriting the first half of `evals` instead of allocating a new table
+pub fn fix_variable_in_place<F: SumcheckField>(evals: &mut Vec<F>, r: F) {
+    assert!(evals.len().is_power_of_two() && evals.len() > 1);
+    let half = evals.len() / 2;
+    for i in 0..half {
+        evals[i] = evals[2 * i] + r * (evals[2 * i + 1] - evals[2 * i]);
+    }
+    evals.truncate(half);
+}
+
+/// Evaluates the mle given by its 2^n evaluations at `point`, in O(2^n) by fixing one variable at a time
+pub fn evaluate_mle<F: SumcheckField>(evals: &[F], point: &[F]) -> F {
+    assert_eq!(evals.len(), 1 << point.len(), "wrong number of evaluations");
+    let mut table = evals.to_vec();
+    for r in point {
+        fix_variable_in_place(&mut table, *r);
+    }
+    table[0]
+}
+
+/// `fix_variable` for an mle over F and r in an extension E: the first round of a small field sumcheck,
+/// after which the evaluations are in E
+pub fn fix_variable_extension<F: SumcheckField, E: ExtensionField<F>>(evals: &[F], r: E) -> Vec<E> {
+    assert!(evals.len().is_power_of_two() && evals.len() > 1);
+    evals
+        .chunks(2)
+        .map(|pair| E::lift(pair[0]) + r.mul_base(&(pair[1] - pair[0])))
+        .collect()
+}
+
+/// `evaluate_mle` for an mle over F, at a point of an extension E
+pub fn evaluate_mle_extension<F: SumcheckField, E: ExtensionField<F>>(
+    evals: &[F],
+    point: &[E],
+) -> E {
+    assert_eq!(evals.len(), 1 << point.len(), "wrong number of evaluations");
+    match point.split_first() {
+        None => E::lift(evals[0]),
+        Some((r, rest)) => evaluate_mle(&fix_variable_extension(evals, *r), rest),
+    }
+}
+
+/// Evaluations of eq(t, b) over the hypercube, bit j of b's index being variable j, see `eq_table`
+pub fn eq_evaluations<F: SumcheckField>(t: &[F]) -> Vec<F> {
+    eq_table(t)
+}
+
+/// A multilinear polynomial of n variables, given by its 2^n evaluations over the hypercube
+/// (variable i is bit i of the evaluation index, as in ark's `DenseMultilinearExtension`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mle<F: SumcheckField> {
+    num_vars: usize,
+    evaluations: Vec<F>,
+}
+
+impl<F: SumcheckField> Mle<F> {
+    /// The number of variables is log2 of the number of evaluations
+    pub fn new(evaluations: Vec<F>) -> Self {
+        assert!(
+            evaluations.len().is_power_of_two(),
+            "the number of evaluations should be a power of two"
+        );
+        Self {
+            num_vars: evaluations.len().trailing_zeros() as usize,
+            evaluations,
+        }
+    }
+
+    pub fn constant(num_vars: usize, value: F) -> Self {
+        Self::new(vec![value; 1 << num_vars])
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn evaluations(&self) -> &[F] {
+        &self.evaluations
+    }
+
+    pub fn into_evaluations(self) -> Vec<F> {
+        self.evaluations
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        evaluate_mle(&self.evaluations, point)
+    }
+
+    /// Evaluation at a point of an extension of F
+    pub fn evaluate_extension<E: ExtensionField<F>>(&self, point: &[E]) -> E {
+        evaluate_mle_extension(&self.evaluations, point)
+    }
+
+    /// Binds the first variables to `partial_point`, the result has n - partial_point.len() variables
+    pub fn fix_variables(&self, partial_point: &[F]) -> Self {
+        assert!(partial_point.len() <= self.num_vars, "too many variables");
+        let mut evaluations = self.evaluations.clone();
+        for r in partial_point {
+            fix_variable_in_place(&mut evaluations, *r);
+        }
+        Self::new(evaluations)
+    }
+
+    /// Sums over the first k variables: x -> sum_{b in {0,1}^k} f(b, x)
+    pub fn partial_sum(&self, k: usize) -> Self {
+        assert!(k <= self.num_vars, "too many variables");
+        Self::new(
+            self.evaluations
+                .chunks(1 << k)
+                .map(|chunk| chunk.iter().sum())
+                .collect(),
+        )
+    }
+
+    /// Sum over the whole hypercube
+    pub fn sum(&self) -> F {
+        self.evaluations.iter().sum()
+    }
+
+    pub fn scalar_mul(&self, scalar: &F) -> Self {
+        Self::new(self.evaluations.iter().map(|e| *e * scalar).collect())
+    }
+}
+
+impl<F: SumcheckField> Add for &Mle<F> {
+    type Output = Mle<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.num_vars, rhs.num_vars);
+        Mle::new(
+            self.evaluations
+                .iter()
+                .zip(&rhs.evaluations)
+                .map(|(a, b)| *a + b)
+                .collect(),
+        )
+    }
+}
+
+impl<F: SumcheckField> Sub for &Mle<F> {
+    type Output = Mle<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.num_vars, rhs.num_vars);
+        Mle::new(
+            self.evaluations
+                .iter()
+                .zip(&rhs.evaluations)
+                .map(|(a, b)| *a - b)
+                .collect(),
+        )
+    }
+}
+
+/// Pointwise product over the hypercube: the result is the mle of f o g, not the (degree 2) product f * g
+impl<F: SumcheckField> Mul for &Mle<F> {
+    type Output = Mle<F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.num_vars, rhs.num_vars);
+        Mle::new(
+            self.evaluations
+                .iter()
+                .zip(&rhs.evaluations)
+                .map(|(a, b)| *a * b)
+                .collect(),
+        )
+    }
+}
+
+impl<F: PrimeField> From<DenseMultilinearExtension<F>> for Mle<F> {
+    fn from(mle: DenseMultilinearExtension<F>) -> Self {
+        Self {
+            num_vars: mle.num_vars,
+            evaluations: mle.evaluations,
+        }
+    }
+}
+
+impl<F: PrimeField> From<Mle<F>> for DenseMultilinearExtension<F> {
+    fn from(mle: Mle<F>) -> Self {
+        DenseMultilinearExtension::from_evaluations_vec(mle.num_vars, mle.evaluations)
+    }
+}
+
+/// A multilinear polynomial given by its non-zero evaluations over the hypercube, as (index, value) pairs
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMle<F: PrimeField> {
+    num_vars: usize,
+    entries: Vec<(usize, F)>,
+}
+
+impl<F: PrimeField> SparseMle<F> {
+    pub fn new(num_vars: usize, entries: Vec<(usize, F)>) -> Self {
+        assert!(
+            entries.iter().all(|(i, _)| *i < 1 << num_vars),
+            "index out of the hypercube"
+        );
+        Self { num_vars, entries }
+    }
+
+    /// M(x, y) = M[x][y]: the first log(rows) variables index the row, the next log(cols) ones the column,
+    /// both dimensions being padded to powers of two
+    pub fn from_matrix(matrix: &Matrix<F>) -> Self {
+        let row_vars = matrix.num_rows().next_power_of_two().trailing_zeros() as usize;
+        let col_vars = matrix.num_cols().next_power_of_two().trailing_zeros() as usize;
+        let mut entries = vec![];
+        for (i, row) in matrix.rows().iter().enumerate() {
+            for (j, value) in row.elements().iter().enumerate() {
+                if !value.is_zero() {
+                    entries.push((i + (j << row_vars), *value));
+                }
+            }
+        }
+        Self::new(row_vars + col_vars, entries)
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn entries(&self) -> &[(usize, F)] {
+        &self.entries
+    }
+
+    /// Evaluation at `point` in O(nnz * n): sum_i M_i * eq(i, point)
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        self.entries
+            .iter()
+            .map(|(index, value)| {
+                point.iter().enumerate().fold(*value, |acc, (k, r_k)| {
+                    if index >> k & 1 == 1 {
+                        acc * r_k
+                    } else {
+                        acc * (F::one() - r_k)
+                    }
+                })
+            })
+            .sum()
+    }
+
+    /// Binds the first variables to `partial_point`, the result being dense in the remaining variables
+    /// Runs in O(nnz + 2^k + 2^{n - k}), e.g. M(r_x, y) for Spartan's second sumcheck.
+    pub fn fix_variables(&self, partial_point: &[F]) -> Mle<F> {
+        let k = partial_point.len();
+        assert!(k <= self.num_vars, "too many variables");
+        let eq = eq_evaluations(partial_point);
+        let mut evaluations = vec![F::zero(); 1 << (self.num_vars - k)];
+        for (index, value) in self.entries.iter() {
+            evaluations[index >> k] += eq[index & ((1 << k) - 1)] * value;
+        }
+        Mle::new(evaluations)
+    }
+
+    pub fn to_dense(&self) -> Mle<F> {
+        self.fix_variables(&[])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,23 +470,114 @@ mod tests {
         let hypercube = get_hypercube_points::<Fr>(n_vars);
         let evaluations = get_evaluations_f_over_hypercube::<Fr>(&poly, &hypercube);
         let mle = DenseMultilinearExtension::from_evaluations_vec(n_vars, evaluations.clone());
-        let x = sample_random_vector::<Fr>(n_vars);
+        let x = sample_random_vector::<Fr, _>(n_vars, &mut rng);
         let naive_eval = naive_mle_evaluation::<Fr>(&evaluations, &hypercube, x.clone());
         let mle_eval = mle.evaluate(&x).unwrap();
         assert_eq!(naive_eval, mle_eval);
     }
 
     #[test]
-    fn test_build_memoized_chi_table() {
+    fn test_fix_variable_and_evaluate_mle() {
+        let mut rng = test_rng();
+        let n_vars = 5;
+        let mle = DenseMultilinearExtension::<Fr>::rand(n_vars, &mut rng);
+        let x = sample_random_vector::<Fr, _>(n_vars, &mut rng);
+        assert_eq!(
+            evaluate_mle(&mle.evaluations, &x),
+            mle.evaluate(&x).unwrap()
+        );
+
+        // fixing the first variable agrees with ark's fix_variables, in place or not
+        let fixed = fix_variable(&mle.evaluations, x[0]);
+        assert_eq!(fixed, mle.fix_variables(&x[..1]).evaluations);
+        let mut in_place = mle.evaluations.clone();
+        fix_variable_in_place(&mut in_place, x[0]);
+        assert_eq!(in_place, fixed);
+        assert_eq!(evaluate_mle(&fixed, &x[1..]), mle.evaluate(&x).unwrap());
+
+        // on the hypercube, the evaluation is read from the table
+        let hypercube = get_hypercube_points::<Fr>(n_vars);
+        assert_eq!(
+            evaluate_mle(&mle.evaluations, &hypercube[13]),
+            mle.evaluations[13]
+        );
+    }
+
+    #[test]
+    fn test_mle() {
+        let mut rng = test_rng();
+        let n_vars = 4;
+        let ark_mle = DenseMultilinearExtension::<Fr>::rand(n_vars, &mut rng);
+        let f = Mle::from(ark_mle.clone());
+        let g = Mle::new(sample_random_vector::<Fr, _>(1 << n_vars, &mut rng));
+        let x = sample_random_vector::<Fr, _>(n_vars, &mut rng);
+        assert_eq!(f.num_vars(), n_vars);
+        assert_eq!(f.evaluate(&x), ark_mle.evaluate(&x).unwrap());
+        assert_eq!(DenseMultilinearExtension::from(f.clone()), ark_mle);
+
+        // fixing variables
+        let fixed = f.fix_variables(&x[..2]);
+        assert_eq!(fixed.num_vars(), 2);
+        assert_eq!(fixed.evaluate(&x[2..]), f.evaluate(&x));
+
+        // add and sub are those of polynomials, mul is pointwise over the hypercube
+        assert_eq!((&f + &g).evaluate(&x), f.evaluate(&x) + g.evaluate(&x));
+        assert_eq!((&f - &g).evaluate(&x), f.evaluate(&x) - g.evaluate(&x));
+        let f_g = &f * &g;
+        assert_eq!(
+            f_g.evaluations()[5],
+            f.evaluations()[5] * g.evaluations()[5]
+        );
+        assert_eq!(f.scalar_mul(&x[0]).evaluate(&x), x[0] * f.evaluate(&x));
+
+        // partial sums
+        let partial = f.partial_sum(3);
+        assert_eq!(partial.num_vars(), 1);
+        assert_eq!(
+            partial.evaluations()[1],
+            f.evaluations()[8..].iter().sum::<Fr>()
+        );
+        assert_eq!(partial.sum(), f.sum());
+        assert_eq!(f.partial_sum(0), f);
+    }
+
+    #[test]
+    fn test_sparse_mle() {
+        let mut rng = test_rng();
+        // 3 x 3 matrix, padded to 4 x 4: 4 variables
+        let matrix = Matrix::new_from_vecs(&vec![
+            vec![Fr::from(1u64), Fr::from(0u64), Fr::from(2u64)],
+            vec![Fr::from(0u64), Fr::from(0u64), Fr::from(0u64)],
+            vec![Fr::from(0u64), Fr::from(3u64), Fr::from(0u64)],
+        ]);
+        let sparse = SparseMle::from_matrix(&matrix);
+        assert_eq!(sparse.num_vars(), 4);
+        assert_eq!(sparse.entries().len(), 3);
+        let dense = sparse.to_dense();
+        // M[2][1] sits at row 2 + (column 1 << 2)
+        assert_eq!(dense.evaluations()[2 + (1 << 2)], Fr::from(3u64));
+        assert_eq!(dense.sum(), Fr::from(6u64));
+
+        let x = sample_random_vector::<Fr, _>(4, &mut rng);
+        assert_eq!(sparse.evaluate(&x), dense.evaluate(&x));
+        let fixed_rows = sparse.fix_variables(&x[..2]);
+        assert_eq!(fixed_rows.num_vars(), 2);
+        assert_eq!(fixed_rows.evaluate(&x[2..]), sparse.evaluate(&x));
+    }
+
+    #[test]
+    fn test_eq_table() {
+        let mut rng = test_rng();
         let n_vars = 5;
-        let r = sample_random_vector::<Fr>(n_vars);
+        let r = sample_random_vector::<Fr, _>(n_vars, &mut rng);
         let hypercube = get_hypercube_points::<Fr>(n_vars);
-        let prev_table = vec![Fr::ONE; hypercube.len()];
-        let table = build_memoized_chi_table::<Fr>(0, prev_table, &hypercube, &r);
+        let table = eq_table::<Fr>(&r);
+        assert_eq!(table.len(), hypercube.len());
         for (i, w) in hypercube.iter().enumerate() {
-            let chi_w = compute_chi_w::<Fr>(&w, &r);
+            let chi_w = compute_chi_w::<Fr>(w, &r);
             assert_eq!(chi_w, table[i]);
         }
+        assert_eq!(eq_table::<Fr>(&[]), vec![Fr::ONE]);
     }
 
     #[test]
@@ -148,9 +588,8 @@ mod tests {
         let hypercube = get_hypercube_points::<Fr>(n_vars);
         let evaluations = get_evaluations_f_over_hypercube::<Fr>(&poly, &hypercube);
         let mle = DenseMultilinearExtension::from_evaluations_vec(n_vars, evaluations.clone());
-        let x = sample_random_vector::<Fr>(n_vars);
-        let chi_table =
-            build_memoized_chi_table::<Fr>(0, vec![Fr::ONE; hypercube.len()], &hypercube, &x);
+        let x = sample_random_vector::<Fr, _>(n_vars, &mut rng);
+        let chi_table = eq_table::<Fr>(&x);
         let memoized_eval = memoized_mle_evaluation::<Fr>(&evaluations, &chi_table);
         let mle_eval = mle.evaluate(&x).unwrap();
         assert_eq!(memoized_eval, mle_eval);
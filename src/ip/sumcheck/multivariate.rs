@@ -0,0 +1,147 @@
+// Univariate restrictions of a multivariate polynomial p of n variables, given by its terms
+// - along a coordinate: X_i free and the other variables fixed, p(c_0, .., c_{i-1}, X, c_{i+1}, ..),
+// - along a line through a and b: p(a + X * (b - a)), e.g. to reduce two claims on p to one,
+// - as a sumcheck round polynomial, with the first i variables bound to the challenges r_0, .., r_{i-1}:
+//   g_i(X) = sum_{b in {0,1}^{n-i-1}} p(r_0, .., r_{i-1}, X, b), variable i being bit i of b's index.
+use ark_ff::{Field, PrimeField};
+use ark_poly::{
+    multivariate::{SparsePolynomial, SparseTerm},
+    univariate::DensePolynomial,
+    DenseUVPolynomial,
+};
+
+/// The maximum power of `variable` in the terms of p
+fn degree_in<F: Field>(poly: &SparsePolynomial<F, SparseTerm>, variable: usize) -> usize {
+    poly.terms
+        .iter()
+        .flat_map(|(_, term)| term.iter())
+        .filter(|(var, _)| *var == variable)
+        .map(|(_, exponent)| *exponent)
+        .max()
+        .unwrap_or(0)
+}
+
+/// p(c_0, .., c_{i-1}, X, c_{i+1}, ..), `point` holding n values, the one of `variable` being ignored
+pub fn restrict_to_variable<F: PrimeField>(
+    poly: &SparsePolynomial<F, SparseTerm>,
+    variable: usize,
+    point: &[F],
+) -> DensePolynomial<F> {
+    assert_eq!(point.len(), poly.num_vars, "expected a value per variable");
+    let mut coeffs = vec![F::zero(); degree_in(poly, variable) + 1];
+    for (coeff, term) in &poly.terms {
+        let mut scalar = *coeff;
+        let mut power = 0;
+        for (var, exponent) in term.iter() {
+            if *var == variable {
+                power = *exponent;
+            } else {
+                scalar *= point[*var].pow([*exponent as u64]);
+            }
+        }
+        coeffs[power] += scalar;
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// p(a + X * (b - a)), of degree at most the total degree of p
+pub fn restrict_to_line<F: PrimeField>(
+    poly: &SparsePolynomial<F, SparseTerm>,
+    a: &[F],
+    b: &[F],
+) -> DensePolynomial<F> {
+    assert!(a.len() == poly.num_vars && b.len() == poly.num_vars);
+    let lines = a
+        .iter()
+        .zip(b)
+        .map(|(a_i, b_i)| DensePolynomial::from_coefficients_vec(vec![*a_i, *b_i - a_i]))
+        .collect::<Vec<_>>();
+    let mut result = DensePolynomial::from_coefficients_vec(vec![]);
+    for (coeff, term) in &poly.terms {
+        let mut product = DensePolynomial::from_coefficients_vec(vec![*coeff]);
+        for (var, exponent) in term.iter() {
+            for _ in 0..*exponent {
+                product = &product * &lines[*var];
+            }
+        }
+        result += &product;
+    }
+    result
+}
+
+/// The round polynomial g_i, i being the number of `challenges`
+pub fn sumcheck_round_polynomial<F: PrimeField>(
+    poly: &SparsePolynomial<F, SparseTerm>,
+    challenges: &[F],
+) -> DensePolynomial<F> {
+    let i = challenges.len();
+    assert!(i < poly.num_vars, "all the variables are already bound");
+    let n_free = poly.num_vars - i - 1;
+    let mut point = challenges.to_vec();
+    point.resize(poly.num_vars, F::zero());
+    let mut round_polynomial = DensePolynomial::from_coefficients_vec(vec![]);
+    for b in 0..1usize << n_free {
+        for j in 0..n_free {
+            point[i + 1 + j] = F::from((b >> j & 1) as u64);
+        }
+        round_polynomial += &restrict_to_variable(poly, i, &point);
+    }
+    round_polynomial
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{restrict_to_line, restrict_to_variable, sumcheck_round_polynomial};
+    use crate::ip::sumcheck::{get_evaluations_f_over_hypercube, get_hypercube_points};
+    use ark_ff::UniformRand;
+    use ark_pallas::Fr;
+    use ark_poly::{
+        multivariate::{SparsePolynomial, SparseTerm},
+        DenseMVPolynomial, Polynomial,
+    };
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_multivariate_restrictions() {
+        let mut rng = test_rng();
+        let n_vars = 4;
+        let poly: SparsePolynomial<Fr, SparseTerm> = SparsePolynomial::rand(3, n_vars, &mut rng);
+        let mut rand_point = || (0..n_vars).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (a, b) = (rand_point(), rand_point());
+        let t = Fr::rand(&mut test_rng());
+
+        let restricted = restrict_to_variable(&poly, 2, &a);
+        let mut moved = a.clone();
+        moved[2] = t;
+        assert_eq!(restricted.evaluate(&t), poly.evaluate(&moved));
+
+        let line = restrict_to_line(&poly, &a, &b);
+        assert!(line.degree() <= 3);
+        assert_eq!(line.evaluate(&Fr::from(0u64)), poly.evaluate(&a));
+        assert_eq!(line.evaluate(&Fr::from(1u64)), poly.evaluate(&b));
+        let on_line = a
+            .iter()
+            .zip(&b)
+            .map(|(a_i, b_i)| *a_i + t * (*b_i - a_i))
+            .collect::<Vec<_>>();
+        assert_eq!(line.evaluate(&t), poly.evaluate(&on_line));
+
+        // the rounds of a sumcheck on the sum over the hypercube
+        let hypercube = get_hypercube_points::<Fr>(n_vars);
+        let mut claim: Fr = get_evaluations_f_over_hypercube(&poly, &hypercube)
+            .iter()
+            .sum();
+        let mut challenges = vec![];
+        for _ in 0..n_vars {
+            let g = sumcheck_round_polynomial(&poly, &challenges);
+            assert_eq!(
+                g.evaluate(&Fr::from(0u64)) + g.evaluate(&Fr::from(1u64)),
+                claim
+            );
+            let r = Fr::rand(&mut rng);
+            claim = g.evaluate(&r);
+            challenges.push(r);
+        }
+        assert_eq!(poly.evaluate(&challenges), claim);
+    }
+}
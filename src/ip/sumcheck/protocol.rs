@@ -0,0 +1,405 @@
+// Sumcheck protocol for sums of products of multilinear polynomials
+// The prover claims s = sum_{x in {0,1}^n} g(x), with g(x) = sum_j c_j * prod_{k in P_j} f_k(x).
+// In round i, it sends g_i(X) = sum over the remaining hypercube of g(r_0, .., r_{i-1}, X, x_{i+1}, ..), as
+// its evaluations at 0..=d (d the degree of g; `SumcheckField::from_index` gives distinct points in binary
+// fields). The verifier checks g_i(0) + g_i(1) against the running claim, and replaces the claim by g_i(r_i).
+// It ends with the subclaim g(r) = expected, which has to be checked with an oracle to the f_k (e.g. pcs
+// openings).
+// Variables are bound in order: variable i is bit i of the evaluation index.
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use super::{
+    fix_variable_extension, fix_variable_in_place, from_count, ExtensionField, Mle, SumcheckField,
+};
+use crate::transcript::Transcript;
+
+/// g(x) = sum_j c_j * prod_{k in P_j} f_k(x), the f_k being multilinear
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualPolynomial<F: SumcheckField> {
+    num_vars: usize,
+    mles: Vec<Mle<F>>,
+    products: Vec<(F, Vec<usize>)>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct SumcheckProof<F: SumcheckField> {
+    /// Evaluations of each round polynomial at 0, 1, .., d
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde::vec_vec"))]
+    pub round_polynomials: Vec<Vec<F>>,
+}
+
+/// What is left to check once the sumcheck verifier accepts: g(point) == expected_evaluation
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumcheckSubClaim<F: SumcheckField> {
+    pub point: Vec<F>,
+    pub expected_evaluation: F,
+}
+
+/// Evaluates at r the polynomial of degree < evals.len() taking values evals[i] at `F::from_index(i)`
+pub(crate) fn interpolate_at<F: SumcheckField>(evals: &[F], r: F) -> F {
+    let points = (0..evals.len() as u64)
+        .map(F::from_index)
+        .collect::<Vec<_>>();
+    let mut result = F::zero();
+    for (i, y_i) in evals.iter().enumerate() {
+        let mut numerator = F::one();
+        let mut denominator = F::one();
+        for (j, x_j) in points.iter().enumerate() {
+            if i != j {
+                numerator *= r - x_j;
+                denominator *= points[i] - x_j;
+            }
+        }
+        result += *y_i * numerator * denominator.invert().unwrap();
+    }
+    result
+}
+
+impl<F: SumcheckField> VirtualPolynomial<F> {
+    pub fn new(num_vars: usize) -> Self {
+        Self {
+            num_vars,
+            mles: vec![],
+            products: vec![],
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// Adds a multilinear polynomial of n variables, returns its index
+    pub fn add_mle(&mut self, mle: Mle<F>) -> usize {
+        assert_eq!(mle.num_vars(), self.num_vars, "wrong number of variables");
+        self.mles.push(mle);
+        self.mles.len() - 1
+    }
+
+    /// Adds the term c * prod_{k in indices} f_k, an empty product being the constant c
+    pub fn add_product(&mut self, coefficient: F, indices: &[usize]) {
+        assert!(indices.iter().all(|k| *k < self.mles.len()), "unknown mle");
+        self.products.push((coefficient, indices.to_vec()));
+    }
+
+    /// g * f, for f multilinear
+    pub fn mul_by_mle(&self, mle: Mle<F>) -> Self {
+        let mut result = self.clone();
+        let k = result.add_mle(mle);
+        for (_, indices) in result.products.iter_mut() {
+            indices.push(k);
+        }
+        result
+    }
+
+    /// Degree of g in each variable
+    pub fn degree(&self) -> usize {
+        self.products
+            .iter()
+            .map(|(_, indices)| indices.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn combine(&self, values: &[F]) -> F {
+        combine(&self.products, values)
+    }
+
+    /// g(point), from the evaluations of the f_k at point
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars);
+        let values = self
+            .mles
+            .iter()
+            .map(|mle| mle.evaluate(point))
+            .collect::<Vec<_>>();
+        self.combine(&values)
+    }
+
+    /// g(point) for a point of an extension of F
+    pub fn evaluate_extension<E: ExtensionField<F>>(&self, point: &[E]) -> E {
+        assert_eq!(point.len(), self.num_vars);
+        let values = self
+            .mles
+            .iter()
+            .map(|mle| mle.evaluate_extension(point))
+            .collect::<Vec<_>>();
+        combine(&self.lift_products(), &values)
+    }
+
+    fn lift_products<E: ExtensionField<F>>(&self) -> Vec<(E, Vec<usize>)> {
+        self.products
+            .iter()
+            .map(|(c, indices)| (E::lift(*c), indices.clone()))
+            .collect()
+    }
+
+    pub fn sum_over_hypercube(&self) -> F {
+        (0..1 << self.num_vars)
+            .map(|x| {
+                let values = self
+                    .mles
+                    .iter()
+                    .map(|mle| mle.evaluations()[x])
+                    .collect::<Vec<_>>();
+                self.combine(&values)
+            })
+            .sum()
+    }
+}
+
+fn combine<F: SumcheckField>(products: &[(F, Vec<usize>)], values: &[F]) -> F {
+    products
+        .iter()
+        .map(|(c, indices)| indices.iter().fold(*c, |acc, k| acc * values[*k]))
+        .sum()
+}
+
+/// Evaluations at 0..=degree of the round polynomial, `mles` holding the tables of the still free variables
+fn round_polynomial<F: SumcheckField>(
+    products: &[(F, Vec<usize>)],
+    mles: &[Vec<F>],
+    degree: usize,
+    num_free_vars: usize,
+) -> Vec<F> {
+    if mles.is_empty() {
+        // only constants: g does not depend on the variables
+        let remaining = 1u64 << (num_free_vars - 1);
+        return vec![combine(products, &[]) * from_count::<F>(remaining); degree + 1];
+    }
+    let half = mles[0].len() / 2;
+    let mut round_polynomial = vec![F::zero(); degree + 1];
+    let mut values = vec![F::zero(); mles.len()];
+    for b in 0..half {
+        // f_k(t, b) = f_k(0, b) + t * (f_k(1, b) - f_k(0, b))
+        for (t, evaluation) in round_polynomial.iter_mut().enumerate() {
+            let t = F::from_index(t as u64);
+            for (value, evals) in values.iter_mut().zip(mles.iter()) {
+                *value = evals[2 * b] + t * (evals[2 * b + 1] - evals[2 * b]);
+            }
+            *evaluation += combine(products, &values);
+        }
+    }
+    round_polynomial
+}
+
+/// Runs the remaining rounds, after the variables of `point` have been bound in `mles`
+fn prove_rounds<F: SumcheckField>(
+    products: &[(F, Vec<usize>)],
+    num_vars: usize,
+    degree: usize,
+    mut mles: Vec<Vec<F>>,
+    mut proof: SumcheckProof<F>,
+    mut point: Vec<F>,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<F>, Vec<F>) {
+    while point.len() < num_vars {
+        let round_polynomial = round_polynomial(products, &mles, degree, num_vars - point.len());
+        transcript.absorb(&round_polynomial);
+        let r = F::challenge(transcript);
+        for evals in mles.iter_mut() {
+            fix_variable_in_place(evals, r);
+        }
+        proof.round_polynomials.push(round_polynomial);
+        point.push(r);
+    }
+    (proof, point)
+}
+
+/// Proves the value of sum_x g(x), returns the proof and the point r the variables were bound to
+pub fn prove<F: SumcheckField>(
+    polynomial: &VirtualPolynomial<F>,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<F>, Vec<F>) {
+    // round polynomials have at least two evaluations, for g_i(0) + g_i(1)
+    let degree = polynomial.degree().max(1);
+    let mles = polynomial
+        .mles
+        .iter()
+        .map(|mle| mle.evaluations().to_vec())
+        .collect::<Vec<_>>();
+    let proof = SumcheckProof {
+        round_polynomials: Vec::with_capacity(polynomial.num_vars),
+    };
+    let point = Vec::with_capacity(polynomial.num_vars);
+    prove_rounds(
+        &polynomial.products,
+        polynomial.num_vars,
+        degree,
+        mles,
+        proof,
+        point,
+        transcript,
+    )
+}
+
+/// `prove` for g over a small field F, with challenges drawn from an extension E
+/// The first round runs entirely over F, binding its variable lifts the tables to E (half their original size),
+/// the next rounds run over E. The proof is verified by `verify` over E, against the lifted sum; the subclaim
+/// is checked with `VirtualPolynomial::evaluate_extension`.
+pub fn prove_small_field<F: SumcheckField, E: ExtensionField<F>>(
+    polynomial: &VirtualPolynomial<F>,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<E>, Vec<E>) {
+    let degree = polynomial.degree().max(1);
+    let products = polynomial.lift_products::<E>();
+    let mut proof = SumcheckProof {
+        round_polynomials: Vec::with_capacity(polynomial.num_vars),
+    };
+    let mut point = Vec::with_capacity(polynomial.num_vars);
+    if polynomial.num_vars == 0 {
+        return (proof, point);
+    }
+    let mles = polynomial
+        .mles
+        .iter()
+        .map(|mle| mle.evaluations().to_vec())
+        .collect::<Vec<_>>();
+    let first_round = round_polynomial(&polynomial.products, &mles, degree, polynomial.num_vars)
+        .into_iter()
+        .map(E::lift)
+        .collect::<Vec<_>>();
+    transcript.absorb(&first_round);
+    let r = E::challenge(transcript);
+    let mles = mles
+        .iter()
+        .map(|evals| fix_variable_extension(evals, r))
+        .collect();
+    proof.round_polynomials.push(first_round);
+    point.push(r);
+    prove_rounds(
+        &products,
+        polynomial.num_vars,
+        degree,
+        mles,
+        proof,
+        point,
+        transcript,
+    )
+}
+
+/// Checks the rounds of a proof that sum_x g(x) = claimed_sum, for g of n variables and degree d
+/// Returns the subclaim on g that remains to be checked, or None if a round is inconsistent.
+pub fn verify<F: SumcheckField>(
+    num_vars: usize,
+    degree: usize,
+    claimed_sum: F,
+    proof: &SumcheckProof<F>,
+    transcript: &mut Transcript,
+) -> Option<SumcheckSubClaim<F>> {
+    if proof.round_polynomials.len() != num_vars {
+        return None;
+    }
+    let degree = degree.max(1);
+    let mut claim = claimed_sum;
+    let mut point = Vec::with_capacity(num_vars);
+    for round_polynomial in &proof.round_polynomials {
+        if round_polynomial.len() != degree + 1
+            || round_polynomial[0] + round_polynomial[1] != claim
+        {
+            return None;
+        }
+        transcript.absorb(round_polynomial);
+        let r = F::challenge(transcript);
+        claim = interpolate_at(round_polynomial, r);
+        point.push(r);
+    }
+    Some(SumcheckSubClaim {
+        point,
+        expected_evaluation: claim,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove, prove_small_field, verify, VirtualPolynomial};
+    use crate::ip::sumcheck::{ExtensionField, Mle};
+    use crate::transcript::Transcript;
+    use ark_bn254::{Fq, Fq2};
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_pallas::Fr;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_sumcheck() {
+        let mut rng = test_rng();
+        let num_vars = 4;
+        // g = 3 * f_0 * f_1 * f_2 + f_1 - 5
+        let mut g = VirtualPolynomial::new(num_vars);
+        for _ in 0..3 {
+            g.add_mle(Mle::new(
+                (0..1 << num_vars).map(|_| Fr::rand(&mut rng)).collect(),
+            ));
+        }
+        g.add_product(Fr::from(3u64), &[0, 1, 2]);
+        g.add_product(Fr::one(), &[1]);
+        g.add_product(-Fr::from(5u64), &[]);
+        assert_eq!(g.degree(), 3);
+        let sum = g.sum_over_hypercube();
+
+        let (proof, point) = prove(&g, &mut Transcript::new(b"test"));
+        let subclaim = verify(num_vars, 3, sum, &proof, &mut Transcript::new(b"test")).unwrap();
+        assert_eq!(subclaim.point, point);
+        assert_eq!(subclaim.expected_evaluation, g.evaluate(&point));
+
+        // a wrong sum is caught in the rounds, a wrong last round polynomial only by the subclaim
+        assert!(verify(
+            num_vars,
+            3,
+            sum + Fr::one(),
+            &proof,
+            &mut Transcript::new(b"test")
+        )
+        .is_none());
+        let mut wrong_proof = proof.clone();
+        wrong_proof.round_polynomials[3][3] += Fr::one();
+        let subclaim = verify(
+            num_vars,
+            3,
+            sum,
+            &wrong_proof,
+            &mut Transcript::new(b"test"),
+        )
+        .unwrap();
+        assert_ne!(subclaim.expected_evaluation, g.evaluate(&subclaim.point));
+    }
+
+    #[test]
+    pub fn test_small_field_sumcheck() {
+        let mut rng = test_rng();
+        let num_vars = 4;
+        // g = f_0 * f_1 + 2 over the base field, challenges from its quadratic extension
+        let mut g = VirtualPolynomial::new(num_vars);
+        for _ in 0..2 {
+            g.add_mle(Mle::new(
+                (0..1 << num_vars).map(|_| Fq::rand(&mut rng)).collect(),
+            ));
+        }
+        g.add_product(Fq::one(), &[0, 1]);
+        g.add_product(Fq::from(2u64), &[]);
+        let sum = Fq2::lift(g.sum_over_hypercube());
+
+        let (proof, point) = prove_small_field::<Fq, Fq2>(&g, &mut Transcript::new(b"test"));
+        let subclaim = verify(num_vars, 2, sum, &proof, &mut Transcript::new(b"test")).unwrap();
+        assert_eq!(subclaim.point, point);
+        assert_eq!(subclaim.expected_evaluation, g.evaluate_extension(&point));
+        assert!(point.iter().any(|r| r.c1 != Fq::zero()));
+        assert!(verify(
+            num_vars,
+            2,
+            sum + Fq2::one(),
+            &proof,
+            &mut Transcript::new(b"test")
+        )
+        .is_none());
+
+        // with F as its own extension, this is the plain sumcheck
+        let (plain_proof, plain_point) = prove(&g, &mut Transcript::new(b"test"));
+        let (proof, point) = prove_small_field::<Fq, Fq>(&g, &mut Transcript::new(b"test"));
+        assert_eq!((proof, point), (plain_proof, plain_point));
+    }
+}
@@ -0,0 +1,111 @@
+// ZeroCheck: proves that g(x) = 0 for all x in the hypercube, g being a virtual polynomial
+// (https://eprint.iacr.org/2022/1355, section 3.2)
+// g vanishing on the hypercube is equivalent to its multilinear extension sum_x eq(X, x) * g(x) being zero,
+// so the verifier samples r and both run the sumcheck on eq(r, x) * g(x) with claimed sum 0.
+// The final subclaim eq(r, z) * g(z) = e becomes a claim on g alone: g(z) = e / eq(r, z).
+use ark_ff::PrimeField;
+
+use crate::ip::sumcheck::{
+    compute_chi_w, eq_evaluations,
+    protocol::{self, SumcheckProof, VirtualPolynomial},
+    Mle,
+};
+use crate::transcript::Transcript;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroCheckProof<F: PrimeField> {
+    pub sumcheck_proof: SumcheckProof<F>,
+}
+
+/// What is left to check once the zerocheck verifier accepts: g(point) == expected_evaluation
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroCheckSubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub expected_evaluation: F,
+}
+
+/// Proves that `polynomial` vanishes over the hypercube, returns the proof and the point of the subclaim
+pub fn prove<F: PrimeField>(
+    polynomial: &VirtualPolynomial<F>,
+    transcript: &mut Transcript,
+) -> (ZeroCheckProof<F>, Vec<F>) {
+    let r: Vec<F> = transcript.challenges(polynomial.num_vars());
+    let (sumcheck_proof, point) = protocol::prove(
+        &polynomial.mul_by_mle(Mle::new(eq_evaluations(&r))),
+        transcript,
+    );
+    (ZeroCheckProof { sumcheck_proof }, point)
+}
+
+/// Checks a zerocheck proof for a polynomial of n variables and degree d
+pub fn verify<F: PrimeField>(
+    num_vars: usize,
+    degree: usize,
+    proof: &ZeroCheckProof<F>,
+    transcript: &mut Transcript,
+) -> Option<ZeroCheckSubClaim<F>> {
+    let r: Vec<F> = transcript.challenges(num_vars);
+    let subclaim = protocol::verify(
+        num_vars,
+        degree + 1,
+        F::zero(),
+        &proof.sumcheck_proof,
+        transcript,
+    )?;
+    // eq(r, z) is zero with negligible probability
+    let eq_r_z = compute_chi_w(&r, &subclaim.point);
+    Some(ZeroCheckSubClaim {
+        expected_evaluation: subclaim.expected_evaluation * eq_r_z.inverse()?,
+        point: subclaim.point,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove, verify};
+    use crate::ip::sumcheck::{protocol::VirtualPolynomial, Mle};
+    use crate::transcript::Transcript;
+    use ark_ff::{One, UniformRand};
+    use ark_pallas::Fr;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_zerocheck() {
+        let mut rng = test_rng();
+        let num_vars = 3;
+        // g = f_0 * f_1 - f_2, with f_2 = f_0 o f_1 over the hypercube
+        let f_0 = (0..1 << num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let f_1 = (0..1 << num_vars)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let f_2 = f_0
+            .iter()
+            .zip(&f_1)
+            .map(|(a, b)| *a * b)
+            .collect::<Vec<_>>();
+        let mut g = VirtualPolynomial::new(num_vars);
+        let (i_0, i_1) = (g.add_mle(Mle::new(f_0)), g.add_mle(Mle::new(f_1.clone())));
+        let i_2 = g.add_mle(Mle::new(f_2));
+        g.add_product(Fr::one(), &[i_0, i_1]);
+        g.add_product(-Fr::one(), &[i_2]);
+
+        let (proof, point) = prove(&g, &mut Transcript::new(b"test"));
+        let subclaim = verify(num_vars, 2, &proof, &mut Transcript::new(b"test")).unwrap();
+        assert_eq!(subclaim.point, point);
+        assert_eq!(subclaim.expected_evaluation, g.evaluate(&point));
+
+        // a polynomial summing to zero without vanishing on the hypercube
+        let mut h = VirtualPolynomial::new(num_vars);
+        let mut f = f_1;
+        let sum: Fr = f.iter().sum();
+        f[0] -= sum;
+        let i = h.add_mle(Mle::new(f));
+        h.add_product(Fr::one(), &[i]);
+        assert_eq!(h.sum_over_hypercube(), Fr::from(0u64));
+        let (proof, _) = prove(&h, &mut Transcript::new(b"test"));
+        assert!(verify(num_vars, 1, &proof, &mut Transcript::new(b"test"))
+            .is_none_or(|subclaim| subclaim.expected_evaluation != h.evaluate(&subclaim.point)));
+    }
+}
@@ -1,5 +1,16 @@
 pub mod circuits;
+pub mod crypto;
 pub mod cs;
+pub mod curves;
+pub mod error;
+pub mod evm;
+pub mod fields;
 pub mod folding;
 pub mod ip;
+pub mod prelude;
+pub mod setup;
+pub mod snark;
+pub mod transcript;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,40 @@
+// Intended public surface of the crate: `use ark_algorithms::prelude::*;`
+pub use crate::circuits::air::{Air, BoundaryConstraint};
+pub use crate::circuits::ccs::CCS;
+pub use crate::circuits::qap::QAP;
+pub use crate::circuits::r1cs::R1CS;
+pub use crate::circuits::relaxed_r1cs::{R1CSRelaxed, R1CSRelaxedInstance, R1CSRelaxedWitness};
+pub use crate::crypto::mimc::MiMCParameters;
+pub use crate::crypto::poseidon::{PoseidonParameters, PoseidonSponge};
+pub use crate::crypto::rescue::RescueParameters;
+pub use crate::cs::obligations::{MsmTerm, Obligation, ObligationSet};
+pub use crate::cs::pcs::ipa::{IPAAccumulator, IPAProof, IPA};
+pub use crate::cs::pcs::kzg::{
+    shplonk::ShplonkProof, zeromorph::ZeromorphProof, KZGOpening, MultiOpenProof, KZG,
+};
+pub use crate::cs::pcs::mkzg::MultilinearKZG;
+pub use crate::cs::pedersen::Pedersen;
+pub use crate::cs::CommitmentScheme;
+pub use crate::curves::{Bn254Grumpkin, CurveCycle, PallasVesta};
+pub use crate::error::AlgorithmsError;
+pub use crate::folding::{
+    ivc::{StepCircuit, IVC},
+    nifs::{CommittedRelaxedInstance, RelaxedWitness, NIFS},
+    protogalaxy::{ProtoGalaxy, ProtoGalaxyInstance, ProtoGalaxyProof, ProtoGalaxyWitness},
+};
+pub use crate::ip::sumcheck::{
+    protocol::{SumcheckProof, VirtualPolynomial},
+    Mle,
+};
+pub use crate::snark::groth16::{pipeline::prove_circuit, Groth16, Groth16Proof};
+pub use crate::snark::pinocchio::{Pinocchio, PinocchioProof};
+pub use crate::snark::plonk::{Plonk, PlonkError, PlonkProof};
+pub use crate::snark::spartan::{SpartanProof, SpartanProver, SpartanVerifier};
+pub use crate::snark::stark::{Stark, StarkProof};
+pub use crate::transcript::{
+    bytes::{Blake2bTranscript, KeccakTranscript},
+    Transcript,
+};
+pub use crate::utils::curve::SubgroupCheck;
+pub use crate::utils::linear_algebra::{Matrix, Vector};
+pub use crate::utils::serialization::Artifact;
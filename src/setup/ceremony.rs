@@ -0,0 +1,562 @@
+// Simulation of a multi-party trusted setup ceremony, as in https://eprint.iacr.org/2017/1050
+// - phase 1 (powers of tau) is circuit independent, the accumulator holds [tau^i]_1 for i < 2n - 1,
+//   [tau^i]_2, [alpha * tau^i]_1, [beta * tau^i]_1 for i < n, and [beta]_2.
+//   Each participant samples (tau', alpha', beta') and multiplies them into the accumulator.
+//   Its first n powers of tau are a KZG srs.
+// - phase 2 is circuit specific: the groth16 srs of an r1cs is computed from the phase 1 output with delta = 1,
+//   then each participant samples delta' and divides the L and H queries by it.
+// The toxic waste stays unknown as long as a single participant deletes their secrets.
+// Each contribution comes with an update proof: [s]_1, [s]_2 and a schnorr proof of knowledge of s,
+// bound to the previous accumulator. The new accumulator is then checked to be the previous one updated by s,
+// e.g. e([tau * tau']_1, g_2) = e([tau]_1, [tau']_2), and to be well formed, each vector being a geometric
+// sequence: e(sum_i r_i [tau^i]_1, [tau]_2) = e(sum_i r_i [tau^{i+1}]_1, g_2), with r_i read from a transcript.
+use ark_ec::{pairing::Pairing, CurveGroup, Group};
+use ark_ff::{Field, UniformRand};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain};
+use ark_std::{rand::Rng, Zero};
+
+use crate::circuits::qap::QAP;
+use crate::circuits::r1cs::R1CS;
+use crate::cs::pcs::kzg::KZG;
+use crate::transcript::Transcript;
+use crate::utils::{
+    curve::{msm, SubgroupCheck},
+    pairing::multi_pairing_check,
+};
+
+/// Checks that points[i + 1] = tau * points[i] for all i, with a random linear combination
+fn is_geometric<E: Pairing>(points: &[E::G1], tau_g2: E::G2, transcript: &mut Transcript) -> bool {
+    transcript.absorb(points);
+    let r = transcript.challenges::<E::ScalarField>(points.len() - 1);
+    multi_pairing_check::<E>(&[
+        (msm(&points[..points.len() - 1], &r).unwrap(), tau_g2),
+        (-msm(&points[1..], &r).unwrap(), E::G2::generator()),
+    ])
+}
+
+/// [p(tau)] from the powers of tau in `bases`, which must outnumber the coefficients of p
+fn at_tau<G: CurveGroup>(bases: &[G], coeffs: &[G::ScalarField]) -> G {
+    msm(&bases[..coeffs.len()], coeffs).unwrap()
+}
+
+/// Proof that an accumulator has been updated by a secret s known to the participant
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpdateProof<E: Pairing> {
+    pub s_g1: E::G1,
+    pub s_g2: E::G2,
+    /// schnorr proof of knowledge of s: [k]_1 and k + c * s
+    pub r: E::G1,
+    pub z: E::ScalarField,
+}
+
+impl<E: Pairing> UpdateProof<E> {
+    fn new<R: Rng>(s: E::ScalarField, transcript: &mut Transcript, rng: &mut R) -> Self {
+        let k = E::ScalarField::rand(rng);
+        let g_1 = E::G1::generator();
+        let (s_g1, s_g2, r) = (g_1 * s, E::G2::generator() * s, g_1 * k);
+        let c = Self::challenge(s_g1, s_g2, r, transcript);
+        Self {
+            s_g1,
+            s_g2,
+            r,
+            z: k + c * s,
+        }
+    }
+
+    fn challenge(
+        s_g1: E::G1,
+        s_g2: E::G2,
+        r: E::G1,
+        transcript: &mut Transcript,
+    ) -> E::ScalarField {
+        transcript.absorb(&s_g1);
+        transcript.absorb(&s_g2);
+        transcript.absorb(&r);
+        transcript.challenge()
+    }
+
+    /// Checks the proof of knowledge, and that [s]_1 and [s]_2 hide the same non-zero s
+    fn verify(&self, transcript: &mut Transcript) -> bool {
+        let c = Self::challenge(self.s_g1, self.s_g2, self.r, transcript);
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+        !self.s_g1.is_zero()
+            && g_1 * self.z == self.r + self.s_g1 * c
+            && multi_pairing_check::<E>(&[(self.s_g1, g_2), (-g_1, self.s_g2)])
+    }
+}
+
+/// Phase 1 accumulator, for circuits of up to `size` constraints
+#[derive(Clone, Debug, PartialEq)]
+pub struct PowersOfTau<E: Pairing> {
+    pub tau_g1: Vec<E::G1>,
+    pub tau_g2: Vec<E::G2>,
+    pub alpha_tau_g1: Vec<E::G1>,
+    pub beta_tau_g1: Vec<E::G1>,
+    pub beta_g2: E::G2,
+}
+
+/// Update proofs for tau, alpha and beta
+#[derive(Clone, Debug, PartialEq)]
+pub struct PowersOfTauProof<E: Pairing> {
+    pub tau: UpdateProof<E>,
+    pub alpha: UpdateProof<E>,
+    pub beta: UpdateProof<E>,
+}
+
+impl<E: Pairing> PowersOfTau<E> {
+    /// The accumulator before any contribution, where tau = alpha = beta = 1
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 2, "the accumulator should hold at least [tau]_2");
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+        Self {
+            tau_g1: vec![g_1; 2 * size - 1],
+            tau_g2: vec![g_2; size],
+            alpha_tau_g1: vec![g_1; size],
+            beta_tau_g1: vec![g_1; size],
+            beta_g2: g_2,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    fn absorb(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.tau_g1);
+        transcript.absorb(&self.tau_g2);
+        transcript.absorb(&self.alpha_tau_g1);
+        transcript.absorb(&self.beta_tau_g1);
+        transcript.absorb(&self.beta_g2);
+    }
+
+    /// Multiplies fresh (tau', alpha', beta') into the accumulator, returns it with the update proof
+    pub fn contribute<R: Rng>(&self, rng: &mut R) -> (Self, PowersOfTauProof<E>) {
+        let mut sample = || E::ScalarField::rand(rng);
+        let (tau, alpha, beta) = (sample(), sample(), sample());
+        let mut transcript = Transcript::new(b"powers_of_tau");
+        self.absorb(&mut transcript);
+        let proof = PowersOfTauProof {
+            tau: UpdateProof::new(tau, &mut transcript, rng),
+            alpha: UpdateProof::new(alpha, &mut transcript, rng),
+            beta: UpdateProof::new(beta, &mut transcript, rng),
+        };
+
+        let mut powers = vec![E::ScalarField::ONE];
+        for i in 1..self.tau_g1.len() {
+            powers.push(powers[i - 1] * tau);
+        }
+        let scale = |points: &[E::G1], factor: E::ScalarField| -> Vec<E::G1> {
+            points
+                .iter()
+                .zip(&powers)
+                .map(|(p, t)| *p * (*t * factor))
+                .collect()
+        };
+        let next = Self {
+            tau_g1: scale(&self.tau_g1, E::ScalarField::ONE),
+            tau_g2: self
+                .tau_g2
+                .iter()
+                .zip(&powers)
+                .map(|(p, t)| *p * t)
+                .collect(),
+            alpha_tau_g1: scale(&self.alpha_tau_g1, alpha),
+            beta_tau_g1: scale(&self.beta_tau_g1, beta),
+            beta_g2: self.beta_g2 * beta,
+        };
+        (next, proof)
+    }
+
+    /// Checks that `next` is the accumulator updated by the secrets of `proof`, and that it is well formed
+    pub fn verify_contribution(&self, next: &Self, proof: &PowersOfTauProof<E>) -> bool {
+        if next.tau_g1.len() != self.tau_g1.len()
+            || next.tau_g2.len() != self.tau_g2.len()
+            || next.alpha_tau_g1.len() != self.alpha_tau_g1.len()
+            || next.beta_tau_g1.len() != self.beta_tau_g1.len()
+        {
+            return false;
+        }
+        let mut transcript = Transcript::new(b"powers_of_tau");
+        self.absorb(&mut transcript);
+        if !(proof.tau.verify(&mut transcript)
+            && proof.alpha.verify(&mut transcript)
+            && proof.beta.verify(&mut transcript))
+        {
+            return false;
+        }
+
+        let g_2 = E::G2::generator();
+        let updated =
+            multi_pairing_check::<E>(&[(next.tau_g1[1], g_2), (-self.tau_g1[1], proof.tau.s_g2)])
+                && multi_pairing_check::<E>(&[
+                    (next.alpha_tau_g1[0], g_2),
+                    (-self.alpha_tau_g1[0], proof.alpha.s_g2),
+                ])
+                && multi_pairing_check::<E>(&[
+                    (next.beta_tau_g1[0], g_2),
+                    (-self.beta_tau_g1[0], proof.beta.s_g2),
+                ]);
+        updated && next.is_well_formed(&mut transcript)
+    }
+
+    /// Checks that the accumulator holds the powers of a single tau, and the same beta in G1 and G2
+    fn is_well_formed(&self, transcript: &mut Transcript) -> bool {
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+        let tau_g2 = self.tau_g2[1];
+        if self.tau_g1[0] != g_1
+            || self.tau_g2[0] != g_2
+            || !is_geometric::<E>(&self.tau_g1, tau_g2, transcript)
+            || !is_geometric::<E>(&self.alpha_tau_g1, tau_g2, transcript)
+            || !is_geometric::<E>(&self.beta_tau_g1, tau_g2, transcript)
+        {
+            return false;
+        }
+        // [tau^i]_2 hides the same powers as [tau^i]_1
+        transcript.absorb(&self.tau_g2);
+        let r = transcript.challenges::<E::ScalarField>(self.size());
+        let n = self.size();
+        let tau_g1 = msm(&self.tau_g1[..n], &r).unwrap();
+        multi_pairing_check::<E>(&[(tau_g1, g_2), (-g_1, msm(&self.tau_g2, &r).unwrap())])
+            && multi_pairing_check::<E>(&[(self.beta_tau_g1[0], g_2), (-g_1, self.beta_g2)])
+    }
+
+    /// The KZG srs for polynomials of degree < `size`
+    pub fn kzg(&self) -> KZG<E>
+    where
+        E::G1: SubgroupCheck,
+        E::G2: SubgroupCheck,
+    {
+        KZG::from_srs(self.tau_g1[..self.size()].to_vec(), self.tau_g2.clone())
+    }
+}
+
+/// A phase 1 ceremony: the successive accumulators and the proofs of the contributions
+pub struct PowersOfTauCeremony<E: Pairing> {
+    accumulators: Vec<PowersOfTau<E>>,
+    proofs: Vec<PowersOfTauProof<E>>,
+}
+
+impl<E: Pairing> PowersOfTauCeremony<E> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            accumulators: vec![PowersOfTau::new(size)],
+            proofs: vec![],
+        }
+    }
+
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        let (next, proof) = self.srs().contribute(rng);
+        self.accumulators.push(next);
+        self.proofs.push(proof);
+    }
+
+    /// The latest accumulator
+    pub fn srs(&self) -> &PowersOfTau<E> {
+        self.accumulators.last().unwrap()
+    }
+
+    pub fn accumulators(&self) -> &[PowersOfTau<E>] {
+        &self.accumulators
+    }
+
+    pub fn proofs(&self) -> &[PowersOfTauProof<E>] {
+        &self.proofs
+    }
+
+    /// Verifies the chain of contributions, from the accumulator with no contribution
+    pub fn verify(&self) -> bool {
+        !self.proofs.is_empty()
+            && self.proofs.len() + 1 == self.accumulators.len()
+            && self.accumulators[0] == PowersOfTau::new(self.srs().size())
+            && self
+                .accumulators
+                .windows(2)
+                .zip(&self.proofs)
+                .all(|(pair, proof)| pair[0].verify_contribution(&pair[1], proof))
+    }
+}
+
+/// Phase 2 accumulator: the groth16 srs of an r1cs, with gamma = 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct Groth16Srs<E: Pairing> {
+    pub alpha_g1: E::G1,
+    pub beta_g1: E::G1,
+    pub beta_g2: E::G2,
+    pub delta_g1: E::G1,
+    pub delta_g2: E::G2,
+    /// [A_j(tau)]_1, [B_j(tau)]_1 and [B_j(tau)]_2, for all the variables
+    pub a_query: Vec<E::G1>,
+    pub b_g1_query: Vec<E::G1>,
+    pub b_g2_query: Vec<E::G2>,
+    /// [beta * A_j(tau) + alpha * B_j(tau) + C_j(tau)]_1, for the public (1, x) variables
+    pub ic: Vec<E::G1>,
+    /// the same for the witness variables, divided by delta
+    pub l_query: Vec<E::G1>,
+    /// [tau^i * Z_H(tau) / delta]_1, for i < n - 1
+    pub h_query: Vec<E::G1>,
+}
+
+impl<E: Pairing> Groth16Srs<E> {
+    /// The srs of the r1cs before any phase 2 contribution, where delta = 1
+    pub fn new(phase_1: &PowersOfTau<E>, r1cs: &R1CS<E::ScalarField>) -> Self {
+        let qap = QAP::from_r1cs_with_input_constraints(r1cs).unwrap();
+        let n = qap.domain().size();
+        assert!(
+            n <= phase_1.size(),
+            "the circuit is too large for the powers of tau: {} > {}",
+            n,
+            phase_1.size()
+        );
+        let at_tau_all = |bases: &[E::G1], polys: &[DensePolynomial<E::ScalarField>]| {
+            polys
+                .iter()
+                .map(|p| at_tau(bases, &p.coeffs))
+                .collect::<Vec<_>>()
+        };
+        let abc = qap
+            .a()
+            .iter()
+            .zip(qap.b())
+            .zip(qap.c())
+            .map(|((a, b), c)| {
+                at_tau(&phase_1.beta_tau_g1, &a.coeffs)
+                    + at_tau(&phase_1.alpha_tau_g1, &b.coeffs)
+                    + at_tau(&phase_1.tau_g1, &c.coeffs)
+            })
+            .collect::<Vec<_>>();
+        let (ic, l_query) = abc.split_at(r1cs.n_instance());
+        Self {
+            alpha_g1: phase_1.alpha_tau_g1[0],
+            beta_g1: phase_1.beta_tau_g1[0],
+            beta_g2: phase_1.beta_g2,
+            delta_g1: E::G1::generator(),
+            delta_g2: E::G2::generator(),
+            a_query: at_tau_all(&phase_1.tau_g1, qap.a()),
+            b_g1_query: at_tau_all(&phase_1.tau_g1, qap.b()),
+            b_g2_query: qap
+                .b()
+                .iter()
+                .map(|p| at_tau(&phase_1.tau_g2, &p.coeffs))
+                .collect(),
+            ic: ic.to_vec(),
+            l_query: l_query.to_vec(),
+            // [tau^i * Z_H(tau)]_1, from the two terms of Z_H
+            h_query: (0..n - 1)
+                .map(|i| {
+                    qap.z_h()
+                        .terms()
+                        .iter()
+                        .map(|(degree, coeff)| phase_1.tau_g1[i + degree] * coeff)
+                        .sum()
+                })
+                .collect(),
+        }
+    }
+
+    fn absorb(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.delta_g1);
+        transcript.absorb(&self.delta_g2);
+        transcript.absorb(&self.l_query);
+        transcript.absorb(&self.h_query);
+    }
+
+    /// Multiplies a fresh delta' into delta and divides the L and H queries by it
+    pub fn contribute<R: Rng>(&self, rng: &mut R) -> (Self, UpdateProof<E>) {
+        let delta = E::ScalarField::rand(rng);
+        let mut transcript = Transcript::new(b"groth16_phase_2");
+        self.absorb(&mut transcript);
+        let proof = UpdateProof::new(delta, &mut transcript, rng);
+        let delta_inverse = delta.inverse().unwrap();
+        let next = Self {
+            delta_g1: self.delta_g1 * delta,
+            delta_g2: self.delta_g2 * delta,
+            l_query: self.l_query.iter().map(|l| *l * delta_inverse).collect(),
+            h_query: self.h_query.iter().map(|h| *h * delta_inverse).collect(),
+            ..self.clone()
+        };
+        (next, proof)
+    }
+
+    /// Checks that `next` is the srs updated by the secret of `proof`
+    pub fn verify_contribution(&self, next: &Self, proof: &UpdateProof<E>) -> bool {
+        // everything but delta, L and H is left untouched
+        let untouched = Self {
+            delta_g1: self.delta_g1,
+            delta_g2: self.delta_g2,
+            l_query: self.l_query.clone(),
+            h_query: self.h_query.clone(),
+            ..next.clone()
+        };
+        if untouched != *self
+            || next.l_query.len() != self.l_query.len()
+            || next.h_query.len() != self.h_query.len()
+        {
+            return false;
+        }
+        let mut transcript = Transcript::new(b"groth16_phase_2");
+        self.absorb(&mut transcript);
+        if !proof.verify(&mut transcript) {
+            return false;
+        }
+
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+        let updated =
+            multi_pairing_check::<E>(&[(next.delta_g1, g_2), (-self.delta_g1, proof.s_g2)])
+                && multi_pairing_check::<E>(&[(next.delta_g1, g_2), (-g_1, next.delta_g2)]);
+        // e(sum_i r_i * L'_i, [delta']_2) = e(sum_i r_i * L_i, [delta]_2), and the same for H
+        next.absorb(&mut transcript);
+        let (old, new) = (
+            [self.l_query.clone(), self.h_query.clone()].concat(),
+            [next.l_query.clone(), next.h_query.clone()].concat(),
+        );
+        let r = transcript.challenges::<E::ScalarField>(old.len());
+        updated
+            && multi_pairing_check::<E>(&[
+                (msm(&new, &r).unwrap(), next.delta_g2),
+                (-msm(&old, &r).unwrap(), self.delta_g2),
+            ])
+    }
+}
+
+/// A phase 2 ceremony for an r1cs, on top of the output of a phase 1 ceremony
+pub struct Groth16Ceremony<E: Pairing> {
+    accumulators: Vec<Groth16Srs<E>>,
+    proofs: Vec<UpdateProof<E>>,
+}
+
+impl<E: Pairing> Groth16Ceremony<E> {
+    pub fn new(phase_1: &PowersOfTau<E>, r1cs: &R1CS<E::ScalarField>) -> Self {
+        Self {
+            accumulators: vec![Groth16Srs::new(phase_1, r1cs)],
+            proofs: vec![],
+        }
+    }
+
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        let (next, proof) = self.srs().contribute(rng);
+        self.accumulators.push(next);
+        self.proofs.push(proof);
+    }
+
+    /// The latest srs
+    pub fn srs(&self) -> &Groth16Srs<E> {
+        self.accumulators.last().unwrap()
+    }
+
+    pub fn accumulators(&self) -> &[Groth16Srs<E>] {
+        &self.accumulators
+    }
+
+    pub fn proofs(&self) -> &[UpdateProof<E>] {
+        &self.proofs
+    }
+
+    /// Verifies the chain of contributions, starting from the srs computed from `phase_1` and `r1cs`
+    /// `phase_1` should come from a verified phase 1 ceremony
+    pub fn verify(&self, phase_1: &PowersOfTau<E>, r1cs: &R1CS<E::ScalarField>) -> bool {
+        !self.proofs.is_empty()
+            && self.proofs.len() + 1 == self.accumulators.len()
+            && self.accumulators[0] == Groth16Srs::new(phase_1, r1cs)
+            && self
+                .accumulators
+                .windows(2)
+                .zip(&self.proofs)
+                .all(|(pair, proof)| pair[0].verify_contribution(&pair[1], proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Groth16Ceremony, PowersOfTauCeremony};
+    use crate::circuits::qap::QAP;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::utils::curve::msm;
+    use ark_bn254::{Bn254, Fr, G1Projective};
+    use ark_ec::{pairing::Pairing, Group};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_powers_of_tau_ceremony() {
+        let mut rng = test_rng();
+        let mut ceremony = PowersOfTauCeremony::<Bn254>::new(8);
+        // no contribution, the toxic waste is known
+        assert!(!ceremony.verify());
+        for _ in 0..3 {
+            ceremony.contribute(&mut rng);
+        }
+        assert!(ceremony.verify());
+
+        // the output is a working KZG srs
+        let kzg = ceremony.srs().kzg();
+        let poly =
+            DensePolynomial::from_coefficients_vec((0..8).map(|_| Fr::rand(&mut rng)).collect());
+        let z = Fr::rand(&mut rng);
+        let commitment = kzg.commit(&poly).unwrap();
+        let y = poly.evaluate(&z);
+        let pi = kzg.open(&poly, z, y).unwrap();
+        assert!(kzg.verify(y, z, commitment, pi));
+
+        // a contribution that breaks the powers structure
+        let mut wrong = PowersOfTauCeremony::<Bn254>::new(8);
+        wrong.contribute(&mut rng);
+        wrong.accumulators[1].tau_g1[3] += G1Projective::generator();
+        assert!(!wrong.verify());
+        // a contribution that doesn't use the proven secret
+        let mut wrong = PowersOfTauCeremony::<Bn254>::new(8);
+        wrong.contribute(&mut rng);
+        wrong.contribute(&mut rng);
+        wrong.proofs[1] = wrong.proofs[0].clone();
+        assert!(!wrong.verify());
+    }
+
+    #[test]
+    pub fn test_groth16_ceremony() {
+        let mut rng = test_rng();
+        let mut phase_1 = PowersOfTauCeremony::<Bn254>::new(8);
+        phase_1.contribute(&mut rng);
+        phase_1.contribute(&mut rng);
+
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let mut phase_2 = Groth16Ceremony::new(phase_1.srs(), &r1cs);
+        for _ in 0..3 {
+            phase_2.contribute(&mut rng);
+        }
+        assert!(phase_2.verify(phase_1.srs(), &r1cs));
+
+        // groth16 proof with no blinding:
+        // e(A, B) = e(alpha, beta) * e(sum_io z_j * IC_j, g_2) * e(C, delta)
+        let srs = phase_2.srs();
+        let h = QAP::from_r1cs_with_input_constraints(&r1cs)
+            .unwrap()
+            .compute_h(&z)
+            .unwrap();
+        let (io, w) = z.elements().split_at(r1cs.n_instance());
+        let a = srs.alpha_g1 + msm(&srs.a_query, z.elements()).unwrap();
+        let b = srs.beta_g2 + msm(&srs.b_g2_query, z.elements()).unwrap();
+        let h_query = &srs.h_query[..h.coeffs.len()];
+        let c = msm(&srs.l_query, w).unwrap() + msm(h_query, &h.coeffs).unwrap();
+        assert_eq!(
+            Bn254::pairing(a, b),
+            Bn254::multi_pairing(
+                [srs.alpha_g1, msm(&srs.ic, io).unwrap(), c],
+                [
+                    srs.beta_g2,
+                    <Bn254 as Pairing>::G2::generator(),
+                    srs.delta_g2
+                ]
+            )
+        );
+
+        // a contribution that divides L by a different delta
+        let mut wrong = Groth16Ceremony::new(phase_1.srs(), &r1cs);
+        wrong.contribute(&mut rng);
+        wrong.accumulators[1].l_query[0] += G1Projective::generator();
+        assert!(!wrong.verify(phase_1.srs(), &r1cs));
+        // a phase 2 started from another phase 1
+        let other_phase_1 = PowersOfTauCeremony::<Bn254>::new(8);
+        assert!(!phase_2.verify(other_phase_1.srs(), &r1cs));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ceremony;
+pub mod secret;
@@ -0,0 +1,70 @@
+// Toxic waste handling: a setup takes its secrets by value, wrapped in `Secret`, which zeroizes them when dropped.
+// The caller can't use a secret once it has been handed over, and `setup_from_rng`-like constructors
+// sample it internally, so that it's never exposed at all.
+use ark_std::{fmt, rand::Rng, UniformRand};
+use zeroize::Zeroize;
+
+/// A secret value (e.g. the tau of a KZG setup), zeroized when dropped, and never printed
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Samples the secret from `rng`, which should be cryptographically secure outside of tests
+    pub fn random<R: Rng>(rng: &mut R) -> Self
+    where
+        T: UniformRand,
+    {
+        Self(T::rand(rng))
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Secret;
+    use ark_bn254::Fr;
+    use ark_ff::Field;
+    use ark_std::test_rng;
+    use std::{cell::Cell, rc::Rc};
+    use zeroize::Zeroize;
+
+    #[test]
+    pub fn test_secret() {
+        let secret = Secret::new(Fr::from(42u64));
+        assert_eq!(*secret.expose(), Fr::from(42u64));
+        assert_eq!(format!("{secret:?}"), "Secret(..)");
+        let random = Secret::<Fr>::random(&mut test_rng());
+        assert_ne!(*random.expose(), Fr::ZERO);
+
+        // the value is zeroized when dropped
+        struct Flag(Rc<Cell<bool>>);
+        impl Zeroize for Flag {
+            fn zeroize(&mut self) {
+                self.0.set(true);
+            }
+        }
+        let zeroized = Rc::new(Cell::new(false));
+        let secret = Secret::new(Flag(zeroized.clone()));
+        assert!(!zeroized.get());
+        drop(secret);
+        assert!(zeroized.get());
+    }
+}
@@ -0,0 +1,218 @@
+// Commit-and-prove groth16, as LegoGroth16: https://eprint.iacr.org/2019/142 (appendix H)
+// Some witness entries z_S of z = (1, x, w) are committed to outside of the snark, in a pedersen commitment
+// C_ext = <z_S, G> + r * H, and the proof shows that the same z_S satisfy the r1cs.
+// Setup takes the committed indices out of the proving key: their L_i = beta * A_i(tau) + alpha * B_i(tau) + C_i(tau)
+// are divided by gamma instead of delta, as the public inputs, and form with [eta / gamma]_1 a pedersen key.
+// The prover adds D = sum_S z_i * [L_i / gamma]_1 + nu * [eta / gamma]_1 to the proof, removes nu * [eta / delta]_1
+// from C, and the verifier checks e(A, B) = e([alpha]_1, [beta]_2) * e(ic + D, [gamma]_2) * e(C, [delta]_2).
+// D is then a commitment to z_S bound to the statement, and a sigma protocol shows that D and C_ext
+// hide the same messages (the link).
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, UniformRand};
+use ark_std::rand::Rng;
+
+use super::{CommittedKey, Groth16, Groth16Proof, Groth16ProvingKey, Groth16VerifyingKey};
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::cs::pedersen::{sigma::EqualityProof, Pedersen};
+use crate::error::AlgorithmsError;
+use crate::utils::curve::msm;
+
+pub struct CPGroth16ProvingKey<E: Pairing> {
+    proving_key: Groth16ProvingKey<E>,
+    committed: Vec<usize>,
+    /// Commits to z_S inside the statement, with generators [L_i / gamma]_1 and blinding generator [eta / gamma]_1
+    link_key: Pedersen<E::G1>,
+    eta_delta: E::G1,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CPGroth16VerifyingKey<E: Pairing> {
+    verifying_key: Groth16VerifyingKey<E>,
+    link_key: Pedersen<E::G1>,
+}
+
+/// A groth16 proof, the commitment D to the committed witness entries and the proof that D and the
+/// external commitment open to the same values
+#[derive(Clone, Debug, PartialEq)]
+pub struct CPGroth16Proof<E: Pairing> {
+    pub proof: Groth16Proof<E>,
+    pub d: E::G1,
+    pub link: EqualityProof<E::G1>,
+}
+
+impl<E: Pairing> CPGroth16VerifyingKey<E> {
+    /// Number of committed witness entries
+    pub fn n_committed(&self) -> usize {
+        self.link_key.size()
+    }
+}
+
+impl<E: Pairing> Groth16<E> {
+    /// Keys for proofs about `r1cs` whose witness entries at `committed` (indices in z) are committed to
+    pub fn setup_commit_and_prove<R: Rng>(
+        r1cs: &R1CS<E::ScalarField>,
+        committed: &[usize],
+        rng: &mut R,
+    ) -> Result<(CPGroth16ProvingKey<E>, CPGroth16VerifyingKey<E>), AlgorithmsError> {
+        let (
+            proving_key,
+            verifying_key,
+            CommittedKey {
+                l_gamma,
+                eta_gamma,
+                eta_delta,
+            },
+        ) = Self::setup_with_committed(r1cs, committed, rng)?;
+        let link_key = Pedersen::new(l_gamma, eta_gamma);
+        Ok((
+            CPGroth16ProvingKey {
+                proving_key,
+                committed: committed.to_vec(),
+                link_key: link_key.clone(),
+                eta_delta,
+            },
+            CPGroth16VerifyingKey {
+                verifying_key,
+                link_key,
+            },
+        ))
+    }
+
+    /// Proves that z satisfies the r1cs and that its committed entries are the messages of `external_commitment`,
+    /// made with `external_key` and `external_blinding`
+    pub fn prove_commit_and_prove<R: Rng>(
+        proving_key: &CPGroth16ProvingKey<E>,
+        z: &R1CSInstanceWitness<E::ScalarField>,
+        external_key: &Pedersen<E::G1>,
+        external_commitment: &E::G1,
+        external_blinding: &E::ScalarField,
+        rng: &mut R,
+    ) -> Result<CPGroth16Proof<E>, AlgorithmsError> {
+        let mut proof = Self::prove(&proving_key.proving_key, z, rng)?;
+        let committed = proving_key
+            .committed
+            .iter()
+            .map(|i| z.elements()[*i])
+            .collect::<Vec<_>>();
+        let nu = E::ScalarField::rand(rng);
+        let d = proving_key.link_key.commit(&committed, &nu);
+        proof.c -= proving_key.eta_delta * nu;
+        let link = proving_key.link_key.prove_equality_fs(
+            external_key,
+            &d,
+            external_commitment,
+            &committed,
+            &nu,
+            external_blinding,
+            rng,
+        );
+        Ok(CPGroth16Proof { proof, d, link })
+    }
+
+    /// Verifies the groth16 proof with D as an extra public input, and its link with `external_commitment`
+    pub fn verify_commit_and_prove(
+        verifying_key: &CPGroth16VerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+        external_key: &Pedersen<E::G1>,
+        external_commitment: &E::G1,
+        proof: &CPGroth16Proof<E>,
+    ) -> bool {
+        let vk = &verifying_key.verifying_key;
+        if public_inputs.len() + 1 != vk.ic.len() {
+            return false;
+        }
+        let io = [&[E::ScalarField::ONE], public_inputs].concat();
+        Self::check(vk, msm(&vk.ic, &io).unwrap() + proof.d, &proof.proof)
+            && verifying_key.link_key.verify_equality_proof_fs(
+                external_key,
+                &proof.d,
+                external_commitment,
+                &proof.link,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::cs::pedersen::Pedersen;
+    use crate::snark::groth16::Groth16;
+    use ark_bn254::{Bn254, Fr, G1Projective};
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_commit_and_prove() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        // commit to a and b, z = (1, c, a, b, ...)
+        let committed = [2, 3];
+        let (pk, vk) =
+            Groth16::<Bn254>::setup_commit_and_prove(&r1cs, &committed, &mut rng).unwrap();
+        assert_eq!(vk.n_committed(), 2);
+
+        let external_key = Pedersen::<G1Projective>::setup(2);
+        let blinding = Fr::rand(&mut rng);
+        let values = [Fr::from(3u64), Fr::from(4u64)];
+        assert_eq!(values, [z.elements()[2], z.elements()[3]]);
+        let commitment = external_key.commit(&values, &blinding);
+
+        let proof = Groth16::prove_commit_and_prove(
+            &pk,
+            &z,
+            &external_key,
+            &commitment,
+            &blinding,
+            &mut rng,
+        )
+        .unwrap();
+        let public = [Fr::from(25u64)];
+        assert!(Groth16::verify_commit_and_prove(
+            &vk,
+            &public,
+            &external_key,
+            &commitment,
+            &proof
+        ));
+        assert!(!Groth16::verify_commit_and_prove(
+            &vk,
+            &[Fr::from(26u64)],
+            &external_key,
+            &commitment,
+            &proof
+        ));
+        // a commitment to other values
+        let other = external_key.commit(&[Fr::from(4u64), Fr::from(3u64)], &blinding);
+        assert!(!Groth16::verify_commit_and_prove(
+            &vk,
+            &public,
+            &external_key,
+            &other,
+            &proof
+        ));
+        // the link holds for other values, but D doesn't fit the groth16 equation anymore
+        let wrong_values = [Fr::from(5u64), Fr::from(4u64)];
+        let wrong_commitment = external_key.commit(&wrong_values, &blinding);
+        let mut wrong_proof = proof.clone();
+        let nu = Fr::one();
+        wrong_proof.d = vk.link_key.commit(&wrong_values, &nu);
+        wrong_proof.link = vk.link_key.prove_equality_fs(
+            &external_key,
+            &wrong_proof.d,
+            &wrong_commitment,
+            &wrong_values,
+            &nu,
+            &blinding,
+            &mut rng,
+        );
+        assert!(!Groth16::verify_commit_and_prove(
+            &vk,
+            &public,
+            &external_key,
+            &wrong_commitment,
+            &wrong_proof
+        ));
+    }
+}
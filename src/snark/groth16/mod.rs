@@ -0,0 +1,372 @@
+// Groth16: https://eprint.iacr.org/2016/260 (section 3.2), over the qap of an r1cs.
+// With z = (1, x, w), A_z(X) = sum_i z_i * A_i(X) (same for B, C) and H(X) = (A_z * B_z - C_z) / Z_H:
+// - setup samples tau, alpha, beta, gamma, delta (the toxic waste) and publishes, besides [alpha]_1, [beta]_{1,2},
+//   [gamma]_2 and [delta]_{1,2}, the A_i(tau), B_i(tau), the L_i = (beta * A_i(tau) + alpha * B_i(tau) + C_i(tau))
+//   divided by gamma for the public (1, x) indices and by delta for the witness indices, and tau^i * Z_H(tau) / delta,
+// - the prover samples r, s and sends A = [alpha + A_z(tau) + r * delta]_1, B = [beta + B_z(tau) + s * delta]_2,
+//   C = [sum_w z_i * L_i + H(tau) * Z_H(tau) / delta + s * A + r * B - r * s * delta]_1,
+// - the verifier checks e(A, B) = e([alpha]_1, [beta]_2) * e(sum_io z_i * [L_i]_1, [gamma]_2) * e(C, [delta]_2).
+// gamma separates the public inputs from the witness: the prover can't shift terms between them.
+// The qap is built with one extra constraint z_i * 0 = 0 per public index, see `QAP::from_r1cs_with_input_constraints`.
+// r and s make the proof zero-knowledge.
+// Proofs are malleable: anyone can turn a proof into another valid proof of the same statement, see `rerandomize`.
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{Field, UniformRand};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::{marker::PhantomData, rand::Rng, Zero};
+
+use crate::circuits::qap::QAP;
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::error::AlgorithmsError;
+use crate::utils::{
+    curve::{msm, msm_secret, mul_secret, FixedBaseTable},
+    pairing::multi_pairing_check,
+};
+
+pub mod commit_and_prove;
+pub mod pipeline;
+#[cfg(feature = "serde")]
+pub mod snarkjs;
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct Groth16Proof<E: Pairing> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub a: E::G1,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub b: E::G2,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    pub c: E::G1,
+}
+
+#[derive(Clone, Debug)]
+pub struct Groth16ProvingKey<E: Pairing> {
+    qap: QAP<E::ScalarField>,
+    n_instance: usize,
+    alpha_g1: E::G1,
+    beta_g1: E::G1,
+    beta_g2: E::G2,
+    delta_g1: E::G1,
+    delta_g2: E::G2,
+    /// [A_i(tau)]_1, for every index
+    a_query: Vec<E::G1>,
+    /// [B_i(tau)]_1 and [B_i(tau)]_2, for every index
+    b_g1_query: Vec<E::G1>,
+    b_g2_query: Vec<E::G2>,
+    /// [L_i / delta]_1, for the witness indices
+    l_query: Vec<E::G1>,
+    /// [tau^i * Z_H(tau) / delta]_1, for i < |H| - 1
+    h_query: Vec<E::G1>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct Groth16VerifyingKey<E: Pairing> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    alpha_g1: E::G1,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    beta_g2: E::G2,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    gamma_g2: E::G2,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde"))]
+    delta_g2: E::G2,
+    /// [L_i / gamma]_1, for the public (1, x) indices
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde::vec"))]
+    ic: Vec<E::G1>,
+}
+
+/// The commitment key of the committed witness indices: [L_i / gamma]_1, [eta / gamma]_1,
+/// and [eta / delta]_1 which takes the commitment's blinding out of C
+struct CommittedKey<E: Pairing> {
+    l_gamma: Vec<E::G1>,
+    eta_gamma: E::G1,
+    eta_delta: E::G1,
+}
+
+type CommittedSetup<E> = (
+    Groth16ProvingKey<E>,
+    Groth16VerifyingKey<E>,
+    CommittedKey<E>,
+);
+
+pub struct Groth16<E: Pairing> {
+    _pairing: PhantomData<E>,
+}
+
+impl<E: Pairing> Groth16Proof<E> {
+    /// A fresh proof of the same statement, without the witness: with random r_1, r_2,
+    /// A' = A / r_1, B' = r_1 * B + r_1 * r_2 * [delta]_2, C' = C + r_2 * A,
+    /// since e(A', B') = e(A, B) * e(r_2 * A, [delta]_2)
+    pub fn rerandomize<R: Rng>(&self, verifying_key: &Groth16VerifyingKey<E>, rng: &mut R) -> Self {
+        let (r_1, r_2) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+        Self {
+            a: self.a * r_1.inverse().unwrap(),
+            b: (self.b + verifying_key.delta_g2 * r_2) * r_1,
+            c: self.c + self.a * r_2,
+        }
+    }
+}
+
+impl<E: Pairing> Groth16ProvingKey<E> {
+    pub fn qap(&self) -> &QAP<E::ScalarField> {
+        &self.qap
+    }
+}
+
+impl<E: Pairing> Groth16VerifyingKey<E> {
+    /// Number of public inputs, the leading 1 excluded
+    pub fn n_public_inputs(&self) -> usize {
+        self.ic.len() - 1
+    }
+}
+
+impl<E: Pairing> Groth16<E> {
+    /// Samples the toxic waste and computes the keys for the qap of `r1cs`
+    pub fn setup<R: Rng>(
+        r1cs: &R1CS<E::ScalarField>,
+        rng: &mut R,
+    ) -> Result<(Groth16ProvingKey<E>, Groth16VerifyingKey<E>), AlgorithmsError> {
+        let (proving_key, verifying_key, _) = Self::setup_with_committed(r1cs, &[], rng)?;
+        Ok((proving_key, verifying_key))
+    }
+
+    /// `setup`, with the witness indices in `committed` left out of the proving key: their L_i go,
+    /// divided by gamma, to the commitment key of the commit-and-prove variant
+    fn setup_with_committed<R: Rng>(
+        r1cs: &R1CS<E::ScalarField>,
+        committed: &[usize],
+        rng: &mut R,
+    ) -> Result<CommittedSetup<E>, AlgorithmsError> {
+        // with the input constraints, the L_i / gamma of the public indices bind every input
+        let qap = QAP::from_r1cs_with_input_constraints(r1cs)?;
+        let mut sample = || E::ScalarField::rand(rng);
+        let (tau, alpha, beta, gamma, delta) = (sample(), sample(), sample(), sample(), sample());
+        let eta = sample();
+        let (gamma_inv, delta_inv) = (gamma.inverse().unwrap(), delta.inverse().unwrap());
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+
+        let at_tau = |polys: &[DensePolynomial<E::ScalarField>]| {
+            polys
+                .iter()
+                .map(|poly| poly.evaluate(&tau))
+                .collect::<Vec<_>>()
+        };
+        let (a_tau, b_tau, c_tau) = (at_tau(qap.a()), at_tau(qap.b()), at_tau(qap.c()));
+        let l = a_tau
+            .iter()
+            .zip(&b_tau)
+            .zip(&c_tau)
+            .map(|((a, b), c)| beta * a + alpha * b + c)
+            .collect::<Vec<_>>();
+        // a, b, l and h queries, with one fixed-base table per group
+        let g1_table = FixedBaseTable::new(g_1, 4 * l.len() + qap.domain().size());
+        let g2_table = FixedBaseTable::new(g_2, b_tau.len());
+        let to_g1 = |values: &[E::ScalarField], factor: E::ScalarField| -> Vec<E::G1> {
+            g1_table.batch_mul(&values.iter().map(|v| *v * factor).collect::<Vec<_>>())
+        };
+        let n_instance = r1cs.n_instance();
+        assert!(
+            committed.iter().all(|i| (n_instance..l.len()).contains(i)),
+            "only witness indices can be committed"
+        );
+        let mut l_witness = l[n_instance..].to_vec();
+        for i in committed {
+            l_witness[i - n_instance] = E::ScalarField::zero();
+        }
+
+        let z_tau_delta = qap.z_h().evaluate(&tau) * delta_inv;
+        let mut power = E::ScalarField::ONE;
+        let mut h_scalars = vec![];
+        for _ in 0..qap.domain().size() - 1 {
+            h_scalars.push(power * z_tau_delta);
+            power *= tau;
+        }
+        let h_query = g1_table.batch_mul(&h_scalars);
+        let verifying_key = Groth16VerifyingKey {
+            alpha_g1: g_1 * alpha,
+            beta_g2: g_2 * beta,
+            gamma_g2: g_2 * gamma,
+            delta_g2: g_2 * delta,
+            ic: to_g1(&l[..n_instance], gamma_inv),
+        };
+        let proving_key = Groth16ProvingKey {
+            n_instance,
+            alpha_g1: g_1 * alpha,
+            beta_g1: g_1 * beta,
+            beta_g2: g_2 * beta,
+            delta_g1: g_1 * delta,
+            delta_g2: g_2 * delta,
+            a_query: to_g1(&a_tau, E::ScalarField::ONE),
+            b_g1_query: to_g1(&b_tau, E::ScalarField::ONE),
+            b_g2_query: g2_table.batch_mul(&b_tau),
+            l_query: to_g1(&l_witness, delta_inv),
+            h_query,
+            qap,
+        };
+        let committed_key = CommittedKey {
+            l_gamma: to_g1(
+                &committed.iter().map(|i| l[*i]).collect::<Vec<_>>(),
+                gamma_inv,
+            ),
+            eta_gamma: g_1 * (eta * gamma_inv),
+            eta_delta: g_1 * (eta * delta_inv),
+        };
+        Ok((proving_key, verifying_key, committed_key))
+    }
+
+    /// Proves that z = (1, x, w) satisfies the r1cs, an error if it doesn't
+    pub fn prove<R: Rng>(
+        proving_key: &Groth16ProvingKey<E>,
+        z: &R1CSInstanceWitness<E::ScalarField>,
+        rng: &mut R,
+    ) -> Result<Groth16Proof<E>, AlgorithmsError> {
+        // h, r and s are zeroized with the `constant-time` feature
+        #[cfg_attr(not(feature = "constant-time"), allow(unused_mut))]
+        let mut h = proving_key.qap.compute_h(z)?;
+        #[cfg_attr(not(feature = "constant-time"), allow(unused_mut))]
+        let (mut r, mut s) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+        let z = z.elements();
+
+        // z, h, r and s are secrets, see `msm_secret` and `mul_secret`
+        let a = proving_key.alpha_g1
+            + msm_secret(&proving_key.a_query, z)?
+            + mul_secret(proving_key.delta_g1, &r);
+        let b = proving_key.beta_g2
+            + msm_secret(&proving_key.b_g2_query, z)?
+            + mul_secret(proving_key.delta_g2, &s);
+        let b_g1 = proving_key.beta_g1
+            + msm_secret(&proving_key.b_g1_query, z)?
+            + mul_secret(proving_key.delta_g1, &s);
+        let h_term = if h.is_zero() {
+            E::G1::zero()
+        } else {
+            msm_secret(&proving_key.h_query[..h.coeffs.len()], &h.coeffs)?
+        };
+        let c = msm_secret(&proving_key.l_query, &z[proving_key.n_instance..])?
+            + h_term
+            + mul_secret(a, &s)
+            + mul_secret(b_g1, &r)
+            - mul_secret(proving_key.delta_g1, &(r * s));
+        #[cfg(feature = "constant-time")]
+        {
+            use zeroize::Zeroize;
+            h.coeffs.zeroize();
+            r.zeroize();
+            s.zeroize();
+        }
+        Ok(Groth16Proof { a, b, c })
+    }
+
+    /// Verifies that the prover knows w such that (1, x, w) satisfies the r1cs, x being `public_inputs`
+    pub fn verify(
+        verifying_key: &Groth16VerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+        proof: &Groth16Proof<E>,
+    ) -> bool {
+        if public_inputs.len() + 1 != verifying_key.ic.len() {
+            return false;
+        }
+        let io = [&[E::ScalarField::ONE], public_inputs].concat();
+        Self::check(verifying_key, msm(&verifying_key.ic, &io).unwrap(), proof)
+    }
+
+    /// e(A, B) = e([alpha]_1, [beta]_2) * e(ic, [gamma]_2) * e(C, [delta]_2), ic being sum_io z_i * [L_i / gamma]_1
+    fn check(verifying_key: &Groth16VerifyingKey<E>, ic: E::G1, proof: &Groth16Proof<E>) -> bool {
+        // the proof points must be on the curve and in the prime order subgroups before entering the pairings
+        if proof.check().is_err() {
+            return false;
+        }
+        // e(A, B) * e(-alpha, beta) * e(-IC, gamma) * e(-C, delta) = 1
+        multi_pairing_check::<E>(&[
+            (proof.a, proof.b),
+            (-verifying_key.alpha_g1, verifying_key.beta_g2),
+            (-ic, verifying_key.gamma_g2),
+            (-proof.c, verifying_key.delta_g2),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Groth16;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::circuits::r1cs::R1CS;
+    use crate::error::AlgorithmsError;
+    use crate::utils::linear_algebra::{Matrix, Vector};
+    use ark_bn254::{Bn254, Fr};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_groth16() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let (pk, vk) = Groth16::<Bn254>::setup(&r1cs, &mut rng).unwrap();
+        assert_eq!(vk.n_public_inputs(), 1);
+
+        let proof = Groth16::prove(&pk, &z, &mut rng).unwrap();
+        assert!(Groth16::verify(&vk, &[Fr::from(25u64)], &proof));
+        // proofs are randomized
+        let other_proof = Groth16::prove(&pk, &z, &mut rng).unwrap();
+        assert_ne!(proof, other_proof);
+        assert!(Groth16::verify(&vk, &[Fr::from(25u64)], &other_proof));
+
+        // wrong public inputs or tampered proof
+        assert!(!Groth16::verify(&vk, &[Fr::from(26u64)], &proof));
+        assert!(!Groth16::verify(&vk, &[], &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.c += wrong_proof.a;
+        assert!(!Groth16::verify(&vk, &[Fr::from(25u64)], &wrong_proof));
+
+        // anyone can rerandomize a proof, which still verifies
+        let rerandomized = proof.rerandomize(&vk, &mut rng);
+        assert_ne!(rerandomized, proof);
+        assert!(Groth16::verify(&vk, &[Fr::from(25u64)], &rerandomized));
+        assert!(!Groth16::verify(&vk, &[Fr::from(26u64)], &rerandomized));
+
+        // an unsatisfying assignment can't be proven
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(26u64));
+        assert_eq!(
+            Groth16::<Bn254>::prove(&pk, &get_z_from_cs(circuit).unwrap(), &mut rng),
+            Err(AlgorithmsError::UnsatisfiedConstraints)
+        );
+    }
+
+    #[test]
+    pub fn test_groth16_unused_public_input() {
+        // z = (1, x_1, x_2, w) with the single constraint w * w = x_1: no constraint uses x_2
+        let mut rng = test_rng();
+        let row = |values: [u64; 4]| Vector::new(&values.map(Fr::from));
+        let r1cs = R1CS::new(
+            1,
+            3,
+            Matrix::new(&[row([0, 0, 0, 1])]),
+            Matrix::new(&[row([0, 0, 0, 1])]),
+            Matrix::new(&[row([0, 1, 0, 0])]),
+        );
+        let z = row([1, 9, 5, 3]);
+        let (pk, vk) = Groth16::<Bn254>::setup(&r1cs, &mut rng).unwrap();
+        let proof = Groth16::prove(&pk, &z, &mut rng).unwrap();
+        assert!(Groth16::verify(
+            &vk,
+            &[Fr::from(9u64), Fr::from(5u64)],
+            &proof
+        ));
+        // the input constraints bind x_2 all the same
+        assert!(!Groth16::verify(
+            &vk,
+            &[Fr::from(9u64), Fr::from(6u64)],
+            &proof
+        ));
+    }
+}
@@ -0,0 +1,93 @@
+// The whole groth16 pipeline in one call, for stepping through it: the circuit is synthesized into an
+// arkworks constraint system, the r1cs matrices and the assignment z = (1, x, w) are extracted, the r1cs is
+// reduced to a qap, whose quotient H(X) the witness has to yield, then come setup, proof and verification.
+// Every intermediate artifact is returned. The setup samples the toxic waste from `rng`: this is for
+// exploration, a real deployment runs the setup once, e.g. with a ceremony, see `crate::setup::ceremony`.
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_std::rand::Rng;
+
+use super::{Groth16, Groth16Proof, Groth16ProvingKey, Groth16VerifyingKey};
+use crate::circuits::qap::QAP;
+use crate::circuits::r1cs::{
+    utils::{extract_r1cs, extract_z, generate_constraint_system, R1CSInstanceWitness},
+    R1CS,
+};
+use crate::error::AlgorithmsError;
+
+/// The artifacts of each step of `prove_circuit`
+#[derive(Clone, Debug)]
+pub struct Groth16Pipeline<E: Pairing> {
+    pub r1cs: R1CS<E::ScalarField>,
+    pub z: R1CSInstanceWitness<E::ScalarField>,
+    /// x, z without its leading 1 and the witness
+    pub public_inputs: Vec<E::ScalarField>,
+    pub qap: QAP<E::ScalarField>,
+    pub h: DensePolynomial<E::ScalarField>,
+    pub proving_key: Groth16ProvingKey<E>,
+    pub verifying_key: Groth16VerifyingKey<E>,
+    pub proof: Groth16Proof<E>,
+    pub verified: bool,
+}
+
+/// Runs extraction, qap reduction, setup, proof and verification for `circuit`
+pub fn prove_circuit<E: Pairing, R: Rng>(
+    circuit: impl ConstraintSynthesizer<E::ScalarField>,
+    rng: &mut R,
+) -> Result<Groth16Pipeline<E>, AlgorithmsError> {
+    let cs = generate_constraint_system(circuit).map_err(AlgorithmsError::Synthesis)?;
+    let r1cs = extract_r1cs(&cs);
+    let z = extract_z(&cs);
+    let public_inputs = z.elements()[1..r1cs.n_instance()].to_vec();
+
+    let qap = QAP::from_r1cs_with_input_constraints(&r1cs)?;
+    let h = qap.compute_h(&z)?;
+    let (proving_key, verifying_key) = Groth16::<E>::setup(&r1cs, rng)?;
+    let proof = Groth16::prove(&proving_key, &z, rng)?;
+    let verified = Groth16::verify(&verifying_key, &public_inputs, &proof);
+    Ok(Groth16Pipeline {
+        r1cs,
+        z,
+        public_inputs,
+        qap,
+        h,
+        proving_key,
+        verifying_key,
+        proof,
+        verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prove_circuit;
+    use crate::circuits::r1cs::utils::TestPythagoreCircuit;
+    use crate::error::AlgorithmsError;
+    use ark_bn254::{Bn254, Fr};
+    use ark_poly::Polynomial;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_prove_circuit() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let pipeline = prove_circuit::<Bn254, _>(circuit, &mut rng).unwrap();
+        assert!(pipeline.verified);
+        assert_eq!(pipeline.public_inputs, vec![Fr::from(25u64)]);
+        assert!(pipeline.r1cs.is_satisfied(&pipeline.z));
+        // A_z * B_z - C_z = H * Z_H
+        let (a_z, b_z, c_z) = pipeline.qap.combine_with_witness(&pipeline.z).unwrap();
+        assert_eq!(
+            &(&a_z * &b_z) - &c_z,
+            pipeline.qap.z_h().mul_dense(&pipeline.h)
+        );
+        assert!(pipeline.h.degree() < pipeline.qap.z_h().degree());
+
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(26u64));
+        assert_eq!(
+            prove_circuit::<Bn254, _>(circuit, &mut rng).unwrap_err(),
+            AlgorithmsError::UnsatisfiedConstraints
+        );
+    }
+}
@@ -0,0 +1,159 @@
+// snarkjs' groth16 proof.json and verification_key.json, over bn254 (bn128 for snarkjs).
+// Both verifiers check the same equation, e(A, B) = e(alpha, beta) * e(IC, gamma) * e(C, delta), with the
+// public signals in the same order, the (1, x) of z: proofs and keys can be exchanged both ways.
+// vk_alphabeta_12, a cached e(alpha, beta), isn't written and is ignored when reading: snarkjs' verifier
+// doesn't read it either.
+// The json files next to this module were produced by snarkjs, they come from risc0-groth16's test data
+// (Apache-2.0).
+use ark_bn254::{g1, g2, Bn254};
+use serde::{Deserialize, Serialize};
+
+use super::{Groth16Proof, Groth16VerifyingKey};
+use crate::error::AlgorithmsError;
+use crate::snark::snarkjs::{check_header, json_error, SnarkjsPoint};
+
+const PROTOCOL: &str = "groth16";
+const CURVE: &str = "bn128";
+
+#[derive(Serialize, Deserialize)]
+struct SnarkjsProof {
+    pi_a: SnarkjsPoint<g1::Config>,
+    pi_b: SnarkjsPoint<g2::Config>,
+    pi_c: SnarkjsPoint<g1::Config>,
+    protocol: String,
+    curve: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: SnarkjsPoint<g1::Config>,
+    vk_beta_2: SnarkjsPoint<g2::Config>,
+    vk_gamma_2: SnarkjsPoint<g2::Config>,
+    vk_delta_2: SnarkjsPoint<g2::Config>,
+    #[serde(rename = "IC")]
+    ic: Vec<SnarkjsPoint<g1::Config>>,
+}
+
+pub fn proof_to_json(proof: &Groth16Proof<Bn254>) -> String {
+    let proof = SnarkjsProof {
+        pi_a: SnarkjsPoint(proof.a),
+        pi_b: SnarkjsPoint(proof.b),
+        pi_c: SnarkjsPoint(proof.c),
+        protocol: PROTOCOL.to_string(),
+        curve: CURVE.to_string(),
+    };
+    serde_json::to_string_pretty(&proof).expect("serializing to a string doesn't fail")
+}
+
+pub fn proof_from_json(json: &str) -> Result<Groth16Proof<Bn254>, AlgorithmsError> {
+    let proof: SnarkjsProof = serde_json::from_str(json).map_err(json_error)?;
+    check_header(&proof.protocol, &proof.curve, PROTOCOL)?;
+    Ok(Groth16Proof {
+        a: proof.pi_a.0,
+        b: proof.pi_b.0,
+        c: proof.pi_c.0,
+    })
+}
+
+pub fn verifying_key_to_json(verifying_key: &Groth16VerifyingKey<Bn254>) -> String {
+    let verifying_key = SnarkjsVerifyingKey {
+        protocol: PROTOCOL.to_string(),
+        curve: CURVE.to_string(),
+        n_public: verifying_key.n_public_inputs(),
+        vk_alpha_1: SnarkjsPoint(verifying_key.alpha_g1),
+        vk_beta_2: SnarkjsPoint(verifying_key.beta_g2),
+        vk_gamma_2: SnarkjsPoint(verifying_key.gamma_g2),
+        vk_delta_2: SnarkjsPoint(verifying_key.delta_g2),
+        ic: verifying_key.ic.iter().map(|p| SnarkjsPoint(*p)).collect(),
+    };
+    serde_json::to_string_pretty(&verifying_key).expect("serializing to a string doesn't fail")
+}
+
+pub fn verifying_key_from_json(json: &str) -> Result<Groth16VerifyingKey<Bn254>, AlgorithmsError> {
+    let verifying_key: SnarkjsVerifyingKey = serde_json::from_str(json).map_err(json_error)?;
+    check_header(&verifying_key.protocol, &verifying_key.curve, PROTOCOL)?;
+    if verifying_key.ic.len() != verifying_key.n_public + 1 {
+        return Err(AlgorithmsError::Serialization(format!(
+            "{} IC points for {} public signals",
+            verifying_key.ic.len(),
+            verifying_key.n_public
+        )));
+    }
+    Ok(Groth16VerifyingKey {
+        alpha_g1: verifying_key.vk_alpha_1.0,
+        beta_g2: verifying_key.vk_beta_2.0,
+        gamma_g2: verifying_key.vk_gamma_2.0,
+        delta_g2: verifying_key.vk_delta_2.0,
+        ic: verifying_key.ic.into_iter().map(|p| p.0).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proof_from_json, proof_to_json, verifying_key_from_json, verifying_key_to_json};
+    use crate::circuits::r1cs::utils::TestPythagoreCircuit;
+    use crate::snark::groth16::{pipeline::prove_circuit, Groth16};
+    use crate::snark::snarkjs::{public_signals_from_json, public_signals_to_json};
+    use ark_bn254::{Bn254, Fr};
+    use ark_std::test_rng;
+
+    const PROOF: &str = include_str!("proof.json");
+    const VERIFYING_KEY: &str = include_str!("verification_key.json");
+    const PUBLIC: &str = include_str!("public.json");
+
+    #[test]
+    pub fn test_groth16_snarkjs_json() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let pipeline = prove_circuit::<Bn254, _>(circuit, &mut rng).unwrap();
+
+        // through text, as files would be exchanged
+        let proof_json = proof_to_json(&pipeline.proof);
+        let vk_json = verifying_key_to_json(&pipeline.verifying_key);
+        let public_json = public_signals_to_json(&pipeline.public_inputs);
+        let proof = proof_from_json(&proof_json).unwrap();
+        let vk = verifying_key_from_json(&vk_json).unwrap();
+        let public_inputs = public_signals_from_json::<Fr>(&public_json).unwrap();
+        assert_eq!(proof, pipeline.proof);
+        assert_eq!(vk, pipeline.verifying_key);
+        assert!(Groth16::verify(&vk, &public_inputs, &proof));
+
+        // another protocol or curve, or an inconsistent key
+        assert!(proof_from_json(&proof_json.replace("groth16", "plonk")).is_err());
+        assert!(proof_from_json(&proof_json.replace("bn128", "bls12381")).is_err());
+        let wrong_vk = vk_json.replace("\"nPublic\": 1", "\"nPublic\": 2");
+        assert!(verifying_key_from_json(&wrong_vk).is_err());
+    }
+
+    #[test]
+    pub fn test_groth16_snarkjs_fixtures() {
+        // a proof, key and public signals written by snarkjs
+        let proof = proof_from_json(PROOF).unwrap();
+        let vk = verifying_key_from_json(VERIFYING_KEY).unwrap();
+        let public_inputs = public_signals_from_json::<Fr>(PUBLIC).unwrap();
+        assert!(Groth16::verify(&vk, &public_inputs, &proof));
+        assert!(!Groth16::verify(
+            &vk,
+            &[public_inputs[0] + Fr::from(1u64)],
+            &proof
+        ));
+
+        // which are written back with the same values, vk_alphabeta_12 aside
+        let values = |json: &str| serde_json::from_str::<serde_json::Value>(json).unwrap();
+        assert_eq!(values(&proof_to_json(&proof)), values(PROOF));
+        let mut expected_vk = values(VERIFYING_KEY);
+        expected_vk
+            .as_object_mut()
+            .unwrap()
+            .remove("vk_alphabeta_12");
+        assert_eq!(values(&verifying_key_to_json(&vk)), expected_vk);
+        assert_eq!(
+            values(&public_signals_to_json(&public_inputs)),
+            values(PUBLIC)
+        );
+    }
+}
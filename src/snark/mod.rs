@@ -0,0 +1,7 @@
+pub mod groth16;
+pub mod pinocchio;
+pub mod plonk;
+#[cfg(feature = "serde")]
+pub mod snarkjs;
+pub mod spartan;
+pub mod stark;
@@ -0,0 +1,247 @@
+// Pinocchio: https://eprint.iacr.org/2013/279 (protocol 2), in its asymmetric pairing version
+// from https://eprint.iacr.org/2013/879 (appendix B), over the qap of an r1cs.
+// With z = (1, x, w), A_z(X) = sum_i z_i * A_i(X) (same for B, C) and H(X) = (A_z * B_z - C_z) / Z_H:
+// - setup samples tau, rho_A, rho_B, alpha_A, alpha_B, alpha_C, beta, gamma (the toxic waste), rho_C = rho_A * rho_B,
+//   and publishes the A_i, B_i, C_i evaluated at tau, each in the clear and multiplied by its alpha,
+// - the proof holds pi_A = [rho_A * A_w(tau)]_1, pi_B = [rho_B * B_w(tau)]_2, pi_C = [rho_C * C_w(tau)]_1,
+//   (A_w being the sum over the witness indices), their alpha-shifted versions pi_A', pi_B', pi_C',
+//   pi_K = [beta * (rho_A * A_w + rho_B * B_w + rho_C * C_w)(tau)]_1 and pi_H = [H(tau)]_1: 8 group elements,
+// - the verifier runs three separate kinds of checks:
+//   1. knowledge: e(pi_A, [alpha_A]_2) = e(pi_A', g_2), e([alpha_B]_1, pi_B) = e(pi_B', g_2),
+//      e(pi_C, [alpha_C]_2) = e(pi_C', g_2), each pi being a combination of the srs,
+//   2. same coefficients: e(pi_K, [gamma]_2) = e(pi_A + pi_C, [beta * gamma]_2) * e([beta * gamma]_1, pi_B),
+//      A, B and C use the same w,
+//   3. divisibility: e(vk_A + pi_A, vk_B + pi_B) = e(pi_H, [rho_C * Z_H(tau)]_2) * e(vk_C + pi_C, g_2),
+//      where vk_A, vk_B, vk_C are the contributions of the public (1, x), computed by the verifier.
+// Groth16 merges all of it in a single pairing equation with a 3 element proof.
+// This version is not zero-knowledge: the paper adds random multiples of Z_H to A_w, B_w and C_w.
+use ark_ec::{pairing::Pairing, Group};
+use ark_ff::{Field, UniformRand};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Polynomial};
+use ark_std::{marker::PhantomData, rand::Rng};
+
+use crate::circuits::qap::QAP;
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::utils::{
+    curve::{msm, FixedBaseTable},
+    pairing::multi_pairing_check,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PinocchioProof<E: Pairing> {
+    pub a: E::G1,
+    pub a_prime: E::G1,
+    pub b: E::G2,
+    pub b_prime: E::G1,
+    pub c: E::G1,
+    pub c_prime: E::G1,
+    pub k: E::G1,
+    pub h: E::G1,
+}
+
+/// The evaluation key: srs elements for the witness indices, and powers of tau for H
+pub struct PinocchioProvingKey<E: Pairing> {
+    qap: QAP<E::ScalarField>,
+    n_instance: usize,
+    a: Vec<E::G1>,
+    a_prime: Vec<E::G1>,
+    b: Vec<E::G2>,
+    b_prime: Vec<E::G1>,
+    c: Vec<E::G1>,
+    c_prime: Vec<E::G1>,
+    k: Vec<E::G1>,
+    powers_of_tau: Vec<E::G1>,
+}
+
+/// The verification key, with the srs elements of the public (1, x) indices
+#[derive(Clone, Debug, PartialEq)]
+pub struct PinocchioVerifyingKey<E: Pairing> {
+    alpha_a: E::G2,
+    alpha_b: E::G1,
+    alpha_c: E::G2,
+    gamma: E::G2,
+    beta_gamma_1: E::G1,
+    beta_gamma_2: E::G2,
+    rho_c_z_tau: E::G2,
+    io_a: Vec<E::G1>,
+    io_b: Vec<E::G2>,
+    io_c: Vec<E::G1>,
+}
+
+pub struct Pinocchio<E: Pairing> {
+    _pairing: PhantomData<E>,
+}
+
+impl<E: Pairing> Pinocchio<E> {
+    /// Samples the toxic waste and computes the keys for the qap of `r1cs`
+    pub fn setup<R: Rng>(
+        r1cs: &R1CS<E::ScalarField>,
+        rng: &mut R,
+    ) -> (PinocchioProvingKey<E>, PinocchioVerifyingKey<E>) {
+        let qap = QAP::from_r1cs(r1cs).unwrap();
+        let mut sample = || E::ScalarField::rand(rng);
+        let (tau, rho_a, rho_b) = (sample(), sample(), sample());
+        let (alpha_a, alpha_b, alpha_c) = (sample(), sample(), sample());
+        let (beta, gamma) = (sample(), sample());
+        let rho_c = rho_a * rho_b;
+        let (g_1, g_2) = (E::G1::generator(), E::G2::generator());
+
+        // rho_A * A_i(tau), rho_B * B_i(tau), rho_C * C_i(tau)
+        let at_tau = |polys: &[DensePolynomial<E::ScalarField>], rho: E::ScalarField| {
+            polys
+                .iter()
+                .map(|poly| rho * poly.evaluate(&tau))
+                .collect::<Vec<_>>()
+        };
+        let a_tau = at_tau(qap.a(), rho_a);
+        let b_tau = at_tau(qap.b(), rho_b);
+        let c_tau = at_tau(qap.c(), rho_c);
+        let g1_table = FixedBaseTable::new(g_1, 7 * a_tau.len() + qap.domain().size());
+        let to_g1 = |values: &[E::ScalarField], factor: E::ScalarField| -> Vec<E::G1> {
+            g1_table.batch_mul(&values.iter().map(|v| *v * factor).collect::<Vec<_>>())
+        };
+        let n_instance = r1cs.n_instance();
+        let (io, w) = (..n_instance, n_instance..);
+
+        let k = a_tau
+            .iter()
+            .zip(&b_tau)
+            .zip(&c_tau)
+            .map(|((a, b), c)| beta * (*a + b + c))
+            .collect::<Vec<_>>();
+        let mut power = E::ScalarField::ONE;
+        let mut powers = vec![];
+        for _ in 0..qap.domain().size() {
+            powers.push(power);
+            power *= tau;
+        }
+        let powers_of_tau = g1_table.batch_mul(&powers);
+        let proving_key = PinocchioProvingKey {
+            n_instance,
+            a: to_g1(&a_tau[w.clone()], E::ScalarField::ONE),
+            a_prime: to_g1(&a_tau[w.clone()], alpha_a),
+            b: FixedBaseTable::new(g_2, b_tau.len()).batch_mul(&b_tau[w.clone()]),
+            b_prime: to_g1(&b_tau[w.clone()], alpha_b),
+            c: to_g1(&c_tau[w.clone()], E::ScalarField::ONE),
+            c_prime: to_g1(&c_tau[w.clone()], alpha_c),
+            k: to_g1(&k[w], E::ScalarField::ONE),
+            powers_of_tau,
+            qap,
+        };
+        let z_tau = proving_key.qap.z_h().evaluate(&tau);
+        let verifying_key = PinocchioVerifyingKey {
+            alpha_a: g_2 * alpha_a,
+            alpha_b: g_1 * alpha_b,
+            alpha_c: g_2 * alpha_c,
+            gamma: g_2 * gamma,
+            beta_gamma_1: g_1 * (beta * gamma),
+            beta_gamma_2: g_2 * (beta * gamma),
+            rho_c_z_tau: g_2 * (rho_c * z_tau),
+            io_a: to_g1(&a_tau[io], E::ScalarField::ONE),
+            io_b: b_tau[io].iter().map(|b| g_2 * b).collect(),
+            io_c: to_g1(&c_tau[io], E::ScalarField::ONE),
+        };
+        (proving_key, verifying_key)
+    }
+
+    /// Proves that z = (1, x, w) satisfies the r1cs, None if it doesn't
+    pub fn prove(
+        proving_key: &PinocchioProvingKey<E>,
+        z: &R1CSInstanceWitness<E::ScalarField>,
+    ) -> Option<PinocchioProof<E>> {
+        let h = proving_key.qap.compute_h(z).ok()?;
+        let w = &z.elements()[proving_key.n_instance..];
+        Some(PinocchioProof {
+            a: msm(&proving_key.a, w).ok()?,
+            a_prime: msm(&proving_key.a_prime, w).ok()?,
+            b: msm(&proving_key.b, w).ok()?,
+            b_prime: msm(&proving_key.b_prime, w).ok()?,
+            c: msm(&proving_key.c, w).ok()?,
+            c_prime: msm(&proving_key.c_prime, w).ok()?,
+            k: msm(&proving_key.k, w).ok()?,
+            h: msm(&proving_key.powers_of_tau[..h.coeffs.len()], &h.coeffs).ok()?,
+        })
+    }
+
+    /// Verifies that the prover knows w such that (1, x, w) satisfies the r1cs, x being `public_inputs`
+    pub fn verify(
+        verifying_key: &PinocchioVerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+        proof: &PinocchioProof<E>,
+    ) -> bool {
+        if public_inputs.len() + 1 != verifying_key.io_a.len() {
+            return false;
+        }
+        let io = [&[E::ScalarField::ONE], public_inputs].concat();
+        let (Ok(vk_a), Ok(vk_b), Ok(vk_c)) = (
+            msm(&verifying_key.io_a, &io),
+            msm(&verifying_key.io_b, &io),
+            msm(&verifying_key.io_c, &io),
+        ) else {
+            return false;
+        };
+        let g_2 = E::G2::generator();
+
+        // 1. knowledge checks, one per polynomial
+        let knowledge =
+            multi_pairing_check::<E>(&[(proof.a, verifying_key.alpha_a), (-proof.a_prime, g_2)])
+                && multi_pairing_check::<E>(&[
+                    (verifying_key.alpha_b, proof.b),
+                    (-proof.b_prime, g_2),
+                ])
+                && multi_pairing_check::<E>(&[
+                    (proof.c, verifying_key.alpha_c),
+                    (-proof.c_prime, g_2),
+                ]);
+        // 2. A, B and C use the same coefficients
+        let same_coefficients = multi_pairing_check::<E>(&[
+            (proof.k, verifying_key.gamma),
+            (-(proof.a + proof.c), verifying_key.beta_gamma_2),
+            (-verifying_key.beta_gamma_1, proof.b),
+        ]);
+        // 3. A_z * B_z - C_z = H * Z_H at tau
+        let divisibility = multi_pairing_check::<E>(&[
+            (vk_a + proof.a, vk_b + proof.b),
+            (-proof.h, verifying_key.rho_c_z_tau),
+            (-(vk_c + proof.c), g_2),
+        ]);
+        knowledge && same_coefficients && divisibility
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pinocchio;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use ark_bn254::{Bn254, Fr};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_pinocchio() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let (pk, vk) = Pinocchio::<Bn254>::setup(&r1cs, &mut rng);
+
+        let proof = Pinocchio::prove(&pk, &z).unwrap();
+        assert!(Pinocchio::verify(&vk, &[Fr::from(25u64)], &proof));
+
+        // wrong public input
+        assert!(!Pinocchio::verify(&vk, &[Fr::from(26u64)], &proof));
+        // each check catches a tampered element
+        let mut wrong_proof = proof.clone();
+        wrong_proof.a_prime = wrong_proof.a;
+        assert!(!Pinocchio::verify(&vk, &[Fr::from(25u64)], &wrong_proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.k = wrong_proof.c;
+        assert!(!Pinocchio::verify(&vk, &[Fr::from(25u64)], &wrong_proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.h = wrong_proof.h + wrong_proof.h;
+        assert!(!Pinocchio::verify(&vk, &[Fr::from(25u64)], &wrong_proof));
+
+        // an unsatisfying assignment can't be proven
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(26u64));
+        assert!(Pinocchio::<Bn254>::prove(&pk, &get_z_from_cs(circuit).unwrap()).is_none());
+    }
+}
@@ -0,0 +1,631 @@
+// PLONK: https://eprint.iacr.org/2019/953 (section 8), over KZG and a plonkish circuit of n rows.
+// With the wire polynomials a, b, c, the selectors q_* and the permutation polynomials S_sigma1..3
+// interpolated over H = <omega>, and the cosets H, k_1 H, k_2 H labelling the 3n wire positions:
+// - round 1: commit to a, b, c, blinded with multiples of Z_H,
+// - round 2: commit to the permutation accumulator z, z(omega^0) = 1 and
+//   z(omega^{i+1}) = z(omega^i) * prod_j (w_j,i + beta * k_j * omega^i + gamma) / (w_j,i + beta * S_sigmaj(omega^i) + gamma),
+// - round 3: commit to the quotient t = (gate + alpha * permutation + alpha^2 * (z - 1) * L_1) / Z_H,
+//   split into t_lo, t_mid, t_hi of degree n + 1, n + 1 and n + 5,
+// - round 4: evaluate a, b, c, S_sigma1, S_sigma2 at zeta and z at zeta * omega,
+// - round 5: open the linearization r at zeta along with the evaluated polynomials, and z at zeta * omega.
+//   r is linear in the committed polynomials once the evaluations are known, and r(zeta) = 0.
+// The verifier computes [r] - r(0) from the commitments and checks both batched openings with two pairings.
+// Challenges are derived with a Fiat-Shamir transcript.
+use ark_ec::pairing::Pairing;
+use ark_ff::{FftField, Field, UniformRand};
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
+    GeneralEvaluationDomain, Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{marker::PhantomData, rand::Rng, Zero};
+
+use crate::circuits::plonkish::{PlonkishCircuit, PlonkishWitness};
+use crate::cs::pcs::kzg::KZG;
+use crate::error::AlgorithmsError;
+use crate::transcript::Transcript;
+use crate::utils::curve::{check_subgroup_all, SubgroupCheck};
+use crate::utils::polynomial::{linear_combination, sparse::SparsePolynomial, vanishing_poly};
+
+#[cfg(feature = "serde")]
+pub mod snarkjs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlonkError {
+    /// The srs can't commit to the blinded quotient pieces
+    SrsTooSmall {
+        needed: usize,
+        degree: usize,
+    },
+    /// The witness doesn't satisfy the gates or the copy constraints
+    UnsatisfiedCircuit,
+    PublicInputsLength {
+        expected: usize,
+        got: usize,
+    },
+    /// A proof point isn't on the curve or in the prime order subgroup
+    InvalidProofPoint,
+    /// The batched kzg openings don't verify
+    PairingCheckFailed,
+    /// A kzg commitment or opening failed
+    Kzg(AlgorithmsError),
+}
+
+impl From<AlgorithmsError> for PlonkError {
+    fn from(error: AlgorithmsError) -> Self {
+        PlonkError::Kzg(error)
+    }
+}
+
+impl std::fmt::Display for PlonkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlonkError::SrsTooSmall { needed, degree } => {
+                write!(f, "srs of degree {degree}, the circuit needs {needed}")
+            }
+            PlonkError::UnsatisfiedCircuit => write!(f, "the witness doesn't satisfy the circuit"),
+            PlonkError::PublicInputsLength { expected, got } => {
+                write!(f, "expected {expected} public inputs, got {got}")
+            }
+            PlonkError::InvalidProofPoint => write!(f, "invalid proof point"),
+            PlonkError::PairingCheckFailed => write!(f, "pairing check failed"),
+            PlonkError::Kzg(error) => write!(f, "kzg: {error}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlonkProof<E: Pairing> {
+    pub a: E::G1,
+    pub b: E::G1,
+    pub c: E::G1,
+    pub z: E::G1,
+    pub t_lo: E::G1,
+    pub t_mid: E::G1,
+    pub t_hi: E::G1,
+    pub w_zeta: E::G1,
+    pub w_zeta_omega: E::G1,
+    pub a_eval: E::ScalarField,
+    pub b_eval: E::ScalarField,
+    pub c_eval: E::ScalarField,
+    pub s_sigma_1_eval: E::ScalarField,
+    pub s_sigma_2_eval: E::ScalarField,
+    pub z_omega_eval: E::ScalarField,
+}
+
+/// Commitments to the selectors and the permutation polynomials
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PlonkVerifyingKey<E: Pairing> {
+    n: usize,
+    n_public: usize,
+    k_1: E::ScalarField,
+    k_2: E::ScalarField,
+    q_m: E::G1,
+    q_l: E::G1,
+    q_r: E::G1,
+    q_o: E::G1,
+    q_c: E::G1,
+    s_sigma_1: E::G1,
+    s_sigma_2: E::G1,
+    s_sigma_3: E::G1,
+}
+
+pub struct PlonkProvingKey<E: Pairing> {
+    circuit: PlonkishCircuit<E::ScalarField>,
+    domain: GeneralEvaluationDomain<E::ScalarField>,
+    q_m: DensePolynomial<E::ScalarField>,
+    q_l: DensePolynomial<E::ScalarField>,
+    q_r: DensePolynomial<E::ScalarField>,
+    q_o: DensePolynomial<E::ScalarField>,
+    q_c: DensePolynomial<E::ScalarField>,
+    /// S_sigma1, S_sigma2, S_sigma3 and their evaluations over H, concatenated
+    s_sigma: [DensePolynomial<E::ScalarField>; 3],
+    s_sigma_evals: Vec<E::ScalarField>,
+    vk: PlonkVerifyingKey<E>,
+}
+
+impl<E: Pairing> PlonkProvingKey<E> {
+    pub fn vk(&self) -> &PlonkVerifyingKey<E> {
+        &self.vk
+    }
+}
+
+pub struct Plonk<E: Pairing> {
+    _pairing: PhantomData<E>,
+}
+
+fn interpolate<F: FftField>(domain: GeneralEvaluationDomain<F>, evals: &[F]) -> DensePolynomial<F> {
+    Evaluations::from_vec_and_domain(evals.to_vec(), domain).interpolate()
+}
+
+/// poly + (b_0 + b_1 * X + ...) * Z_H, which agrees with poly over H
+fn blind<F: FftField, R: Rng>(
+    poly: DensePolynomial<F>,
+    z_h: &SparsePolynomial<F>,
+    n_blinding: usize,
+    rng: &mut R,
+) -> DensePolynomial<F> {
+    let blinding =
+        DensePolynomial::from_coefficients_vec((0..n_blinding).map(|_| F::rand(rng)).collect());
+    &poly + &z_h.mul_dense(&blinding)
+}
+
+/// p(omega * X)
+fn shift<F: Field>(poly: &DensePolynomial<F>, omega: F) -> DensePolynomial<F> {
+    let mut power = F::ONE;
+    let mut coeffs = vec![];
+    for c in &poly.coeffs {
+        coeffs.push(*c * power);
+        power *= omega;
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
+
+/// The coefficients of poly in [from, to), as a polynomial
+fn piece<F: Field>(poly: &DensePolynomial<F>, from: usize, to: usize) -> DensePolynomial<F> {
+    let to = to.min(poly.coeffs.len());
+    DensePolynomial::from_coefficients_slice(if from < to {
+        &poly.coeffs[from..to]
+    } else {
+        &[]
+    })
+}
+
+impl<E: Pairing> Plonk<E>
+where
+    E::G1: SubgroupCheck,
+    E::G2: SubgroupCheck,
+{
+    /// Interpolates and commits to the selectors and the permutation of the circuit
+    pub fn setup(
+        kzg: &KZG<E>,
+        circuit: &PlonkishCircuit<E::ScalarField>,
+    ) -> Result<PlonkProvingKey<E>, PlonkError> {
+        let n = circuit.n();
+        let needed = n + 5;
+        if kzg.degree() < needed {
+            return Err(PlonkError::SrsTooSmall {
+                needed,
+                degree: kzg.degree(),
+            });
+        }
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(n).unwrap();
+        // H, k_1 H and k_2 H are disjoint since the generator of F* has an order larger than n
+        let k_1 = E::ScalarField::GENERATOR;
+        let k_2 = k_1.square();
+        let omegas = domain.elements().collect::<Vec<_>>();
+        let s_sigma_evals = circuit
+            .sigma()
+            .iter()
+            .map(|j| [E::ScalarField::ONE, k_1, k_2][j / n] * omegas[j % n])
+            .collect::<Vec<_>>();
+        let s_sigma = [0, 1, 2].map(|k| interpolate(domain, &s_sigma_evals[k * n..(k + 1) * n]));
+        let [q_m, q_l, q_r, q_o, q_c] = [
+            circuit.q_m(),
+            circuit.q_l(),
+            circuit.q_r(),
+            circuit.q_o(),
+            circuit.q_c(),
+        ]
+        .map(|q| interpolate(domain, q));
+        let vk = PlonkVerifyingKey {
+            n,
+            n_public: circuit.n_public(),
+            k_1,
+            k_2,
+            q_m: kzg.commit(&q_m)?,
+            q_l: kzg.commit(&q_l)?,
+            q_r: kzg.commit(&q_r)?,
+            q_o: kzg.commit(&q_o)?,
+            q_c: kzg.commit(&q_c)?,
+            s_sigma_1: kzg.commit(&s_sigma[0])?,
+            s_sigma_2: kzg.commit(&s_sigma[1])?,
+            s_sigma_3: kzg.commit(&s_sigma[2])?,
+        };
+        Ok(PlonkProvingKey {
+            circuit: circuit.clone(),
+            domain,
+            q_m,
+            q_l,
+            q_r,
+            q_o,
+            q_c,
+            s_sigma,
+            s_sigma_evals,
+            vk,
+        })
+    }
+
+    fn transcript(vk: &PlonkVerifyingKey<E>, public_inputs: &[E::ScalarField]) -> Transcript {
+        let mut transcript = Transcript::new(b"plonk");
+        for commitment in [
+            vk.q_m,
+            vk.q_l,
+            vk.q_r,
+            vk.q_o,
+            vk.q_c,
+            vk.s_sigma_1,
+            vk.s_sigma_2,
+            vk.s_sigma_3,
+        ] {
+            transcript.absorb(&commitment);
+        }
+        transcript.absorb(public_inputs);
+        transcript
+    }
+
+    pub fn prove<R: Rng>(
+        kzg: &KZG<E>,
+        pk: &PlonkProvingKey<E>,
+        witness: &PlonkishWitness<E::ScalarField>,
+        rng: &mut R,
+    ) -> Result<PlonkProof<E>, PlonkError> {
+        let circuit = &pk.circuit;
+        let public_inputs = witness
+            .a
+            .get(..circuit.n_public())
+            .ok_or(PlonkError::UnsatisfiedCircuit)?
+            .to_vec();
+        if !circuit.is_satisfied(witness, &public_inputs) {
+            return Err(PlonkError::UnsatisfiedCircuit);
+        }
+        let (n, domain) = (pk.vk.n, pk.domain);
+        let omega = domain.group_gen();
+        let (k_1, k_2) = (pk.vk.k_1, pk.vk.k_2);
+        let z_h = vanishing_poly(domain);
+        let mut transcript = Self::transcript(&pk.vk, &public_inputs);
+
+        // round 1: wire polynomials
+        let a = blind(interpolate(domain, &witness.a), &z_h, 2, rng);
+        let b = blind(interpolate(domain, &witness.b), &z_h, 2, rng);
+        let c = blind(interpolate(domain, &witness.c), &z_h, 2, rng);
+        let (a_commitment, b_commitment, c_commitment) =
+            (kzg.commit(&a)?, kzg.commit(&b)?, kzg.commit(&c)?);
+        for commitment in [a_commitment, b_commitment, c_commitment] {
+            transcript.absorb(&commitment);
+        }
+        let beta: E::ScalarField = transcript.challenge();
+        let gamma: E::ScalarField = transcript.challenge();
+
+        // round 2: permutation accumulator
+        let wires = witness.wires();
+        let mut accumulator = vec![E::ScalarField::ONE];
+        let mut omega_i = E::ScalarField::ONE;
+        for i in 0..n - 1 {
+            let mut numerator = E::ScalarField::ONE;
+            let mut denominator = E::ScalarField::ONE;
+            for (j, k) in [E::ScalarField::ONE, k_1, k_2].iter().enumerate() {
+                numerator *= wires[j * n + i] + beta * k * omega_i + gamma;
+                denominator *= wires[j * n + i] + beta * pk.s_sigma_evals[j * n + i] + gamma;
+            }
+            accumulator.push(accumulator[i] * numerator * denominator.inverse().unwrap());
+            omega_i *= omega;
+        }
+        let z = blind(interpolate(domain, &accumulator), &z_h, 3, rng);
+        let z_commitment = kzg.commit(&z)?;
+        transcript.absorb(&z_commitment);
+        let alpha: E::ScalarField = transcript.challenge();
+
+        // round 3: quotient
+        let mut pi_evals = vec![E::ScalarField::zero(); n];
+        for (pi, x) in pi_evals.iter_mut().zip(&public_inputs) {
+            *pi = -*x;
+        }
+        let pi = interpolate(domain, &pi_evals);
+        let mut l_1_evals = vec![E::ScalarField::zero(); n];
+        l_1_evals[0] = E::ScalarField::ONE;
+        let l_1 = interpolate(domain, &l_1_evals);
+        let constant = |c| DensePolynomial::from_coefficients_vec(vec![c]);
+        // w + beta * s + gamma, s being either the identity k_j * X or S_sigmaj
+        let gamma_poly = constant(gamma);
+        let permuted = |w: &DensePolynomial<E::ScalarField>,
+                        s: &DensePolynomial<E::ScalarField>| {
+            &(w + &(s * beta)) + &gamma_poly
+        };
+        let identity = |k: E::ScalarField| {
+            DensePolynomial::from_coefficients_vec(vec![E::ScalarField::zero(), k])
+        };
+        let gate = &(&(&(&(&(&a * &b) * &pk.q_m) + &(&a * &pk.q_l)) + &(&b * &pk.q_r))
+            + &(&c * &pk.q_o))
+            + &(&pk.q_c + &pi);
+        let permutation = &(&(&(&permuted(&a, &identity(E::ScalarField::ONE))
+            * &permuted(&b, &identity(k_1)))
+            * &permuted(&c, &identity(k_2)))
+            * &z)
+            - &(&(&(&permuted(&a, &pk.s_sigma[0]) * &permuted(&b, &pk.s_sigma[1]))
+                * &permuted(&c, &pk.s_sigma[2]))
+                * &shift(&z, omega));
+        let z_minus_one = &z - &constant(E::ScalarField::ONE);
+        let numerator = linear_combination(&[
+            (E::ScalarField::ONE, &gate),
+            (alpha, &permutation),
+            (alpha.square(), &(&z_minus_one * &l_1)),
+        ]);
+        let (t, remainder) = numerator.divide_by_vanishing_poly(domain).unwrap();
+        if !remainder.is_zero() {
+            return Err(PlonkError::UnsatisfiedCircuit);
+        }
+        // t = t_lo + X^{n+2} * t_mid + X^{2n+4} * t_hi, with the pieces blinded by b_10 and b_11
+        let (b_10, b_11) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+        let x_n_2 = |coeff| {
+            let mut coeffs = vec![E::ScalarField::zero(); n + 3];
+            coeffs[n + 2] = coeff;
+            DensePolynomial::from_coefficients_vec(coeffs)
+        };
+        let t_lo = &piece(&t, 0, n + 2) + &x_n_2(b_10);
+        let t_mid = &(&piece(&t, n + 2, 2 * n + 4) - &constant(b_10)) + &x_n_2(b_11);
+        let t_hi = &piece(&t, 2 * n + 4, t.coeffs.len()) - &constant(b_11);
+        let (t_lo_commitment, t_mid_commitment, t_hi_commitment) =
+            (kzg.commit(&t_lo)?, kzg.commit(&t_mid)?, kzg.commit(&t_hi)?);
+        for commitment in [t_lo_commitment, t_mid_commitment, t_hi_commitment] {
+            transcript.absorb(&commitment);
+        }
+        let zeta: E::ScalarField = transcript.challenge();
+
+        // round 4: evaluations
+        let evals = [&a, &b, &c, &pk.s_sigma[0], &pk.s_sigma[1]].map(|p| p.evaluate(&zeta));
+        let [a_eval, b_eval, c_eval, s_sigma_1_eval, s_sigma_2_eval] = evals;
+        let z_omega_eval = z.evaluate(&(zeta * omega));
+        for eval in evals.iter().chain([&z_omega_eval]) {
+            transcript.absorb(eval);
+        }
+        let v: E::ScalarField = transcript.challenge();
+
+        // round 5: linearization and openings
+        let z_h_zeta = domain.evaluate_vanishing_polynomial(zeta);
+        let l_1_zeta = l_1.evaluate(&zeta);
+        let identity_product = (a_eval + beta * zeta + gamma)
+            * (b_eval + beta * k_1 * zeta + gamma)
+            * (c_eval + beta * k_2 * zeta + gamma);
+        let sigma_product =
+            (a_eval + beta * s_sigma_1_eval + gamma) * (b_eval + beta * s_sigma_2_eval + gamma);
+        let zeta_n_2 = zeta.pow([n as u64 + 2]);
+        let r = &linear_combination(&[
+            (a_eval * b_eval, &pk.q_m),
+            (a_eval, &pk.q_l),
+            (b_eval, &pk.q_r),
+            (c_eval, &pk.q_o),
+            (E::ScalarField::ONE, &pk.q_c),
+            (alpha * identity_product + alpha.square() * l_1_zeta, &z),
+            (-alpha * sigma_product * beta * z_omega_eval, &pk.s_sigma[2]),
+            (-z_h_zeta, &t_lo),
+            (-z_h_zeta * zeta_n_2, &t_mid),
+            (-z_h_zeta * zeta_n_2.square(), &t_hi),
+        ]) + &constant(
+            pi.evaluate(&zeta)
+                - alpha * sigma_product * (c_eval + gamma) * z_omega_eval
+                - alpha.square() * l_1_zeta,
+        );
+        debug_assert!(r.evaluate(&zeta).is_zero());
+        let mut v_i = E::ScalarField::ONE;
+        let mut terms = vec![(v_i, &r)];
+        for p in [&a, &b, &c, &pk.s_sigma[0], &pk.s_sigma[1]] {
+            v_i *= v;
+            terms.push((v_i, p));
+        }
+        let (_, w_zeta) = kzg.open_linear_combination(&terms, zeta)?;
+
+        Ok(PlonkProof {
+            a: a_commitment,
+            b: b_commitment,
+            c: c_commitment,
+            z: z_commitment,
+            t_lo: t_lo_commitment,
+            t_mid: t_mid_commitment,
+            t_hi: t_hi_commitment,
+            w_zeta,
+            w_zeta_omega: kzg
+                .open(&z, zeta * omega, z_omega_eval)
+                .expect("z_omega_eval is the evaluation at zeta * omega"),
+            a_eval,
+            b_eval,
+            c_eval,
+            s_sigma_1_eval,
+            s_sigma_2_eval,
+            z_omega_eval,
+        })
+    }
+
+    /// Verifies the proof against the public inputs, with the error of the first failing check
+    pub fn verify(
+        kzg: &KZG<E>,
+        vk: &PlonkVerifyingKey<E>,
+        public_inputs: &[E::ScalarField],
+        proof: &PlonkProof<E>,
+    ) -> Result<(), PlonkError> {
+        if public_inputs.len() != vk.n_public {
+            return Err(PlonkError::PublicInputsLength {
+                expected: vk.n_public,
+                got: public_inputs.len(),
+            });
+        }
+        if !check_subgroup_all(&[
+            proof.a,
+            proof.b,
+            proof.c,
+            proof.z,
+            proof.t_lo,
+            proof.t_mid,
+            proof.t_hi,
+            proof.w_zeta,
+            proof.w_zeta_omega,
+        ]) {
+            return Err(PlonkError::InvalidProofPoint);
+        }
+
+        // challenges
+        let mut transcript = Self::transcript(vk, public_inputs);
+        for commitment in [proof.a, proof.b, proof.c] {
+            transcript.absorb(&commitment);
+        }
+        let beta: E::ScalarField = transcript.challenge();
+        let gamma: E::ScalarField = transcript.challenge();
+        transcript.absorb(&proof.z);
+        let alpha: E::ScalarField = transcript.challenge();
+        for commitment in [proof.t_lo, proof.t_mid, proof.t_hi] {
+            transcript.absorb(&commitment);
+        }
+        let zeta: E::ScalarField = transcript.challenge();
+        let evals = [
+            proof.a_eval,
+            proof.b_eval,
+            proof.c_eval,
+            proof.s_sigma_1_eval,
+            proof.s_sigma_2_eval,
+            proof.z_omega_eval,
+        ];
+        for eval in &evals {
+            transcript.absorb(eval);
+        }
+        let v: E::ScalarField = transcript.challenge();
+        transcript.absorb(&proof.w_zeta);
+        transcript.absorb(&proof.w_zeta_omega);
+        let u: E::ScalarField = transcript.challenge();
+
+        // Z_H(zeta), L_1(zeta) and PI(zeta), with L_i(zeta) = omega^i * Z_H(zeta) / (n * (zeta - omega^i))
+        let domain = GeneralEvaluationDomain::<E::ScalarField>::new(vk.n).unwrap();
+        let omega = domain.group_gen();
+        let z_h_zeta = domain.evaluate_vanishing_polynomial(zeta);
+        let lagrange = |omega_i: E::ScalarField| {
+            omega_i
+                * z_h_zeta
+                * (E::ScalarField::from(vk.n as u64) * (zeta - omega_i))
+                    .inverse()
+                    .unwrap()
+        };
+        let l_1_zeta = lagrange(E::ScalarField::ONE);
+        let pi_zeta = domain
+            .elements()
+            .zip(public_inputs)
+            .map(|(omega_i, x)| -*x * lagrange(omega_i))
+            .sum::<E::ScalarField>();
+
+        // r(0), and [D] = [r] - r(0) + u * [z]
+        let (a_eval, b_eval, c_eval) = (proof.a_eval, proof.b_eval, proof.c_eval);
+        let sigma_product = (a_eval + beta * proof.s_sigma_1_eval + gamma)
+            * (b_eval + beta * proof.s_sigma_2_eval + gamma);
+        let r_0 = pi_zeta
+            - alpha * sigma_product * (c_eval + gamma) * proof.z_omega_eval
+            - alpha.square() * l_1_zeta;
+        let identity_product = (a_eval + beta * zeta + gamma)
+            * (b_eval + beta * vk.k_1 * zeta + gamma)
+            * (c_eval + beta * vk.k_2 * zeta + gamma);
+        let zeta_n_2 = zeta.pow([vk.n as u64 + 2]);
+        let d = KZG::<E>::commit_linear_combination(&[
+            (a_eval * b_eval, vk.q_m),
+            (a_eval, vk.q_l),
+            (b_eval, vk.q_r),
+            (c_eval, vk.q_o),
+            (E::ScalarField::ONE, vk.q_c),
+            (
+                alpha * identity_product + alpha.square() * l_1_zeta + u,
+                proof.z,
+            ),
+            (
+                -alpha * sigma_product * beta * proof.z_omega_eval,
+                vk.s_sigma_3,
+            ),
+            (-z_h_zeta, proof.t_lo),
+            (-z_h_zeta * zeta_n_2, proof.t_mid),
+            (-z_h_zeta * zeta_n_2.square(), proof.t_hi),
+        ]);
+
+        // [F] = [D] + v [a] + v^2 [b] + .., [E] = (-r(0) + v * a(zeta) + .. + u * z(zeta * omega)) [1]_1
+        let mut v_i = E::ScalarField::ONE;
+        let mut f = d;
+        let mut e = -r_0 + u * proof.z_omega_eval;
+        for (commitment, eval) in [proof.a, proof.b, proof.c, vk.s_sigma_1, vk.s_sigma_2]
+            .iter()
+            .zip(&evals)
+        {
+            v_i *= v;
+            f += *commitment * v_i;
+            e += v_i * eval;
+        }
+
+        // e(W_zeta + u * W_zeta_omega, [tau]_2) = e(zeta * W_zeta + u * zeta * omega * W_zeta_omega + F - E, g_2)
+        let lhs = proof.w_zeta + proof.w_zeta_omega * u;
+        let rhs = proof.w_zeta * zeta + proof.w_zeta_omega * (u * zeta * omega) + f - kzg.g1() * e;
+        if !E::multi_pairing([lhs, -rhs], [kzg.vk(), kzg.g2()]).is_zero() {
+            return Err(PlonkError::PairingCheckFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Plonk, PlonkError};
+    use crate::circuits::plonkish::{PlonkishBuilder, PlonkishCircuit, PlonkishWitness};
+    use crate::cs::pcs::kzg::KZG;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_std::test_rng;
+
+    /// x^3 + x + 5 = y, y public
+    fn cubic_circuit(x: u64, y: u64) -> (PlonkishCircuit<Fr>, PlonkishWitness<Fr>) {
+        let mut builder = PlonkishBuilder::new();
+        let y = builder.public_input(Fr::from(y));
+        let x = builder.private_input(Fr::from(x));
+        let x_2 = builder.mul(x, x);
+        let x_3 = builder.mul(x_2, x);
+        let sum = builder.add(x_3, x);
+        let result = builder.add_constant(sum, Fr::from(5u64));
+        builder.assert_equal(result, y);
+        builder.build()
+    }
+
+    #[test]
+    pub fn test_plonk() {
+        let mut rng = test_rng();
+        let (circuit, witness) = cubic_circuit(3, 35);
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 16);
+        kzg.setup_from_rng(&mut rng);
+        let pk = Plonk::setup(&kzg, &circuit).unwrap();
+
+        let proof = Plonk::prove(&kzg, &pk, &witness, &mut rng).unwrap();
+        assert_eq!(
+            Plonk::verify(&kzg, pk.vk(), &[Fr::from(35u64)], &proof),
+            Ok(())
+        );
+
+        assert_eq!(
+            Plonk::verify(&kzg, pk.vk(), &[Fr::from(36u64)], &proof),
+            Err(PlonkError::PairingCheckFailed)
+        );
+        assert_eq!(
+            Plonk::verify(&kzg, pk.vk(), &[], &proof),
+            Err(PlonkError::PublicInputsLength {
+                expected: 1,
+                got: 0
+            })
+        );
+        let mut wrong_proof = proof.clone();
+        wrong_proof.c_eval += Fr::from(1u64);
+        assert_eq!(
+            Plonk::verify(&kzg, pk.vk(), &[Fr::from(35u64)], &wrong_proof),
+            Err(PlonkError::PairingCheckFailed)
+        );
+        let mut wrong_proof = proof.clone();
+        wrong_proof.t_mid = wrong_proof.t_lo;
+        assert_eq!(
+            Plonk::verify(&kzg, pk.vk(), &[Fr::from(35u64)], &wrong_proof),
+            Err(PlonkError::PairingCheckFailed)
+        );
+
+        // a witness that doesn't satisfy the circuit
+        let (_, wrong_witness) = cubic_circuit(4, 35);
+        assert_eq!(
+            Plonk::prove(&kzg, &pk, &wrong_witness, &mut rng),
+            Err(PlonkError::UnsatisfiedCircuit)
+        );
+
+        // the srs is too small for the blinded polynomials
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 8);
+        kzg.setup_from_rng(&mut rng);
+        assert!(matches!(
+            Plonk::setup(&kzg, &circuit),
+            Err(PlonkError::SrsTooSmall { needed: 13, .. })
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+// snarkjs' plonk proof.json and verification_key.json, over bn254 (bn128 for snarkjs).
+// Only the layouts match: snarkjs derives its challenges with keccak over its own transcript and splits
+// the quotient in pieces of another degree, so a proof made here doesn't verify with snarkjs, nor the
+// other way around. Matching it would mean replacing this crate's transcript and quotient split, which
+// the other protocols share. The files can still be exchanged, e.g. to inspect or re-encode them.
+// The verification key also carries [tau]_2 (X_2) and the generator w of the domain of size 2^power.
+use ark_bn254::{g1, g2, Bn254, Fr};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use serde::{Deserialize, Serialize};
+
+use super::{PlonkProof, PlonkVerifyingKey};
+use crate::cs::pcs::kzg::KZG;
+use crate::error::AlgorithmsError;
+use crate::snark::snarkjs::{check_header, json_error, Decimal, SnarkjsPoint};
+
+const PROTOCOL: &str = "plonk";
+const CURVE: &str = "bn128";
+
+#[derive(Serialize, Deserialize)]
+struct SnarkjsProof {
+    #[serde(rename = "A")]
+    a: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "B")]
+    b: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "C")]
+    c: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Z")]
+    z: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "T1")]
+    t1: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "T2")]
+    t2: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "T3")]
+    t3: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Wxi")]
+    wxi: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Wxiw")]
+    wxiw: SnarkjsPoint<g1::Config>,
+    eval_a: Decimal<Fr>,
+    eval_b: Decimal<Fr>,
+    eval_c: Decimal<Fr>,
+    eval_s1: Decimal<Fr>,
+    eval_s2: Decimal<Fr>,
+    eval_zw: Decimal<Fr>,
+    protocol: String,
+    curve: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    power: u32,
+    k1: Decimal<Fr>,
+    k2: Decimal<Fr>,
+    #[serde(rename = "Qm")]
+    q_m: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Ql")]
+    q_l: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Qr")]
+    q_r: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Qo")]
+    q_o: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "Qc")]
+    q_c: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "S1")]
+    s1: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "S2")]
+    s2: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "S3")]
+    s3: SnarkjsPoint<g1::Config>,
+    #[serde(rename = "X_2")]
+    x_2: SnarkjsPoint<g2::Config>,
+    w: Decimal<Fr>,
+}
+
+pub fn proof_to_json(proof: &PlonkProof<Bn254>) -> String {
+    let proof = SnarkjsProof {
+        a: SnarkjsPoint(proof.a),
+        b: SnarkjsPoint(proof.b),
+        c: SnarkjsPoint(proof.c),
+        z: SnarkjsPoint(proof.z),
+        t1: SnarkjsPoint(proof.t_lo),
+        t2: SnarkjsPoint(proof.t_mid),
+        t3: SnarkjsPoint(proof.t_hi),
+        wxi: SnarkjsPoint(proof.w_zeta),
+        wxiw: SnarkjsPoint(proof.w_zeta_omega),
+        eval_a: Decimal(proof.a_eval),
+        eval_b: Decimal(proof.b_eval),
+        eval_c: Decimal(proof.c_eval),
+        eval_s1: Decimal(proof.s_sigma_1_eval),
+        eval_s2: Decimal(proof.s_sigma_2_eval),
+        eval_zw: Decimal(proof.z_omega_eval),
+        protocol: PROTOCOL.to_string(),
+        curve: CURVE.to_string(),
+    };
+    serde_json::to_string_pretty(&proof).expect("serializing to a string doesn't fail")
+}
+
+pub fn proof_from_json(json: &str) -> Result<PlonkProof<Bn254>, AlgorithmsError> {
+    let proof: SnarkjsProof = serde_json::from_str(json).map_err(json_error)?;
+    check_header(&proof.protocol, &proof.curve, PROTOCOL)?;
+    Ok(PlonkProof {
+        a: proof.a.0,
+        b: proof.b.0,
+        c: proof.c.0,
+        z: proof.z.0,
+        t_lo: proof.t1.0,
+        t_mid: proof.t2.0,
+        t_hi: proof.t3.0,
+        w_zeta: proof.wxi.0,
+        w_zeta_omega: proof.wxiw.0,
+        a_eval: proof.eval_a.0,
+        b_eval: proof.eval_b.0,
+        c_eval: proof.eval_c.0,
+        s_sigma_1_eval: proof.eval_s1.0,
+        s_sigma_2_eval: proof.eval_s2.0,
+        z_omega_eval: proof.eval_zw.0,
+    })
+}
+
+/// The verification key, along with [tau]_2 from the srs of `kzg`
+pub fn verifying_key_to_json(kzg: &KZG<Bn254>, verifying_key: &PlonkVerifyingKey<Bn254>) -> String {
+    let domain = GeneralEvaluationDomain::<Fr>::new(verifying_key.n)
+        .expect("the key's domain was built at setup");
+    let verifying_key = SnarkjsVerifyingKey {
+        protocol: PROTOCOL.to_string(),
+        curve: CURVE.to_string(),
+        n_public: verifying_key.n_public,
+        power: verifying_key.n.trailing_zeros(),
+        k1: Decimal(verifying_key.k_1),
+        k2: Decimal(verifying_key.k_2),
+        q_m: SnarkjsPoint(verifying_key.q_m),
+        q_l: SnarkjsPoint(verifying_key.q_l),
+        q_r: SnarkjsPoint(verifying_key.q_r),
+        q_o: SnarkjsPoint(verifying_key.q_o),
+        q_c: SnarkjsPoint(verifying_key.q_c),
+        s1: SnarkjsPoint(verifying_key.s_sigma_1),
+        s2: SnarkjsPoint(verifying_key.s_sigma_2),
+        s3: SnarkjsPoint(verifying_key.s_sigma_3),
+        x_2: SnarkjsPoint(kzg.crs_2()[1]),
+        w: Decimal(domain.group_gen()),
+    };
+    serde_json::to_string_pretty(&verifying_key).expect("serializing to a string doesn't fail")
+}
+
+/// Parses a verification key, checking that w generates the domain of size 2^power. X_2 is left to the srs.
+pub fn verifying_key_from_json(json: &str) -> Result<PlonkVerifyingKey<Bn254>, AlgorithmsError> {
+    let verifying_key: SnarkjsVerifyingKey = serde_json::from_str(json).map_err(json_error)?;
+    check_header(&verifying_key.protocol, &verifying_key.curve, PROTOCOL)?;
+    let power = verifying_key.power;
+    let domain = 1usize
+        .checked_shl(power)
+        .and_then(GeneralEvaluationDomain::<Fr>::new)
+        .ok_or(AlgorithmsError::Serialization(format!(
+            "no domain of size 2^{power}"
+        )))?;
+    if verifying_key.w.0 != domain.group_gen() {
+        return Err(AlgorithmsError::Serialization(format!(
+            "w doesn't generate the domain of size 2^{power}"
+        )));
+    }
+    Ok(PlonkVerifyingKey {
+        n: domain.size(),
+        n_public: verifying_key.n_public,
+        k_1: verifying_key.k1.0,
+        k_2: verifying_key.k2.0,
+        q_m: verifying_key.q_m.0,
+        q_l: verifying_key.q_l.0,
+        q_r: verifying_key.q_r.0,
+        q_o: verifying_key.q_o.0,
+        q_c: verifying_key.q_c.0,
+        s_sigma_1: verifying_key.s1.0,
+        s_sigma_2: verifying_key.s2.0,
+        s_sigma_3: verifying_key.s3.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proof_from_json, proof_to_json, verifying_key_from_json, verifying_key_to_json};
+    use crate::circuits::plonkish::PlonkishBuilder;
+    use crate::cs::pcs::kzg::KZG;
+    use crate::snark::plonk::Plonk;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_std::test_rng;
+
+    /// The keys of snarkjs' plonk proof.json and verification_key.json
+    const PROOF_KEYS: [&str; 17] = [
+        "A", "B", "C", "Z", "T1", "T2", "T3", "Wxi", "Wxiw", "eval_a", "eval_b", "eval_c",
+        "eval_s1", "eval_s2", "eval_zw", "protocol", "curve",
+    ];
+    const VERIFYING_KEY_KEYS: [&str; 16] = [
+        "protocol", "curve", "nPublic", "power", "k1", "k2", "Qm", "Ql", "Qr", "Qo", "Qc", "S1",
+        "S2", "S3", "X_2", "w",
+    ];
+
+    #[test]
+    pub fn test_plonk_snarkjs_json() {
+        let mut rng = test_rng();
+        // x * x = y, y public
+        let mut builder = PlonkishBuilder::new();
+        let y = builder.public_input(Fr::from(9u64));
+        let x = builder.private_input(Fr::from(3u64));
+        let x_2 = builder.mul(x, x);
+        builder.assert_equal(x_2, y);
+        let (circuit, witness) = builder.build();
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 16);
+        kzg.setup_from_rng(&mut rng);
+        let pk = Plonk::setup(&kzg, &circuit).unwrap();
+        let proof = Plonk::prove(&kzg, &pk, &witness, &mut rng).unwrap();
+
+        // through text, as files would be exchanged
+        let proof_json = proof_to_json(&proof);
+        let vk_json = verifying_key_to_json(&kzg, pk.vk());
+        let parsed_proof = proof_from_json(&proof_json).unwrap();
+        let parsed_vk = verifying_key_from_json(&vk_json).unwrap();
+        assert_eq!(parsed_proof, proof);
+        assert_eq!(&parsed_vk, pk.vk());
+        assert_eq!(
+            Plonk::verify(&kzg, &parsed_vk, &[Fr::from(9u64)], &parsed_proof),
+            Ok(())
+        );
+
+        // the same objects as snarkjs writes, with the same keys
+        let keys = |json: &str| {
+            let value = serde_json::from_str::<serde_json::Value>(json).unwrap();
+            let mut keys = value
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+        let sorted = |expected: &[&str]| {
+            let mut expected = expected.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+            expected.sort();
+            expected
+        };
+        assert_eq!(keys(&proof_json), sorted(&PROOF_KEYS));
+        assert_eq!(keys(&vk_json), sorted(&VERIFYING_KEY_KEYS));
+        let power = format!("\"power\": {}", pk.vk().n.trailing_zeros());
+        assert!(vk_json.contains(&power));
+
+        // another protocol or curve, or a key whose w doesn't match its power
+        assert!(proof_from_json(&proof_json.replace("\"plonk\"", "\"groth16\"")).is_err());
+        assert!(proof_from_json(&proof_json.replace("bn128", "bls12381")).is_err());
+        assert!(verifying_key_from_json(&vk_json.replace(&power, "\"power\": 10")).is_err());
+        assert!(verifying_key_from_json(&vk_json.replace(&power, "\"power\": 64")).is_err());
+    }
+}
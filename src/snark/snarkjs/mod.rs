@@ -0,0 +1,221 @@
+// snarkjs' json encodings: https://github.com/iden3/snarkjs, behind the `serde` feature
+// Field elements are decimal strings of their canonical representative. Points are affine, with a trailing
+// z coordinate: ["x", "y", "1"], the point at infinity being ["0", "1", "0"]. Coordinates over an extension
+// field, as for G2, are arrays of their components: [["x_0", "x_1"], ["y_0", "y_1"], ["1", "0"]].
+// The public signals (public.json) are an array of field elements.
+// `Decimal` and `SnarkjsPoint` (de)serialize with serde, the layouts live in `groth16::snarkjs`
+// and `plonk::snarkjs`.
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{Field, One, PrimeField, Zero};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::AlgorithmsError;
+
+pub fn field_to_decimal<F: PrimeField>(element: &F) -> String {
+    element.into_bigint().to_string()
+}
+
+/// Parses a decimal string, which should be reduced modulo p
+pub fn field_from_decimal<F: PrimeField>(s: &str) -> Result<F, String> {
+    let element = F::from_str(s).map_err(|_| format!("invalid field element {s}"))?;
+    if field_to_decimal(&element) != s {
+        return Err(format!("{s} isn't a reduced field element"));
+    }
+    Ok(element)
+}
+
+/// A field element serialized as its decimal string
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decimal<F>(pub F);
+
+impl<F: PrimeField> Serialize for Decimal<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&field_to_decimal(&self.0))
+    }
+}
+
+impl<'de, F: PrimeField> Deserialize<'de> for Decimal<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        field_from_decimal(&s)
+            .map(Decimal)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A coordinate: a decimal string over a prime field, the list of its components over an extension
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Coordinate {
+    Prime(String),
+    Extension(Vec<String>),
+}
+
+impl Coordinate {
+    fn new<F: Field>(coordinate: &F) -> Self {
+        let mut components = coordinate
+            .to_base_prime_field_elements()
+            .map(|c| field_to_decimal(&c))
+            .collect::<Vec<_>>();
+        if components.len() == 1 {
+            Coordinate::Prime(components.remove(0))
+        } else {
+            Coordinate::Extension(components)
+        }
+    }
+
+    fn parse<F: Field>(&self) -> Result<F, String> {
+        let components = match self {
+            Coordinate::Prime(s) => vec![field_from_decimal(s)?],
+            Coordinate::Extension(components) => components
+                .iter()
+                .map(|s| field_from_decimal(s))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        F::from_base_prime_field_elems(&components)
+            .ok_or("wrong number of coordinate components".to_string())
+    }
+}
+
+/// A point serialized as [x, y, z], checked to be on the curve and in the prime order subgroup when decoded
+pub struct SnarkjsPoint<P: SWCurveConfig>(pub Projective<P>);
+
+impl<P: SWCurveConfig> Serialize for SnarkjsPoint<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (x, y, z) = match self.0.into_affine().xy() {
+            Some((x, y)) => (*x, *y, P::BaseField::one()),
+            None => (
+                P::BaseField::zero(),
+                P::BaseField::one(),
+                P::BaseField::zero(),
+            ),
+        };
+        [x, y, z]
+            .iter()
+            .map(Coordinate::new)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+}
+
+impl<'de, P: SWCurveConfig> Deserialize<'de> for SnarkjsPoint<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let coordinates = Vec::<Coordinate>::deserialize(deserializer)?;
+        let [x, y, z] = coordinates.as_slice() else {
+            return Err(D::Error::custom("expected a point as [x, y, z]"));
+        };
+        let parse = |c: &Coordinate| c.parse::<P::BaseField>().map_err(D::Error::custom);
+        let (x, y, z) = (parse(x)?, parse(y)?, parse(z)?);
+        if z.is_zero() {
+            return Ok(SnarkjsPoint(Projective::zero()));
+        }
+        if !z.is_one() {
+            return Err(D::Error::custom("expected an affine point, with z = 1"));
+        }
+        let point = Affine::<P>::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(D::Error::custom(
+                "point not on the curve or not in the prime order subgroup",
+            ));
+        }
+        Ok(SnarkjsPoint(point.into_group()))
+    }
+}
+
+pub(crate) fn json_error(error: serde_json::Error) -> AlgorithmsError {
+    AlgorithmsError::Serialization(error.to_string())
+}
+
+/// public.json
+pub fn public_signals_to_json<F: PrimeField>(signals: &[F]) -> String {
+    let signals = signals.iter().map(|s| Decimal(*s)).collect::<Vec<_>>();
+    serde_json::to_string_pretty(&signals).expect("serializing to a string doesn't fail")
+}
+
+pub fn public_signals_from_json<F: PrimeField>(json: &str) -> Result<Vec<F>, AlgorithmsError> {
+    let signals: Vec<Decimal<F>> = serde_json::from_str(json).map_err(json_error)?;
+    Ok(signals.into_iter().map(|s| s.0).collect())
+}
+
+/// Checks the "protocol" and "curve" fields of a snarkjs object
+pub(crate) fn check_header(
+    protocol: &str,
+    curve: &str,
+    expected_protocol: &str,
+) -> Result<(), AlgorithmsError> {
+    if protocol != expected_protocol {
+        return Err(AlgorithmsError::Serialization(format!(
+            "expected a {expected_protocol} object"
+        )));
+    }
+    if curve != "bn128" {
+        return Err(AlgorithmsError::Serialization(
+            "expected the bn128 curve".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{public_signals_from_json, public_signals_to_json, Decimal, SnarkjsPoint};
+    use ark_bn254::{g1, g2, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::Zero;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    pub fn test_snarkjs_encodings() {
+        let mut rng = test_rng();
+        assert_eq!(
+            serde_json::to_string(&Decimal(Fr::from(25u64))).unwrap(),
+            "\"25\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Decimal(Fr::zero())).unwrap(),
+            "\"0\""
+        );
+        let signals = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        assert_eq!(
+            public_signals_from_json::<Fr>(&public_signals_to_json(&signals)).unwrap(),
+            signals
+        );
+        // not reduced, or not a number
+        let p = "\"21888242871839275222246405745257275088548364400416034343698204186575808495617\"";
+        assert!(serde_json::from_str::<Decimal<Fr>>(p).is_err());
+        assert!(serde_json::from_str::<Decimal<Fr>>("\"12a\"").is_err());
+
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let json = serde_json::to_string(&SnarkjsPoint(g1)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SnarkjsPoint<g1::Config>>(&json)
+                .unwrap()
+                .0,
+            g1
+        );
+        let json = serde_json::to_string(&SnarkjsPoint(g2)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SnarkjsPoint<g2::Config>>(&json)
+                .unwrap()
+                .0,
+            g2
+        );
+        let infinity = serde_json::to_string(&SnarkjsPoint(G1Projective::zero())).unwrap();
+        assert_eq!(infinity, "[\"0\",\"1\",\"0\"]");
+        let decoded = serde_json::from_str::<SnarkjsPoint<g1::Config>>(&infinity).unwrap();
+        assert!(decoded.0.is_zero());
+        let generator = serde_json::to_value(SnarkjsPoint(G2Projective::generator())).unwrap();
+        assert_eq!(generator[2], serde_json::json!(["1", "0"]));
+
+        // off the curve, or not affine
+        let mut off_curve = serde_json::to_value(SnarkjsPoint(G1Projective::generator())).unwrap();
+        off_curve[1] = serde_json::json!("3");
+        assert!(serde_json::from_value::<SnarkjsPoint<g1::Config>>(off_curve).is_err());
+        let projective = serde_json::json!(["1", "2", "2"]);
+        assert!(serde_json::from_value::<SnarkjsPoint<g1::Config>>(projective).is_err());
+    }
+}
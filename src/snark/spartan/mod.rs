@@ -0,0 +1,292 @@
+// Spartan-like SNARK for R1CS: https://eprint.iacr.org/2019/550
+// z = (io, w), with io = (1, x), is laid out over the hypercube as io (padded) in the first half and w (padded)
+// in the second one, so that z(y) = (1 - y_top) * io(y') + y_top * w(y'), y = (y', y_top).
+// The prover commits to w with hyrax, then:
+// - phase 1: a zerocheck that Az o Bz - Cz vanishes over the rows, ending with claims v_A, v_B, v_C
+//   on Az(r_x), Bz(r_x), Cz(r_x),
+// - phase 2: a sumcheck of sum_y (r_A * A(r_x, y) + r_B * B(r_x, y) + r_C * C(r_x, y)) * z(y)
+//   against r_A * v_A + r_B * v_B + r_C * v_C, ending at r_y,
+// - w is opened at r_y'.
+// The verifier evaluates the sparse mles of A, B and C at (r_x, r_y) itself, in O(nnz) (no spark compiler),
+// and io at r_y' from the public inputs.
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_std::{marker::PhantomData, rand::Rng, One, Zero};
+
+use crate::circuits::r1cs::{utils::R1CSInstanceWitness, R1CS};
+use crate::cs::pcs::hyrax::{Hyrax, HyraxCommitment, HyraxProof};
+use crate::error::AlgorithmsError;
+use crate::ip::sumcheck::{
+    protocol::{self, SumcheckProof, VirtualPolynomial},
+    Mle, SparseMle,
+};
+use crate::ip::zerocheck::{self, ZeroCheckProof};
+use crate::transcript::Transcript;
+use crate::utils::linear_algebra::{Matrix, MatrixOps, Vector};
+
+pub mod phase_1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpartanProof<C: CurveGroup> {
+    pub witness_commitment: HyraxCommitment<C>,
+    pub phase_1_proof: ZeroCheckProof<C::ScalarField>,
+    /// Az(r_x), Bz(r_x), Cz(r_x)
+    pub v_a: C::ScalarField,
+    pub v_b: C::ScalarField,
+    pub v_c: C::ScalarField,
+    pub phase_2_proof: SumcheckProof<C::ScalarField>,
+    /// w(r_y')
+    pub witness_evaluation: C::ScalarField,
+    pub witness_opening: HyraxProof<C>,
+}
+
+pub struct SpartanProver<C: CurveGroup> {
+    _curve: PhantomData<C>,
+}
+
+pub struct SpartanVerifier<C: CurveGroup> {
+    _curve: PhantomData<C>,
+}
+
+/// The r1cs matrices as sparse mles, with columns remapped to the (io, w) layout of z
+struct SpartanKey<C: CurveGroup> {
+    num_row_vars: usize,
+    num_col_vars: usize,
+    n_instance: usize,
+    a: SparseMle<C::ScalarField>,
+    b: SparseMle<C::ScalarField>,
+    c: SparseMle<C::ScalarField>,
+    hyrax: Hyrax<C>,
+}
+
+impl<C: CurveGroup> SpartanKey<C> {
+    fn new(r1cs: &R1CS<C::ScalarField>) -> Self {
+        let n_instance = r1cs.n_instance();
+        let half = n_instance.max(r1cs.n_witness()).next_power_of_two();
+        let num_row_vars = r1cs.n_constraints().next_power_of_two().trailing_zeros() as usize;
+        let num_col_vars = half.trailing_zeros() as usize + 1;
+        let sparse = |matrix: &Matrix<C::ScalarField>| {
+            let mut entries = vec![];
+            for (i, row) in matrix.rows().iter().enumerate() {
+                for (j, value) in row.elements().iter().enumerate() {
+                    if !value.is_zero() {
+                        let column = if j < n_instance {
+                            j
+                        } else {
+                            half + j - n_instance
+                        };
+                        entries.push((i + (column << num_row_vars), *value));
+                    }
+                }
+            }
+            SparseMle::new(num_row_vars + num_col_vars, entries)
+        };
+        Self {
+            num_row_vars,
+            num_col_vars,
+            n_instance,
+            a: sparse(r1cs.a()),
+            b: sparse(r1cs.b()),
+            c: sparse(r1cs.c()),
+            hyrax: Hyrax::new(num_col_vars - 1),
+        }
+    }
+
+    /// (1, x) or w, padded to half of z
+    fn padded(&self, values: &[C::ScalarField]) -> Mle<C::ScalarField> {
+        let mut padded = values.to_vec();
+        padded.resize(1 << (self.num_col_vars - 1), C::ScalarField::zero());
+        Mle::new(padded)
+    }
+
+    fn transcript(&self, public_inputs: &[C::ScalarField]) -> Transcript {
+        let mut transcript = Transcript::new(b"spartan");
+        transcript.absorb(&public_inputs.to_vec());
+        transcript
+    }
+}
+
+/// Mz padded to a power of two rows
+fn matrix_vector_mle<F: PrimeField, M: MatrixOps<F>>(
+    matrix: &M,
+    z: &Vector<F>,
+) -> Result<Mle<F>, AlgorithmsError> {
+    let mut evaluations = matrix.dot_vector(z)?.elements().to_vec();
+    evaluations.resize(evaluations.len().next_power_of_two(), F::zero());
+    Ok(Mle::new(evaluations))
+}
+
+impl<C: CurveGroup> SpartanProver<C> {
+    /// Proves that z = (1, x, w) satisfies `r1cs`, x being public, an error if z doesn't fit the r1cs
+    pub fn prove<R: Rng>(
+        r1cs: &R1CS<C::ScalarField>,
+        z: &R1CSInstanceWitness<C::ScalarField>,
+        rng: &mut R,
+    ) -> Result<SpartanProof<C>, AlgorithmsError> {
+        let key = SpartanKey::<C>::new(r1cs);
+        let (io, w) = z.elements().split_at(key.n_instance);
+        let mut transcript = key.transcript(&io[1..]);
+        let w_mle = key.padded(w);
+        let (witness_commitment, blindings) = key.hyrax.commit(&w_mle.clone().into(), rng);
+        transcript.absorb(&witness_commitment.row_commitments);
+
+        // phase 1: Az o Bz - Cz = 0 over the rows
+        let (az, bz, cz) = (
+            matrix_vector_mle(r1cs.a(), z)?,
+            matrix_vector_mle(r1cs.b(), z)?,
+            matrix_vector_mle(r1cs.c(), z)?,
+        );
+        let mut phase_1 = VirtualPolynomial::new(key.num_row_vars);
+        let i_a = phase_1.add_mle(az.clone());
+        let i_b = phase_1.add_mle(bz.clone());
+        let i_c = phase_1.add_mle(cz.clone());
+        phase_1.add_product(C::ScalarField::one(), &[i_a, i_b]);
+        phase_1.add_product(-C::ScalarField::one(), &[i_c]);
+        let (phase_1_proof, r_x) = zerocheck::prove(&phase_1, &mut transcript);
+        let (v_a, v_b, v_c) = (az.evaluate(&r_x), bz.evaluate(&r_x), cz.evaluate(&r_x));
+        transcript.absorb(&[v_a, v_b, v_c]);
+
+        // phase 2: sum_y M(r_x, y) * z(y), M = r_A * A + r_B * B + r_C * C
+        let (r_a, r_b, r_c) = (
+            transcript.challenge(),
+            transcript.challenge(),
+            transcript.challenge(),
+        );
+        let m = &(&key.a.fix_variables(&r_x).scalar_mul(&r_a)
+            + &key.b.fix_variables(&r_x).scalar_mul(&r_b))
+            + &key.c.fix_variables(&r_x).scalar_mul(&r_c);
+        let z_mle = Mle::new(
+            [
+                key.padded(io).into_evaluations(),
+                w_mle.evaluations().to_vec(),
+            ]
+            .concat(),
+        );
+        let mut phase_2 = VirtualPolynomial::new(key.num_col_vars);
+        let i_m = phase_2.add_mle(m);
+        let i_z = phase_2.add_mle(z_mle);
+        phase_2.add_product(C::ScalarField::one(), &[i_m, i_z]);
+        let (phase_2_proof, r_y) = protocol::prove(&phase_2, &mut transcript);
+
+        // opening w at r_y'
+        let (witness_opening, witness_evaluation) =
+            key.hyrax
+                .open(&w_mle.into(), &blindings, &r_y[..key.num_col_vars - 1]);
+        Ok(SpartanProof {
+            witness_commitment,
+            phase_1_proof,
+            v_a,
+            v_b,
+            v_c,
+            phase_2_proof,
+            witness_evaluation,
+            witness_opening,
+        })
+    }
+}
+
+impl<C: CurveGroup> SpartanVerifier<C> {
+    /// Verifies that the prover knows w such that (1, x, w) satisfies `r1cs`, x being `public_inputs`
+    pub fn verify(
+        r1cs: &R1CS<C::ScalarField>,
+        public_inputs: &[C::ScalarField],
+        proof: &SpartanProof<C>,
+    ) -> bool {
+        let key = SpartanKey::<C>::new(r1cs);
+        if public_inputs.len() + 1 != key.n_instance {
+            return false;
+        }
+        let mut transcript = key.transcript(public_inputs);
+        transcript.absorb(&proof.witness_commitment.row_commitments);
+
+        let Some(phase_1) =
+            zerocheck::verify(key.num_row_vars, 2, &proof.phase_1_proof, &mut transcript)
+        else {
+            return false;
+        };
+        if proof.v_a * proof.v_b - proof.v_c != phase_1.expected_evaluation {
+            return false;
+        }
+        transcript.absorb(&[proof.v_a, proof.v_b, proof.v_c]);
+
+        let (r_a, r_b, r_c): (C::ScalarField, C::ScalarField, C::ScalarField) = (
+            transcript.challenge(),
+            transcript.challenge(),
+            transcript.challenge(),
+        );
+        let claim = r_a * proof.v_a + r_b * proof.v_b + r_c * proof.v_c;
+        let Some(phase_2) = protocol::verify(
+            key.num_col_vars,
+            2,
+            claim,
+            &proof.phase_2_proof,
+            &mut transcript,
+        ) else {
+            return false;
+        };
+
+        // z(r_y) from the public io and the opened witness evaluation
+        let r_x = phase_1.point;
+        let r_y = phase_2.point;
+        let (r_y_low, r_y_top) = (&r_y[..key.num_col_vars - 1], r_y[key.num_col_vars - 1]);
+        let io = [&[C::ScalarField::one()], public_inputs].concat();
+        let z_r_y = (C::ScalarField::one() - r_y_top) * key.padded(&io).evaluate(r_y_low)
+            + r_y_top * proof.witness_evaluation;
+        let r_x_y = [r_x, r_y.clone()].concat();
+        let m_r_x_y = r_a * key.a.evaluate(&r_x_y)
+            + r_b * key.b.evaluate(&r_x_y)
+            + r_c * key.c.evaluate(&r_x_y);
+        m_r_x_y * z_r_y == phase_2.expected_evaluation
+            && key.hyrax.verify(
+                &proof.witness_commitment,
+                r_y_low,
+                proof.witness_evaluation,
+                &proof.witness_opening,
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpartanProver, SpartanVerifier};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use ark_ff::One;
+    use ark_pallas::{Fr, Projective};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_spartan() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(r1cs.is_satisfied(&z));
+
+        let proof = SpartanProver::<Projective>::prove(&r1cs, &z, &mut rng).unwrap();
+        assert!(SpartanVerifier::verify(&r1cs, &[Fr::from(25u64)], &proof));
+
+        // wrong public input, or tampered proof
+        assert!(!SpartanVerifier::verify(&r1cs, &[Fr::from(26u64)], &proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.witness_evaluation += Fr::one();
+        assert!(!SpartanVerifier::verify(
+            &r1cs,
+            &[Fr::from(25u64)],
+            &wrong_proof
+        ));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.v_c += Fr::one();
+        assert!(!SpartanVerifier::verify(
+            &r1cs,
+            &[Fr::from(25u64)],
+            &wrong_proof
+        ));
+
+        // an unsatisfying assignment doesn't give a valid proof
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(26u64));
+        let z = get_z_from_cs(circuit).unwrap();
+        assert!(!r1cs.is_satisfied(&z));
+        let proof = SpartanProver::<Projective>::prove(&r1cs, &z, &mut rng).unwrap();
+        assert!(!SpartanVerifier::verify(&r1cs, &[Fr::from(26u64)], &proof));
+    }
+}
@@ -0,0 +1,165 @@
+// Spartan's first phase over a relaxed r1cs: https://eprint.iacr.org/2019/550 (section 5)
+// With z = (u, x, w) and the m rows padded to 2^s, the relaxed r1cs holds when
+// g(x) = Az(x) * Bz(x) - u * Cz(x) - E(x) vanishes over {0, 1}^s, Az, Bz, Cz and E being the mles of the row
+// vectors. A zerocheck reduces it to g(r_x) = v_A * v_B - u * v_C - v_E, the claimed v_A = Az(r_x),
+// v_B = Bz(r_x), v_C = Cz(r_x) and v_E = E(r_x) being left to the caller: a second sumcheck over the
+// columns for the first three (as in `SpartanProver`), an opening of a commitment to E for the last one.
+// A plain r1cs is the relaxed one with u = 1 and E = 0, see `R1CSRelaxed::relax`.
+use ark_ff::PrimeField;
+
+use super::matrix_vector_mle;
+use crate::circuits::relaxed_r1cs::{R1CSRelaxed, R1CSRelaxedInstance, R1CSRelaxedWitness};
+use crate::error::AlgorithmsError;
+use crate::ip::sumcheck::{protocol::VirtualPolynomial, Mle};
+use crate::ip::zerocheck::{self, ZeroCheckProof};
+use crate::transcript::Transcript;
+use crate::utils::linear_algebra::MatrixOps;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phase1Proof<F: PrimeField> {
+    pub zerocheck_proof: ZeroCheckProof<F>,
+    /// Az(r_x), Bz(r_x), Cz(r_x), E(r_x)
+    pub v_a: F,
+    pub v_b: F,
+    pub v_c: F,
+    pub v_e: F,
+}
+
+/// What is left to check once phase 1 is accepted: the claimed evaluations of the proof, at `point`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phase1SubClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub v_a: F,
+    pub v_b: F,
+    pub v_c: F,
+    pub v_e: F,
+}
+
+/// Number of variables of the row mles
+pub fn num_row_vars<F: PrimeField, M: MatrixOps<F>>(r1cs: &R1CSRelaxed<F, M>) -> usize {
+    r1cs.n_constraints().next_power_of_two().trailing_zeros() as usize
+}
+
+/// Az o Bz - u * Cz - E, as a polynomial over the rows hypercube, along with Az, Bz, Cz and E
+/// An error if the instance-witness pair doesn't fit the r1cs
+pub fn relaxed_r1cs_polynomial<F: PrimeField, M: MatrixOps<F>>(
+    r1cs: &R1CSRelaxed<F, M>,
+    instance: &R1CSRelaxedInstance<F>,
+    witness: &R1CSRelaxedWitness<F>,
+) -> Result<(VirtualPolynomial<F>, [Mle<F>; 4]), AlgorithmsError> {
+    let z = R1CSRelaxed::<F, M>::z(instance, witness);
+    let (az, bz, cz) = (
+        matrix_vector_mle(r1cs.a(), &z)?,
+        matrix_vector_mle(r1cs.b(), &z)?,
+        matrix_vector_mle(r1cs.c(), &z)?,
+    );
+    let mut e = instance.e().elements().to_vec();
+    e.resize(az.evaluations().len(), F::zero());
+    let e = Mle::new(e);
+    let mut polynomial = VirtualPolynomial::new(num_row_vars(r1cs));
+    let i_a = polynomial.add_mle(az.clone());
+    let i_b = polynomial.add_mle(bz.clone());
+    let i_c = polynomial.add_mle(cz.clone());
+    let i_e = polynomial.add_mle(e.clone());
+    polynomial.add_product(F::one(), &[i_a, i_b]);
+    polynomial.add_product(-instance.u(), &[i_c]);
+    polynomial.add_product(-F::one(), &[i_e]);
+    Ok((polynomial, [az, bz, cz, e]))
+}
+
+/// Runs the zerocheck on Az o Bz - u * Cz - E, returns the proof and r_x
+pub fn prove<F: PrimeField, M: MatrixOps<F>>(
+    r1cs: &R1CSRelaxed<F, M>,
+    instance: &R1CSRelaxedInstance<F>,
+    witness: &R1CSRelaxedWitness<F>,
+    transcript: &mut Transcript,
+) -> Result<(Phase1Proof<F>, Vec<F>), AlgorithmsError> {
+    let (polynomial, [az, bz, cz, e]) = relaxed_r1cs_polynomial(r1cs, instance, witness)?;
+    let (zerocheck_proof, r_x) = zerocheck::prove(&polynomial, transcript);
+    let proof = Phase1Proof {
+        zerocheck_proof,
+        v_a: az.evaluate(&r_x),
+        v_b: bz.evaluate(&r_x),
+        v_c: cz.evaluate(&r_x),
+        v_e: e.evaluate(&r_x),
+    };
+    transcript.absorb(&[proof.v_a, proof.v_b, proof.v_c, proof.v_e]);
+    Ok((proof, r_x))
+}
+
+/// Checks the zerocheck for a relaxed r1cs with `num_row_vars` row variables and the instance's u
+pub fn verify<F: PrimeField>(
+    num_row_vars: usize,
+    u: F,
+    proof: &Phase1Proof<F>,
+    transcript: &mut Transcript,
+) -> Option<Phase1SubClaim<F>> {
+    let subclaim = zerocheck::verify(num_row_vars, 2, &proof.zerocheck_proof, transcript)?;
+    if proof.v_a * proof.v_b - u * proof.v_c - proof.v_e != subclaim.expected_evaluation {
+        return None;
+    }
+    transcript.absorb(&[proof.v_a, proof.v_b, proof.v_c, proof.v_e]);
+    Some(Phase1SubClaim {
+        point: subclaim.point,
+        v_a: proof.v_a,
+        v_b: proof.v_b,
+        v_c: proof.v_c,
+        v_e: proof.v_e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{num_row_vars, prove, verify};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::circuits::relaxed_r1cs::{R1CSRelaxed, R1CSRelaxedInstance};
+    use crate::ip::sumcheck::Mle;
+    use crate::transcript::Transcript;
+    use ark_ff::One;
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_relaxed_r1cs_phase_1() {
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = R1CSRelaxed::from(get_r1cs_from_cs(circuit.clone()).unwrap());
+        let (instance_1, witness_1) = r1cs.relax(&get_z_from_cs(circuit).unwrap());
+        let circuit = TestPythagoreCircuit::new(Fr::from(5u64), Fr::from(12u64), Fr::from(169u64));
+        let (instance_2, witness_2) = r1cs.relax(&get_z_from_cs(circuit).unwrap());
+        // u != 1 and E != 0
+        let (instance, witness) = r1cs
+            .fold(
+                &instance_1,
+                &witness_1,
+                &instance_2,
+                &witness_2,
+                &Fr::from(7u64),
+            )
+            .unwrap();
+        assert!(r1cs.is_satisfied(&instance, &witness));
+
+        let s = num_row_vars(&r1cs);
+        for (instance, witness) in [(&instance_1, &witness_1), (&instance, &witness)] {
+            let (proof, r_x) =
+                prove(&r1cs, instance, witness, &mut Transcript::new(b"test")).unwrap();
+            let subclaim = verify(s, instance.u(), &proof, &mut Transcript::new(b"test")).unwrap();
+            assert_eq!(subclaim.point, r_x);
+            // v_E is E's mle at r_x
+            let mut e = instance.e().elements().to_vec();
+            e.resize(1 << s, Fr::from(0u64));
+            assert_eq!(subclaim.v_e, Mle::new(e).evaluate(&r_x));
+
+            let mut wrong_proof = proof.clone();
+            wrong_proof.v_e += Fr::one();
+            assert!(verify(s, instance.u(), &wrong_proof, &mut Transcript::new(b"test")).is_none());
+        }
+
+        // the folded witness with another u
+        let wrong = R1CSRelaxedInstance::new(
+            instance.e().clone(),
+            instance.u() + Fr::one(),
+            instance.x().clone(),
+        );
+        let (proof, _) = prove(&r1cs, &wrong, &witness, &mut Transcript::new(b"test")).unwrap();
+        assert!(verify(s, wrong.u(), &proof, &mut Transcript::new(b"test")).is_none());
+    }
+}
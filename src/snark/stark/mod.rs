@@ -0,0 +1,364 @@
+// STARK: https://eprint.iacr.org/2018/046, in the DEEP-ALI form of https://eprint.iacr.org/2019/336
+// over an air of n rows, H = <g> being the trace domain:
+// - the prover interpolates the trace columns T_k over H and commits to each with fri,
+// - with challenges alpha_j and beta_k, it builds the composition polynomial
+//   C(X) = sum_j alpha_j * P_j(T(X), T(gX)) * (X - g^{n-1}) / Z_H(X) + sum_k beta_k * (T_{c_k}(X) - v_k) / (X - g^{r_k}),
+//   which is a polynomial iff the transition constraints P_j hold over H \ {g^{n-1}} and the boundary
+//   constraints T_{c_k}(g^{r_k}) = v_k hold, and commits to it with fri,
+// - at an out of domain point z, it opens every T_k at z and gz and C at z. The fri openings prove that the
+//   committed codewords are low degree, and the verifier recomputes C(z) from the T_k(z), T_k(gz).
+// C is computed over a coset of a domain larger than its degree, where neither Z_H nor the X - g^{r_k} vanish.
+// The trace is not blinded, the proof is not zero-knowledge.
+use ark_ff::PrimeField;
+use ark_poly::{
+    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+    Polynomial,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+
+use crate::circuits::air::Air;
+use crate::cs::merkle::Digest;
+use crate::cs::pcs::fri::{FRIOpeningProof, FRI};
+use crate::transcript::Transcript;
+
+/// Openings of a trace column at z and gz
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct StarkTraceOpening<F: PrimeField> {
+    pub at_z: F,
+    pub at_gz: F,
+    pub proof_z: FRIOpeningProof<F>,
+    pub proof_gz: FRIOpeningProof<F>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct StarkProof<F: PrimeField> {
+    pub trace_roots: Vec<Digest>,
+    pub composition_root: Digest,
+    pub trace_openings: Vec<StarkTraceOpening<F>>,
+    pub composition_at_z: F,
+    pub composition_proof: FRIOpeningProof<F>,
+}
+
+pub struct Stark<F: PrimeField> {
+    blowup_factor: usize,
+    num_queries: usize,
+    _field: PhantomData<F>,
+}
+
+/// Degree of C: the transition part is of degree at most (d - 1) * (n - 1), the boundary part n - 2
+fn composition_degree<F: PrimeField, A: Air<F>>(air: &A) -> usize {
+    let n = air.trace_length();
+    ((air.transition_degree() - 1) * (n - 1)).max(n - 2)
+}
+
+/// Evaluates C at x from the trace rows at x and gx
+fn evaluate_composition<F: PrimeField, A: Air<F>>(
+    air: &A,
+    domain: &GeneralEvaluationDomain<F>,
+    alphas: &[F],
+    betas: &[F],
+    x: F,
+    current: &[F],
+    next: &[F],
+) -> F {
+    let last_row = domain.element(domain.size() - 1);
+    let transition_factor =
+        (x - last_row) * domain.evaluate_vanishing_polynomial(x).inverse().unwrap();
+    let transitions = air
+        .evaluate_transition(current, next)
+        .iter()
+        .zip(alphas)
+        .map(|(p, alpha)| *alpha * p)
+        .sum::<F>();
+    let boundaries = air
+        .boundary_constraints()
+        .iter()
+        .zip(betas)
+        .map(|(b, beta)| {
+            *beta * (current[b.column] - b.value) * (x - domain.element(b.row)).inverse().unwrap()
+        })
+        .sum::<F>();
+    transitions * transition_factor + boundaries
+}
+
+/// Samples z such that z and gz lie outside of the fri domains, and so outside of H
+fn sample_ood_point<F: PrimeField>(
+    transcript: &mut Transcript,
+    generator: F,
+    trace_fri: &FRI<F>,
+    composition_fri: &FRI<F>,
+) -> F {
+    loop {
+        let z: F = transcript.challenge();
+        if !trace_fri
+            .domain()
+            .evaluate_vanishing_polynomial(z)
+            .is_zero()
+            && !trace_fri
+                .domain()
+                .evaluate_vanishing_polynomial(generator * z)
+                .is_zero()
+            && !composition_fri
+                .domain()
+                .evaluate_vanishing_polynomial(z)
+                .is_zero()
+        {
+            return z;
+        }
+    }
+}
+
+impl<F: PrimeField> Stark<F> {
+    pub fn new(blowup_factor: usize, num_queries: usize) -> Self {
+        Self {
+            blowup_factor,
+            num_queries,
+            _field: PhantomData,
+        }
+    }
+
+    /// The fri instances for the trace columns and the composition polynomial
+    fn fris<A: Air<F>>(&self, air: &A) -> (FRI<F>, FRI<F>) {
+        (
+            FRI::new(air.trace_length() - 1, self.blowup_factor, self.num_queries),
+            FRI::new(
+                composition_degree(air),
+                self.blowup_factor,
+                self.num_queries,
+            ),
+        )
+    }
+
+    /// Proves that `trace`, given as one vector per column, satisfies `air`, returns None if it doesn't
+    pub fn prove<A: Air<F>>(&self, air: &A, trace: &[Vec<F>]) -> Option<StarkProof<F>> {
+        if !air.is_satisfied(trace) {
+            return None;
+        }
+        assert!(air.transition_degree() >= 1);
+        let n = air.trace_length();
+        let domain = GeneralEvaluationDomain::<F>::new(n).unwrap();
+        let (trace_fri, composition_fri) = self.fris(air);
+        let g = domain.group_gen();
+
+        let columns = trace
+            .iter()
+            .map(|column| DensePolynomial::from_coefficients_vec(domain.ifft(column)))
+            .collect::<Vec<_>>();
+        let trace_roots = columns
+            .iter()
+            .map(|column| trace_fri.commit(column))
+            .collect::<Vec<_>>();
+        let mut transcript = Transcript::new(b"stark");
+        for root in trace_roots.iter() {
+            transcript.absorb(&root[..]);
+        }
+        let alphas = transcript.challenges(air.num_transition_constraints());
+        let betas = transcript.challenges(air.boundary_constraints().len());
+
+        // C over a coset of size > deg(C), with T(gX) having coefficients c_i * g^i
+        let coset = GeneralEvaluationDomain::<F>::new(air.transition_degree().max(2) * n)
+            .unwrap()
+            .get_coset(F::GENERATOR)
+            .unwrap();
+        let current = columns
+            .iter()
+            .map(|column| coset.fft(&column.coeffs))
+            .collect::<Vec<_>>();
+        let next = columns
+            .iter()
+            .map(|column| {
+                let mut power = F::one();
+                let shifted = column
+                    .coeffs
+                    .iter()
+                    .map(|c| {
+                        let shifted = *c * power;
+                        power *= g;
+                        shifted
+                    })
+                    .collect::<Vec<_>>();
+                coset.fft(&shifted)
+            })
+            .collect::<Vec<_>>();
+        let row =
+            |evaluations: &[Vec<F>], i: usize| evaluations.iter().map(|e| e[i]).collect::<Vec<_>>();
+        let evaluations = coset
+            .elements()
+            .enumerate()
+            .map(|(i, x)| {
+                let (current, next) = (row(&current, i), row(&next, i));
+                evaluate_composition(air, &domain, &alphas, &betas, x, &current, &next)
+            })
+            .collect::<Vec<_>>();
+        let composition = DensePolynomial::from_coefficients_vec(coset.ifft(&evaluations));
+        let composition_root = composition_fri.commit(&composition);
+        transcript.absorb(&composition_root[..]);
+
+        let z = sample_ood_point(&mut transcript, g, &trace_fri, &composition_fri);
+        let trace_openings = columns
+            .iter()
+            .map(|column| {
+                let (proof_z, at_z) = trace_fri.open(column, z);
+                let (proof_gz, at_gz) = trace_fri.open(column, g * z);
+                StarkTraceOpening {
+                    at_z,
+                    at_gz,
+                    proof_z,
+                    proof_gz,
+                }
+            })
+            .collect();
+        let (composition_proof, composition_at_z) = composition_fri.open(&composition, z);
+        debug_assert_eq!(composition_at_z, composition.evaluate(&z));
+        Some(StarkProof {
+            trace_roots,
+            composition_root,
+            trace_openings,
+            composition_at_z,
+            composition_proof,
+        })
+    }
+
+    pub fn verify<A: Air<F>>(&self, air: &A, proof: &StarkProof<F>) -> bool {
+        let n = air.trace_length();
+        if proof.trace_roots.len() != air.width() || proof.trace_openings.len() != air.width() {
+            return false;
+        }
+        let domain = GeneralEvaluationDomain::<F>::new(n).unwrap();
+        let (trace_fri, composition_fri) = self.fris(air);
+        let g = domain.group_gen();
+
+        let mut transcript = Transcript::new(b"stark");
+        for root in proof.trace_roots.iter() {
+            transcript.absorb(&root[..]);
+        }
+        let alphas = transcript.challenges(air.num_transition_constraints());
+        let betas = transcript.challenges(air.boundary_constraints().len());
+        transcript.absorb(&proof.composition_root[..]);
+        let z = sample_ood_point(&mut transcript, g, &trace_fri, &composition_fri);
+
+        for (root, opening) in proof.trace_roots.iter().zip(proof.trace_openings.iter()) {
+            if !trace_fri.verify(root, z, opening.at_z, &opening.proof_z)
+                || !trace_fri.verify(root, g * z, opening.at_gz, &opening.proof_gz)
+            {
+                return false;
+            }
+        }
+        if !composition_fri.verify(
+            &proof.composition_root,
+            z,
+            proof.composition_at_z,
+            &proof.composition_proof,
+        ) {
+            return false;
+        }
+        let current = proof
+            .trace_openings
+            .iter()
+            .map(|opening| opening.at_z)
+            .collect::<Vec<_>>();
+        let next = proof
+            .trace_openings
+            .iter()
+            .map(|opening| opening.at_gz)
+            .collect::<Vec<_>>();
+        evaluate_composition(air, &domain, &alphas, &betas, z, &current, &next)
+            == proof.composition_at_z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stark;
+    use crate::circuits::air::{Air, BoundaryConstraint, FibonacciAir};
+    use ark_bn254::Fr;
+    use ark_ff::{Field, One};
+
+    /// x -> x^2 + 1, the degree 2 transition of a squaring chain
+    struct SquareAir {
+        trace_length: usize,
+        result: Fr,
+    }
+
+    impl Air<Fr> for SquareAir {
+        fn width(&self) -> usize {
+            1
+        }
+
+        fn trace_length(&self) -> usize {
+            self.trace_length
+        }
+
+        fn num_transition_constraints(&self) -> usize {
+            1
+        }
+
+        fn transition_degree(&self) -> usize {
+            2
+        }
+
+        fn evaluate_transition(&self, current: &[Fr], next: &[Fr]) -> Vec<Fr> {
+            vec![next[0] - current[0].square() - Fr::one()]
+        }
+
+        fn boundary_constraints(&self) -> Vec<BoundaryConstraint<Fr>> {
+            vec![
+                BoundaryConstraint {
+                    column: 0,
+                    row: 0,
+                    value: Fr::from(3u64),
+                },
+                BoundaryConstraint {
+                    column: 0,
+                    row: self.trace_length - 1,
+                    value: self.result,
+                },
+            ]
+        }
+    }
+
+    #[test]
+    pub fn test_stark_fibonacci() {
+        let stark = Stark::<Fr>::new(4, 16);
+        let trace = FibonacciAir::<Fr>::trace(32);
+        let air = FibonacciAir::new(32, trace[1][31]);
+        let proof = stark.prove(&air, &trace).unwrap();
+        assert!(stark.verify(&air, &proof));
+
+        // another claimed result
+        let wrong_air = FibonacciAir::new(32, trace[1][31] + Fr::one());
+        assert!(stark.prove(&wrong_air, &trace).is_none());
+        assert!(!stark.verify(&wrong_air, &proof));
+
+        // tampered openings
+        let mut wrong_proof = proof.clone();
+        wrong_proof.composition_at_z += Fr::one();
+        assert!(!stark.verify(&air, &wrong_proof));
+        let mut wrong_proof = proof.clone();
+        wrong_proof.trace_openings[0].at_gz += Fr::one();
+        assert!(!stark.verify(&air, &wrong_proof));
+        let mut wrong_proof = proof;
+        wrong_proof.trace_roots.swap(0, 1);
+        assert!(!stark.verify(&air, &wrong_proof));
+    }
+
+    #[test]
+    pub fn test_stark_degree_two() {
+        let stark = Stark::<Fr>::new(4, 16);
+        let mut column = vec![Fr::from(3u64)];
+        for i in 1..16 {
+            column.push(column[i - 1].square() + Fr::one());
+        }
+        let air = SquareAir {
+            trace_length: 16,
+            result: column[15],
+        };
+        let proof = stark.prove(&air, &[column.clone()]).unwrap();
+        assert!(stark.verify(&air, &proof));
+
+        let mut wrong = column;
+        wrong[7] += Fr::one();
+        assert!(stark.prove(&air, &[wrong]).is_none());
+    }
+}
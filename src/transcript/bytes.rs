@@ -0,0 +1,186 @@
+// Byte oriented transcripts, whose challenges can be recomputed outside of arkworks, e.g. by a solidity verifier.
+// Messages are byte strings, tagged with a label, and the state is a 32 bytes running hash H:
+// - new(domain):              state = H(domain)
+// - absorb(label, data):      state = H(state || 0x01 || len(label) || label || data)
+// - challenge(label):         state = H(state || 0x02 || len(label) || label), c = state mod r
+// where len(label) is a single byte, and state mod r reads the state as a big-endian uint256.
+// The domain separates protocols, the labels separate the messages of a protocol, and the 0x01 / 0x02 tags
+// separate absorbing from squeezing. data is the last input of its hash, so its length is implied.
+// Field elements are absorbed as big-endian 32 bytes words (uint256), short weierstrass points as (x, y),
+// the point at infinity as (0, 0): the encodings of the `evm` module for bn254.
+// With keccak, a solidity verifier reproduces a challenge with
+//   state = keccak256(abi.encodePacked(state, uint8(2), uint8(bytes(label).length), label));
+//   c = uint256(state) % R;
+// and absorbs a scalar s with keccak256(abi.encodePacked(state, uint8(1), uint8(bytes(label).length), label, s)).
+// Reducing 256 bits modulo a ~254 bits r is slightly biased, as for the keccak `Transcript`.
+// BLAKE2b is the faster backend natively, keccak the cheaper one on the EVM, which has an opcode for it.
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField};
+use sha3::{Digest, Keccak256};
+use std::marker::PhantomData;
+
+use crate::crypto::blake2::blake2b;
+
+const ABSORB_TAG: u8 = 0x01;
+const CHALLENGE_TAG: u8 = 0x02;
+
+/// A 32 bytes hash function, the backend of a `ByteTranscript`
+pub trait TranscriptHash {
+    fn hash(data: &[u8]) -> [u8; 32];
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeccakHash;
+
+impl TranscriptHash for KeccakHash {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        Keccak256::digest(data).into()
+    }
+}
+
+/// BLAKE2b with a 32 bytes digest
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blake2bHash;
+
+impl TranscriptHash for Blake2bHash {
+    fn hash(data: &[u8]) -> [u8; 32] {
+        blake2b::<32>(data)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ByteTranscript<H: TranscriptHash> {
+    state: [u8; 32],
+    _hash: PhantomData<H>,
+}
+
+pub type KeccakTranscript = ByteTranscript<KeccakHash>;
+pub type Blake2bTranscript = ByteTranscript<Blake2bHash>;
+
+/// Big-endian encoding of a field element, left padded to a multiple of 32 bytes
+fn encode_field<F: PrimeField>(element: &F) -> Vec<u8> {
+    let bytes = element.into_bigint().to_bytes_be();
+    let mut word = vec![0u8; bytes.len().next_multiple_of(32) - bytes.len()];
+    word.extend(bytes);
+    word
+}
+
+impl<H: TranscriptHash> ByteTranscript<H> {
+    /// Starts a transcript, `domain` separates the protocols using it
+    pub fn new(domain: &[u8]) -> Self {
+        Self {
+            state: H::hash(domain),
+            _hash: PhantomData,
+        }
+    }
+
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+
+    /// Hashes (state || tag || len(label) || label || data) into the state
+    fn update(&mut self, tag: u8, label: &[u8], data: &[u8]) {
+        let label_len = u8::try_from(label.len()).expect("labels have at most 255 bytes");
+        let input = [&self.state[..], &[tag, label_len], label, data].concat();
+        self.state = H::hash(&input);
+    }
+
+    pub fn absorb(&mut self, label: &[u8], data: &[u8]) {
+        self.update(ABSORB_TAG, label, data);
+    }
+
+    pub fn absorb_field<F: PrimeField>(&mut self, label: &[u8], elements: &[F]) {
+        let data = elements.iter().flat_map(encode_field).collect::<Vec<_>>();
+        self.absorb(label, &data);
+    }
+
+    pub fn absorb_point<P: SWCurveConfig>(&mut self, label: &[u8], points: &[Projective<P>])
+    where
+        P::BaseField: PrimeField,
+    {
+        let coordinates = Projective::normalize_batch(points)
+            .iter()
+            .flat_map(|p| p.xy().map_or([P::BaseField::ZERO; 2], |(x, y)| [*x, *y]))
+            .collect::<Vec<_>>();
+        self.absorb_field(label, &coordinates);
+    }
+
+    /// 32 challenge bytes, the new state
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 32] {
+        self.update(CHALLENGE_TAG, label, &[]);
+        self.state
+    }
+
+    /// The new state, read as a big-endian integer, modulo the field's order
+    pub fn challenge<F: PrimeField>(&mut self, label: &[u8]) -> F {
+        F::from_be_bytes_mod_order(&self.challenge_bytes(label))
+    }
+
+    pub fn challenges<F: PrimeField>(&mut self, label: &[u8], n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge(label)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Blake2bTranscript, KeccakTranscript};
+    use crate::cs::pcs::kzg::evm::{encode_fr, encode_g1};
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::Group;
+    use ark_ff::Zero;
+    use std::str::FromStr;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    pub fn test_byte_transcripts() {
+        // test vectors, from an independent implementation of the derivation
+        let g = G1Projective::generator();
+        let mut keccak = KeccakTranscript::new(b"ark-algorithms");
+        keccak.absorb_field(b"z", &[Fr::from(42u64)]);
+        keccak.absorb_point(b"commitment", &[g, G1Projective::zero()]);
+        let c: Fr = keccak.challenge(b"alpha");
+        assert_eq!(
+            c,
+            Fr::from_str(
+                "10682319372994461458942469794654878279626891090505316349879315070734601666338"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            keccak.challenge::<Fr>(b"alpha"),
+            Fr::from_str(
+                "9096250919697336230883555274708987812486548430005311005219774522967965851525"
+            )
+            .unwrap()
+        );
+
+        let mut blake2b = Blake2bTranscript::new(b"ark-algorithms");
+        blake2b.absorb(b"data", b"abc");
+        assert_eq!(
+            hex(&blake2b.challenge_bytes(b"beta")),
+            "0fda1df3e9557b35dacd93d6ce6151ab43ed73ab98076c9e5ec048dca0f6dbc8"
+        );
+
+        // the field and point encodings are the evm ones
+        let mut evm = KeccakTranscript::new(b"ark-algorithms");
+        evm.absorb(b"z", &encode_fr(&Fr::from(42u64)));
+        let points = [encode_g1(&g), encode_g1(&G1Projective::zero())].concat();
+        evm.absorb(b"commitment", &points);
+        assert_eq!(evm.challenge::<Fr>(b"alpha"), c);
+
+        // labels and tags separate messages with the same bytes
+        let mut t_1 = KeccakTranscript::new(b"ark-algorithms");
+        let mut t_2 = t_1.clone();
+        t_1.absorb(b"ab", b"c");
+        t_2.absorb(b"a", b"bc");
+        assert_ne!(t_1.state(), t_2.state());
+        let mut t_3 = KeccakTranscript::new(b"ark-algorithms");
+        t_3.absorb(b"alpha", b"");
+        let mut t_4 = KeccakTranscript::new(b"ark-algorithms");
+        assert_ne!(t_3.state(), t_4.challenge_bytes(b"alpha"));
+    }
+}
@@ -0,0 +1,76 @@
+// Fiat-Shamir transcript over keccak256
+// The state is a running hash: absorbing a message hashes (state || message), where messages are serialized
+// with their canonical compressed encoding. A challenge is read from the state after absorbing a separator,
+// so that two successive challenges differ.
+// Arkworks' serialization makes these challenges hard to recompute elsewhere: `bytes` has byte oriented
+// transcripts, over keccak or blake2b, with encodings a solidity verifier can reproduce.
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+
+pub mod bytes;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    /// Starts a transcript, `label` separates the protocols using it
+    pub fn new(label: &[u8]) -> Self {
+        Self {
+            state: Keccak256::digest(label).into(),
+        }
+    }
+
+    pub fn absorb<T: CanonicalSerialize + ?Sized>(&mut self, message: &T) {
+        let mut bytes = self.state.to_vec();
+        message.serialize_compressed(&mut bytes).unwrap();
+        self.state = Keccak256::digest(&bytes).into();
+    }
+
+    pub fn challenge<F: PrimeField>(&mut self) -> F {
+        F::from_le_bytes_mod_order(&self.challenge_bytes())
+    }
+
+    /// 32 challenge bytes, for challenges outside of prime fields
+    pub fn challenge_bytes(&mut self) -> [u8; 32] {
+        self.absorb(&0u8);
+        self.state
+    }
+
+    pub fn challenges<F: PrimeField>(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.challenge()).collect()
+    }
+
+    /// Samples a position in [0, n), n being a power of two
+    pub fn challenge_index(&mut self, n: usize) -> usize {
+        let bytes: [u8; 8] = self.challenge_bytes()[..8].try_into().unwrap();
+        (u64::from_le_bytes(bytes) as usize) & (n - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transcript;
+    use ark_pallas::Fr;
+
+    #[test]
+    pub fn test_transcript() {
+        let mut t_1 = Transcript::new(b"test");
+        let mut t_2 = Transcript::new(b"test");
+        t_1.absorb(&Fr::from(1u64));
+        t_2.absorb(&Fr::from(1u64));
+        let c: Vec<Fr> = t_1.challenges(2);
+        assert_eq!(c, t_2.challenges::<Fr>(2));
+        assert_ne!(c[0], c[1]);
+
+        // different labels or messages give different challenges
+        let mut t_3 = Transcript::new(b"other");
+        t_3.absorb(&Fr::from(1u64));
+        assert_ne!(t_3.challenge::<Fr>(), c[0]);
+        let mut t_4 = Transcript::new(b"test");
+        t_4.absorb(&Fr::from(2u64));
+        assert_ne!(t_4.challenge::<Fr>(), c[0]);
+    }
+}
@@ -0,0 +1,81 @@
+// Side-channel hardened scalar multiplications, for secret scalars (toxic waste, blinding factors, witnesses).
+// - `mul` is a montgomery ladder over all the bits of the scalar's representation, leading zeros included:
+//   each step computes both the sum and the doubling, whatever the bit, and selects the results by index,
+// - `msm` sums ladders instead of running pippenger, whose buckets depend on the scalars,
+// - the scalar's bits and the ladder's intermediate points are zeroized before returning.
+// This hardens the sequence of group operations only: arkworks' field and curve formulas are not audited
+// for constant-time execution (e.g. additions check for the identity), nor is this module.
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::cfg_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use zeroize::Zeroize;
+
+use super::check_msm_lengths;
+use crate::error::AlgorithmsError;
+
+/// base * scalar, with the same operations for all scalars
+pub fn mul<G: CurveGroup>(base: G, scalar: &G::ScalarField) -> G {
+    let mut bits = scalar.into_bigint().to_bits_be();
+    // invariant: ladder[1] - ladder[0] = base
+    let mut ladder = [G::zero(), base];
+    for bit in &bits {
+        let b = *bit as usize;
+        let mut steps = [ladder[0] + ladder[1], ladder[b].double()];
+        // bit = 0: (2 * r_0, r_0 + r_1), bit = 1: (r_0 + r_1, 2 * r_1)
+        ladder = [steps[1 - b], steps[b]];
+        steps.zeroize();
+    }
+    let result = ladder[0];
+    ladder.zeroize();
+    bits.zeroize();
+    result
+}
+
+/// sum_i scalars[i] * bases[i], an error if both slices don't have the same length
+pub fn msm<G: CurveGroup>(bases: &[G], scalars: &[G::ScalarField]) -> Result<G, AlgorithmsError> {
+    check_msm_lengths(bases, scalars)?;
+    Ok(cfg_iter!(bases)
+        .zip(scalars)
+        .map(|(base, scalar)| mul(*base, scalar))
+        .sum())
+}
+
+/// base * scalars[i] for all scalars, in parallel with the `parallel` feature
+pub fn batch_mul<G: CurveGroup>(base: G, scalars: &[G::ScalarField]) -> Vec<G> {
+    cfg_iter!(scalars).map(|scalar| mul(base, scalar)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_mul, msm, mul};
+    use ark_bn254::{Fr, G1Projective, G2Projective};
+    use ark_ec::{CurveGroup, VariableBaseMSM};
+    use ark_ff::{Field, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_constant_time_mul() {
+        let mut rng = test_rng();
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        let mut scalars = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        scalars.extend([Fr::ZERO, Fr::ONE, -Fr::ONE]);
+        for s in &scalars {
+            assert_eq!(mul(g1, s), g1 * s);
+            assert_eq!(mul(g2, s), g2 * s);
+        }
+        assert_eq!(
+            batch_mul(g1, &scalars),
+            scalars.iter().map(|s| g1 * s).collect::<Vec<_>>()
+        );
+
+        let bases = (0..scalars.len())
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let expected = G1Projective::msm(&G1Projective::normalize_batch(&bases), &scalars).unwrap();
+        assert_eq!(msm(&bases, &scalars).unwrap(), expected);
+        assert!(msm(&bases[..3], &scalars).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::cfg_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::error::AlgorithmsError;
+
+#[cfg(feature = "constant-time")]
+pub mod constant_time;
+
+/// Validation of curve points coming from an untrusted source (proofs, commitments, srs, ...)
+/// Points built through group operations are always valid, but points that have been
+/// deserialized without validation might not lie on the curve, or lie outside of the prime order subgroup.
+pub trait SubgroupCheck {
+    /// Returns true if the point is on the curve and in the prime order subgroup
+    fn check_subgroup(&self) -> bool;
+}
+
+impl<P: SWCurveConfig> SubgroupCheck for Affine<P> {
+    fn check_subgroup(&self) -> bool {
+        if !self.is_on_curve() {
+            return false;
+        }
+        // when the cofactor is 1 (e.g. bn254's G1, pallas, vesta), the curve is the subgroup
+        if P::cofactor_is_one() {
+            return true;
+        }
+        self.is_in_correct_subgroup_assuming_on_curve()
+    }
+}
+
+impl<P: SWCurveConfig> SubgroupCheck for Projective<P> {
+    fn check_subgroup(&self) -> bool {
+        self.into_affine().check_subgroup()
+    }
+}
+
+/// Checks a batch of points, returns false as soon as one of them is invalid
+pub fn check_subgroup_all<T: SubgroupCheck>(points: &[T]) -> bool {
+    points.iter().all(|p| p.check_subgroup())
+}
+
+/// Precomputed multiples of a fixed base, to multiply it by many scalars, e.g. g_1 * tau^i in a setup
+/// Scalars are split in windows of w bits, and the table holds k * 2^{j * w} * base for all k < 2^w and windows j:
+/// a multiplication is one addition per window, without any doubling.
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<G: CurveGroup> {
+    window: usize,
+    table: Vec<Vec<G::Affine>>,
+}
+
+impl<G: CurveGroup> FixedBaseTable<G> {
+    /// Table for `num_scalars` multiplications: the window grows with it, as a larger table pays off over more scalars
+    pub fn new(base: G, num_scalars: usize) -> Self {
+        let window = if num_scalars < 32 {
+            3
+        } else {
+            (num_scalars as f64).ln().ceil() as usize
+        };
+        Self::with_window(base, window)
+    }
+
+    /// Panics if the window is 0
+    pub fn with_window(base: G, window: usize) -> Self {
+        assert!(window > 0, "the window should hold at least a bit");
+        let num_windows = (G::ScalarField::MODULUS_BIT_SIZE as usize).div_ceil(window);
+        let mut table = Vec::with_capacity(num_windows);
+        let mut window_base = base;
+        for _ in 0..num_windows {
+            let mut row = Vec::with_capacity(1 << window);
+            let mut multiple = G::zero();
+            for _ in 0..1 << window {
+                row.push(multiple);
+                multiple += window_base;
+            }
+            table.push(G::normalize_batch(&row));
+            // 2^w * window_base
+            window_base = multiple;
+        }
+        Self { window, table }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn mul(&self, scalar: &G::ScalarField) -> G {
+        let bits = scalar.into_bigint().to_bits_le();
+        let mut result = G::zero();
+        for (chunk, row) in bits.chunks(self.window).zip(&self.table) {
+            let k = chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, bit| (acc << 1) | *bit as usize);
+            result += row[k];
+        }
+        result
+    }
+
+    /// Multiplies the base by each scalar, in parallel with the `parallel` feature
+    pub fn batch_mul(&self, scalars: &[G::ScalarField]) -> Vec<G> {
+        cfg_iter!(scalars).map(|scalar| self.mul(scalar)).collect()
+    }
+}
+
+/// base * scalar for a secret scalar (toxic waste, blinding factor, ...):
+/// a side-channel hardened ladder with the `constant-time` feature, arkworks' multiplication otherwise
+pub fn mul_secret<G: CurveGroup>(base: G, scalar: &G::ScalarField) -> G {
+    #[cfg(feature = "constant-time")]
+    {
+        constant_time::mul(base, scalar)
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        base * scalar
+    }
+}
+
+/// sum_i scalars[i] * bases[i], for public scalars, an error if both slices don't have the same length
+/// Uses ark's msm, which runs in parallel with the `parallel` feature
+pub fn msm<G: CurveGroup>(bases: &[G], scalars: &[G::ScalarField]) -> Result<G, AlgorithmsError> {
+    check_msm_lengths(bases, scalars)?;
+    Ok(G::msm_unchecked(&G::normalize_batch(bases), scalars))
+}
+
+/// `msm` for secret scalars (e.g. a witness): a sum of hardened ladders with the `constant-time` feature
+pub fn msm_secret<G: CurveGroup>(
+    bases: &[G],
+    scalars: &[G::ScalarField],
+) -> Result<G, AlgorithmsError> {
+    #[cfg(feature = "constant-time")]
+    {
+        constant_time::msm(bases, scalars)
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        msm(bases, scalars)
+    }
+}
+
+pub(crate) fn check_msm_lengths<G, F>(bases: &[G], scalars: &[F]) -> Result<(), AlgorithmsError> {
+    if bases.len() != scalars.len() {
+        return Err(AlgorithmsError::DimensionMismatch {
+            expected: bases.len(),
+            got: scalars.len(),
+        });
+    }
+    Ok(())
+}
+
+/// base * scalars[i] for secret scalars, e.g. the powers of tau of a setup:
+/// hardened ladders with the `constant-time` feature, a `FixedBaseTable` otherwise
+pub fn batch_mul_secret<G: CurveGroup>(base: G, scalars: &[G::ScalarField]) -> Vec<G> {
+    #[cfg(feature = "constant-time")]
+    {
+        constant_time::batch_mul(base, scalars)
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        FixedBaseTable::new(base, scalars.len()).batch_mul(scalars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_subgroup_all, msm, msm_secret, FixedBaseTable, SubgroupCheck};
+    use crate::error::AlgorithmsError;
+    use ark_bn254::{G1Affine, G1Projective, G2Affine, G2Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_check_subgroup() {
+        let mut rng = test_rng();
+        let g1 = G1Projective::rand(&mut rng);
+        let g2 = G2Projective::rand(&mut rng);
+        assert!(g1.check_subgroup());
+        assert!(g2.check_subgroup());
+        assert!(G1Affine::zero().check_subgroup());
+        assert!(check_subgroup_all(&[g1, g1 * ark_bn254::Fr::from(3)]));
+
+        // a point that is not on the curve
+        let g1_affine = g1.into_affine();
+        let off_curve = G1Affine::new_unchecked(g1_affine.x, g1_affine.y + ark_bn254::Fq::ONE);
+        assert!(!off_curve.check_subgroup());
+
+        // a point on the twist, outside of bn254's G2 prime order subgroup:
+        // find some x such that x^3 + b is a square, without clearing the cofactor
+        let mut x = ark_bn254::Fq2::ONE;
+        let point = loop {
+            if let Some(p) = G2Affine::get_point_from_x_unchecked(x, false) {
+                break p;
+            }
+            x += ark_bn254::Fq2::ONE;
+        };
+        assert!(point.is_on_curve());
+        assert!(!point.check_subgroup());
+    }
+
+    #[test]
+    pub fn test_fixed_base_table() {
+        let mut rng = test_rng();
+        let base = G1Projective::rand(&mut rng);
+        let mut scalars = (0..40)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        scalars.extend([ark_bn254::Fr::ZERO, ark_bn254::Fr::ONE, -ark_bn254::Fr::ONE]);
+        for table in [
+            FixedBaseTable::new(base, scalars.len()),
+            FixedBaseTable::with_window(base, 1),
+            FixedBaseTable::with_window(base, 7),
+        ] {
+            let expected = scalars.iter().map(|s| base * s).collect::<Vec<_>>();
+            assert_eq!(table.batch_mul(&scalars), expected);
+        }
+        let g2 = G2Projective::rand(&mut rng);
+        let table = FixedBaseTable::new(g2, 1);
+        assert_eq!(table.window(), 3);
+        assert_eq!(table.mul(&scalars[0]), g2 * scalars[0]);
+    }
+
+    #[test]
+    pub fn test_msm_lengths() {
+        let mut rng = test_rng();
+        let bases = (0..4)
+            .map(|_| G1Projective::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let scalars = (0..4)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let expected = bases.iter().zip(&scalars).map(|(b, s)| *b * s).sum();
+        assert_eq!(msm(&bases, &scalars), Ok(expected));
+        assert_eq!(msm_secret(&bases, &scalars), Ok(expected));
+        // no silent truncation, on either side
+        let mismatch = Err(AlgorithmsError::DimensionMismatch {
+            expected: 4,
+            got: 3,
+        });
+        assert_eq!(msm(&bases, &scalars[..3]), mismatch);
+        assert_eq!(msm_secret(&bases, &scalars[..3]), mismatch);
+        assert!(msm(&bases[..3], &scalars).is_err());
+    }
+}
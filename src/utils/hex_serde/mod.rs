@@ -0,0 +1,197 @@
+// Serde support for arkworks types, behind the `serde` feature: field elements and points are written as
+// "0x" prefixed hex strings of their compressed CanonicalSerialize bytes (so, little-endian for field elements),
+// which keeps json artifacts readable by js tooling. Decoding validates points.
+// Fields of such types are annotated with `#[serde(with = "crate::utils::hex_serde")]`, or one of the
+// submodules for vectors and sparse matrix entries.
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn to_hex<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = vec![];
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("serializing to a vec doesn't fail");
+    let digits = bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("0x{digits}")
+}
+
+pub fn from_hex<T: CanonicalDeserialize>(hex: &str) -> Result<T, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return Err("odd number of hex characters".to_string());
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut reader = &bytes[..];
+    let value = T::deserialize_compressed(&mut reader).map_err(|e| e.to_string())?;
+    if !reader.is_empty() {
+        return Err("trailing bytes".to_string());
+    }
+    Ok(value)
+}
+
+/// A value serialized as its hex string
+pub struct Hex<T>(pub T);
+
+impl<T: CanonicalSerialize> Serialize for Hex<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(&self.0))
+    }
+}
+
+impl<'de, T: CanonicalDeserialize> Deserialize<'de> for Hex<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        from_hex(&hex).map(Hex).map_err(D::Error::custom)
+    }
+}
+
+pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(value))
+}
+
+pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    Ok(Hex::deserialize(deserializer)?.0)
+}
+
+/// A vector, as a list of hex strings
+pub mod vec {
+    use super::{to_hex, Hex};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(to_hex))
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        let values = Vec::<Hex<T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|value| value.0).collect())
+    }
+}
+
+/// A vector of vectors, as a list of lists of hex strings
+pub mod vec_vec {
+    use super::{to_hex, Hex};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        values: &[Vec<T>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            values
+                .iter()
+                .map(|row| row.iter().map(to_hex).collect::<Vec<_>>()),
+        )
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<T>>, D::Error> {
+        let values = Vec::<Vec<Hex<T>>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(|row| row.into_iter().map(|value| value.0).collect())
+            .collect())
+    }
+}
+
+/// Sparse matrix entries (row, col, value), as [row, col, hex] lists
+pub mod entries {
+    use super::{to_hex, Hex};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        entries: &[(usize, usize, T)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            entries
+                .iter()
+                .map(|(row, col, value)| (row, col, to_hex(value))),
+        )
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(usize, usize, T)>, D::Error> {
+        let entries = Vec::<(usize, usize, Hex<T>)>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|(row, col, value)| (row, col, value.0))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_hex, to_hex};
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::circuits::r1cs::R1CS;
+    use crate::snark::groth16::{Groth16, Groth16Proof};
+    use crate::utils::linear_algebra::SparseMatrix;
+    use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+    use ark_ec::Group;
+    use ark_ff::One;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_hex() {
+        assert_eq!(to_hex(&Fr::from(258u64))[..8], *"0x020100");
+        assert_eq!(
+            from_hex::<Fr>(&to_hex(&Fr::from(258u64))),
+            Ok(Fr::from(258u64))
+        );
+        let point = G1Projective::generator() * Fr::from(5u64);
+        assert_eq!(from_hex::<G1Projective>(&to_hex(&point)), Ok(point));
+        assert!(from_hex::<Fr>("0x0").is_err());
+        // a field element above the modulus, an x coordinate with no point on the curve
+        assert!(from_hex::<Fr>(&format!("0x{}", "ff".repeat(32))).is_err());
+        let mut x = Fq::one();
+        while G1Affine::get_point_from_x_unchecked(x, false).is_some() {
+            x += Fq::one();
+        }
+        assert!(from_hex::<G1Affine>(&to_hex(&x)).is_err());
+    }
+
+    #[test]
+    pub fn test_json_artifacts() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+
+        let json = serde_json::to_string(&r1cs).unwrap();
+        let decoded_r1cs: R1CS<Fr> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&decoded_r1cs).unwrap(), json);
+        let sparse = SparseMatrix::from_dense(r1cs.a());
+        let json = serde_json::to_string(&sparse).unwrap();
+        let decoded: SparseMatrix<Fr> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.entries(), sparse.entries());
+
+        let (pk, vk) = Groth16::<Bn254>::setup(&decoded_r1cs, &mut rng).unwrap();
+        let proof = Groth16::prove(&pk, &z, &mut rng).unwrap();
+        let json = serde_json::to_value(&proof).unwrap();
+        assert!(json["a"].as_str().unwrap().starts_with("0x"));
+        let decoded: Groth16Proof<Bn254> = serde_json::from_value(json).unwrap();
+        assert!(Groth16::verify(&vk, &[Fr::from(25u64)], &decoded));
+    }
+}
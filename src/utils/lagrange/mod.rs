@@ -1,20 +1,22 @@
-use ark_ff::PrimeField;
+use ark_ff::{batch_inversion, PrimeField};
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations,
     GeneralEvaluationDomain,
 };
 
+use crate::error::AlgorithmsError;
+use crate::utils::build_zero_polynomial;
+
 /// Computes the lagrange interpolation for the set of points:
 /// (\omega^{0}, y_0), (\omega^{1}, y_1), ..., (\omega^{n}, y_n)
 /// where \omega is a primitive n-th root of unity.
+/// An error if the field has no subgroup of order evals.len() (rounded to a power of two)
 pub fn compute_lagrange_interpolation_on_roots_of_unity<F: PrimeField>(
-    evals: &Vec<F>,
-) -> DensePolynomial<F> {
-    let k = evals.len() as usize;
-    let omegas = GeneralEvaluationDomain::<F>::new(k).unwrap();
-    let lagrange: DensePolynomial<F> =
-        Evaluations::<F>::from_vec_and_domain(evals.to_vec(), omegas).interpolate();
-    lagrange
+    evals: &[F],
+) -> Result<DensePolynomial<F>, AlgorithmsError> {
+    let k = evals.len();
+    let omegas = GeneralEvaluationDomain::<F>::new(k).ok_or(AlgorithmsError::DomainNotFound(k))?;
+    Ok(Evaluations::<F>::from_vec_and_domain(evals.to_vec(), omegas).interpolate())
 }
 
 /// Computes the lagrange interpolated polynomial from the given points `p_i`
@@ -61,3 +63,100 @@ pub fn compute_lagrange_interpolation<F: PrimeField>(p_i: &[F]) -> DensePolynomi
 
     lagrange_poly
 }
+
+/// Computes the lagrange interpolated polynomial going through (x_values[i], y_values[i])
+/// Unlike `compute_lagrange_interpolation`, the x values can be arbitrary (but distinct) points
+pub fn compute_lagrange_interpolation_on_points<F: PrimeField>(
+    x_values: &[F],
+    y_values: &[F],
+) -> DensePolynomial<F> {
+    assert_eq!(x_values.len(), y_values.len());
+    // l(x) = (x - x_0) * ... * (x - x_n), common to every basis polynomial
+    let l_x = build_zero_polynomial(x_values);
+    let mut lagrange_poly = DensePolynomial::from_coefficients_vec(vec![F::ZERO]);
+    for (j, (x_j, y_j)) in x_values.iter().zip(y_values).enumerate() {
+        // w_j = 1 / prod_{m != j} (x_j - x_m)
+        let mut w_j = F::ONE;
+        for (m, x_m) in x_values.iter().enumerate() {
+            if m != j {
+                w_j *= *x_j - x_m;
+            }
+        }
+        let w_j = w_j.inverse().expect("x values should be distinct");
+        let poly_denominator = DensePolynomial::from_coefficients_vec(vec![-*x_j, F::ONE]);
+        let basis_poly = &l_x / &poly_denominator;
+        lagrange_poly = &lagrange_poly + &(&basis_poly * (w_j * y_j));
+    }
+    lagrange_poly
+}
+
+/// Evaluates at x the polynomial p of degree < n such that p(omega^i) = evals[i], omega generating `domain`
+/// Uses the barycentric formula p(x) = (x^n - 1) / n * sum_i evals[i] * omega^i / (x - omega^i),
+/// in O(n) field operations and a single batch inversion, without interpolating.
+pub fn evaluate_from_evals<F: PrimeField>(
+    evals: &[F],
+    domain: GeneralEvaluationDomain<F>,
+    x: F,
+) -> F {
+    assert_eq!(evals.len(), domain.size());
+    let z_h = domain.evaluate_vanishing_polynomial(x);
+    if z_h.is_zero() {
+        // x = omega^i, the formula divides by zero
+        let i = domain.elements().position(|omega_i| omega_i == x).unwrap();
+        return evals[i];
+    }
+    let omegas = domain.elements().collect::<Vec<_>>();
+    let mut denominators = omegas.iter().map(|omega_i| x - omega_i).collect::<Vec<_>>();
+    batch_inversion(&mut denominators);
+    let sum = evals
+        .iter()
+        .zip(omegas.iter().zip(denominators))
+        .map(|(eval, (omega_i, d))| *eval * omega_i * d)
+        .sum::<F>();
+    z_h * domain.size_inv() * sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_lagrange_interpolation, compute_lagrange_interpolation_on_points,
+        compute_lagrange_interpolation_on_roots_of_unity, evaluate_from_evals,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_lagrange_interpolation_on_points() {
+        let mut rng = test_rng();
+        let x_values = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let y_values = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let poly = compute_lagrange_interpolation_on_points(&x_values, &y_values);
+        for (x, y) in x_values.iter().zip(y_values.iter()) {
+            assert_eq!(poly.evaluate(x), *y);
+        }
+
+        // matches the 0..n interpolation
+        let x_values = (0..5).map(|i| Fr::from(i as u64)).collect::<Vec<_>>();
+        assert_eq!(
+            compute_lagrange_interpolation_on_points(&x_values, &y_values),
+            compute_lagrange_interpolation(&y_values)
+        );
+    }
+
+    #[test]
+    pub fn test_evaluate_from_evals() {
+        let mut rng = test_rng();
+        let evals = (0..16).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let poly = compute_lagrange_interpolation_on_roots_of_unity(&evals).unwrap();
+        let x = Fr::rand(&mut rng);
+        assert_eq!(evaluate_from_evals(&evals, domain, x), poly.evaluate(&x));
+        // within the domain
+        assert_eq!(
+            evaluate_from_evals(&evals, domain, domain.element(5)),
+            evals[5]
+        );
+    }
+}
@@ -1,26 +1,78 @@
 use ark_ff::PrimeField;
 use ark_relations::r1cs::Matrix as ArkMatrix;
+use ark_std::cfg_iter;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::ops::{Add, Mul, Sub};
+
+use crate::error::AlgorithmsError;
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Matrix<F: PrimeField> {
-    pub rows: Vec<Vector<F>>,
-    pub num_rows: usize,
-    pub num_cols: usize,
+    rows: Vec<Vector<F>>,
+    num_rows: usize,
+    num_cols: usize,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Vector<F: PrimeField> {
-    pub elements: Vec<F>,
-    pub size: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde::vec"))]
+    elements: Vec<F>,
+    size: usize,
+}
+
+/// A matrix given by its non-zero entries, as (row, col, value)
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct SparseMatrix<F: PrimeField> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::hex_serde::entries"))]
+    entries: Vec<(usize, usize, F)>,
+    num_rows: usize,
+    num_cols: usize,
+}
+
+/// What r1cs structures need from their matrices, so that they can be backed by dense or sparse ones
+pub trait MatrixOps<F: PrimeField>: Clone {
+    fn num_rows(&self) -> usize;
+    fn num_cols(&self) -> usize;
+    fn dot_vector(&self, rhs: &Vector<F>) -> Result<Vector<F>, AlgorithmsError>;
+    fn to_ark_matrix(&self) -> ArkMatrix<F>;
+    fn from_ark_matrix(matrix: &ArkMatrix<F>, n_rows: usize, n_cols: usize) -> Self;
 }
 
 impl<F: PrimeField> Matrix<F> {
-    pub fn new(rows: &Vec<Vector<F>>) -> Self {
-        Self {
-            rows: rows.clone(),
-            num_rows: rows.len(),
-            num_cols: rows[0].elements.len(),
+    /// Panics if the rows don't all have the same length, see `try_new`
+    pub fn new(rows: &[Vector<F>]) -> Self {
+        Self::try_new(rows).unwrap()
+    }
+
+    /// Returns an error if the rows don't all have the same length
+    pub fn try_new(rows: &[Vector<F>]) -> Result<Self, AlgorithmsError> {
+        let num_cols = rows.first().map_or(0, |row| row.size);
+        if let Some(row) = rows.iter().find(|row| row.size != num_cols) {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: num_cols,
+                got: row.size,
+            });
         }
+        Ok(Self {
+            rows: rows.to_vec(),
+            num_rows: rows.len(),
+            num_cols,
+        })
     }
 
     pub fn new_from_vecs(rows: &Vec<Vec<F>>) -> Self {
@@ -35,20 +87,83 @@ impl<F: PrimeField> Matrix<F> {
     /// Need to convert them to dense matrices
     /// See: https://github.com/privacy-scaling-explorations/folding-schemes
     pub fn new_from_ark_matrix(matrix: &ArkMatrix<F>, n_rows: usize, n_cols: usize) -> Self {
+        assert!(matrix.len() <= n_rows, "more rows than n_rows");
         let mut r: Vec<Vec<F>> = vec![vec![F::zero(); n_cols]; n_rows];
         for (row_i, row) in matrix.iter().enumerate() {
             for &(value, col_i) in row.iter() {
+                assert!(col_i < n_cols, "column {col_i} out of bounds");
                 r[row_i][col_i] = value;
             }
         }
         Self::new_from_vecs(&r)
     }
+
+    /// Back to arkworks' format: for each row, its non-zero (value, col) entries
+    pub fn to_ark_matrix(&self) -> ArkMatrix<F> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.elements
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(col_i, value)| (*value, col_i))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn rows(&self) -> &[Vector<F>] {
+        &self.rows
+    }
+
+    /// The entry at row i and column j, None if out of bounds
+    pub fn get(&self, i: usize, j: usize) -> Option<F> {
+        self.rows.get(i)?.get(j)
+    }
+
+    /// Sets the entry at row i and column j, returns an error if out of bounds:
+    /// the dimension that row i needs, i + 1, against the number of rows (and the same for j)
+    pub fn set(&mut self, i: usize, j: usize, value: F) -> Result<(), AlgorithmsError> {
+        let num_rows = self.num_rows;
+        self.rows
+            .get_mut(i)
+            .ok_or(AlgorithmsError::DimensionMismatch {
+                expected: num_rows,
+                got: i + 1,
+            })?
+            .set(j, value)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &Vector<F>> {
+        self.rows.iter()
+    }
+
+    pub fn iter_cols(&self) -> impl Iterator<Item = Vector<F>> + '_ {
+        (0..self.num_cols).map(|j| {
+            Vector::new(
+                &self
+                    .rows
+                    .iter()
+                    .map(|row| row.elements[j])
+                    .collect::<Vec<_>>(),
+            )
+        })
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
 }
 
 impl<F: PrimeField> Vector<F> {
-    pub fn new(elements: &Vec<F>) -> Self {
+    pub fn new(elements: &[F]) -> Self {
         Self {
-            elements: elements.clone(),
+            elements: elements.to_vec(),
             size: elements.len(),
         }
     }
@@ -59,81 +174,286 @@ impl<F: PrimeField> Vector<F> {
             size,
         }
     }
+
+    pub fn elements(&self) -> &[F] {
+        &self.elements
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The i-th element, None if out of bounds
+    pub fn get(&self, i: usize) -> Option<F> {
+        self.elements.get(i).copied()
+    }
+
+    /// Sets the i-th element, returns an error if out of bounds: the dimension i + 1 against the size
+    pub fn set(&mut self, i: usize, value: F) -> Result<(), AlgorithmsError> {
+        let size = self.size;
+        *self
+            .elements
+            .get_mut(i)
+            .ok_or(AlgorithmsError::DimensionMismatch {
+                expected: size,
+                got: i + 1,
+            })? = value;
+        Ok(())
+    }
+
+    fn check_size(&self, rhs: &Vector<F>) -> Result<(), AlgorithmsError> {
+        if self.size != rhs.size {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.size,
+                got: rhs.size,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> From<Vec<F>> for Vector<F> {
+    fn from(elements: Vec<F>) -> Self {
+        Self {
+            size: elements.len(),
+            elements,
+        }
+    }
+}
+
+/// Panics if the rows don't all have the same length, see `Matrix::try_new`
+impl<F: PrimeField> From<Vec<Vec<F>>> for Matrix<F> {
+    fn from(rows: Vec<Vec<F>>) -> Self {
+        let rows = rows.into_iter().map(Vector::from).collect::<Vec<_>>();
+        Self::new(&rows)
+    }
 }
 
 impl<F: PrimeField> Matrix<F> {
-    pub fn dot(&self, rhs: &Matrix<F>) -> Matrix<F> {
-        assert_eq!(self.num_cols, rhs.num_rows);
-        let mut res = vec![];
-        for i in 0..self.num_rows {
-            let mut row = vec![];
-            for j in 0..rhs.num_cols {
+    /// Rows of the product are computed in parallel with the `parallel` feature
+    /// An error if rhs's number of rows isn't self's number of columns
+    pub fn dot(&self, rhs: &Matrix<F>) -> Result<Matrix<F>, AlgorithmsError> {
+        if self.num_cols != rhs.num_rows {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_cols,
+                got: rhs.num_rows,
+            });
+        }
+        let res = cfg_iter!(self.rows)
+            .map(|row| {
+                let product = (0..rhs.num_cols)
+                    .map(|j| {
+                        let mut sum = F::zero();
+                        for k in 0..self.num_cols {
+                            sum += row.elements[k] * rhs.rows[k].elements[j];
+                        }
+                        sum
+                    })
+                    .collect::<Vec<_>>();
+                Vector::new(&product)
+            })
+            .collect::<Vec<_>>();
+        Ok(Matrix::new(&res))
+    }
+
+    /// Rows of the product are computed in parallel with the `parallel` feature
+    /// An error if the vector's size isn't the number of columns
+    pub fn dot_vector(&self, rhs: &Vector<F>) -> Result<Vector<F>, AlgorithmsError> {
+        if self.num_cols != rhs.size {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_cols,
+                got: rhs.size,
+            });
+        }
+        let res = cfg_iter!(self.rows)
+            .map(|row| {
                 let mut sum = F::zero();
-                for k in 0..self.num_cols {
-                    sum += self.rows[i].elements[k] * rhs.rows[k].elements[j];
+                for (a, b) in row.elements.iter().zip(&rhs.elements) {
+                    sum += *a * b;
+                }
+                sum
+            })
+            .collect::<Vec<_>>();
+        Ok(Vector::new(&res))
+    }
+}
+
+impl<F: PrimeField> SparseMatrix<F> {
+    pub fn new(num_rows: usize, num_cols: usize, entries: Vec<(usize, usize, F)>) -> Self {
+        assert!(
+            entries
+                .iter()
+                .all(|(row, col, _)| *row < num_rows && *col < num_cols),
+            "entry out of the matrix bounds"
+        );
+        Self {
+            entries,
+            num_rows,
+            num_cols,
+        }
+    }
+
+    /// Keeps the arkworks entries as they are, without densifying the matrix
+    pub fn new_from_ark_matrix(matrix: &ArkMatrix<F>, n_rows: usize, n_cols: usize) -> Self {
+        let entries = matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(row_i, row)| row.iter().map(move |&(value, col_i)| (row_i, col_i, value)))
+            .collect();
+        Self::new(n_rows, n_cols, entries)
+    }
+
+    pub fn from_dense(matrix: &Matrix<F>) -> Self {
+        let mut entries = vec![];
+        for (row_i, row) in matrix.rows.iter().enumerate() {
+            for (col_i, value) in row.elements.iter().enumerate() {
+                if !value.is_zero() {
+                    entries.push((row_i, col_i, *value));
                 }
-                row.push(sum);
             }
-            res.push(Vector::new(&row));
         }
-        Matrix::new(&res)
+        Self::new(matrix.num_rows, matrix.num_cols, entries)
+    }
+
+    /// Back to arkworks' format, each row sorted by column, duplicated entries summed and zeros dropped
+    pub fn to_ark_matrix(&self) -> ArkMatrix<F> {
+        let mut rows = vec![std::collections::BTreeMap::new(); self.num_rows];
+        for &(row_i, col_i, value) in &self.entries {
+            *rows[row_i].entry(col_i).or_insert_with(F::zero) += value;
+        }
+        rows.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(col_i, value)| (value, col_i))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn to_dense(&self) -> Matrix<F> {
+        let mut rows = vec![vec![F::zero(); self.num_cols]; self.num_rows];
+        for &(row_i, col_i, value) in &self.entries {
+            rows[row_i][col_i] += value;
+        }
+        Matrix::new_from_vecs(&rows)
+    }
+
+    pub fn entries(&self) -> &[(usize, usize, F)] {
+        &self.entries
     }
 
-    pub fn dot_vector(&self, rhs: &Vector<F>) -> Vector<F> {
-        assert_eq!(self.num_cols, rhs.size);
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// O(number of non-zero entries), an error if the vector's size isn't the number of columns
+    pub fn dot_vector(&self, rhs: &Vector<F>) -> Result<Vector<F>, AlgorithmsError> {
+        if self.num_cols != rhs.size {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_cols,
+                got: rhs.size,
+            });
+        }
         let mut res = vec![F::zero(); self.num_rows];
-        for i in 0..self.num_rows {
-            for j in 0..self.num_cols {
-                res[i] += self.rows[i].elements[j] * rhs.elements[j];
-            }
+        for &(row_i, col_i, value) in &self.entries {
+            res[row_i] += value * rhs.elements[col_i];
         }
-        Vector::new(&res)
+        Ok(Vector::new(&res))
+    }
+}
+
+impl<F: PrimeField> MatrixOps<F> for Matrix<F> {
+    fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    fn dot_vector(&self, rhs: &Vector<F>) -> Result<Vector<F>, AlgorithmsError> {
+        Matrix::dot_vector(self, rhs)
+    }
+
+    fn to_ark_matrix(&self) -> ArkMatrix<F> {
+        Matrix::to_ark_matrix(self)
+    }
+
+    fn from_ark_matrix(matrix: &ArkMatrix<F>, n_rows: usize, n_cols: usize) -> Self {
+        Matrix::new_from_ark_matrix(matrix, n_rows, n_cols)
+    }
+}
+
+impl<F: PrimeField> MatrixOps<F> for SparseMatrix<F> {
+    fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    fn dot_vector(&self, rhs: &Vector<F>) -> Result<Vector<F>, AlgorithmsError> {
+        SparseMatrix::dot_vector(self, rhs)
+    }
+
+    fn to_ark_matrix(&self) -> ArkMatrix<F> {
+        SparseMatrix::to_ark_matrix(self)
+    }
+
+    fn from_ark_matrix(matrix: &ArkMatrix<F>, n_rows: usize, n_cols: usize) -> Self {
+        SparseMatrix::new_from_ark_matrix(matrix, n_rows, n_cols)
     }
 }
 
 impl<F: PrimeField> Sub for Vector<F> {
-    type Output = Vector<F>;
+    type Output = Result<Vector<F>, AlgorithmsError>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.elements.len(), rhs.elements.len());
+        self.check_size(&rhs)?;
         let mut res = vec![F::zero(); self.elements.len()];
-        for i in 0..self.elements.len() {
-            res[i] = self.elements[i] - rhs.elements[i];
+        for (i, res_i) in res.iter_mut().enumerate() {
+            *res_i = self.elements[i] - rhs.elements[i];
         }
-        Vector::new(&res)
+        Ok(Vector::new(&res))
     }
 }
 
 impl<F: PrimeField> Mul for Vector<F> {
-    type Output = Vector<F>;
+    type Output = Result<Vector<F>, AlgorithmsError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.elements.len(), rhs.elements.len());
+        self.check_size(&rhs)?;
         let mut res = vec![F::zero(); self.elements.len()];
-        for i in 0..self.elements.len() {
-            res[i] = self.elements[i] * rhs.elements[i];
+        for (i, res_i) in res.iter_mut().enumerate() {
+            *res_i = self.elements[i] * rhs.elements[i];
         }
-        Vector::new(&res)
+        Ok(Vector::new(&res))
     }
 }
 
 impl<F: PrimeField> Add for Vector<F> {
-    type Output = Vector<F>;
+    type Output = Result<Vector<F>, AlgorithmsError>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        assert_eq!(self.elements.len(), rhs.elements.len());
+        self.check_size(&rhs)?;
         let mut res = vec![F::zero(); self.elements.len()];
-        for i in 0..self.elements.len() {
-            res[i] = self.elements[i] + rhs.elements[i];
+        for (i, res_i) in res.iter_mut().enumerate() {
+            *res_i = self.elements[i] + rhs.elements[i];
         }
-        Vector::new(&res)
+        Ok(Vector::new(&res))
     }
 }
 
 impl<F: PrimeField> Vector<F> {
     pub fn is_zero_vector(&self) -> bool {
-        for i in 0..self.elements.len() {
-            if self.elements[i] != F::zero() {
+        for element in self.elements.iter() {
+            if *element != F::zero() {
                 return false;
             }
         }
@@ -142,9 +462,378 @@ impl<F: PrimeField> Vector<F> {
 
     pub fn scalar_mul(&self, scalar: &F) -> Vector<F> {
         let mut res = vec![F::zero(); self.elements.len()];
-        for i in 0..self.elements.len() {
-            res[i] = self.elements[i] * scalar;
+        for (i, res_i) in res.iter_mut().enumerate() {
+            *res_i = self.elements[i] * scalar;
         }
         Vector::new(&res)
     }
 }
+
+impl<F: PrimeField> Matrix<F> {
+    /// The n x n identity matrix
+    pub fn identity(n: usize) -> Self {
+        let rows = (0..n)
+            .map(|i| {
+                let mut row = vec![F::zero(); n];
+                row[i] = F::one();
+                row
+            })
+            .collect();
+        Self::new_from_vecs(&rows)
+    }
+
+    pub fn transpose(&self) -> Matrix<F> {
+        Matrix::new(&self.iter_cols().collect::<Vec<_>>())
+    }
+
+    /// Entry-wise product, an error if both matrices don't have the same dimensions
+    pub fn hadamard(&self, rhs: &Matrix<F>) -> Result<Matrix<F>, AlgorithmsError> {
+        self.entry_wise(rhs.clone(), |lhs_row, rhs_row| lhs_row * rhs_row)
+    }
+
+    fn entry_wise(
+        &self,
+        rhs: Matrix<F>,
+        op: impl Fn(Vector<F>, Vector<F>) -> Result<Vector<F>, AlgorithmsError>,
+    ) -> Result<Matrix<F>, AlgorithmsError> {
+        if self.num_rows != rhs.num_rows {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_rows,
+                got: rhs.num_rows,
+            });
+        }
+        let rows = self
+            .rows
+            .iter()
+            .zip(rhs.rows)
+            .map(|(lhs_row, rhs_row)| op(lhs_row.clone(), rhs_row))
+            .collect::<Result<Vec<_>, _>>()?;
+        // rows of equal sizes, once each one matched rhs's
+        Ok(Matrix {
+            rows,
+            num_rows: self.num_rows,
+            num_cols: self.num_cols,
+        })
+    }
+
+    /// The block matrix (self[i][j] * rhs)_{i, j}, of size (m * p) x (n * q) for an m x n self and a p x q rhs
+    pub fn kronecker(&self, rhs: &Matrix<F>) -> Matrix<F> {
+        let mut rows = vec![];
+        for lhs_row in &self.rows {
+            for rhs_row in &rhs.rows {
+                let row = lhs_row
+                    .elements
+                    .iter()
+                    .flat_map(|a| rhs_row.elements.iter().map(move |b| *a * b))
+                    .collect::<Vec<_>>();
+                rows.push(row);
+            }
+        }
+        Matrix::new_from_vecs(&rows)
+    }
+
+    pub fn scalar_mul(&self, scalar: &F) -> Matrix<F> {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.scalar_mul(scalar))
+            .collect::<Vec<_>>();
+        Matrix::new(&rows)
+    }
+}
+
+/// Gaussian elimination on the first `n_cols` columns of `rows`, which end up in reduced row echelon form
+/// Returns the pivot column of each of the first rows, the others being zero on those columns.
+fn row_reduce<F: PrimeField>(rows: &mut [Vec<F>], n_cols: usize) -> Vec<usize> {
+    let mut pivots = vec![];
+    for col in 0..n_cols {
+        let row = pivots.len();
+        let Some(pivot_row) = (row..rows.len()).find(|i| !rows[*i][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, pivot_row);
+        let inverse = rows[row][col].inverse().unwrap();
+        for e in rows[row].iter_mut() {
+            *e *= inverse;
+        }
+        let pivot = rows[row].clone();
+        for (i, other) in rows.iter_mut().enumerate() {
+            let factor = other[col];
+            if i != row && !factor.is_zero() {
+                for (e, p) in other.iter_mut().zip(&pivot) {
+                    *e -= factor * p;
+                }
+            }
+        }
+        pivots.push(col);
+        if pivots.len() == rows.len() {
+            break;
+        }
+    }
+    pivots
+}
+
+impl<F: PrimeField> Matrix<F> {
+    fn to_vecs(&self) -> Vec<Vec<F>> {
+        self.rows.iter().map(|row| row.elements.clone()).collect()
+    }
+
+    /// Number of linearly independent rows (or columns)
+    pub fn rank(&self) -> usize {
+        row_reduce(&mut self.to_vecs(), self.num_cols).len()
+    }
+
+    /// Some x such that self * x = b, free variables being set to zero, or None if there is none
+    /// An error if b's size isn't the number of rows
+    pub fn solve(&self, b: &Vector<F>) -> Result<Option<Vector<F>>, AlgorithmsError> {
+        if self.num_rows != b.size {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_rows,
+                got: b.size,
+            });
+        }
+        let mut augmented = self.to_vecs();
+        for (row, b_i) in augmented.iter_mut().zip(&b.elements) {
+            row.push(*b_i);
+        }
+        let pivots = row_reduce(&mut augmented, self.num_cols + 1);
+        if pivots.last() == Some(&self.num_cols) {
+            // 0 = 1 after elimination: the system is inconsistent
+            return Ok(None);
+        }
+        let mut x = vec![F::zero(); self.num_cols];
+        for (row, col) in augmented.iter().zip(pivots) {
+            x[col] = row[self.num_cols];
+        }
+        Ok(Some(Vector::new(&x)))
+    }
+
+    /// The inverse of a square matrix, or None if it is singular
+    /// An error if the matrix isn't square
+    pub fn inverse(&self) -> Result<Option<Matrix<F>>, AlgorithmsError> {
+        if self.num_rows != self.num_cols {
+            return Err(AlgorithmsError::DimensionMismatch {
+                expected: self.num_rows,
+                got: self.num_cols,
+            });
+        }
+        let n = self.num_rows;
+        let mut augmented = self.to_vecs();
+        for (i, row) in augmented.iter_mut().enumerate() {
+            row.extend((0..n).map(|j| if i == j { F::one() } else { F::zero() }));
+        }
+        if row_reduce(&mut augmented, n).len() < n {
+            return Ok(None);
+        }
+        let rows = augmented.into_iter().map(|row| row[n..].to_vec()).collect();
+        Ok(Some(Matrix::new_from_vecs(&rows)))
+    }
+}
+
+impl<F: PrimeField> Add for Matrix<F> {
+    type Output = Result<Matrix<F>, AlgorithmsError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.entry_wise(rhs, |lhs_row, rhs_row| lhs_row + rhs_row)
+    }
+}
+
+impl<F: PrimeField> Sub for Matrix<F> {
+    type Output = Result<Matrix<F>, AlgorithmsError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.entry_wise(rhs, |lhs_row, rhs_row| lhs_row - rhs_row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Matrix, SparseMatrix, Vector};
+    use crate::circuits::r1cs::utils::get_test_r1cs;
+    use crate::error::AlgorithmsError;
+    use ark_bn254::Fr;
+
+    fn matrix(rows: &[&[u64]]) -> Matrix<Fr> {
+        Matrix::new_from_vecs(
+            &rows
+                .iter()
+                .map(|row| row.iter().map(|e| Fr::from(*e)).collect())
+                .collect(),
+        )
+    }
+
+    fn elements(matrix: &Matrix<Fr>) -> Vec<Vec<Fr>> {
+        matrix
+            .rows()
+            .iter()
+            .map(|row| row.elements().to_vec())
+            .collect()
+    }
+
+    #[test]
+    pub fn test_accessors() {
+        let mut a = Matrix::from(vec![
+            vec![Fr::from(1), Fr::from(2)],
+            vec![Fr::from(3), Fr::from(4)],
+            vec![Fr::from(5), Fr::from(6)],
+        ]);
+        assert_eq!(a.get(2, 1), Some(Fr::from(6)));
+        assert_eq!(a.get(3, 0), None);
+        assert_eq!(a.get(0, 2), None);
+        a.set(1, 0, Fr::from(7)).unwrap();
+        assert_eq!(a.get(1, 0), Some(Fr::from(7)));
+        assert!(a.set(0, 2, Fr::from(7)).is_err());
+        assert!(a.set(3, 0, Fr::from(7)).is_err());
+
+        let rows = a
+            .iter_rows()
+            .map(|row| row.elements().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, elements(&a));
+        let cols = a
+            .iter_cols()
+            .map(|col| col.elements().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(cols, elements(&a.transpose()));
+
+        let mut v = Vector::from(vec![Fr::from(1), Fr::from(2)]);
+        assert_eq!(v.get(1), Some(Fr::from(2)));
+        assert!(v.set(2, Fr::from(3)).is_err());
+        v.set(0, Fr::from(3)).unwrap();
+        assert_eq!(v.elements(), &[Fr::from(3), Fr::from(2)]);
+
+        // ragged rows are rejected
+        let ragged = [
+            Vector::new(&[Fr::from(1), Fr::from(2)]),
+            Vector::new(&[Fr::from(3)]),
+        ];
+        assert!(Matrix::try_new(&ragged).is_err());
+        assert!(Matrix::try_new(&ragged[..1]).is_ok());
+    }
+
+    #[test]
+    pub fn test_gaussian_elimination() {
+        let a = matrix(&[&[2, 1, 1], &[1, 3, 2], &[1, 0, 0]]);
+        assert_eq!(a.rank(), 3);
+        let a_inverse = a.inverse().unwrap().unwrap();
+        let identity = elements(&Matrix::identity(3));
+        assert_eq!(elements(&a.dot(&a_inverse).unwrap()), identity);
+        assert_eq!(elements(&a_inverse.dot(&a).unwrap()), identity);
+        let b = Vector::new(&[Fr::from(4), Fr::from(5), Fr::from(6)]);
+        let x = a.solve(&b).unwrap().unwrap();
+        assert_eq!(a.dot_vector(&x).unwrap().elements(), b.elements());
+
+        // the third row is the sum of the first two
+        let singular = matrix(&[&[1, 2, 3], &[4, 5, 6], &[5, 7, 9]]);
+        assert_eq!(singular.rank(), 2);
+        assert!(singular.inverse().unwrap().is_none());
+        assert!(singular.solve(&b).unwrap().is_none());
+        let b = Vector::new(&[Fr::from(1), Fr::from(1), Fr::from(2)]);
+        let x = singular.solve(&b).unwrap().unwrap();
+
+        // a right-hand side of the wrong size, a matrix that isn't square
+        assert_eq!(
+            singular
+                .solve(&Vector::new(&[Fr::from(1)]))
+                .map(|x| x.map(|x| x.elements().to_vec())),
+            Err(AlgorithmsError::DimensionMismatch {
+                expected: 3,
+                got: 1
+            })
+        );
+        assert!(matrix(&[&[1, 2]]).inverse().is_err());
+        assert_eq!(singular.dot_vector(&x).unwrap().elements(), b.elements());
+
+        // the r1cs constraints of the test circuit are linearly independent
+        let (a, b, c) = get_test_r1cs::<Fr>();
+        assert_eq!(a.rank(), 4);
+        assert_eq!(b.rank(), 2);
+        assert_eq!(c.rank(), 4);
+    }
+
+    #[test]
+    pub fn test_matrix_operations() {
+        let a = matrix(&[&[1, 2, 3], &[4, 5, 6]]);
+        let b = matrix(&[&[6, 5, 4], &[3, 2, 1]]);
+
+        assert_eq!(
+            elements(&a.transpose()),
+            elements(&matrix(&[&[1, 4], &[2, 5], &[3, 6]]))
+        );
+        assert_eq!(
+            elements(&a.dot(&Matrix::identity(3)).unwrap()),
+            elements(&a)
+        );
+        assert_eq!(
+            elements(&a.hadamard(&b).unwrap()),
+            elements(&matrix(&[&[6, 10, 12], &[12, 10, 6]]))
+        );
+        assert_eq!(
+            elements(&(a.clone() + b.clone()).unwrap()),
+            elements(&matrix(&[&[7, 7, 7], &[7, 7, 7]]))
+        );
+        assert_eq!(
+            elements(&(a.clone() - a.clone()).unwrap()),
+            elements(&matrix(&[&[0, 0, 0], &[0, 0, 0]]))
+        );
+        assert_eq!(
+            elements(&a.scalar_mul(&Fr::from(2))),
+            elements(&(a.clone() + a.clone()).unwrap())
+        );
+
+        // mismatched dimensions are errors, not panics
+        let mismatch = |expected, got| Err(AlgorithmsError::DimensionMismatch { expected, got });
+        let c = matrix(&[&[1, 2], &[3, 4]]);
+        let d = matrix(&[&[1, 2, 3]]);
+        assert_eq!(a.dot(&a).map(|m| elements(&m)), mismatch(3, 2));
+        assert_eq!(a.hadamard(&c).map(|m| elements(&m)), mismatch(3, 2));
+        assert_eq!(a.hadamard(&d).map(|m| elements(&m)), mismatch(2, 1));
+        assert_eq!(
+            (a.clone() + c.clone()).map(|m| elements(&m)),
+            mismatch(3, 2)
+        );
+        assert_eq!((a.clone() - d).map(|m| elements(&m)), mismatch(2, 1));
+        let v = Vector::new(&[Fr::from(1), Fr::from(2)]);
+        let w = Vector::new(&[Fr::from(1)]);
+        assert!((v.clone() + w.clone()).is_err());
+        assert!((v.clone() - w.clone()).is_err());
+        assert!((v * w).is_err());
+        assert_eq!(
+            Matrix::try_new(
+                &c.rows()[..1]
+                    .iter()
+                    .cloned()
+                    .chain([Vector::new(&[Fr::from(1)])])
+                    .collect::<Vec<_>>()
+            )
+            .map(|m| elements(&m)),
+            mismatch(2, 1)
+        );
+
+        let k = matrix(&[&[1, 2], &[3, 4]]).kronecker(&matrix(&[&[0, 5], &[6, 7]]));
+        assert_eq!(
+            elements(&k),
+            elements(&matrix(&[
+                &[0, 5, 0, 10],
+                &[6, 7, 12, 14],
+                &[0, 15, 0, 20],
+                &[18, 21, 24, 28]
+            ]))
+        );
+
+        // (A^T)^T = A and I_1 x A = A
+        let (a, _, _) = get_test_r1cs::<Fr>();
+        assert_eq!(elements(&a.transpose().transpose()), elements(&a));
+        assert_eq!(elements(&Matrix::identity(1).kronecker(&a)), elements(&a));
+
+        // a vector of the wrong size
+        let error = AlgorithmsError::DimensionMismatch {
+            expected: a.num_cols(),
+            got: 2,
+        };
+        let v = Vector::new(&[Fr::from(1), Fr::from(2)]);
+        assert_eq!(a.dot_vector(&v).unwrap_err(), error);
+        let sparse = SparseMatrix::from_dense(&a);
+        assert_eq!(sparse.dot_vector(&v).unwrap_err(), error);
+    }
+}
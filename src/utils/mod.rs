@@ -1,33 +1,93 @@
-use ark_ff::PrimeField;
+use ark_ff::{Field, PrimeField};
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
 };
 
+use crate::error::AlgorithmsError;
+
+pub mod curve;
+#[cfg(feature = "serde")]
+pub mod hex_serde;
 pub mod lagrange;
 pub mod linear_algebra;
+pub mod pairing;
+pub mod polynomial;
+pub mod rs;
+pub mod serialization;
 
-pub fn get_omega_domain<F: PrimeField>(n: usize) -> (GeneralEvaluationDomain<F>, Vec<F>) {
-    // Builds the domain consisting of n roots of unity in F
-    let omegas = GeneralEvaluationDomain::<F>::new(n).unwrap();
-    let mut domain_elements: Vec<F> = vec![];
-    for element in omegas.elements() {
-        domain_elements.push(element);
-    }
-    (omegas, domain_elements)
+pub fn get_omega_domain<F: PrimeField>(
+    n: usize,
+) -> Result<(GeneralEvaluationDomain<F>, Vec<F>), AlgorithmsError> {
+    // Builds the domain consisting of n roots of unity in F, n being rounded to the next power of two
+    let omegas = GeneralEvaluationDomain::<F>::new(n).ok_or(AlgorithmsError::DomainNotFound(n))?;
+    let domain_elements = omegas.elements().collect();
+    Ok((omegas, domain_elements))
 }
 
-pub fn build_zero_polynomial<F: PrimeField>(roots: &Vec<F>) -> DensePolynomial<F> {
+pub fn build_zero_polynomial<F: PrimeField>(roots: &[F]) -> DensePolynomial<F> {
     // roots are the values at which the polynomial will be zero
-    // (X - roots[0]) * (X - roots[1]) * ... * (X - roots[n])
-    let mut polys = vec![];
-    for root in roots {
-        let poly = DensePolynomial::from_coefficients_vec(vec![*root * (-F::ONE), F::ONE]);
-        polys.push(poly);
+    // (X - roots[0]) * (X - roots[1]) * ... * (X - roots[n]), multiplied along a subproduct tree
+    // with no roots, this is the constant polynomial 1
+    if roots.is_empty() {
+        return DensePolynomial::from_coefficients_vec(vec![F::ONE]);
+    }
+    polynomial::SubproductTree::new(roots)
+        .zero_polynomial()
+        .clone()
+}
+
+/// sum_i a[i] * b[i], over the shortest of both slices
+pub fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(a_i, b_i)| *a_i * b_i).sum()
+}
+
+/// 1, x, x^2, ..., x^{n-1}
+pub fn powers<F: Field>(x: F, n: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(n);
+    let mut x_i = F::one();
+    for _ in 0..n {
+        powers.push(x_i);
+        x_i *= x;
     }
-    // multiply all the different polys together to get one single polynomial
-    let mut zero_poly = polys[0].clone();
-    for i in 1..polys.len() {
-        zero_poly = &zero_poly * &polys[i];
+    powers
+}
+
+/// Reorders `values` so that values[i] moves to index reverse_bits(i)
+/// `values.len()` should be a power of two
+pub fn bit_reversal_permutation<T: Clone>(values: &[T]) -> Vec<T> {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "length should be a power of two");
+    let log_n = n.trailing_zeros();
+    (0..n)
+        .map(|i| {
+            let reversed = if log_n == 0 {
+                0
+            } else {
+                i.reverse_bits() >> (usize::BITS - log_n)
+            };
+            values[reversed].clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_omega_domain;
+    use crate::error::AlgorithmsError;
+    use ark_bn254::Fr;
+    use ark_ff::FftField;
+    use ark_poly::EvaluationDomain;
+
+    #[test]
+    pub fn test_get_omega_domain() {
+        let (domain, elements) = get_omega_domain::<Fr>(5).unwrap();
+        assert_eq!(elements.len(), 8);
+        assert_eq!(elements[1], domain.group_gen());
+        // bn254's scalar field has no subgroup of order 2^29
+        let n = 1 << (Fr::TWO_ADICITY + 1);
+        assert_eq!(
+            get_omega_domain::<Fr>(n).unwrap_err(),
+            AlgorithmsError::DomainNotFound(n)
+        );
     }
-    zero_poly
 }
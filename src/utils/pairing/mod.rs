@@ -0,0 +1,42 @@
+use ark_ec::pairing::Pairing;
+use ark_std::Zero;
+
+/// Checks that prod_i e(a_i, b_i) == 1, with a single final exponentiation
+/// The miller loops of all pairs are run together by `Pairing::multi_pairing`, so a check of k pairings
+/// costs much less than k full pairings compared pairwise: move the terms of an equation e(a, b) == e(c, d)
+/// to one side as e(a, b) * e(-c, d) == 1.
+pub fn multi_pairing_check<E: Pairing>(pairs: &[(E::G1, E::G2)]) -> bool {
+    let (g1s, g2s): (Vec<_>, Vec<_>) = pairs.iter().copied().unzip();
+    E::multi_pairing(g1s, g2s).is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::multi_pairing_check;
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::{pairing::Pairing, Group};
+    use ark_ff::{One, UniformRand};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_multi_pairing_check() {
+        let mut rng = test_rng();
+        let (a, b) = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+        let (g_1, g_2) = (G1Projective::generator(), G2Projective::generator());
+        // e(a * g_1, b * g_2) == e(ab * g_1, g_2)
+        assert_eq!(
+            Bn254::pairing(g_1 * a, g_2 * b),
+            Bn254::pairing(g_1 * (a * b), g_2)
+        );
+        assert!(multi_pairing_check::<Bn254>(&[
+            (g_1 * a, g_2 * b),
+            (-g_1 * (a * b), g_2)
+        ]));
+        assert!(!multi_pairing_check::<Bn254>(&[
+            (g_1 * a, g_2 * b),
+            (-g_1 * (a * b + Fr::one()), g_2)
+        ]));
+        // the empty product is 1
+        assert!(multi_pairing_check::<Bn254>(&[]));
+    }
+}
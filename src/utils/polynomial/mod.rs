@@ -0,0 +1,370 @@
+// Univariate polynomial helpers
+// ark's `/` on dense polynomials returns the quotient only, dropping any remainder: exact divisions, where a
+// non-zero remainder means a bug or a cheating prover, should go through `divide_with_remainder` or
+// `ruffini_division` and check it.
+// The subproduct tree over points x_0, ..., x_{n-1} holds the products of the (X - x_i) two by two, then four by
+// four, up to the vanishing polynomial at its root: O(n log^2 n) with fft multiplications. Reducing a polynomial
+// modulo each node, from the root down, evaluates it at every x_i (the remainder modulo X - x_i being p(x_i)).
+// The reductions divide with newton iteration, through the inverse of the reversed divisor as a power series.
+pub mod sparse;
+
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::{
+    univariate::{DenseOrSparsePolynomial, DensePolynomial},
+    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
+};
+
+use sparse::SparsePolynomial;
+
+/// Evaluates `polynomial` over the coset offset * H, H being `domain`
+pub fn coset_evaluate<F: FftField>(
+    polynomial: &DensePolynomial<F>,
+    domain: GeneralEvaluationDomain<F>,
+    offset: F,
+) -> Vec<F> {
+    // the fft would silently reduce the polynomial modulo X^n - offset^n
+    assert!(
+        polynomial.coeffs.len() <= domain.size(),
+        "polynomial degree exceeds the domain size"
+    );
+    domain.get_coset(offset).unwrap().fft(&polynomial.coeffs)
+}
+
+/// Interpolates the polynomial p of degree < |H| such that p(offset * omega^i) = evals[i]
+pub fn coset_interpolate<F: FftField>(
+    evals: &[F],
+    domain: GeneralEvaluationDomain<F>,
+    offset: F,
+) -> DensePolynomial<F> {
+    assert_eq!(evals.len(), domain.size());
+    DensePolynomial::from_coefficients_vec(domain.get_coset(offset).unwrap().ifft(evals))
+}
+
+/// Returns (q, r) such that numerator = q * denominator + r and deg(r) < deg(denominator),
+/// None if the denominator is zero
+pub fn divide_with_remainder<F: Field>(
+    numerator: &DensePolynomial<F>,
+    denominator: &DensePolynomial<F>,
+) -> Option<(DensePolynomial<F>, DensePolynomial<F>)> {
+    if denominator.is_zero() {
+        return None;
+    }
+    DenseOrSparsePolynomial::from(numerator)
+        .divide_with_q_and_r(&DenseOrSparsePolynomial::from(denominator))
+}
+
+/// sum_i scalars[i] * polys[i]
+pub fn linear_combination<F: Field>(terms: &[(F, &DensePolynomial<F>)]) -> DensePolynomial<F> {
+    terms
+        .iter()
+        .fold(DensePolynomial::zero(), |acc, (s, p)| &acc + &(*p * *s))
+}
+
+/// Synthetic division by X - z: returns (q, r) such that p = q * (X - z) + r, the remainder r being p(z)
+pub fn ruffini_division<F: Field>(
+    polynomial: &DensePolynomial<F>,
+    z: F,
+) -> (DensePolynomial<F>, F) {
+    // from the leading coefficient down: q_{i-1} = p_i + z * q_i, the last accumulated value is p(z)
+    let mut quotient = vec![F::zero(); polynomial.coeffs.len().saturating_sub(1)];
+    let mut remainder = F::zero();
+    for (i, coeff) in polynomial.coeffs.iter().enumerate().rev() {
+        remainder = *coeff + z * remainder;
+        if i > 0 {
+            quotient[i - 1] = remainder;
+        }
+    }
+    (DensePolynomial::from_coefficients_vec(quotient), remainder)
+}
+
+/// Evaluates (x - roots[0]) * ... * (x - roots[n - 1]) without building the polynomial
+pub fn evaluate_vanishing_polynomial<F: Field>(roots: &[F], x: F) -> F {
+    roots.iter().map(|root| x - root).product()
+}
+
+/// Z_H(X) = X^n - c^n, vanishing on the domain H = c * <omega> (c = 1 unless H is itself a coset)
+pub fn vanishing_poly<F: FftField>(domain: GeneralEvaluationDomain<F>) -> SparsePolynomial<F> {
+    coset_vanishing_poly(domain, F::ONE)
+}
+
+/// X^n - (offset * c)^n, vanishing on the coset offset * H
+pub fn coset_vanishing_poly<F: FftField>(
+    domain: GeneralEvaluationDomain<F>,
+    offset: F,
+) -> SparsePolynomial<F> {
+    let n = domain.size();
+    let constant = offset.pow([n as u64]) * domain.coset_offset_pow_size();
+    SparsePolynomial::from_terms(vec![(0, -constant), (n, F::one())])
+}
+
+/// The vanishing polynomial of offset * H at x, in O(log n) multiplications
+pub fn evaluate_coset_vanishing_poly<F: FftField>(
+    domain: GeneralEvaluationDomain<F>,
+    offset: F,
+    x: F,
+) -> F {
+    let n = [domain.size() as u64];
+    x.pow(n) - offset.pow(n) * domain.coset_offset_pow_size()
+}
+
+/// Returns (q, r) such that p = q * Z(X) + r and deg(r) < n, Z being the vanishing polynomial of offset * H
+/// Linear in the degree of p, without building Z: X^n is reduced to (offset * c)^n from the top coefficient down.
+pub fn divide_by_coset_vanishing_poly<F: FftField>(
+    polynomial: &DensePolynomial<F>,
+    domain: GeneralEvaluationDomain<F>,
+    offset: F,
+) -> (DensePolynomial<F>, DensePolynomial<F>) {
+    let n = domain.size();
+    let constant = offset.pow([n as u64]) * domain.coset_offset_pow_size();
+    let mut remainder = polynomial.coeffs.clone();
+    let mut quotient = vec![F::zero(); remainder.len().saturating_sub(n)];
+    for i in (n..remainder.len()).rev() {
+        let coeff = remainder[i];
+        quotient[i - n] = coeff;
+        remainder[i - n] += coeff * constant;
+    }
+    remainder.truncate(n);
+    (
+        DensePolynomial::from_coefficients_vec(quotient),
+        DensePolynomial::from_coefficients_vec(remainder),
+    )
+}
+
+/// Power series inverse: g such that f * g = 1 mod X^k, f(0) being non-zero
+fn inverse_series<F: FftField>(f: &DensePolynomial<F>, k: usize) -> DensePolynomial<F> {
+    // g <- g * (2 - f * g), doubling the precision at each step
+    let mut g = DensePolynomial::from_coefficients_vec(vec![f.coeffs[0].inverse().unwrap()]);
+    let mut precision = 1;
+    while precision < k {
+        precision = (2 * precision).min(k);
+        let f_truncated =
+            DensePolynomial::from_coefficients_slice(&f.coeffs[..precision.min(f.coeffs.len())]);
+        let mut fg = truncate(&(&f_truncated * &g), precision);
+        for c in fg.coeffs.iter_mut() {
+            *c = -*c;
+        }
+        fg.coeffs[0] += F::from(2u64);
+        g = truncate(&(&g * &fg), precision);
+    }
+    g
+}
+
+/// p mod X^k, with k coefficients
+fn truncate<F: Field>(polynomial: &DensePolynomial<F>, k: usize) -> DensePolynomial<F> {
+    let mut coeffs = polynomial.coeffs[..k.min(polynomial.coeffs.len())].to_vec();
+    coeffs.resize(k, F::zero());
+    DensePolynomial { coeffs }
+}
+
+/// Remainder of `numerator` modulo `denominator`, through the reversed quotient:
+/// rev(q) = rev(numerator) * rev(denominator)^-1 mod X^{deg(numerator) - deg(denominator) + 1}
+fn fast_remainder<F: FftField>(
+    numerator: &DensePolynomial<F>,
+    denominator: &DensePolynomial<F>,
+    denominator_rev_inverse: &DensePolynomial<F>,
+) -> DensePolynomial<F> {
+    if numerator.is_zero() || numerator.degree() < denominator.degree() {
+        return numerator.clone();
+    }
+    let k = numerator.degree() - denominator.degree() + 1;
+    let numerator_rev = DensePolynomial::from_coefficients_vec(
+        numerator.coeffs.iter().rev().take(k).copied().collect(),
+    );
+    let mut quotient = truncate(&(&numerator_rev * &truncate(denominator_rev_inverse, k)), k);
+    quotient.coeffs.reverse();
+    let quotient = DensePolynomial::from_coefficients_vec(quotient.coeffs);
+    numerator - &(&quotient * denominator)
+}
+
+/// The subproduct tree over a set of points: layers[0] holds the X - x_i, each next layer the products of
+/// consecutive pairs (an odd node out being carried up as is), the last one the vanishing polynomial
+#[derive(Clone, Debug)]
+pub struct SubproductTree<F: FftField> {
+    points: Vec<F>,
+    layers: Vec<Vec<DensePolynomial<F>>>,
+}
+
+impl<F: FftField> SubproductTree<F> {
+    pub fn new(points: &[F]) -> Self {
+        assert!(!points.is_empty(), "the tree needs at least one point");
+        let mut layers = vec![points
+            .iter()
+            .map(|x| DensePolynomial::from_coefficients_vec(vec![-*x, F::one()]))
+            .collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => left * right,
+                    [node] => node.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(layer);
+        }
+        Self {
+            points: points.to_vec(),
+            layers,
+        }
+    }
+
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// (X - x_0) * ... * (X - x_{n-1})
+    pub fn zero_polynomial(&self) -> &DensePolynomial<F> {
+        &self.layers.last().unwrap()[0]
+    }
+
+    /// Evaluates `polynomial` at every point, in O(n log^2 n) for a polynomial of degree O(n)
+    pub fn evaluate(&self, polynomial: &DensePolynomial<F>) -> Vec<F> {
+        let mut remainders = vec![polynomial.clone()];
+        for layer in self.layers.iter().rev() {
+            // node i of a layer is the child of node i / 2 of the layer above
+            remainders = layer
+                .iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    let mut node_rev = node.coeffs.clone();
+                    node_rev.reverse();
+                    let inverse = inverse_series(
+                        &DensePolynomial { coeffs: node_rev },
+                        remainders[i / 2].coeffs.len().max(1),
+                    );
+                    fast_remainder(&remainders[i / 2], node, &inverse)
+                })
+                .collect();
+        }
+        remainders
+            .iter()
+            .map(|r| r.coeffs.first().copied().unwrap_or_default())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        coset_evaluate, coset_interpolate, coset_vanishing_poly, divide_by_coset_vanishing_poly,
+        divide_with_remainder, evaluate_coset_vanishing_poly, evaluate_vanishing_polynomial,
+        ruffini_division, vanishing_poly, SubproductTree,
+    };
+    use crate::utils::build_zero_polynomial;
+    use ark_bn254::Fr;
+    use ark_ff::{FftField, One, UniformRand, Zero};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain,
+        Polynomial,
+    };
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_polynomial_utils() {
+        let mut rng = test_rng();
+        let p = DensePolynomial::<Fr>::rand(10, &mut rng);
+        let z = Fr::rand(&mut rng);
+
+        let (q, r) = ruffini_division(&p, z);
+        assert_eq!(r, p.evaluate(&z));
+        let x_minus_z = DensePolynomial::from_coefficients_vec(vec![-z, Fr::from(1u64)]);
+        assert_eq!(
+            &(&q * &x_minus_z) + &DensePolynomial::from_coefficients_vec(vec![r]),
+            p
+        );
+        let (q_div, r_div) = divide_with_remainder(&p, &x_minus_z).unwrap();
+        assert_eq!(
+            (q_div, r_div.coeffs.first().copied().unwrap_or_default()),
+            (q, r)
+        );
+
+        // a non exact division keeps its remainder
+        let d = DensePolynomial::<Fr>::rand(4, &mut rng);
+        let (q, r) = divide_with_remainder(&p, &d).unwrap();
+        assert!(r.degree() < 4 && !r.is_zero());
+        assert_eq!(&(&q * &d) + &r, p);
+        assert!(divide_with_remainder(&p, &DensePolynomial::zero()).is_none());
+
+        let roots = (0..5).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        assert_eq!(
+            evaluate_vanishing_polynomial(&roots, z),
+            build_zero_polynomial(&roots).evaluate(&z)
+        );
+        assert!(evaluate_vanishing_polynomial(&roots, roots[2]).is_zero());
+
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let evals = coset_evaluate(&p, domain, Fr::GENERATOR);
+        for (i, eval) in evals.iter().enumerate() {
+            assert_eq!(*eval, p.evaluate(&(Fr::GENERATOR * domain.element(i))));
+        }
+        assert_eq!(coset_interpolate(&evals, domain, Fr::GENERATOR), p);
+    }
+
+    #[test]
+    pub fn test_subproduct_tree() {
+        let mut rng = test_rng();
+        // not a power of two, some nodes are carried up
+        let points = (0..37).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let tree = SubproductTree::new(&points);
+        let mut naive = DensePolynomial::from_coefficients_vec(vec![Fr::from(1u64)]);
+        for x in points.iter() {
+            naive = &naive * &DensePolynomial::from_coefficients_vec(vec![-*x, Fr::from(1u64)]);
+        }
+        assert_eq!(tree.zero_polynomial(), &naive);
+        assert_eq!(build_zero_polynomial(&points), naive);
+
+        // lower and higher degrees than the number of points
+        for degree in [0, 20, 100] {
+            let p = DensePolynomial::<Fr>::rand(degree, &mut rng);
+            let evals = points.iter().map(|x| p.evaluate(x)).collect::<Vec<_>>();
+            assert_eq!(tree.evaluate(&p), evals);
+        }
+        assert_eq!(
+            tree.evaluate(&DensePolynomial::zero()),
+            vec![Fr::zero(); 37]
+        );
+    }
+
+    #[test]
+    pub fn test_vanishing_poly() {
+        let mut rng = test_rng();
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        let offset = Fr::GENERATOR;
+        let z_h = vanishing_poly(domain);
+        assert_eq!(z_h.to_dense(), domain.vanishing_polynomial().into());
+        assert!(domain
+            .elements()
+            .all(|omega| z_h.evaluate(&omega).is_zero()));
+
+        // over the coset, as a domain of its own or through the offset
+        let coset = domain.get_coset(offset).unwrap();
+        let z_coset = coset_vanishing_poly(domain, offset);
+        assert_eq!(z_coset, vanishing_poly(coset));
+        assert!(coset.elements().all(|x| z_coset.evaluate(&x).is_zero()));
+        assert!(!z_coset.evaluate(&Fr::one()).is_zero());
+        let x = Fr::rand(&mut rng);
+        assert_eq!(
+            evaluate_coset_vanishing_poly(domain, offset, x),
+            z_coset.evaluate(&x)
+        );
+        assert_eq!(
+            evaluate_coset_vanishing_poly(domain, Fr::one(), x),
+            domain.evaluate_vanishing_polynomial(x)
+        );
+
+        // division, exact or not
+        let p = DensePolynomial::<Fr>::rand(40, &mut rng);
+        let (q, r) = divide_by_coset_vanishing_poly(&p, domain, offset);
+        assert!(r.degree() < 16);
+        assert_eq!(&z_coset.mul_dense(&q) + &r, p);
+        let (q_exact, r_zero) =
+            divide_by_coset_vanishing_poly(&z_coset.mul_dense(&q), domain, offset);
+        assert_eq!((q_exact, r_zero.is_zero()), (q, true));
+        let small = DensePolynomial::<Fr>::rand(5, &mut rng);
+        let (q, r) = divide_by_coset_vanishing_poly(&small, domain, offset);
+        assert!(q.is_zero());
+        assert_eq!(r, small);
+    }
+}
@@ -0,0 +1,119 @@
+// Sparse univariate polynomials, as a list of (degree, coefficient) terms
+// Selector-like polynomials (mostly zero coefficients) are cheaper to commit to and evaluate this way:
+// the work is linear in the number of non-zero terms rather than in the degree.
+use ark_ff::Field;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+
+/// sum_i c_i X^{d_i}, terms sorted by increasing degree, with distinct degrees and non-zero coefficients
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparsePolynomial<F: Field> {
+    terms: Vec<(usize, F)>,
+}
+
+impl<F: Field> SparsePolynomial<F> {
+    /// Terms sharing a degree are summed, zero coefficients dropped
+    pub fn from_terms(mut terms: Vec<(usize, F)>) -> Self {
+        terms.sort_by_key(|(degree, _)| *degree);
+        let mut merged: Vec<(usize, F)> = Vec::with_capacity(terms.len());
+        for (degree, coeff) in terms {
+            match merged.last_mut() {
+                Some((last, sum)) if *last == degree => *sum += coeff,
+                _ => merged.push((degree, coeff)),
+            }
+        }
+        merged.retain(|(_, coeff)| !coeff.is_zero());
+        Self { terms: merged }
+    }
+
+    pub fn from_dense(polynomial: &DensePolynomial<F>) -> Self {
+        let terms = polynomial
+            .coeffs
+            .iter()
+            .enumerate()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|(degree, coeff)| (degree, *coeff))
+            .collect();
+        Self { terms }
+    }
+
+    pub fn to_dense(&self) -> DensePolynomial<F> {
+        let mut coeffs = vec![F::zero(); self.degree() + 1];
+        for (degree, coeff) in &self.terms {
+            coeffs[*degree] = *coeff;
+        }
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    pub fn terms(&self) -> &[(usize, F)] {
+        &self.terms
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// 0 for the zero polynomial, as for ark's dense polynomials
+    pub fn degree(&self) -> usize {
+        self.terms.last().map_or(0, |(degree, _)| *degree)
+    }
+
+    /// self * other, in O(#terms * deg(other))
+    pub fn mul_dense(&self, other: &DensePolynomial<F>) -> DensePolynomial<F> {
+        if self.is_zero() || other.coeffs.is_empty() {
+            return DensePolynomial::from_coefficients_vec(vec![]);
+        }
+        let mut coeffs = vec![F::zero(); self.degree() + other.coeffs.len()];
+        for (degree, coeff) in &self.terms {
+            for (i, other_coeff) in other.coeffs.iter().enumerate() {
+                coeffs[degree + i] += *coeff * other_coeff;
+            }
+        }
+        DensePolynomial::from_coefficients_vec(coeffs)
+    }
+
+    /// Square and multiply from one term's degree to the next
+    pub fn evaluate(&self, x: &F) -> F {
+        let mut result = F::zero();
+        let (mut power, mut x_power) = (0, F::one());
+        for (degree, coeff) in &self.terms {
+            x_power *= x.pow([(degree - power) as u64]);
+            power = *degree;
+            result += x_power * coeff;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparsePolynomial;
+    use ark_bn254::Fr;
+    use ark_ff::{UniformRand, Zero};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_sparse_polynomial() {
+        let mut rng = test_rng();
+        let (a, b, c) = (Fr::rand(&mut rng), Fr::rand(&mut rng), Fr::rand(&mut rng));
+        // duplicated degrees are summed, cancelling terms dropped
+        let p =
+            SparsePolynomial::from_terms(vec![(100, a), (3, b), (0, c), (3, b), (7, a), (7, -a)]);
+        assert_eq!(p.terms(), &[(0, c), (3, b + b), (100, a)]);
+        assert_eq!(p.degree(), 100);
+
+        let dense = p.to_dense();
+        assert_eq!(dense.degree(), 100);
+        assert_eq!(SparsePolynomial::from_dense(&dense), p);
+        let x = Fr::rand(&mut rng);
+        assert_eq!(p.evaluate(&x), dense.evaluate(&x));
+        let q = DensePolynomial::rand(10, &mut rng);
+        assert_eq!(p.mul_dense(&q), &dense * &q);
+
+        let zero = SparsePolynomial::<Fr>::from_terms(vec![(2, a), (2, -a)]);
+        assert!(zero.is_zero());
+        assert_eq!(zero.degree(), 0);
+        assert!(zero.to_dense().is_zero());
+        assert_eq!(zero.evaluate(&x), Fr::zero());
+    }
+}
@@ -0,0 +1,215 @@
+// Reed-Solomon codes, the low degree extensions behind FRI, Ligero and STARK traces
+// A message of k symbols is read as the evaluations of a polynomial p of degree < k over a domain D of size k,
+// and encoded as p's evaluations over a domain L of size n = k * blowup_factor: a code of rate 1 / blowup_factor
+// and minimum distance n - k + 1. Taking L = offset * <omega> (a coset) keeps L disjoint from D, as STARKs do.
+// When L is the subgroup, D is its subgroup of size k and the code is systematic: message[i] = codeword[i * blowup].
+// Proximity testing reads codewords at a few positions: two distinct codewords agree on at most k - 1 of them,
+// and a random combination of words close to the code is close to it as well (the Ligero/FRI test).
+use ark_ff::{FftField, PrimeField};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
+};
+
+use super::polynomial::{coset_evaluate, coset_interpolate};
+use crate::error::AlgorithmsError;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReedSolomon<F: FftField> {
+    message_length: usize,
+    blowup_factor: usize,
+    offset: F,
+    message_domain: GeneralEvaluationDomain<F>,
+    domain: GeneralEvaluationDomain<F>,
+}
+
+impl<F: FftField> ReedSolomon<F> {
+    /// Encodes messages of `message_length` symbols over the subgroup of size message_length * blowup_factor
+    pub fn new(message_length: usize, blowup_factor: usize) -> Self {
+        Self::new_with_offset(message_length, blowup_factor, F::ONE)
+    }
+
+    /// Same as `new`, the codewords being evaluations over offset * <omega>
+    /// Panics if the field has no subgroup for the codewords, see `try_new_with_offset`
+    pub fn new_with_offset(message_length: usize, blowup_factor: usize, offset: F) -> Self {
+        Self::try_new_with_offset(message_length, blowup_factor, offset)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// An error if the field has no subgroup of order message_length * blowup_factor, e.g. for a small
+    /// field's two-adicity
+    pub fn try_new_with_offset(
+        message_length: usize,
+        blowup_factor: usize,
+        offset: F,
+    ) -> Result<Self, AlgorithmsError> {
+        assert!(
+            message_length.is_power_of_two() && blowup_factor.is_power_of_two(),
+            "message length and blowup factor must be powers of two"
+        );
+        let n = message_length * blowup_factor;
+        let domain =
+            GeneralEvaluationDomain::<F>::new(n).ok_or(AlgorithmsError::DomainNotFound(n))?;
+        // a subgroup of the codewords' one
+        let message_domain = GeneralEvaluationDomain::<F>::new(message_length).unwrap();
+        assert_eq!(domain.size(), n);
+        Ok(Self {
+            message_length,
+            blowup_factor,
+            offset,
+            message_domain,
+            domain,
+        })
+    }
+
+    pub fn message_length(&self) -> usize {
+        self.message_length
+    }
+
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    pub fn codeword_length(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// The codewords' domain is offset * <omega>
+    pub fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.domain
+    }
+
+    pub fn offset(&self) -> F {
+        self.offset
+    }
+
+    /// n - k + 1
+    pub fn minimum_distance(&self) -> usize {
+        self.codeword_length() - self.message_length + 1
+    }
+
+    /// The interpolant of `message` over the message domain, shifted by the offset
+    pub fn message_polynomial(&self, message: &[F]) -> DensePolynomial<F> {
+        assert_eq!(message.len(), self.message_length, "wrong message length");
+        coset_interpolate(message, self.message_domain, self.offset)
+    }
+
+    /// The low degree extension of `message`: its interpolant evaluated over the codewords' domain
+    pub fn encode(&self, message: &[F]) -> Vec<F> {
+        self.encode_polynomial(&self.message_polynomial(message))
+    }
+
+    /// Evaluates a polynomial of degree < k over the codewords' domain
+    pub fn encode_polynomial(&self, polynomial: &DensePolynomial<F>) -> Vec<F> {
+        assert!(
+            polynomial.coeffs.len() <= self.message_length,
+            "polynomial degree exceeds the message length"
+        );
+        coset_evaluate(polynomial, self.domain, self.offset)
+    }
+
+    /// The polynomial of degree < n taking the values of `word` over the domain
+    pub fn interpolate(&self, word: &[F]) -> DensePolynomial<F> {
+        coset_interpolate(word, self.domain, self.offset)
+    }
+
+    /// Decodes a codeword back to its message, None if `word` isn't a codeword
+    pub fn decode(&self, word: &[F]) -> Option<Vec<F>> {
+        if !self.is_codeword(word) {
+            return None;
+        }
+        Some(coset_evaluate(
+            &self.interpolate(word),
+            self.message_domain,
+            self.offset,
+        ))
+    }
+
+    /// Whether `word` is the evaluation of a polynomial of degree < k, in O(n log n)
+    pub fn is_codeword(&self, word: &[F]) -> bool {
+        word.len() == self.codeword_length()
+            && self.interpolate(word).coeffs.len() <= self.message_length
+    }
+}
+
+/// Number of positions where `a` and `b` differ
+pub fn hamming_distance<F: PrimeField>(a: &[F], b: &[F]) -> usize {
+    assert_eq!(a.len(), b.len());
+    a.iter().zip(b).filter(|(a_i, b_i)| a_i != b_i).count()
+}
+
+/// Hamming distance divided by the length
+pub fn relative_distance<F: PrimeField>(a: &[F], b: &[F]) -> f64 {
+    hamming_distance(a, b) as f64 / a.len() as f64
+}
+
+/// sum_j coefficients[j] * words[j], the random combination of a proximity test
+pub fn combine<F: PrimeField>(words: &[Vec<F>], coefficients: &[F]) -> Vec<F> {
+    assert_eq!(words.len(), coefficients.len());
+    let n = words.first().map_or(0, Vec::len);
+    let mut combination = vec![F::zero(); n];
+    for (word, coefficient) in words.iter().zip(coefficients) {
+        assert_eq!(word.len(), n, "words should have the same length");
+        for (c, w) in combination.iter_mut().zip(word) {
+            *c += *coefficient * w;
+        }
+    }
+    combination
+}
+
+/// Checks that `word` agrees with `polynomial` at the given positions of the domain, as a verifier
+/// holding the claimed polynomial does
+pub fn spot_check<F: PrimeField>(
+    code: &ReedSolomon<F>,
+    word: &[F],
+    polynomial: &DensePolynomial<F>,
+    positions: &[usize],
+) -> bool {
+    polynomial.coeffs.len() <= code.message_length()
+        && positions.iter().all(|p| {
+            *p < word.len()
+                && word[*p] == polynomial.evaluate(&(code.offset() * code.domain().element(*p)))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combine, hamming_distance, spot_check, ReedSolomon};
+    use ark_ff::{FftField, UniformRand};
+    use ark_pallas::Fr;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_reed_solomon() {
+        let mut rng = test_rng();
+        let code = ReedSolomon::<Fr>::new(8, 4);
+        assert_eq!((code.codeword_length(), code.minimum_distance()), (32, 25));
+        let message = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let codeword = code.encode(&message);
+        // systematic over the subgroup
+        for (i, m) in message.iter().enumerate() {
+            assert_eq!(codeword[4 * i], *m);
+        }
+        assert!(code.is_codeword(&codeword));
+        assert_eq!(code.decode(&codeword), Some(message.clone()));
+
+        // a single error takes the word out of the code
+        let mut corrupted = codeword.clone();
+        corrupted[5] += Fr::from(1u64);
+        assert!(!code.is_codeword(&corrupted));
+        assert!(code.decode(&corrupted).is_none());
+        assert_eq!(hamming_distance(&codeword, &corrupted), 1);
+        let polynomial = code.message_polynomial(&message);
+        assert!(spot_check(&code, &corrupted, &polynomial, &[0, 7, 31]));
+        assert!(!spot_check(&code, &corrupted, &polynomial, &[0, 5]));
+
+        // codewords over a coset, and their combinations
+        let coset_code = ReedSolomon::<Fr>::new_with_offset(8, 4, Fr::GENERATOR);
+        let other = (0..8).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (a, b) = (coset_code.encode(&message), coset_code.encode(&other));
+        assert_eq!(coset_code.decode(&a), Some(message));
+        let r = Fr::rand(&mut rng);
+        let combination = combine(&[a.clone(), b], &[Fr::from(1u64), r]);
+        assert!(coset_code.is_codeword(&combination));
+        assert!(!coset_code.is_codeword(&combine(&[a, corrupted], &[Fr::from(1u64), r])));
+    }
+}
@@ -0,0 +1,363 @@
+// Versioned binary encoding of the artifacts the crate produces (proofs, keys, folding instances), so they can be
+// persisted and exchanged between processes. An artifact is laid out as:
+// - the magic bytes "arka" and the format version (1 byte),
+// - a domain tag naming the artifact type, prefixed by its length (1 byte), e.g. "groth16-proof",
+// - the compressed CanonicalSerialize encoding of the artifact.
+// Decoding checks the header, validates points (on the curve and in the prime order subgroup) and rejects
+// trailing bytes: bytes meant for another type, version or curve fail instead of decoding to garbage.
+// As with arkworks' serialization flags, `Validate::No` skips the point checks, e.g. for bytes from a trusted
+// store: `validate` then runs them later, and must succeed before the artifact's points enter a pairing.
+use ark_ec::{pairing::Pairing, short_weierstrass::SWCurveConfig, CurveGroup};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+
+use crate::cs::pcs::{
+    dory::DoryProof,
+    fri::{FRIOpeningProof, FRIProof},
+    ipa::IPAProof,
+    kzg::{KZGOpening, MultiOpenProof, KZG},
+};
+use crate::cs::pedersen::Pedersen;
+use crate::error::AlgorithmsError;
+use crate::folding::{
+    mova::MovaInstance,
+    nifs::{CommittedRelaxedInstance, RelaxedWitness},
+    protogalaxy::ProtoGalaxyInstance,
+};
+use crate::ip::sumcheck::protocol::SumcheckProof;
+use crate::snark::{
+    groth16::{Groth16Proof, Groth16VerifyingKey},
+    plonk::{PlonkProof, PlonkVerifyingKey},
+    stark::StarkProof,
+};
+
+pub const MAGIC: [u8; 4] = *b"arka";
+pub const FORMAT_VERSION: u8 = 1;
+
+/// A type with a versioned binary encoding
+pub trait Artifact: CanonicalSerialize + CanonicalDeserialize {
+    /// Names the type in the header, distinct for each artifact type
+    const TAG: &'static str;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(FORMAT_VERSION);
+        bytes.push(Self::TAG.len() as u8);
+        bytes.extend(Self::TAG.as_bytes());
+        self.serialize_compressed(&mut bytes)
+            .expect("serializing to a vec doesn't fail");
+        bytes
+    }
+
+    /// Decodes and validates the artifact
+    fn from_bytes(bytes: &[u8]) -> Result<Self, AlgorithmsError> {
+        Self::from_bytes_with_mode(bytes, Validate::Yes)
+    }
+
+    /// Decodes the artifact, validating its points only with `Validate::Yes`
+    fn from_bytes_with_mode(bytes: &[u8], validate: Validate) -> Result<Self, AlgorithmsError> {
+        let error = |e: &str| AlgorithmsError::Serialization(e.to_string());
+        let header_size = MAGIC.len() + 2 + Self::TAG.len();
+        if bytes.len() < header_size || bytes[..MAGIC.len()] != MAGIC {
+            return Err(error("missing header"));
+        }
+        if bytes[MAGIC.len()] != FORMAT_VERSION {
+            return Err(AlgorithmsError::Serialization(format!(
+                "unsupported format version {}",
+                bytes[MAGIC.len()]
+            )));
+        }
+        let tag = &bytes[MAGIC.len() + 2..];
+        if bytes[MAGIC.len() + 1] as usize != Self::TAG.len()
+            || !tag.starts_with(Self::TAG.as_bytes())
+        {
+            return Err(AlgorithmsError::Serialization(format!(
+                "expected a {} artifact",
+                Self::TAG
+            )));
+        }
+        let mut payload = &bytes[header_size..];
+        let artifact = Self::deserialize_with_mode(&mut payload, Compress::Yes, validate)
+            .map_err(|e| AlgorithmsError::Serialization(e.to_string()))?;
+        if !payload.is_empty() {
+            return Err(error("trailing bytes"));
+        }
+        Ok(artifact)
+    }
+
+    /// Checks that all the points are on the curve and in the prime order subgroup,
+    /// for artifacts decoded with `Validate::No`
+    fn validate(&self) -> Result<(), AlgorithmsError> {
+        self.check().map_err(|_| AlgorithmsError::InvalidPoint)
+    }
+}
+
+impl<E: Pairing> Artifact for KZG<E> {
+    const TAG: &'static str = "kzg-srs";
+}
+
+impl<E: Pairing> Artifact for KZGOpening<E> {
+    const TAG: &'static str = "kzg-opening";
+}
+
+impl<E: Pairing> Artifact for MultiOpenProof<E> {
+    const TAG: &'static str = "kzg-multi-open-proof";
+}
+
+impl<C: CurveGroup> Artifact for Pedersen<C> {
+    const TAG: &'static str = "pedersen-parameters";
+}
+
+impl<F: PrimeField> Artifact for SumcheckProof<F> {
+    const TAG: &'static str = "sumcheck-proof";
+}
+
+impl<C: CurveGroup> Artifact for CommittedRelaxedInstance<C> {
+    const TAG: &'static str = "nova-instance";
+}
+
+impl<C: CurveGroup> Artifact for MovaInstance<C> {
+    const TAG: &'static str = "mova-instance";
+}
+
+impl<C: CurveGroup> Artifact for ProtoGalaxyInstance<C> {
+    const TAG: &'static str = "protogalaxy-instance";
+}
+
+impl<F: PrimeField> Artifact for RelaxedWitness<F> {
+    const TAG: &'static str = "nova-witness";
+}
+
+impl<E: Pairing> Artifact for Groth16Proof<E> {
+    const TAG: &'static str = "groth16-proof";
+}
+
+impl<E: Pairing> Artifact for Groth16VerifyingKey<E> {
+    const TAG: &'static str = "groth16-vk";
+}
+
+impl<E: Pairing> Artifact for PlonkProof<E> {
+    const TAG: &'static str = "plonk-proof";
+}
+
+impl<E: Pairing> Artifact for PlonkVerifyingKey<E> {
+    const TAG: &'static str = "plonk-vk";
+}
+
+impl<F: PrimeField> Artifact for StarkProof<F> {
+    const TAG: &'static str = "stark-proof";
+}
+
+impl<E: Pairing> Artifact for DoryProof<E> {
+    const TAG: &'static str = "dory-proof";
+}
+
+impl<P: SWCurveConfig> Artifact for IPAProof<P> {
+    const TAG: &'static str = "ipa-proof";
+}
+
+impl<F: PrimeField> Artifact for FRIProof<F> {
+    const TAG: &'static str = "fri-proof";
+}
+
+impl<F: PrimeField> Artifact for FRIOpeningProof<F> {
+    const TAG: &'static str = "fri-opening-proof";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Artifact, FORMAT_VERSION, MAGIC};
+    use crate::circuits::air::FibonacciAir;
+    use crate::circuits::plonkish::PlonkishBuilder;
+    use crate::circuits::r1cs::utils::{get_r1cs_from_cs, get_z_from_cs, TestPythagoreCircuit};
+    use crate::cs::pcs::{
+        dory::{Dory, DoryProof},
+        fri::{FRIOpeningProof, FRIProof, FRI},
+        ipa::{IPAProof, IPA},
+        kzg::{KZGOpening, KZG},
+    };
+    use crate::cs::pedersen::Pedersen;
+    use crate::error::AlgorithmsError;
+    use crate::folding::{mova::MovaInstance, protogalaxy::ProtoGalaxyInstance};
+    use crate::ip::sumcheck::protocol::SumcheckProof;
+    use crate::snark::{
+        groth16::{Groth16, Groth16Proof, Groth16VerifyingKey},
+        plonk::{Plonk, PlonkProof, PlonkVerifyingKey},
+        stark::{Stark, StarkProof},
+    };
+    use ark_bn254::{Bn254, Fq2, Fr, G1Projective, G2Affine, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::{Field, UniformRand};
+    use ark_poly::{
+        univariate::DensePolynomial, DenseMultilinearExtension, DenseUVPolynomial,
+        MultilinearExtension, Polynomial,
+    };
+    use ark_serialize::Validate;
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_kzg_artifacts() {
+        let mut rng = test_rng();
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 7);
+        kzg.setup_from_rng(&mut rng);
+        let kzg = KZG::<Bn254>::from_bytes(&kzg.to_bytes()).unwrap();
+
+        let polynomial = DensePolynomial::<Fr>::rand(7, &mut rng);
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let opening = KZGOpening {
+            commitment: kzg.commit(&polynomial).unwrap(),
+            z,
+            y,
+            pi: kzg.open(&polynomial, z, y).unwrap(),
+        };
+        let bytes = opening.to_bytes();
+        assert_eq!(&bytes[..4], &MAGIC);
+        assert_eq!(bytes[4], FORMAT_VERSION);
+        let decoded = KZGOpening::<Bn254>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, opening);
+        assert!(kzg.verify_opening(&decoded));
+
+        // another version, another type, trailing or missing bytes
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] += 1;
+        assert!(KZGOpening::<Bn254>::from_bytes(&wrong_version).is_err());
+        assert!(matches!(
+            SumcheckProof::<Fr>::from_bytes(&bytes),
+            Err(AlgorithmsError::Serialization(_))
+        ));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(KZGOpening::<Bn254>::from_bytes(&trailing).is_err());
+        assert!(KZGOpening::<Bn254>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    pub fn test_groth16_artifacts() {
+        let mut rng = test_rng();
+        let circuit = TestPythagoreCircuit::new(Fr::from(3u64), Fr::from(4u64), Fr::from(25u64));
+        let r1cs = get_r1cs_from_cs(circuit.clone()).unwrap();
+        let z = get_z_from_cs(circuit).unwrap();
+        let (pk, vk) = Groth16::<Bn254>::setup(&r1cs, &mut rng).unwrap();
+        let proof = Groth16::prove(&pk, &z, &mut rng).unwrap();
+
+        let vk = Groth16VerifyingKey::<Bn254>::from_bytes(&vk.to_bytes()).unwrap();
+        let proof = Groth16Proof::<Bn254>::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(Groth16::verify(&vk, &[Fr::from(25u64)], &proof));
+
+        // b on the twist, outside of bn254's G2 prime order subgroup
+        let mut x = Fq2::ONE;
+        let b = loop {
+            if let Some(p) = G2Affine::get_point_from_x_unchecked(x, false) {
+                break p;
+            }
+            x += Fq2::ONE;
+        };
+        let invalid = Groth16Proof::<Bn254> {
+            b: b.into(),
+            ..proof
+        };
+        let bytes = invalid.to_bytes();
+        assert!(matches!(
+            Groth16Proof::<Bn254>::from_bytes(&bytes),
+            Err(AlgorithmsError::Serialization(_))
+        ));
+        let unchecked = Groth16Proof::<Bn254>::from_bytes_with_mode(&bytes, Validate::No).unwrap();
+        assert_eq!(unchecked.validate(), Err(AlgorithmsError::InvalidPoint));
+        assert!(!Groth16::verify(&vk, &[Fr::from(25u64)], &unchecked));
+        assert_eq!(proof.validate(), Ok(()));
+    }
+
+    #[test]
+    pub fn test_pedersen_and_folding_artifacts() {
+        let mut rng = test_rng();
+        let pedersen = Pedersen::<G1Projective>::setup_random(4, &mut rng);
+        assert_eq!(
+            Pedersen::<G1Projective>::from_bytes(&pedersen.to_bytes()).unwrap(),
+            pedersen
+        );
+        let mova = MovaInstance::<G1Projective> {
+            comm_w: G1Projective::rand(&mut rng),
+            u: Fr::rand(&mut rng),
+            x: vec![Fr::rand(&mut rng)],
+            r_e: vec![Fr::rand(&mut rng); 3],
+            v: Fr::rand(&mut rng),
+        };
+        let bytes = mova.to_bytes();
+        assert_eq!(MovaInstance::from_bytes(&bytes).unwrap(), mova);
+        assert_eq!(
+            MovaInstance::<G1Projective>::from_bytes_with_mode(&bytes, Validate::No).unwrap(),
+            mova
+        );
+        let protogalaxy = ProtoGalaxyInstance::<G1Projective> {
+            comm_w: G1Projective::rand(&mut rng),
+            x: vec![Fr::rand(&mut rng)],
+            betas: vec![Fr::rand(&mut rng); 2],
+            e: Fr::rand(&mut rng),
+        };
+        assert_eq!(
+            ProtoGalaxyInstance::from_bytes(&protogalaxy.to_bytes()).unwrap(),
+            protogalaxy
+        );
+        assert!(ProtoGalaxyInstance::<G1Projective>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    pub fn test_proof_artifacts() {
+        let mut rng = test_rng();
+
+        // x * x = y, y public
+        let mut builder = PlonkishBuilder::new();
+        let y = builder.public_input(Fr::from(9u64));
+        let x = builder.private_input(Fr::from(3u64));
+        let x_2 = builder.mul(x, x);
+        builder.assert_equal(x_2, y);
+        let (circuit, witness) = builder.build();
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 16);
+        kzg.setup_from_rng(&mut rng);
+        let pk = Plonk::setup(&kzg, &circuit).unwrap();
+        let proof = Plonk::prove(&kzg, &pk, &witness, &mut rng).unwrap();
+        let vk = PlonkVerifyingKey::<Bn254>::from_bytes(&pk.vk().to_bytes()).unwrap();
+        let proof = PlonkProof::<Bn254>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(Plonk::verify(&kzg, &vk, &[Fr::from(9u64)], &proof), Ok(()));
+        assert!(Groth16Proof::<Bn254>::from_bytes(&proof.to_bytes()).is_err());
+
+        let stark = Stark::<Fr>::new(4, 8);
+        let trace = FibonacciAir::<Fr>::trace(16);
+        let air = FibonacciAir::new(16, trace[1][15]);
+        let proof = stark.prove(&air, &trace).unwrap();
+        let bytes = proof.to_bytes();
+        assert_eq!(StarkProof::<Fr>::from_bytes(&bytes).unwrap(), proof);
+        assert!(stark.verify(&air, &StarkProof::from_bytes(&bytes).unwrap()));
+
+        let fri = FRI::<Fr>::new(7, 4, 8);
+        let polynomial = DensePolynomial::<Fr>::rand(7, &mut rng);
+        let proof = fri.prove_low_degree(&polynomial);
+        assert_eq!(
+            FRIProof::<Fr>::from_bytes(&proof.to_bytes()).unwrap(),
+            proof
+        );
+        let (proof, _) = fri.open(&polynomial, Fr::rand(&mut rng));
+        assert_eq!(
+            FRIOpeningProof::<Fr>::from_bytes(&proof.to_bytes()).unwrap(),
+            proof
+        );
+        assert!(FRIProof::<Fr>::from_bytes(&proof.to_bytes()).is_err());
+
+        let dory = Dory::<Bn254>::new(4);
+        let mle = DenseMultilinearExtension::<Fr>::rand(4, &mut rng);
+        let (commitment, row_commitments) = dory.commit(&mle);
+        let r = (0..4).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let (proof, y) = dory.open(&mle, &row_commitments, &r);
+        let proof = DoryProof::<Bn254>::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(dory.verify(&commitment, &r, y, &proof));
+
+        let ipa = IPA::<ark_pallas::PallasConfig>::new(7, b"ark-algorithms ipa test");
+        let polynomial = DensePolynomial::<ark_pallas::Fr>::rand(7, &mut rng);
+        let commitment = ipa.commit(&polynomial);
+        let z = ark_pallas::Fr::rand(&mut rng);
+        let (proof, y) = ipa.open(&polynomial, commitment, z);
+        let proof = IPAProof::<ark_pallas::PallasConfig>::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(ipa.verify(commitment, z, y, &proof));
+    }
+}
@@ -0,0 +1,90 @@
+// wasm-bindgen bindings, so that proofs produced natively can be verified in a browser
+// Build with `wasm-pack build --target web --features wasm`. Points and scalars cross the boundary as bytes,
+// in the EVM encoding of `cs::pcs::kzg::evm`: 32 bytes big-endian scalars, (x, y) G1 points and
+// (x_c1, x_c0, y_c1, y_c0) G2 points. Decoding checks that points are in their prime order subgroup.
+use ark_bn254::Bn254;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::cs::pcs::kzg::{
+    evm::{decode_fr, decode_g1, decode_g2},
+    KZG,
+};
+
+/// Verifier of single point bn254 KZG openings, holding g1, g2 and vk = [tau]_2
+#[wasm_bindgen]
+pub struct KzgVerifier {
+    kzg: KZG<Bn254>,
+}
+
+#[wasm_bindgen]
+impl KzgVerifier {
+    #[wasm_bindgen(constructor)]
+    pub fn new(g1_bytes: &[u8], g2_bytes: &[u8], vk_bytes: &[u8]) -> Result<KzgVerifier, String> {
+        let g1 = decode_g1(g1_bytes).map_err(|e| e.to_string())?;
+        let g2 = decode_g2(g2_bytes).map_err(|e| e.to_string())?;
+        let vk = decode_g2(vk_bytes).map_err(|e| e.to_string())?;
+        Ok(Self {
+            kzg: KZG::from_srs(vec![g1], vec![g2, vk]),
+        })
+    }
+
+    /// Checks that `proof` opens `commitment` to y at z, errors on bytes that don't decode
+    pub fn verify_kzg(
+        &self,
+        commitment_bytes: &[u8],
+        proof_bytes: &[u8],
+        z: &[u8],
+        y: &[u8],
+    ) -> Result<bool, String> {
+        let commitment = decode_g1(commitment_bytes).map_err(|e| e.to_string())?;
+        let proof = decode_g1(proof_bytes).map_err(|e| e.to_string())?;
+        let (y, z) = (decode_fr(y), decode_fr(z));
+        let (y, z) = (y.map_err(|e| e.to_string())?, z.map_err(|e| e.to_string())?);
+        Ok(self.kzg.verify(y, z, commitment, proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KzgVerifier;
+    use crate::cs::pcs::kzg::{
+        evm::{encode_fr, encode_g1, encode_g2},
+        KZG,
+    };
+    use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+    use ark_ec::Group;
+    use ark_ff::{One, UniformRand};
+    use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
+    use ark_std::test_rng;
+
+    #[test]
+    pub fn test_kzg_verifier() {
+        let mut rng = test_rng();
+        let mut kzg = KZG::<Bn254>::new(G1Projective::generator(), G2Projective::generator(), 15);
+        kzg.setup_from_rng(&mut rng);
+        let polynomial = DensePolynomial::<Fr>::rand(15, &mut rng);
+        let z = Fr::rand(&mut rng);
+        let y = polynomial.evaluate(&z);
+        let commitment = encode_g1(&kzg.commit(&polynomial).unwrap());
+        let proof = encode_g1(&kzg.open(&polynomial, z, y).unwrap());
+
+        let verifier = KzgVerifier::new(
+            &encode_g1(&kzg.g1()),
+            &encode_g2(&kzg.g2()),
+            &encode_g2(&kzg.vk()),
+        )
+        .unwrap();
+        let (z, y_bytes) = (encode_fr(&z), encode_fr(&y));
+        assert!(verifier
+            .verify_kzg(&commitment, &proof, &z, &y_bytes)
+            .unwrap());
+        let wrong_y = encode_fr(&(y + Fr::one()));
+        assert!(!verifier
+            .verify_kzg(&commitment, &proof, &z, &wrong_y)
+            .unwrap());
+        // a truncated proof
+        assert!(verifier
+            .verify_kzg(&commitment, &proof[..32], &z, &y_bytes)
+            .is_err());
+    }
+}